@@ -0,0 +1,341 @@
+//! Approval workflows.
+
+use super::{auth, ticket};
+use crate::{approval, audit, db, into_io_err};
+use common::approval::{
+    check_steps, ApprovalChainDTO, ApprovalChainFormDTO, ApprovalDTO, DecisionDTO,
+    PendingApprovalDTO, Status as ApprovalStatus,
+};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::{convert::TryFrom, io};
+use uuid::Uuid;
+
+/// Maximum length of the name of a chain, in characters.
+const MAX_NAME_LEN: usize = 100;
+
+/// Maximum length of the comment of a decision, in characters.
+const MAX_COMMENT_LEN: usize = 1000;
+
+/// List the approval chains
+#[get("/approval-chains")]
+pub async fn chains(
+    _admin: auth::Admin,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<ApprovalChainDTO>>> {
+    let chains = conn.run(db::approval::get_chains).await?;
+
+    chains
+        .into_iter()
+        .map(|chain| ApprovalChainDTO::try_from(chain).map_err(into_io_err))
+        .collect::<io::Result<_>>()
+        .map(Json)
+}
+
+/// Create a new approval chain
+#[post("/approval-chains", format = "json", data = "<chain>")]
+pub async fn create_chain(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    chain: Json<ApprovalChainFormDTO>,
+) -> io::Result<(Status, Json<Result<ApprovalChainDTO, String>>)> {
+    let chain = chain.into_inner();
+    let name = match validate_chain(&conn, None, &chain).await? {
+        Ok(name) => name,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let steps = serde_json::to_value(&chain.steps).map_err(into_io_err)?;
+    let created = conn
+        .run(move |c| {
+            db::approval::insert_chain(c, &db::model::ApprovalChainForm { name: &name, steps })
+        })
+        .await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(ApprovalChainDTO::try_from(created).map_err(into_io_err)?)),
+    ))
+}
+
+/// Update an approval chain, without affecting the running approvals
+#[put("/approval-chains/<id>", format = "json", data = "<chain>")]
+pub async fn update_chain(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    chain: Json<ApprovalChainFormDTO>,
+) -> io::Result<(Status, Json<Result<ApprovalChainDTO, String>>)> {
+    let chain = chain.into_inner();
+    let name = match validate_chain(&conn, Some(id), &chain).await? {
+        Ok(name) => name,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let steps = serde_json::to_value(&chain.steps).map_err(into_io_err)?;
+    let updated = conn
+        .run(move |c| {
+            db::approval::update_chain(c, id, &db::model::ApprovalChainForm { name: &name, steps })
+        })
+        .await?;
+
+    Ok(match updated {
+        Some(updated) => (
+            Status::Ok,
+            Json(Ok(ApprovalChainDTO::try_from(updated).map_err(into_io_err)?)),
+        ),
+        None => (Status::NotFound, Json(Err("chain not found".to_owned()))),
+    })
+}
+
+/// Delete an approval chain, without affecting the running approvals
+#[delete("/approval-chains/<id>")]
+pub async fn delete_chain(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<Status> {
+    let deleted = conn.run(move |c| db::approval::delete_chain(c, id)).await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the approvals waiting for a decision of the user
+#[get("/approvals")]
+pub async fn pending(
+    user: auth::User,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<PendingApprovalDTO>>> {
+    let user_id = user.id;
+    let pending = conn
+        .run(move |c| db::approval::get_pending(c, user_id))
+        .await?;
+
+    Ok(Json(
+        pending
+            .into_iter()
+            .map(|(approver, ticket)| pending_dto(approver, ticket))
+            .collect(),
+    ))
+}
+
+/// Get the approval an email link is for, if it's still waiting for a decision
+#[get("/approvals/<token>")]
+pub async fn with_token(
+    conn: db::Connection,
+    token: String,
+) -> io::Result<(Status, Json<Option<PendingApprovalDTO>>)> {
+    let pending = conn
+        .run(move |c| {
+            let approver = match db::approval::get_approver_with_token(c, &token)? {
+                Some(approver) => approver,
+                None => return Ok(None),
+            };
+            if !is_waiting(c, &approver)? {
+                return Ok(None);
+            }
+
+            let ticket =
+                db::ticket::get_with_id(c, &db::tenant::Viewer::system(), approver.ticket_id)?;
+            Ok::<_, io::Error>(ticket.map(|ticket| pending_dto(approver, ticket)))
+        })
+        .await?;
+
+    // Expired and used links are not told apart
+    Ok(match pending {
+        Some(pending) => (Status::Ok, Json(Some(pending))),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Approve or reject a ticket with the link of an approval email
+#[post("/approvals/<token>", format = "json", data = "<decision>")]
+pub async fn decide_with_token(
+    conn: db::Connection,
+    ctx: audit::Context,
+    token: String,
+    decision: Json<DecisionDTO>,
+) -> io::Result<(Status, Json<Result<ApprovalStatus, &'static str>>)> {
+    let DecisionDTO { decision, comment } = decision.into_inner();
+    if comment.chars().count() > MAX_COMMENT_LEN {
+        return Ok((Status::BadRequest, Json(Err("comment is too long"))));
+    }
+
+    let approver = match conn
+        .run(move |c| db::approval::get_approver_with_token(c, &token))
+        .await?
+    {
+        Some(approver) => approver,
+        None => return Ok((Status::NotFound, Json(Err("approval not found")))),
+    };
+
+    let status = conn
+        .run(move |c| {
+            approval::decide(
+                c,
+                &ctx,
+                approver.ticket_id,
+                approver.user_id,
+                decision,
+                &comment,
+                true,
+            )
+        })
+        .await?;
+
+    Ok(match status {
+        Some(status) => (Status::Ok, Json(Ok(status))),
+        None => (
+            Status::Conflict,
+            Json(Err("approval is not waiting for your decision")),
+        ),
+    })
+}
+
+/// Get the approval of a ticket, if the user can see the ticket or is one of its approvers
+#[get("/tickets/<id>/approval")]
+pub async fn get(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<ApprovalDTO>>)> {
+    let approval = match get_approval(&conn, &user, id).await? {
+        Some(approval) => approval,
+        None => return Ok((Status::NotFound, Json(None))),
+    };
+
+    Ok((Status::Ok, Json(Some(approval))))
+}
+
+/// Approve or reject a ticket in the application
+#[post("/tickets/<id>/approval", format = "json", data = "<decision>")]
+pub async fn decide(
+    user: auth::User,
+    conn: db::Connection,
+    ctx: audit::Context,
+    id: Uuid,
+    decision: Json<DecisionDTO>,
+) -> io::Result<(Status, Json<Result<ApprovalDTO, &'static str>>)> {
+    let DecisionDTO { decision, comment } = decision.into_inner();
+    if comment.chars().count() > MAX_COMMENT_LEN {
+        return Ok((Status::BadRequest, Json(Err("comment is too long"))));
+    }
+    if get_approval(&conn, &user, id).await?.is_none() {
+        return Ok((Status::NotFound, Json(Err("approval not found"))));
+    }
+
+    let user_id = user.id;
+    let status = conn
+        .run(move |c| approval::decide(c, &ctx, id, user_id, decision, &comment, false))
+        .await?;
+    if status.is_none() {
+        return Ok((
+            Status::Conflict,
+            Json(Err("approval is not waiting for your decision")),
+        ));
+    }
+
+    let approval = get_approval(&conn, &user, id)
+        .await?
+        .expect("approval disappeared while deciding on it");
+    Ok((Status::Ok, Json(Ok(approval))))
+}
+
+/// Gets the approval of a ticket, if the user can see the ticket or is one of its approvers.
+async fn get_approval(
+    conn: &db::Connection,
+    user: &db::model::User,
+    ticket_id: Uuid,
+) -> io::Result<Option<ApprovalDTO>> {
+    let viewer = ticket::viewer(conn, user).await?;
+    conn.run(move |c| {
+        let approval = match db::approval::get_approval(c, ticket_id)? {
+            Some(approval) => approval,
+            None => return Ok(None),
+        };
+        let approvers = db::approval::get_approvers(c, ticket_id)?;
+        let is_approver = approvers
+            .iter()
+            .any(|(approver, _username)| approver.user_id == viewer.user_id);
+        if !is_approver && db::ticket::get_with_id(c, &viewer, ticket_id)?.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(ApprovalDTO {
+            ticket_id,
+            status: approval.status(),
+            step: approval.step,
+            steps: approval.steps().map_err(into_io_err)?,
+            approvers: approvers
+                .into_iter()
+                .map(|(approver, username)| approver.into_dto(username))
+                .collect(),
+        }))
+    })
+    .await
+}
+
+/// Checks if the approval of an approver is waiting for their decision.
+fn is_waiting(
+    conn: &mut diesel::PgConnection,
+    approver: &db::model::TicketApprover,
+) -> io::Result<bool> {
+    if approver.decision.is_some() {
+        return Ok(false);
+    }
+
+    Ok(
+        db::approval::get_approval(conn, approver.ticket_id)?.map_or(false, |approval| {
+            approval.status() == ApprovalStatus::Pending && approval.step == approver.step
+        }),
+    )
+}
+
+/// Converts a pending approver and their ticket to the approval waiting for them.
+fn pending_dto(
+    approver: db::model::TicketApprover,
+    ticket: db::model::Ticket,
+) -> PendingApprovalDTO {
+    PendingApprovalDTO {
+        ticket_id: ticket.id,
+        number: ticket.number,
+        title: ticket.title,
+        description: ticket.description,
+        step: approver.step,
+        requested_on: approver.created_on,
+    }
+}
+
+/// Validates an approval chain, returning its trimmed name, or the reason if it's not valid.
+async fn validate_chain(
+    conn: &db::Connection,
+    id: Option<Uuid>,
+    chain: &ApprovalChainFormDTO,
+) -> io::Result<Result<String, (Status, String)>> {
+    let name = chain.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok(Err((Status::BadRequest, "name can't be empty".to_owned())));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Ok(Err((Status::BadRequest, "name is too long".to_owned())));
+    }
+    if let Err(e) = check_steps(&chain.steps) {
+        return Ok(Err((Status::BadRequest, e)));
+    }
+
+    let taken_name = name.clone();
+    if conn
+        .run(move |c| db::approval::chain_name_taken(c, &taken_name, id))
+        .await?
+    {
+        return Ok(Err((
+            Status::Conflict,
+            "name belongs to another chain".to_owned(),
+        )));
+    }
+
+    Ok(Ok(name))
+}
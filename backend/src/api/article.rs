@@ -0,0 +1,484 @@
+//! Knowledge base articles.
+//!
+//! Published articles can be read without logging in, depending on their visibility, so that the
+//! customer portal can show them. Only agents can see drafts, revisions and diffs.
+
+use super::{auth, ticket::viewer};
+use crate::{db, knowledge};
+use common::{
+    article::{
+        ArticleDTO, ArticleFormDTO, ArticleLinkDTO, ArticleSummaryDTO, CategoryDTO,
+        CategoryFormDTO, DiffDTO, RevisionDTO, Status as ArticleStatus, Visibility,
+    },
+    user::Role,
+};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum number of articles returned in a single query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of articles returned in a single query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// Maximum length of article titles, in characters.
+const MAX_TITLE_LEN: usize = 200;
+
+/// Maximum length of category names, in characters.
+const MAX_NAME_LEN: usize = 100;
+
+/// List all the categories of articles
+#[get("/articles/categories")]
+pub async fn categories(conn: db::Connection) -> io::Result<Json<Vec<CategoryDTO>>> {
+    let categories = conn.run(db::article::get_categories).await?;
+
+    Ok(Json(categories.into_iter().map(Into::into).collect()))
+}
+
+/// Create a new category of articles
+#[post("/articles/categories", format = "json", data = "<category>")]
+pub async fn create_category(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    category: Json<CategoryFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<CategoryDTO, &'static str>>)> {
+    let name = match validate_category(&conn, None, &category).await? {
+        Ok(name) => name,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let description = category.description.trim().to_owned();
+    let category = conn
+        .run(move |c| {
+            db::article::insert_category(
+                c,
+                &db::model::ArticleCategoryForm {
+                    name: &name,
+                    description: &description,
+                },
+            )
+        })
+        .await?;
+
+    Ok((Status::Created, Json(Ok(category.into()))))
+}
+
+/// Update a category of articles
+#[put("/articles/categories/<id>", format = "json", data = "<category>")]
+pub async fn update_category(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    category: Json<CategoryFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<CategoryDTO, &'static str>>)> {
+    let name = match validate_category(&conn, Some(id), &category).await? {
+        Ok(name) => name,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let description = category.description.trim().to_owned();
+    let category = conn
+        .run(move |c| {
+            db::article::update_category(
+                c,
+                id,
+                &db::model::ArticleCategoryForm {
+                    name: &name,
+                    description: &description,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match category {
+        Some(category) => (Status::Ok, Json(Ok(category.into()))),
+        None => (Status::NotFound, Json(Err("category not found"))),
+    })
+}
+
+/// Delete a category of articles, keeping its articles without a category
+#[delete("/articles/categories/<id>")]
+pub async fn delete_category(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<Status> {
+    let deleted = conn
+        .run(move |c| db::article::delete_category(c, id))
+        .await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the articles readable by the user, ordered by title
+///
+/// Anonymous users only get the public articles. Articles matching the words searched for, if
+/// any, are ordered by relevance instead.
+#[get("/articles?<category>&<q>&<limit>&<offset>")]
+pub async fn list(
+    user: Option<auth::User>,
+    conn: db::Connection,
+    category: Option<Uuid>,
+    q: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<Json<Vec<ArticleSummaryDTO>>> {
+    let role = user.map(|user| user.role());
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+    let words = q.filter(|q| !q.trim().is_empty());
+    let articles = conn
+        .run(move |c| {
+            db::article::get_listed(
+                c,
+                &visibilities(role),
+                sees_drafts(role),
+                category,
+                words.as_deref(),
+                limit,
+                offset,
+            )
+        })
+        .await?;
+
+    Ok(Json(articles.into_iter().map(Into::into).collect()))
+}
+
+/// Get an article readable by the user
+#[get("/articles/<id>")]
+pub async fn get(
+    user: Option<auth::User>,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<ArticleDTO>>)> {
+    let role = user.map(|user| user.role());
+    let article = conn.run(move |c| db::article::get_with_id(c, id)).await?;
+
+    Ok(match article {
+        Some(article) if readable(&article, role) => (Status::Ok, Json(Some(article.into()))),
+        _ => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Write a new article
+#[post("/articles", format = "json", data = "<article>")]
+pub async fn create(
+    agent: auth::Agent,
+    conn: db::Connection,
+    article: Json<ArticleFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<ArticleDTO, &'static str>>)> {
+    let article = article.into_inner();
+    let title = match validate_article(&conn, &article).await? {
+        Ok(title) => title,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    let (author_id, body) = (agent.id, article.body);
+    let (category_id, status, visibility) =
+        (article.category_id, article.status, article.visibility);
+    let article = conn
+        .run(move |c| {
+            db::article::insert(
+                c,
+                &db::model::ArticleForm {
+                    category_id,
+                    title: &title,
+                    body: &body,
+                    status: status.as_str(),
+                    visibility: visibility.as_str(),
+                },
+                author_id,
+            )
+        })
+        .await?;
+
+    Ok((Status::Created, Json(Ok(article.into()))))
+}
+
+/// Update an article, creating a new revision if its title or body change
+#[put("/articles/<id>", format = "json", data = "<article>")]
+pub async fn update(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    article: Json<ArticleFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<ArticleDTO, &'static str>>)> {
+    let article = article.into_inner();
+    let title = match validate_article(&conn, &article).await? {
+        Ok(title) => title,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    let (editor_id, body) = (agent.id, article.body);
+    let (category_id, status, visibility) =
+        (article.category_id, article.status, article.visibility);
+    let article = conn
+        .run(move |c| {
+            db::article::update(
+                c,
+                id,
+                &db::model::ArticleForm {
+                    category_id,
+                    title: &title,
+                    body: &body,
+                    status: status.as_str(),
+                    visibility: visibility.as_str(),
+                },
+                editor_id,
+            )
+        })
+        .await?;
+
+    Ok(match article {
+        Some(article) => (Status::Ok, Json(Ok(article.into()))),
+        None => (Status::NotFound, Json(Err("article not found"))),
+    })
+}
+
+/// Delete an article, along with its revision history
+#[delete("/articles/<id>")]
+pub async fn delete(_admin: auth::Admin, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    let deleted = conn.run(move |c| db::article::delete(c, id)).await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the revisions of an article, newest first
+#[get("/articles/<id>/revisions")]
+pub async fn revisions(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<RevisionDTO>>)> {
+    let revisions = conn.run(move |c| db::article::get_revisions(c, id)).await?;
+
+    // Articles always have their first revision
+    Ok(if revisions.is_empty() {
+        (Status::NotFound, Json(Vec::new()))
+    } else {
+        (
+            Status::Ok,
+            Json(revisions.into_iter().map(Into::into).collect()),
+        )
+    })
+}
+
+/// Get a revision of an article
+#[get("/articles/<id>/revisions/<revision>")]
+pub async fn revision(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    revision: i32,
+) -> io::Result<(Status, Json<Option<RevisionDTO>>)> {
+    let revision = conn
+        .run(move |c| db::article::get_revision(c, id, revision))
+        .await?;
+
+    Ok(match revision {
+        Some(revision) => (Status::Ok, Json(Some(revision.into()))),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Compare two revisions of an article
+///
+/// By default, the latest revision is compared with the previous one.
+#[get("/articles/<id>/diff?<from>&<to>")]
+pub async fn diff(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    from: Option<i32>,
+    to: Option<i32>,
+) -> io::Result<(Status, Json<Option<DiffDTO>>)> {
+    // Revisions are numbered from 1
+    if from.into_iter().chain(to).any(|revision| revision < 1) {
+        return Ok((Status::BadRequest, Json(None)));
+    }
+
+    let revisions = conn
+        .run(move |c| {
+            let to = match to {
+                Some(to) => to,
+                None => match db::article::get_with_id(c, id)? {
+                    Some(article) => article.revision,
+                    None => return Ok(None),
+                },
+            };
+            let from = from.unwrap_or(to - 1).max(1);
+
+            Ok::<_, io::Error>(
+                db::article::get_revision(c, id, from)?.zip(db::article::get_revision(c, id, to)?),
+            )
+        })
+        .await?;
+
+    Ok(match revisions {
+        Some((from, to)) => (
+            Status::Ok,
+            Json(Some(DiffDTO {
+                from: from.revision,
+                to: to.revision,
+                title: knowledge::diff(&from.title, &to.title),
+                body: knowledge::diff(&from.body, &to.body),
+            })),
+        ),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// List the articles linked to a ticket that the user can read
+#[get("/tickets/<id>/articles")]
+pub async fn linked(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<ArticleSummaryDTO>>)> {
+    let viewer = viewer(&conn, &user).await?;
+    let role = Some(user.role());
+    let articles = conn
+        .run(move |c| {
+            if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::article::get_linked(c, id, &visibilities(role), sees_drafts(role)).map(Some)
+        })
+        .await?;
+
+    Ok(match articles {
+        Some(articles) => (
+            Status::Ok,
+            Json(articles.into_iter().map(Into::into).collect()),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Link an article to a ticket
+#[post("/tickets/<id>/articles", format = "json", data = "<link>")]
+pub async fn link(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    link: Json<ArticleLinkDTO>,
+) -> io::Result<(Status, Json<Result<(), &'static str>>)> {
+    let viewer = viewer(&conn, &agent).await?;
+    let (article_id, linked_by) = (link.article_id, agent.id);
+
+    conn.run(move |c| {
+        if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("ticket not found"))));
+        }
+        if db::article::get_with_id(c, article_id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("article not found"))));
+        }
+
+        Ok(if db::article::link(c, id, article_id, linked_by)? {
+            (Status::Created, Json(Ok(())))
+        } else {
+            (Status::Ok, Json(Ok(())))
+        })
+    })
+    .await
+}
+
+/// Unlink an article from a ticket
+#[delete("/tickets/<id>/articles/<article_id>")]
+pub async fn unlink(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    article_id: Uuid,
+) -> io::Result<Status> {
+    let unlinked = conn
+        .run(move |c| db::article::unlink(c, id, article_id))
+        .await?;
+
+    Ok(if unlinked {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// Gets the visibilities of the articles readable with a role, as stored in the database.
+fn visibilities(role: Option<Role>) -> Vec<&'static str> {
+    Visibility::readable_by(role)
+        .iter()
+        .map(|visibility| visibility.as_str())
+        .collect()
+}
+
+/// Checks if a role can see the drafts of articles.
+fn sees_drafts(role: Option<Role>) -> bool {
+    role.map_or(false, Role::is_staff)
+}
+
+/// Checks if an article can be read with a role.
+fn readable(article: &db::model::Article, role: Option<Role>) -> bool {
+    Visibility::readable_by(role).contains(&article.visibility())
+        && (article.status() == ArticleStatus::Published || sees_drafts(role))
+}
+
+/// Validates a category, returning its trimmed name, or the status and reason if it's not
+/// valid.
+async fn validate_category(
+    conn: &db::Connection,
+    id: Option<Uuid>,
+    category: &CategoryFormDTO<'_>,
+) -> io::Result<Result<String, (Status, &'static str)>> {
+    let name = category.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok(Err((Status::BadRequest, "name can't be empty")));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Ok(Err((Status::BadRequest, "name is too long")));
+    }
+
+    let taken_name = name.clone();
+    if conn
+        .run(move |c| db::article::category_name_taken(c, &taken_name, id))
+        .await?
+    {
+        return Ok(Err((Status::Conflict, "name belongs to another category")));
+    }
+
+    Ok(Ok(name))
+}
+
+/// Validates an article, returning its trimmed title, or the reason if it's not valid.
+async fn validate_article(
+    conn: &db::Connection,
+    article: &ArticleFormDTO<'_>,
+) -> io::Result<Result<String, &'static str>> {
+    let title = article.title.trim().to_owned();
+    if title.is_empty() {
+        return Ok(Err("title can't be empty"));
+    }
+    if title.chars().count() > MAX_TITLE_LEN {
+        return Ok(Err("title is too long"));
+    }
+
+    if let Some(category_id) = article.category_id {
+        if conn
+            .run(move |c| db::article::get_category_with_id(c, category_id))
+            .await?
+            .is_none()
+        {
+            return Ok(Err("category not found"));
+        }
+    }
+
+    Ok(Ok(title))
+}
@@ -0,0 +1,68 @@
+//! Audit log queries.
+
+use super::{auth, parse_date};
+use crate::db;
+use common::audit::{AuditEntryDTO, AuditEvent};
+use rocket::{get, http::Status, serde::json::Json, FromForm};
+use std::{convert::TryFrom, io};
+use uuid::Uuid;
+
+/// Maximum number of entries returned in a single query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of entries returned in a single query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// Audit log query parameters.
+#[derive(Debug, FromForm)]
+pub struct Query<'r> {
+    /// Kind of event.
+    kind: Option<&'r str>,
+    /// ID of the user that performed the action.
+    actor: Option<Uuid>,
+    /// RFC 3339 timestamp, only events at or after it will be returned.
+    since: Option<&'r str>,
+    /// RFC 3339 timestamp, only events before it will be returned.
+    until: Option<&'r str>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Query the audit log
+#[get("/audit?<query..>")]
+pub async fn query(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    query: Query<'_>,
+) -> io::Result<(Status, Json<Vec<AuditEntryDTO>>)> {
+    if let Some(kind) = query.kind {
+        if !AuditEvent::KINDS.contains(&kind) {
+            return Ok((Status::BadRequest, Json(Vec::new())));
+        }
+    }
+
+    let (since, until) = match (parse_date(query.since), parse_date(query.until)) {
+        (Ok(since), Ok(until)) => (since, until),
+        _ => return Ok((Status::BadRequest, Json(Vec::new()))),
+    };
+
+    let filter = db::audit::Filter {
+        kind: query.kind.map(ToOwned::to_owned),
+        actor_id: query.actor,
+        since,
+        until,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let entries = conn
+        .run(move |c| db::audit::query(c, &filter, limit, offset))
+        .await?;
+
+    let entries = entries
+        .into_iter()
+        .map(AuditEntryDTO::try_from)
+        .collect::<Result<_, _>>()?;
+
+    Ok((Status::Ok, Json(entries)))
+}
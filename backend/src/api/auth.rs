@@ -0,0 +1,243 @@
+//! User authentication.
+//!
+//! Users log in with their username and password, and receive a session cookie. The request guards
+//! in this module check that cookie, and the role of the logged in user. Only the hash of the
+//! session token is stored.
+
+use crate::{audit, db, rand_code};
+use common::{
+    audit::AuditEvent,
+    login::LoginDTO,
+    user::{Role, UserDTO},
+};
+use rand::{thread_rng, RngCore};
+use rocket::{
+    get,
+    http::{Cookie, CookieJar, SameSite, Status},
+    outcome::{try_outcome, Outcome},
+    post,
+    request::{self, FromRequest, Request},
+    serde::json::Json,
+};
+use sha3::{Digest, Sha3_256};
+use std::{io, ops::Deref};
+use subtle::ConstantTimeEq;
+
+/// Name of the cookie storing the session token.
+const SESSION_COOKIE: &str = "session";
+
+/// Length of the password salt, in bytes.
+const SALT_LEN: usize = 10;
+
+/// Length of the session token.
+const SESSION_TOKEN_LEN: usize = 32;
+
+/// Logged in user.
+///
+/// Routes using this guard will return `401 Unauthorized` if there is no valid session.
+///
+/// The guard needs a database connection to check the session, so it must be placed before any
+/// [`db::Connection`] parameter of the route. Otherwise, it could wait forever for a connection if
+/// the pool is exhausted.
+#[derive(Debug, Clone)]
+pub struct User(pub db::model::User);
+
+impl Deref for User {
+    type Target = db::model::User;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for User {
+    type Error = &'static str;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        // The user is cached, so that several guards in the same request only query it once
+        let user = req
+            .local_cache_async(async {
+                let token_hash = hash_token(req.cookies().get(SESSION_COOKIE)?.value());
+                let conn = req.guard::<db::Connection>().await.succeeded()?;
+
+                conn.run(move |c| db::user::get_with_session(c, &token_hash))
+                    .await
+                    .ok()
+                    .flatten()
+            })
+            .await;
+
+        match user {
+            Some(user) => Outcome::Success(Self(user.clone())),
+            None => Outcome::Failure((Status::Unauthorized, "not logged in")),
+        }
+    }
+}
+
+/// Logged in agent or administrator.
+///
+/// Routes using this guard will return `403 Forbidden` if the logged in user is a customer.
+#[derive(Debug, Clone)]
+pub struct Agent(pub db::model::User);
+
+impl Deref for Agent {
+    type Target = db::model::User;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Agent {
+    type Error = &'static str;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let User(user) = try_outcome!(req.guard::<User>().await);
+
+        if user.role().is_staff() {
+            Outcome::Success(Self(user))
+        } else {
+            Outcome::Failure((Status::Forbidden, "agent role required"))
+        }
+    }
+}
+
+/// Logged in administrator.
+///
+/// Routes using this guard will return `403 Forbidden` if the logged in user is not an
+/// administrator.
+#[derive(Debug, Clone)]
+pub struct Admin(pub db::model::User);
+
+impl Deref for Admin {
+    type Target = db::model::User;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = &'static str;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let User(user) = try_outcome!(req.guard::<User>().await);
+
+        if user.role() == Role::Admin {
+            Outcome::Success(Self(user))
+        } else {
+            Outcome::Failure((Status::Forbidden, "admin role required"))
+        }
+    }
+}
+
+/// Log in with a username and a password.
+#[post("/login", format = "json", data = "<login>")]
+pub async fn login(
+    conn: db::Connection,
+    ctx: audit::Context,
+    cookies: &CookieJar<'_>,
+    login: Json<LoginDTO<'_>>,
+) -> io::Result<(Status, Json<Option<UserDTO>>)> {
+    let LoginDTO { username, password } = login.into_inner();
+
+    let username = username.trim().to_owned();
+    let username_clone = username.clone();
+    let user = conn
+        .run(move |c| db::user::get_with_username(c, &username_clone))
+        .await?;
+
+    let user = match user {
+        Some(user) if user.active && verify_password(&user.password, password) => user,
+        _ => {
+            let event = AuditEvent::LoginFailure { username };
+            conn.run(move |c| audit::record(c, &ctx, None, &event))
+                .await?;
+
+            return Ok((Status::Unauthorized, Json(None)));
+        }
+    };
+
+    let user_id = user.id;
+    conn.run(move |c| audit::record(c, &ctx, Some(user_id), &AuditEvent::LoginSuccess))
+        .await?;
+
+    let token = rand_code(SESSION_TOKEN_LEN);
+    let token_hash = hash_token(&token);
+    conn.run(move |c| db::user::insert_session(c, &token_hash, user_id))
+        .await?;
+
+    cookies.add(
+        Cookie::build(SESSION_COOKIE, token)
+            .http_only(true)
+            .secure(true)
+            .same_site(SameSite::Strict)
+            .finish(),
+    );
+
+    Ok((Status::Ok, Json(Some(user.into()))))
+}
+
+/// Log out, closing the current session.
+#[post("/logout")]
+pub async fn logout(conn: db::Connection, cookies: &CookieJar<'_>) -> io::Result<Status> {
+    if let Some(cookie) = cookies.get(SESSION_COOKIE) {
+        let token_hash = hash_token(cookie.value());
+        conn.run(move |c| db::user::delete_session(c, &token_hash))
+            .await?;
+    }
+    cookies.remove(Cookie::named(SESSION_COOKIE));
+
+    Ok(Status::NoContent)
+}
+
+/// Gets the currently logged in user.
+#[get("/login")]
+pub fn current_user(user: User) -> Json<UserDTO> {
+    Json(user.0.into())
+}
+
+/// Hashes a password with a random salt.
+///
+/// The result contains the salt followed by the SHA3-256 hash of the salt and the password.
+pub fn hash_password(password: &str) -> Vec<u8> {
+    let mut salt = [0u8; SALT_LEN];
+    thread_rng().fill_bytes(&mut salt);
+
+    let mut hasher = Sha3_256::new();
+    hasher.update(salt);
+    hasher.update(password.as_bytes());
+
+    let result = hasher.finalize();
+    let mut db_pass = Vec::with_capacity(SALT_LEN + result.len());
+    db_pass.extend_from_slice(&salt);
+    db_pass.extend_from_slice(&result[..]);
+
+    debug_assert_eq!(db_pass.len(), 42);
+
+    db_pass
+}
+
+/// Checks a password against a hash generated by [`hash_password()`].
+pub fn verify_password(hash: &[u8], password: &str) -> bool {
+    if hash.len() <= SALT_LEN {
+        return false;
+    }
+    let (salt, expected) = hash.split_at(SALT_LEN);
+
+    let mut hasher = Sha3_256::new();
+    hasher.update(salt);
+    hasher.update(password.as_bytes());
+
+    hasher.finalize()[..].ct_eq(expected).into()
+}
+
+/// Hashes a session token, to find its session without storing the token itself.
+///
+/// Tokens are random, so they need no salt.
+fn hash_token(token: &str) -> Vec<u8> {
+    Sha3_256::digest(token.as_bytes()).to_vec()
+}
@@ -0,0 +1,274 @@
+//! Automation rules.
+
+use super::auth;
+use crate::{
+    automation::Rule,
+    db::{self, tenant::Viewer},
+};
+use common::automation::{Action, DryRunDTO, DryRunResultDTO, LogEntryDTO, RuleDTO, RuleFormDTO};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json, FromForm};
+use std::{convert::TryFrom, io};
+use uuid::Uuid;
+
+/// Maximum number of entries returned in a single execution log query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of entries returned in a single execution log query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// List all the automation rules, in evaluation order
+#[get("/automations")]
+pub async fn list(_admin: auth::Admin, conn: db::Connection) -> io::Result<Json<Vec<RuleDTO>>> {
+    let rules = conn.run(db::automation::get_rules).await?;
+
+    Ok(Json(
+        rules
+            .into_iter()
+            .map(RuleDTO::try_from)
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+/// Get an automation rule
+#[get("/automations/<id>")]
+pub async fn get(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<RuleDTO>>)> {
+    let rule = conn
+        .run(move |c| db::automation::get_rule_with_id(c, id))
+        .await?;
+
+    Ok(match rule {
+        Some(rule) => (Status::Ok, Json(Some(RuleDTO::try_from(rule)?))),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Create a new automation rule
+#[post("/automations", format = "json", data = "<rule>")]
+pub async fn create(
+    admin: auth::Admin,
+    conn: db::Connection,
+    rule: Json<RuleFormDTO>,
+) -> io::Result<(Status, Json<Result<RuleDTO, &'static str>>)> {
+    let rule = rule.into_inner();
+    if let Err((status, e)) = validate_rule(&conn, None, &rule).await? {
+        return Ok((status, Json(Err(e))));
+    }
+
+    let created_by = admin.id;
+    let rule = conn
+        .run(move |c| {
+            db::automation::insert_rule(
+                c,
+                &db::model::AutomationRuleForm {
+                    name: rule.name.trim(),
+                    enabled: rule.enabled,
+                    position: rule.position,
+                    condition: serde_json::to_value(&rule.condition)?,
+                    actions: serde_json::to_value(&rule.actions)?,
+                },
+                created_by,
+            )
+        })
+        .await?;
+
+    Ok((Status::Created, Json(Ok(RuleDTO::try_from(rule)?))))
+}
+
+/// Update an automation rule
+#[put("/automations/<id>", format = "json", data = "<rule>")]
+pub async fn update(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    rule: Json<RuleFormDTO>,
+) -> io::Result<(Status, Json<Result<RuleDTO, &'static str>>)> {
+    let rule = rule.into_inner();
+    if let Err((status, e)) = validate_rule(&conn, Some(id), &rule).await? {
+        return Ok((status, Json(Err(e))));
+    }
+
+    let rule = conn
+        .run(move |c| {
+            db::automation::update_rule(
+                c,
+                id,
+                &db::model::AutomationRuleForm {
+                    name: rule.name.trim(),
+                    enabled: rule.enabled,
+                    position: rule.position,
+                    condition: serde_json::to_value(&rule.condition)?,
+                    actions: serde_json::to_value(&rule.actions)?,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match rule {
+        Some(rule) => (Status::Ok, Json(Ok(RuleDTO::try_from(rule)?))),
+        None => (Status::NotFound, Json(Err("rule not found"))),
+    })
+}
+
+/// Delete an automation rule, along with its execution log
+#[delete("/automations/<id>")]
+pub async fn delete(_admin: auth::Admin, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    let deleted = conn
+        .run(move |c| db::automation::delete_rule(c, id))
+        .await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// Validates an automation rule, returning the status and reason if it's not valid.
+async fn validate_rule(
+    conn: &db::Connection,
+    id: Option<Uuid>,
+    rule: &RuleFormDTO,
+) -> io::Result<Result<(), (Status, &'static str)>> {
+    let name = rule.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok(Err((Status::BadRequest, "name can't be empty")));
+    }
+    if rule.actions.is_empty() {
+        return Ok(Err((Status::BadRequest, "rule has no actions")));
+    }
+    for action in &rule.actions {
+        if let Err(e) = action.validate() {
+            return Ok(Err((Status::BadRequest, e)));
+        }
+    }
+
+    let actions = rule.actions.clone();
+    let references = conn
+        .run(move |c| {
+            for action in actions {
+                match action {
+                    Action::Assign {
+                        assignee_id: Some(assignee_id),
+                    } => match db::user::get_with_id(c, assignee_id)? {
+                        Some(user) if user.role().is_staff() => {}
+                        _ => return Ok(Err("assignee must be an agent")),
+                    },
+                    Action::MoveToQueue { queue_id }
+                        if db::queue::get_with_id(c, queue_id)?.is_none() =>
+                    {
+                        return Ok(Err("queue not found"));
+                    }
+                    Action::CallWebhook { subscription_id }
+                        if db::webhook::get_subscription_with_id(c, subscription_id)?.is_none() =>
+                    {
+                        return Ok(Err("webhook subscription not found"));
+                    }
+                    _ => {}
+                }
+            }
+
+            Ok::<_, io::Error>(Ok(()))
+        })
+        .await?;
+    if let Err(e) = references {
+        return Ok(Err((Status::BadRequest, e)));
+    }
+
+    let rules = conn.run(db::automation::get_rules).await?;
+    if rules
+        .iter()
+        .any(|rule| rule.name == name && Some(rule.id) != id)
+    {
+        return Ok(Err((Status::Conflict, "name belongs to another rule")));
+    }
+
+    Ok(Ok(()))
+}
+
+/// Test an automation rule against a ticket, without changing it
+#[post("/automations/dry-run", format = "json", data = "<dry_run>")]
+pub async fn dry_run(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    dry_run: Json<DryRunDTO>,
+) -> io::Result<(Status, Json<Result<DryRunResultDTO, &'static str>>)> {
+    let DryRunDTO {
+        rule,
+        ticket_id,
+        event,
+    } = dry_run.into_inner();
+    if let Some(e) = rule
+        .actions
+        .iter()
+        .find_map(|action| action.validate().err())
+    {
+        return Ok((Status::BadRequest, Json(Err(e))));
+    }
+
+    let ticket = match conn
+        .run(move |c| db::ticket::get_with_id(c, &Viewer::system(), ticket_id))
+        .await?
+    {
+        Some(ticket) => ticket,
+        None => return Ok((Status::NotFound, Json(Err("ticket not found")))),
+    };
+
+    // There is no previous version of the ticket, so no field is considered changed
+    let mut ticket = ticket.into();
+    let rule = Rule {
+        id: Uuid::nil(),
+        condition: rule.condition,
+        actions: rule.actions,
+    };
+    let matched = rule.evaluate(event, None, &mut ticket)?;
+
+    Ok((
+        Status::Ok,
+        Json(Ok(DryRunResultDTO {
+            matched,
+            actions: if matched { rule.actions } else { Vec::new() },
+            ticket,
+        })),
+    ))
+}
+
+/// Execution log query parameters.
+#[derive(Debug, FromForm)]
+pub struct LogQuery {
+    /// ID of the rule.
+    rule: Option<Uuid>,
+    /// ID of the ticket.
+    ticket: Option<Uuid>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Query the automation execution log
+#[get("/automations/log?<query..>")]
+pub async fn log(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    query: LogQuery,
+) -> io::Result<Json<Vec<LogEntryDTO>>> {
+    let filter = db::automation::LogFilter {
+        rule_id: query.rule,
+        ticket_id: query.ticket,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let entries = conn
+        .run(move |c| db::automation::query_log(c, filter, limit, offset))
+        .await?;
+
+    Ok(Json(
+        entries
+            .into_iter()
+            .map(LogEntryDTO::try_from)
+            .collect::<Result<_, _>>()?,
+    ))
+}
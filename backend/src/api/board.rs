@@ -0,0 +1,174 @@
+//! Ticket boards.
+//!
+//! Boards lay out the tickets in columns, by status or by assignee. Agents move tickets between
+//! columns by updating them, and follow the moves of the other agents through the ticket activity
+//! stream. Administrators limit the work in progress of each column.
+
+use super::{auth, custom_field, ticket};
+use crate::db::{self, model};
+use chrono::Utc;
+use common::{
+    board::{
+        status_title, BoardDTO, ColumnDTO, ColumnSettingsDTO, Grouping, MAX_WIP_LIMIT, UNASSIGNED,
+    },
+    query::Query,
+    ticket::{Status as TicketStatus, TicketDTO},
+};
+use rocket::{get, http::Status, put, serde::json::Json};
+use std::{collections::HashMap, io, iter};
+
+/// Get the ticket board, with the tickets grouped by status or assignee
+///
+/// The board holds the unresolved tickets, and the resolved ones when grouped by status. They can
+/// be narrowed down with a ticket search query. Only the newest tickets are on the board when too
+/// many of them match, and the board is then marked as truncated.
+#[get("/board?<group>&<q>")]
+pub async fn board(
+    agent: auth::Agent,
+    conn: db::Connection,
+    group: Option<&str>,
+    q: Option<&str>,
+) -> io::Result<(Status, Json<Result<BoardDTO, &'static str>>)> {
+    let grouping = match group.map(str::parse::<Grouping>).transpose() {
+        Ok(grouping) => grouping.unwrap_or_default(),
+        Err(_) => return Ok((Status::BadRequest, Json(Err("invalid grouping")))),
+    };
+
+    let base = match grouping {
+        Grouping::Status => "status:new,open,pending,resolved",
+        Grouping::Assignee => "status:new,open,pending",
+    };
+    let query = match format!("{} {}", base, q.unwrap_or_default()).parse::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok((Status::BadRequest, Json(Err("invalid query")))),
+    };
+    let fields = custom_field::definitions(&conn).await?;
+    if ticket::check_custom_filters(&fields, &query).is_err() {
+        return Ok((Status::BadRequest, Json(Err("invalid query"))));
+    }
+
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let (mut tickets, settings, staff) = conn
+        .run(move |c| {
+            let tickets = db::ticket::query::get_matching(
+                c,
+                &viewer,
+                &query,
+                Utc::now(),
+                // One more to know if some were left out
+                ticket::MAX_LIMIT + 1,
+                0,
+            )?;
+            let settings = db::board::get_columns(c, grouping.as_str())?;
+            let staff = match grouping {
+                Grouping::Status => Vec::new(),
+                Grouping::Assignee => db::user::get_staff(c)?,
+            };
+            Ok::<_, io::Error>((tickets, settings, staff))
+        })
+        .await?;
+
+    let truncated = tickets.len() as i64 > ticket::MAX_LIMIT;
+    tickets.truncate(ticket::MAX_LIMIT as usize);
+
+    let limits = settings
+        .into_iter()
+        // Limits set before the columns of inactive tickets were refused don't apply
+        .filter(|column| grouping.limits(&column.column_key))
+        .map(|column| (column.column_key, column.wip_limit))
+        .collect::<HashMap<_, _>>();
+    let column = |key: String, title: String| ColumnDTO {
+        wip_limit: limits.get(&key).copied(),
+        key,
+        title,
+        tickets: Vec::new(),
+    };
+    let mut columns = match grouping {
+        Grouping::Status => TicketStatus::ALL
+            .iter()
+            .filter(|&&status| status != TicketStatus::Closed)
+            .map(|&status| column(status.to_string(), status_title(status).to_owned()))
+            .collect::<Vec<_>>(),
+        Grouping::Assignee => iter::once(column(UNASSIGNED.to_owned(), "Unassigned".to_owned()))
+            .chain(staff.into_iter().map(|user: model::User| {
+                column(
+                    user.id.to_string(),
+                    format!("{} {}", user.first_name, user.last_name),
+                )
+            }))
+            .collect(),
+    };
+    for ticket in tickets.into_iter().map(TicketDTO::from) {
+        let key = grouping.key(&ticket);
+        match columns.iter_mut().find(|column| column.key == key) {
+            Some(column) => column.tickets.push(ticket),
+            // Closed tickets matching the query, or tickets of former agents
+            None => {
+                let mut extra = column(key.clone(), key);
+                extra.tickets.push(ticket);
+                columns.push(extra);
+            }
+        }
+    }
+
+    Ok((
+        Status::Ok,
+        Json(Ok(BoardDTO {
+            grouping,
+            columns,
+            truncated,
+        })),
+    ))
+}
+
+/// Change the settings of a column of the ticket board
+#[put("/board/<group>/columns/<key>", format = "json", data = "<settings>")]
+pub async fn update_column(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    group: &str,
+    key: &str,
+    settings: Json<ColumnSettingsDTO>,
+) -> io::Result<(Status, Json<Result<ColumnSettingsDTO, &'static str>>)> {
+    let grouping = match group.parse::<Grouping>() {
+        Ok(grouping) => grouping,
+        Err(_) => return Ok((Status::NotFound, Json(Err("board not found")))),
+    };
+    // Keys are valid if tickets can be moved to their column
+    if grouping.update(key).is_none() {
+        return Ok((Status::NotFound, Json(Err("column not found"))));
+    }
+
+    let settings = settings.into_inner();
+    let key = key.to_owned();
+    match settings.wip_limit {
+        Some(wip_limit) if !(1..=MAX_WIP_LIMIT).contains(&wip_limit) => {
+            return Ok((Status::BadRequest, Json(Err("invalid WIP limit"))))
+        }
+        Some(_) if !grouping.limits(&key) => {
+            return Ok((
+                Status::BadRequest,
+                Json(Err("only columns of active tickets have a WIP limit")),
+            ))
+        }
+        Some(wip_limit) => {
+            conn.run(move |c| {
+                db::board::upsert_column(
+                    c,
+                    &model::NewBoardColumn {
+                        grouping: grouping.as_str(),
+                        column_key: &key,
+                        wip_limit,
+                    },
+                )
+            })
+            .await?;
+        }
+        None => {
+            conn.run(move |c| db::board::delete_column(c, grouping.as_str(), &key))
+                .await?;
+        }
+    }
+
+    Ok((Status::Ok, Json(Ok(settings))))
+}
@@ -0,0 +1,483 @@
+//! Service catalog.
+
+use super::{auth, ticket};
+use crate::{
+    approval, audit,
+    db::{self, user::PickFilter},
+    into_io_err,
+};
+use common::{
+    approval::{Approver, StepDefinition},
+    catalog::{
+        Answers, CatalogItemDTO, CatalogItemFormDTO, FieldError, FieldKind, PickableUserDTO,
+        ServiceRequestDTO, ServiceRequestFormDTO, Value,
+    },
+    ticket::TicketDTO,
+    user::Role,
+};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::{collections::HashMap, convert::TryFrom, io};
+use uuid::Uuid;
+
+/// Maximum length of the name of an item, in characters.
+const MAX_NAME_LEN: usize = 100;
+
+/// Maximum number of users returned when searching users to pick.
+const MAX_PICKABLE: i64 = 20;
+
+/// List the items of the catalog, including inactive ones if an administrator asks for all
+#[get("/catalog?<all>")]
+pub async fn list(
+    user: auth::User,
+    conn: db::Connection,
+    all: Option<bool>,
+) -> io::Result<Json<Vec<CatalogItemDTO>>> {
+    let inactive = all.unwrap_or(false) && user.role() == Role::Admin;
+    let items = conn
+        .run(move |c| db::catalog::get_items(c, inactive))
+        .await?;
+
+    items
+        .into_iter()
+        .map(|item| CatalogItemDTO::try_from(item).map_err(into_io_err))
+        .collect::<io::Result<_>>()
+        .map(Json)
+}
+
+/// Get an item of the catalog
+#[get("/catalog/<id>")]
+pub async fn get(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<CatalogItemDTO>>)> {
+    let item = conn
+        .run(move |c| db::catalog::get_item_with_id(c, id))
+        .await?;
+
+    // Only administrators see the inactive items
+    Ok(match item {
+        Some(item) if item.active || user.role() == Role::Admin => (
+            Status::Ok,
+            Json(Some(CatalogItemDTO::try_from(item).map_err(into_io_err)?)),
+        ),
+        _ => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Create a new item in the catalog
+#[post("/catalog", format = "json", data = "<item>")]
+pub async fn create(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    item: Json<CatalogItemFormDTO>,
+) -> io::Result<(Status, Json<Result<CatalogItemDTO, String>>)> {
+    let item = item.into_inner();
+    let name = match validate_item(&conn, None, &item).await? {
+        Ok(name) => name,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let form = serde_json::to_value(&item.form).map_err(into_io_err)?;
+    let created = conn
+        .run(move |c| {
+            db::catalog::insert_item(
+                c,
+                &db::model::CatalogItemForm {
+                    name: &name,
+                    description: &item.description,
+                    form,
+                    priority: item.priority.as_str(),
+                    queue_id: item.queue_id,
+                    approval_chain_id: item.approval_chain_id,
+                    active: item.active,
+                },
+            )
+        })
+        .await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(CatalogItemDTO::try_from(created).map_err(into_io_err)?)),
+    ))
+}
+
+/// Update an item of the catalog
+#[put("/catalog/<id>", format = "json", data = "<item>")]
+pub async fn update(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    item: Json<CatalogItemFormDTO>,
+) -> io::Result<(Status, Json<Result<CatalogItemDTO, String>>)> {
+    let item = item.into_inner();
+    let name = match validate_item(&conn, Some(id), &item).await? {
+        Ok(name) => name,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let form = serde_json::to_value(&item.form).map_err(into_io_err)?;
+    let updated = conn
+        .run(move |c| {
+            db::catalog::update_item(
+                c,
+                id,
+                &db::model::CatalogItemForm {
+                    name: &name,
+                    description: &item.description,
+                    form,
+                    priority: item.priority.as_str(),
+                    queue_id: item.queue_id,
+                    approval_chain_id: item.approval_chain_id,
+                    active: item.active,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match updated {
+        Some(updated) => (
+            Status::Ok,
+            Json(Ok(CatalogItemDTO::try_from(updated).map_err(into_io_err)?)),
+        ),
+        None => (Status::NotFound, Json(Err("item not found".to_owned()))),
+    })
+}
+
+/// Delete an item of the catalog, keeping the tickets requesting it
+#[delete("/catalog/<id>")]
+pub async fn delete(_admin: auth::Admin, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    let deleted = conn.run(move |c| db::catalog::delete_item(c, id)).await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// Request an item of the catalog, opening a ticket with the answers to its form
+///
+/// Tickets of items with an approval chain stay pending until they are approved.
+#[post("/catalog/<id>/requests", format = "json", data = "<request>")]
+pub async fn request(
+    user: auth::User,
+    conn: db::Connection,
+    ctx: audit::Context,
+    id: Uuid,
+    request: Json<ServiceRequestFormDTO>,
+) -> io::Result<(Status, Json<Result<TicketDTO, Vec<FieldError>>>)> {
+    let ServiceRequestFormDTO {
+        answers,
+        organisation_id,
+    } = request.into_inner();
+    let item = match conn
+        .run(move |c| db::catalog::get_item_with_id(c, id))
+        .await?
+    {
+        Some(item) if item.active => CatalogItemDTO::try_from(item).map_err(into_io_err)?,
+        _ => return Ok((Status::NotFound, Json(Err(Vec::new())))),
+    };
+    let viewer = ticket::viewer(&conn, &user).await?;
+
+    let answers = match item.form.validate(&answers) {
+        Ok(answers) => answers,
+        Err(errors) => return Ok((Status::BadRequest, Json(Err(errors)))),
+    };
+    let usernames = match validate_users(&conn, &viewer, &item, &answers).await? {
+        Ok(usernames) => usernames,
+        Err(errors) => return Ok((Status::BadRequest, Json(Err(errors)))),
+    };
+
+    let organisation_id =
+        match ticket::resolve_organisation(&conn, &viewer, organisation_id).await? {
+            Ok(organisation_id) => organisation_id,
+            Err((status, message)) => {
+                let error = FieldError {
+                    field: "organisation_id".to_owned(),
+                    message: message.to_owned(),
+                };
+                return Ok((status, Json(Err(vec![error]))));
+            }
+        };
+    // The queue of an item is unset when the queue is deleted, so it always exists
+    let queue_id = ticket::resolve_queue(&conn, item.queue_id)
+        .await?
+        .unwrap_or(None);
+
+    let description = describe(&item, &answers, &usernames);
+    let (form, answers_value) = (
+        serde_json::to_value(&item.form).map_err(into_io_err)?,
+        serde_json::to_value(&answers).map_err(into_io_err)?,
+    );
+    let opened = conn
+        .run(move |c| {
+            let opened = db::ticket::insert(
+                c,
+                &viewer,
+                &db::model::NewTicket {
+                    title: &item.name,
+                    description: &description,
+                    priority: item.priority.as_str(),
+                    requester_id: viewer.user_id,
+                    organisation_id,
+                    queue_id,
+                    category: None,
+                    custom_fields: None,
+                },
+            )?;
+            let _ = db::catalog::insert_request(
+                c,
+                &db::model::NewServiceRequest {
+                    ticket_id: opened.id,
+                    catalog_item_id: item.id,
+                    form,
+                    answers: answers_value,
+                },
+            )?;
+
+            // The chain may have been deleted since the item was loaded
+            let chain = match item.approval_chain_id {
+                Some(chain_id) => db::approval::get_chain_with_id(c, chain_id)?,
+                None => None,
+            };
+            match chain {
+                Some(chain) => {
+                    let steps = serde_json::from_value::<Vec<StepDefinition>>(chain.steps)
+                        .map_err(into_io_err)?;
+                    approval::start(c, &ctx, opened, &steps, &answers)
+                }
+                None => Ok(opened),
+            }
+        })
+        .await?;
+    let opened = ticket::opened(&conn, user.id, opened).await?;
+
+    Ok((Status::Created, Json(Ok(opened.into()))))
+}
+
+/// Get the answers of a ticket opened from the catalog
+#[get("/tickets/<id>/request")]
+pub async fn ticket_request(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<ServiceRequestDTO>>)> {
+    let viewer = ticket::viewer(&conn, &user).await?;
+    let request = conn
+        .run(move |c| match db::ticket::get_with_id(c, &viewer, id)? {
+            Some(_) => db::catalog::get_request(c, id),
+            None => Ok(None),
+        })
+        .await?;
+
+    Ok(match request {
+        Some(request) => (
+            Status::Ok,
+            Json(Some(
+                ServiceRequestDTO::try_from(request).map_err(into_io_err)?,
+            )),
+        ),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Search the users that can be picked in user fields, by name or ID
+///
+/// Customers can pick themselves and the members of their organisations, and staff anyone.
+#[get("/catalog/users?<q>")]
+pub async fn users(
+    user: auth::User,
+    conn: db::Connection,
+    q: Option<&str>,
+) -> io::Result<Json<Vec<PickableUserDTO>>> {
+    let viewer = ticket::viewer(&conn, &user).await?;
+    let text = q.unwrap_or_default().trim().to_owned();
+    let users = conn
+        .run(move |c| {
+            let organisation_ids = (!viewer.sees_all()).then(|| viewer.organisation_ids);
+            let filter = match text.parse() {
+                Ok(id) => PickFilter::Id(id),
+                Err(_) => PickFilter::Text(&text),
+            };
+            db::user::get_pickable(
+                c,
+                viewer.user_id,
+                organisation_ids.as_deref(),
+                filter,
+                MAX_PICKABLE,
+            )
+        })
+        .await?;
+
+    Ok(Json(users.into_iter().map(pickable).collect()))
+}
+
+/// Converts a user to the information shown when picking it.
+fn pickable(user: db::model::User) -> PickableUserDTO {
+    PickableUserDTO {
+        id: user.id,
+        name: format!("{} {}", user.first_name, user.last_name),
+        username: user.username,
+    }
+}
+
+/// Validates an item of the catalog, returning its trimmed name, or the reason if it's not valid.
+async fn validate_item(
+    conn: &db::Connection,
+    id: Option<Uuid>,
+    item: &CatalogItemFormDTO,
+) -> io::Result<Result<String, (Status, String)>> {
+    let name = item.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok(Err((Status::BadRequest, "name can't be empty".to_owned())));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Ok(Err((Status::BadRequest, "name is too long".to_owned())));
+    }
+    if let Err(e) = item.form.check() {
+        return Ok(Err((Status::BadRequest, e)));
+    }
+
+    if let Some(queue_id) = item.queue_id {
+        if conn
+            .run(move |c| db::queue::get_with_id(c, queue_id))
+            .await?
+            .is_none()
+        {
+            return Ok(Err((Status::BadRequest, "queue not found".to_owned())));
+        }
+    }
+    if let Some(chain_id) = item.approval_chain_id {
+        if conn
+            .run(move |c| db::approval::get_chain_with_id(c, chain_id))
+            .await?
+            .is_none()
+        {
+            return Ok(Err((
+                Status::BadRequest,
+                "approval chain not found".to_owned(),
+            )));
+        }
+    }
+
+    let taken_name = name.clone();
+    if conn
+        .run(move |c| db::catalog::item_name_taken(c, &taken_name, id))
+        .await?
+    {
+        return Ok(Err((
+            Status::Conflict,
+            "name belongs to another item".to_owned(),
+        )));
+    }
+
+    Ok(Ok(name))
+}
+
+/// Checks that the users picked in the answers can be picked by the viewer, returning their
+/// usernames by ID, or an error for every field with a user that can't be picked.
+///
+/// Viewers can't pick themselves in the fields that choose the approvers of their request.
+async fn validate_users(
+    conn: &db::Connection,
+    viewer: &db::tenant::Viewer,
+    item: &CatalogItemDTO,
+    answers: &Answers,
+) -> io::Result<Result<HashMap<String, String>, Vec<FieldError>>> {
+    let mut usernames = HashMap::new();
+    let mut errors = Vec::new();
+    let approver_keys = match item.approval_chain_id {
+        Some(chain_id) => approver_keys(conn, chain_id).await?,
+        None => Vec::new(),
+    };
+
+    for field in &item.form.fields {
+        let user_id = match (&field.kind, answers.get(&field.key)) {
+            (FieldKind::User, Some(Value::Text(id))) => id.clone(),
+            _ => continue,
+        };
+
+        let (picker_id, id) = (viewer.user_id, user_id.parse().map_err(into_io_err)?);
+        if id == picker_id && approver_keys.contains(&field.key) {
+            errors.push(FieldError {
+                field: field.key.clone(),
+                message: "can't be you, as it approves your request".to_owned(),
+            });
+            continue;
+        }
+        let organisation_ids = (!viewer.sees_all()).then(|| viewer.organisation_ids.clone());
+        let picked = conn
+            .run(move |c| {
+                db::user::get_pickable(
+                    c,
+                    picker_id,
+                    organisation_ids.as_deref(),
+                    PickFilter::Id(id),
+                    1,
+                )
+            })
+            .await?;
+        match picked.into_iter().next() {
+            Some(user) => {
+                let _ = usernames.insert(user_id, user.username);
+            }
+            None => errors.push(FieldError {
+                field: field.key.clone(),
+                message: "is not a user you can pick".to_owned(),
+            }),
+        }
+    }
+
+    Ok(if errors.is_empty() {
+        Ok(usernames)
+    } else {
+        Err(errors)
+    })
+}
+
+/// Gets the keys of the fields that choose approvers in the steps of a chain.
+async fn approver_keys(conn: &db::Connection, chain_id: Uuid) -> io::Result<Vec<String>> {
+    let chain = match conn
+        .run(move |c| db::approval::get_chain_with_id(c, chain_id))
+        .await?
+    {
+        Some(chain) => chain,
+        None => return Ok(Vec::new()),
+    };
+    let steps = serde_json::from_value::<Vec<StepDefinition>>(chain.steps).map_err(into_io_err)?;
+
+    Ok(steps
+        .into_iter()
+        .flat_map(|step| step.approvers)
+        .filter_map(|approver| match approver {
+            Approver::Field { key } => Some(key),
+            Approver::User { .. } => None,
+        })
+        .collect())
+}
+
+/// Describes the answers to the form of an item, with a line for every answered field.
+fn describe(
+    item: &CatalogItemDTO,
+    answers: &Answers,
+    usernames: &HashMap<String, String>,
+) -> String {
+    item.form
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let answer = match answers.get(&field.key)? {
+                Value::Bool(_) => "yes".to_owned(),
+                Value::Text(id) if field.kind == FieldKind::User => {
+                    usernames.get(id).cloned().unwrap_or_else(|| id.clone())
+                }
+                Value::Text(text) => text.clone(),
+                Value::List(options) => options.join(", "),
+            };
+
+            Some(format!("{}: {}", field.label, answer))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,625 @@
+//! Change management.
+//!
+//! Only agents can see and request changes. Each change is tracked by a ticket, which goes through
+//! the approval chain of the change advisory board set for the risk level of the change. Collisions
+//! with other changes and maintenance windows are reported, not prevented, so that the board can
+//! decide whether colliding changes go ahead.
+
+use super::{auth, parse_date, ticket};
+use crate::{approval, audit, db, ical, into_io_err};
+use chrono::{Duration, Utc};
+use common::{
+    approval::StepDefinition,
+    catalog::{self, FieldError},
+    change::{
+        self, CabChainDTO, ChangeDTO, ChangeFormDTO, ChangePlanDTO, CollisionDTO,
+        MaintenanceWindowDTO, MaintenanceWindowFormDTO, Risk,
+    },
+    ticket::{Priority, Status as TicketStatus},
+};
+use rocket::{
+    delete, get,
+    http::{ContentType, Header, Status},
+    post, put,
+    serde::json::Json,
+    Responder,
+};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum number of changes returned in a single query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of changes returned in a single query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// Maximum number of changes in the calendar.
+const MAX_CALENDAR_CHANGES: i64 = 5000;
+
+/// Number of past days in the calendar when no start is given.
+const CALENDAR_PAST_DAYS: i64 = 30;
+
+/// Maximum length of change and maintenance window titles, in characters.
+const MAX_TITLE_LEN: usize = 200;
+
+/// List the changes planned in a period, by planned start
+///
+/// The period is given as RFC 3339 timestamps, and changes overlapping it are returned.
+#[get("/changes?<from>&<to>&<limit>&<offset>")]
+pub async fn list(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<(Status, Json<Vec<ChangeDTO>>)> {
+    let (from, to) = match (parse_date(from), parse_date(to)) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => return Ok((Status::BadRequest, Json(Vec::new()))),
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+
+    let (changes, mut ci_ids) = conn
+        .run(move |c| {
+            let changes = db::change::get_all(c, from, to, limit, offset)?;
+            let ids = changes
+                .iter()
+                .map(|(change, _)| change.id)
+                .collect::<Vec<_>>();
+            Ok::<_, io::Error>((changes, db::change::get_ci_ids(c, &ids)?))
+        })
+        .await?;
+
+    let changes = changes
+        .into_iter()
+        .map(|(change, ticket)| {
+            let ci_ids = ci_ids.remove(&change.id).unwrap_or_default();
+            change.into_dto(ticket, ci_ids)
+        })
+        .collect::<Result<_, _>>()
+        .map_err(into_io_err)?;
+
+    Ok((Status::Ok, Json(changes)))
+}
+
+/// Get a change
+#[get("/changes/<id>")]
+pub async fn get(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<ChangeDTO>>)> {
+    let change = conn
+        .run(move |c| match db::change::get_with_id(c, id)? {
+            Some((change, ticket)) => {
+                let ci_ids = db::change::get_ci_ids(c, &[id])?.remove(&id);
+                Ok::<_, io::Error>(Some((change, ticket, ci_ids.unwrap_or_default())))
+            }
+            None => Ok(None),
+        })
+        .await?;
+
+    Ok(match change {
+        Some((change, ticket, ci_ids)) => (
+            Status::Ok,
+            Json(Some(change.into_dto(ticket, ci_ids).map_err(into_io_err)?)),
+        ),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Request a change, opening its ticket
+///
+/// The ticket stays pending until the change advisory board approves it, if the risk level of the
+/// change has an approval chain.
+#[post("/changes", format = "json", data = "<change>")]
+pub async fn create(
+    agent: auth::Agent,
+    conn: db::Connection,
+    ctx: audit::Context,
+    change: Json<ChangeFormDTO>,
+) -> io::Result<(Status, Json<Result<ChangeDTO, Vec<FieldError>>>)> {
+    let ChangeFormDTO {
+        title,
+        description,
+        mut plan,
+    } = change.into_inner();
+    let title = title.trim().to_owned();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LEN {
+        let error = field_error("title", "title must be between 1 and 200 characters");
+        return Ok((Status::BadRequest, Json(Err(vec![error]))));
+    }
+    let assessment = match validate_plan(&conn, &mut plan).await? {
+        Ok(assessment) => assessment,
+        Err(errors) => return Ok((Status::BadRequest, Json(Err(errors)))),
+    };
+
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let answers = serde_json::to_value(&plan.answers).map_err(into_io_err)?;
+    let (change, opened) = conn
+        .run(move |c| {
+            let opened = db::ticket::insert(
+                c,
+                &viewer,
+                &db::model::NewTicket {
+                    title: &title,
+                    description: &description,
+                    priority: priority(assessment.risk).as_str(),
+                    requester_id: viewer.user_id,
+                    organisation_id: None,
+                    queue_id: None,
+                    category: None,
+                    custom_fields: None,
+                },
+            )?;
+            let change = db::change::insert(
+                c,
+                opened.id,
+                &db::model::ChangePlan {
+                    planned_start: plan.planned_start,
+                    planned_end: plan.planned_end,
+                    answers,
+                    risk_score: assessment.risk_score,
+                    impact_score: assessment.impact_score,
+                    risk: assessment.risk.as_str(),
+                },
+                &plan.ci_ids,
+            )?;
+
+            let opened = match db::change::get_cab_chain(c, assessment.risk)? {
+                Some(chain) => {
+                    let steps = serde_json::from_value::<Vec<StepDefinition>>(chain.steps)
+                        .map_err(into_io_err)?;
+                    approval::start(c, &ctx, opened, &steps, &catalog::Answers::new())?
+                }
+                None => opened,
+            };
+            Ok::<_, io::Error>((change, opened))
+        })
+        .await?;
+    let opened = ticket::opened(&conn, agent.id, opened).await?;
+
+    let ci_ids = conn
+        .run(move |c| db::change::get_ci_ids(c, &[change.id]))
+        .await?
+        .remove(&change.id)
+        .unwrap_or_default();
+
+    Ok((
+        Status::Created,
+        Json(Ok(change.into_dto(opened, ci_ids).map_err(into_io_err)?)),
+    ))
+}
+
+/// Update the plan of a change, assessing it again
+///
+/// Only active changes can be planned again, and they don't go through the approval again.
+#[put("/changes/<id>", format = "json", data = "<plan>")]
+pub async fn update(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    plan: Json<ChangePlanDTO>,
+) -> io::Result<(Status, Json<Result<ChangeDTO, Vec<FieldError>>>)> {
+    let mut plan = plan.into_inner();
+    let assessment = match validate_plan(&conn, &mut plan).await? {
+        Ok(assessment) => assessment,
+        Err(errors) => return Ok((Status::BadRequest, Json(Err(errors)))),
+    };
+
+    let answers = serde_json::to_value(&plan.answers).map_err(into_io_err)?;
+    conn.run(move |c| {
+        let ticket = match db::change::get_with_id(c, id)? {
+            Some((_, ticket)) => ticket,
+            None => return Ok((Status::NotFound, Json(Err(Vec::new())))),
+        };
+        if !ticket.status().is_active() {
+            let error = field_error("status", "finished changes can't be planned again");
+            return Ok((Status::BadRequest, Json(Err(vec![error]))));
+        }
+
+        let plan_record = db::model::ChangePlan {
+            planned_start: plan.planned_start,
+            planned_end: plan.planned_end,
+            answers,
+            risk_score: assessment.risk_score,
+            impact_score: assessment.impact_score,
+            risk: assessment.risk.as_str(),
+        };
+        match db::change::update(c, id, &plan_record, &plan.ci_ids)? {
+            Some(change) => {
+                let ci_ids = db::change::get_ci_ids(c, &[id])?.remove(&id);
+                let change = change
+                    .into_dto(ticket, ci_ids.unwrap_or_default())
+                    .map_err(into_io_err)?;
+                Ok((Status::Ok, Json(Ok(change))))
+            }
+            None => Ok((Status::NotFound, Json(Err(Vec::new())))),
+        }
+    })
+    .await
+}
+
+/// Get the other active changes and the maintenance windows colliding with a change
+///
+/// They collide if they overlap the planned period of the change and touch some of its
+/// configuration items.
+#[get("/changes/<id>/collisions")]
+pub async fn collisions(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<CollisionDTO>>)> {
+    conn.run(move |c| {
+        let change = match db::change::get_with_id(c, id)? {
+            Some((change, _)) => change,
+            None => return Ok((Status::NotFound, Json(Vec::new()))),
+        };
+        let ci_ids = db::change::get_ci_ids(c, &[id])?
+            .remove(&id)
+            .unwrap_or_default();
+        let (start, end) = (change.planned_start, change.planned_end);
+
+        let changes =
+            db::change::get_colliding_changes(c, Some(id), start, end, &ci_ids)?.into_iter();
+        let windows = db::change::get_colliding_windows(c, start, end, &ci_ids)?.into_iter();
+        let collisions = changes
+            .map(|(change, ticket, ci_ids)| CollisionDTO::Change {
+                id: change.id,
+                number: ticket.number,
+                title: ticket.title,
+                starts_on: change.planned_start,
+                ends_on: change.planned_end,
+                ci_ids,
+            })
+            .chain(
+                windows.map(|(window, ci_ids)| CollisionDTO::MaintenanceWindow {
+                    id: window.id,
+                    title: window.title,
+                    starts_on: window.starts_on,
+                    ends_on: window.ends_on,
+                    ci_ids,
+                }),
+            )
+            .collect();
+
+        Ok((Status::Ok, Json(collisions)))
+    })
+    .await
+}
+
+/// Calendar, downloaded as an iCalendar file.
+#[derive(Responder)]
+pub struct Calendar {
+    data: String,
+    content_type: ContentType,
+    disposition: Header<'static>,
+}
+
+/// Export the calendar of the changes and maintenance windows as iCalendar
+///
+/// The period is given as RFC 3339 timestamps, and starts 30 days ago by default. Closed changes
+/// were cancelled, so they are left out.
+#[get("/changes/calendar.ics?<from>&<to>")]
+pub async fn calendar(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> io::Result<Result<Calendar, Status>> {
+    let now = Utc::now();
+    let (from, to) = match (parse_date(from), parse_date(to)) {
+        (Ok(from), Ok(to)) => (
+            from.unwrap_or_else(|| now - Duration::days(CALENDAR_PAST_DAYS)),
+            to,
+        ),
+        _ => return Ok(Err(Status::BadRequest)),
+    };
+
+    let (changes, windows) = conn
+        .run(move |c| {
+            let changes = db::change::get_all(c, Some(from), to, MAX_CALENDAR_CHANGES, 0)?;
+            Ok::<_, io::Error>((changes, db::change::get_windows(c, Some(from), to)?))
+        })
+        .await?;
+
+    let changes = changes
+        .into_iter()
+        .filter(|(_, ticket)| ticket.status() != TicketStatus::Closed)
+        .map(|(change, ticket)| {
+            let summary = format!("Change #{}: {}", ticket.number, ticket.title);
+            (change, ticket, summary)
+        })
+        .collect::<Vec<_>>();
+    let windows = windows
+        .into_iter()
+        .map(|window| {
+            let summary = format!("Maintenance: {}", window.title);
+            (window, summary)
+        })
+        .collect::<Vec<_>>();
+
+    let events = changes
+        .iter()
+        .map(|(change, ticket, summary)| ical::Event {
+            uid: format!("change-{}@mysupport", change.id),
+            summary,
+            description: &ticket.description,
+            start: change.planned_start,
+            end: change.planned_end,
+        })
+        .chain(windows.iter().map(|(window, summary)| ical::Event {
+            uid: format!("maintenance-{}@mysupport", window.id),
+            summary,
+            description: "",
+            start: window.starts_on,
+            end: window.ends_on,
+        }))
+        .collect::<Vec<_>>();
+
+    Ok(Ok(Calendar {
+        data: ical::write("Changes", &events, now),
+        content_type: ContentType::Calendar,
+        disposition: Header::new(
+            "Content-Disposition",
+            "attachment; filename=\"changes.ics\"",
+        ),
+    }))
+}
+
+/// List the maintenance windows in a period, by start
+///
+/// The period is given as RFC 3339 timestamps, and windows overlapping it are returned.
+#[get("/maintenance-windows?<from>&<to>")]
+pub async fn windows(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> io::Result<(Status, Json<Vec<MaintenanceWindowDTO>>)> {
+    let (from, to) = match (parse_date(from), parse_date(to)) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => return Ok((Status::BadRequest, Json(Vec::new()))),
+    };
+
+    let (windows, mut ci_ids) = conn
+        .run(move |c| {
+            let windows = db::change::get_windows(c, from, to)?;
+            let ids = windows.iter().map(|window| window.id).collect::<Vec<_>>();
+            Ok::<_, io::Error>((windows, db::change::get_window_ci_ids(c, &ids)?))
+        })
+        .await?;
+
+    Ok((
+        Status::Ok,
+        Json(
+            windows
+                .into_iter()
+                .map(|window| {
+                    let ci_ids = ci_ids.remove(&window.id).unwrap_or_default();
+                    window.into_dto(ci_ids)
+                })
+                .collect(),
+        ),
+    ))
+}
+
+/// Schedule a maintenance window
+#[post("/maintenance-windows", format = "json", data = "<window>")]
+pub async fn create_window(
+    agent: auth::Agent,
+    conn: db::Connection,
+    window: Json<MaintenanceWindowFormDTO>,
+) -> io::Result<(Status, Json<Result<MaintenanceWindowDTO, &'static str>>)> {
+    let window = match validate_window(&conn, window.into_inner()).await? {
+        Ok(window) => window,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    let created_by = agent.id;
+    let created = conn
+        .run(move |c| {
+            let created = db::change::insert_window(
+                c,
+                &db::model::MaintenanceWindowForm {
+                    title: &window.title,
+                    starts_on: window.starts_on,
+                    ends_on: window.ends_on,
+                },
+                created_by,
+                &window.ci_ids,
+            )?;
+            let ci_ids = db::change::get_window_ci_ids(c, &[created.id])?.remove(&created.id);
+            Ok::<_, io::Error>(created.into_dto(ci_ids.unwrap_or_default()))
+        })
+        .await?;
+
+    Ok((Status::Created, Json(Ok(created))))
+}
+
+/// Update a maintenance window
+#[put("/maintenance-windows/<id>", format = "json", data = "<window>")]
+pub async fn update_window(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    window: Json<MaintenanceWindowFormDTO>,
+) -> io::Result<(Status, Json<Result<MaintenanceWindowDTO, &'static str>>)> {
+    let window = match validate_window(&conn, window.into_inner()).await? {
+        Ok(window) => window,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    let updated = conn
+        .run(move |c| {
+            let updated = db::change::update_window(
+                c,
+                id,
+                &db::model::MaintenanceWindowForm {
+                    title: &window.title,
+                    starts_on: window.starts_on,
+                    ends_on: window.ends_on,
+                },
+                &window.ci_ids,
+            )?;
+            match updated {
+                Some(updated) => {
+                    let ci_ids = db::change::get_window_ci_ids(c, &[id])?.remove(&id);
+                    Ok::<_, io::Error>(Some(updated.into_dto(ci_ids.unwrap_or_default())))
+                }
+                None => Ok(None),
+            }
+        })
+        .await?;
+
+    Ok(match updated {
+        Some(updated) => (Status::Ok, Json(Ok(updated))),
+        None => (Status::NotFound, Json(Err("maintenance window not found"))),
+    })
+}
+
+/// Cancel a maintenance window
+#[delete("/maintenance-windows/<id>")]
+pub async fn delete_window(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<Status> {
+    Ok(
+        if conn.run(move |c| db::change::delete_window(c, id)).await? {
+            Status::NoContent
+        } else {
+            Status::NotFound
+        },
+    )
+}
+
+/// List the approval chains of the change advisory board, for each risk level
+#[get("/cab-chains")]
+pub async fn cab_chains(
+    _agent: auth::Agent,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<CabChainDTO>>> {
+    let chains = conn.run(db::change::get_cab_chains).await?;
+
+    Ok(Json(
+        Risk::ALL
+            .iter()
+            .map(|&risk| CabChainDTO {
+                risk,
+                approval_chain_id: chains
+                    .iter()
+                    .find(|(chain_risk, _)| chain_risk == risk.as_str())
+                    .map(|&(_, chain_id)| chain_id),
+            })
+            .collect(),
+    ))
+}
+
+/// Set or remove the approval chain of the change advisory board for a risk level
+///
+/// Changes already requested keep going through the approval they started with.
+#[put("/cab-chains", format = "json", data = "<chain>")]
+pub async fn set_cab_chain(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    chain: Json<CabChainDTO>,
+) -> io::Result<(Status, Json<Result<CabChainDTO, &'static str>>)> {
+    let chain = chain.into_inner();
+    let CabChainDTO {
+        risk,
+        approval_chain_id,
+    } = chain;
+
+    conn.run(move |c| {
+        if let Some(chain_id) = approval_chain_id {
+            if db::approval::get_chain_with_id(c, chain_id)?.is_none() {
+                return Ok((Status::NotFound, Json(Err("approval chain not found"))));
+            }
+        }
+        db::change::set_cab_chain(c, risk, approval_chain_id)?;
+
+        Ok((Status::Ok, Json(Ok(chain))))
+    })
+    .await
+}
+
+/// Validates the plan of a change, deduplicating its configuration items, and assesses it.
+async fn validate_plan(
+    conn: &db::Connection,
+    plan: &mut ChangePlanDTO,
+) -> io::Result<Result<change::Assessment, Vec<FieldError>>> {
+    if let Err(e) = plan.check() {
+        return Ok(Err(vec![field_error("planned_end", e)]));
+    }
+    let assessment = match change::assess(&plan.answers) {
+        Ok(assessment) => assessment,
+        Err(errors) => return Ok(Err(errors)),
+    };
+
+    plan.ci_ids.sort_unstable();
+    plan.ci_ids.dedup();
+    if !items_exist(conn, plan.ci_ids.clone()).await? {
+        return Ok(Err(vec![field_error(
+            "ci_ids",
+            "configuration item not found",
+        )]));
+    }
+
+    Ok(Ok(assessment))
+}
+
+/// Validates a maintenance window, trimming its title and deduplicating its configuration items.
+async fn validate_window(
+    conn: &db::Connection,
+    mut window: MaintenanceWindowFormDTO,
+) -> io::Result<Result<MaintenanceWindowFormDTO, &'static str>> {
+    window.title = window.title.trim().to_owned();
+    if window.title.is_empty() || window.title.chars().count() > MAX_TITLE_LEN {
+        return Ok(Err("title must be between 1 and 200 characters"));
+    }
+    if let Err(e) = window.check() {
+        return Ok(Err(e));
+    }
+
+    window.ci_ids.sort_unstable();
+    window.ci_ids.dedup();
+    if !items_exist(conn, window.ci_ids.clone()).await? {
+        return Ok(Err("configuration item not found"));
+    }
+
+    Ok(Ok(window))
+}
+
+/// Checks that configuration items exist.
+async fn items_exist(conn: &db::Connection, ci_ids: Vec<Uuid>) -> io::Result<bool> {
+    conn.run(move |c| {
+        for ci_id in ci_ids {
+            if db::cmdb::get_item_with_id(c, ci_id)?.is_none() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    })
+    .await
+}
+
+/// Gets the priority of the ticket of a change with a risk level.
+fn priority(risk: Risk) -> Priority {
+    match risk {
+        Risk::Low => Priority::Low,
+        Risk::Medium => Priority::Normal,
+        Risk::High => Priority::High,
+    }
+}
+
+/// Creates the error of a field.
+fn field_error(field: &str, message: &str) -> FieldError {
+    FieldError {
+        field: field.to_owned(),
+        message: message.to_owned(),
+    }
+}
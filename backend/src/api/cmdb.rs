@@ -0,0 +1,841 @@
+//! Configuration management database (CMDB).
+//!
+//! Only agents can see and edit configuration items and their relationships, while types are
+//! defined by administrators. Items linked to a ticket can be seen by anyone who can see the
+//! ticket, so that customers know which of their devices or services are concerned.
+
+use super::{auth, ticket::viewer};
+use crate::{csv, db, into_io_err};
+use common::{
+    cmdb::{
+        CiLinkDTO, CiTypeDTO, CiTypeFormDTO, ConfigurationItemDTO, ConfigurationItemFormDTO,
+        ImpactedItemDTO, ImportErrorDTO, ImportReportDTO, RelationshipDTO, RelationshipFormDTO,
+        NAME_COLUMN,
+    },
+    custom_field::{Kind, Value, Values},
+    ticket::TicketDTO,
+};
+use rocket::{
+    data::{Data, ToByteUnit},
+    delete, get,
+    http::Status,
+    post, put,
+    serde::json::Json,
+};
+use std::{collections::HashSet, convert::TryFrom, io};
+use uuid::Uuid;
+
+/// Maximum number of items returned in a single query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of items returned in a single query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// Maximum length of type names, in characters.
+const MAX_TYPE_NAME_LEN: usize = 100;
+
+/// Maximum length of item names, in characters.
+const MAX_ITEM_NAME_LEN: usize = 200;
+
+/// Maximum number of relationships walked to find the impact of an outage.
+const MAX_DEPTH: i32 = 20;
+
+/// Default number of relationships walked to find the impact of an outage.
+const DEFAULT_DEPTH: i32 = 10;
+
+/// Maximum size of a CSV import, in mebibytes.
+const MAX_IMPORT_MIB: u64 = 10;
+
+/// List all the types of configuration items
+#[get("/ci-types")]
+pub async fn types(_agent: auth::Agent, conn: db::Connection) -> io::Result<Json<Vec<CiTypeDTO>>> {
+    conn.run(db::cmdb::get_types)
+        .await?
+        .into_iter()
+        .map(|ci_type| CiTypeDTO::try_from(ci_type).map_err(into_io_err))
+        .collect::<io::Result<_>>()
+        .map(Json)
+}
+
+/// Create a new type of configuration items
+#[post("/ci-types", format = "json", data = "<ci_type>")]
+pub async fn create_type(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    ci_type: Json<CiTypeFormDTO>,
+) -> io::Result<(Status, Json<Result<CiTypeDTO, String>>)> {
+    let ci_type = match validate_type(&conn, None, ci_type.into_inner()).await? {
+        Ok(ci_type) => ci_type,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let attributes = serde_json::to_value(&ci_type.attributes).map_err(into_io_err)?;
+    let created = conn
+        .run(move |c| {
+            db::cmdb::insert_type(
+                c,
+                &db::model::CiTypeForm {
+                    name: &ci_type.name,
+                    attributes,
+                },
+            )
+        })
+        .await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(CiTypeDTO::try_from(created).map_err(into_io_err)?)),
+    ))
+}
+
+/// Update a type of configuration items
+#[put("/ci-types/<id>", format = "json", data = "<ci_type>")]
+pub async fn update_type(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    ci_type: Json<CiTypeFormDTO>,
+) -> io::Result<(Status, Json<Result<CiTypeDTO, String>>)> {
+    let ci_type = match validate_type(&conn, Some(id), ci_type.into_inner()).await? {
+        Ok(ci_type) => ci_type,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let attributes = serde_json::to_value(&ci_type.attributes).map_err(into_io_err)?;
+    let updated = conn
+        .run(move |c| {
+            db::cmdb::update_type(
+                c,
+                id,
+                &db::model::CiTypeForm {
+                    name: &ci_type.name,
+                    attributes,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match updated {
+        Some(updated) => (
+            Status::Ok,
+            Json(Ok(CiTypeDTO::try_from(updated).map_err(into_io_err)?)),
+        ),
+        None => (Status::NotFound, Json(Err("type not found".to_owned()))),
+    })
+}
+
+/// Delete a type of configuration items, if it has no items
+#[delete("/ci-types/<id>")]
+pub async fn delete_type(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<Status> {
+    conn.run(move |c| {
+        if db::cmdb::type_has_items(c, id)? {
+            return Ok(Status::Conflict);
+        }
+
+        Ok(if db::cmdb::delete_type(c, id)? {
+            Status::NoContent
+        } else {
+            Status::NotFound
+        })
+    })
+    .await
+}
+
+/// Import configuration items of a type from a CSV file
+///
+/// The header holds the `name` column and attribute keys. Items are matched by name, and existing
+/// items get the imported attributes. Nothing is imported if a line has an error.
+#[post("/ci-types/<id>/import", data = "<raw>")]
+pub async fn import(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    raw: Data<'_>,
+) -> io::Result<(Status, Json<Option<ImportReportDTO>>)> {
+    let ci_type = match conn.run(move |c| db::cmdb::get_type_with_id(c, id)).await? {
+        Some(ci_type) => CiTypeDTO::try_from(ci_type).map_err(into_io_err)?,
+        None => return Ok((Status::NotFound, Json(None))),
+    };
+    let raw = raw.open(MAX_IMPORT_MIB.mebibytes()).into_string().await;
+    let raw = match raw {
+        Ok(raw) if raw.is_complete() => raw.into_inner(),
+        Ok(_) => return Ok((Status::PayloadTooLarge, Json(None))),
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+            return Ok(failed_import(1, "the file is not valid UTF-8".to_owned()))
+        }
+        Err(e) => return Err(e),
+    };
+
+    let records = match csv::parse(&raw) {
+        Ok(records) => records,
+        Err(e) => return Ok(failed_import(e.line, e.message.to_owned())),
+    };
+    let (header, records) = match records.split_first() {
+        Some((header, records)) => (header, records),
+        None => return Ok(failed_import(1, "the file has no header".to_owned())),
+    };
+    if let Err(e) = check_header(&ci_type, &header.fields) {
+        return Ok(failed_import(header.line, e));
+    }
+
+    let mut items = Vec::with_capacity(records.len());
+    let mut errors = Vec::new();
+    for record in records {
+        match parse_record(&ci_type, &header.fields, &record.fields) {
+            Ok(item) => items.push((record.line, item)),
+            Err(message) => errors.push(ImportErrorDTO {
+                line: record.line,
+                message,
+            }),
+        }
+    }
+    let mut user_ids = HashSet::new();
+    for (_, (_, values)) in &items {
+        for (_, id) in referenced_users(&ci_type, values)? {
+            let _ = user_ids.insert(id);
+        }
+    }
+    let active = active_users(&conn, user_ids.into_iter().collect()).await?;
+    for (line, (_, values)) in &items {
+        if let Err(message) = check_users(&ci_type, values, &active)? {
+            errors.push(ImportErrorDTO {
+                line: *line,
+                message,
+            });
+        }
+    }
+    let mut names = HashSet::new();
+    for (line, (name, _)) in &items {
+        if !names.insert(name.as_str()) {
+            errors.push(ImportErrorDTO {
+                line: *line,
+                message: format!("{:?} is already on a previous line", name),
+            });
+        }
+    }
+    let names = items
+        .iter()
+        .map(|(_, (name, _))| name.clone())
+        .collect::<Vec<_>>();
+    let existing = conn
+        .run(move |c| {
+            let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+            db::cmdb::get_items_with_names(c, &names)
+        })
+        .await?;
+    for (line, (name, _)) in &items {
+        if existing
+            .iter()
+            .any(|item| item.name == *name && item.type_id != id)
+        {
+            errors.push(ImportErrorDTO {
+                line: *line,
+                message: format!("{:?} is an item of another type", name),
+            });
+        }
+    }
+    if !errors.is_empty() {
+        errors.sort_by_key(|error| error.line);
+        return Ok((
+            Status::BadRequest,
+            Json(Some(ImportReportDTO {
+                errors,
+                ..ImportReportDTO::default()
+            })),
+        ));
+    }
+
+    let items = items
+        .into_iter()
+        .map(|(_, (name, values))| {
+            serde_json::to_value(values)
+                .map(|values| (name, values))
+                .map_err(into_io_err)
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    let (created, updated) = conn.run(move |c| db::cmdb::import(c, id, &items)).await?;
+
+    Ok((
+        Status::Ok,
+        Json(Some(ImportReportDTO {
+            created,
+            updated,
+            errors: Vec::new(),
+        })),
+    ))
+}
+
+/// List the configuration items, optionally of a type, ordered by name
+#[get("/configuration-items?<type_id>&<limit>&<offset>")]
+pub async fn items(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    type_id: Option<Uuid>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<Json<Vec<ConfigurationItemDTO>>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+
+    conn.run(move |c| db::cmdb::get_items(c, type_id, limit, offset))
+        .await?
+        .into_iter()
+        .map(|item| ConfigurationItemDTO::try_from(item).map_err(into_io_err))
+        .collect::<io::Result<_>>()
+        .map(Json)
+}
+
+/// Get a configuration item
+#[get("/configuration-items/<id>")]
+pub async fn item(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<ConfigurationItemDTO>>)> {
+    Ok(
+        match conn.run(move |c| db::cmdb::get_item_with_id(c, id)).await? {
+            Some(item) => (
+                Status::Ok,
+                Json(Some(
+                    ConfigurationItemDTO::try_from(item).map_err(into_io_err)?,
+                )),
+            ),
+            None => (Status::NotFound, Json(None)),
+        },
+    )
+}
+
+/// Create a new configuration item
+#[post("/configuration-items", format = "json", data = "<item>")]
+pub async fn create_item(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    item: Json<ConfigurationItemFormDTO>,
+) -> io::Result<(Status, Json<Result<ConfigurationItemDTO, String>>)> {
+    let item = match validate_item(&conn, None, item.into_inner()).await? {
+        Ok(item) => item,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let attributes = serde_json::to_value(&item.attributes).map_err(into_io_err)?;
+    let created = conn
+        .run(move |c| {
+            db::cmdb::insert_item(
+                c,
+                &db::model::ConfigurationItemForm {
+                    type_id: item.type_id,
+                    name: &item.name,
+                    attributes,
+                },
+            )
+        })
+        .await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(
+            ConfigurationItemDTO::try_from(created).map_err(into_io_err)?
+        )),
+    ))
+}
+
+/// Update a configuration item
+#[put("/configuration-items/<id>", format = "json", data = "<item>")]
+pub async fn update_item(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    item: Json<ConfigurationItemFormDTO>,
+) -> io::Result<(Status, Json<Result<ConfigurationItemDTO, String>>)> {
+    let item = match validate_item(&conn, Some(id), item.into_inner()).await? {
+        Ok(item) => item,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let attributes = serde_json::to_value(&item.attributes).map_err(into_io_err)?;
+    let updated = conn
+        .run(move |c| {
+            db::cmdb::update_item(
+                c,
+                id,
+                &db::model::ConfigurationItemForm {
+                    type_id: item.type_id,
+                    name: &item.name,
+                    attributes,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match updated {
+        Some(updated) => (
+            Status::Ok,
+            Json(Ok(
+                ConfigurationItemDTO::try_from(updated).map_err(into_io_err)?
+            )),
+        ),
+        None => (Status::NotFound, Json(Err("item not found".to_owned()))),
+    })
+}
+
+/// Delete a configuration item, along with its relationships and ticket links
+#[delete("/configuration-items/<id>")]
+pub async fn delete_item(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<Status> {
+    let deleted = conn.run(move |c| db::cmdb::delete_item(c, id)).await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the relationships of a configuration item, as the source or the target
+#[get("/configuration-items/<id>/relationships")]
+pub async fn relationships(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<RelationshipDTO>>)> {
+    let relationships = conn
+        .run(move |c| {
+            if db::cmdb::get_item_with_id(c, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::cmdb::get_relationships(c, id).map(Some)
+        })
+        .await?;
+
+    Ok(match relationships {
+        Some(relationships) => (
+            Status::Ok,
+            Json(relationships.into_iter().map(Into::into).collect()),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Relate a configuration item, as the source, to a target item
+#[post(
+    "/configuration-items/<id>/relationships",
+    format = "json",
+    data = "<relationship>"
+)]
+pub async fn relate(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    relationship: Json<RelationshipFormDTO>,
+) -> io::Result<(Status, Json<Result<Option<RelationshipDTO>, &'static str>>)> {
+    let RelationshipFormDTO { target_id, kind } = relationship.into_inner();
+    if target_id == id {
+        return Ok((
+            Status::BadRequest,
+            Json(Err("an item can't be related to itself")),
+        ));
+    }
+
+    conn.run(move |c| {
+        if db::cmdb::get_item_with_id(c, id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("item not found"))));
+        }
+        if db::cmdb::get_item_with_id(c, target_id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("target not found"))));
+        }
+
+        let related = db::cmdb::relate(
+            c,
+            &db::model::NewCiRelationship {
+                source_id: id,
+                target_id,
+                kind: kind.as_str(),
+            },
+        )?;
+        Ok(match related {
+            Some(related) => (Status::Created, Json(Ok(Some(related.into())))),
+            None => (Status::Ok, Json(Ok(None))),
+        })
+    })
+    .await
+}
+
+/// Delete a relationship of a configuration item
+#[delete("/configuration-items/<id>/relationships/<relationship_id>")]
+pub async fn unrelate(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    relationship_id: Uuid,
+) -> io::Result<Status> {
+    let deleted = conn
+        .run(move |c| db::cmdb::unrelate(c, id, relationship_id))
+        .await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the configuration items impacted by an outage of an item, nearest first
+///
+/// The dependency graph is walked up to `depth` relationships away.
+#[get("/configuration-items/<id>/impact?<depth>")]
+pub async fn impact(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    depth: Option<i32>,
+) -> io::Result<(Status, Json<Vec<ImpactedItemDTO>>)> {
+    let depth = depth.unwrap_or(DEFAULT_DEPTH).clamp(1, MAX_DEPTH);
+    let impacted = conn
+        .run(move |c| {
+            if db::cmdb::get_item_with_id(c, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::cmdb::get_impacted(c, id, depth).map(Some)
+        })
+        .await?;
+
+    Ok(match impacted {
+        Some(impacted) => (
+            Status::Ok,
+            Json(impacted.into_iter().map(Into::into).collect()),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// List the tickets linked to a configuration item, newest first
+#[get("/configuration-items/<id>/tickets")]
+pub async fn tickets(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<Json<Vec<TicketDTO>>> {
+    let viewer = viewer(&conn, &agent).await?;
+    let tickets = conn
+        .run(move |c| db::cmdb::get_linked_tickets(c, &viewer, id))
+        .await?;
+
+    Ok(Json(tickets.into_iter().map(Into::into).collect()))
+}
+
+/// List the configuration items linked to a ticket
+#[get("/tickets/<id>/configuration-items")]
+pub async fn linked(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<ConfigurationItemDTO>>)> {
+    let viewer = viewer(&conn, &user).await?;
+    let items = conn
+        .run(move |c| {
+            if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::cmdb::get_linked(c, id).map(Some)
+        })
+        .await?;
+
+    Ok(match items {
+        Some(items) => (
+            Status::Ok,
+            Json(
+                items
+                    .into_iter()
+                    .map(|item| ConfigurationItemDTO::try_from(item).map_err(into_io_err))
+                    .collect::<io::Result<_>>()?,
+            ),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Link a configuration item to a ticket
+#[post("/tickets/<id>/configuration-items", format = "json", data = "<link>")]
+pub async fn link(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    link: Json<CiLinkDTO>,
+) -> io::Result<(Status, Json<Result<(), &'static str>>)> {
+    let viewer = viewer(&conn, &agent).await?;
+    let (ci_id, linked_by) = (link.ci_id, agent.id);
+
+    conn.run(move |c| {
+        if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("ticket not found"))));
+        }
+        if db::cmdb::get_item_with_id(c, ci_id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("item not found"))));
+        }
+
+        Ok(if db::cmdb::link(c, id, ci_id, linked_by)? {
+            (Status::Created, Json(Ok(())))
+        } else {
+            (Status::Ok, Json(Ok(())))
+        })
+    })
+    .await
+}
+
+/// Unlink a configuration item from a ticket
+#[delete("/tickets/<id>/configuration-items/<ci_id>")]
+pub async fn unlink(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    ci_id: Uuid,
+) -> io::Result<Status> {
+    let unlinked = conn.run(move |c| db::cmdb::unlink(c, id, ci_id)).await?;
+
+    Ok(if unlinked {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// Validates a type, returning it with its name and labels trimmed.
+async fn validate_type(
+    conn: &db::Connection,
+    id: Option<Uuid>,
+    mut ci_type: CiTypeFormDTO,
+) -> io::Result<Result<CiTypeFormDTO, (Status, String)>> {
+    if let Err(e) = ci_type.check() {
+        return Ok(Err((Status::BadRequest, e)));
+    }
+    ci_type.name = ci_type.name.trim().to_owned();
+    if ci_type.name.chars().count() > MAX_TYPE_NAME_LEN {
+        return Ok(Err((
+            Status::BadRequest,
+            format!("name can't be longer than {} characters", MAX_TYPE_NAME_LEN),
+        )));
+    }
+    for attribute in &mut ci_type.attributes {
+        attribute.label = attribute.label.trim().to_owned();
+    }
+
+    let name = ci_type.name.clone();
+    if conn
+        .run(move |c| db::cmdb::type_name_taken(c, &name, id))
+        .await?
+    {
+        return Ok(Err((
+            Status::Conflict,
+            "name belongs to another type".to_owned(),
+        )));
+    }
+
+    Ok(Ok(ci_type))
+}
+
+/// Validates an item, returning it with its name trimmed and its attributes normalized.
+async fn validate_item(
+    conn: &db::Connection,
+    id: Option<Uuid>,
+    mut item: ConfigurationItemFormDTO,
+) -> io::Result<Result<ConfigurationItemFormDTO, (Status, String)>> {
+    item.name = item.name.trim().to_owned();
+    if let Err(e) = check_name(&item.name) {
+        return Ok(Err((Status::BadRequest, e)));
+    }
+    let type_id = item.type_id;
+    let ci_type = match conn
+        .run(move |c| db::cmdb::get_type_with_id(c, type_id))
+        .await?
+    {
+        Some(ci_type) => CiTypeDTO::try_from(ci_type).map_err(into_io_err)?,
+        None => return Ok(Err((Status::BadRequest, "type not found".to_owned()))),
+    };
+    item.attributes = match validate_attributes(conn, &ci_type, &item.attributes).await? {
+        Ok(attributes) => attributes,
+        Err(e) => return Ok(Err((Status::BadRequest, e))),
+    };
+
+    let name = item.name.clone();
+    if conn
+        .run(move |c| db::cmdb::item_name_taken(c, &name, id))
+        .await?
+    {
+        return Ok(Err((
+            Status::Conflict,
+            "name belongs to another item".to_owned(),
+        )));
+    }
+
+    Ok(Ok(item))
+}
+
+/// Checks the trimmed name of an item.
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        Err("name can't be empty".to_owned())
+    } else if name.chars().count() > MAX_ITEM_NAME_LEN {
+        Err(format!(
+            "name can't be longer than {} characters",
+            MAX_ITEM_NAME_LEN
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Validates the attribute values of an item of a type, returning them normalized.
+///
+/// Users must be active users.
+async fn validate_attributes(
+    conn: &db::Connection,
+    ci_type: &CiTypeDTO,
+    values: &Values,
+) -> io::Result<Result<Values, String>> {
+    let values = match normalize_attributes(ci_type, values) {
+        Ok(values) => values,
+        Err(e) => return Ok(Err(e)),
+    };
+    let user_ids = referenced_users(ci_type, &values)?
+        .into_iter()
+        .map(|(_, id)| id)
+        .collect();
+    let active = active_users(conn, user_ids).await?;
+
+    Ok(check_users(ci_type, &values, &active)?.map(|()| values))
+}
+
+/// Normalizes the attribute values of an item of a type, checking them except for their users.
+fn normalize_attributes(ci_type: &CiTypeDTO, values: &Values) -> Result<Values, String> {
+    ci_type.validate(values).map_err(|errors| {
+        errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect::<Vec<_>>()
+            .join(", ")
+    })
+}
+
+/// Gets the users that normalized attribute values refer to, with the keys of their attributes.
+fn referenced_users<'a>(
+    ci_type: &'a CiTypeDTO,
+    values: &Values,
+) -> io::Result<Vec<(&'a str, Uuid)>> {
+    let mut users = Vec::new();
+    for attribute in ci_type.attributes.iter().filter(|a| a.kind == Kind::User) {
+        if let Some(Value::Text(id)) = values.get(&attribute.key) {
+            users.push((attribute.key.as_str(), id.parse().map_err(into_io_err)?));
+        }
+    }
+
+    Ok(users)
+}
+
+/// Retrieves which of the given users are active, in a single query.
+async fn active_users(conn: &db::Connection, ids: Vec<Uuid>) -> io::Result<HashSet<Uuid>> {
+    if ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    Ok(conn
+        .run(move |c| db::user::get_active_ids(c, &ids))
+        .await?
+        .into_iter()
+        .collect())
+}
+
+/// Checks that the users normalized attribute values refer to are among the given active users.
+fn check_users(
+    ci_type: &CiTypeDTO,
+    values: &Values,
+    active: &HashSet<Uuid>,
+) -> io::Result<Result<(), String>> {
+    for (key, id) in referenced_users(ci_type, values)? {
+        if !active.contains(&id) {
+            return Ok(Err(format!("{}: is not an active user", key)));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+/// Checks that the columns of a CSV header are the name and distinct attributes of a type.
+fn check_header(ci_type: &CiTypeDTO, header: &[String]) -> Result<(), String> {
+    if !header.iter().any(|column| column == NAME_COLUMN) {
+        return Err(format!("the header has no {:?} column", NAME_COLUMN));
+    }
+    for (i, column) in header.iter().enumerate() {
+        if header[..i].contains(column) {
+            return Err(format!("column {:?} is repeated", column));
+        }
+        if column != NAME_COLUMN && !ci_type.attributes.iter().any(|a| a.key == *column) {
+            return Err(format!("{:?} is not an attribute of this type", column));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a CSV record into the name and normalized attributes of an item.
+///
+/// The users of the attributes are left to [check](check_users) for all the records at once.
+fn parse_record(
+    ci_type: &CiTypeDTO,
+    header: &[String],
+    fields: &[String],
+) -> Result<(String, Values), String> {
+    if fields.len() != header.len() {
+        return Err(format!(
+            "expected {} fields, found {}",
+            header.len(),
+            fields.len()
+        ));
+    }
+
+    let mut name = String::new();
+    let mut values = Values::new();
+    for (column, text) in header.iter().zip(fields) {
+        if column == NAME_COLUMN {
+            name = text.trim().to_owned();
+            continue;
+        }
+        if text.trim().is_empty() {
+            continue;
+        }
+        let attribute = ci_type
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == *column)
+            .expect("header was not checked");
+        match attribute.kind.parse(text) {
+            Some(value) => {
+                let _ = values.insert(column.clone(), value);
+            }
+            None => return Err(format!("{}: invalid value {:?}", column, text)),
+        }
+    }
+    check_name(&name)?;
+
+    normalize_attributes(ci_type, &values).map(|values| (name, values))
+}
+
+/// Gets the response to an import failing on a line.
+fn failed_import(line: usize, message: String) -> (Status, Json<Option<ImportReportDTO>>) {
+    (
+        Status::BadRequest,
+        Json(Some(ImportReportDTO {
+            errors: vec![ImportErrorDTO { line, message }],
+            ..ImportReportDTO::default()
+        })),
+    )
+}
@@ -0,0 +1,162 @@
+//! Custom fields of tickets.
+
+use super::auth;
+use crate::{db, into_io_err};
+use common::custom_field::{CustomFieldDTO, CustomFieldFormDTO};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::{convert::TryFrom, io};
+use uuid::Uuid;
+
+/// Maximum length of the label of a field, in characters.
+const MAX_LABEL_LEN: usize = 100;
+
+/// Gets the definitions of the custom fields.
+pub(super) async fn definitions(conn: &db::Connection) -> io::Result<Vec<CustomFieldDTO>> {
+    conn.run(db::custom_field::get_all)
+        .await?
+        .into_iter()
+        .map(|field| CustomFieldDTO::try_from(field).map_err(into_io_err))
+        .collect()
+}
+
+/// List the custom fields of tickets
+#[get("/custom-fields")]
+pub async fn list(
+    _user: auth::User,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<CustomFieldDTO>>> {
+    definitions(&conn).await.map(Json)
+}
+
+/// Create a new custom field
+#[post("/custom-fields", format = "json", data = "<field>")]
+pub async fn create(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    field: Json<CustomFieldFormDTO>,
+) -> io::Result<(Status, Json<Result<CustomFieldDTO, String>>)> {
+    let field = match validate_field(field.into_inner()) {
+        Ok(field) => field,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+    let key = field.key.clone();
+    if conn
+        .run(move |c| db::custom_field::key_taken(c, &key))
+        .await?
+    {
+        return Ok((
+            Status::Conflict,
+            Json(Err("key belongs to another field".to_owned())),
+        ));
+    }
+
+    let kind = serde_json::to_value(&field.kind).map_err(into_io_err)?;
+    let created = conn
+        .run(move |c| {
+            db::custom_field::insert(
+                c,
+                &db::model::CustomFieldForm {
+                    key: &field.key,
+                    label: &field.label,
+                    kind,
+                    required: field.required,
+                    categories: &field.categories,
+                    position: field.position,
+                },
+            )
+        })
+        .await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(CustomFieldDTO::try_from(created).map_err(into_io_err)?)),
+    ))
+}
+
+/// Update a custom field, whose key can't change
+#[put("/custom-fields/<id>", format = "json", data = "<field>")]
+pub async fn update(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    field: Json<CustomFieldFormDTO>,
+) -> io::Result<(Status, Json<Result<CustomFieldDTO, String>>)> {
+    let field = match validate_field(field.into_inner()) {
+        Ok(field) => field,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+    match conn
+        .run(move |c| db::custom_field::get_with_id(c, id))
+        .await?
+    {
+        Some(existing) if existing.key != field.key => {
+            return Ok((
+                Status::BadRequest,
+                Json(Err("the key of a field can't change".to_owned())),
+            ))
+        }
+        Some(_) => {}
+        None => return Ok((Status::NotFound, Json(Err("field not found".to_owned())))),
+    }
+
+    let kind = serde_json::to_value(&field.kind).map_err(into_io_err)?;
+    let updated = conn
+        .run(move |c| {
+            db::custom_field::update(
+                c,
+                id,
+                &db::model::CustomFieldForm {
+                    key: &field.key,
+                    label: &field.label,
+                    kind,
+                    required: field.required,
+                    categories: &field.categories,
+                    position: field.position,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match updated {
+        Some(updated) => (
+            Status::Ok,
+            Json(Ok(CustomFieldDTO::try_from(updated).map_err(into_io_err)?)),
+        ),
+        None => (Status::NotFound, Json(Err("field not found".to_owned()))),
+    })
+}
+
+/// Delete a custom field, along with its values in tickets
+#[delete("/custom-fields/<id>")]
+pub async fn delete(_admin: auth::Admin, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    let deleted = conn.run(move |c| db::custom_field::delete(c, id)).await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// Validates a custom field, returning it with its label and categories trimmed.
+fn validate_field(mut field: CustomFieldFormDTO) -> Result<CustomFieldFormDTO, String> {
+    field.check()?;
+    field.label = field.label.trim().to_owned();
+    if field.label.chars().count() > MAX_LABEL_LEN {
+        return Err(format!(
+            "label can't be longer than {} characters",
+            MAX_LABEL_LEN
+        ));
+    }
+    field.categories = field
+        .categories
+        .iter()
+        .map(|category| category.trim())
+        .filter(|category| !category.is_empty())
+        .map(ToOwned::to_owned)
+        .collect();
+    field.categories.sort_unstable();
+    field.categories.dedup();
+
+    Ok(field)
+}
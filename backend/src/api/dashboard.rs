@@ -0,0 +1,119 @@
+//! Agent dashboards.
+//!
+//! Agents lay out the widgets of their dashboard, which is stored per agent. Widgets listing
+//! tickets use the ticket search, while the SLA at risk and recent activity widgets have their own
+//! routes. Ticket activity is streamed as it happens, so that dashboards refresh live.
+
+use super::auth;
+use crate::{
+    db::{self, model},
+    notification::activity,
+};
+use chrono::Utc;
+use common::dashboard::{
+    ActivityDTO, DashboardDTO, SlaRiskDTO, DEFAULT_WIDGET_LIMIT, MAX_WIDGET_LIMIT,
+};
+use rocket::{
+    get,
+    http::Status,
+    put,
+    response::stream::{Event, EventStream},
+    serde::json::Json,
+    Shutdown,
+};
+use std::{convert::TryFrom, io};
+
+/// Share of the time of an SLA target left under which tickets are at risk of missing it.
+const AT_RISK_SHARE: f64 = 0.25;
+
+/// Get the dashboard layout of the agent
+#[get("/dashboard")]
+pub async fn dashboard(agent: auth::Agent, conn: db::Connection) -> io::Result<Json<DashboardDTO>> {
+    let user_id = agent.id;
+    let dashboard = conn.run(move |c| db::dashboard::get(c, user_id)).await?;
+
+    Ok(Json(match dashboard {
+        Some(dashboard) => DashboardDTO::try_from(dashboard)?,
+        None => DashboardDTO::default(),
+    }))
+}
+
+/// Replace the dashboard layout of the agent
+#[put("/dashboard", format = "json", data = "<dashboard>")]
+pub async fn update_dashboard(
+    agent: auth::Agent,
+    conn: db::Connection,
+    dashboard: Json<DashboardDTO>,
+) -> io::Result<(Status, Json<Result<DashboardDTO, &'static str>>)> {
+    let dashboard = dashboard.into_inner();
+    if let Err(e) = dashboard.validate() {
+        return Ok((Status::BadRequest, Json(Err(e))));
+    }
+
+    let new = model::NewDashboard {
+        user_id: agent.id,
+        widgets: serde_json::to_value(&dashboard.widgets)?,
+    };
+    let stored = conn.run(move |c| db::dashboard::upsert(c, &new)).await?;
+
+    Ok((Status::Ok, Json(Ok(DashboardDTO::try_from(stored)?))))
+}
+
+/// List the unresolved tickets at risk of missing their next SLA target, the most urgent first
+///
+/// Tickets are at risk when less than a quarter of the time of the target is left, or when they
+/// already missed it.
+#[get("/dashboard/sla-at-risk?<limit>")]
+pub async fn sla_at_risk(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    limit: Option<i64>,
+) -> io::Result<Json<Vec<SlaRiskDTO>>> {
+    let limit = limit
+        .unwrap_or(DEFAULT_WIDGET_LIMIT)
+        .clamp(1, MAX_WIDGET_LIMIT);
+    let risks = conn
+        .run(move |c| db::dashboard::get_sla_at_risk(c, AT_RISK_SHARE, limit))
+        .await?;
+    let now = Utc::now();
+
+    Ok(Json(
+        risks
+            .into_iter()
+            .map(|(ticket, risk)| SlaRiskDTO {
+                target: risk.target(),
+                due_on: risk.due_on,
+                breached: risk.due_on <= now,
+                ticket: ticket.into(),
+            })
+            .collect(),
+    ))
+}
+
+/// List the latest tickets opened, commented on or changed, newest first
+#[get("/dashboard/activity?<limit>")]
+pub async fn recent_activity(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    limit: Option<i64>,
+) -> io::Result<Json<Vec<ActivityDTO>>> {
+    let limit = limit
+        .unwrap_or(DEFAULT_WIDGET_LIMIT)
+        .clamp(1, MAX_WIDGET_LIMIT);
+    let activity = conn
+        .run(move |c| db::dashboard::get_activity(c, limit))
+        .await?;
+
+    Ok(Json(activity.into_iter().map(Into::into).collect()))
+}
+
+/// Stream the ticket activity, as server-sent events
+///
+/// Each ticket created, changed or commented on is sent as an `activity` event. If the stream
+/// falls behind, a `lagged` event is sent instead, and clients should reload the tickets they show.
+#[get("/tickets/activity")]
+pub async fn activity_stream(_agent: auth::Agent, shutdown: Shutdown) -> EventStream![] {
+    super::event_stream(activity::subscribe(), shutdown, |activity| {
+        Some(Event::json(&activity).event("activity"))
+    })
+}
@@ -0,0 +1,95 @@
+//! Inbound email-to-ticket ingestion.
+
+use super::auth;
+use crate::{automation, db, inbound};
+use common::inbound::InboundEmailDTO;
+use rocket::{
+    data::{Data, ToByteUnit},
+    get,
+    http::Status,
+    outcome::Outcome,
+    post,
+    request::{self, FromRequest, Request},
+    serde::json::Json,
+};
+use std::{convert::Infallible, io};
+use subtle::ConstantTimeEq;
+
+/// Header with the token of the mail server.
+const TOKEN_HEADER: &str = "X-Inbound-Token";
+
+/// Maximum size of a raw email, in mebibytes.
+const MAX_EMAIL_MIB: u64 = 25;
+
+/// Maximum number of log entries returned in a single query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of log entries returned in a single query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// Mail server posting emails with the configured token.
+///
+/// This guard never fails: it holds whether the token is valid.
+#[derive(Debug, Clone, Copy)]
+pub struct MailServer(bool);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for MailServer {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let token = req.headers().get_one(TOKEN_HEADER);
+
+        // Compared in constant time, like the password hashes
+        let valid = match (token, &*inbound::INBOUND_EMAIL_TOKEN) {
+            (Some(token), Some(expected)) => token.as_bytes().ct_eq(expected.as_bytes()).into(),
+            _ => false,
+        };
+
+        Outcome::Success(Self(valid))
+    }
+}
+
+/// Ingest a raw RFC 822 email, as piped by the mail server
+///
+/// Only the mail server, with the configured token, and administrators can post emails.
+#[post("/inbound/email", data = "<raw>")]
+pub async fn email(
+    admin: Option<auth::Admin>,
+    mail_server: MailServer,
+    conn: db::Connection,
+    raw: Data<'_>,
+) -> io::Result<(Status, Json<Option<InboundEmailDTO>>)> {
+    if admin.is_none() && !mail_server.0 {
+        return Ok((Status::Unauthorized, Json(None)));
+    }
+
+    let raw = raw.open(MAX_EMAIL_MIB.mebibytes()).into_bytes().await?;
+    if !raw.is_complete() {
+        return Ok((Status::PayloadTooLarge, Json(None)));
+    }
+
+    let raw = raw.into_inner();
+    let ingested = conn.run(move |c| inbound::ingest(c, &raw)).await?;
+    automation::dispatch(&conn, ingested.effects).await?;
+
+    // Duplicates are accepted, so that the mail server doesn't retry them
+    Ok((Status::Ok, Json(ingested.entry.map(Into::into))))
+}
+
+/// Get the inbound email log, newest first
+#[get("/inbound/log?<limit>&<offset>")]
+pub async fn log(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<Json<Vec<InboundEmailDTO>>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+    let entries = conn
+        .run(move |c| db::inbound::get_log(c, limit, offset))
+        .await?;
+
+    Ok(Json(entries.into_iter().map(Into::into).collect()))
+}
@@ -0,0 +1,160 @@
+//! Invitations, to onboard users when open registration is not desired.
+
+use super::{auth, register};
+use crate::{audit, db, notification, rand_code, BASE_URL};
+use common::{
+    audit::AuditEvent,
+    registration::{InvitationDTO, PendingInvitationDTO},
+};
+use rocket::{delete, get, http::Status, post, serde::json::Json, tokio::task::spawn_blocking};
+use std::{io, sync::Arc};
+
+/// Invite a user with a given email and role
+#[post("/invitations", format = "json", data = "<invitation>")]
+pub async fn create(
+    agent: auth::Agent,
+    conn: db::Connection,
+    ctx: audit::Context,
+    invitation: Json<InvitationDTO<'_>>,
+) -> io::Result<(Status, Json<&'static str>)> {
+    let InvitationDTO {
+        email,
+        role,
+        organisation_id,
+    } = invitation.into_inner();
+    let email = Arc::new(email.trim().to_owned());
+
+    if let Err(e) = register::validate_email(&email) {
+        return Ok((Status::BadRequest, Json(e)));
+    }
+
+    // Users can't give more privileges than they have
+    if role > agent.role() {
+        return Ok((Status::Forbidden, Json("role not allowed")));
+    }
+
+    if let Some(organisation_id) = organisation_id {
+        if conn
+            .run(move |c| db::organisation::get_with_id(c, organisation_id))
+            .await?
+            .is_none()
+        {
+            return Ok((Status::BadRequest, Json("organisation not found")));
+        }
+    }
+
+    let email_clone = email.clone();
+    if conn
+        .run(move |c| db::user::get_with_email(c, &email_clone))
+        .await?
+        .is_some()
+    {
+        return Ok((Status::Conflict, Json("user already exists")));
+    }
+
+    // Remove any existing invitations for that email
+    let email_clone = email.clone();
+    conn.run(move |c| db::user::delete_invitations_for_email(c, &email_clone))
+        .await?;
+
+    // Generate the random code
+    let code = loop {
+        let code = Arc::new(rand_code(10));
+        let code_clone = code.clone();
+        if conn
+            .run(move |c| db::user::get_invitation_with_code(c, &code_clone))
+            .await?
+            .is_none()
+        {
+            break code;
+        }
+    };
+
+    let email_clone = email.clone();
+    let code_clone = code.clone();
+    let invited_by = agent.id;
+    conn.run(move |c| {
+        db::user::insert_invitation(
+            c,
+            &email_clone,
+            &code_clone,
+            role,
+            invited_by,
+            organisation_id,
+        )
+    })
+    .await?;
+
+    let event = AuditEvent::InvitationCreated {
+        email: (*email).clone(),
+        role,
+    };
+    conn.run(move |c| audit::record(c, &ctx, Some(invited_by), &event))
+        .await?;
+
+    #[cfg(debug_assertions)]
+    println!("Invitation code: {}", code);
+
+    let body = format!(
+        "Welcome to MySupport!
+
+        {} {} has invited you to join MySupport. In order to create your account, please follow
+        {}/register/{}
+
+        Best regards,
+        The MySupport team",
+        agent.first_name, agent.last_name, *BASE_URL, code
+    );
+
+    // TODO: use queues
+    spawn_blocking(move || notification::email::send(&email, "Invitation to MySupport", body))
+        .await??;
+
+    Ok((Status::Ok, Json("")))
+}
+
+/// List the pending invitations
+#[get("/invitations")]
+pub async fn list(
+    _agent: auth::Agent,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<PendingInvitationDTO>>> {
+    let invitations = conn.run(db::user::get_pending_invitations).await?;
+
+    Ok(Json(
+        invitations
+            .into_iter()
+            .map(|(invitation, invited_by)| PendingInvitationDTO {
+                role: invitation.role(),
+                code: invitation.code,
+                email: invitation.email,
+                organisation_id: invitation.organisation_id,
+                invited_by,
+            })
+            .collect(),
+    ))
+}
+
+/// Revoke a pending invitation
+#[delete("/invitations/<code>")]
+pub async fn revoke(
+    agent: auth::Agent,
+    conn: db::Connection,
+    ctx: audit::Context,
+    code: String,
+) -> io::Result<Status> {
+    let code_clone = code.clone();
+    let deleted = conn
+        .run(move |c| db::user::delete_invitation(c, &code_clone))
+        .await?;
+
+    if deleted {
+        let (actor_id, event) = (agent.id, AuditEvent::InvitationRevoked { code });
+        conn.run(move |c| audit::record(c, &ctx, Some(actor_id), &event))
+            .await?;
+
+        Ok(Status::NoContent)
+    } else {
+        Ok(Status::NotFound)
+    }
+}
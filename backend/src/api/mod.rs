@@ -1,12 +1,234 @@
 use crate::db;
-use rocket::{get, routes, Route};
+use chrono::{DateTime, Utc};
+use rocket::{
+    fairing::{AdHoc, Fairing},
+    futures::future::{self, Either},
+    get,
+    response::stream::{Event, EventStream},
+    routes,
+    tokio::sync::broadcast::{self, error::RecvError},
+    Route, Shutdown,
+};
 use std::io;
 
+mod approval;
+mod article;
+mod audit;
+mod auth;
+mod automation;
+mod board;
+mod catalog;
+mod change;
+mod cmdb;
+mod custom_field;
+mod dashboard;
+mod inbound;
+mod invitation;
+mod notification;
+mod organisation;
+mod problem;
+mod queue;
 mod register;
+mod relation;
+mod report;
+mod search;
+mod survey;
+mod team;
+mod ticket;
+mod user;
+mod view;
+mod webhook;
+mod work_log;
+
+/// Gets the fairing loading the configuration of the API.
+pub fn config() -> impl Fairing {
+    AdHoc::config::<register::Config>()
+}
 
 /// Gets the routes for the backend API.
 pub fn routes() -> Vec<Route> {
-    routes![hello, register::email, register::register]
+    routes![
+        hello,
+        approval::chains,
+        approval::create_chain,
+        approval::update_chain,
+        approval::delete_chain,
+        approval::pending,
+        approval::with_token,
+        approval::decide_with_token,
+        approval::get,
+        approval::decide,
+        article::categories,
+        article::create_category,
+        article::update_category,
+        article::delete_category,
+        article::list,
+        article::get,
+        article::create,
+        article::update,
+        article::delete,
+        article::revisions,
+        article::revision,
+        article::diff,
+        article::linked,
+        article::link,
+        article::unlink,
+        audit::query,
+        auth::login,
+        auth::logout,
+        auth::current_user,
+        automation::list,
+        automation::get,
+        automation::create,
+        automation::update,
+        automation::delete,
+        automation::dry_run,
+        automation::log,
+        catalog::list,
+        catalog::users,
+        catalog::get,
+        catalog::create,
+        catalog::update,
+        catalog::delete,
+        catalog::request,
+        catalog::ticket_request,
+        change::list,
+        change::calendar,
+        change::get,
+        change::create,
+        change::update,
+        change::collisions,
+        change::windows,
+        change::create_window,
+        change::update_window,
+        change::delete_window,
+        change::cab_chains,
+        change::set_cab_chain,
+        cmdb::types,
+        cmdb::create_type,
+        cmdb::update_type,
+        cmdb::delete_type,
+        cmdb::import,
+        cmdb::items,
+        cmdb::item,
+        cmdb::create_item,
+        cmdb::update_item,
+        cmdb::delete_item,
+        cmdb::relationships,
+        cmdb::relate,
+        cmdb::unrelate,
+        cmdb::impact,
+        cmdb::tickets,
+        cmdb::linked,
+        cmdb::link,
+        cmdb::unlink,
+        custom_field::list,
+        custom_field::create,
+        custom_field::update,
+        custom_field::delete,
+        inbound::email,
+        inbound::log,
+        invitation::create,
+        invitation::list,
+        invitation::revoke,
+        notification::list,
+        notification::unread,
+        notification::read,
+        notification::read_all,
+        notification::preferences,
+        notification::update_preferences,
+        notification::stream,
+        organisation::list,
+        organisation::create,
+        organisation::update,
+        organisation::members,
+        organisation::add_member,
+        organisation::remove_member,
+        problem::list,
+        problem::get,
+        problem::create,
+        problem::update,
+        problem::resolve,
+        problem::publish,
+        problem::tickets,
+        problem::link,
+        problem::unlink,
+        problem::linked,
+        queue::list,
+        queue::create,
+        queue::update,
+        register::email,
+        register::code_info,
+        register::register,
+        relation::by_number,
+        relation::merge,
+        relation::split,
+        relation::children,
+        relation::set_parent,
+        relation::links,
+        relation::link,
+        relation::unlink,
+        relation::watchers,
+        relation::watch,
+        relation::unwatch,
+        report::report,
+        report::export_csv,
+        report::export_xlsx,
+        report::sla_policies,
+        report::update_sla_policy,
+        dashboard::dashboard,
+        dashboard::update_dashboard,
+        dashboard::sla_at_risk,
+        dashboard::recent_activity,
+        dashboard::activity_stream,
+        organisation::branding,
+        organisation::update_branding,
+        organisation::portal_branding,
+        board::board,
+        board::update_column,
+        search::search,
+        survey::with_token,
+        survey::answer_with_token,
+        survey::surveys,
+        survey::suppressions,
+        survey::suppress,
+        survey::unsuppress,
+        team::list,
+        team::create,
+        team::rename,
+        team::set_member,
+        team::remove_member,
+        ticket::create,
+        ticket::list,
+        ticket::get,
+        ticket::update,
+        ticket::assignments,
+        ticket::comments,
+        ticket::comment,
+        ticket::attachment,
+        user::role,
+        view::list,
+        view::create,
+        view::update,
+        view::delete,
+        view::tickets,
+        webhook::list,
+        webhook::get,
+        webhook::create,
+        webhook::update,
+        webhook::delete,
+        webhook::deliveries,
+        work_log::work_logs,
+        work_log::log_work,
+        work_log::update_work_log,
+        work_log::delete_work_log,
+        work_log::totals,
+        work_log::export,
+        work_log::contracts,
+        work_log::create_contract,
+        work_log::update_contract,
+        work_log::delete_contract
+    ]
 }
 
 /// Hello world
@@ -22,3 +244,42 @@ pub async fn hello(conn: db::Connection, email: String) -> io::Result<String> {
         Ok("There is no user with this email :(".to_owned())
     }
 }
+
+/// Parses an optional RFC 3339 date.
+fn parse_date(date: Option<&str>) -> Result<Option<DateTime<Utc>>, chrono::ParseError> {
+    date.map(|date| DateTime::parse_from_rfc3339(date).map(|date| date.with_timezone(&Utc)))
+        .transpose()
+}
+
+/// Streams the values broadcast to the receiver as server-sent events, until the application shuts
+/// down.
+///
+/// Values are mapped to events, skipping the ones mapped to `None`. If the stream falls behind, a
+/// `lagged` event is sent instead.
+fn event_stream<T, F>(
+    mut receiver: broadcast::Receiver<T>,
+    mut shutdown: Shutdown,
+    mut to_event: F,
+) -> EventStream![]
+where
+    T: Clone + Send + 'static,
+    F: FnMut(T) -> Option<Event> + Send + 'static,
+{
+    EventStream! {
+        loop {
+            let received = match future::select(Box::pin(receiver.recv()), &mut shutdown).await {
+                Either::Left((received, _)) => received,
+                Either::Right(_) => break,
+            };
+            match received {
+                Ok(value) => {
+                    if let Some(event) = to_event(value) {
+                        yield event;
+                    }
+                }
+                Err(RecvError::Closed) => break,
+                Err(RecvError::Lagged(_)) => yield Event::data("").event("lagged"),
+            }
+        }
+    }
+}
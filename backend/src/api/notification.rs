@@ -0,0 +1,156 @@
+//! In-app notifications.
+
+use super::auth;
+use crate::{db, notification::centre};
+use common::notification::{Kind, NotificationDTO, PreferenceDTO, UnreadCountDTO};
+use rocket::{
+    get,
+    http::Status,
+    post, put,
+    response::stream::{Event, EventStream},
+    serde::json::Json,
+    Shutdown,
+};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum number of notifications returned in a single query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of notifications returned in a single query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// List the notifications of the user, newest first
+#[get("/notifications?<unread>&<limit>&<offset>")]
+pub async fn list(
+    user: auth::User,
+    conn: db::Connection,
+    unread: Option<bool>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<Json<Vec<NotificationDTO>>> {
+    let user_id = user.id;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+    let notifications = conn
+        .run(move |c| {
+            db::notification::get_for_user(c, user_id, unread.unwrap_or(false), limit, offset)
+        })
+        .await?;
+
+    Ok(Json(notifications.into_iter().map(Into::into).collect()))
+}
+
+/// Get the number of unread notifications of the user
+#[get("/notifications/unread")]
+pub async fn unread(user: auth::User, conn: db::Connection) -> io::Result<Json<UnreadCountDTO>> {
+    let user_id = user.id;
+    let count = conn
+        .run(move |c| db::notification::count_unread(c, user_id))
+        .await?;
+
+    Ok(Json(UnreadCountDTO { count }))
+}
+
+/// Get the notification preferences of the user, for every kind of notification
+#[get("/notifications/preferences")]
+pub async fn preferences(
+    user: auth::User,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<PreferenceDTO>>> {
+    let user_id = user.id;
+    let stored = conn
+        .run(move |c| db::notification::get_preferences(c, user_id))
+        .await?;
+
+    Ok(Json(with_defaults(stored.into_iter().map(Into::into))))
+}
+
+/// Update the notification preferences of the user
+///
+/// Kinds of notifications not included keep their current preference.
+#[put("/notifications/preferences", format = "json", data = "<preferences>")]
+pub async fn update_preferences(
+    user: auth::User,
+    conn: db::Connection,
+    preferences: Json<Vec<PreferenceDTO>>,
+) -> io::Result<Json<Vec<PreferenceDTO>>> {
+    let user_id = user.id;
+    let stored = conn
+        .run(move |c| {
+            let new = preferences
+                .iter()
+                .map(|preference| db::model::NewNotificationPreference {
+                    user_id,
+                    kind: preference.kind.as_str(),
+                    delivery: preference.delivery.as_str(),
+                })
+                .collect::<Vec<_>>();
+            if !new.is_empty() {
+                db::notification::upsert_preferences(c, &new)?;
+            }
+
+            db::notification::get_preferences(c, user_id)
+        })
+        .await?;
+
+    Ok(Json(with_defaults(stored.into_iter().map(Into::into))))
+}
+
+/// Completes the stored preferences with the default one for the missing kinds of notifications.
+fn with_defaults(stored: impl Iterator<Item = PreferenceDTO>) -> Vec<PreferenceDTO> {
+    let mut preferences = Kind::ALL
+        .iter()
+        .map(|&kind| PreferenceDTO {
+            kind,
+            delivery: kind.default_delivery(),
+        })
+        .collect::<Vec<_>>();
+    for stored in stored {
+        if let Some(preference) = preferences.iter_mut().find(|p| p.kind == stored.kind) {
+            *preference = stored;
+        }
+    }
+
+    preferences
+}
+
+/// Mark a notification of the user as read
+#[post("/notifications/<id>/read")]
+pub async fn read(user: auth::User, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    let user_id = user.id;
+    let found = conn
+        .run(move |c| db::notification::mark_read(c, user_id, id))
+        .await?;
+
+    Ok(if found {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// Mark all the notifications of the user as read
+#[post("/notifications/read")]
+pub async fn read_all(user: auth::User, conn: db::Connection) -> io::Result<Status> {
+    let user_id = user.id;
+    let _ = conn
+        .run(move |c| db::notification::mark_all_read(c, user_id))
+        .await?;
+
+    Ok(Status::NoContent)
+}
+
+/// Stream the new notifications of the user, as server-sent events
+///
+/// Each notification is sent as a `notification` event. If the stream falls behind, a `lagged`
+/// event is sent instead, and clients should reload the notifications.
+#[get("/notifications/stream")]
+pub async fn stream(user: auth::User, shutdown: Shutdown) -> EventStream![] {
+    let user_id = user.id;
+
+    super::event_stream(centre::subscribe(), shutdown, move |notification| {
+        (notification.user_id == user_id)
+            .then(|| Event::json(&NotificationDTO::from(notification)).event("notification"))
+    })
+}
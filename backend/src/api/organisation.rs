@@ -0,0 +1,368 @@
+//! Customer organisations.
+//!
+//! Each organisation can brand the customer portal of its members with a logo, colours and a
+//! welcome text.
+
+use super::{auth, register};
+use crate::db::{self, model};
+use common::organisation::{
+    BrandingDTO, MemberDTO, MembershipDTO, OrganisationDTO, OrganisationFormDTO,
+};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum length of the URL of the logo of a portal, in bytes.
+const MAX_LOGO_URL_LENGTH: usize = 500;
+
+/// Maximum length of the welcome text of a portal, in characters.
+const MAX_WELCOME_TEXT_LENGTH: usize = 2000;
+
+/// List all the organisations
+#[get("/organisations")]
+pub async fn list(
+    _agent: auth::Agent,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<OrganisationDTO>>> {
+    let organisations = conn.run(db::organisation::get_all).await?;
+
+    Ok(Json(
+        organisations
+            .into_iter()
+            .map(|(org, domains)| OrganisationDTO {
+                id: org.id,
+                name: org.name,
+                domains,
+            })
+            .collect(),
+    ))
+}
+
+/// Create a new organisation
+#[post("/organisations", format = "json", data = "<organisation>")]
+pub async fn create(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    organisation: Json<OrganisationFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<OrganisationDTO, &'static str>>)> {
+    let (name, domains) = match validate_form(organisation.into_inner()) {
+        Ok(form) => form,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    if let Some(conflict) = find_conflict(&conn, None, name.clone(), domains.clone()).await? {
+        return Ok((Status::Conflict, Json(Err(conflict))));
+    }
+
+    let domains_clone = domains.clone();
+    let org = conn
+        .run(move |c| {
+            let domains = domains_clone.iter().map(String::as_str).collect::<Vec<_>>();
+            db::organisation::insert(c, &name, &domains)
+        })
+        .await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(OrganisationDTO {
+            id: org.id,
+            name: org.name,
+            domains,
+        })),
+    ))
+}
+
+/// Update the name and email domains of an organisation
+#[put("/organisations/<id>", format = "json", data = "<organisation>")]
+pub async fn update(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    organisation: Json<OrganisationFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<OrganisationDTO, &'static str>>)> {
+    let (name, domains) = match validate_form(organisation.into_inner()) {
+        Ok(form) => form,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    if let Some(conflict) = find_conflict(&conn, Some(id), name.clone(), domains.clone()).await? {
+        return Ok((Status::Conflict, Json(Err(conflict))));
+    }
+
+    let domains_clone = domains.clone();
+    let org = conn
+        .run(move |c| {
+            let domains = domains_clone.iter().map(String::as_str).collect::<Vec<_>>();
+            db::organisation::update(c, id, &name, &domains)
+        })
+        .await?;
+
+    Ok(match org {
+        Some(org) => (
+            Status::Ok,
+            Json(Ok(OrganisationDTO {
+                id: org.id,
+                name: org.name,
+                domains,
+            })),
+        ),
+        None => (Status::NotFound, Json(Err("organisation not found"))),
+    })
+}
+
+/// Validates the organisation form, returning the trimmed name and the lowercase domains.
+fn validate_form(form: OrganisationFormDTO<'_>) -> Result<(String, Vec<String>), &'static str> {
+    let name = form.name.trim();
+    if name.is_empty() {
+        return Err("name can't be empty");
+    }
+
+    let mut domains = Vec::with_capacity(form.domains.len());
+    for domain in form.domains {
+        // Domains are validated as if they were part of an email address
+        let domain = register::validate_email(&format!("user@{}", domain.trim()))
+            .map_err(|_| "invalid domain")?;
+        if !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+
+    Ok((name.to_owned(), domains))
+}
+
+/// Checks if the name or any of the domains already belongs to a different organisation.
+async fn find_conflict(
+    conn: &db::Connection,
+    id: Option<Uuid>,
+    name: String,
+    domains: Vec<String>,
+) -> io::Result<Option<&'static str>> {
+    conn.run(move |c| {
+        if db::organisation::get_all(c)?
+            .iter()
+            .any(|(org, _)| org.name == name && Some(org.id) != id)
+        {
+            return Ok(Some("name belongs to another organisation"));
+        }
+
+        for domain in domains {
+            match db::organisation::get_id_with_domain(c, &domain)? {
+                Some(org_id) if Some(org_id) != id => {
+                    return Ok(Some("domain belongs to another organisation"))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    })
+    .await
+}
+
+/// List the members of an organisation
+#[get("/organisations/<id>/members")]
+pub async fn members(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<MemberDTO>>)> {
+    if conn
+        .run(move |c| db::organisation::get_with_id(c, id))
+        .await?
+        .is_none()
+    {
+        return Ok((Status::NotFound, Json(Vec::new())));
+    }
+
+    let members = conn
+        .run(move |c| db::organisation::get_members(c, id))
+        .await?;
+
+    Ok((
+        Status::Ok,
+        Json(
+            members
+                .into_iter()
+                .map(|user| MemberDTO {
+                    user_id: user.id,
+                    username: user.username,
+                    email: user.email,
+                })
+                .collect(),
+        ),
+    ))
+}
+
+/// Add a user to an organisation
+#[post("/organisations/<id>/members", format = "json", data = "<membership>")]
+pub async fn add_member(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    membership: Json<MembershipDTO>,
+) -> io::Result<(Status, Json<&'static str>)> {
+    let MembershipDTO { user_id } = membership.into_inner();
+
+    if conn
+        .run(move |c| db::organisation::get_with_id(c, id))
+        .await?
+        .is_none()
+    {
+        return Ok((Status::NotFound, Json("organisation not found")));
+    }
+
+    if conn
+        .run(move |c| db::user::get_with_id(c, user_id))
+        .await?
+        .is_none()
+    {
+        return Ok((Status::BadRequest, Json("user not found")));
+    }
+
+    conn.run(move |c| db::organisation::insert_member(c, id, user_id))
+        .await?;
+
+    Ok((Status::Ok, Json("")))
+}
+
+/// Remove a user from an organisation
+#[delete("/organisations/<id>/members/<user_id>")]
+pub async fn remove_member(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    user_id: Uuid,
+) -> io::Result<Status> {
+    let deleted = conn
+        .run(move |c| db::organisation::delete_member(c, id, user_id))
+        .await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// Get the branding of the customer portal for the members of an organisation
+///
+/// Organisations that never changed it get the default branding.
+#[get("/organisations/<id>/branding")]
+pub async fn branding(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Result<BrandingDTO, &'static str>>)> {
+    let found = conn
+        .run(move |c| {
+            Ok::<_, io::Error>(match db::organisation::get_with_id(c, id)? {
+                Some(_) => Some(db::organisation::get_branding(c, id)?),
+                None => None,
+            })
+        })
+        .await?;
+
+    Ok(match found {
+        Some(branding) => (
+            Status::Ok,
+            Json(Ok(branding.map(Into::into).unwrap_or_default())),
+        ),
+        None => (Status::NotFound, Json(Err("organisation not found"))),
+    })
+}
+
+/// Replace the branding of the customer portal for the members of an organisation
+#[put("/organisations/<id>/branding", format = "json", data = "<branding>")]
+pub async fn update_branding(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    branding: Json<BrandingDTO>,
+) -> io::Result<(Status, Json<Result<BrandingDTO, &'static str>>)> {
+    let branding = match validate_branding(branding.into_inner()) {
+        Ok(branding) => branding,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    let stored = conn
+        .run(move |c| {
+            if db::organisation::get_with_id(c, id)?.is_none() {
+                return Ok(None);
+            }
+
+            let new = model::NewOrganisationBranding {
+                organisation_id: id,
+                logo_url: branding.logo_url.as_deref(),
+                primary_colour: &branding.primary_colour,
+                accent_colour: &branding.accent_colour,
+                welcome_text: &branding.welcome_text,
+            };
+            db::organisation::upsert_branding(c, &new).map(Some)
+        })
+        .await?;
+
+    Ok(match stored {
+        Some(stored) => (Status::Ok, Json(Ok(stored.into()))),
+        None => (Status::NotFound, Json(Err("organisation not found"))),
+    })
+}
+
+/// Get the branding of the customer portal for the user
+///
+/// Users outside of any organisation get the default branding.
+#[get("/portal/branding")]
+pub async fn portal_branding(
+    user: auth::User,
+    conn: db::Connection,
+) -> io::Result<Json<BrandingDTO>> {
+    let user_id = user.id;
+    let branding = conn
+        .run(move |c| db::organisation::get_branding_for_user(c, user_id))
+        .await?;
+
+    Ok(Json(branding.map(Into::into).unwrap_or_default()))
+}
+
+/// Validates the branding, returning it with trimmed texts and lowercase colours.
+fn validate_branding(branding: BrandingDTO) -> Result<BrandingDTO, &'static str> {
+    let logo_url = branding
+        .logo_url
+        .map(|url| url.trim().to_owned())
+        .filter(|url| !url.is_empty());
+    if let Some(url) = &logo_url {
+        // Logos are either served over HTTPS or by the application itself
+        let relative = url.starts_with('/') && !url.starts_with("//");
+        if !(url.starts_with("https://") || relative)
+            || url.len() > MAX_LOGO_URL_LENGTH
+            || url
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, '"' | '<' | '>'))
+        {
+            return Err("invalid logo URL");
+        }
+    }
+
+    let primary_colour = validate_colour(&branding.primary_colour)?;
+    let accent_colour = validate_colour(&branding.accent_colour)?;
+
+    let welcome_text = branding.welcome_text.trim();
+    if welcome_text.chars().count() > MAX_WELCOME_TEXT_LENGTH {
+        return Err("welcome text is too long");
+    }
+
+    Ok(BrandingDTO {
+        logo_url,
+        primary_colour,
+        accent_colour,
+        welcome_text: welcome_text.to_owned(),
+    })
+}
+
+/// Validates a colour, returning it as lowercase `#rrggbb`.
+fn validate_colour(colour: &str) -> Result<String, &'static str> {
+    let colour = colour.trim().to_ascii_lowercase();
+    match colour.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) => Ok(colour),
+        _ => Err("invalid colour"),
+    }
+}
@@ -0,0 +1,403 @@
+//! Problem management.
+//!
+//! Only agents can see and edit problems. Incident tickets linked to a problem are resolved along
+//! with it, and known errors can be published as knowledge base articles that are kept up to date
+//! each time they're published again.
+
+use super::{auth, ticket::viewer};
+use crate::{audit, automation, db, problem};
+use common::{
+    article::{ArticleDTO, Status as ArticleStatus},
+    problem::{
+        IncidentLinkDTO, ProblemDTO, ProblemFormDTO, PublicationFormDTO, ResolutionDTO,
+        ResolutionFormDTO, Status as ProblemStatus,
+    },
+    ticket::TicketDTO,
+};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum number of problems returned in a single query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of problems returned in a single query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// Maximum length of problem titles, in characters.
+const MAX_TITLE_LEN: usize = 200;
+
+/// Maximum length of resolution comments, in characters.
+const MAX_COMMENT_LEN: usize = 10_000;
+
+/// List the problems, optionally with a status, newest first
+#[get("/problems?<status>&<limit>&<offset>")]
+pub async fn list(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    status: Option<&str>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<(Status, Json<Vec<ProblemDTO>>)> {
+    let status = match status.map(str::parse::<ProblemStatus>).transpose() {
+        Ok(status) => status,
+        Err(_) => return Ok((Status::BadRequest, Json(Vec::new()))),
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+
+    let problems = conn
+        .run(move |c| db::problem::get_all(c, status, limit, offset))
+        .await?;
+
+    Ok((
+        Status::Ok,
+        Json(problems.into_iter().map(Into::into).collect()),
+    ))
+}
+
+/// Get a problem
+#[get("/problems/<id>")]
+pub async fn get(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<ProblemDTO>>)> {
+    Ok(
+        match conn.run(move |c| db::problem::get_with_id(c, id)).await? {
+            Some(problem) => (Status::Ok, Json(Some(problem.into()))),
+            None => (Status::NotFound, Json(None)),
+        },
+    )
+}
+
+/// Create a new problem
+#[post("/problems", format = "json", data = "<problem>")]
+pub async fn create(
+    agent: auth::Agent,
+    conn: db::Connection,
+    problem: Json<ProblemFormDTO>,
+) -> io::Result<(Status, Json<Result<ProblemDTO, &'static str>>)> {
+    let problem = match validate_problem(&conn, problem.into_inner()).await? {
+        Ok(problem) => problem,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+    if problem.status == ProblemStatus::Resolved {
+        return Ok((
+            Status::BadRequest,
+            Json(Err("problems are resolved with the resolve action")),
+        ));
+    }
+
+    let created_by = agent.id;
+    let created = conn
+        .run(move |c| {
+            db::problem::insert(
+                c,
+                &db::model::ProblemForm {
+                    title: &problem.title,
+                    description: &problem.description,
+                    status: problem.status.as_str(),
+                    root_cause: &problem.root_cause,
+                    workaround: &problem.workaround,
+                    owner_id: problem.owner_id,
+                    resolved_on: None,
+                },
+                created_by,
+            )
+        })
+        .await?;
+
+    Ok((Status::Created, Json(Ok(created.into()))))
+}
+
+/// Update a problem
+///
+/// Resolved problems are reopened by changing their status.
+#[put("/problems/<id>", format = "json", data = "<problem>")]
+pub async fn update(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    problem: Json<ProblemFormDTO>,
+) -> io::Result<(Status, Json<Result<ProblemDTO, &'static str>>)> {
+    let problem = match validate_problem(&conn, problem.into_inner()).await? {
+        Ok(problem) => problem,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    conn.run(move |c| {
+        let current = match db::problem::get_with_id(c, id)? {
+            Some(current) => current,
+            None => return Ok((Status::NotFound, Json(Err("problem not found")))),
+        };
+        let resolved_on = match (current.status(), problem.status) {
+            (ProblemStatus::Resolved, ProblemStatus::Resolved) => current.resolved_on,
+            (_, ProblemStatus::Resolved) => {
+                return Ok((
+                    Status::BadRequest,
+                    Json(Err("problems are resolved with the resolve action")),
+                ))
+            }
+            _ => None,
+        };
+
+        let updated = db::problem::update(
+            c,
+            id,
+            &db::model::ProblemForm {
+                title: &problem.title,
+                description: &problem.description,
+                status: problem.status.as_str(),
+                root_cause: &problem.root_cause,
+                workaround: &problem.workaround,
+                owner_id: problem.owner_id,
+                resolved_on,
+            },
+        )?;
+
+        Ok(match updated {
+            Some(updated) => (Status::Ok, Json(Ok(updated.into()))),
+            None => (Status::NotFound, Json(Err("problem not found"))),
+        })
+    })
+    .await
+}
+
+/// Resolve a problem, along with its incidents that still require work
+#[post("/problems/<id>/resolve", format = "json", data = "<resolution>")]
+pub async fn resolve(
+    agent: auth::Agent,
+    conn: db::Connection,
+    ctx: audit::Context,
+    id: Uuid,
+    resolution: Json<ResolutionFormDTO>,
+) -> io::Result<(Status, Json<Result<ResolutionDTO, &'static str>>)> {
+    let ResolutionFormDTO { comment } = resolution.into_inner();
+    if comment.chars().count() > MAX_COMMENT_LEN {
+        return Ok((Status::BadRequest, Json(Err("comment is too long"))));
+    }
+
+    let actor_id = agent.id;
+    let resolution = conn
+        .run(move |c| {
+            let current = match db::problem::get_with_id(c, id)? {
+                Some(current) => current,
+                None => return Ok(Err((Status::NotFound, "problem not found"))),
+            };
+            if current.root_cause.trim().is_empty() {
+                return Ok(Err((
+                    Status::BadRequest,
+                    "resolved problems need a root cause",
+                )));
+            }
+
+            Ok::<_, io::Error>(match problem::resolve(c, &ctx, id, actor_id, &comment)? {
+                Some(resolution) => Ok(resolution),
+                None => Err((Status::NotFound, "problem not found")),
+            })
+        })
+        .await?;
+
+    Ok(match resolution {
+        Ok(resolution) => {
+            automation::dispatch(&conn, resolution.effects).await?;
+            (
+                Status::Ok,
+                Json(Ok(ResolutionDTO {
+                    problem: resolution.problem.into(),
+                    resolved_ticket_ids: resolution.resolved_ids,
+                })),
+            )
+        }
+        Err((status, error)) => (status, Json(Err(error))),
+    })
+}
+
+/// Publish the known error of a problem in the knowledge base, or update its article
+#[post("/problems/<id>/publish", format = "json", data = "<publication>")]
+pub async fn publish(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    publication: Json<PublicationFormDTO>,
+) -> io::Result<(Status, Json<Result<ArticleDTO, &'static str>>)> {
+    let PublicationFormDTO {
+        category_id,
+        visibility,
+    } = publication.into_inner();
+
+    let editor_id = agent.id;
+    conn.run(move |c| {
+        let current = match db::problem::get_with_id(c, id)? {
+            Some(current) => current,
+            None => return Ok((Status::NotFound, Json(Err("problem not found")))),
+        };
+        if current.status() == ProblemStatus::Investigating {
+            return Ok((
+                Status::BadRequest,
+                Json(Err("only known errors can be published")),
+            ));
+        }
+        if let Some(category_id) = category_id {
+            if db::article::get_category_with_id(c, category_id)?.is_none() {
+                return Ok((Status::BadRequest, Json(Err("category not found"))));
+            }
+        }
+
+        let (title, body) = problem::known_error(&current);
+        let form = db::model::ArticleForm {
+            category_id,
+            title: &title,
+            body: &body,
+            status: ArticleStatus::Published.as_str(),
+            visibility: visibility.as_str(),
+        };
+        let updated = match current.article_id {
+            Some(article_id) => db::article::update(c, article_id, &form, editor_id)?,
+            None => None,
+        };
+
+        Ok(match updated {
+            Some(article) => (Status::Ok, Json(Ok(article.into()))),
+            None => {
+                let article = db::article::insert(c, &form, editor_id)?;
+                let _ = db::problem::set_article(c, id, article.id)?;
+                (Status::Created, Json(Ok(article.into())))
+            }
+        })
+    })
+    .await
+}
+
+/// List the incident tickets of a problem, newest first
+#[get("/problems/<id>/tickets")]
+pub async fn tickets(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<TicketDTO>>)> {
+    let viewer = viewer(&conn, &agent).await?;
+    let tickets = conn
+        .run(move |c| {
+            if db::problem::get_with_id(c, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::problem::get_linked_tickets(c, &viewer, id).map(Some)
+        })
+        .await?;
+
+    Ok(match tickets {
+        Some(tickets) => (
+            Status::Ok,
+            Json(tickets.into_iter().map(Into::into).collect()),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Link an incident ticket to a problem
+#[post("/problems/<id>/tickets", format = "json", data = "<link>")]
+pub async fn link(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    link: Json<IncidentLinkDTO>,
+) -> io::Result<(Status, Json<Result<(), &'static str>>)> {
+    let viewer = viewer(&conn, &agent).await?;
+    let (ticket_id, linked_by) = (link.ticket_id, agent.id);
+
+    conn.run(move |c| {
+        if db::problem::get_with_id(c, id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("problem not found"))));
+        }
+        if db::ticket::get_with_id(c, &viewer, ticket_id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("ticket not found"))));
+        }
+
+        Ok(if db::problem::link(c, id, ticket_id, linked_by)? {
+            (Status::Created, Json(Ok(())))
+        } else {
+            (Status::Ok, Json(Ok(())))
+        })
+    })
+    .await
+}
+
+/// Unlink an incident ticket from a problem
+#[delete("/problems/<id>/tickets/<ticket_id>")]
+pub async fn unlink(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    ticket_id: Uuid,
+) -> io::Result<Status> {
+    let unlinked = conn
+        .run(move |c| db::problem::unlink(c, id, ticket_id))
+        .await?;
+
+    Ok(if unlinked {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the problems a ticket is an incident of, newest first
+#[get("/tickets/<id>/problems")]
+pub async fn linked(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<ProblemDTO>>)> {
+    let viewer = viewer(&conn, &agent).await?;
+    let problems = conn
+        .run(move |c| {
+            if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::problem::get_for_ticket(c, id).map(Some)
+        })
+        .await?;
+
+    Ok(match problems {
+        Some(problems) => (
+            Status::Ok,
+            Json(problems.into_iter().map(Into::into).collect()),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Validates a problem, returning it with its texts trimmed.
+///
+/// Owners must be agents or administrators.
+async fn validate_problem(
+    conn: &db::Connection,
+    mut problem: ProblemFormDTO,
+) -> io::Result<Result<ProblemFormDTO, &'static str>> {
+    if let Err(e) = problem.check() {
+        return Ok(Err(e));
+    }
+    problem.title = problem.title.trim().to_owned();
+    if problem.title.chars().count() > MAX_TITLE_LEN {
+        return Ok(Err("title is too long"));
+    }
+    problem.description = problem.description.trim().to_owned();
+    problem.root_cause = problem.root_cause.trim().to_owned();
+    problem.workaround = problem.workaround.trim().to_owned();
+
+    if let Some(owner_id) = problem.owner_id {
+        match conn
+            .run(move |c| db::user::get_with_id(c, owner_id))
+            .await?
+        {
+            Some(user) if user.role().is_staff() => {}
+            _ => return Ok(Err("owner must be an agent")),
+        }
+    }
+
+    Ok(Ok(problem))
+}
@@ -0,0 +1,122 @@
+//! Queues of tickets.
+
+use super::auth;
+use crate::db;
+use common::team::{QueueDTO, QueueFormDTO};
+use rocket::{get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// List all the queues
+#[get("/queues")]
+pub async fn list(_user: auth::User, conn: db::Connection) -> io::Result<Json<Vec<QueueDTO>>> {
+    let queues = conn.run(db::queue::get_all).await?;
+
+    Ok(Json(queues.into_iter().map(Into::into).collect()))
+}
+
+/// Create a new queue
+#[post("/queues", format = "json", data = "<queue>")]
+pub async fn create(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    queue: Json<QueueFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<QueueDTO, &'static str>>)> {
+    let form = queue.into_inner();
+    if let Err((status, e)) = validate_form(&conn, None, &form).await? {
+        return Ok((status, Json(Err(e))));
+    }
+
+    let (name, skills) = (
+        form.name.trim().to_owned(),
+        common::normalize_labels(form.skills),
+    );
+    let (team_id, strategy, is_default) = (form.team_id, form.strategy, form.is_default);
+    let queue = conn
+        .run(move |c| {
+            db::queue::insert(
+                c,
+                &db::model::QueueForm {
+                    name: &name,
+                    team_id,
+                    strategy: strategy.as_str(),
+                    skills: &skills,
+                    is_default,
+                },
+            )
+        })
+        .await?;
+
+    Ok((Status::Created, Json(Ok(queue.into()))))
+}
+
+/// Update the settings of a queue
+#[put("/queues/<id>", format = "json", data = "<queue>")]
+pub async fn update(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    queue: Json<QueueFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<QueueDTO, &'static str>>)> {
+    let form = queue.into_inner();
+    if let Err((status, e)) = validate_form(&conn, Some(id), &form).await? {
+        return Ok((status, Json(Err(e))));
+    }
+
+    let (name, skills) = (
+        form.name.trim().to_owned(),
+        common::normalize_labels(form.skills),
+    );
+    let (team_id, strategy, is_default) = (form.team_id, form.strategy, form.is_default);
+    let queue = conn
+        .run(move |c| {
+            db::queue::update(
+                c,
+                id,
+                &db::model::QueueForm {
+                    name: &name,
+                    team_id,
+                    strategy: strategy.as_str(),
+                    skills: &skills,
+                    is_default,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match queue {
+        Some(queue) => (Status::Ok, Json(Ok(queue.into()))),
+        None => (Status::NotFound, Json(Err("queue not found"))),
+    })
+}
+
+/// Validates the queue form, returning the status and reason if it's not valid.
+async fn validate_form(
+    conn: &db::Connection,
+    id: Option<Uuid>,
+    form: &QueueFormDTO<'_>,
+) -> io::Result<Result<(), (Status, &'static str)>> {
+    let name = form.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok(Err((Status::BadRequest, "name can't be empty")));
+    }
+
+    let team_id = form.team_id;
+    if conn
+        .run(move |c| db::team::get_with_id(c, team_id))
+        .await?
+        .is_none()
+    {
+        return Ok(Err((Status::BadRequest, "team not found")));
+    }
+
+    let queues = conn.run(db::queue::get_all).await?;
+    if queues
+        .iter()
+        .any(|queue| queue.name == name && Some(queue.id) != id)
+    {
+        return Ok(Err((Status::Conflict, "name belongs to another queue")));
+    }
+
+    Ok(Ok(()))
+}
@@ -1,13 +1,34 @@
-use crate::{db, notification, BASE_URL};
-use common::registration::{Email, ResponseDTO, SubmitDTO};
+use super::auth;
+use crate::{audit, db, notification, rand_code, BASE_URL};
+use common::{
+    audit::AuditEvent,
+    registration::{CodeInfoDTO, Email, ResponseDTO, SubmitDTO},
+    user::Role,
+};
 use once_cell::sync::Lazy;
-use rand::{distributions, thread_rng, Rng, RngCore};
 use regex::Regex;
-use rocket::{http::Status, post, serde::json::Json, tokio::task::spawn_blocking};
-use sha3::{Digest, Sha3_256};
+use rocket::{get, http::Status, post, serde::json::Json, tokio::task::spawn_blocking, State};
+use serde::Deserialize;
 use std::{io, sync::Arc};
+use uuid::Uuid;
 use zxcvbn::{zxcvbn, ZxcvbnError};
 
+/// Registration settings, managed by Rocket.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Config {
+    /// Whether users can register themselves, without an invitation.
+    ///
+    /// It can be disabled by setting the `open_registration` configuration key, or the
+    /// `OPEN_REGISTRATION` environment variable, to `false`.
+    #[serde(default = "open_registration_default")]
+    pub open_registration: bool,
+}
+
+/// Gets the default of [`Config::open_registration`].
+fn open_registration_default() -> bool {
+    true
+}
+
 static VALID_EMAIL: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"[^@]+@([^@]+\.[^@]+)")
         .expect("could not compile the valid email regular expression")
@@ -36,30 +57,21 @@ const FORBIDDEN_DOMAINS: [&str; 16] = [
 #[post("/register/email", format = "json", data = "<email>")]
 pub async fn email(
     conn: db::Connection,
+    ctx: audit::Context,
+    config: &State<Config>,
     email: Json<Email<'_>>,
 ) -> io::Result<(Status, Json<&'static str>)> {
-    let email = Arc::new(email.into_inner().email.trim().to_owned());
-
-    // Check if the email is correct:
-    if !VALID_EMAIL.is_match(&email) {
-        return Ok((Status::BadRequest, Json("invalid email")));
+    if !config.open_registration {
+        return Ok((Status::Forbidden, Json("open registration is disabled")));
     }
-    let cap = VALID_EMAIL
-        .captures(&email)
-        .expect("couldn't get captures for email address after validation");
 
-    let domain = cap
-        .get(1)
-        .expect("email domain disappeared")
-        .as_str()
-        .to_lowercase();
+    let email = Arc::new(email.into_inner().email.trim().to_owned());
 
-    if FORBIDDEN_DOMAINS
-        .into_iter()
-        .any(|forbidden| forbidden == domain)
-    {
-        return Ok((Status::BadRequest, Json("email not allowed")));
-    }
+    // Check if the email is correct:
+    let domain = match validate_email(&email) {
+        Ok(domain) => domain,
+        Err(e) => return Ok((Status::BadRequest, Json(e))),
+    };
 
     // Checks if there was an existing user with the email
     let email_clone = email.clone();
@@ -75,12 +87,20 @@ pub async fn email(
     // TODO: check if email registered recently (do not send more than one per 10 minutes)
     // Remove any existing codes for that email
     let email_clone = email.clone();
-    conn.run(move |c| db::user::delete_email_registrations_for_email(c, &email_clone))
-        .await?;
+    if conn
+        .run(move |c| db::user::delete_email_registrations_for_email(c, &email_clone))
+        .await?
+    {
+        let event = AuditEvent::EmailRegistrationDeleted {
+            email: (*email).clone(),
+        };
+        conn.run(move |c| audit::record(c, &ctx, None, &event))
+            .await?;
+    }
 
     // Generate the random code
     let code = loop {
-        let code = Arc::new(rand_reg_code());
+        let code = Arc::new(rand_code(10));
         let code_clone = code.clone();
         if conn
             .run(move |c| db::user::get_email_registration_with_code(c, &code_clone))
@@ -94,7 +114,11 @@ pub async fn email(
     let email_clone = email.clone();
     let code_clone = code.clone();
     let _ = conn
-        .run(move |c| db::user::insert_email_registration(c, &email_clone, &code_clone))
+        .run(move |c| {
+            // Users are automatically members of the organisation matching their email domain
+            let organisation_id = db::organisation::get_id_with_domain(c, &domain)?;
+            db::user::insert_email_registration(c, &email_clone, &code_clone, organisation_id)
+        })
         .await?;
 
     #[cfg(debug_assertions)]
@@ -117,37 +141,114 @@ pub async fn email(
     Ok((Status::Ok, Json("")))
 }
 
-/// Creates a random registration code.
-fn rand_reg_code() -> String {
-    let vec = thread_rng()
-        .sample_iter(distributions::Alphanumeric)
-        .take(10)
-        .collect::<Vec<u8>>();
+/// Validates an email address, returning its domain in lowercase, or the reason if it's not
+/// valid.
+pub(super) fn validate_email(email: &str) -> Result<String, &'static str> {
+    let cap = VALID_EMAIL.captures(email).ok_or("invalid email")?;
+
+    let domain = cap
+        .get(1)
+        .expect("email domain disappeared")
+        .as_str()
+        .to_lowercase();
+
+    if FORBIDDEN_DOMAINS
+        .into_iter()
+        .any(|forbidden| forbidden == domain)
+    {
+        return Err("email not allowed");
+    }
+
+    Ok(domain)
+}
+
+/// User information a registration code is valid for.
+#[derive(Debug)]
+struct CodeInfo {
+    email: String,
+    role: Role,
+    organisation_id: Option<Uuid>,
+    /// Whether the code comes from an invitation.
+    invited: bool,
+}
+
+/// Gets the user information a registration code is valid for.
+///
+/// The code can either come from an invitation or, if registration is open, from an email
+/// registration.
+async fn get_code_info(
+    conn: &db::Connection,
+    config: &Config,
+    code: String,
+) -> io::Result<Option<CodeInfo>> {
+    let code_clone = code.clone();
+    let invitation = conn
+        .run(move |c| db::user::get_invitation_with_code(c, &code_clone))
+        .await?;
+    if let Some(invitation) = invitation {
+        return Ok(Some(CodeInfo {
+            role: invitation.role(),
+            email: invitation.email,
+            organisation_id: invitation.organisation_id,
+            invited: true,
+        }));
+    }
 
-    // We know that the code is ASCII
-    String::from_utf8(vec).expect("invalid code generated")
+    if !config.open_registration {
+        return Ok(None);
+    }
+
+    let email_registration = conn
+        .run(move |c| db::user::get_email_registration_with_code(c, &code))
+        .await?;
+
+    Ok(email_registration.map(|reg| CodeInfo {
+        email: reg.email,
+        role: Role::Customer,
+        organisation_id: reg.organisation_id,
+        invited: false,
+    }))
+}
+
+/// Get the information of a registration code, to pre-fill the registration form
+#[get("/register/code/<code>")]
+pub async fn code_info(
+    conn: db::Connection,
+    config: &State<Config>,
+    code: String,
+) -> io::Result<(Status, Json<Option<CodeInfoDTO>>)> {
+    Ok(match get_code_info(&conn, config, code).await? {
+        Some(CodeInfo { email, invited, .. }) => {
+            (Status::Ok, Json(Some(CodeInfoDTO { email, invited })))
+        }
+        None => (Status::NotFound, Json(None)),
+    })
 }
 
 /// Register a user from a given code
 #[post("/register/user/<code>", format = "json", data = "<user>")]
 pub async fn register(
     conn: db::Connection,
+    ctx: audit::Context,
+    config: &State<Config>,
     code: String,
     user: Json<SubmitDTO<'_>>,
 ) -> io::Result<(Status, Json<ResponseDTO>)> {
     let user = user.into_inner();
     let mut response = ResponseDTO::default();
 
-    let email_registration = conn
-        .run(move |c| db::user::get_email_registration_with_code(c, &code))
-        .await?;
-
-    let email = if let Some(reg) = email_registration {
-        Arc::new(reg.email)
+    let CodeInfo {
+        email,
+        role,
+        organisation_id,
+        invited,
+    } = if let Some(code_info) = get_code_info(&conn, config, code.clone()).await? {
+        code_info
     } else {
         response.other = Some("invalid registration code".to_owned());
         return Ok((Status::BadRequest, Json(response)));
     };
+    let email = Arc::new(email);
 
     let cloned_email = email.clone();
     let db_user = conn
@@ -178,31 +279,49 @@ pub async fn register(
     };
 
     if response.is_ok() {
-        let mut salt = [0u8; 10];
-        thread_rng().fill_bytes(&mut salt); // Salt
-
-        let mut hasher = Sha3_256::new();
-        hasher.update(&salt);
-        hasher.update(user.password.as_bytes());
-
-        let result = hasher.finalize();
-        let mut db_pass = Vec::with_capacity(42);
-        db_pass.extend_from_slice(&salt);
-        db_pass.extend_from_slice(&result[..]);
-
-        debug_assert_eq!(db_pass.len(), 42);
-
         let (username, password, first_name, last_name) = (
             user.username.to_owned(),
-            db_pass,
+            auth::hash_password(user.password),
             user.first_name.to_owned(),
             user.last_name.to_owned(),
         );
 
-        conn.run(move |c| {
-            db::user::insert_user(c, &username, &email, &password, &first_name, &last_name)
-        })
-        .await?;
+        let db_user = conn
+            .run(move |c| {
+                db::user::insert_user(
+                    c,
+                    &username,
+                    &email,
+                    &password,
+                    &first_name,
+                    &last_name,
+                    role,
+                )
+            })
+            .await?;
+
+        if let Some(organisation_id) = organisation_id {
+            let user_id = db_user.id;
+            conn.run(move |c| db::organisation::insert_member(c, organisation_id, user_id))
+                .await?;
+        }
+
+        // Invitations can only be used once
+        if invited {
+            let _ = conn
+                .run(move |c| db::user::delete_invitation(c, &code))
+                .await?;
+        }
+
+        let (user_id, event) = (
+            db_user.id,
+            AuditEvent::Registration {
+                user_id: db_user.id,
+                invited,
+            },
+        );
+        conn.run(move |c| audit::record(c, &ctx, Some(user_id), &event))
+            .await?;
 
         Ok((Status::Ok, Json(response)))
     } else {
@@ -0,0 +1,461 @@
+//! Relationships between tickets.
+//!
+//! Agents merge duplicate tickets into a surviving one, split comments out into new tickets, break
+//! tickets down into child tasks and link related or duplicate tickets. Merged tickets are closed,
+//! and lead to the ticket they were merged into when looked up by number.
+
+use super::{auth, ticket};
+use crate::{audit, db, notification::centre, webhook};
+use common::{
+    audit::AuditEvent,
+    ticket::{
+        CommentSource, LinkKind, MergeDTO, ParentDTO, SplitDTO, Status as TicketStatus, TicketDTO,
+        TicketLinkDTO, TicketLinkFormDTO,
+    },
+    webhook::Event as WebhookEvent,
+};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum length of the title of a ticket split out of another one.
+const MAX_TITLE_LENGTH: usize = 200;
+
+/// Get the ticket with a number, following merges
+#[get("/tickets/by-number?<number>")]
+pub async fn by_number(
+    user: auth::User,
+    conn: db::Connection,
+    number: i64,
+) -> io::Result<(Status, Json<Option<TicketDTO>>)> {
+    let viewer = ticket::viewer(&conn, &user).await?;
+    let ticket = conn
+        .run(
+            move |c| match db::ticket::get_with_number(c, &viewer, number)? {
+                Some(ticket) => db::relation::get_survivor(c, &viewer, ticket).map(Some),
+                None => Ok(None),
+            },
+        )
+        .await?;
+
+    Ok(match ticket {
+        Some(ticket) => (Status::Ok, Json(Some(ticket.into()))),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Merge a ticket into another one, returning the surviving ticket
+///
+/// Both tickets must have the same requester or organisation, so that merges don't show customers
+/// the comments of other customers.
+#[post("/tickets/<id>/merge", format = "json", data = "<merge>")]
+pub async fn merge(
+    agent: auth::Agent,
+    conn: db::Connection,
+    ctx: audit::Context,
+    id: Uuid,
+    merge: Json<MergeDTO>,
+) -> io::Result<(Status, Json<Result<TicketDTO, &'static str>>)> {
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let (into_id, actor_id) = (merge.into_id, agent.id);
+    if into_id == id {
+        return Ok((
+            Status::BadRequest,
+            Json(Err("a ticket can't be merged into itself")),
+        ));
+    }
+
+    conn.run(move |c| {
+        let (source, target) = match (
+            db::ticket::get_with_id(c, &viewer, id)?,
+            db::ticket::get_with_id(c, &viewer, into_id)?,
+        ) {
+            (Some(source), Some(target)) => (source, target),
+            _ => return Ok((Status::NotFound, Json(Err("ticket not found")))),
+        };
+        if source.merged_into_id.is_some() || target.merged_into_id.is_some() {
+            return Ok((Status::BadRequest, Json(Err("ticket was already merged"))));
+        }
+        if target.status() == TicketStatus::Closed {
+            return Ok((
+                Status::BadRequest,
+                Json(Err("can't merge into a closed ticket")),
+            ));
+        }
+        let same_organisation =
+            source.organisation_id.is_some() && source.organisation_id == target.organisation_id;
+        if source.requester_id != target.requester_id && !same_organisation {
+            return Ok((
+                Status::BadRequest,
+                Json(Err("tickets must have the same requester or organisation")),
+            ));
+        }
+
+        let merged = db::relation::merge(c, &source, &target)?;
+        let _ = db::ticket::insert_comment(
+            c,
+            &db::model::NewTicketComment {
+                ticket_id: target.id,
+                author_id: actor_id,
+                body: &format!("Ticket #{} was merged into this ticket.", source.number),
+                source: CommentSource::Web.as_str(),
+            },
+        )?;
+        audit::record(
+            c,
+            &ctx,
+            Some(actor_id),
+            &AuditEvent::TicketMerged {
+                ticket_id: id,
+                into_id,
+            },
+        )?;
+        webhook::enqueue(c, WebhookEvent::TicketUpdated, &merged)?;
+        if merged.status != source.status {
+            centre::ticket_status_changed(c, &merged, Some(actor_id))?;
+        }
+
+        Ok::<_, io::Error>((Status::Ok, Json(Ok(target.into()))))
+    })
+    .await
+}
+
+/// Split a comment of a ticket out into a new ticket, with the same requester
+#[post(
+    "/tickets/<id>/comments/<comment_id>/split",
+    format = "json",
+    data = "<split>"
+)]
+pub async fn split(
+    agent: auth::Agent,
+    conn: db::Connection,
+    ctx: audit::Context,
+    id: Uuid,
+    comment_id: Uuid,
+    split: Json<SplitDTO>,
+) -> io::Result<(Status, Json<Result<TicketDTO, &'static str>>)> {
+    let title = split.title.trim().to_owned();
+    if title.is_empty() {
+        return Ok((Status::BadRequest, Json(Err("title can't be empty"))));
+    }
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return Ok((Status::BadRequest, Json(Err("title is too long"))));
+    }
+
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let actor_id = agent.id;
+    let split = conn
+        .run(move |c| {
+            let original = match db::ticket::get_with_id(c, &viewer, id)? {
+                Some(original) => original,
+                None => return Ok(Err("ticket not found")),
+            };
+            if !db::ticket::get_comments(c, id)?
+                .iter()
+                .any(|comment| comment.id == comment_id)
+            {
+                return Ok(Err("comment not found"));
+            }
+
+            let ticket = db::ticket::insert(
+                c,
+                &viewer,
+                &db::model::NewTicket {
+                    title: &title,
+                    description: &format!("Split from ticket #{}.", original.number),
+                    priority: &original.priority,
+                    requester_id: original.requester_id,
+                    organisation_id: original.organisation_id,
+                    queue_id: original.queue_id,
+                    category: original.category.as_deref(),
+                    custom_fields: Some(original.custom_fields.clone()),
+                },
+            )?;
+            let _ = db::relation::move_comment(c, id, comment_id, ticket.id)?;
+            let _ = db::relation::insert_link(
+                c,
+                &db::model::NewTicketLink::new(id, ticket.id, LinkKind::Related, actor_id),
+            )?;
+            let _ = db::ticket::insert_comment(
+                c,
+                &db::model::NewTicketComment {
+                    ticket_id: id,
+                    author_id: actor_id,
+                    body: &format!("Comment split out into ticket #{}.", ticket.number),
+                    source: CommentSource::Web.as_str(),
+                },
+            )?;
+            audit::record(
+                c,
+                &ctx,
+                Some(actor_id),
+                &AuditEvent::TicketSplit {
+                    ticket_id: id,
+                    comment_id,
+                    into_id: ticket.id,
+                },
+            )?;
+
+            Ok::<_, io::Error>(Ok((original.requester_id, ticket)))
+        })
+        .await?;
+
+    match split {
+        Ok((requester_id, ticket)) => {
+            let ticket = ticket::opened(&conn, requester_id, ticket).await?;
+            Ok((Status::Created, Json(Ok(ticket.into()))))
+        }
+        Err(error) => Ok((Status::NotFound, Json(Err(error)))),
+    }
+}
+
+/// List the child tickets of a ticket, oldest first
+#[get("/tickets/<id>/children")]
+pub async fn children(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<TicketDTO>>)> {
+    let viewer = ticket::viewer(&conn, &user).await?;
+    let children = conn
+        .run(move |c| {
+            if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::relation::get_children(c, &viewer, id).map(Some)
+        })
+        .await?;
+
+    Ok(match children {
+        Some(children) => (
+            Status::Ok,
+            Json(children.into_iter().map(Into::into).collect()),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Set or remove the parent of a ticket
+///
+/// A ticket can't become a child of one of its descendants, and parents can't be closed while
+/// their children are active.
+#[put("/tickets/<id>/parent", format = "json", data = "<parent>")]
+pub async fn set_parent(
+    agent: auth::Agent,
+    conn: db::Connection,
+    ctx: audit::Context,
+    id: Uuid,
+    parent: Json<ParentDTO>,
+) -> io::Result<(Status, Json<Result<TicketDTO, &'static str>>)> {
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let (parent_id, actor_id) = (parent.parent_id, agent.id);
+    if parent_id == Some(id) {
+        return Ok((
+            Status::BadRequest,
+            Json(Err("a ticket can't be its own parent")),
+        ));
+    }
+
+    conn.run(move |c| {
+        let before = match db::ticket::get_with_id(c, &viewer, id)? {
+            Some(ticket) => ticket,
+            None => return Ok((Status::NotFound, Json(Err("ticket not found")))),
+        };
+        if let Some(parent_id) = parent_id {
+            let parent = match db::ticket::get_with_id(c, &viewer, parent_id)? {
+                Some(parent) => parent,
+                None => return Ok((Status::NotFound, Json(Err("parent ticket not found")))),
+            };
+            if parent.merged_into_id.is_some() {
+                return Ok((Status::BadRequest, Json(Err("parent ticket was merged"))));
+            }
+            if db::relation::is_ancestor(c, id, parent_id)? {
+                return Ok((
+                    Status::BadRequest,
+                    Json(Err("parent ticket is a child of the ticket")),
+                ));
+            }
+        }
+
+        let after = db::relation::set_parent(c, id, parent_id)?
+            .expect("ticket disappeared while updating it");
+        if after.parent_id != before.parent_id {
+            audit::record(
+                c,
+                &ctx,
+                Some(actor_id),
+                &AuditEvent::TicketFieldChange {
+                    ticket_id: id,
+                    field: "parent_id".to_owned(),
+                    before: before.parent_id.map(|id| id.to_string()),
+                    after: after.parent_id.map(|id| id.to_string()),
+                },
+            )?;
+            webhook::enqueue(c, WebhookEvent::TicketUpdated, &after)?;
+        }
+
+        Ok::<_, io::Error>((Status::Ok, Json(Ok(after.into()))))
+    })
+    .await
+}
+
+/// List the tickets linked to a ticket, oldest link first
+#[get("/tickets/<id>/links")]
+pub async fn links(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<TicketLinkDTO>>)> {
+    let viewer = ticket::viewer(&conn, &user).await?;
+    let links = conn
+        .run(move |c| {
+            if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::relation::get_links(c, &viewer, id).map(Some)
+        })
+        .await?;
+
+    Ok(match links {
+        Some(links) => (
+            Status::Ok,
+            Json(
+                links
+                    .into_iter()
+                    .map(|(link, ticket)| TicketLinkDTO {
+                        kind: link.kind_from(id),
+                        created_by: link.created_by,
+                        created_on: link.created_on,
+                        ticket: ticket.into(),
+                    })
+                    .collect(),
+            ),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Link a ticket to another one
+#[post("/tickets/<id>/links", format = "json", data = "<link>")]
+pub async fn link(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    link: Json<TicketLinkFormDTO>,
+) -> io::Result<(Status, Json<Result<(), &'static str>>)> {
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let (TicketLinkFormDTO { ticket_id, kind }, created_by) = (link.into_inner(), agent.id);
+    if ticket_id == id {
+        return Ok((
+            Status::BadRequest,
+            Json(Err("a ticket can't be linked to itself")),
+        ));
+    }
+
+    conn.run(move |c| {
+        if db::ticket::get_with_id(c, &viewer, id)?.is_none()
+            || db::ticket::get_with_id(c, &viewer, ticket_id)?.is_none()
+        {
+            return Ok((Status::NotFound, Json(Err("ticket not found"))));
+        }
+
+        // Tickets are linked once, so changing the kind of a link means unlinking them first
+        if let Some(existing) = db::relation::get_link(c, id, ticket_id)? {
+            return Ok(if existing.kind_from(id) == kind {
+                (Status::Ok, Json(Ok(())))
+            } else {
+                (
+                    Status::Conflict,
+                    Json(Err("tickets are already linked with another kind")),
+                )
+            });
+        }
+
+        let link = db::model::NewTicketLink::new(id, ticket_id, kind, created_by);
+        Ok(if db::relation::insert_link(c, &link)? {
+            (Status::Created, Json(Ok(())))
+        } else {
+            (Status::Ok, Json(Ok(())))
+        })
+    })
+    .await
+}
+
+/// Unlink a ticket from another one
+#[delete("/tickets/<id>/links/<ticket_id>")]
+pub async fn unlink(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    ticket_id: Uuid,
+) -> io::Result<Status> {
+    let unlinked = conn
+        .run(move |c| db::relation::delete_link(c, id, ticket_id))
+        .await?;
+
+    Ok(if unlinked {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the users watching a ticket
+#[get("/tickets/<id>/watchers")]
+pub async fn watchers(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<Uuid>>)> {
+    let viewer = ticket::viewer(&conn, &user).await?;
+    let watcher_ids = conn
+        .run(move |c| {
+            if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::ticket::get_watcher_ids(c, id).map(Some)
+        })
+        .await?;
+
+    Ok(match watcher_ids {
+        Some(watcher_ids) => (Status::Ok, Json(watcher_ids)),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Watch a ticket, to be notified of its comments and status changes
+#[put("/tickets/<id>/watch")]
+pub async fn watch(user: auth::User, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    let viewer = ticket::viewer(&conn, &user).await?;
+    let user_id = user.id;
+    conn.run(move |c| {
+        if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+            return Ok(Status::NotFound);
+        }
+
+        Ok(if db::ticket::insert_watcher(c, id, user_id)? {
+            Status::Created
+        } else {
+            Status::Ok
+        })
+    })
+    .await
+}
+
+/// Stop watching a ticket
+#[delete("/tickets/<id>/watch")]
+pub async fn unwatch(user: auth::User, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    let user_id = user.id;
+    let unwatched = conn
+        .run(move |c| db::ticket::delete_watcher(c, id, user_id))
+        .await?;
+
+    Ok(if unwatched {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
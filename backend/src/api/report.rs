@@ -0,0 +1,388 @@
+//! Reports for supervisors.
+//!
+//! Agents run reports of the backlog, volume, response and resolution times, SLA compliance and
+//! satisfaction of the tickets, by period and optionally by team, agent or category. Reports are
+//! returned as JSON for charts, or exported as CSV or XLSX files streamed while the rows are
+//! fetched. Administrators set the SLA policies compliance is measured against.
+
+use super::{auth, parse_date};
+use crate::{
+    csv,
+    db::{self, model, report::Query},
+    xlsx::{self, Cell},
+};
+use chrono::{Duration, Utc};
+use common::{
+    report::{Dimension, Metric, Period, ReportDTO, ReportRowDTO, SlaPolicyDTO, SlaPolicyFormDTO},
+    ticket::Priority,
+};
+use rocket::{
+    error,
+    futures::stream::Stream,
+    get,
+    http::{ContentType, Header, Status},
+    put,
+    response::{self, stream::ByteStream, Responder, Response},
+    serde::json::Json,
+    tokio::{self, sync::mpsc},
+    Request,
+};
+use std::io;
+
+/// Default length of the time range of reports, in days.
+const DEFAULT_DAYS: i64 = 30;
+
+/// Maximum number of periods of a report.
+const MAX_PERIODS: i64 = 1000;
+
+/// Default timezone of reports.
+const DEFAULT_TIMEZONE: &str = "UTC";
+
+/// Number of chunks of rows fetched ahead of an export being sent.
+const EXPORT_BUFFER: usize = 2;
+
+/// Header of the exports of reports.
+const EXPORT_HEADER: [&str; 5] = ["period", "group_id", "group", "value", "count"];
+
+/// Run a report for charts
+///
+/// The report covers the time range from `from` to `to`, given as RFC 3339 timestamps, by default
+/// the last 30 days. Periods start at midnight in the `tz` timezone, UTC by default.
+#[get("/reports/<metric>?<by>&<period>&<from>&<to>&<tz>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn report(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    metric: &str,
+    by: Option<&str>,
+    period: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+    tz: Option<&str>,
+) -> io::Result<(Status, Json<Result<ReportDTO, &'static str>>)> {
+    let query = match parse_query(&conn, metric, by, period, from, to, tz).await? {
+        Ok(query) => query,
+        Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+    };
+
+    let (metric, by, period, timezone) =
+        (query.metric, query.by, query.period, query.timezone.clone());
+    let rows = conn
+        .run(move |c| {
+            let mut rows = Vec::new();
+            db::report::get_rows(c, &query, |chunk| {
+                rows.extend(chunk.into_iter().map(ReportRowDTO::from));
+                true
+            })?;
+
+            Ok::<_, io::Error>(rows)
+        })
+        .await?;
+
+    Ok((
+        Status::Ok,
+        Json(Ok(ReportDTO {
+            metric,
+            by,
+            period,
+            timezone,
+            rows,
+        })),
+    ))
+}
+
+/// Report exported as a file, sent while its rows are fetched.
+pub struct Export<S> {
+    data: ByteStream<S>,
+    content_type: ContentType,
+    disposition: Header<'static>,
+}
+
+impl<'r, S> Responder<'r, 'r> for Export<S>
+where
+    S: Stream<Item = Vec<u8>> + Send + 'r,
+{
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'r> {
+        Response::build_from(self.data.respond_to(request)?)
+            .header(self.content_type)
+            .header(self.disposition)
+            .ok()
+    }
+}
+
+/// Export a report as CSV, with the same parameters as the report for charts
+#[get("/reports/<metric>/export.csv?<by>&<period>&<from>&<to>&<tz>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_csv(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    metric: &str,
+    by: Option<&str>,
+    period: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+    tz: Option<&str>,
+) -> io::Result<Result<Export<impl Stream<Item = Vec<u8>> + Send>, Status>> {
+    let query = match parse_query(&conn, metric, by, period, from, to, tz).await? {
+        Ok(query) => query,
+        Err(_) => return Ok(Err(Status::BadRequest)),
+    };
+    let filename = export_filename(&query, "csv");
+
+    let mut chunks = fetch_rows(conn, query);
+    let data = ByteStream! {
+        let mut text = String::new();
+        csv::write_record(&mut text, EXPORT_HEADER);
+        yield text.into_bytes();
+
+        while let Some(rows) = chunks.recv().await {
+            let mut text = String::new();
+            for row in rows {
+                csv::write_record(
+                    &mut text,
+                    [
+                        row.period.to_string(),
+                        row.group_id.map(|id| id.to_string()).unwrap_or_default(),
+                        row.group_name.unwrap_or_default(),
+                        row.value.map(|value| value.to_string()).unwrap_or_default(),
+                        row.count.to_string(),
+                    ],
+                );
+            }
+            yield text.into_bytes();
+        }
+    };
+
+    Ok(Ok(Export {
+        data,
+        content_type: ContentType::CSV,
+        disposition: attachment(&filename),
+    }))
+}
+
+/// Export a report as an XLSX workbook, with the same parameters as the report for charts
+#[get("/reports/<metric>/export.xlsx?<by>&<period>&<from>&<to>&<tz>")]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_xlsx(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    metric: &str,
+    by: Option<&str>,
+    period: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+    tz: Option<&str>,
+) -> io::Result<Result<Export<impl Stream<Item = Vec<u8>> + Send>, Status>> {
+    let query = match parse_query(&conn, metric, by, period, from, to, tz).await? {
+        Ok(query) => query,
+        Err(_) => return Ok(Err(Status::BadRequest)),
+    };
+    let filename = export_filename(&query, "xlsx");
+    let (mut writer, start) = xlsx::Writer::start(query.metric.as_str())?;
+
+    let mut chunks = fetch_rows(conn, query);
+    let data = ByteStream! {
+        yield start;
+        let header = EXPORT_HEADER.map(Cell::Text);
+        match writer.write_row(&header) {
+            Ok(bytes) => yield bytes,
+            Err(e) => {
+                error!("could not export report: {}", e);
+                return;
+            }
+        }
+
+        while let Some(rows) = chunks.recv().await {
+            match write_rows(&mut writer, rows) {
+                Ok(bytes) => yield bytes,
+                Err(e) => {
+                    error!("could not export report: {}", e);
+                    return;
+                }
+            }
+        }
+
+        match writer.finish() {
+            Ok(bytes) => yield bytes,
+            Err(e) => error!("could not export report: {}", e),
+        }
+    };
+
+    Ok(Ok(Export {
+        data,
+        content_type: ContentType::new(
+            "application",
+            "vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        ),
+        disposition: attachment(&filename),
+    }))
+}
+
+/// List the SLA policies of the ticket priorities, from the lowest priority
+#[get("/sla-policies")]
+pub async fn sla_policies(
+    _agent: auth::Agent,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<SlaPolicyDTO>>> {
+    let policies = conn.run(db::report::get_sla_policies).await?;
+
+    Ok(Json(policies.into_iter().map(SlaPolicyDTO::from).collect()))
+}
+
+/// Change the SLA policy of a ticket priority
+#[put("/sla-policies/<priority>", format = "json", data = "<form>")]
+pub async fn update_sla_policy(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    priority: &str,
+    form: Json<SlaPolicyFormDTO>,
+) -> io::Result<(Status, Json<Result<SlaPolicyDTO, &'static str>>)> {
+    let priority = match priority.parse::<Priority>() {
+        Ok(priority) => priority,
+        Err(_) => return Ok((Status::NotFound, Json(Err("unknown priority")))),
+    };
+    let form = form.into_inner();
+    if form.response_minutes <= 0 || form.resolution_minutes < form.response_minutes {
+        return Ok((
+            Status::BadRequest,
+            Json(Err(
+                "resolution target can't be shorter than response target",
+            )),
+        ));
+    }
+
+    let policy = conn
+        .run(move |c| {
+            db::report::update_sla_policy(
+                c,
+                priority,
+                &model::SlaPolicyForm {
+                    response_minutes: form.response_minutes,
+                    resolution_minutes: form.resolution_minutes,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match policy {
+        Some(policy) => (Status::Ok, Json(Ok(policy.into()))),
+        None => (Status::NotFound, Json(Err("unknown priority"))),
+    })
+}
+
+/// Parses the parameters of a report, returning the reason if they are invalid.
+async fn parse_query(
+    conn: &db::Connection,
+    metric: &str,
+    by: Option<&str>,
+    period: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+    tz: Option<&str>,
+) -> io::Result<Result<Query, &'static str>> {
+    let metric = match metric.parse::<Metric>() {
+        Ok(metric) => metric,
+        Err(_) => return Ok(Err("unknown metric")),
+    };
+    let by = match by.map(str::parse).transpose() {
+        Ok(by) => by.unwrap_or(Dimension::Total),
+        Err(_) => return Ok(Err("unknown dimension")),
+    };
+    let period = match period.map(str::parse).transpose() {
+        Ok(period) => period.unwrap_or(Period::Day),
+        Err(_) => return Ok(Err("unknown period")),
+    };
+    let (from, to) = match (parse_date(from), parse_date(to)) {
+        (Ok(from), Ok(to)) => {
+            let to = to.unwrap_or_else(Utc::now);
+            (from.unwrap_or(to - Duration::days(DEFAULT_DAYS)), to)
+        }
+        _ => return Ok(Err("invalid date")),
+    };
+    if from >= to {
+        return Ok(Err("the time range is empty"));
+    }
+    let period_days = match period {
+        Period::Day => 1,
+        Period::Week => 7,
+        Period::Month => 28,
+    };
+    if (to - from).num_days() / period_days > MAX_PERIODS {
+        return Ok(Err("the time range has too many periods"));
+    }
+
+    let timezone = tz.unwrap_or(DEFAULT_TIMEZONE).to_owned();
+    let known = {
+        let timezone = timezone.clone();
+        conn.run(move |c| db::report::is_timezone(c, &timezone))
+            .await?
+    };
+    if !known {
+        return Ok(Err("unknown timezone"));
+    }
+
+    Ok(Ok(Query {
+        metric,
+        by,
+        period,
+        timezone,
+        from,
+        to,
+    }))
+}
+
+/// Fetches the rows of a report in the background, receiving them in chunks.
+///
+/// Fetching stops early if the receiver is dropped, such as when the client disconnects. Errors
+/// can only be logged, since the response has already started.
+fn fetch_rows(conn: db::Connection, query: Query) -> mpsc::Receiver<Vec<model::ReportRow>> {
+    let (sender, receiver) = mpsc::channel(EXPORT_BUFFER);
+    let _task = tokio::spawn(async move {
+        let fetched = conn
+            .run(move |c| {
+                db::report::get_rows(c, &query, |rows| sender.blocking_send(rows).is_ok())
+            })
+            .await;
+        if let Err(e) = fetched {
+            error!("could not export report: {}", e);
+        }
+    });
+
+    receiver
+}
+
+/// Writes rows of a report to a workbook.
+fn write_rows(writer: &mut xlsx::Writer, rows: Vec<model::ReportRow>) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for row in rows {
+        let (period, group_id) = (
+            row.period.to_string(),
+            row.group_id.map(|id| id.to_string()).unwrap_or_default(),
+        );
+        bytes.extend(writer.write_row(&[
+            Cell::Text(&period),
+            Cell::Text(&group_id),
+            Cell::Text(row.group_name.as_deref().unwrap_or_default()),
+            row.value.map_or(Cell::Empty, Cell::Number),
+            Cell::Number(row.count as f64),
+        ])?);
+    }
+
+    Ok(bytes)
+}
+
+/// Gets the name of the file a report is exported to.
+fn export_filename(query: &Query, extension: &str) -> String {
+    format!(
+        "report-{}-{}-{}.{}",
+        query.metric, query.by, query.period, extension
+    )
+}
+
+/// Builds the header downloading a response as a file.
+fn attachment(filename: &str) -> Header<'static> {
+    Header::new(
+        "Content-Disposition",
+        format!("attachment; filename=\"{}\"", filename),
+    )
+}
@@ -0,0 +1,99 @@
+//! Full-text search.
+
+use super::{auth, custom_field, parse_date, ticket::viewer};
+use crate::db;
+use common::{
+    custom_field::{CustomFieldDTO, Values},
+    search::TicketHitDTO,
+    ticket::Status as TicketStatus,
+};
+use rocket::{get, http::Status, serde::json::Json, FromForm};
+use std::{collections::HashMap, io};
+use uuid::Uuid;
+
+/// Maximum number of results returned in a single query.
+const MAX_LIMIT: i64 = 100;
+
+/// Default number of results returned in a single query.
+const DEFAULT_LIMIT: i64 = 20;
+
+/// Search query parameters.
+#[derive(Debug, FromForm)]
+pub struct Query<'r> {
+    /// Words to search, in web search syntax.
+    q: &'r str,
+    /// Only tickets with this status will be returned.
+    status: Option<&'r str>,
+    /// Only tickets assigned to this agent will be returned.
+    assignee: Option<Uuid>,
+    /// Only tickets of this organisation will be returned.
+    organisation: Option<Uuid>,
+    /// RFC 3339 timestamp, only tickets created at or after it will be returned.
+    since: Option<&'r str>,
+    /// RFC 3339 timestamp, only tickets created before it will be returned.
+    until: Option<&'r str>,
+    /// Values of custom fields by key, as `cf[key]=value`. Only tickets with all of them will be
+    /// returned. Users are given by ID.
+    cf: HashMap<&'r str, &'r str>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Search the tickets visible to the user, most relevant first
+#[get("/search?<query..>")]
+pub async fn search(
+    user: auth::User,
+    conn: db::Connection,
+    query: Query<'_>,
+) -> io::Result<(Status, Json<Vec<TicketHitDTO>>)> {
+    let status = match query.status.map(str::parse::<TicketStatus>).transpose() {
+        Ok(status) => status,
+        Err(_) => return Ok((Status::BadRequest, Json(Vec::new()))),
+    };
+    let (since, until) = match (parse_date(query.since), parse_date(query.until)) {
+        (Ok(since), Ok(until)) => (since, until),
+        _ => return Ok((Status::BadRequest, Json(Vec::new()))),
+    };
+    if query.q.trim().is_empty() {
+        return Ok((Status::BadRequest, Json(Vec::new())));
+    }
+    let custom_fields = if query.cf.is_empty() {
+        None
+    } else {
+        let fields = custom_field::definitions(&conn).await?;
+        match custom_values(&fields, &query.cf) {
+            Some(values) => Some(serde_json::json!(values)),
+            None => return Ok((Status::BadRequest, Json(Vec::new()))),
+        }
+    };
+
+    let viewer = viewer(&conn, &user).await?;
+    let words = query.q.to_owned();
+    let filter = db::search::TicketFilter {
+        status: status.map(|status| status.as_str().to_owned()),
+        assignee_id: query.assignee,
+        organisation_id: query.organisation,
+        since,
+        until,
+        custom_fields,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let hits = conn
+        .run(move |c| db::search::tickets(c, &viewer, &words, &filter, limit, offset))
+        .await?;
+
+    Ok((Status::Ok, Json(hits.into_iter().map(Into::into).collect())))
+}
+
+/// Parses the values of custom fields given by key, returning `None` if any is invalid.
+fn custom_values(fields: &[CustomFieldDTO], values: &HashMap<&str, &str>) -> Option<Values> {
+    values
+        .iter()
+        .map(|(key, value)| {
+            let field = fields.iter().find(|field| field.key == *key)?;
+            Some(((*key).to_owned(), field.kind.parse(value)?))
+        })
+        .collect()
+}
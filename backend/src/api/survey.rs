@@ -0,0 +1,160 @@
+//! Customer satisfaction surveys.
+//!
+//! Requesters answer the survey of a resolved ticket from the links of the survey email, without
+//! logging in. Agents see the answers of the tickets, and administrators choose the ticket
+//! categories whose tickets don't get surveys.
+
+use super::{auth, ticket};
+use crate::db;
+use common::survey::{SurveyAnswerDTO, SurveyDTO, SurveyResultDTO};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum length of the comment of an answer.
+const MAX_COMMENT_LEN: usize = 2000;
+
+/// Maximum length of ticket categories.
+const MAX_CATEGORY_LEN: usize = 50;
+
+/// Get the survey an email link is for
+#[get("/surveys/<token>")]
+pub async fn with_token(
+    conn: db::Connection,
+    token: String,
+) -> io::Result<(Status, Json<Option<SurveyDTO>>)> {
+    let survey = conn.run(move |c| survey_with_token(c, &token)).await?;
+
+    // Expired and unknown links are not told apart
+    Ok(match survey {
+        Some(survey) => (Status::Ok, Json(Some(survey))),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Answer a survey with the link of a survey email
+///
+/// Answers can be changed until the link expires, so that a comment can follow the rating.
+#[post("/surveys/<token>", format = "json", data = "<answer>")]
+pub async fn answer_with_token(
+    conn: db::Connection,
+    token: String,
+    answer: Json<SurveyAnswerDTO>,
+) -> io::Result<(Status, Json<Result<SurveyDTO, &'static str>>)> {
+    let SurveyAnswerDTO { rating, comment } = answer.into_inner();
+    if comment.chars().count() > MAX_COMMENT_LEN {
+        return Ok((Status::BadRequest, Json(Err("comment is too long"))));
+    }
+
+    conn.run(move |c| {
+        let survey = match db::survey::get_with_token(c, &token)? {
+            Some(survey) => survey,
+            None => return Ok((Status::NotFound, Json(Err("survey not found")))),
+        };
+        let _ = db::survey::answer(c, survey.id, rating, comment.trim())?;
+
+        Ok(match survey_with_token(c, &token)? {
+            Some(survey) => (Status::Ok, Json(Ok(survey))),
+            None => (Status::NotFound, Json(Err("survey not found"))),
+        })
+    })
+    .await
+}
+
+/// List the surveys of a ticket with their answers, oldest first
+#[get("/tickets/<id>/surveys")]
+pub async fn surveys(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<SurveyResultDTO>>)> {
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let surveys = conn
+        .run(move |c| {
+            if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::survey::get_for_ticket(c, id).map(Some)
+        })
+        .await?;
+
+    Ok(match surveys {
+        Some(surveys) => (
+            Status::Ok,
+            Json(surveys.into_iter().map(SurveyResultDTO::from).collect()),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// List the ticket categories whose tickets don't get surveys
+#[get("/survey-suppressions")]
+pub async fn suppressions(
+    _admin: auth::Admin,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<String>>> {
+    conn.run(db::survey::get_suppressions).await.map(Json)
+}
+
+/// Stop sending surveys for the tickets of a category
+#[put("/survey-suppressions/<category>")]
+pub async fn suppress(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    category: String,
+) -> io::Result<Status> {
+    let category = category.trim().to_owned();
+    if category.is_empty() || category.chars().count() > MAX_CATEGORY_LEN {
+        return Ok(Status::BadRequest);
+    }
+
+    Ok(
+        if conn
+            .run(move |c| db::survey::insert_suppression(c, &category))
+            .await?
+        {
+            Status::Created
+        } else {
+            Status::Ok
+        },
+    )
+}
+
+/// Send surveys again for the tickets of a category
+#[delete("/survey-suppressions/<category>")]
+pub async fn unsuppress(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    category: String,
+) -> io::Result<Status> {
+    Ok(
+        if conn
+            .run(move |c| db::survey::delete_suppression(c, category.trim()))
+            .await?
+        {
+            Status::NoContent
+        } else {
+            Status::NotFound
+        },
+    )
+}
+
+/// Gets the survey with a token along with its ticket, if it exists and hasn't expired.
+fn survey_with_token(
+    conn: &mut diesel::PgConnection,
+    token: &str,
+) -> io::Result<Option<SurveyDTO>> {
+    let survey = match db::survey::get_with_token(conn, token)? {
+        Some(survey) => survey,
+        None => return Ok(None),
+    };
+    let ticket = db::ticket::get_with_id(conn, &db::tenant::Viewer::system(), survey.ticket_id)?;
+
+    Ok(ticket.map(|ticket| SurveyDTO {
+        number: ticket.number,
+        title: ticket.title,
+        rating: survey.rating(),
+        comment: survey.comment,
+    }))
+}
@@ -0,0 +1,144 @@
+//! Teams of agents.
+
+use super::auth;
+use crate::db;
+use common::team::{TeamDTO, TeamFormDTO, TeamMemberDTO, TeamMemberFormDTO};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// List all the teams, with their members
+#[get("/teams")]
+pub async fn list(_agent: auth::Agent, conn: db::Connection) -> io::Result<Json<Vec<TeamDTO>>> {
+    let teams = conn.run(db::team::get_all).await?;
+
+    Ok(Json(
+        teams
+            .into_iter()
+            .map(|(team, members)| TeamDTO {
+                id: team.id,
+                name: team.name,
+                members: members
+                    .into_iter()
+                    .map(|(member, username)| TeamMemberDTO {
+                        user_id: member.user_id,
+                        username,
+                        skills: member.skills,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    ))
+}
+
+/// Create a new team
+#[post("/teams", format = "json", data = "<team>")]
+pub async fn create(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    team: Json<TeamFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<TeamDTO, &'static str>>)> {
+    let name = team.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok((Status::BadRequest, Json(Err("name can't be empty"))));
+    }
+
+    if name_taken(&conn, None, name.clone()).await? {
+        return Ok((Status::Conflict, Json(Err("name belongs to another team"))));
+    }
+
+    let team = conn.run(move |c| db::team::insert(c, &name)).await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(TeamDTO {
+            id: team.id,
+            name: team.name,
+            members: Vec::new(),
+        })),
+    ))
+}
+
+/// Rename a team
+#[put("/teams/<id>", format = "json", data = "<team>")]
+pub async fn rename(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    team: Json<TeamFormDTO<'_>>,
+) -> io::Result<(Status, Json<&'static str>)> {
+    let name = team.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok((Status::BadRequest, Json("name can't be empty")));
+    }
+
+    if name_taken(&conn, Some(id), name.clone()).await? {
+        return Ok((Status::Conflict, Json("name belongs to another team")));
+    }
+
+    Ok(
+        match conn.run(move |c| db::team::rename(c, id, &name)).await? {
+            Some(_) => (Status::Ok, Json("")),
+            None => (Status::NotFound, Json("team not found")),
+        },
+    )
+}
+
+/// Checks if a team name already belongs to a different team.
+async fn name_taken(conn: &db::Connection, id: Option<Uuid>, name: String) -> io::Result<bool> {
+    let teams = conn.run(db::team::get_all).await?;
+
+    Ok(teams
+        .iter()
+        .any(|(team, _)| team.name == name && Some(team.id) != id))
+}
+
+/// Add an agent to a team, or update their skills
+#[put("/teams/<id>/members", format = "json", data = "<member>")]
+pub async fn set_member(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    member: Json<TeamMemberFormDTO>,
+) -> io::Result<(Status, Json<&'static str>)> {
+    let TeamMemberFormDTO { user_id, skills } = member.into_inner();
+
+    if conn
+        .run(move |c| db::team::get_with_id(c, id))
+        .await?
+        .is_none()
+    {
+        return Ok((Status::NotFound, Json("team not found")));
+    }
+
+    // Only the support staff can be assigned tickets
+    match conn.run(move |c| db::user::get_with_id(c, user_id)).await? {
+        Some(user) if user.role().is_staff() => {}
+        _ => return Ok((Status::BadRequest, Json("member must be an agent"))),
+    }
+
+    let skills = common::normalize_labels(skills);
+    conn.run(move |c| db::team::upsert_member(c, id, user_id, &skills))
+        .await?;
+
+    Ok((Status::Ok, Json("")))
+}
+
+/// Remove an agent from a team
+#[delete("/teams/<id>/members/<user_id>")]
+pub async fn remove_member(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    user_id: Uuid,
+) -> io::Result<Status> {
+    let deleted = conn
+        .run(move |c| db::team::delete_member(c, id, user_id))
+        .await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
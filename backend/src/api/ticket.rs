@@ -0,0 +1,644 @@
+//! Support tickets.
+
+use super::{auth, custom_field};
+use crate::{
+    audit, automation,
+    db::{self, tenant::Viewer, user::PickFilter},
+    into_io_err,
+    notification::centre,
+};
+use chrono::Utc;
+use common::{
+    custom_field::{CustomFieldDTO, Kind, Value, Values},
+    query::{Filter, Query},
+    team::AssignmentDTO,
+    ticket::{
+        AttachmentDTO, CommentDTO, CommentSource, NewCommentDTO, NewTicketDTO, TicketDTO,
+        TicketUpdateDTO,
+    },
+};
+use rocket::{
+    get,
+    http::{ContentType, Header, Status},
+    patch, post,
+    serde::json::Json,
+    Responder,
+};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum number of tickets returned in a single query.
+pub(super) const MAX_LIMIT: i64 = 500;
+
+/// Default number of tickets returned in a single query.
+pub(super) const DEFAULT_LIMIT: i64 = 50;
+
+/// Gets the viewer information of a user, to access tenant data.
+pub(super) async fn viewer(conn: &db::Connection, user: &db::model::User) -> io::Result<Viewer> {
+    let (user_id, role) = (user.id, user.role());
+    let organisation_ids = conn
+        .run(move |c| db::organisation::get_user_organisation_ids(c, user_id))
+        .await?;
+
+    Ok(Viewer {
+        user_id,
+        role,
+        organisation_ids,
+    })
+}
+
+/// Resolves the organisation of a new ticket opened by a viewer.
+///
+/// Customers can only open tickets for their own organisations, and default to their organisation
+/// if they have a single one.
+pub(super) async fn resolve_organisation(
+    conn: &db::Connection,
+    viewer: &Viewer,
+    organisation_id: Option<Uuid>,
+) -> io::Result<Result<Option<Uuid>, (Status, &'static str)>> {
+    Ok(Ok(match organisation_id {
+        Some(org_id) if viewer.sees_all() => {
+            if conn
+                .run(move |c| db::organisation::get_with_id(c, org_id))
+                .await?
+                .is_none()
+            {
+                return Ok(Err((Status::BadRequest, "organisation not found")));
+            }
+            Some(org_id)
+        }
+        Some(org_id) if viewer.organisation_ids.contains(&org_id) => Some(org_id),
+        Some(_) => return Ok(Err((Status::Forbidden, "organisation not allowed"))),
+        None => viewer.default_organisation_id(),
+    }))
+}
+
+/// Resolves the queue of a new ticket, which is the default queue if none is given.
+pub(super) async fn resolve_queue(
+    conn: &db::Connection,
+    queue_id: Option<Uuid>,
+) -> io::Result<Result<Option<Uuid>, (Status, &'static str)>> {
+    Ok(Ok(match queue_id {
+        Some(queue_id) => {
+            if conn
+                .run(move |c| db::queue::get_with_id(c, queue_id))
+                .await?
+                .is_none()
+            {
+                return Ok(Err((Status::BadRequest, "queue not found")));
+            }
+            Some(queue_id)
+        }
+        None => conn
+            .run(db::queue::get_default)
+            .await?
+            .map(|queue| queue.id),
+    }))
+}
+
+/// Validates the custom field values of a ticket of a category, returning them normalized.
+///
+/// Users must be users the viewer can pick, as in service requests.
+pub(super) async fn validate_custom_fields(
+    conn: &db::Connection,
+    viewer: &Viewer,
+    category: Option<&str>,
+    values: &Values,
+) -> io::Result<Result<Values, String>> {
+    let fields = custom_field::definitions(conn).await?;
+    let values = match common::custom_field::validate(&fields, category, values) {
+        Ok(values) => values,
+        Err(errors) => {
+            return Ok(Err(errors
+                .iter()
+                .map(|error| format!("{}: {}", error.field, error.message))
+                .collect::<Vec<_>>()
+                .join(", ")))
+        }
+    };
+
+    for field in fields.iter().filter(|field| field.kind == Kind::User) {
+        let id = match values.get(&field.key) {
+            Some(Value::Text(id)) => id.parse().map_err(into_io_err)?,
+            _ => continue,
+        };
+        let picker_id = viewer.user_id;
+        let organisation_ids = (!viewer.sees_all()).then(|| viewer.organisation_ids.clone());
+        let picked = conn
+            .run(move |c| {
+                db::user::get_pickable(
+                    c,
+                    picker_id,
+                    organisation_ids.as_deref(),
+                    PickFilter::Id(id),
+                    1,
+                )
+            })
+            .await?;
+        if picked.is_empty() {
+            return Ok(Err(format!("{}: is not a user you can pick", field.key)));
+        }
+    }
+
+    Ok(Ok(values))
+}
+
+/// Checks the filters of a query on custom fields against their definitions.
+pub(super) fn check_custom_filters(fields: &[CustomFieldDTO], query: &Query) -> Result<(), String> {
+    query.terms.iter().try_for_each(|term| match &term.filter {
+        Filter::Custom {
+            key,
+            operator,
+            value,
+        } => common::custom_field::check_filter(fields, key, *operator, value.as_deref()),
+        _ => Ok(()),
+    })
+}
+
+/// Runs the side effects of opening a ticket that was just inserted, returning it updated.
+///
+/// The side effects of the automation rules are dispatched once they are stored.
+pub(super) async fn opened(
+    conn: &db::Connection,
+    requester_id: Uuid,
+    ticket: db::model::Ticket,
+) -> io::Result<db::model::Ticket> {
+    let (ticket, effects) = conn
+        .run(move |c| crate::ticket::opened(c, requester_id, ticket))
+        .await?;
+    automation::dispatch(conn, effects).await?;
+
+    Ok(ticket)
+}
+
+/// Open a new ticket
+#[post("/tickets", format = "json", data = "<ticket>")]
+pub async fn create(
+    user: auth::User,
+    conn: db::Connection,
+    ticket: Json<NewTicketDTO<'_>>,
+) -> io::Result<(Status, Json<Result<TicketDTO, String>>)> {
+    let NewTicketDTO {
+        title,
+        description,
+        priority,
+        category,
+        organisation_id,
+        queue_id,
+        custom_fields,
+    } = ticket.into_inner();
+    let viewer = viewer(&conn, &user).await?;
+
+    let title = title.trim().to_owned();
+    if title.is_empty() {
+        return Ok((
+            Status::BadRequest,
+            Json(Err("title can't be empty".to_owned())),
+        ));
+    }
+
+    let organisation_id = match resolve_organisation(&conn, &viewer, organisation_id).await? {
+        Ok(organisation_id) => organisation_id,
+        Err((status, error)) => return Ok((status, Json(Err(error.to_owned())))),
+    };
+    let queue_id = match resolve_queue(&conn, queue_id).await? {
+        Ok(queue_id) => queue_id,
+        Err((status, error)) => return Ok((status, Json(Err(error.to_owned())))),
+    };
+
+    let (description, category) = (
+        description.to_owned(),
+        category
+            .map(str::trim)
+            .filter(|category| !category.is_empty())
+            .map(ToOwned::to_owned),
+    );
+    let custom_fields =
+        match validate_custom_fields(&conn, &viewer, category.as_deref(), &custom_fields).await? {
+            Ok(custom_fields) => serde_json::to_value(custom_fields).map_err(into_io_err)?,
+            Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+        };
+    let ticket = conn
+        .run(move |c| {
+            db::ticket::insert(
+                c,
+                &viewer,
+                &db::model::NewTicket {
+                    title: &title,
+                    description: &description,
+                    priority: priority.as_str(),
+                    requester_id: viewer.user_id,
+                    organisation_id,
+                    queue_id,
+                    category: category.as_deref(),
+                    custom_fields: Some(custom_fields),
+                },
+            )
+        })
+        .await?;
+    let ticket = opened(&conn, user.id, ticket).await?;
+
+    Ok((Status::Created, Json(Ok(ticket.into()))))
+}
+
+/// List the tickets visible to the user, optionally matching a query
+#[get("/tickets?<q>&<limit>&<offset>")]
+pub async fn list(
+    user: auth::User,
+    conn: db::Connection,
+    q: Option<&str>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<(Status, Json<Vec<TicketDTO>>)> {
+    let viewer = viewer(&conn, &user).await?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+    let tickets = match q {
+        Some(q) => match q.parse::<Query>() {
+            Ok(query) => {
+                let fields = custom_field::definitions(&conn).await?;
+                if check_custom_filters(&fields, &query).is_err() {
+                    return Ok((Status::BadRequest, Json(Vec::new())));
+                }
+                conn.run(move |c| {
+                    db::ticket::query::get_matching(c, &viewer, &query, Utc::now(), limit, offset)
+                })
+                .await?
+            }
+            Err(_) => return Ok((Status::BadRequest, Json(Vec::new()))),
+        },
+        None => {
+            conn.run(move |c| db::ticket::get_visible(c, &viewer, limit, offset))
+                .await?
+        }
+    };
+
+    Ok((
+        Status::Ok,
+        Json(tickets.into_iter().map(Into::into).collect()),
+    ))
+}
+
+/// Get a ticket
+#[get("/tickets/<id>")]
+pub async fn get(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<TicketDTO>>)> {
+    let viewer = viewer(&conn, &user).await?;
+    let ticket = conn
+        .run(move |c| db::ticket::get_with_id(c, &viewer, id))
+        .await?;
+
+    // Tickets from other organisations are reported as missing, to not leak their existence
+    Ok(match ticket {
+        Some(ticket) => (Status::Ok, Json(Some(ticket.into()))),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Update the title, status, priority, assignee, queue or custom fields of a ticket
+#[patch("/tickets/<id>", format = "json", data = "<update>")]
+pub async fn update(
+    agent: auth::Agent,
+    conn: db::Connection,
+    ctx: audit::Context,
+    id: Uuid,
+    update: Json<TicketUpdateDTO>,
+) -> io::Result<(Status, Json<Result<TicketDTO, String>>)> {
+    let update = update.into_inner();
+    let viewer = viewer(&conn, &agent).await?;
+
+    if matches!(&update.title, Some(title) if title.trim().is_empty()) {
+        return Ok((
+            Status::BadRequest,
+            Json(Err("title can't be empty".to_owned())),
+        ));
+    }
+
+    if let Some(Some(assignee_id)) = update.assignee_id {
+        let assignee = conn
+            .run(move |c| db::user::get_with_id(c, assignee_id))
+            .await?;
+        if !matches!(assignee, Some(assignee) if assignee.role().is_staff()) {
+            return Ok((
+                Status::BadRequest,
+                Json(Err("assignee must be an agent".to_owned())),
+            ));
+        }
+    }
+
+    if let Some(Some(queue_id)) = update.queue_id {
+        if conn
+            .run(move |c| db::queue::get_with_id(c, queue_id))
+            .await?
+            .is_none()
+        {
+            return Ok((Status::BadRequest, Json(Err("queue not found".to_owned()))));
+        }
+    }
+
+    let viewer_clone = viewer.clone();
+    let before = match conn
+        .run(move |c| db::ticket::get_with_id(c, &viewer_clone, id))
+        .await?
+    {
+        Some(ticket) => ticket,
+        None => return Ok((Status::NotFound, Json(Err("ticket not found".to_owned())))),
+    };
+
+    if let Some(status) = update.status {
+        let before_clone = before.clone();
+        if let Err(e) = conn
+            .run(move |c| crate::ticket::check_status(c, &before_clone, status))
+            .await?
+        {
+            return Ok((Status::BadRequest, Json(Err(e.to_owned()))));
+        }
+    }
+
+    // Tickets can't be moved to a board column that reached its limit of work in progress
+    if update.status.is_some() || update.assignee_id.is_some() {
+        let (before_clone, update_clone) = (before.clone(), update.clone());
+        if let Err(e) = conn
+            .run(move |c| crate::ticket::check_wip_limits(c, &before_clone, &update_clone))
+            .await?
+        {
+            return Ok((Status::Conflict, Json(Err(e))));
+        }
+    }
+
+    // Values are checked against the fields of the new category, dropping the ones it doesn't
+    // have when only the category changes
+    let custom_fields = if update.custom_fields.is_some() || update.category.is_some() {
+        let category = match &update.category {
+            Some(category) => category
+                .as_deref()
+                .map(str::trim)
+                .filter(|category| !category.is_empty()),
+            None => before.category.as_deref(),
+        };
+        let values = match &update.custom_fields {
+            Some(values) => values.clone(),
+            None => {
+                let fields = custom_field::definitions(&conn).await?;
+                let mut values = before.custom_fields();
+                values.retain(|key, _| {
+                    fields
+                        .iter()
+                        .any(|field| field.key == *key && field.applies_to(category))
+                });
+                values
+            }
+        };
+        match validate_custom_fields(&conn, &viewer, category, &values).await? {
+            Ok(values) => Some(serde_json::to_value(values).map_err(into_io_err)?),
+            Err(e) => return Ok((Status::BadRequest, Json(Err(e)))),
+        }
+    } else {
+        None
+    };
+
+    let update_clone = update.clone();
+    let after = conn
+        .run(move |c| {
+            let title = update_clone.title.as_deref().map(str::trim);
+            let category = update_clone.category.as_ref().map(|category| {
+                category
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|category| !category.is_empty())
+            });
+            let tags = update_clone.tags.map(common::normalize_labels);
+            db::ticket::update(
+                c,
+                &viewer,
+                id,
+                &db::model::TicketChanges {
+                    title,
+                    status: update_clone.status.map(|status| status.as_str()),
+                    priority: update_clone.priority.map(|priority| priority.as_str()),
+                    assignee_id: update_clone.assignee_id,
+                    queue_id: update_clone.queue_id,
+                    category,
+                    tags: tags.as_deref(),
+                    custom_fields,
+                },
+            )
+        })
+        .await?
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "ticket disappeared while updating it",
+            )
+        })?;
+
+    let after = if after.assignee_id != before.assignee_id {
+        let (ticket, previous_assignee_id, assigned_by) =
+            (after.clone(), before.assignee_id, agent.id);
+        conn.run(move |c| {
+            db::assignment::record_change(c, &ticket, previous_assignee_id, Some(assigned_by))
+        })
+        .await?;
+        after
+    } else if update.assignee_id.is_none()
+        && after.queue_id.is_some()
+        && after.queue_id != before.queue_id
+    {
+        // Tickets moved to a queue without an explicit assignee follow its strategy
+        conn.run(move |c| db::assignment::auto_assign(c, id))
+            .await?
+            .unwrap_or(after)
+    } else {
+        after
+    };
+
+    let actor_id = agent.id;
+    let (after, effects) = conn
+        .run(move |c| crate::ticket::updated(c, &ctx, actor_id, &before, after))
+        .await?;
+    automation::dispatch(&conn, effects).await?;
+
+    Ok((Status::Ok, Json(Ok(after.into()))))
+}
+
+/// Get the assignment history of a ticket
+#[get("/tickets/<id>/assignments")]
+pub async fn assignments(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<AssignmentDTO>>)> {
+    let viewer = viewer(&conn, &agent).await?;
+    if conn
+        .run(move |c| db::ticket::get_with_id(c, &viewer, id))
+        .await?
+        .is_none()
+    {
+        return Ok((Status::NotFound, Json(Vec::new())));
+    }
+
+    let history = conn
+        .run(move |c| db::assignment::get_history(c, id))
+        .await?;
+
+    Ok((
+        Status::Ok,
+        Json(history.into_iter().map(Into::into).collect()),
+    ))
+}
+
+/// Get the comments of a ticket, oldest first
+#[get("/tickets/<id>/comments")]
+pub async fn comments(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<CommentDTO>>)> {
+    let viewer = viewer(&conn, &user).await?;
+    if conn
+        .run(move |c| db::ticket::get_with_id(c, &viewer, id))
+        .await?
+        .is_none()
+    {
+        return Ok((Status::NotFound, Json(Vec::new())));
+    }
+
+    let (comments, attachments) = conn
+        .run(move |c| {
+            Ok::<_, io::Error>((
+                db::ticket::get_comments(c, id)?,
+                db::ticket::get_attachments(c, id)?,
+            ))
+        })
+        .await?;
+
+    let comments = comments
+        .into_iter()
+        .map(|comment| CommentDTO {
+            source: comment.source(),
+            attachments: attachments
+                .iter()
+                .filter(|attachment| attachment.comment_id == Some(comment.id))
+                .cloned()
+                .map(AttachmentDTO::from)
+                .collect(),
+            id: comment.id,
+            ticket_id: comment.ticket_id,
+            author_id: comment.author_id,
+            body: comment.body,
+            created_on: comment.created_on,
+        })
+        .collect();
+
+    Ok((Status::Ok, Json(comments)))
+}
+
+/// Add a comment to a ticket
+#[post("/tickets/<id>/comments", format = "json", data = "<comment>")]
+pub async fn comment(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+    comment: Json<NewCommentDTO<'_>>,
+) -> io::Result<(Status, Json<Result<CommentDTO, &'static str>>)> {
+    let body = comment.body.trim().to_owned();
+    if body.is_empty() {
+        return Ok((Status::BadRequest, Json(Err("body can't be empty"))));
+    }
+
+    let viewer = viewer(&conn, &user).await?;
+    let ticket = match conn
+        .run(move |c| db::ticket::get_with_id(c, &viewer, id))
+        .await?
+    {
+        Some(ticket) => ticket,
+        None => return Ok((Status::NotFound, Json(Err("ticket not found")))),
+    };
+
+    let author_id = user.id;
+    let comment = conn
+        .run(move |c| {
+            let comment = db::ticket::insert_comment(
+                c,
+                &db::model::NewTicketComment {
+                    ticket_id: id,
+                    author_id,
+                    body: &body,
+                    source: CommentSource::Web.as_str(),
+                },
+            )?;
+            centre::ticket_commented(c, &ticket, author_id, &comment.body)?;
+
+            Ok::<_, io::Error>(comment)
+        })
+        .await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(CommentDTO {
+            source: comment.source(),
+            attachments: Vec::new(),
+            id: comment.id,
+            ticket_id: comment.ticket_id,
+            author_id: comment.author_id,
+            body: comment.body,
+            created_on: comment.created_on,
+        })),
+    ))
+}
+
+/// Attachment contents, downloaded as a file.
+#[derive(Responder)]
+pub struct Download {
+    data: Vec<u8>,
+    content_type: ContentType,
+    disposition: Header<'static>,
+}
+
+/// Download an attachment of a ticket
+#[get("/tickets/<id>/attachments/<attachment_id>")]
+pub async fn attachment(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+    attachment_id: Uuid,
+) -> io::Result<Option<Download>> {
+    let viewer = viewer(&conn, &user).await?;
+    if conn
+        .run(move |c| db::ticket::get_with_id(c, &viewer, id))
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    let attachment = conn
+        .run(move |c| db::ticket::get_attachment_with_id(c, id, attachment_id))
+        .await?;
+
+    Ok(attachment.map(|(info, data)| {
+        // Attachments are always downloaded, never rendered, so that they can't run scripts
+        let filename = info
+            .filename
+            .chars()
+            .map(|c| match c {
+                '"' | '\\' => '_',
+                c if c.is_ascii_control() => '_',
+                c => c,
+            })
+            .collect::<String>();
+
+        Download {
+            data,
+            content_type: ContentType::parse_flexible(&info.content_type)
+                .unwrap_or(ContentType::Binary),
+            disposition: Header::new(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        }
+    }))
+}
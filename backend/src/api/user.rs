@@ -0,0 +1,53 @@
+//! User management.
+
+use super::auth;
+use crate::{audit, db};
+use common::{
+    audit::{AuditEvent, RoleDTO},
+    user::UserDTO,
+};
+use rocket::{http::Status, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// Change the role of a user
+#[put("/users/<id>/role", format = "json", data = "<role>")]
+pub async fn role(
+    admin: auth::Admin,
+    conn: db::Connection,
+    ctx: audit::Context,
+    id: Uuid,
+    role: Json<RoleDTO>,
+) -> io::Result<(Status, Json<Option<UserDTO>>)> {
+    let RoleDTO { role } = role.into_inner();
+
+    // Administrators can't remove their own privileges, so that there is always one left
+    if id == admin.id {
+        return Ok((Status::Forbidden, Json(None)));
+    }
+
+    let before = match conn.run(move |c| db::user::get_with_id(c, id)).await? {
+        Some(user) => user.role(),
+        None => return Ok((Status::NotFound, Json(None))),
+    };
+
+    let user = conn
+        .run(move |c| db::user::update_role(c, id, role))
+        .await?
+        .expect("user disappeared while changing its role");
+
+    if before != role {
+        let (actor_id, event) = (
+            admin.id,
+            AuditEvent::RoleChange {
+                user_id: id,
+                before,
+                after: role,
+            },
+        );
+        conn.run(move |c| audit::record(c, &ctx, Some(actor_id), &event))
+            .await?;
+    }
+
+    Ok((Status::Ok, Json(Some(user.into()))))
+}
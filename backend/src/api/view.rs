@@ -0,0 +1,213 @@
+//! Saved ticket views.
+
+use super::{
+    auth, custom_field,
+    ticket::{check_custom_filters, viewer, DEFAULT_LIMIT, MAX_LIMIT},
+};
+use crate::db;
+use chrono::Utc;
+use common::{
+    query::Query,
+    ticket::TicketDTO,
+    user::Role,
+    view::{ViewDTO, ViewFormDTO},
+};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum length of view names, in characters.
+const MAX_NAME_LEN: usize = 100;
+
+/// List the views of the user, along with the shared ones
+#[get("/views")]
+pub async fn list(user: auth::User, conn: db::Connection) -> io::Result<Json<Vec<ViewDTO>>> {
+    let user_id = user.id;
+    let views = conn.run(move |c| db::view::get_listed(c, user_id)).await?;
+
+    Ok(Json(views.into_iter().map(Into::into).collect()))
+}
+
+/// Save a new view
+#[post("/views", format = "json", data = "<view>")]
+pub async fn create(
+    user: auth::User,
+    conn: db::Connection,
+    view: Json<ViewFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<ViewDTO, String>>)> {
+    let (name, query) = match validate_view(&conn, &user, None, &view).await? {
+        Ok(valid) => valid,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let (owner_id, shared) = (user.id, view.shared);
+    let view = conn
+        .run(move |c| {
+            db::view::insert(
+                c,
+                &db::model::TicketViewForm {
+                    owner_id,
+                    name: &name,
+                    query: &query,
+                    shared,
+                },
+            )
+        })
+        .await?;
+
+    Ok((Status::Created, Json(Ok(view.into()))))
+}
+
+/// Update a view
+///
+/// Only its owner can update a view, or an administrator if it's shared.
+#[put("/views/<id>", format = "json", data = "<view>")]
+pub async fn update(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+    view: Json<ViewFormDTO<'_>>,
+) -> io::Result<(Status, Json<Result<ViewDTO, String>>)> {
+    let existing = match get_editable(&conn, &user, id).await? {
+        Some(existing) => existing,
+        None => return Ok((Status::NotFound, Json(Err("view not found".to_owned())))),
+    };
+    let (name, query) = match validate_view(&conn, &user, Some(&existing), &view).await? {
+        Ok(valid) => valid,
+        Err((status, e)) => return Ok((status, Json(Err(e)))),
+    };
+
+    let (owner_id, shared) = (existing.owner_id, view.shared);
+    let view = conn
+        .run(move |c| {
+            db::view::update(
+                c,
+                id,
+                &db::model::TicketViewForm {
+                    owner_id,
+                    name: &name,
+                    query: &query,
+                    shared,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match view {
+        Some(view) => (Status::Ok, Json(Ok(view.into()))),
+        None => (Status::NotFound, Json(Err("view not found".to_owned()))),
+    })
+}
+
+/// Delete a view
+///
+/// Only its owner can delete a view, or an administrator if it's shared.
+#[delete("/views/<id>")]
+pub async fn delete(user: auth::User, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    if get_editable(&conn, &user, id).await?.is_none() {
+        return Ok(Status::NotFound);
+    }
+    let deleted = conn.run(move |c| db::view::delete(c, id)).await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the tickets matching a view
+#[get("/views/<id>/tickets?<limit>&<offset>")]
+pub async fn tickets(
+    user: auth::User,
+    conn: db::Connection,
+    id: Uuid,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<(Status, Json<Vec<TicketDTO>>)> {
+    let user_id = user.id;
+    let view = match conn.run(move |c| db::view::get_with_id(c, id)).await? {
+        Some(view) if view.shared || view.owner_id == user_id => view,
+        _ => return Ok((Status::NotFound, Json(Vec::new()))),
+    };
+    // Views are validated when saved, but the query language could have changed since
+    let query = match view.query.parse::<Query>() {
+        Ok(query) => query,
+        Err(_) => return Ok((Status::UnprocessableEntity, Json(Vec::new()))),
+    };
+
+    let viewer = viewer(&conn, &user).await?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+    let tickets = conn
+        .run(move |c| {
+            db::ticket::query::get_matching(c, &viewer, &query, Utc::now(), limit, offset)
+        })
+        .await?;
+
+    Ok((
+        Status::Ok,
+        Json(tickets.into_iter().map(Into::into).collect()),
+    ))
+}
+
+/// Gets a view that the user can change, if it exists.
+async fn get_editable(
+    conn: &db::Connection,
+    user: &auth::User,
+    id: Uuid,
+) -> io::Result<Option<db::model::TicketView>> {
+    let (user_id, is_admin) = (user.id, user.role() == Role::Admin);
+    let view = conn.run(move |c| db::view::get_with_id(c, id)).await?;
+
+    Ok(view.filter(|view| view.owner_id == user_id || (view.shared && is_admin)))
+}
+
+/// Validates a view, returning its trimmed name and canonical query, or the status and reason
+/// if it's not valid.
+async fn validate_view(
+    conn: &db::Connection,
+    user: &auth::User,
+    existing: Option<&db::model::TicketView>,
+    view: &ViewFormDTO<'_>,
+) -> io::Result<Result<(String, String), (Status, String)>> {
+    let name = view.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok(Err((Status::BadRequest, "name can't be empty".to_owned())));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Ok(Err((Status::BadRequest, "name is too long".to_owned())));
+    }
+    let query = match view.query.parse::<Query>() {
+        Ok(query) => query,
+        Err(e) => return Ok(Err((Status::BadRequest, e.to_string()))),
+    };
+    let fields = custom_field::definitions(conn).await?;
+    if let Err(e) = check_custom_filters(&fields, &query) {
+        return Ok(Err((Status::BadRequest, e)));
+    }
+    let query = query.to_string();
+    if view.shared && !user.role().is_staff() {
+        return Ok(Err((
+            Status::Forbidden,
+            "only agents can share views".to_owned(),
+        )));
+    }
+
+    let (owner_id, except) = match existing {
+        Some(existing) => (existing.owner_id, Some(existing.id)),
+        None => (user.id, None),
+    };
+    let taken_name = name.clone();
+    if conn
+        .run(move |c| db::view::name_taken(c, owner_id, &taken_name, except))
+        .await?
+    {
+        return Ok(Err((
+            Status::Conflict,
+            "name belongs to another view".to_owned(),
+        )));
+    }
+
+    Ok(Ok((name, query)))
+}
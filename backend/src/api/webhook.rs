@@ -0,0 +1,210 @@
+//! Outbound webhook subscriptions.
+
+use super::auth;
+use crate::{db, rand_code};
+use common::webhook::{DeliveryDTO, SubscriptionDTO, SubscriptionFormDTO};
+use rocket::{delete, get, http::Status, post, put, serde::json::Json};
+use std::io;
+use uuid::Uuid;
+
+/// Maximum length of the URL of a subscription.
+const MAX_URL_LEN: usize = 2048;
+
+/// Minimum length of the secret of a subscription.
+const MIN_SECRET_LEN: usize = 16;
+
+/// Maximum length of the secret of a subscription.
+const MAX_SECRET_LEN: usize = 128;
+
+/// Length of the generated secrets.
+const GENERATED_SECRET_LEN: usize = 32;
+
+/// Maximum number of deliveries returned in a single query.
+const MAX_LIMIT: i64 = 500;
+
+/// Default number of deliveries returned in a single query.
+const DEFAULT_LIMIT: i64 = 50;
+
+/// List all the webhook subscriptions
+#[get("/webhooks")]
+pub async fn list(
+    _admin: auth::Admin,
+    conn: db::Connection,
+) -> io::Result<Json<Vec<SubscriptionDTO>>> {
+    let subscriptions = conn.run(db::webhook::get_subscriptions).await?;
+
+    Ok(Json(subscriptions.into_iter().map(Into::into).collect()))
+}
+
+/// Get a webhook subscription
+#[get("/webhooks/<id>")]
+pub async fn get(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Option<SubscriptionDTO>>)> {
+    let subscription = conn
+        .run(move |c| db::webhook::get_subscription_with_id(c, id))
+        .await?;
+
+    Ok(match subscription {
+        Some(subscription) => (Status::Ok, Json(Some(subscription.into()))),
+        None => (Status::NotFound, Json(None)),
+    })
+}
+
+/// Create a new webhook subscription
+///
+/// The response includes the secret used to sign the payloads, which is not sent again.
+#[post("/webhooks", format = "json", data = "<subscription>")]
+pub async fn create(
+    admin: auth::Admin,
+    conn: db::Connection,
+    subscription: Json<SubscriptionFormDTO>,
+) -> io::Result<(Status, Json<Result<SubscriptionDTO, &'static str>>)> {
+    let mut form = subscription.into_inner();
+    if let Err(e) = validate_form(&mut form) {
+        return Ok((Status::BadRequest, Json(Err(e))));
+    }
+
+    let secret = form
+        .secret
+        .take()
+        .unwrap_or_else(|| rand_code(GENERATED_SECRET_LEN));
+    let (created_by, secret_clone) = (admin.id, secret.clone());
+    let subscription = conn
+        .run(move |c| {
+            let events = form
+                .events
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            db::webhook::insert_subscription(
+                c,
+                &db::model::WebhookSubscriptionForm {
+                    url: &form.url,
+                    events: &events,
+                    secret: Some(&secret_clone),
+                    enabled: form.enabled,
+                },
+                created_by,
+            )
+        })
+        .await?;
+
+    Ok((
+        Status::Created,
+        Json(Ok(SubscriptionDTO {
+            secret: Some(secret),
+            ..subscription.into()
+        })),
+    ))
+}
+
+/// Update a webhook subscription
+///
+/// The secret is only changed if provided.
+#[put("/webhooks/<id>", format = "json", data = "<subscription>")]
+pub async fn update(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    subscription: Json<SubscriptionFormDTO>,
+) -> io::Result<(Status, Json<Result<SubscriptionDTO, &'static str>>)> {
+    let mut form = subscription.into_inner();
+    if let Err(e) = validate_form(&mut form) {
+        return Ok((Status::BadRequest, Json(Err(e))));
+    }
+
+    let subscription = conn
+        .run(move |c| {
+            let events = form
+                .events
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            db::webhook::update_subscription(
+                c,
+                id,
+                &db::model::WebhookSubscriptionForm {
+                    url: &form.url,
+                    events: &events,
+                    secret: form.secret.as_deref(),
+                    enabled: form.enabled,
+                },
+            )
+        })
+        .await?;
+
+    Ok(match subscription {
+        Some(subscription) => (Status::Ok, Json(Ok(subscription.into()))),
+        None => (Status::NotFound, Json(Err("subscription not found"))),
+    })
+}
+
+/// Validates and normalizes a subscription form.
+fn validate_form(form: &mut SubscriptionFormDTO) -> Result<(), &'static str> {
+    form.url = form.url.trim().to_owned();
+    let host = form
+        .url
+        .strip_prefix("https://")
+        .or_else(|| form.url.strip_prefix("http://"))
+        .ok_or("the URL must use HTTP or HTTPS")?;
+    if host.is_empty() || host.starts_with('/') || form.url.len() > MAX_URL_LEN {
+        return Err("invalid URL");
+    }
+
+    if let Some(secret) = &form.secret {
+        if !(MIN_SECRET_LEN..=MAX_SECRET_LEN).contains(&secret.len()) {
+            return Err("the secret must have between 16 and 128 characters");
+        }
+    }
+
+    form.events.sort_unstable();
+    form.events.dedup();
+
+    Ok(())
+}
+
+/// Delete a webhook subscription, along with its deliveries
+#[delete("/webhooks/<id>")]
+pub async fn delete(_admin: auth::Admin, conn: db::Connection, id: Uuid) -> io::Result<Status> {
+    let deleted = conn
+        .run(move |c| db::webhook::delete_subscription(c, id))
+        .await?;
+
+    Ok(if deleted {
+        Status::NoContent
+    } else {
+        Status::NotFound
+    })
+}
+
+/// List the deliveries of a webhook subscription, newest first
+#[get("/webhooks/<id>/deliveries?<limit>&<offset>")]
+pub async fn deliveries(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> io::Result<(Status, Json<Vec<DeliveryDTO>>)> {
+    if conn
+        .run(move |c| db::webhook::get_subscription_with_id(c, id))
+        .await?
+        .is_none()
+    {
+        return Ok((Status::NotFound, Json(Vec::new())));
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0).max(0);
+    let deliveries = conn
+        .run(move |c| db::webhook::get_deliveries(c, id, limit, offset))
+        .await?;
+
+    Ok((
+        Status::Ok,
+        Json(deliveries.into_iter().map(Into::into).collect()),
+    ))
+}
@@ -0,0 +1,353 @@
+//! Time tracking.
+//!
+//! Agents log their work on tickets, and can correct or remove their own entries, administrators
+//! those of anyone. Administrators manage the contracts of organisations, whose balance is the
+//! prepaid time left once the billable work on their tickets during the contract is taken off.
+
+use super::{auth, parse_date, ticket};
+use crate::{csv, db};
+use chrono::Utc;
+use common::{
+    user::Role,
+    work_log::{ContractDTO, ContractFormDTO, Grouping, TimeTotalDTO, WorkLogDTO, WorkLogFormDTO},
+};
+use rocket::{
+    delete, get,
+    http::{ContentType, Header, Status},
+    post, put,
+    serde::json::Json,
+    Responder,
+};
+use std::io;
+use uuid::Uuid;
+
+/// Header of the CSV export of the work logs.
+const EXPORT_HEADER: [&str; 8] = [
+    "worked_on",
+    "ticket",
+    "organisation",
+    "agent",
+    "activity",
+    "minutes",
+    "billable",
+    "description",
+];
+
+/// List the work logged on a ticket, by time of work
+#[get("/tickets/<id>/work-logs")]
+pub async fn work_logs(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<WorkLogDTO>>)> {
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let logs = conn
+        .run(move |c| {
+            if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::work_log::get_for_ticket(c, id).map(Some)
+        })
+        .await?;
+
+    Ok(match logs {
+        Some(logs) => (
+            Status::Ok,
+            Json(logs.into_iter().map(WorkLogDTO::from).collect()),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Log work on a ticket
+#[post("/tickets/<id>/work-logs", format = "json", data = "<log>")]
+pub async fn log_work(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    log: Json<WorkLogFormDTO>,
+) -> io::Result<(Status, Json<Result<WorkLogDTO, &'static str>>)> {
+    if let Err(e) = log.check() {
+        return Ok((Status::BadRequest, Json(Err(e))));
+    }
+    let viewer = ticket::viewer(&conn, &agent).await?;
+    let agent_id = agent.id;
+
+    conn.run(move |c| {
+        if db::ticket::get_with_id(c, &viewer, id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("ticket not found"))));
+        }
+
+        let log = db::work_log::insert(c, id, agent_id, &form(&log))?;
+        Ok((Status::Created, Json(Ok(log.into()))))
+    })
+    .await
+}
+
+/// Correct a work log entry
+///
+/// Agents can only correct their own entries, administrators those of anyone.
+#[put("/work-logs/<id>", format = "json", data = "<log>")]
+pub async fn update_work_log(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+    log: Json<WorkLogFormDTO>,
+) -> io::Result<(Status, Json<Result<WorkLogDTO, &'static str>>)> {
+    if let Err(e) = log.check() {
+        return Ok((Status::BadRequest, Json(Err(e))));
+    }
+    let (agent_id, is_admin) = (agent.id, agent.role() == Role::Admin);
+
+    conn.run(move |c| {
+        match db::work_log::get_with_id(c, id)? {
+            Some(existing) if existing.agent_id == agent_id || is_admin => {}
+            Some(_) => {
+                return Ok((
+                    Status::Forbidden,
+                    Json(Err("only the author can correct a work log")),
+                ))
+            }
+            None => return Ok((Status::NotFound, Json(Err("work log not found")))),
+        }
+
+        Ok(match db::work_log::update(c, id, &form(&log))? {
+            Some(log) => (Status::Ok, Json(Ok(log.into()))),
+            None => (Status::NotFound, Json(Err("work log not found"))),
+        })
+    })
+    .await
+}
+
+/// Remove a work log entry
+///
+/// Agents can only remove their own entries, administrators those of anyone.
+#[delete("/work-logs/<id>")]
+pub async fn delete_work_log(
+    agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<Status> {
+    let (agent_id, is_admin) = (agent.id, agent.role() == Role::Admin);
+
+    conn.run(move |c| match db::work_log::get_with_id(c, id)? {
+        Some(existing) if existing.agent_id == agent_id || is_admin => {
+            Ok(if db::work_log::delete(c, id)? {
+                Status::NoContent
+            } else {
+                Status::NotFound
+            })
+        }
+        Some(_) => Ok(Status::Forbidden),
+        None => Ok(Status::NotFound),
+    })
+    .await
+}
+
+/// Total the time logged in a period, per organisation or agent
+///
+/// The period is given as RFC 3339 timestamps, and is unbounded on missing sides. Totals are per
+/// organisation by default.
+#[get("/work-logs/totals?<by>&<from>&<to>")]
+pub async fn totals(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    by: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> io::Result<(Status, Json<Result<Vec<TimeTotalDTO>, &'static str>>)> {
+    let grouping = match by.map(str::parse).transpose() {
+        Ok(grouping) => grouping.unwrap_or(Grouping::Organisation),
+        Err(_) => return Ok((Status::BadRequest, Json(Err("unknown grouping")))),
+    };
+    let (from, to) = match (parse_date(from), parse_date(to)) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => return Ok((Status::BadRequest, Json(Err("invalid date")))),
+    };
+
+    let totals = conn
+        .run(move |c| db::work_log::get_totals(c, grouping, from, to))
+        .await?;
+
+    Ok((
+        Status::Ok,
+        Json(Ok(totals.into_iter().map(TimeTotalDTO::from).collect())),
+    ))
+}
+
+/// Work logs, downloaded as a CSV file.
+#[derive(Responder)]
+pub struct Export {
+    data: String,
+    content_type: ContentType,
+    disposition: Header<'static>,
+}
+
+/// Export the work logged in a period as CSV, optionally for an organisation or by an agent
+///
+/// The period is given as RFC 3339 timestamps, and is unbounded on missing sides.
+#[get("/work-logs/export.csv?<from>&<to>&<organisation_id>&<agent_id>")]
+pub async fn export(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    from: Option<&str>,
+    to: Option<&str>,
+    organisation_id: Option<Uuid>,
+    agent_id: Option<Uuid>,
+) -> io::Result<Result<Export, Status>> {
+    let (from, to) = match (parse_date(from), parse_date(to)) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => return Ok(Err(Status::BadRequest)),
+    };
+
+    let rows = conn
+        .run(move |c| db::work_log::get_export(c, from, to, organisation_id, agent_id))
+        .await?;
+
+    let mut data = String::new();
+    csv::write_record(&mut data, EXPORT_HEADER);
+    for row in rows {
+        csv::write_record(
+            &mut data,
+            [
+                row.log.worked_on.to_rfc3339(),
+                row.ticket_number.to_string(),
+                row.organisation.unwrap_or_default(),
+                row.agent,
+                row.log.activity().to_string(),
+                row.log.minutes.to_string(),
+                row.log.billable.to_string(),
+                row.log.description,
+            ],
+        );
+    }
+
+    Ok(Ok(Export {
+        data,
+        content_type: ContentType::CSV,
+        disposition: Header::new(
+            "Content-Disposition",
+            "attachment; filename=\"work-logs.csv\"",
+        ),
+    }))
+}
+
+/// List the contracts of an organisation with their balance, newest first
+#[get("/organisations/<id>/contracts")]
+pub async fn contracts(
+    _agent: auth::Agent,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<(Status, Json<Vec<ContractDTO>>)> {
+    let contracts = conn
+        .run(move |c| {
+            if db::organisation::get_with_id(c, id)?.is_none() {
+                return Ok(None);
+            }
+
+            db::work_log::get_contracts(c, id).map(Some)
+        })
+        .await?;
+
+    Ok(match contracts {
+        Some(contracts) => (
+            Status::Ok,
+            Json(
+                contracts
+                    .into_iter()
+                    .map(|(contract, used)| contract.into_dto(used))
+                    .collect(),
+            ),
+        ),
+        None => (Status::NotFound, Json(Vec::new())),
+    })
+}
+
+/// Create a contract for an organisation
+#[post("/organisations/<id>/contracts", format = "json", data = "<contract>")]
+pub async fn create_contract(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    contract: Json<ContractFormDTO>,
+) -> io::Result<(Status, Json<Result<ContractDTO, &'static str>>)> {
+    if let Err(e) = contract.check() {
+        return Ok((Status::BadRequest, Json(Err(e))));
+    }
+
+    conn.run(move |c| {
+        if db::organisation::get_with_id(c, id)?.is_none() {
+            return Ok((Status::NotFound, Json(Err("organisation not found"))));
+        }
+
+        let contract = db::work_log::insert_contract(c, id, &contract_form(&contract))?;
+        let used = db::work_log::get_used_minutes(c, &contract)?;
+        Ok((Status::Created, Json(Ok(contract.into_dto(used)))))
+    })
+    .await
+}
+
+/// Update a contract
+#[put("/contracts/<id>", format = "json", data = "<contract>")]
+pub async fn update_contract(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+    contract: Json<ContractFormDTO>,
+) -> io::Result<(Status, Json<Result<ContractDTO, &'static str>>)> {
+    if let Err(e) = contract.check() {
+        return Ok((Status::BadRequest, Json(Err(e))));
+    }
+
+    conn.run(
+        move |c| match db::work_log::update_contract(c, id, &contract_form(&contract))? {
+            Some(contract) => {
+                let used = db::work_log::get_used_minutes(c, &contract)?;
+                Ok((Status::Ok, Json(Ok(contract.into_dto(used)))))
+            }
+            None => Ok((Status::NotFound, Json(Err("contract not found")))),
+        },
+    )
+    .await
+}
+
+/// Delete a contract
+#[delete("/contracts/<id>")]
+pub async fn delete_contract(
+    _admin: auth::Admin,
+    conn: db::Connection,
+    id: Uuid,
+) -> io::Result<Status> {
+    Ok(
+        if conn
+            .run(move |c| db::work_log::delete_contract(c, id))
+            .await?
+        {
+            Status::NoContent
+        } else {
+            Status::NotFound
+        },
+    )
+}
+
+/// Gets the database form of a checked work log entry, done now unless stated otherwise.
+fn form(log: &WorkLogFormDTO) -> db::model::WorkLogForm<'_> {
+    db::model::WorkLogForm {
+        minutes: log.minutes,
+        billable: log.billable,
+        activity: log.activity.as_str(),
+        description: &log.description,
+        worked_on: log.worked_on.unwrap_or_else(Utc::now),
+    }
+}
+
+/// Gets the database form of a checked contract.
+fn contract_form(contract: &ContractFormDTO) -> db::model::ContractForm<'_> {
+    db::model::ContractForm {
+        name: contract.name.trim(),
+        prepaid_minutes: contract.prepaid_minutes,
+        starts_on: contract.starts_on,
+        ends_on: contract.ends_on,
+    }
+}
@@ -0,0 +1,182 @@
+//! Approval workflows.
+//!
+//! Tickets that need an approval wait in the pending status while their approval chain runs.
+//! The approvers of each step are notified when it's reached, with a link to decide from the
+//! notification email, and every decision is audited. Tickets are opened once the last step
+//! passes, and closed as soon as a step is rejected.
+
+use crate::{
+    audit,
+    db::{self, model, tenant::Viewer},
+    notification::centre,
+    ticket, BASE_URL,
+};
+use common::{
+    approval::{ApprovalStepDTO, Approver, Decision, Status, StepDefinition},
+    audit::AuditEvent,
+    catalog::{Answers, Value},
+    notification::Kind,
+    ticket::Status as TicketStatus,
+};
+use diesel::PgConnection;
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Resolves the approvers of the steps of a chain, given the answers of the request form.
+///
+/// Steps without approvers, because no user was picked in their fields, are dropped.
+pub fn resolve(steps: &[StepDefinition], answers: &Answers) -> Vec<ApprovalStepDTO> {
+    steps
+        .iter()
+        .filter_map(|step| {
+            let mut approver_ids = Vec::with_capacity(step.approvers.len());
+            for approver in &step.approvers {
+                let id = match approver {
+                    Approver::User { id } => Some(*id),
+                    Approver::Field { key } => match answers.get(key) {
+                        Some(Value::Text(id)) => id.parse().ok(),
+                        _ => None,
+                    },
+                };
+                if let Some(id) = id.filter(|id| !approver_ids.contains(id)) {
+                    approver_ids.push(id);
+                }
+            }
+
+            (!approver_ids.is_empty()).then(|| ApprovalStepDTO {
+                mode: step.mode,
+                approver_ids,
+            })
+        })
+        .collect()
+}
+
+/// Starts the approval of a new ticket, returning the ticket updated.
+///
+/// Tickets whose chain has no approvers left once resolved don't need an approval.
+pub fn start(
+    conn: &mut PgConnection,
+    ctx: &audit::Context,
+    ticket: model::Ticket,
+    steps: &[StepDefinition],
+    answers: &Answers,
+) -> io::Result<model::Ticket> {
+    let steps = resolve(steps, answers);
+    if steps.is_empty() {
+        return Ok(ticket);
+    }
+
+    let approvers = db::approval::start(conn, ticket.id, &steps)?;
+    request(conn, &ticket, &approvers)?;
+
+    set_status(conn, ctx, ticket, TicketStatus::Pending)
+}
+
+/// Records the decision of an approver on a ticket, moving the ticket on if the approval finishes.
+///
+/// Returns the status of the approval after the decision, or `None` if the approval is not
+/// waiting for a decision of the approver.
+pub fn decide(
+    conn: &mut PgConnection,
+    ctx: &audit::Context,
+    ticket_id: Uuid,
+    approver_id: Uuid,
+    decision: Decision,
+    comment: &str,
+    by_email: bool,
+) -> io::Result<Option<Status>> {
+    let decided =
+        match db::approval::decide(conn, ticket_id, approver_id, decision, comment.trim())? {
+            Some(decided) => decided,
+            None => return Ok(None),
+        };
+    let event = AuditEvent::ApprovalDecision {
+        ticket_id,
+        step: decided.step,
+        approver_id,
+        decision,
+        by_email,
+    };
+    audit::record(conn, ctx, Some(approver_id), &event)?;
+
+    let ticket = db::ticket::get_with_id(conn, &Viewer::system(), ticket_id)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "approved ticket disappeared"))?;
+    request(conn, &ticket, &decided.next_approvers)?;
+    match decided.status {
+        Status::Pending => {}
+        Status::Approved => {
+            let _ = set_status(conn, ctx, ticket, TicketStatus::Open)?;
+        }
+        Status::Rejected => {
+            let _ = set_status(conn, ctx, ticket, TicketStatus::Closed)?;
+        }
+    }
+
+    Ok(Some(decided.status))
+}
+
+/// Asks the approvers of a step to decide on a ticket.
+///
+/// Every approver gets the link with their own token.
+fn request(
+    conn: &mut PgConnection,
+    ticket: &model::Ticket,
+    approvers: &[model::TicketApprover],
+) -> io::Result<()> {
+    for approver in approvers {
+        let body = format!(
+            "{}\n\nApprove or reject it at {}/approvals/{}",
+            ticket.title, *BASE_URL, approver.token
+        );
+        centre::notify(
+            conn,
+            &[approver.user_id],
+            Kind::ApprovalRequested,
+            &format!("Approval requested for ticket #{}", ticket.number),
+            &body,
+            Some(ticket.id),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Changes the status of a ticket on behalf of the approval, auditing the change and notifying
+/// the requester.
+///
+/// Rejected tickets with unfinished child tickets stay open, like when agents close them.
+fn set_status(
+    conn: &mut PgConnection,
+    ctx: &audit::Context,
+    ticket: model::Ticket,
+    status: TicketStatus,
+) -> io::Result<model::Ticket> {
+    if ticket.status() == status || ticket::check_status(conn, &ticket, status)?.is_err() {
+        return Ok(ticket);
+    }
+
+    let after = db::ticket::update(
+        conn,
+        &Viewer::system(),
+        ticket.id,
+        &model::TicketChanges {
+            status: Some(status.as_str()),
+            ..Default::default()
+        },
+    )?
+    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "approved ticket disappeared"))?;
+
+    let event = AuditEvent::TicketFieldChange {
+        ticket_id: ticket.id,
+        field: "status".to_owned(),
+        before: Some(ticket.status),
+        after: Some(after.status.clone()),
+    };
+    audit::record(conn, ctx, None, &event)?;
+    centre::ticket_status_changed(conn, &after, None)?;
+
+    Ok(after)
+}
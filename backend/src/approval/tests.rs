@@ -0,0 +1,169 @@
+use super::*;
+use crate::db::{establish_connection, fixtures::user_id};
+use common::approval::Mode;
+use diesel::Connection;
+
+/// Builds the context of a test request.
+fn context() -> audit::Context {
+    audit::Context {
+        ip_address: None,
+        user_agent: None,
+        request_id: "ut-approval".to_owned(),
+    }
+}
+
+/// Sunny day unit test for the `resolve()` function.
+#[test]
+fn ut_sunny_resolve() {
+    let (admin, manager) = (Uuid::new_v4(), Uuid::new_v4());
+    let steps = [
+        StepDefinition {
+            mode: Mode::Any,
+            approvers: vec![
+                Approver::Field {
+                    key: "manager".to_owned(),
+                },
+                Approver::User { id: admin },
+            ],
+        },
+        StepDefinition {
+            mode: Mode::All,
+            approvers: vec![Approver::User { id: admin }],
+        },
+    ];
+    let mut answers = Answers::new();
+    let _ = answers.insert("manager".to_owned(), Value::Text(manager.to_string()));
+
+    assert_eq!(
+        resolve(&steps, &answers),
+        vec![
+            ApprovalStepDTO {
+                mode: Mode::Any,
+                approver_ids: vec![manager, admin],
+            },
+            ApprovalStepDTO {
+                mode: Mode::All,
+                approver_ids: vec![admin],
+            },
+        ]
+    );
+
+    // The same user is not asked twice in a step
+    let _ = answers.insert("manager".to_owned(), Value::Text(admin.to_string()));
+    assert_eq!(resolve(&steps, &answers)[0].approver_ids, vec![admin]);
+}
+
+/// Rainy day unit test for the `resolve()` function.
+#[test]
+fn ut_rainy_resolve() {
+    let steps = [StepDefinition {
+        mode: Mode::Any,
+        approvers: vec![Approver::Field {
+            key: "manager".to_owned(),
+        }],
+    }];
+
+    assert!(resolve(&steps, &Answers::new()).is_empty());
+    let mut answers = Answers::new();
+    let _ = answers.insert("manager".to_owned(), Value::Text("nobody".to_owned()));
+    assert!(resolve(&steps, &answers).is_empty());
+}
+
+/// Sunny day unit test for a ticket going through its approval.
+#[test]
+fn ut_sunny_start_decide() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (alice, bob) = (user_id(&mut conn, "alice"), user_id(&mut conn, "bob"));
+    let carol = user_id(&mut conn, "carol");
+    let ticket = db::ticket::insert(
+        &mut conn,
+        &Viewer::system(),
+        &model::NewTicket {
+            title: "UT approval monitor",
+            description: "",
+            priority: "normal",
+            requester_id: carol,
+            organisation_id: None,
+            queue_id: None,
+            category: None,
+            custom_fields: None,
+        },
+    )
+    .expect("error inserting ticket");
+    let steps = [
+        StepDefinition {
+            mode: Mode::Any,
+            approvers: vec![Approver::Field {
+                key: "manager".to_owned(),
+            }],
+        },
+        StepDefinition {
+            mode: Mode::Any,
+            approvers: vec![Approver::User { id: alice }],
+        },
+    ];
+    let mut answers = Answers::new();
+    let _ = answers.insert("manager".to_owned(), Value::Text(bob.to_string()));
+
+    let ticket =
+        start(&mut conn, &context(), ticket, &steps, &answers).expect("error starting approval");
+    assert_eq!(ticket.status(), TicketStatus::Pending);
+    let notified = |conn: &mut PgConnection, user_id| {
+        db::notification::get_for_user(conn, user_id, false, 50, 0)
+            .expect("error retrieving notifications")
+            .into_iter()
+            .any(|n| n.ticket_id == Some(ticket.id) && n.kind == "approval.requested")
+    };
+    assert!(notified(&mut conn, bob), "Bob was not asked to approve");
+    assert!(
+        !notified(&mut conn, alice),
+        "Alice was asked before her step"
+    );
+
+    assert_eq!(
+        decide(
+            &mut conn,
+            &context(),
+            ticket.id,
+            bob,
+            Decision::Approved,
+            "",
+            false
+        )
+        .expect("error deciding"),
+        Some(Status::Pending)
+    );
+    assert!(notified(&mut conn, alice), "Alice was not asked to approve");
+    assert_eq!(
+        decide(
+            &mut conn,
+            &context(),
+            ticket.id,
+            alice,
+            Decision::Approved,
+            "",
+            true
+        )
+        .expect("error deciding"),
+        Some(Status::Approved)
+    );
+    let ticket = db::ticket::get_with_id(&mut conn, &Viewer::system(), ticket.id)
+        .expect("error retrieving ticket")
+        .expect("ticket was not found");
+    assert_eq!(ticket.status(), TicketStatus::Open);
+    assert_eq!(
+        decide(
+            &mut conn,
+            &context(),
+            ticket.id,
+            alice,
+            Decision::Rejected,
+            "",
+            false
+        )
+        .expect("error deciding"),
+        None
+    );
+}
@@ -0,0 +1,76 @@
+//! Audit log of security-relevant and ticket events.
+//!
+//! Events are stored in the append-only `sys_audit_log` table, along with information about the
+//! request that generated them: the user performing the action, the client IP address and user
+//! agent, and the request ID.
+
+use crate::db;
+use common::audit::AuditEvent;
+use diesel::PgConnection;
+use rocket::{
+    outcome::Outcome,
+    request::{self, FromRequest, Request},
+};
+use std::{convert::Infallible, io};
+use uuid::Uuid;
+
+/// Header that clients or proxies can use to provide the ID of the request.
+const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Maximum length of the request ID.
+const REQUEST_ID_MAX_LEN: usize = 64;
+
+/// Context of the request that generated an event.
+#[derive(Debug, Clone)]
+pub struct Context {
+    /// IP address of the client, if known.
+    pub ip_address: Option<String>,
+    /// User agent of the client, if known.
+    pub user_agent: Option<String>,
+    /// ID of the request.
+    ///
+    /// It is taken from the `X-Request-Id` header if present, or randomly generated otherwise.
+    pub request_id: String,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Context {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let request_id = req
+            .headers()
+            .get_one(REQUEST_ID_HEADER)
+            .filter(|id| !id.is_empty() && id.len() <= REQUEST_ID_MAX_LEN)
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        Outcome::Success(Self {
+            ip_address: req.client_ip().map(|ip| ip.to_string()),
+            user_agent: req.headers().get_one("User-Agent").map(ToOwned::to_owned),
+            request_id,
+        })
+    }
+}
+
+/// Records an event in the audit log.
+pub fn record(
+    conn: &mut PgConnection,
+    ctx: &Context,
+    actor_id: Option<Uuid>,
+    event: &AuditEvent,
+) -> io::Result<()> {
+    let details = serde_json::to_value(event)?;
+
+    db::audit::insert(
+        conn,
+        &db::model::NewAuditEntry {
+            kind: event.kind(),
+            actor_id,
+            ip_address: ctx.ip_address.as_deref(),
+            user_agent: ctx.user_agent.as_deref(),
+            request_id: &ctx.request_id,
+            details,
+        },
+    )
+}
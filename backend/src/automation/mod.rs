@@ -0,0 +1,337 @@
+//! Automation engine.
+//!
+//! Automation rules are evaluated in order on every ticket event. The actions of the matching
+//! rules change the ticket, which is stored once all rules have been evaluated. Since these changes
+//! are ticket updates themselves, rules are then evaluated again for the update.
+//!
+//! To avoid loops, each rule runs at most once per original event, and cascaded updates stop after
+//! [`MAX_DEPTH`] evaluations.
+//!
+//! Emails and webhooks are not sent during the evaluation, but returned as [`Effect`]s, to be
+//! [dispatched](dispatch) once the ticket has been stored.
+
+use crate::{
+    db::{self, model, tenant::Viewer},
+    notification, ticket,
+};
+use common::{
+    automation::{render_template, Action, Condition, Event},
+    ticket::TicketDTO,
+};
+use diesel::PgConnection;
+use rocket::{error, tokio::task::spawn_blocking};
+use std::{collections::HashSet, io};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of evaluations of the rules for a single ticket event.
+pub const MAX_DEPTH: i16 = 5;
+
+/// Event sent with the webhooks called by automation rules.
+///
+/// Their delivery ID is `automation-<ID of the execution log entry>`, so that it doesn't collide
+/// with the deliveries of [subscriptions](crate::webhook).
+const WEBHOOK_EVENT: &str = "automation";
+
+/// Automation rule, ready to be evaluated.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    /// The ID of the rule.
+    pub id: Uuid,
+    /// The condition of the rule.
+    pub condition: Condition,
+    /// The actions of the rule.
+    pub actions: Vec<Action>,
+}
+
+impl Rule {
+    /// Evaluates the rule for an event, applying its actions to the ticket if it matches.
+    ///
+    /// Returns whether the rule matched, or an error naming the rule if one of its actions is not
+    /// valid, in which case the ticket is left untouched.
+    pub fn evaluate(
+        &self,
+        event: Event,
+        before: Option<&TicketDTO>,
+        ticket: &mut TicketDTO,
+    ) -> io::Result<bool> {
+        if !self.condition.matches(event, before, ticket) {
+            return Ok(false);
+        }
+        if let Some(e) = self
+            .actions
+            .iter()
+            .find_map(|action| action.validate().err())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid action in automation rule {}: {}", self.id, e),
+            ));
+        }
+
+        for action in &self.actions {
+            // The whole ticket is compared once all the rules have run
+            let _changed = action.apply(ticket);
+        }
+
+        Ok(true)
+    }
+}
+
+/// Side effect of an automation action, to be run after the ticket is stored.
+#[derive(Debug, Clone)]
+pub enum Effect {
+    /// Send an email.
+    Email {
+        /// The ID of the execution log entry.
+        log_id: i64,
+        /// The ticket the email is about.
+        thread: notification::email::Thread,
+        to: String,
+        subject: String,
+        body: String,
+    },
+    /// Call the webhook of a subscription.
+    Webhook {
+        /// The ID of the execution log entry.
+        log_id: i64,
+        url: String,
+        /// The secret of the subscription, to sign the body.
+        secret: String,
+        body: String,
+    },
+}
+
+/// Runs the enabled automation rules for an event of a ticket.
+///
+/// The ticket before the event is only available for updates. Returns the ticket after running
+/// the actions of the matching rules, along with their side effects.
+pub fn run(
+    conn: &mut PgConnection,
+    event: Event,
+    before: Option<&model::Ticket>,
+    ticket: model::Ticket,
+) -> io::Result<(model::Ticket, Vec<Effect>)> {
+    let rules = db::automation::get_enabled_rules(conn)?
+        .into_iter()
+        .map(|rule| {
+            Ok(Rule {
+                id: rule.id,
+                condition: serde_json::from_value(rule.condition)?,
+                actions: serde_json::from_value(rule.actions)?,
+            })
+        })
+        .collect::<Result<Vec<_>, serde_json::Error>>()?;
+
+    let (mut event, mut before) = (event, before.cloned().map(TicketDTO::from));
+    let mut ticket = ticket;
+    let mut fired = HashSet::new();
+    let mut effects = Vec::new();
+
+    for depth in 0..MAX_DEPTH {
+        let start = TicketDTO::from(ticket.clone());
+        let mut current = start.clone();
+        // Execution log entry of the last rule changing the status
+        let mut status_log_id = None;
+
+        for rule in &rules {
+            if fired.contains(&rule.id) {
+                continue;
+            }
+            let status = current.status;
+            // A broken rule must not stop the other rules, nor the change that triggered it
+            match rule.evaluate(event, before.as_ref(), &mut current) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    error!("{}", e);
+                    continue;
+                }
+            }
+            fired.insert(rule.id);
+
+            let log_id = db::automation::insert_log(
+                conn,
+                &model::NewAutomationLogEntry {
+                    rule_id: rule.id,
+                    ticket_id: ticket.id,
+                    event: event.as_str(),
+                    depth,
+                    actions: serde_json::to_value(&rule.actions)?,
+                },
+            )?;
+            if current.status != status {
+                status_log_id = Some(log_id);
+            }
+            for action in &rule.actions {
+                match action {
+                    Action::SendEmail { to, subject, body } => effects.push(Effect::Email {
+                        log_id,
+                        thread: notification::email::Thread {
+                            ticket_id: current.id,
+                            number: current.number,
+                        },
+                        to: to.clone(),
+                        subject: render_template(subject, &current),
+                        body: render_template(body, &current),
+                    }),
+                    Action::CallWebhook { subscription_id } => {
+                        match db::webhook::get_subscription_with_id(conn, *subscription_id)? {
+                            Some(subscription) if subscription.enabled => {
+                                effects.push(Effect::Webhook {
+                                    log_id,
+                                    url: subscription.url,
+                                    secret: subscription.secret,
+                                    body: serde_json::to_string(&current)?,
+                                });
+                            }
+                            Some(_) => db::automation::set_log_error(
+                                conn,
+                                log_id,
+                                "webhook subscription is disabled",
+                            )?,
+                            None => db::automation::set_log_error(
+                                conn,
+                                log_id,
+                                "webhook subscription not found",
+                            )?,
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Status changes follow the same rules as the ones made by agents
+        if let Some(log_id) = status_log_id {
+            if let Err(e) = ticket::check_status(conn, &ticket, current.status)? {
+                current.status = start.status;
+                db::automation::set_log_error(conn, log_id, e)?;
+            }
+        }
+
+        if current == start {
+            break;
+        }
+
+        ticket = store(conn, &ticket, &current)?;
+        before = Some(start);
+        event = Event::Updated;
+    }
+
+    Ok((ticket, effects))
+}
+
+/// Stores the changes made by automation rules to a ticket.
+///
+/// Tickets moved to a new queue without a new assignee are assigned following the strategy of
+/// the queue.
+fn store(
+    conn: &mut PgConnection,
+    ticket: &model::Ticket,
+    changed: &TicketDTO,
+) -> io::Result<model::Ticket> {
+    let updated = db::ticket::update(
+        conn,
+        &Viewer::system(),
+        ticket.id,
+        &model::TicketChanges {
+            title: Some(&changed.title),
+            status: Some(changed.status.as_str()),
+            priority: Some(changed.priority.as_str()),
+            assignee_id: Some(changed.assignee_id),
+            queue_id: Some(changed.queue_id),
+            category: Some(changed.category.as_deref()),
+            tags: Some(&changed.tags),
+            custom_fields: None,
+        },
+    )?
+    .ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "ticket disappeared while running automations",
+        )
+    })?;
+
+    if updated.assignee_id != ticket.assignee_id {
+        db::assignment::record_change(conn, &updated, ticket.assignee_id, None)?;
+        Ok(updated)
+    } else if updated.queue_id.is_some() && updated.queue_id != ticket.queue_id {
+        Ok(db::assignment::auto_assign(conn, updated.id)?.unwrap_or(updated))
+    } else {
+        Ok(updated)
+    }
+}
+
+/// Runs the side effects of automation actions.
+///
+/// Errors are stored in the execution log.
+pub async fn dispatch(conn: &db::Connection, effects: Vec<Effect>) -> io::Result<()> {
+    for effect in effects {
+        // TODO: use queues
+        let (log_id, result) = spawn_blocking(move || run_effect(effect)).await?;
+
+        if let Err(e) = result {
+            let error = e.to_string();
+            conn.run(move |c| db::automation::set_log_error(c, log_id, &error))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the side effects of automation actions, blocking the current thread.
+///
+/// Errors are stored in the execution log.
+pub fn dispatch_blocking(conn: &mut PgConnection, effects: Vec<Effect>) -> io::Result<()> {
+    for effect in effects {
+        if let (log_id, Err(e)) = run_effect(effect) {
+            db::automation::set_log_error(conn, log_id, &e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a side effect, returning the ID of its execution log entry along with the result.
+fn run_effect(effect: Effect) -> (i64, io::Result<()>) {
+    match effect {
+        Effect::Email {
+            log_id,
+            thread,
+            to,
+            subject,
+            body,
+        } => (
+            log_id,
+            notification::email::send_in_thread(&to, thread, subject, body),
+        ),
+        Effect::Webhook {
+            log_id,
+            url,
+            secret,
+            body,
+        } => {
+            let delivery_id = format!("automation-{}", log_id);
+            let result = notification::webhook::post_signed(
+                &url,
+                &secret,
+                WEBHOOK_EVENT,
+                &delivery_id,
+                &body,
+            )
+            .and_then(|code| match code {
+                200..=299 => Ok(()),
+                code => Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("unexpected response status {}", code),
+                )),
+            });
+
+            (log_id, result)
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+};
+use common::automation::Field;
+use diesel::Connection;
+
+/// Inserts an enabled rule.
+fn insert_rule(conn: &mut PgConnection, name: &str, condition: Condition, actions: &[Action]) {
+    let admin = db::user::get_with_username(conn, "alice")
+        .expect("error retrieving user from database")
+        .expect("Alice was not in the database");
+    let _ = db::automation::insert_rule(
+        conn,
+        &model::AutomationRuleForm {
+            name,
+            enabled: true,
+            position: 0,
+            condition: serde_json::to_value(&condition).expect("error serializing condition"),
+            actions: serde_json::to_value(actions).expect("error serializing actions"),
+        },
+        admin.id,
+    )
+    .expect("error inserting rule");
+}
+
+/// Inserts an urgent network ticket requested by Carol, with a unique title so that only the test
+/// rules match it.
+fn insert_network_ticket(conn: &mut PgConnection, title: &str) -> model::Ticket {
+    let carol = user_id(conn, "carol");
+
+    insert_ticket(
+        conn,
+        &model::NewTicket {
+            priority: "urgent",
+            category: Some("network"),
+            ..new_ticket(carol, title)
+        },
+    )
+}
+
+/// Condition matching the title of a test ticket.
+fn title_is(title: &str) -> Condition {
+    Condition::Equals {
+        field: Field::Title,
+        value: Some(title.to_owned()),
+    }
+}
+
+/// Sunny day unit test for the `run()` function.
+#[test]
+fn ut_sunny_run() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let title = "UT automation network";
+    insert_rule(
+        &mut conn,
+        "UT urgent network",
+        Condition::All {
+            conditions: vec![
+                title_is(title),
+                Condition::Equals {
+                    field: Field::Priority,
+                    value: Some("urgent".to_owned()),
+                },
+                Condition::Equals {
+                    field: Field::Category,
+                    value: Some("network".to_owned()),
+                },
+            ],
+        },
+        &[
+            Action::AddTag {
+                tag: "outage".to_owned(),
+            },
+            Action::SendEmail {
+                to: "oncall@example.com".to_owned(),
+                subject: "Urgent: {{title}}".to_owned(),
+                body: String::new(),
+            },
+        ],
+    );
+    // Cascaded update, triggered by the tag added by the first rule
+    insert_rule(
+        &mut conn,
+        "UT outage status",
+        Condition::All {
+            conditions: vec![
+                title_is(title),
+                Condition::HasTag {
+                    tag: "outage".to_owned(),
+                },
+                Condition::Event {
+                    event: Event::Updated,
+                },
+            ],
+        },
+        &[Action::SetField {
+            field: Field::Status,
+            value: Some("open".to_owned()),
+        }],
+    );
+
+    let ticket = insert_network_ticket(&mut conn, title);
+    let (ticket, effects) =
+        run(&mut conn, Event::Created, None, ticket).expect("error running automations");
+
+    assert_eq!(ticket.tags, vec!["outage".to_owned()]);
+    assert_eq!(ticket.status, "open");
+    assert_eq!(effects.len(), 1);
+    assert!(
+        matches!(&effects[0], Effect::Email { subject, .. } if subject == "Urgent: UT automation network")
+    );
+
+    let log = db::automation::query_log(
+        &mut conn,
+        db::automation::LogFilter {
+            ticket_id: Some(ticket.id),
+            ..db::automation::LogFilter::default()
+        },
+        10,
+        0,
+    )
+    .expect("error retrieving execution log");
+    let depths = log.iter().map(|entry| entry.depth).collect::<Vec<_>>();
+    assert_eq!(depths, vec![1, 0], "executions were not logged");
+}
+
+/// Rainy day unit test for the `run()` function, with rules undoing each other.
+#[test]
+fn ut_rainy_run_loop() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let title = "UT automation loop";
+    for (name, from, to) in [("UT ping", "ping", "pong"), ("UT pong", "pong", "ping")] {
+        insert_rule(
+            &mut conn,
+            name,
+            Condition::All {
+                conditions: vec![
+                    title_is(title),
+                    Condition::HasTag {
+                        tag: from.to_owned(),
+                    },
+                ],
+            },
+            &[
+                Action::RemoveTag {
+                    tag: from.to_owned(),
+                },
+                Action::AddTag { tag: to.to_owned() },
+            ],
+        );
+    }
+
+    let ticket = insert_network_ticket(&mut conn, title);
+    let ticket = db::ticket::update(
+        &mut conn,
+        &Viewer::system(),
+        ticket.id,
+        &model::TicketChanges {
+            tags: Some(&["ping".to_owned()]),
+            ..model::TicketChanges::default()
+        },
+    )
+    .expect("error updating ticket")
+    .expect("ticket disappeared");
+
+    let (ticket, _) =
+        run(&mut conn, Event::Updated, None, ticket).expect("error running automations");
+    assert_eq!(
+        ticket.tags,
+        vec!["ping".to_owned()],
+        "each rule must run only once"
+    );
+}
+
+/// Rainy day unit test for the `run()` function, with a rule having an invalid action.
+#[test]
+fn ut_rainy_run_invalid_action() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let title = "UT automation invalid action";
+    insert_rule(
+        &mut conn,
+        "UT invalid status",
+        title_is(title),
+        &[
+            Action::AddTag {
+                tag: "broken".to_owned(),
+            },
+            Action::SetField {
+                field: Field::Status,
+                value: Some("archived".to_owned()),
+            },
+        ],
+    );
+    insert_rule(
+        &mut conn,
+        "UT valid tag",
+        title_is(title),
+        &[Action::AddTag {
+            tag: "working".to_owned(),
+        }],
+    );
+
+    let ticket = insert_network_ticket(&mut conn, title);
+    let (ticket, _) =
+        run(&mut conn, Event::Created, None, ticket).expect("error running automations");
+    assert_eq!(
+        ticket.tags,
+        vec!["working".to_owned()],
+        "the invalid rule changed the ticket"
+    );
+}
+
+/// Rainy day unit test for the `run()` function, with a rule resolving a parent ticket before its
+/// child.
+#[test]
+fn ut_rainy_run_parent_status() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let title = "UT automation parent";
+    insert_rule(
+        &mut conn,
+        "UT resolve parent",
+        title_is(title),
+        &[
+            Action::AddTag {
+                tag: "done".to_owned(),
+            },
+            Action::SetField {
+                field: Field::Status,
+                value: Some("resolved".to_owned()),
+            },
+        ],
+    );
+
+    let parent = insert_network_ticket(&mut conn, title);
+    let child = insert_network_ticket(&mut conn, "UT automation child");
+    let _ = db::relation::set_parent(&mut conn, child.id, Some(parent.id))
+        .expect("error setting parent ticket");
+    let (ticket, _) =
+        run(&mut conn, Event::Created, None, parent).expect("error running automations");
+    assert_eq!(ticket.status, "new", "the parent ticket was resolved");
+    assert_eq!(ticket.tags, vec!["done".to_owned()]);
+
+    let log = db::automation::query_log(
+        &mut conn,
+        db::automation::LogFilter {
+            ticket_id: Some(ticket.id),
+            ..db::automation::LogFilter::default()
+        },
+        10,
+        0,
+    )
+    .expect("error retrieving execution log");
+    assert_eq!(
+        log.iter()
+            .map(|entry| entry.error.as_deref())
+            .collect::<Vec<_>>(),
+        vec![Some("ticket has unfinished child tickets")]
+    );
+}
@@ -0,0 +1,124 @@
+//! Comma-separated values.
+//!
+//! Records are [parsed](parse) following RFC 4180: fields are separated by commas, and quoted
+//! fields can contain commas, line breaks and doubled quotes. Lines can end with `\r\n` or `\n`,
+//! and empty lines are skipped.
+//!
+//! Records are [written](write_record) the same way, quoting only the fields that need it and
+//! ending lines with `\r\n`.
+
+#[cfg(test)]
+mod tests;
+
+/// Record of a CSV text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Line on which the record starts, starting at 1.
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+/// Error found while parsing a CSV text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// Line of the error, starting at 1.
+    pub line: usize,
+    pub message: &'static str,
+}
+
+/// Parses the records of a CSV text, ignoring any leading byte order mark.
+pub fn parse(text: &str) -> Result<Vec<Record>, Error> {
+    let mut records = Vec::new();
+    let mut chars = text.trim_start_matches('\u{FEFF}').chars().peekable();
+    let mut line = 1;
+
+    while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+
+        loop {
+            match chars.next() {
+                Some('"') if field.is_empty() && !quoted => {
+                    quoted = true;
+                    loop {
+                        match chars.next() {
+                            Some('"') if chars.peek() == Some(&'"') => {
+                                field.push('"');
+                                let _ = chars.next();
+                            }
+                            Some('"') => break,
+                            Some(c) => {
+                                if c == '\n' {
+                                    line += 1;
+                                }
+                                field.push(c);
+                            }
+                            None => {
+                                return Err(Error {
+                                    line: start,
+                                    message: "unterminated quoted field",
+                                })
+                            }
+                        }
+                    }
+                    if !matches!(chars.peek(), None | Some(',' | '\r' | '\n')) {
+                        return Err(Error {
+                            line,
+                            message: "unexpected character after a quoted field",
+                        });
+                    }
+                }
+                Some(',') => {
+                    fields.push(std::mem::take(&mut field));
+                    quoted = false;
+                }
+                Some('\r') if chars.peek() == Some(&'\n') => {}
+                Some('\n') | None => {
+                    fields.push(field);
+                    line += 1;
+                    break;
+                }
+                Some('"') => {
+                    return Err(Error {
+                        line,
+                        message: "unexpected quote in an unquoted field",
+                    })
+                }
+                Some(c) => field.push(c),
+            }
+        }
+
+        if fields.len() > 1 || !fields[0].is_empty() || quoted {
+            records.push(Record {
+                line: start,
+                fields,
+            });
+        }
+    }
+
+    Ok(records)
+}
+
+/// Appends a record to a CSV text, quoting the fields containing commas, quotes or line breaks.
+pub fn write_record<I, S>(text: &mut String, fields: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            text.push(',');
+        }
+        let field = field.as_ref();
+        if field.contains(&[',', '"', '\r', '\n'][..]) {
+            text.push('"');
+            text.push_str(&field.replace('"', "\"\""));
+            text.push('"');
+        } else {
+            text.push_str(field);
+        }
+    }
+    text.push_str("\r\n");
+}
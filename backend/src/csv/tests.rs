@@ -0,0 +1,68 @@
+use super::*;
+
+/// Gets the fields of parsed records.
+fn fields(text: &str) -> Vec<Vec<String>> {
+    parse(text)
+        .expect("error parsing CSV")
+        .into_iter()
+        .map(|record| record.fields)
+        .collect()
+}
+
+/// Quoted and unquoted fields are parsed, with any line ending.
+#[test]
+fn ut_sunny_parse() {
+    assert_eq!(
+        fields("\u{FEFF}name,os\r\nweb-1,linux\n\n\"db, primary\",\"say \"\"hi\"\"\"\n,\n"),
+        [
+            vec!["name", "os"],
+            vec!["web-1", "linux"],
+            vec!["db, primary", "say \"hi\""],
+            vec!["", ""],
+        ]
+    );
+    assert_eq!(fields("a"), [vec!["a"]]);
+    assert_eq!(fields("\"\""), [vec![""]]);
+    assert!(fields("").is_empty());
+
+    let records = parse("name\n\"multi\nline\"\nlast").expect("error parsing CSV");
+    assert_eq!(
+        records.iter().map(|record| record.line).collect::<Vec<_>>(),
+        [1, 2, 4]
+    );
+    assert_eq!(records[1].fields, ["multi\nline"]);
+}
+
+/// Malformed quotes are rejected, with the line of the error.
+#[test]
+fn ut_rainy_parse() {
+    assert_eq!(
+        parse("name\n\"unterminated\nfield"),
+        Err(Error {
+            line: 2,
+            message: "unterminated quoted field"
+        })
+    );
+    assert_eq!(parse("a\nb\"c").map_err(|e| e.line), Err(2));
+    assert_eq!(parse("\"a\"b").map_err(|e| e.line), Err(1));
+}
+
+/// Written records are quoted only when needed, and parse back to the same fields.
+#[test]
+fn ut_sunny_write_record() {
+    let records = [
+        vec!["name", "note"],
+        vec!["web-1", ""],
+        vec!["db, primary", "say \"hi\"\non two lines"],
+    ];
+    let mut text = String::new();
+    for record in &records {
+        write_record(&mut text, record);
+    }
+
+    assert_eq!(
+        text,
+        "name,note\r\nweb-1,\r\n\"db, primary\",\"say \"\"hi\"\"\non two lines\"\r\n"
+    );
+    assert_eq!(fields(&text), records);
+}
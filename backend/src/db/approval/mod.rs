@@ -0,0 +1,317 @@
+use super::{into_option, model, schema::*};
+use crate::{into_io_err, rand_code};
+use chrono::{Duration, Utc};
+use common::approval::{ApprovalStepDTO, Decision, Status};
+use diesel::{dsl::count_star, prelude::*, result::Error as DieselError, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Timeout for approval tokens, in seconds, counted from the moment their step is reached.
+const APPROVAL_TOKEN_TIMEOUT: i64 = 7 * 24 * 60 * 60;
+
+/// Length of approval tokens.
+const TOKEN_LEN: usize = 32;
+
+/// Retrieves all the approval chains, ordered by name.
+pub fn get_chains(conn: &mut PgConnection) -> io::Result<Vec<model::ApprovalChain>> {
+    approval_chain::table
+        .order(approval_chain::name)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves an approval chain with an ID, if it exists.
+pub fn get_chain_with_id(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> io::Result<Option<model::ApprovalChain>> {
+    into_option(approval_chain::table.find(id).first(conn))
+}
+
+/// Checks if the name of a chain is taken by a chain other than the given one.
+pub fn chain_name_taken(
+    conn: &mut PgConnection,
+    name: &str,
+    except: Option<Uuid>,
+) -> io::Result<bool> {
+    let mut query = approval_chain::table
+        .select(count_star())
+        .filter(approval_chain::name.eq(name))
+        .into_boxed();
+    if let Some(id) = except {
+        query = query.filter(approval_chain::id.ne(id));
+    }
+
+    query
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new approval chain.
+pub fn insert_chain(
+    conn: &mut PgConnection,
+    chain: &model::ApprovalChainForm<'_>,
+) -> io::Result<model::ApprovalChain> {
+    diesel::insert_into(approval_chain::table)
+        .values(chain)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates an approval chain, returning it if it exists.
+///
+/// Running approvals keep the steps they started with.
+pub fn update_chain(
+    conn: &mut PgConnection,
+    id: Uuid,
+    chain: &model::ApprovalChainForm<'_>,
+) -> io::Result<Option<model::ApprovalChain>> {
+    into_option(
+        diesel::update(approval_chain::table.find(id))
+            .set((chain, approval_chain::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
+
+/// Deletes an approval chain, returning whether it existed.
+pub fn delete_chain(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(approval_chain::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+type ApprovalColumns = (
+    ticket_approval::steps,
+    ticket_approval::step,
+    ticket_approval::status,
+);
+
+/// Columns of the approvals, except their ticket and timestamps.
+const APPROVAL_COLUMNS: ApprovalColumns = (
+    ticket_approval::steps,
+    ticket_approval::step,
+    ticket_approval::status,
+);
+
+/// Retrieves the approval of a ticket, if it has one.
+///
+/// The visibility of the ticket must be checked beforehand.
+pub fn get_approval(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+) -> io::Result<Option<model::TicketApproval>> {
+    into_option(
+        ticket_approval::table
+            .find(ticket_id)
+            .select(APPROVAL_COLUMNS)
+            .first(conn),
+    )
+}
+
+/// Retrieves the approvers of a ticket, along with their usernames, by step.
+pub fn get_approvers(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+) -> io::Result<Vec<(model::TicketApprover, String)>> {
+    ticket_approver::table
+        .inner_join(sys_user::table)
+        .filter(ticket_approver::ticket_id.eq(ticket_id))
+        .order((ticket_approver::step, sys_user::username))
+        .select((ticket_approver::all_columns, sys_user::username))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the approver with a token, if the token has not expired.
+pub fn get_approver_with_token(
+    conn: &mut PgConnection,
+    token: &str,
+) -> io::Result<Option<model::TicketApprover>> {
+    let limit = Utc::now() - Duration::seconds(APPROVAL_TOKEN_TIMEOUT);
+
+    into_option(
+        ticket_approver::table
+            .filter(
+                ticket_approver::token
+                    .eq(token)
+                    .and(ticket_approver::created_on.ge(limit)),
+            )
+            .first(conn),
+    )
+}
+
+/// Retrieves the approvals waiting for a decision of a user, along with their tickets, oldest
+/// first.
+pub fn get_pending(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> io::Result<Vec<(model::TicketApprover, model::Ticket)>> {
+    ticket_approver::table
+        .inner_join(ticket_approval::table.inner_join(ticket::table))
+        .filter(ticket_approver::user_id.eq(user_id))
+        .filter(ticket_approver::decision.is_null())
+        .filter(ticket_approval::status.eq(Status::Pending.as_str()))
+        .filter(ticket_approval::step.eq(ticket_approver::step))
+        .order(ticket_approver::created_on)
+        .select((ticket_approver::all_columns, ticket::all_columns))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Starts the approval of a ticket, returning the approvers of the first step.
+///
+/// Every step must have at least one approver.
+pub fn start(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    steps: &[ApprovalStepDTO],
+) -> io::Result<Vec<model::TicketApprover>> {
+    let first_step = steps
+        .first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "approval without steps"))?;
+    let steps = serde_json::to_value(steps)?;
+
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        let _ = diesel::insert_into(ticket_approval::table)
+            .values(&model::NewTicketApproval { ticket_id, steps })
+            .execute(conn)?;
+
+        insert_approvers(conn, ticket_id, 0, first_step)
+    })
+    .map_err(into_io_err)
+}
+
+/// Result of a decision on the approval of a ticket.
+#[derive(Debug, Clone)]
+pub struct Decided {
+    /// The index of the step the decision was taken on.
+    pub step: i32,
+    /// The status of the approval after the decision.
+    pub status: Status,
+    /// The approvers of the next step, if the decision made the approval move on to it.
+    pub next_approvers: Vec<model::TicketApprover>,
+}
+
+/// Records the decision of a user on the approval of a ticket, moving the approval on if the
+/// current step passes or is rejected.
+///
+/// Returns `None` if the approval is not waiting for a decision of the user.
+pub fn decide(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    user_id: Uuid,
+    decision: Decision,
+    comment: &str,
+) -> io::Result<Option<Decided>> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        // The approval is locked, so that concurrent decisions are counted one after the other
+        let approval = match ticket_approval::table
+            .find(ticket_id)
+            .select(APPROVAL_COLUMNS)
+            .for_update()
+            .first::<model::TicketApproval>(conn)
+            .optional()?
+        {
+            Some(approval) if approval.status() == Status::Pending => approval,
+            _ => return Ok(None),
+        };
+        let step = approval.step;
+
+        let current = ticket_approver::table
+            .filter(ticket_approver::ticket_id.eq(ticket_id))
+            .filter(ticket_approver::step.eq(step));
+        let recorded = diesel::update(
+            current
+                .filter(ticket_approver::user_id.eq(user_id))
+                .filter(ticket_approver::decision.is_null()),
+        )
+        .set((
+            ticket_approver::decision.eq(decision.as_str()),
+            ticket_approver::comment.eq(comment),
+            ticket_approver::decided_on.eq(Utc::now()),
+        ))
+        .execute(conn)?;
+        if recorded == 0 {
+            return Ok(None);
+        }
+
+        let decisions = current
+            .select(ticket_approver::decision)
+            .load::<Option<String>>(conn)?
+            .into_iter()
+            .map(|decision| decision.and_then(|decision| decision.parse().ok()))
+            .collect::<Vec<_>>();
+        let steps = approval
+            .steps()
+            .map_err(|e| DieselError::DeserializationError(Box::new(e)))?;
+        let mode = steps
+            .get(step as usize)
+            .map(|step| step.mode)
+            .ok_or(DieselError::NotFound)?;
+
+        let (status, next_step) = match mode.outcome(&decisions) {
+            None => (Status::Pending, step),
+            Some(Decision::Approved) if (step as usize) + 1 < steps.len() => {
+                (Status::Pending, step + 1)
+            }
+            Some(outcome) => (outcome.into(), step),
+        };
+        let next_approvers = if next_step != step {
+            insert_approvers(conn, ticket_id, next_step, &steps[next_step as usize])?
+        } else {
+            Vec::new()
+        };
+        if status != Status::Pending || next_step != step {
+            let _ = diesel::update(ticket_approval::table.find(ticket_id))
+                .set((
+                    ticket_approval::step.eq(next_step),
+                    ticket_approval::status.eq(status.as_str()),
+                    ticket_approval::updated_on.eq(Utc::now()),
+                ))
+                .execute(conn)?;
+        }
+
+        Ok(Some(Decided {
+            step,
+            status,
+            next_approvers,
+        }))
+    })
+    .map_err(into_io_err)
+}
+
+/// Inserts the approvers of a step, with new tokens.
+fn insert_approvers(
+    conn: &PgConnection,
+    ticket_id: Uuid,
+    step: i32,
+    definition: &ApprovalStepDTO,
+) -> QueryResult<Vec<model::TicketApprover>> {
+    let tokens = definition
+        .approver_ids
+        .iter()
+        .map(|_| rand_code(TOKEN_LEN))
+        .collect::<Vec<_>>();
+    let approvers = definition
+        .approver_ids
+        .iter()
+        .zip(&tokens)
+        .map(|(&user_id, token)| model::NewTicketApprover {
+            ticket_id,
+            step,
+            user_id,
+            token,
+        })
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(ticket_approver::table)
+        .values(&approvers)
+        .get_results(conn)
+}
@@ -0,0 +1,184 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+};
+use common::approval::Mode;
+use serde_json::json;
+
+/// Sunny day unit test for the approval chains.
+#[test]
+fn ut_sunny_chains() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let steps = json!([{"mode": "any", "approvers": [{"type": "field", "key": "manager"}]}]);
+    let chain = insert_chain(
+        &mut conn,
+        &model::ApprovalChainForm {
+            name: "UT approval manager",
+            steps: steps.clone(),
+        },
+    )
+    .expect("error inserting chain");
+    assert!(get_chains(&mut conn)
+        .expect("error retrieving chains")
+        .iter()
+        .any(|listed| listed.id == chain.id));
+    assert!(chain_name_taken(&mut conn, "UT approval manager", None).expect("error checking name"));
+    assert!(
+        !chain_name_taken(&mut conn, "UT approval manager", Some(chain.id))
+            .expect("error checking name")
+    );
+
+    let updated = update_chain(
+        &mut conn,
+        chain.id,
+        &model::ApprovalChainForm {
+            name: "UT approval line manager",
+            steps,
+        },
+    )
+    .expect("error updating chain")
+    .expect("chain was not found");
+    assert_eq!(updated.name, "UT approval line manager");
+
+    assert!(delete_chain(&mut conn, chain.id).expect("error deleting chain"));
+    assert!(get_chain_with_id(&mut conn, chain.id)
+        .expect("error retrieving chain")
+        .is_none());
+    assert!(!delete_chain(&mut conn, chain.id).expect("error deleting chain"));
+}
+
+/// Sunny day unit test for an approval going through its steps.
+#[test]
+fn ut_sunny_decide() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (alice, bob) = (user_id(&mut conn, "alice"), user_id(&mut conn, "bob"));
+    let carol = user_id(&mut conn, "carol");
+    let ticket = insert_ticket(&mut conn, &new_ticket(carol, "UT approval laptop"));
+
+    let steps = [
+        ApprovalStepDTO {
+            mode: Mode::Any,
+            approver_ids: vec![alice, bob],
+        },
+        ApprovalStepDTO {
+            mode: Mode::All,
+            approver_ids: vec![alice, bob],
+        },
+    ];
+    let first = start(&mut conn, ticket.id, &steps).expect("error starting approval");
+    assert_eq!(first.len(), 2);
+    assert_ne!(first[0].token, first[1].token);
+    let pending = get_pending(&mut conn, bob).expect("error retrieving pending approvals");
+    assert!(pending.iter().any(|(_, pending)| pending.id == ticket.id));
+
+    // Any approval is enough for the first step, which unblocks the second one
+    let decided = decide(&mut conn, ticket.id, alice, Decision::Approved, "Fine")
+        .expect("error deciding")
+        .expect("approval was not waiting for Alice");
+    assert_eq!((decided.step, decided.status), (0, Status::Pending));
+    assert_eq!(decided.next_approvers.len(), 2);
+
+    // The second step needs everybody
+    let decided = decide(&mut conn, ticket.id, bob, Decision::Approved, "")
+        .expect("error deciding")
+        .expect("approval was not waiting for Bob");
+    assert_eq!((decided.step, decided.status), (1, Status::Pending));
+    assert!(decided.next_approvers.is_empty());
+    let decided = decide(&mut conn, ticket.id, alice, Decision::Approved, "")
+        .expect("error deciding")
+        .expect("approval was not waiting for Alice");
+    assert_eq!((decided.step, decided.status), (1, Status::Approved));
+
+    let approval = get_approval(&mut conn, ticket.id)
+        .expect("error retrieving approval")
+        .expect("approval was not found");
+    assert_eq!((approval.step, approval.status()), (1, Status::Approved));
+    assert_eq!(
+        get_approvers(&mut conn, ticket.id)
+            .expect("error retrieving approvers")
+            .len(),
+        4
+    );
+    assert!(get_pending(&mut conn, bob)
+        .expect("error retrieving pending approvals")
+        .iter()
+        .all(|(_, pending)| pending.id != ticket.id));
+}
+
+/// Rainy day unit test for rejected approvals and expired tokens.
+#[test]
+fn ut_rainy_decide() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (alice, bob, carol) = (
+        user_id(&mut conn, "alice"),
+        user_id(&mut conn, "bob"),
+        user_id(&mut conn, "carol"),
+    );
+    let ticket = insert_ticket(&mut conn, &new_ticket(carol, "UT approval server"));
+
+    assert!(start(&mut conn, ticket.id, &[]).is_err());
+    let steps = [
+        ApprovalStepDTO {
+            mode: Mode::All,
+            approver_ids: vec![alice, bob],
+        },
+        ApprovalStepDTO {
+            mode: Mode::Any,
+            approver_ids: vec![alice],
+        },
+    ];
+    let first = start(&mut conn, ticket.id, &steps).expect("error starting approval");
+    assert!(
+        decide(&mut conn, ticket.id, carol, Decision::Approved, "")
+            .expect("error deciding")
+            .is_none(),
+        "Carol is not an approver"
+    );
+
+    // A single rejection is final, without reaching the second step
+    let decided = decide(
+        &mut conn,
+        ticket.id,
+        bob,
+        Decision::Rejected,
+        "Too expensive",
+    )
+    .expect("error deciding")
+    .expect("approval was not waiting for Bob");
+    assert_eq!((decided.step, decided.status), (0, Status::Rejected));
+    assert!(decided.next_approvers.is_empty());
+    assert!(
+        decide(&mut conn, ticket.id, alice, Decision::Approved, "")
+            .expect("error deciding")
+            .is_none(),
+        "Alice decided on a rejected approval"
+    );
+    let approvers = get_approvers(&mut conn, ticket.id).expect("error retrieving approvers");
+    let (bob_approver, _) = approvers
+        .iter()
+        .find(|(approver, _)| approver.user_id == bob)
+        .expect("Bob was not an approver");
+    assert_eq!(bob_approver.decision(), Some(Decision::Rejected));
+    assert_eq!(bob_approver.comment, "Too expensive");
+
+    // Tokens expire a week after their step is reached
+    let token = &first[0].token;
+    assert!(get_approver_with_token(&mut conn, token)
+        .expect("error retrieving approver")
+        .is_some());
+    let _ = diesel::update(ticket_approver::table.filter(ticket_approver::token.eq(token)))
+        .set(ticket_approver::created_on.eq(Utc::now() - Duration::days(8)))
+        .execute(&conn)
+        .expect("error backdating approver");
+    assert!(get_approver_with_token(&mut conn, token)
+        .expect("error retrieving approver")
+        .is_none());
+}
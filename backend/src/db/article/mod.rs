@@ -0,0 +1,329 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use common::article::Status;
+use diesel::{
+    dsl::{count_star, sql},
+    prelude::*,
+    sql_types::{Bool, Float, Text},
+    PgConnection,
+};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Full-text search document of the articles, weighting titles above bodies.
+///
+/// It must match the expression of the `article_search_idx` index.
+const SEARCH_DOCUMENT: &str = "(setweight(to_tsvector('english', title), 'A') || \
+    setweight(to_tsvector('english', body), 'B'))";
+
+/// Retrieves all the categories of articles, ordered by name.
+pub fn get_categories(conn: &mut PgConnection) -> io::Result<Vec<model::ArticleCategory>> {
+    article_category::table
+        .order(article_category::name)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a category of articles with an ID, if it exists.
+pub fn get_category_with_id(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> io::Result<Option<model::ArticleCategory>> {
+    into_option(article_category::table.find(id).first(conn))
+}
+
+/// Inserts a new category of articles.
+pub fn insert_category(
+    conn: &mut PgConnection,
+    category: &model::ArticleCategoryForm<'_>,
+) -> io::Result<model::ArticleCategory> {
+    diesel::insert_into(article_category::table)
+        .values(category)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a category of articles, returning it if it exists.
+pub fn update_category(
+    conn: &mut PgConnection,
+    id: Uuid,
+    category: &model::ArticleCategoryForm<'_>,
+) -> io::Result<Option<model::ArticleCategory>> {
+    into_option(
+        diesel::update(article_category::table.find(id))
+            .set(category)
+            .get_result(conn),
+    )
+}
+
+/// Deletes a category of articles, returning whether it existed.
+///
+/// Its articles are kept, without a category.
+pub fn delete_category(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(article_category::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Checks if the name of a category is taken by a category other than the given one.
+pub fn category_name_taken(
+    conn: &mut PgConnection,
+    name: &str,
+    except: Option<Uuid>,
+) -> io::Result<bool> {
+    let mut query = article_category::table
+        .select(count_star())
+        .filter(article_category::name.eq(name))
+        .into_boxed();
+    if let Some(id) = except {
+        query = query.filter(article_category::id.ne(id));
+    }
+
+    query
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the articles with the given visibilities, ordered by title.
+///
+/// Drafts are only included if `drafts` is `true`. If words are given, in web search syntax, only
+/// the matching articles are retrieved, the most relevant first.
+pub fn get_listed(
+    conn: &mut PgConnection,
+    visibilities: &[&str],
+    drafts: bool,
+    category_id: Option<Uuid>,
+    words: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::Article>> {
+    let mut query = article::table
+        .filter(article::visibility.eq_any(visibilities))
+        .into_boxed();
+    if !drafts {
+        query = query.filter(article::status.eq(Status::Published.as_str()));
+    }
+    if let Some(category_id) = category_id {
+        query = query.filter(article::category_id.eq(category_id));
+    }
+    if let Some(words) = words {
+        query = query
+            .filter(
+                sql::<Bool>(&format!(
+                    "{} @@ websearch_to_tsquery('english', ",
+                    SEARCH_DOCUMENT
+                ))
+                .bind::<Text, _>(words)
+                .sql(")"),
+            )
+            .order(
+                sql::<Float>(&format!(
+                    "ts_rank({}, websearch_to_tsquery('english', ",
+                    SEARCH_DOCUMENT
+                ))
+                .bind::<Text, _>(words)
+                .sql("))")
+                .desc(),
+            );
+    }
+
+    query
+        .then_order_by((article::title, article::id))
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves an article with an ID, if it exists.
+pub fn get_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::Article>> {
+    into_option(article::table.find(id).first(conn))
+}
+
+/// Inserts a new article, along with its first revision.
+pub fn insert(
+    conn: &mut PgConnection,
+    form: &model::ArticleForm<'_>,
+    author_id: Uuid,
+) -> io::Result<model::Article> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let published_on = (form.status == Status::Published.as_str()).then(Utc::now);
+        let article: model::Article = diesel::insert_into(article::table)
+            .values((
+                form,
+                article::author_id.eq(author_id),
+                article::published_on.eq(published_on),
+            ))
+            .get_result(conn)?;
+
+        let _ = diesel::insert_into(article_revision::table)
+            .values(&model::ArticleRevision {
+                article_id: article.id,
+                revision: article.revision,
+                title: article.title.clone(),
+                body: article.body.clone(),
+                editor_id: author_id,
+                created_on: article.created_on,
+            })
+            .execute(conn)?;
+
+        Ok(article)
+    })
+    .map_err(into_io_err)
+}
+
+/// Updates an article, returning it if it exists.
+///
+/// A new revision is created if the title or the body change. The publication timestamp is set
+/// the first time the article is published.
+pub fn update(
+    conn: &mut PgConnection,
+    id: Uuid,
+    form: &model::ArticleForm<'_>,
+    editor_id: Uuid,
+) -> io::Result<Option<model::Article>> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let current: model::Article = match article::table.find(id).for_update().first(conn) {
+            Ok(article) => article,
+            Err(diesel::result::Error::NotFound) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let now = Utc::now();
+        let changed = current.title != form.title || current.body != form.body;
+        let revision = if changed {
+            current.revision + 1
+        } else {
+            current.revision
+        };
+        let published_on = match current.published_on {
+            None if form.status == Status::Published.as_str() => Some(now),
+            published_on => published_on,
+        };
+
+        let article: model::Article = diesel::update(article::table.find(id))
+            .set((
+                form,
+                article::revision.eq(revision),
+                article::published_on.eq(published_on),
+                article::updated_on.eq(now),
+            ))
+            .get_result(conn)?;
+
+        if changed {
+            let _ = diesel::insert_into(article_revision::table)
+                .values(&model::ArticleRevision {
+                    article_id: id,
+                    revision,
+                    title: article.title.clone(),
+                    body: article.body.clone(),
+                    editor_id,
+                    created_on: now,
+                })
+                .execute(conn)?;
+        }
+
+        Ok(Some(article))
+    })
+    .map_err(into_io_err)
+}
+
+/// Deletes an article, along with its revisions and ticket links, returning whether it existed.
+pub fn delete(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(article::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the revisions of an article, newest first.
+pub fn get_revisions(
+    conn: &mut PgConnection,
+    article_id: Uuid,
+) -> io::Result<Vec<model::ArticleRevision>> {
+    article_revision::table
+        .filter(article_revision::article_id.eq(article_id))
+        .order(article_revision::revision.desc())
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a revision of an article, if it exists.
+pub fn get_revision(
+    conn: &mut PgConnection,
+    article_id: Uuid,
+    revision: i32,
+) -> io::Result<Option<model::ArticleRevision>> {
+    into_option(
+        article_revision::table
+            .find((article_id, revision))
+            .first(conn),
+    )
+}
+
+/// Retrieves the articles linked to a ticket, with the given visibilities, ordered by title.
+///
+/// Drafts are only included if `drafts` is `true`.
+pub fn get_linked(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    visibilities: &[&str],
+    drafts: bool,
+) -> io::Result<Vec<model::Article>> {
+    let mut query = article::table
+        .filter(
+            article::id.eq_any(
+                ticket_article::table
+                    .select(ticket_article::article_id)
+                    .filter(ticket_article::ticket_id.eq(ticket_id)),
+            ),
+        )
+        .filter(article::visibility.eq_any(visibilities))
+        .into_boxed();
+    if !drafts {
+        query = query.filter(article::status.eq(Status::Published.as_str()));
+    }
+
+    query
+        .order((article::title, article::id))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Links an article to a ticket, returning whether it wasn't already linked.
+pub fn link(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    article_id: Uuid,
+    linked_by: Uuid,
+) -> io::Result<bool> {
+    diesel::insert_into(ticket_article::table)
+        .values((
+            ticket_article::ticket_id.eq(ticket_id),
+            ticket_article::article_id.eq(article_id),
+            ticket_article::linked_by.eq(linked_by),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Unlinks an article from a ticket, returning whether it was linked.
+pub fn unlink(conn: &mut PgConnection, ticket_id: Uuid, article_id: Uuid) -> io::Result<bool> {
+    diesel::delete(
+        ticket_article::table
+            .filter(ticket_article::ticket_id.eq(ticket_id))
+            .filter(ticket_article::article_id.eq(article_id)),
+    )
+    .execute(conn)
+    .map(|count| count > 0)
+    .map_err(into_io_err)
+}
@@ -0,0 +1,208 @@
+use super::*;
+use crate::db::{establish_connection, fixtures::user_id, tenant::Viewer, ticket};
+use common::article::Visibility;
+use diesel::Connection;
+
+/// Creates an article form.
+fn form<'n>(
+    title: &'n str,
+    body: &'n str,
+    status: Status,
+    visibility: Visibility,
+) -> model::ArticleForm<'n> {
+    model::ArticleForm {
+        category_id: None,
+        title,
+        body,
+        status: status.as_str(),
+        visibility: visibility.as_str(),
+    }
+}
+
+/// Sunny day unit test for articles and their revisions.
+#[test]
+fn ut_sunny_articles() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let bob = user_id(&mut conn, "bob");
+    let category = insert_category(
+        &mut conn,
+        &model::ArticleCategoryForm {
+            name: "UT articles",
+            description: "",
+        },
+    )
+    .expect("error inserting category");
+
+    let draft = insert(
+        &mut conn,
+        &model::ArticleForm {
+            category_id: Some(category.id),
+            ..form(
+                "UT printer",
+                "Restart it.",
+                Status::Draft,
+                Visibility::Public,
+            )
+        },
+        bob,
+    )
+    .expect("error inserting article");
+    assert_eq!(draft.revision, 1);
+    assert!(draft.published_on.is_none());
+
+    let public = [Visibility::Public.as_str()];
+    let listed = |conn: &mut PgConnection, drafts| {
+        get_listed(conn, &public, drafts, Some(category.id), None, 500, 0)
+            .expect("error listing articles")
+    };
+    assert!(listed(&mut conn, false).is_empty());
+    assert_eq!(listed(&mut conn, true).len(), 1);
+
+    // Publishing without changing the text doesn't create revisions
+    let published = update(
+        &mut conn,
+        draft.id,
+        &model::ArticleForm {
+            category_id: Some(category.id),
+            ..form(
+                "UT printer",
+                "Restart it.",
+                Status::Published,
+                Visibility::Public,
+            )
+        },
+        bob,
+    )
+    .expect("error updating article")
+    .expect("article was not found");
+    assert_eq!(published.revision, 1);
+    assert!(published.published_on.is_some());
+    assert_eq!(listed(&mut conn, false).len(), 1);
+
+    // Searches match the stems of the words
+    let searched = |conn: &mut PgConnection, words| {
+        get_listed(conn, &public, false, Some(category.id), Some(words), 500, 0)
+            .expect("error searching articles")
+    };
+    assert_eq!(searched(&mut conn, "restarting printers").len(), 1);
+    assert!(searched(&mut conn, "printer -restart").is_empty());
+
+    let edited = update(
+        &mut conn,
+        draft.id,
+        &form(
+            "UT printer",
+            "Unplug it.",
+            Status::Published,
+            Visibility::Public,
+        ),
+        bob,
+    )
+    .expect("error updating article")
+    .expect("article was not found");
+    assert_eq!(edited.revision, 2);
+    assert_eq!(edited.published_on, published.published_on);
+    assert_eq!(edited.category_id, None);
+
+    let revisions = get_revisions(&mut conn, draft.id).expect("error retrieving revisions");
+    assert_eq!(
+        revisions
+            .iter()
+            .map(|revision| (revision.revision, revision.body.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(2, "Unplug it."), (1, "Restart it.")]
+    );
+    assert_eq!(
+        get_revision(&mut conn, draft.id, 1)
+            .expect("error retrieving revision")
+            .map(|revision| revision.body),
+        Some("Restart it.".to_owned())
+    );
+
+    // Links to tickets
+    let carol = user_id(&mut conn, "carol");
+    let ticket = ticket::insert(
+        &mut conn,
+        &Viewer::system(),
+        &model::NewTicket {
+            title: "UT article link",
+            description: "",
+            priority: "normal",
+            requester_id: carol,
+            organisation_id: None,
+            queue_id: None,
+            category: None,
+            custom_fields: None,
+        },
+    )
+    .expect("error inserting ticket");
+    assert!(link(&mut conn, ticket.id, draft.id, bob).expect("error linking article"));
+    assert!(!link(&mut conn, ticket.id, draft.id, bob).expect("error linking article"));
+    assert_eq!(
+        get_linked(&mut conn, ticket.id, &public, false)
+            .expect("error retrieving linked articles")
+            .len(),
+        1
+    );
+    assert!(unlink(&mut conn, ticket.id, draft.id).expect("error unlinking article"));
+
+    assert!(delete(&mut conn, draft.id).expect("error deleting article"));
+    assert!(get_revisions(&mut conn, draft.id)
+        .expect("error retrieving revisions")
+        .is_empty());
+}
+
+/// Rainy day unit test for articles: they are only listed for their audience.
+#[test]
+fn ut_rainy_articles() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let bob = user_id(&mut conn, "bob");
+    let internal = insert(
+        &mut conn,
+        &form(
+            "UT internal",
+            "Admin password reset steps.",
+            Status::Published,
+            Visibility::Internal,
+        ),
+        bob,
+    )
+    .expect("error inserting article");
+
+    let visibilities = Visibility::readable_by(Some(common::user::Role::Customer))
+        .iter()
+        .map(|visibility| visibility.as_str())
+        .collect::<Vec<_>>();
+    assert!(
+        get_listed(&mut conn, &visibilities, true, None, None, 500, 0)
+            .expect("error listing articles")
+            .iter()
+            .all(|article| article.id != internal.id)
+    );
+    assert!(get_listed(
+        &mut conn,
+        &visibilities,
+        true,
+        None,
+        Some("admin password"),
+        500,
+        0
+    )
+    .expect("error searching articles")
+    .iter()
+    .all(|article| article.id != internal.id));
+
+    assert!(update(
+        &mut conn,
+        Uuid::new_v4(),
+        &form("UT missing", "", Status::Draft, Visibility::Public),
+        bob,
+    )
+    .expect("error updating article")
+    .is_none());
+    assert!(!delete(&mut conn, Uuid::new_v4()).expect("error deleting article"));
+}
@@ -0,0 +1,191 @@
+//! Assignment of tickets to agents.
+//!
+//! Tickets entering a queue are assigned to a member of the team of the queue, following the
+//! assignment strategy of the queue. Every change in the assignment of a ticket, either manual or
+//! automatic, is recorded in its assignment history.
+
+use super::{model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use common::{team::Strategy, ticket::Status, user::Role};
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Assigns a ticket following the strategy of its queue.
+///
+/// Returns the updated ticket, or `None` if the ticket doesn't exist, is not in a queue, or no
+/// agent could be picked.
+pub fn auto_assign(conn: &mut PgConnection, ticket_id: Uuid) -> io::Result<Option<model::Ticket>> {
+    let conn: &PgConnection = conn;
+
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let ticket = match ticket::table
+            .find(ticket_id)
+            .for_update()
+            .first::<model::Ticket>(conn)
+            .optional()?
+        {
+            Some(ticket) => ticket,
+            None => return Ok(None),
+        };
+        let queue = match ticket.queue_id {
+            // The queue is locked so that concurrent round-robin assignments don't collide
+            Some(queue_id) => queue::table
+                .find(queue_id)
+                .for_update()
+                .first::<model::Queue>(conn)?,
+            None => return Ok(None),
+        };
+
+        let strategy = queue.strategy();
+        let assignee_id = match pick_assignee(conn, &queue)? {
+            Some(assignee_id) => assignee_id,
+            None => return Ok(None),
+        };
+
+        if strategy == Strategy::RoundRobin {
+            diesel::update(queue::table.find(queue.id))
+                .set(queue::last_assignee_id.eq(assignee_id))
+                .execute(conn)?;
+        }
+
+        let updated = diesel::update(ticket::table.find(ticket.id))
+            .set((
+                ticket::assignee_id.eq(assignee_id),
+                ticket::updated_on.eq(Utc::now()),
+            ))
+            .get_result::<model::Ticket>(conn)?;
+
+        insert(
+            conn,
+            &model::NewTicketAssignment {
+                ticket_id: ticket.id,
+                previous_assignee_id: ticket.assignee_id,
+                assignee_id: Some(assignee_id),
+                queue_id: Some(queue.id),
+                strategy: Some(strategy.as_str()),
+                assigned_by: None,
+            },
+        )?;
+
+        Ok(Some(updated))
+    })
+    .map_err(into_io_err)
+}
+
+/// Picks the agent that should be assigned the next ticket of a queue, if any.
+fn pick_assignee(conn: &PgConnection, queue: &model::Queue) -> QueryResult<Option<Uuid>> {
+    let staff = [Role::Agent.as_str(), Role::Admin.as_str()];
+    let candidates = team_member::table
+        .inner_join(sys_user::table)
+        .filter(team_member::team_id.eq(queue.team_id))
+        .filter(sys_user::active)
+        .filter(sys_user::role.eq_any(&staff[..]))
+        .order(sys_user::username)
+        .select((team_member::user_id, team_member::skills))
+        .load::<(Uuid, Vec<String>)>(conn)?;
+
+    let picked = match queue.strategy() {
+        Strategy::Manual => None,
+        Strategy::RoundRobin => {
+            let next = candidates
+                .iter()
+                .position(|(user_id, _)| Some(*user_id) == queue.last_assignee_id)
+                .map_or(0, |last| last + 1);
+            candidates
+                .get(next)
+                .or_else(|| candidates.first())
+                .map(|(user_id, _)| *user_id)
+        }
+        Strategy::LeastLoaded => {
+            let candidates = candidates
+                .into_iter()
+                .map(|(user_id, _)| user_id)
+                .collect::<Vec<_>>();
+            least_loaded(conn, &candidates)?
+        }
+        Strategy::SkillsBased => {
+            let candidates = candidates
+                .into_iter()
+                .filter(|(_, skills)| queue.skills.iter().all(|skill| skills.contains(skill)))
+                .map(|(user_id, _)| user_id)
+                .collect::<Vec<_>>();
+            least_loaded(conn, &candidates)?
+        }
+    };
+
+    Ok(picked)
+}
+
+/// Picks the agent with the fewest active tickets assigned.
+///
+/// Ties are resolved in favour of the first candidate.
+fn least_loaded(conn: &PgConnection, candidates: &[Uuid]) -> QueryResult<Option<Uuid>> {
+    let active = Status::ALL
+        .iter()
+        .filter(|status| status.is_active())
+        .map(|status| status.as_str())
+        .collect::<Vec<_>>();
+    let assignees = ticket::table
+        .filter(ticket::assignee_id.eq_any(candidates))
+        .filter(ticket::status.eq_any(active))
+        .select(ticket::assignee_id)
+        .load::<Option<Uuid>>(conn)?;
+
+    Ok(candidates
+        .iter()
+        .min_by_key(|candidate| {
+            assignees
+                .iter()
+                .filter(|assignee| **assignee == Some(**candidate))
+                .count()
+        })
+        .copied())
+}
+
+/// Inserts a change in the assignment history of a ticket.
+fn insert(conn: &PgConnection, assignment: &model::NewTicketAssignment<'_>) -> QueryResult<()> {
+    diesel::insert_into(ticket_assignment::table)
+        .values(assignment)
+        .execute(conn)
+        .map(|_count| ())
+}
+
+/// Records a change in the assignment of a ticket, not caused by a queue strategy.
+///
+/// Changes made by automation rules don't have a user that made them.
+pub fn record_change(
+    conn: &mut PgConnection,
+    ticket: &model::Ticket,
+    previous_assignee_id: Option<Uuid>,
+    assigned_by: Option<Uuid>,
+) -> io::Result<()> {
+    insert(
+        conn,
+        &model::NewTicketAssignment {
+            ticket_id: ticket.id,
+            previous_assignee_id,
+            assignee_id: ticket.assignee_id,
+            queue_id: ticket.queue_id,
+            strategy: None,
+            assigned_by,
+        },
+    )
+    .map_err(into_io_err)
+}
+
+/// Retrieves the assignment history of a ticket, oldest first.
+pub fn get_history(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+) -> io::Result<Vec<model::TicketAssignment>> {
+    ticket_assignment::table
+        .filter(ticket_assignment::ticket_id.eq(ticket_id))
+        .order(ticket_assignment::id)
+        .load(conn)
+        .map_err(into_io_err)
+}
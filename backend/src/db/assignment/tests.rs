@@ -0,0 +1,198 @@
+use super::*;
+use crate::db::{establish_connection, queue, team, user};
+use diesel::Connection;
+
+/// Creates a team with the given agents and skills, and a queue with the given strategy.
+///
+/// Must be called inside a test transaction, since the records are not cleaned up.
+fn setup(
+    conn: &mut PgConnection,
+    name: &str,
+    strategy: Strategy,
+    agents: &[(&str, &[&str])],
+    required_skills: &[&str],
+) -> (model::Queue, Vec<Uuid>) {
+    let team = team::insert(conn, name).expect("error inserting team");
+    let agent_ids = agents
+        .iter()
+        .map(|(username, skills)| {
+            let agent = user::insert_user(
+                conn,
+                username,
+                &format!("{}@example.com", username),
+                b"password",
+                "Test",
+                "Agent",
+                Role::Agent,
+            )
+            .expect("error inserting agent");
+            let skills = skills.iter().map(|s| (*s).to_owned()).collect::<Vec<_>>();
+            team::upsert_member(conn, team.id, agent.id, &skills).expect("error adding member");
+            agent.id
+        })
+        .collect();
+
+    let skills = required_skills
+        .iter()
+        .map(|s| (*s).to_owned())
+        .collect::<Vec<_>>();
+    let queue = queue::insert(
+        conn,
+        &model::QueueForm {
+            name,
+            team_id: team.id,
+            strategy: strategy.as_str(),
+            skills: &skills,
+            is_default: false,
+        },
+    )
+    .expect("error inserting queue");
+
+    (queue, agent_ids)
+}
+
+/// Inserts a ticket in a queue and assigns it automatically.
+fn new_ticket(conn: &mut PgConnection, queue_id: Uuid) -> model::Ticket {
+    let requester = user::get_with_username(conn, "carol")
+        .expect("error retrieving user from database")
+        .expect("Carol was not in the database");
+    let ticket = diesel::insert_into(ticket::table)
+        .values(&model::NewTicket {
+            title: "UT assignment",
+            description: "",
+            priority: "normal",
+            requester_id: requester.id,
+            organisation_id: None,
+            queue_id: Some(queue_id),
+            category: None,
+            custom_fields: None,
+        })
+        .get_result::<model::Ticket>(conn)
+        .expect("error inserting ticket");
+
+    auto_assign(conn, ticket.id)
+        .expect("error assigning ticket")
+        .unwrap_or(ticket)
+}
+
+/// Sunny day unit test for the round-robin strategy.
+#[test]
+fn ut_sunny_round_robin() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (queue, agents) = setup(
+        &mut conn,
+        "UT round robin",
+        Strategy::RoundRobin,
+        &[("ut_rr_a", &[]), ("ut_rr_b", &[])],
+        &[],
+    );
+
+    let assignees = (0..3)
+        .map(|_| new_ticket(&mut conn, queue.id).assignee_id)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        assignees,
+        vec![Some(agents[0]), Some(agents[1]), Some(agents[0])]
+    );
+}
+
+/// Sunny day unit test for the least-loaded strategy.
+#[test]
+fn ut_sunny_least_loaded() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (queue, agents) = setup(
+        &mut conn,
+        "UT least loaded",
+        Strategy::LeastLoaded,
+        &[("ut_ll_a", &[]), ("ut_ll_b", &[])],
+        &[],
+    );
+
+    let first = new_ticket(&mut conn, queue.id);
+    assert_eq!(first.assignee_id, Some(agents[0]));
+    let second = new_ticket(&mut conn, queue.id);
+    assert_eq!(second.assignee_id, Some(agents[1]));
+
+    // Resolved tickets don't count towards the load
+    let _ = diesel::update(ticket::table.find(first.id))
+        .set(ticket::status.eq("resolved"))
+        .execute(&conn)
+        .expect("error resolving ticket");
+    let third = new_ticket(&mut conn, queue.id);
+    assert_eq!(third.assignee_id, Some(agents[0]));
+}
+
+/// Sunny day unit test for the skills-based strategy.
+#[test]
+fn ut_sunny_skills_based() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (queue, agents) = setup(
+        &mut conn,
+        "UT skills based",
+        Strategy::SkillsBased,
+        &[
+            ("ut_sb_a", &["printers"]),
+            ("ut_sb_b", &["printers", "spanish"]),
+        ],
+        &["spanish"],
+    );
+
+    for _ in 0..2 {
+        let ticket = new_ticket(&mut conn, queue.id);
+        assert_eq!(ticket.assignee_id, Some(agents[1]));
+    }
+
+    let ticket = new_ticket(&mut conn, queue.id);
+    let history = get_history(&mut conn, ticket.id).expect("error retrieving history");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].strategy.as_deref(), Some("skills_based"));
+    assert_eq!(history[0].assigned_by, None);
+}
+
+/// Rainy day unit test for the skills-based strategy, without any skilled agent.
+#[test]
+fn ut_rainy_skills_based() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (queue, _) = setup(
+        &mut conn,
+        "UT unskilled",
+        Strategy::SkillsBased,
+        &[("ut_us_a", &["printers"])],
+        &["mainframes"],
+    );
+
+    let ticket = new_ticket(&mut conn, queue.id);
+    assert_eq!(ticket.assignee_id, None, "an unskilled agent was assigned");
+    assert!(get_history(&mut conn, ticket.id)
+        .expect("error retrieving history")
+        .is_empty());
+}
+
+/// Rainy day unit test for the manual strategy.
+#[test]
+fn ut_rainy_manual() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (queue, _) = setup(
+        &mut conn,
+        "UT manual",
+        Strategy::Manual,
+        &[("ut_ma_a", &[])],
+        &[],
+    );
+
+    let ticket = new_ticket(&mut conn, queue.id);
+    assert_eq!(
+        ticket.assignee_id, None,
+        "a ticket was assigned automatically"
+    );
+}
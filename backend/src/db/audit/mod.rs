@@ -0,0 +1,61 @@
+use super::{model, schema::*};
+use crate::into_io_err;
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Filter for audit log queries.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// Only retrieve events of this kind.
+    pub kind: Option<String>,
+    /// Only retrieve events performed by this user.
+    pub actor_id: Option<Uuid>,
+    /// Only retrieve events that happened at or after this moment.
+    pub since: Option<DateTime<Utc>>,
+    /// Only retrieve events that happened before this moment.
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Inserts a new entry in the audit log.
+pub fn insert(conn: &mut PgConnection, entry: &model::NewAuditEntry<'_>) -> io::Result<()> {
+    diesel::insert_into(sys_audit_log::table)
+        .values(entry)
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Retrieves the entries of the audit log matching the filter, newest first.
+pub fn query(
+    conn: &mut PgConnection,
+    filter: &Filter,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::AuditEntry>> {
+    let mut query = sys_audit_log::table.into_boxed();
+
+    if let Some(ref kind) = filter.kind {
+        query = query.filter(sys_audit_log::kind.eq(kind));
+    }
+    if let Some(actor_id) = filter.actor_id {
+        query = query.filter(sys_audit_log::actor_id.eq(actor_id));
+    }
+    if let Some(since) = filter.since {
+        query = query.filter(sys_audit_log::created_on.ge(since));
+    }
+    if let Some(until) = filter.until {
+        query = query.filter(sys_audit_log::created_on.lt(until));
+    }
+
+    query
+        .order(sys_audit_log::id.desc())
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
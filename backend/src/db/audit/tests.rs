@@ -0,0 +1,87 @@
+use super::*;
+use crate::db::{establish_connection, user};
+use diesel::sql_query;
+
+/// Sunny day unit test for the `insert()` and `query()` functions.
+#[test]
+fn ut_sunny_insert_query() {
+    let mut conn = establish_connection();
+
+    let alice = user::get_with_username(&mut conn, "alice")
+        .expect("error retrieving user from database")
+        .expect("Alice was not in the database");
+
+    insert(
+        &mut conn,
+        &model::NewAuditEntry {
+            kind: "login_success",
+            actor_id: Some(alice.id),
+            ip_address: Some("127.0.0.1"),
+            user_agent: Some("unit test"),
+            request_id: "ut_sunny_insert_query",
+            details: serde_json::json!({ "kind": "login_success" }),
+        },
+    )
+    .expect("error inserting audit log entry");
+
+    let filter = Filter {
+        kind: Some("login_success".to_owned()),
+        actor_id: Some(alice.id),
+        ..Filter::default()
+    };
+    let entries = query(&mut conn, &filter, 500, 0).expect("error querying the audit log");
+    assert!(
+        entries
+            .iter()
+            .any(|entry| entry.request_id == "ut_sunny_insert_query"),
+        "the entry was not found"
+    );
+    assert!(entries
+        .iter()
+        .all(|entry| entry.kind == "login_success" && entry.actor_id == Some(alice.id)));
+}
+
+/// Rainy day unit test for the `query()` function, with a date range in the future.
+#[test]
+fn ut_rainy_query_future() {
+    let mut conn = establish_connection();
+
+    let filter = Filter {
+        since: Some(Utc::now() + chrono::Duration::days(1)),
+        ..Filter::default()
+    };
+    let entries = query(&mut conn, &filter, 500, 0).expect("error querying the audit log");
+    assert!(entries.is_empty(), "entries from the future were found");
+}
+
+/// Rainy day unit test checking that the audit log can't be modified.
+#[test]
+fn ut_rainy_append_only() {
+    let mut conn = establish_connection();
+
+    insert(
+        &mut conn,
+        &model::NewAuditEntry {
+            kind: "login_failure",
+            actor_id: None,
+            ip_address: None,
+            user_agent: None,
+            request_id: "ut_rainy_append_only",
+            details: serde_json::json!({ "kind": "login_failure", "username": "nobody" }),
+        },
+    )
+    .expect("error inserting audit log entry");
+
+    assert!(
+        sql_query("UPDATE sys_audit_log SET kind = 'login_success'")
+            .execute(&conn)
+            .is_err(),
+        "the audit log was updated"
+    );
+    assert!(
+        sql_query("DELETE FROM sys_audit_log")
+            .execute(&conn)
+            .is_err(),
+        "the audit log was deleted"
+    );
+}
@@ -0,0 +1,123 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Retrieves all automation rules, in evaluation order.
+pub fn get_rules(conn: &mut PgConnection) -> io::Result<Vec<model::AutomationRule>> {
+    automation_rule::table
+        .order((automation_rule::position, automation_rule::name))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the enabled automation rules, in evaluation order.
+pub fn get_enabled_rules(conn: &mut PgConnection) -> io::Result<Vec<model::AutomationRule>> {
+    automation_rule::table
+        .filter(automation_rule::enabled)
+        .order((automation_rule::position, automation_rule::name))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves an automation rule with an ID, if it exists.
+pub fn get_rule_with_id(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> io::Result<Option<model::AutomationRule>> {
+    into_option(automation_rule::table.find(id).first(conn))
+}
+
+/// Inserts a new automation rule.
+pub fn insert_rule(
+    conn: &mut PgConnection,
+    rule: &model::AutomationRuleForm<'_>,
+    created_by: Uuid,
+) -> io::Result<model::AutomationRule> {
+    diesel::insert_into(automation_rule::table)
+        .values((rule, automation_rule::created_by.eq(created_by)))
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates an automation rule, returning it if it exists.
+pub fn update_rule(
+    conn: &mut PgConnection,
+    id: Uuid,
+    rule: &model::AutomationRuleForm<'_>,
+) -> io::Result<Option<model::AutomationRule>> {
+    let rule = diesel::update(automation_rule::table.find(id))
+        .set((rule, automation_rule::updated_on.eq(Utc::now())))
+        .get_result(conn);
+
+    into_option(rule)
+}
+
+/// Deletes an automation rule, along with its execution log.
+///
+/// Returns `false` if the rule didn't exist.
+pub fn delete_rule(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(automation_rule::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new entry in the execution log, returning its ID.
+pub fn insert_log(
+    conn: &mut PgConnection,
+    entry: &model::NewAutomationLogEntry<'_>,
+) -> io::Result<i64> {
+    diesel::insert_into(automation_log::table)
+        .values(entry)
+        .returning(automation_log::id)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Sets the error of an entry of the execution log.
+pub fn set_log_error(conn: &mut PgConnection, id: i64, error: &str) -> io::Result<()> {
+    diesel::update(automation_log::table.find(id))
+        .set(automation_log::error.eq(error))
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Filter for execution log queries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogFilter {
+    /// Only retrieve executions of this rule.
+    pub rule_id: Option<Uuid>,
+    /// Only retrieve executions on this ticket.
+    pub ticket_id: Option<Uuid>,
+}
+
+/// Retrieves the entries of the execution log matching the filter, newest first.
+pub fn query_log(
+    conn: &mut PgConnection,
+    filter: LogFilter,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::AutomationLogEntry>> {
+    let mut query = automation_log::table.into_boxed();
+
+    if let Some(rule_id) = filter.rule_id {
+        query = query.filter(automation_log::rule_id.eq(rule_id));
+    }
+    if let Some(ticket_id) = filter.ticket_id {
+        query = query.filter(automation_log::ticket_id.eq(ticket_id));
+    }
+
+    query
+        .order(automation_log::id.desc())
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
@@ -0,0 +1,68 @@
+use super::*;
+use crate::db::establish_connection;
+use common::automation::{Action, Condition};
+use diesel::Connection;
+
+/// Creates the form of a test rule.
+fn form(name: &str) -> model::AutomationRuleForm<'_> {
+    model::AutomationRuleForm {
+        name,
+        enabled: true,
+        position: 0,
+        condition: serde_json::to_value(&Condition::Always).expect("error serializing condition"),
+        actions: serde_json::to_value(&[Action::AddTag {
+            tag: "ut".to_owned(),
+        }])
+        .expect("error serializing actions"),
+    }
+}
+
+/// Sunny day unit test for the rule functions.
+#[test]
+fn ut_sunny_rules() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let alice = crate::db::user::get_with_username(&mut conn, "alice")
+        .expect("error retrieving user from database")
+        .expect("Alice was not in the database");
+
+    let rule = insert_rule(&mut conn, &form("UT rule"), alice.id).expect("error inserting rule");
+    assert_eq!(rule.created_by, Some(alice.id));
+    assert!(get_enabled_rules(&mut conn)
+        .expect("error retrieving rules")
+        .iter()
+        .any(|r| r.id == rule.id));
+
+    let disabled = update_rule(
+        &mut conn,
+        rule.id,
+        &model::AutomationRuleForm {
+            enabled: false,
+            ..form("UT rule")
+        },
+    )
+    .expect("error updating rule")
+    .expect("rule disappeared");
+    assert!(!disabled.enabled);
+    assert!(!get_enabled_rules(&mut conn)
+        .expect("error retrieving rules")
+        .iter()
+        .any(|r| r.id == rule.id));
+
+    assert!(delete_rule(&mut conn, rule.id).expect("error deleting rule"));
+    assert!(get_rule_with_id(&mut conn, rule.id)
+        .expect("error retrieving rule")
+        .is_none());
+}
+
+/// Rainy day unit test for the rule functions.
+#[test]
+fn ut_rainy_rules() {
+    let mut conn = establish_connection();
+
+    assert!(update_rule(&mut conn, Uuid::new_v4(), &form("UT missing"))
+        .expect("error updating rule")
+        .is_none());
+    assert!(!delete_rule(&mut conn, Uuid::new_v4()).expect("error deleting rule"));
+}
@@ -0,0 +1,93 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use common::ticket::Status as TicketStatus;
+use diesel::{pg::upsert::excluded, prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+type BoardColumnColumns = (board_column::column_key, board_column::wip_limit);
+
+/// Columns of the board column settings, except their grouping and timestamp.
+const BOARD_COLUMN_COLUMNS: BoardColumnColumns =
+    (board_column::column_key, board_column::wip_limit);
+
+/// Retrieves the settings of the columns of the board with the given grouping.
+pub fn get_columns(conn: &mut PgConnection, grouping: &str) -> io::Result<Vec<model::BoardColumn>> {
+    board_column::table
+        .select(BOARD_COLUMN_COLUMNS)
+        .filter(board_column::grouping.eq(grouping))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the limit of work in progress of a column of the board, if it has one.
+pub fn get_wip_limit(
+    conn: &mut PgConnection,
+    grouping: &str,
+    key: &str,
+) -> io::Result<Option<i32>> {
+    into_option(
+        board_column::table
+            .find((grouping, key))
+            .select(board_column::wip_limit)
+            .first(conn),
+    )
+}
+
+/// Counts the tickets with the given status, in its column of the board grouped by status.
+pub fn count_with_status(conn: &mut PgConnection, status: &str) -> io::Result<i64> {
+    ticket::table
+        .filter(ticket::status.eq(status))
+        .count()
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Counts the active tickets with the given assignee, or without one, in their column of the
+/// board grouped by assignee.
+pub fn count_assigned(conn: &mut PgConnection, assignee_id: Option<Uuid>) -> io::Result<i64> {
+    let active = TicketStatus::ALL
+        .iter()
+        .filter(|status| status.is_active())
+        .map(|status| status.as_str())
+        .collect::<Vec<_>>();
+
+    let query = ticket::table.filter(ticket::status.eq_any(active)).count();
+    match assignee_id {
+        Some(assignee_id) => query
+            .filter(ticket::assignee_id.eq(assignee_id))
+            .get_result(conn),
+        None => query.filter(ticket::assignee_id.is_null()).get_result(conn),
+    }
+    .map_err(into_io_err)
+}
+
+/// Stores the settings of a column of the board, replacing the current ones.
+pub fn upsert_column(
+    conn: &mut PgConnection,
+    column: &model::NewBoardColumn<'_>,
+) -> io::Result<model::BoardColumn> {
+    diesel::insert_into(board_column::table)
+        .values(column)
+        .on_conflict((board_column::grouping, board_column::column_key))
+        .do_update()
+        .set((
+            board_column::wip_limit.eq(excluded(board_column::wip_limit)),
+            board_column::updated_on.eq(Utc::now()),
+        ))
+        .returning(BOARD_COLUMN_COLUMNS)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Deletes the settings of a column of the board, returning whether it had any.
+pub fn delete_column(conn: &mut PgConnection, grouping: &str, key: &str) -> io::Result<bool> {
+    diesel::delete(board_column::table.find((grouping, key)))
+        .execute(conn)
+        .map(|deleted| deleted > 0)
+        .map_err(into_io_err)
+}
@@ -0,0 +1,97 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+};
+use diesel::Connection;
+
+/// Unit test for the settings of the board columns: limits are stored, replaced and removed per
+/// grouping.
+#[test]
+fn ut_sunny_board_columns() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let column = model::NewBoardColumn {
+        grouping: "status",
+        column_key: "pending",
+        wip_limit: 5,
+    };
+    let stored = upsert_column(&mut conn, &column).expect("error storing board column");
+    assert_eq!(stored.wip_limit, 5);
+    let replaced = upsert_column(
+        &mut conn,
+        &model::NewBoardColumn {
+            wip_limit: 3,
+            ..column
+        },
+    )
+    .expect("error replacing board column");
+    assert_eq!(replaced.wip_limit, 3);
+    assert_eq!(
+        get_wip_limit(&mut conn, "status", "pending").expect("error retrieving WIP limit"),
+        Some(3)
+    );
+
+    let columns = get_columns(&mut conn, "status").expect("error retrieving board columns");
+    assert_eq!(
+        columns
+            .iter()
+            .filter(|c| c.column_key == "pending")
+            .map(|c| c.wip_limit)
+            .collect::<Vec<_>>(),
+        vec![3]
+    );
+    assert!(get_columns(&mut conn, "assignee")
+        .expect("error retrieving board columns")
+        .iter()
+        .all(|c| c.column_key != "pending"));
+
+    assert!(delete_column(&mut conn, "status", "pending").expect("error deleting board column"));
+    assert!(!delete_column(&mut conn, "status", "pending").expect("error deleting board column"));
+    assert_eq!(
+        get_wip_limit(&mut conn, "status", "pending").expect("error retrieving WIP limit"),
+        None
+    );
+}
+
+/// Unit test for the tickets of the board columns: new tickets count in the columns of their
+/// status and of their assignee.
+#[test]
+fn ut_sunny_column_counts() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob_id = user_id(&mut conn, "bob");
+    let counts = |conn: &mut PgConnection| {
+        (
+            count_with_status(conn, "new").expect("error counting tickets"),
+            count_assigned(conn, None).expect("error counting tickets"),
+            count_assigned(conn, Some(bob_id)).expect("error counting tickets"),
+        )
+    };
+    let (new, unassigned, assigned) = counts(&mut conn);
+    let requester_id = user_id(&mut conn, "alice");
+    insert_ticket(&mut conn, &new_ticket(requester_id, "Board column count"));
+    assert_eq!(counts(&mut conn), (new + 1, unassigned + 1, assigned));
+}
+
+/// Unit test for the settings of the board columns: limits must be positive.
+#[test]
+fn ut_rainy_board_columns() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    assert!(upsert_column(
+        &mut conn,
+        &model::NewBoardColumn {
+            grouping: "status",
+            column_key: "open",
+            wip_limit: 0,
+        },
+    )
+    .is_err());
+}
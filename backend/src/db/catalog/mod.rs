@@ -0,0 +1,107 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{dsl::count_star, prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Retrieves the items of the catalog, ordered by name.
+///
+/// Inactive items are only included if `inactive` is `true`.
+pub fn get_items(conn: &mut PgConnection, inactive: bool) -> io::Result<Vec<model::CatalogItem>> {
+    let mut query = catalog_item::table.into_boxed();
+    if !inactive {
+        query = query.filter(catalog_item::active);
+    }
+
+    query
+        .order(catalog_item::name)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves an item of the catalog with an ID, if it exists.
+pub fn get_item_with_id(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> io::Result<Option<model::CatalogItem>> {
+    into_option(catalog_item::table.find(id).first(conn))
+}
+
+/// Checks if the name of an item is taken by an item other than the given one.
+pub fn item_name_taken(
+    conn: &mut PgConnection,
+    name: &str,
+    except: Option<Uuid>,
+) -> io::Result<bool> {
+    let mut query = catalog_item::table
+        .select(count_star())
+        .filter(catalog_item::name.eq(name))
+        .into_boxed();
+    if let Some(id) = except {
+        query = query.filter(catalog_item::id.ne(id));
+    }
+
+    query
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new item in the catalog.
+pub fn insert_item(
+    conn: &mut PgConnection,
+    item: &model::CatalogItemForm<'_>,
+) -> io::Result<model::CatalogItem> {
+    diesel::insert_into(catalog_item::table)
+        .values(item)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates an item of the catalog, returning it if it exists.
+pub fn update_item(
+    conn: &mut PgConnection,
+    id: Uuid,
+    item: &model::CatalogItemForm<'_>,
+) -> io::Result<Option<model::CatalogItem>> {
+    into_option(
+        diesel::update(catalog_item::table.find(id))
+            .set((item, catalog_item::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
+
+/// Deletes an item of the catalog, returning whether it existed.
+///
+/// The requests of the item are kept, with their copy of the form.
+pub fn delete_item(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(catalog_item::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Inserts the structured data of a ticket created from the catalog.
+pub fn insert_request(
+    conn: &mut PgConnection,
+    request: &model::NewServiceRequest,
+) -> io::Result<model::ServiceRequest> {
+    diesel::insert_into(service_request::table)
+        .values(request)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the structured data of a ticket, if it was created from the catalog.
+///
+/// The visibility of the ticket must be checked beforehand.
+pub fn get_request(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+) -> io::Result<Option<model::ServiceRequest>> {
+    into_option(service_request::table.find(ticket_id).first(conn))
+}
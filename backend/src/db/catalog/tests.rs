@@ -0,0 +1,123 @@
+use super::*;
+use crate::db::{
+    establish_connection, organisation,
+    tenant::Viewer,
+    ticket,
+    user::{self, PickFilter},
+};
+use diesel::Connection;
+use serde_json::json;
+
+/// Creates a catalog item form.
+fn item_form(name: &str, active: bool) -> model::CatalogItemForm<'_> {
+    model::CatalogItemForm {
+        name,
+        description: "",
+        form: json!([{"key": "reason", "label": "Reason", "type": "text"}]),
+        priority: "normal",
+        queue_id: None,
+        approval_chain_id: None,
+        active,
+    }
+}
+
+/// Sunny day unit test for the catalog items and their requests.
+#[test]
+fn ut_sunny_catalog() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let active = insert_item(&mut conn, &item_form("UT catalog VPN access", true))
+        .expect("error inserting item");
+    let inactive = insert_item(&mut conn, &item_form("UT catalog fax machine", false))
+        .expect("error inserting item");
+    let ids =
+        |items: Vec<model::CatalogItem>| items.into_iter().map(|item| item.id).collect::<Vec<_>>();
+    let listed = ids(get_items(&mut conn, false).expect("error retrieving items"));
+    assert!(listed.contains(&active.id) && !listed.contains(&inactive.id));
+    let listed = ids(get_items(&mut conn, true).expect("error retrieving items"));
+    assert!(listed.contains(&active.id) && listed.contains(&inactive.id));
+
+    assert!(item_name_taken(&mut conn, "UT catalog VPN access", None).expect("error checking name"));
+    assert!(
+        !item_name_taken(&mut conn, "UT catalog VPN access", Some(active.id))
+            .expect("error checking name")
+    );
+    let updated = update_item(
+        &mut conn,
+        inactive.id,
+        &item_form("UT catalog fax machine", true),
+    )
+    .expect("error updating item")
+    .expect("item was not found");
+    assert!(updated.active);
+
+    let carol = user::get_with_username(&mut conn, "carol")
+        .expect("error retrieving user from database")
+        .expect("Carol was not in the database");
+    let ticket = ticket::insert(
+        &mut conn,
+        &Viewer::system(),
+        &model::NewTicket {
+            title: "UT catalog VPN access",
+            description: "",
+            priority: "normal",
+            requester_id: carol.id,
+            organisation_id: None,
+            queue_id: None,
+            category: None,
+            custom_fields: None,
+        },
+    )
+    .expect("error inserting ticket");
+    let _ = insert_request(
+        &mut conn,
+        &model::NewServiceRequest {
+            ticket_id: ticket.id,
+            catalog_item_id: active.id,
+            form: active.form.clone(),
+            answers: json!({"reason": "Working from home"}),
+        },
+    )
+    .expect("error inserting request");
+
+    // Requests keep their form when the item is deleted
+    assert!(delete_item(&mut conn, active.id).expect("error deleting item"));
+    let request = get_request(&mut conn, ticket.id)
+        .expect("error retrieving request")
+        .expect("request was not found");
+    assert_eq!(request.catalog_item_id, None);
+    assert_eq!(request.form, active.form);
+}
+
+/// Unit test for the users that can be picked in forms.
+#[test]
+fn ut_rainy_pickable_users() {
+    let mut conn = establish_connection();
+    let carol = user::get_with_username(&mut conn, "carol")
+        .expect("error retrieving user from database")
+        .expect("Carol was not in the database");
+    let dave = user::get_with_username(&mut conn, "dave")
+        .expect("error retrieving user from database")
+        .expect("Dave was not in the database");
+    let organisation_ids = organisation::get_user_organisation_ids(&mut conn, carol.id)
+        .expect("error retrieving organisations");
+
+    let pickable = |conn: &mut PgConnection, organisation_ids: Option<&[Uuid]>, filter| {
+        user::get_pickable(conn, carol.id, organisation_ids, filter, 50)
+            .expect("error retrieving pickable users")
+    };
+    assert_eq!(
+        pickable(&mut conn, Some(&organisation_ids), PickFilter::Id(carol.id)).len(),
+        1,
+        "Carol can't pick herself"
+    );
+    assert!(
+        pickable(&mut conn, Some(&organisation_ids), PickFilter::Id(dave.id)).is_empty(),
+        "Carol can pick Dave, from another organisation"
+    );
+    assert!(pickable(&mut conn, Some(&organisation_ids), PickFilter::Text("dav")).is_empty());
+    assert_eq!(pickable(&mut conn, None, PickFilter::Text("dav")).len(), 1);
+    assert!(pickable(&mut conn, None, PickFilter::Text("%")).is_empty());
+}
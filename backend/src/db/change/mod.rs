@@ -0,0 +1,397 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::{DateTime, Utc};
+use common::{change::Risk, ticket::Status as TicketStatus};
+use diesel::{prelude::*, result::Error as DieselError, PgConnection};
+use std::{collections::HashMap, io};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+type ChangeColumns = (
+    change_request::id,
+    change_request::planned_start,
+    change_request::planned_end,
+    change_request::answers,
+    change_request::risk_score,
+    change_request::impact_score,
+    change_request::risk,
+    change_request::created_on,
+    change_request::updated_on,
+);
+
+/// Columns of the changes, except their ticket, which is loaded along with them.
+const CHANGE_COLUMNS: ChangeColumns = (
+    change_request::id,
+    change_request::planned_start,
+    change_request::planned_end,
+    change_request::answers,
+    change_request::risk_score,
+    change_request::impact_score,
+    change_request::risk,
+    change_request::created_on,
+    change_request::updated_on,
+);
+
+/// Retrieves the changes planned in a period, with their tickets, by planned start.
+///
+/// Changes are in the period if they overlap it, and the period is unbounded on missing sides.
+pub fn get_all(
+    conn: &mut PgConnection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<(model::ChangeRequest, model::Ticket)>> {
+    let mut query = change_request::table
+        .inner_join(ticket::table)
+        .select((CHANGE_COLUMNS, ticket::all_columns))
+        .into_boxed();
+    if let Some(from) = from {
+        query = query.filter(change_request::planned_end.gt(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(change_request::planned_start.lt(to));
+    }
+
+    query
+        .order((change_request::planned_start, ticket::number))
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a change with an ID, along with its ticket, if it exists.
+pub fn get_with_id(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> io::Result<Option<(model::ChangeRequest, model::Ticket)>> {
+    into_option(
+        change_request::table
+            .inner_join(ticket::table)
+            .select((CHANGE_COLUMNS, ticket::all_columns))
+            .filter(change_request::id.eq(id))
+            .first(conn),
+    )
+}
+
+/// Retrieves the IDs of the configuration items of changes, by change ID.
+pub fn get_ci_ids(
+    conn: &mut PgConnection,
+    change_ids: &[Uuid],
+) -> io::Result<HashMap<Uuid, Vec<Uuid>>> {
+    change_ci::table
+        .filter(change_ci::change_id.eq_any(change_ids))
+        .order(change_ci::ci_id)
+        .load::<(Uuid, Uuid)>(conn)
+        .map(group)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new change, tracked by a ticket, with its configuration items.
+pub fn insert(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    plan: &model::ChangePlan<'_>,
+    ci_ids: &[Uuid],
+) -> io::Result<model::ChangeRequest> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        let change: model::ChangeRequest = diesel::insert_into(change_request::table)
+            .values((plan, change_request::ticket_id.eq(ticket_id)))
+            .returning(CHANGE_COLUMNS)
+            .get_result(conn)?;
+        let _ = diesel::insert_into(change_ci::table)
+            .values(
+                ci_ids
+                    .iter()
+                    .map(|ci_id| {
+                        (
+                            change_ci::change_id.eq(change.id),
+                            change_ci::ci_id.eq(ci_id),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        Ok(change)
+    })
+    .map_err(into_io_err)
+}
+
+/// Updates the plan of a change, replacing its configuration items, and returns the change if it
+/// exists.
+pub fn update(
+    conn: &mut PgConnection,
+    id: Uuid,
+    plan: &model::ChangePlan<'_>,
+    ci_ids: &[Uuid],
+) -> io::Result<Option<model::ChangeRequest>> {
+    let conn: &PgConnection = conn;
+    let updated = conn.transaction::<_, DieselError, _>(|| {
+        let change: model::ChangeRequest = diesel::update(change_request::table.find(id))
+            .set((plan, change_request::updated_on.eq(Utc::now())))
+            .returning(CHANGE_COLUMNS)
+            .get_result(conn)?;
+        let _ =
+            diesel::delete(change_ci::table.filter(change_ci::change_id.eq(id))).execute(conn)?;
+        let _ = diesel::insert_into(change_ci::table)
+            .values(
+                ci_ids
+                    .iter()
+                    .map(|ci_id| (change_ci::change_id.eq(id), change_ci::ci_id.eq(ci_id)))
+                    .collect::<Vec<_>>(),
+            )
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        Ok(change)
+    });
+
+    into_option(updated)
+}
+
+/// Retrieves the changes overlapping a period and touching some of the given configuration items,
+/// with their tickets and the IDs of the items they touch among the given ones.
+///
+/// Closed changes, which were rejected or cancelled, don't collide.
+pub fn get_colliding_changes(
+    conn: &mut PgConnection,
+    except: Option<Uuid>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    ci_ids: &[Uuid],
+) -> io::Result<Vec<(model::ChangeRequest, model::Ticket, Vec<Uuid>)>> {
+    let mut query = change_request::table
+        .inner_join(ticket::table)
+        .select((CHANGE_COLUMNS, ticket::all_columns))
+        .filter(change_request::planned_start.lt(end))
+        .filter(change_request::planned_end.gt(start))
+        .filter(ticket::status.ne(TicketStatus::Closed.as_str()))
+        .into_boxed();
+    if let Some(id) = except {
+        query = query.filter(change_request::id.ne(id));
+    }
+    let changes: Vec<(model::ChangeRequest, model::Ticket)> = query
+        .order(change_request::planned_start)
+        .load(conn)
+        .map_err(into_io_err)?;
+
+    let change_ids = changes
+        .iter()
+        .map(|(change, _)| change.id)
+        .collect::<Vec<_>>();
+    let mut shared = change_ci::table
+        .filter(change_ci::change_id.eq_any(&change_ids))
+        .filter(change_ci::ci_id.eq_any(ci_ids))
+        .order(change_ci::ci_id)
+        .load::<(Uuid, Uuid)>(conn)
+        .map(group)
+        .map_err(into_io_err)?;
+
+    Ok(changes
+        .into_iter()
+        .filter_map(|(change, ticket)| {
+            let ci_ids = shared.remove(&change.id)?;
+            Some((change, ticket, ci_ids))
+        })
+        .collect())
+}
+
+/// Retrieves the maintenance windows overlapping a period, by start.
+///
+/// The period is unbounded on missing sides.
+pub fn get_windows(
+    conn: &mut PgConnection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> io::Result<Vec<model::MaintenanceWindow>> {
+    let mut query = maintenance_window::table.into_boxed();
+    if let Some(from) = from {
+        query = query.filter(maintenance_window::ends_on.gt(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(maintenance_window::starts_on.lt(to));
+    }
+
+    query
+        .order(maintenance_window::starts_on)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the IDs of the configuration items of maintenance windows, by window ID.
+pub fn get_window_ci_ids(
+    conn: &mut PgConnection,
+    window_ids: &[Uuid],
+) -> io::Result<HashMap<Uuid, Vec<Uuid>>> {
+    maintenance_window_ci::table
+        .filter(maintenance_window_ci::window_id.eq_any(window_ids))
+        .order(maintenance_window_ci::ci_id)
+        .load::<(Uuid, Uuid)>(conn)
+        .map(group)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new maintenance window with its configuration items.
+pub fn insert_window(
+    conn: &mut PgConnection,
+    form: &model::MaintenanceWindowForm<'_>,
+    created_by: Uuid,
+    ci_ids: &[Uuid],
+) -> io::Result<model::MaintenanceWindow> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        let window: model::MaintenanceWindow = diesel::insert_into(maintenance_window::table)
+            .values((form, maintenance_window::created_by.eq(created_by)))
+            .get_result(conn)?;
+        let _ = diesel::insert_into(maintenance_window_ci::table)
+            .values(
+                ci_ids
+                    .iter()
+                    .map(|ci_id| {
+                        (
+                            maintenance_window_ci::window_id.eq(window.id),
+                            maintenance_window_ci::ci_id.eq(ci_id),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        Ok(window)
+    })
+    .map_err(into_io_err)
+}
+
+/// Updates a maintenance window, replacing its configuration items, and returns the window if it
+/// exists.
+pub fn update_window(
+    conn: &mut PgConnection,
+    id: Uuid,
+    form: &model::MaintenanceWindowForm<'_>,
+    ci_ids: &[Uuid],
+) -> io::Result<Option<model::MaintenanceWindow>> {
+    let conn: &PgConnection = conn;
+    let updated = conn.transaction::<_, DieselError, _>(|| {
+        let window: model::MaintenanceWindow = diesel::update(maintenance_window::table.find(id))
+            .set((form, maintenance_window::updated_on.eq(Utc::now())))
+            .get_result(conn)?;
+        let _ = diesel::delete(
+            maintenance_window_ci::table.filter(maintenance_window_ci::window_id.eq(id)),
+        )
+        .execute(conn)?;
+        let _ = diesel::insert_into(maintenance_window_ci::table)
+            .values(
+                ci_ids
+                    .iter()
+                    .map(|ci_id| {
+                        (
+                            maintenance_window_ci::window_id.eq(id),
+                            maintenance_window_ci::ci_id.eq(ci_id),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        Ok(window)
+    });
+
+    into_option(updated)
+}
+
+/// Deletes a maintenance window, returning whether it existed.
+pub fn delete_window(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(maintenance_window::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the maintenance windows overlapping a period and touching some of the given
+/// configuration items, with the IDs of the items they touch among the given ones.
+pub fn get_colliding_windows(
+    conn: &mut PgConnection,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    ci_ids: &[Uuid],
+) -> io::Result<Vec<(model::MaintenanceWindow, Vec<Uuid>)>> {
+    let windows = get_windows(conn, Some(start), Some(end))?;
+    let window_ids = windows.iter().map(|window| window.id).collect::<Vec<_>>();
+    let mut shared = maintenance_window_ci::table
+        .filter(maintenance_window_ci::window_id.eq_any(&window_ids))
+        .filter(maintenance_window_ci::ci_id.eq_any(ci_ids))
+        .order(maintenance_window_ci::ci_id)
+        .load::<(Uuid, Uuid)>(conn)
+        .map(group)
+        .map_err(into_io_err)?;
+
+    Ok(windows
+        .into_iter()
+        .filter_map(|window| {
+            let ci_ids = shared.remove(&window.id)?;
+            Some((window, ci_ids))
+        })
+        .collect())
+}
+
+/// Retrieves the approval chains of the change advisory board, by risk level.
+pub fn get_cab_chains(conn: &mut PgConnection) -> io::Result<Vec<(String, Uuid)>> {
+    cab_chain::table
+        .order(cab_chain::risk)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the approval chain of the change advisory board for a risk level, if it has one.
+pub fn get_cab_chain(
+    conn: &mut PgConnection,
+    risk: Risk,
+) -> io::Result<Option<model::ApprovalChain>> {
+    into_option(
+        cab_chain::table
+            .inner_join(approval_chain::table)
+            .select(approval_chain::all_columns)
+            .filter(cab_chain::risk.eq(risk.as_str()))
+            .first(conn),
+    )
+}
+
+/// Sets the approval chain of the change advisory board for a risk level, or removes it.
+pub fn set_cab_chain(
+    conn: &mut PgConnection,
+    risk: Risk,
+    approval_chain_id: Option<Uuid>,
+) -> io::Result<()> {
+    match approval_chain_id {
+        Some(approval_chain_id) => diesel::insert_into(cab_chain::table)
+            .values((
+                cab_chain::risk.eq(risk.as_str()),
+                cab_chain::approval_chain_id.eq(approval_chain_id),
+            ))
+            .on_conflict(cab_chain::risk)
+            .do_update()
+            .set(cab_chain::approval_chain_id.eq(approval_chain_id))
+            .execute(conn),
+        None => diesel::delete(cab_chain::table.find(risk.as_str())).execute(conn),
+    }
+    .map(|_| ())
+    .map_err(into_io_err)
+}
+
+/// Groups pairs of IDs by their first ID, keeping their order.
+fn group(pairs: Vec<(Uuid, Uuid)>) -> HashMap<Uuid, Vec<Uuid>> {
+    let mut groups = HashMap::<_, Vec<_>>::new();
+    for (key, value) in pairs {
+        groups.entry(key).or_default().push(value);
+    }
+
+    groups
+}
@@ -0,0 +1,217 @@
+use super::*;
+use crate::db::{
+    approval, cmdb, establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+    tenant::Viewer,
+    ticket,
+};
+use chrono::{Duration, TimeZone};
+use diesel::Connection;
+use serde_json::json;
+
+/// Inserts configuration items with the given names, returning their IDs.
+fn insert_items(conn: &mut PgConnection, names: &[&str]) -> Vec<Uuid> {
+    let server = cmdb::insert_type(
+        conn,
+        &model::CiTypeForm {
+            name: "UT server",
+            attributes: json!([]),
+        },
+    )
+    .expect("error inserting type");
+
+    names
+        .iter()
+        .map(|name| {
+            cmdb::insert_item(
+                conn,
+                &model::ConfigurationItemForm {
+                    type_id: server.id,
+                    name,
+                    attributes: json!({}),
+                },
+            )
+            .expect("error inserting item")
+            .id
+        })
+        .collect()
+}
+
+/// Inserts a change planned for some hours from a start, touching configuration items.
+fn insert_change(
+    conn: &mut PgConnection,
+    title: &str,
+    start: DateTime<Utc>,
+    hours: i64,
+    ci_ids: &[Uuid],
+) -> model::ChangeRequest {
+    let bob = user_id(conn, "bob");
+    let ticket = insert_ticket(conn, &new_ticket(bob, title));
+
+    insert(
+        conn,
+        ticket.id,
+        &model::ChangePlan {
+            planned_start: start,
+            planned_end: start + Duration::hours(hours),
+            answers: json!({}),
+            risk_score: 0,
+            impact_score: 0,
+            risk: Risk::Low.as_str(),
+        },
+        ci_ids,
+    )
+    .expect("error inserting change")
+}
+
+/// Sunny day unit test for collisions: changes and windows overlapping in time and touching the
+/// same items collide, with the items they share.
+#[test]
+fn ut_sunny_collisions() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let ids = insert_items(&mut conn, &["ut-db", "ut-web", "ut-mail"]);
+    let start = Utc.ymd(2031, 3, 1).and_hms(22, 0, 0);
+    let upgrade = insert_change(&mut conn, "UT upgrade", start, 4, &[ids[0], ids[1]]);
+    let alice = user_id(&mut conn, "alice");
+    let window = insert_window(
+        &mut conn,
+        &model::MaintenanceWindowForm {
+            title: "UT provider maintenance",
+            starts_on: start + Duration::hours(3),
+            ends_on: start + Duration::hours(6),
+        },
+        alice,
+        &[ids[1], ids[2]],
+    )
+    .expect("error inserting window");
+
+    let end = start + Duration::hours(5);
+    let changes = get_colliding_changes(&mut conn, None, start, end, &ids)
+        .expect("error retrieving colliding changes");
+    assert_eq!(
+        changes
+            .iter()
+            .map(|(change, _, ci_ids)| (change.id, ci_ids.len()))
+            .collect::<Vec<_>>(),
+        [(upgrade.id, 2)]
+    );
+    let windows = get_colliding_windows(&mut conn, start, end, &[ids[2]])
+        .expect("error retrieving colliding windows");
+    assert_eq!(
+        windows
+            .iter()
+            .map(|(window, ci_ids)| (window.id, ci_ids.clone()))
+            .collect::<Vec<_>>(),
+        [(window.id, vec![ids[2]])]
+    );
+
+    let changes =
+        get_all(&mut conn, Some(start), Some(end), 500, 0).expect("error retrieving changes");
+    assert!(changes.iter().any(|(change, _)| change.id == upgrade.id));
+    let mut ci_ids = get_ci_ids(&mut conn, &[upgrade.id]).expect("error retrieving items");
+    let mut expected = vec![ids[0], ids[1]];
+    expected.sort_unstable();
+    assert_eq!(ci_ids.remove(&upgrade.id), Some(expected));
+}
+
+/// Rainy day unit test for collisions: changes apart in time, on other items, closed or being
+/// checked don't collide.
+#[test]
+fn ut_rainy_collisions() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let ids = insert_items(&mut conn, &["ut-db", "ut-web"]);
+    let start = Utc.ymd(2031, 3, 1).and_hms(22, 0, 0);
+    let checked = insert_change(&mut conn, "UT checked", start, 2, &[ids[0]]);
+    let _ = insert_change(
+        &mut conn,
+        "UT before",
+        start - Duration::hours(2),
+        2,
+        &[ids[0]],
+    );
+    let _ = insert_change(&mut conn, "UT other item", start, 2, &[ids[1]]);
+    let cancelled = insert_change(&mut conn, "UT cancelled", start, 2, &[ids[0]]);
+    let (_, cancelled_ticket) = get_with_id(&mut conn, cancelled.id)
+        .expect("error retrieving change")
+        .expect("change was not found");
+    let _ = ticket::update(
+        &mut conn,
+        &Viewer::system(),
+        cancelled_ticket.id,
+        &model::TicketChanges {
+            status: Some(TicketStatus::Closed.as_str()),
+            ..Default::default()
+        },
+    )
+    .expect("error closing ticket");
+
+    assert!(get_colliding_changes(
+        &mut conn,
+        Some(checked.id),
+        start,
+        start + Duration::hours(2),
+        &[ids[0]],
+    )
+    .expect("error retrieving colliding changes")
+    .is_empty());
+
+    // Updates replace the items of the change
+    let plan = model::ChangePlan {
+        planned_start: checked.planned_start,
+        planned_end: checked.planned_end,
+        answers: json!({}),
+        risk_score: 0,
+        impact_score: 0,
+        risk: Risk::Low.as_str(),
+    };
+    let _ = update(&mut conn, checked.id, &plan, &[ids[1]])
+        .expect("error updating change")
+        .expect("change was not found");
+    assert_eq!(
+        get_ci_ids(&mut conn, &[checked.id])
+            .expect("error retrieving items")
+            .remove(&checked.id),
+        Some(vec![ids[1]])
+    );
+    assert!(update(&mut conn, Uuid::new_v4(), &plan, &[])
+        .expect("error updating change")
+        .is_none());
+}
+
+/// Sunny day unit test for the approval chains of the change advisory board.
+#[test]
+fn ut_sunny_cab_chains() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let chain = approval::insert_chain(
+        &mut conn,
+        &model::ApprovalChainForm {
+            name: "UT CAB",
+            steps: json!([]),
+        },
+    )
+    .expect("error inserting chain");
+
+    set_cab_chain(&mut conn, Risk::High, Some(chain.id)).expect("error setting chain");
+    set_cab_chain(&mut conn, Risk::High, Some(chain.id)).expect("error setting chain");
+    assert_eq!(
+        get_cab_chain(&mut conn, Risk::High)
+            .expect("error retrieving chain")
+            .map(|chain| chain.id),
+        Some(chain.id)
+    );
+    assert!(get_cab_chain(&mut conn, Risk::Low)
+        .expect("error retrieving chain")
+        .is_none());
+
+    set_cab_chain(&mut conn, Risk::High, None).expect("error removing chain");
+    assert!(get_cab_chains(&mut conn)
+        .expect("error retrieving chains")
+        .iter()
+        .all(|(risk, _)| risk != Risk::High.as_str()));
+}
@@ -0,0 +1,389 @@
+use super::{
+    into_option, model,
+    schema::*,
+    tenant::{self, Viewer},
+};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{
+    dsl::count_star,
+    prelude::*,
+    result::Error as DieselError,
+    sql_query,
+    sql_types::{Int4, Uuid as SqlUuid},
+    PgConnection,
+};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Query walking the dependency graph backwards from an item, to the items impacted by its outage.
+///
+/// Paths are bounded in length, which also stops the walk on cycles. Each item is returned once,
+/// with the length of its shortest path.
+const IMPACT_QUERY: &str = "\
+WITH RECURSIVE impact (id, depth) AS (
+    SELECT r.source_id, 1 FROM ci_relationship r WHERE r.target_id = $1
+    UNION
+    SELECT r.source_id, i.depth + 1
+    FROM impact i
+    JOIN ci_relationship r ON r.target_id = i.id
+    WHERE i.depth < $2
+)
+SELECT c.id, c.type_id, c.name, MIN(i.depth) AS depth
+FROM impact i
+JOIN configuration_item c ON c.id = i.id
+WHERE c.id <> $1
+GROUP BY c.id, c.type_id, c.name
+ORDER BY depth, c.name";
+
+/// Retrieves all the types of configuration items, ordered by name.
+pub fn get_types(conn: &mut PgConnection) -> io::Result<Vec<model::CiType>> {
+    ci_type::table
+        .order(ci_type::name)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a type of configuration items with an ID, if it exists.
+pub fn get_type_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::CiType>> {
+    into_option(ci_type::table.find(id).first(conn))
+}
+
+/// Checks if the name of a type is taken by a type other than the given one.
+pub fn type_name_taken(
+    conn: &mut PgConnection,
+    name: &str,
+    except: Option<Uuid>,
+) -> io::Result<bool> {
+    let mut query = ci_type::table
+        .select(count_star())
+        .filter(ci_type::name.eq(name))
+        .into_boxed();
+    if let Some(id) = except {
+        query = query.filter(ci_type::id.ne(id));
+    }
+
+    query
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new type of configuration items.
+pub fn insert_type(
+    conn: &mut PgConnection,
+    ci_type: &model::CiTypeForm<'_>,
+) -> io::Result<model::CiType> {
+    diesel::insert_into(ci_type::table)
+        .values(ci_type)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a type of configuration items, returning it if it exists.
+///
+/// The attributes of the items are kept, even if they no longer match the type.
+pub fn update_type(
+    conn: &mut PgConnection,
+    id: Uuid,
+    form: &model::CiTypeForm<'_>,
+) -> io::Result<Option<model::CiType>> {
+    into_option(
+        diesel::update(ci_type::table.find(id))
+            .set((form, ci_type::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
+
+/// Deletes a type of configuration items, returning whether it existed.
+///
+/// Types with items can't be deleted.
+pub fn delete_type(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(ci_type::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Checks if a type has items.
+pub fn type_has_items(conn: &mut PgConnection, type_id: Uuid) -> io::Result<bool> {
+    configuration_item::table
+        .select(count_star())
+        .filter(configuration_item::type_id.eq(type_id))
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the configuration items, optionally of a type, ordered by name.
+pub fn get_items(
+    conn: &mut PgConnection,
+    type_id: Option<Uuid>,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::ConfigurationItem>> {
+    let mut query = configuration_item::table.into_boxed();
+    if let Some(type_id) = type_id {
+        query = query.filter(configuration_item::type_id.eq(type_id));
+    }
+
+    query
+        .order(configuration_item::name)
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a configuration item with an ID, if it exists.
+pub fn get_item_with_id(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> io::Result<Option<model::ConfigurationItem>> {
+    into_option(configuration_item::table.find(id).first(conn))
+}
+
+/// Retrieves the configuration items with the given names.
+pub fn get_items_with_names(
+    conn: &mut PgConnection,
+    names: &[&str],
+) -> io::Result<Vec<model::ConfigurationItem>> {
+    configuration_item::table
+        .filter(configuration_item::name.eq_any(names))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Checks if the name of an item is taken by an item other than the given one.
+pub fn item_name_taken(
+    conn: &mut PgConnection,
+    name: &str,
+    except: Option<Uuid>,
+) -> io::Result<bool> {
+    let mut query = configuration_item::table
+        .select(count_star())
+        .filter(configuration_item::name.eq(name))
+        .into_boxed();
+    if let Some(id) = except {
+        query = query.filter(configuration_item::id.ne(id));
+    }
+
+    query
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new configuration item.
+pub fn insert_item(
+    conn: &mut PgConnection,
+    item: &model::ConfigurationItemForm<'_>,
+) -> io::Result<model::ConfigurationItem> {
+    diesel::insert_into(configuration_item::table)
+        .values(item)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a configuration item, returning it if it exists.
+pub fn update_item(
+    conn: &mut PgConnection,
+    id: Uuid,
+    item: &model::ConfigurationItemForm<'_>,
+) -> io::Result<Option<model::ConfigurationItem>> {
+    into_option(
+        diesel::update(configuration_item::table.find(id))
+            .set((item, configuration_item::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
+
+/// Deletes a configuration item, along with its relationships and ticket links, returning whether
+/// it existed.
+pub fn delete_item(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(configuration_item::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Imports configuration items of a type in a single transaction, returning the numbers of created
+/// and updated items.
+///
+/// Items are matched by name, and the attributes of existing items are replaced. Names must not
+/// belong to items of other types.
+pub fn import(
+    conn: &mut PgConnection,
+    type_id: Uuid,
+    items: &[(String, serde_json::Value)],
+) -> io::Result<(usize, usize)> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        let (mut created, mut updated) = (0, 0);
+        for (name, attributes) in items {
+            let count = diesel::update(
+                configuration_item::table
+                    .filter(configuration_item::name.eq(name))
+                    .filter(configuration_item::type_id.eq(type_id)),
+            )
+            .set((
+                configuration_item::attributes.eq(attributes),
+                configuration_item::updated_on.eq(Utc::now()),
+            ))
+            .execute(conn)?;
+            if count > 0 {
+                updated += 1;
+            } else {
+                let _ = diesel::insert_into(configuration_item::table)
+                    .values(&model::ConfigurationItemForm {
+                        type_id,
+                        name,
+                        attributes: attributes.clone(),
+                    })
+                    .execute(conn)?;
+                created += 1;
+            }
+        }
+
+        Ok((created, updated))
+    })
+    .map_err(into_io_err)
+}
+
+/// Retrieves the relationships of an item, as the source or the target, oldest first.
+pub fn get_relationships(
+    conn: &mut PgConnection,
+    ci_id: Uuid,
+) -> io::Result<Vec<model::CiRelationship>> {
+    ci_relationship::table
+        .filter(
+            ci_relationship::source_id
+                .eq(ci_id)
+                .or(ci_relationship::target_id.eq(ci_id)),
+        )
+        .order((ci_relationship::created_on, ci_relationship::id))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Inserts a relationship between two items, returning it if it didn't already exist.
+pub fn relate(
+    conn: &mut PgConnection,
+    relationship: &model::NewCiRelationship<'_>,
+) -> io::Result<Option<model::CiRelationship>> {
+    diesel::insert_into(ci_relationship::table)
+        .values(relationship)
+        .on_conflict_do_nothing()
+        .get_result(conn)
+        .optional()
+        .map_err(into_io_err)
+}
+
+/// Deletes a relationship of an item, as the source or the target, returning whether it existed.
+pub fn unrelate(conn: &mut PgConnection, ci_id: Uuid, id: Uuid) -> io::Result<bool> {
+    diesel::delete(
+        ci_relationship::table.find(id).filter(
+            ci_relationship::source_id
+                .eq(ci_id)
+                .or(ci_relationship::target_id.eq(ci_id)),
+        ),
+    )
+    .execute(conn)
+    .map(|count| count > 0)
+    .map_err(into_io_err)
+}
+
+/// Retrieves the items impacted by an outage of an item, up to a number of relationships away.
+///
+/// Items are ordered by their distance to the item, then by name.
+pub fn get_impacted(
+    conn: &mut PgConnection,
+    ci_id: Uuid,
+    max_depth: i32,
+) -> io::Result<Vec<model::ImpactedItem>> {
+    sql_query(IMPACT_QUERY)
+        .bind::<SqlUuid, _>(ci_id)
+        .bind::<Int4, _>(max_depth)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the configuration items linked to a ticket, ordered by name.
+pub fn get_linked(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+) -> io::Result<Vec<model::ConfigurationItem>> {
+    configuration_item::table
+        .filter(
+            configuration_item::id.eq_any(
+                ticket_ci::table
+                    .select(ticket_ci::ci_id)
+                    .filter(ticket_ci::ticket_id.eq(ticket_id)),
+            ),
+        )
+        .order(configuration_item::name)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the tickets linked to a configuration item and visible to the viewer, newest first.
+pub fn get_linked_tickets(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    ci_id: Uuid,
+) -> io::Result<Vec<model::Ticket>> {
+    tenant::run(conn, viewer, |conn| {
+        let mut query = ticket::table
+            .filter(
+                ticket::id.eq_any(
+                    ticket_ci::table
+                        .select(ticket_ci::ticket_id)
+                        .filter(ticket_ci::ci_id.eq(ci_id)),
+                ),
+            )
+            .into_boxed();
+        if !viewer.sees_all() {
+            query = query.filter(
+                ticket::requester_id
+                    .eq(viewer.user_id)
+                    .or(ticket::organisation_id.eq_any(&viewer.organisation_ids)),
+            );
+        }
+
+        query.order(ticket::number.desc()).load(conn)
+    })
+}
+
+/// Links a configuration item to a ticket, returning whether it wasn't already linked.
+pub fn link(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    ci_id: Uuid,
+    linked_by: Uuid,
+) -> io::Result<bool> {
+    diesel::insert_into(ticket_ci::table)
+        .values((
+            ticket_ci::ticket_id.eq(ticket_id),
+            ticket_ci::ci_id.eq(ci_id),
+            ticket_ci::linked_by.eq(linked_by),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Unlinks a configuration item from a ticket, returning whether it was linked.
+pub fn unlink(conn: &mut PgConnection, ticket_id: Uuid, ci_id: Uuid) -> io::Result<bool> {
+    diesel::delete(
+        ticket_ci::table
+            .filter(ticket_ci::ticket_id.eq(ticket_id))
+            .filter(ticket_ci::ci_id.eq(ci_id)),
+    )
+    .execute(conn)
+    .map(|count| count > 0)
+    .map_err(into_io_err)
+}
@@ -0,0 +1,223 @@
+use super::*;
+use crate::db::{establish_connection, fixtures::viewer, ticket};
+use diesel::Connection;
+use serde_json::json;
+
+/// Inserts a type and items with the given names, returning their IDs.
+fn insert_items(conn: &mut PgConnection, names: &[&str]) -> (Uuid, Vec<Uuid>) {
+    let server = insert_type(
+        conn,
+        &model::CiTypeForm {
+            name: "UT server",
+            attributes: json!([]),
+        },
+    )
+    .expect("error inserting type");
+    let ids = names
+        .iter()
+        .map(|name| {
+            insert_item(
+                conn,
+                &model::ConfigurationItemForm {
+                    type_id: server.id,
+                    name,
+                    attributes: json!({}),
+                },
+            )
+            .expect("error inserting item")
+            .id
+        })
+        .collect();
+
+    (server.id, ids)
+}
+
+/// Relates a source item to a target item.
+fn relate_items(conn: &mut PgConnection, source_id: Uuid, target_id: Uuid, kind: &str) {
+    let _ = relate(
+        conn,
+        &model::NewCiRelationship {
+            source_id,
+            target_id,
+            kind,
+        },
+    )
+    .expect("error relating items")
+    .expect("relationship already existed");
+}
+
+/// Sunny day unit test for the impact of an outage: it walks the graph backwards, and items are
+/// returned once at their shortest distance.
+#[test]
+fn ut_sunny_get_impacted() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (_, ids) = insert_items(
+        &mut conn,
+        &["ut-host", "ut-vm", "ut-db", "ut-app", "ut-web"],
+    );
+    let (host, vm, db, app, web) = (ids[0], ids[1], ids[2], ids[3], ids[4]);
+    relate_items(&mut conn, vm, host, "runs_on");
+    relate_items(&mut conn, db, vm, "runs_on");
+    relate_items(&mut conn, app, db, "depends_on");
+    relate_items(&mut conn, web, app, "depends_on");
+    relate_items(&mut conn, web, vm, "runs_on");
+
+    let impacted = get_impacted(&mut conn, host, 10).expect("error retrieving impact");
+    assert_eq!(
+        impacted
+            .iter()
+            .map(|item| (item.name.as_str(), item.depth))
+            .collect::<Vec<_>>(),
+        [("ut-vm", 1), ("ut-db", 2), ("ut-web", 2), ("ut-app", 3)]
+    );
+    assert_eq!(
+        get_impacted(&mut conn, host, 1)
+            .expect("error retrieving impact")
+            .len(),
+        1
+    );
+    assert!(get_impacted(&mut conn, web, 10)
+        .expect("error retrieving impact")
+        .is_empty());
+
+    assert_eq!(
+        get_relationships(&mut conn, vm)
+            .expect("error retrieving relationships")
+            .len(),
+        3
+    );
+}
+
+/// Rainy day unit test for the impact of an outage: cycles don't loop forever, and the item in
+/// outage is not impacted by itself.
+#[test]
+fn ut_rainy_get_impacted() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (_, ids) = insert_items(&mut conn, &["ut-a", "ut-b", "ut-c"]);
+    relate_items(&mut conn, ids[1], ids[0], "depends_on");
+    relate_items(&mut conn, ids[2], ids[1], "depends_on");
+    relate_items(&mut conn, ids[0], ids[2], "depends_on");
+
+    let impacted = get_impacted(&mut conn, ids[0], 100).expect("error retrieving impact");
+    assert_eq!(
+        impacted
+            .iter()
+            .map(|item| (item.id, item.depth))
+            .collect::<Vec<_>>(),
+        [(ids[1], 1), (ids[2], 2)]
+    );
+
+    // Relationships are unique, and can't relate an item to itself
+    assert!(relate(
+        &mut conn,
+        &model::NewCiRelationship {
+            source_id: ids[1],
+            target_id: ids[0],
+            kind: "depends_on",
+        },
+    )
+    .expect("error relating items")
+    .is_none());
+    assert!(!unrelate(&mut conn, ids[2], Uuid::new_v4()).expect("error deleting relationship"));
+}
+
+/// Sunny day unit test for imports and ticket links.
+#[test]
+fn ut_sunny_import() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (server, ids) = insert_items(&mut conn, &["ut-existing"]);
+
+    let imported = import(
+        &mut conn,
+        server,
+        &[
+            ("ut-existing".to_owned(), json!({"os": "linux"})),
+            ("ut-new".to_owned(), json!({})),
+        ],
+    )
+    .expect("error importing items");
+    assert_eq!(imported, (1, 1));
+    let items = get_items_with_names(&mut conn, &["ut-existing", "ut-new"])
+        .expect("error retrieving items");
+    assert_eq!(items.len(), 2);
+    assert!(items
+        .iter()
+        .any(|item| item.id == ids[0] && item.attributes == json!({"os": "linux"})));
+    assert!(type_has_items(&mut conn, server).expect("error counting items"));
+
+    let carol = viewer(&mut conn, "carol");
+    let ticket = ticket::insert(
+        &mut conn,
+        &carol,
+        &model::NewTicket {
+            title: "UT server down",
+            description: "",
+            priority: "high",
+            requester_id: carol.user_id,
+            organisation_id: None,
+            queue_id: None,
+            category: None,
+            custom_fields: None,
+        },
+    )
+    .expect("error inserting ticket");
+    let bob = viewer(&mut conn, "bob");
+    assert!(link(&mut conn, ticket.id, ids[0], bob.user_id).expect("error linking item"));
+    assert!(!link(&mut conn, ticket.id, ids[0], bob.user_id).expect("error linking item"));
+    assert_eq!(
+        get_linked(&mut conn, ticket.id)
+            .expect("error retrieving linked items")
+            .len(),
+        1
+    );
+    assert_eq!(
+        get_linked_tickets(&mut conn, &carol, ids[0])
+            .expect("error retrieving linked tickets")
+            .len(),
+        1
+    );
+    let dave = viewer(&mut conn, "dave");
+    assert!(get_linked_tickets(&mut conn, &dave, ids[0])
+        .expect("error retrieving linked tickets")
+        .is_empty());
+
+    // Deleting an item removes its links
+    assert!(delete_item(&mut conn, ids[0]).expect("error deleting item"));
+    assert!(get_linked(&mut conn, ticket.id)
+        .expect("error retrieving linked items")
+        .is_empty());
+}
+
+/// Rainy day unit test for imports: nothing is imported if a name belongs to another type.
+#[test]
+fn ut_rainy_import() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (_, _) = insert_items(&mut conn, &["ut-taken"]);
+    let laptop = insert_type(
+        &mut conn,
+        &model::CiTypeForm {
+            name: "UT laptop",
+            attributes: json!([]),
+        },
+    )
+    .expect("error inserting type");
+
+    assert!(import(
+        &mut conn,
+        laptop.id,
+        &[
+            ("ut-fresh".to_owned(), json!({})),
+            ("ut-taken".to_owned(), json!({})),
+        ],
+    )
+    .is_err());
+    assert!(!type_has_items(&mut conn, laptop.id).expect("error counting items"));
+}
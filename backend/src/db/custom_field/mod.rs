@@ -0,0 +1,84 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{
+    dsl::count_star, prelude::*, result::Error as DieselError, sql_query, sql_types::Text,
+    PgConnection,
+};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Retrieves the custom fields of tickets, ordered by position and label.
+pub fn get_all(conn: &mut PgConnection) -> io::Result<Vec<model::CustomField>> {
+    custom_field::table
+        .order((custom_field::position, custom_field::label))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a custom field with an ID, if it exists.
+pub fn get_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::CustomField>> {
+    into_option(custom_field::table.find(id).first(conn))
+}
+
+/// Checks if a key is taken by a field.
+pub fn key_taken(conn: &mut PgConnection, key: &str) -> io::Result<bool> {
+    custom_field::table
+        .select(count_star())
+        .filter(custom_field::key.eq(key))
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new custom field.
+pub fn insert(
+    conn: &mut PgConnection,
+    field: &model::CustomFieldForm<'_>,
+) -> io::Result<model::CustomField> {
+    diesel::insert_into(custom_field::table)
+        .values(field)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a custom field, returning it if it exists.
+///
+/// The values of the tickets are kept, even if they no longer match the field.
+pub fn update(
+    conn: &mut PgConnection,
+    id: Uuid,
+    field: &model::CustomFieldForm<'_>,
+) -> io::Result<Option<model::CustomField>> {
+    into_option(
+        diesel::update(custom_field::table.find(id))
+            .set((field, custom_field::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
+
+/// Deletes a custom field along with its values in tickets, returning whether it existed.
+pub fn delete(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        let key = match diesel::delete(custom_field::table.find(id))
+            .returning(custom_field::key)
+            .get_result::<String>(conn)
+            .optional()?
+        {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+        let _ = sql_query(
+            "UPDATE ticket SET custom_fields = custom_fields - $1 WHERE custom_fields ? $1",
+        )
+        .bind::<Text, _>(key)
+        .execute(conn)?;
+
+        Ok(true)
+    })
+    .map_err(into_io_err)
+}
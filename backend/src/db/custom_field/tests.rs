@@ -0,0 +1,98 @@
+use super::*;
+use crate::db::{establish_connection, tenant::Viewer, ticket, user};
+use serde_json::json;
+
+/// Creates a custom field form.
+fn field_form<'n>(
+    key: &'n str,
+    label: &'n str,
+    categories: &'n [String],
+) -> model::CustomFieldForm<'n> {
+    model::CustomFieldForm {
+        key,
+        label,
+        kind: json!({"type": "string"}),
+        required: false,
+        categories,
+        position: 0,
+    }
+}
+
+/// Sunny day unit test for the custom fields.
+#[test]
+fn ut_sunny_custom_fields() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let hardware = ["ut_hardware".to_owned()];
+    let field = insert(
+        &mut conn,
+        &field_form("ut_asset_tag", "Asset tag", &hardware),
+    )
+    .expect("error inserting field");
+    assert!(key_taken(&mut conn, "ut_asset_tag").expect("error checking key"));
+    assert!(get_all(&mut conn)
+        .expect("error retrieving fields")
+        .iter()
+        .any(|listed| listed.id == field.id));
+
+    let updated = update(
+        &mut conn,
+        field.id,
+        &field_form("ut_asset_tag", "Asset number", &[]),
+    )
+    .expect("error updating field")
+    .expect("field was not found");
+    assert_eq!(updated.label, "Asset number");
+    assert!(updated.categories.is_empty());
+
+    // Deleting a field removes its values from tickets
+    let carol = user::get_with_username(&mut conn, "carol")
+        .expect("error retrieving user from database")
+        .expect("Carol was not in the database");
+    let inserted = ticket::insert(
+        &mut conn,
+        &Viewer::system(),
+        &model::NewTicket {
+            title: "UT custom field laptop",
+            description: "",
+            priority: "normal",
+            requester_id: carol.id,
+            organisation_id: None,
+            queue_id: None,
+            category: None,
+            custom_fields: Some(json!({"ut_asset_tag": "LT-042", "ut_other": 3})),
+        },
+    )
+    .expect("error inserting ticket");
+    assert!(delete(&mut conn, field.id).expect("error deleting field"));
+    let fetched = ticket::get_with_id(&mut conn, &Viewer::system(), inserted.id)
+        .expect("error retrieving ticket")
+        .expect("ticket was not found");
+    assert_eq!(fetched.custom_fields, json!({"ut_other": 3}));
+}
+
+/// Rainy day unit test for the custom fields.
+#[test]
+fn ut_rainy_custom_fields() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    assert!(!key_taken(&mut conn, "ut_missing").expect("error checking key"));
+    assert!(get_with_id(&mut conn, Uuid::new_v4())
+        .expect("error retrieving field")
+        .is_none());
+    assert!(update(
+        &mut conn,
+        Uuid::new_v4(),
+        &field_form("ut_missing", "Missing", &[])
+    )
+    .expect("error updating field")
+    .is_none());
+    assert!(!delete(&mut conn, Uuid::new_v4()).expect("error deleting field"));
+
+    // Keys are identifiers, enforced by the database too
+    assert!(insert(&mut conn, &field_form("UT Asset", "Asset", &[])).is_err());
+}
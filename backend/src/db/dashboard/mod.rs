@@ -0,0 +1,122 @@
+use super::{model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{
+    pg::upsert::excluded,
+    prelude::*,
+    sql_query,
+    sql_types::{BigInt, Double},
+    PgConnection,
+};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Query getting the next SLA target of the unresolved tickets, when little time is left for it.
+///
+/// The parameters are the share of the target time left under which tickets are at risk (`$1`),
+/// and the maximum number of tickets (`$2`). Tickets are responded before being resolved, so the
+/// next target is the response until the staff responds.
+const SLA_AT_RISK_QUERY: &str = "\
+SELECT t.id AS ticket_id, n.target,
+    t.created_on + n.minutes * INTERVAL '1 minute' AS due_on
+FROM ticket t
+JOIN ticket_metric m ON m.ticket_id = t.id
+JOIN sla_policy p ON p.priority = t.priority
+CROSS JOIN LATERAL (
+    SELECT CASE WHEN m.first_response_on IS NULL THEN 'response' ELSE 'resolution' END AS target,
+        CASE WHEN m.first_response_on IS NULL THEN p.response_minutes
+            ELSE p.resolution_minutes END AS minutes
+) n
+WHERE t.status IN ('new', 'open', 'pending') AND t.merged_into_id IS NULL
+    AND t.created_on + n.minutes * (1 - $1) * INTERVAL '1 minute' <= CURRENT_TIMESTAMP
+ORDER BY due_on, t.number
+LIMIT $2";
+
+/// Query getting the latest ticket activity, with the maximum number of activities as parameter
+/// (`$1`).
+///
+/// Each source is limited on its own first, so that only their latest rows are merged.
+const ACTIVITY_QUERY: &str = "\
+SELECT a.ticket_id, t.number, t.title, a.kind,
+    u.first_name || ' ' || u.last_name AS actor, a.field, a.value, a.happened_on
+FROM (
+    (SELECT id AS ticket_id, 'created' AS kind, requester_id AS actor_id,
+        NULL AS field, NULL AS value, created_on AS happened_on
+    FROM ticket ORDER BY created_on DESC LIMIT $1)
+    UNION ALL
+    (SELECT ticket_id, 'commented', author_id, NULL, NULL, created_on
+    FROM ticket_comment ORDER BY created_on DESC LIMIT $1)
+    UNION ALL
+    (SELECT (details->>'ticket_id')::uuid, 'changed', actor_id,
+        details->>'field', details->>'after', created_on
+    FROM sys_audit_log WHERE kind = 'ticket_field_change' ORDER BY created_on DESC LIMIT $1)
+) a
+JOIN ticket t ON t.id = a.ticket_id
+LEFT JOIN sys_user u ON u.id = a.actor_id
+ORDER BY a.happened_on DESC
+LIMIT $1";
+
+/// Retrieves the dashboard layout of an agent, if they stored one.
+pub fn get(conn: &mut PgConnection, user_id: Uuid) -> io::Result<Option<model::Dashboard>> {
+    dashboard::table
+        .find(user_id)
+        .first(conn)
+        .optional()
+        .map_err(into_io_err)
+}
+
+/// Stores the dashboard layout of an agent, replacing the current one.
+pub fn upsert(
+    conn: &mut PgConnection,
+    dashboard: &model::NewDashboard,
+) -> io::Result<model::Dashboard> {
+    diesel::insert_into(dashboard::table)
+        .values(dashboard)
+        .on_conflict(dashboard::user_id)
+        .do_update()
+        .set((
+            dashboard::widgets.eq(excluded(dashboard::widgets)),
+            dashboard::updated_on.eq(Utc::now()),
+        ))
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the unresolved tickets with less than a share of the time of their next SLA target
+/// left, including the ones that missed it, the most urgent first.
+pub fn get_sla_at_risk(
+    conn: &mut PgConnection,
+    share: f64,
+    limit: i64,
+) -> io::Result<Vec<(model::Ticket, model::SlaRisk)>> {
+    let risks = sql_query(SLA_AT_RISK_QUERY)
+        .bind::<Double, _>(share)
+        .bind::<BigInt, _>(limit)
+        .load::<model::SlaRisk>(conn)
+        .map_err(into_io_err)?;
+    let ids = risks.iter().map(|risk| risk.ticket_id).collect::<Vec<_>>();
+    let mut tickets = ticket::table
+        .filter(ticket::id.eq_any(&ids))
+        .load::<model::Ticket>(conn)
+        .map_err(into_io_err)?;
+
+    // Tickets could have been resolved in between
+    Ok(risks
+        .into_iter()
+        .filter_map(|risk| {
+            let index = tickets.iter().position(|t| t.id == risk.ticket_id)?;
+            Some((tickets.swap_remove(index), risk))
+        })
+        .collect())
+}
+
+/// Retrieves the latest ticket activity, newest first.
+pub fn get_activity(conn: &mut PgConnection, limit: i64) -> io::Result<Vec<model::Activity>> {
+    sql_query(ACTIVITY_QUERY)
+        .bind::<BigInt, _>(limit)
+        .load(conn)
+        .map_err(into_io_err)
+}
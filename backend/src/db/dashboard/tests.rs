@@ -0,0 +1,127 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+    tenant::Viewer,
+    ticket, user,
+};
+use chrono::Duration;
+use common::{
+    dashboard::{DashboardDTO, SlaTarget},
+    ticket::Status,
+};
+use diesel::Connection;
+use std::convert::TryFrom;
+
+/// Inserts an urgent ticket requested by Carol, opened some minutes ago, returning its ID.
+fn insert_opened_ticket(conn: &mut PgConnection, title: &str, minutes_ago: i64) -> Uuid {
+    let carol = user_id(conn, "carol");
+    let id = insert_ticket(
+        conn,
+        &model::NewTicket {
+            priority: "urgent",
+            ..new_ticket(carol, title)
+        },
+    )
+    .id;
+
+    let _ = diesel::update(super::ticket::table.find(id))
+        .set(super::ticket::created_on.eq(Utc::now() - Duration::minutes(minutes_ago)))
+        .execute(&*conn)
+        .expect("error backdating ticket");
+
+    id
+}
+
+/// Sunny day unit test for dashboards: layouts are stored per agent, and tickets close to their
+/// SLA targets and the latest activity are listed.
+#[test]
+fn ut_sunny_dashboard() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("test user was not in the database");
+
+    let mut layout = DashboardDTO::default();
+    layout.widgets.reverse();
+    let new = model::NewDashboard {
+        user_id: bob.id,
+        widgets: serde_json::to_value(&layout.widgets).expect("error serializing widgets"),
+    };
+    let _ = upsert(&mut conn, &new).expect("error storing dashboard");
+    layout.widgets.truncate(1);
+    let new = model::NewDashboard {
+        user_id: bob.id,
+        widgets: serde_json::to_value(&layout.widgets).expect("error serializing widgets"),
+    };
+    let _ = upsert(&mut conn, &new).expect("error storing dashboard");
+    let stored = get(&mut conn, bob.id)
+        .expect("error retrieving dashboard")
+        .expect("dashboard was not stored");
+    assert_eq!(
+        DashboardDTO::try_from(stored).expect("invalid stored dashboard"),
+        layout
+    );
+
+    let late = insert_opened_ticket(&mut conn, "UT dashboard late", 50);
+    let _ = ticket::insert_comment(
+        &mut conn,
+        &model::NewTicketComment {
+            ticket_id: late,
+            author_id: bob.id,
+            body: "On it",
+            source: "web",
+        },
+    )
+    .expect("error inserting comment");
+    let _ = insert_opened_ticket(&mut conn, "UT dashboard early", 10);
+    let unanswered = insert_opened_ticket(&mut conn, "UT dashboard unanswered", 50);
+
+    let at_risk = get_sla_at_risk(&mut conn, 0.25, i64::MAX).expect("error retrieving tickets");
+    let (ticket, risk) = at_risk
+        .iter()
+        .find(|(ticket, _risk)| ticket.id == unanswered)
+        .expect("ticket at risk was not listed");
+    assert_eq!(risk.target(), SlaTarget::Response);
+    assert_eq!(risk.due_on, ticket.created_on + Duration::minutes(60));
+    assert!(at_risk.iter().all(|(ticket, _risk)| ticket.id != late));
+
+    // Activity older than the one left by other tests may not be listed
+    let fresh = insert_opened_ticket(&mut conn, "UT dashboard fresh", 0);
+    let activity = get_activity(&mut conn, 5).expect("error retrieving activity");
+    assert!(activity
+        .iter()
+        .any(|a| a.ticket_id == late && a.kind == "commented" && a.actor.is_some()));
+    assert!(activity
+        .iter()
+        .any(|a| a.ticket_id == fresh && a.kind == "created"));
+}
+
+/// Rainy day unit test for dashboards: agents without a layout have none stored, and resolved
+/// tickets are never at risk.
+#[test]
+fn ut_rainy_dashboard() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    assert!(get(&mut conn, Uuid::new_v4())
+        .expect("error retrieving dashboard")
+        .is_none());
+
+    let resolved = insert_opened_ticket(&mut conn, "UT dashboard resolved", 500);
+    let _ = ticket::update(
+        &mut conn,
+        &Viewer::system(),
+        resolved,
+        &model::TicketChanges {
+            status: Some(Status::Resolved.as_str()),
+            ..Default::default()
+        },
+    )
+    .expect("error updating ticket");
+
+    let at_risk = get_sla_at_risk(&mut conn, 0.25, i64::MAX).expect("error retrieving tickets");
+    assert!(at_risk.iter().all(|(ticket, _risk)| ticket.id != resolved));
+}
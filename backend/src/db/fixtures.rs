@@ -0,0 +1,49 @@
+//! Fixtures shared by the unit tests.
+
+use super::{model, organisation, tenant::Viewer, ticket, user};
+use diesel::PgConnection;
+use uuid::Uuid;
+
+/// Gets a test user.
+pub(crate) fn user(conn: &mut PgConnection, username: &str) -> model::User {
+    user::get_with_username(conn, username)
+        .expect("error retrieving user from database")
+        .expect("test user was not in the database")
+}
+
+/// Gets the ID of a test user.
+pub(crate) fn user_id(conn: &mut PgConnection, username: &str) -> Uuid {
+    user(conn, username).id
+}
+
+/// Gets the viewer information for a test user.
+pub(crate) fn viewer(conn: &mut PgConnection, username: &str) -> Viewer {
+    let user = user(conn, username);
+
+    Viewer {
+        user_id: user.id,
+        role: user.role(),
+        organisation_ids: organisation::get_user_organisation_ids(conn, user.id)
+            .expect("error retrieving organisations"),
+    }
+}
+
+/// Builds a ticket requested by a user, with a normal priority and no organisation, queue,
+/// category or custom fields.
+pub(crate) fn new_ticket(requester_id: Uuid, title: &str) -> model::NewTicket<'_> {
+    model::NewTicket {
+        title,
+        description: "",
+        priority: "normal",
+        requester_id,
+        organisation_id: None,
+        queue_id: None,
+        category: None,
+        custom_fields: None,
+    }
+}
+
+/// Inserts a ticket, as the system.
+pub(crate) fn insert_ticket(conn: &mut PgConnection, ticket: &model::NewTicket) -> model::Ticket {
+    ticket::insert(conn, &Viewer::system(), ticket).expect("error inserting ticket")
+}
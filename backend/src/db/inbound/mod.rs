@@ -0,0 +1,75 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Inserts a new entry in the inbound email log.
+pub fn insert(
+    conn: &mut PgConnection,
+    email: &model::NewInboundEmail<'_>,
+) -> io::Result<model::InboundEmail> {
+    diesel::insert_into(inbound_email::table)
+        .values(email)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the entry of the inbound email log for a `Message-ID`, if it exists.
+pub fn get_with_message_id(
+    conn: &mut PgConnection,
+    message_id: &str,
+) -> io::Result<Option<model::InboundEmail>> {
+    into_option(
+        inbound_email::table
+            .filter(inbound_email::message_id.eq(message_id))
+            .first(conn),
+    )
+}
+
+/// Retrieves the ticket of the most recent ingested email with any of the given `Message-ID`s.
+pub fn get_ticket_id_with_message_ids(
+    conn: &mut PgConnection,
+    message_ids: &[String],
+) -> io::Result<Option<Uuid>> {
+    let ticket_id = inbound_email::table
+        .select(inbound_email::ticket_id)
+        .filter(inbound_email::message_id.eq_any(message_ids))
+        .filter(inbound_email::ticket_id.is_not_null())
+        .order(inbound_email::id.desc())
+        .first(conn);
+
+    into_option(ticket_id).map(Option::flatten)
+}
+
+/// Counts the emails received from an address since the given time.
+pub fn count_from_address_since(
+    conn: &mut PgConnection,
+    from_address: &str,
+    since: DateTime<Utc>,
+) -> io::Result<i64> {
+    inbound_email::table
+        .filter(inbound_email::from_address.eq(from_address))
+        .filter(inbound_email::created_on.ge(since))
+        .count()
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the entries of the inbound email log, newest first.
+pub fn get_log(
+    conn: &mut PgConnection,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::InboundEmail>> {
+    inbound_email::table
+        .order(inbound_email::id.desc())
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
@@ -0,0 +1,94 @@
+use super::*;
+use crate::db::{establish_connection, tenant::Viewer, ticket, user};
+use chrono::Duration;
+use diesel::Connection;
+
+/// Sunny day unit test for the inbound email log functions.
+#[test]
+fn ut_sunny_inbound_log() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let carol = user::get_with_username(&mut conn, "carol")
+        .expect("error retrieving user from database")
+        .expect("Carol was not in the database");
+    let ticket_id = ticket::insert(
+        &mut conn,
+        &Viewer::system(),
+        &model::NewTicket {
+            title: "UT inbound",
+            description: "",
+            priority: "low",
+            requester_id: carol.id,
+            organisation_id: None,
+            queue_id: None,
+            category: None,
+            custom_fields: None,
+        },
+    )
+    .expect("error inserting ticket")
+    .id;
+    let start = Utc::now() - Duration::seconds(1);
+
+    let entry = insert(
+        &mut conn,
+        &model::NewInboundEmail {
+            message_id: Some("ut-inbound-log@mail.test"),
+            from_address: "ut-inbound@mail.test",
+            subject: "UT inbound",
+            outcome: "commented",
+            reason: None,
+            ticket_id: Some(ticket_id),
+            comment_id: None,
+        },
+    )
+    .expect("error inserting entry");
+
+    assert_eq!(
+        get_with_message_id(&mut conn, "ut-inbound-log@mail.test")
+            .expect("error retrieving entry")
+            .map(|found| found.id),
+        Some(entry.id)
+    );
+    assert_eq!(
+        get_ticket_id_with_message_ids(
+            &mut conn,
+            &[
+                "unknown@mail.test".to_owned(),
+                "ut-inbound-log@mail.test".to_owned()
+            ]
+        )
+        .expect("error retrieving ticket ID"),
+        Some(ticket_id)
+    );
+    assert_eq!(
+        count_from_address_since(&mut conn, "ut-inbound@mail.test", start)
+            .expect("error counting entries"),
+        1
+    );
+    assert_eq!(
+        get_log(&mut conn, 1, 0).expect("error retrieving log")[0].id,
+        entry.id
+    );
+}
+
+/// Rainy day unit test for the inbound email log functions.
+#[test]
+fn ut_rainy_inbound_log() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    assert!(get_with_message_id(&mut conn, "ut-missing@mail.test")
+        .expect("error retrieving entry")
+        .is_none());
+    assert!(get_ticket_id_with_message_ids(&mut conn, &[])
+        .expect("error retrieving ticket ID")
+        .is_none());
+    assert_eq!(
+        count_from_address_since(&mut conn, "ut-missing@mail.test", Utc::now())
+            .expect("error counting entries"),
+        0
+    );
+}
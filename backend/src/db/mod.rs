@@ -3,12 +3,44 @@
 //! This module includes the models and schema for the MySupport application,
 //! along with helper functions to manipulate the required data.
 
+pub mod approval;
+pub mod article;
+pub mod assignment;
+pub mod audit;
+pub mod automation;
+pub mod board;
+pub mod catalog;
+pub mod change;
+pub mod cmdb;
+pub mod custom_field;
+pub mod dashboard;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod inbound;
 pub mod model;
+pub mod notification;
+pub mod organisation;
+pub mod problem;
+pub mod queue;
+pub mod relation;
+pub mod report;
 #[rustfmt::skip]
 mod schema;
+pub mod search;
+pub mod survey;
+pub mod team;
+pub mod tenant;
+pub mod ticket;
 pub mod user;
+pub mod view;
+pub mod webhook;
+pub mod work_log;
 
-use diesel::{PgConnection, QueryResult};
+use crate::into_io_err;
+use diesel::{
+    connection::{Connection as _, TransactionManager},
+    PgConnection, QueryResult,
+};
 use rocket_sync_db_pools::database;
 use std::io;
 
@@ -24,3 +56,54 @@ fn into_option<T>(res: QueryResult<T>) -> io::Result<Option<T>> {
         Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
     }
 }
+
+/// Runs the given function in a transaction, which is committed if it succeeds and rolled back
+/// otherwise.
+///
+/// Unlike [`Connection::transaction`](diesel::Connection::transaction), the function gets the
+/// connection to call the other helpers of this module.
+pub fn transaction<T, F>(conn: &mut PgConnection, f: F) -> io::Result<T>
+where
+    F: FnOnce(&mut PgConnection) -> io::Result<T>,
+{
+    conn.transaction_manager()
+        .begin_transaction(conn)
+        .map_err(into_io_err)?;
+    let result = f(conn);
+    let manager = conn.transaction_manager();
+    match result {
+        Ok(_) => manager.commit_transaction(conn).map_err(into_io_err)?,
+        Err(_) => manager.rollback_transaction(conn).map_err(into_io_err)?,
+    }
+
+    result
+}
+
+/// Escapes the wildcards of a `LIKE` pattern.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Helper function to stablish database connections in unit tests.
+///
+/// The test users are seeded the first time a connection is established.
+#[cfg(test)]
+pub(crate) fn establish_connection() -> PgConnection {
+    use diesel::{connection::SimpleConnection, Connection};
+    use std::sync::Once;
+
+    static SEED: Once = Once::new();
+    let _ = dotenv::dotenv().ok();
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let conn = PgConnection::establish(&database_url)
+        .unwrap_or_else(|e| panic!("error connecting to {}: {}", database_url, e));
+    SEED.call_once(|| {
+        conn.batch_execute(include_str!("../../tests/fixtures/users.sql"))
+            .expect("error seeding the test users");
+    });
+
+    conn
+}
@@ -0,0 +1,139 @@
+use crate::db::schema::{approval_chain, ticket_approval, ticket_approver};
+use chrono::{DateTime, Utc};
+use common::approval::{ApprovalChainDTO, ApprovalStepDTO, ApproverDTO, Decision, Status};
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+/// Structure representing an approval chain in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct ApprovalChain {
+    /// The ID of the chain.
+    pub id: Uuid,
+    /// The unique name of the chain.
+    pub name: String,
+    /// The serialized [`StepDefinition`](common::approval::StepDefinition)s of the chain.
+    pub steps: serde_json::Value,
+    /// The timestamp for the creation of the chain.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the chain record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl TryFrom<ApprovalChain> for ApprovalChainDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(chain: ApprovalChain) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: chain.id,
+            name: chain.name,
+            steps: serde_json::from_value(chain.steps)?,
+        })
+    }
+}
+
+/// Insertable approval chain, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "approval_chain"]
+pub struct ApprovalChainForm<'n> {
+    /// The unique name of the chain.
+    pub name: &'n str,
+    /// The serialized step definitions of the chain.
+    pub steps: serde_json::Value,
+}
+
+/// Structure representing the approval of a ticket in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct TicketApproval {
+    /// The serialized [`ApprovalStepDTO`]s of the approval, with their approvers resolved.
+    pub steps: serde_json::Value,
+    /// The index of the current step.
+    pub step: i32,
+    /// The status of the approval.
+    ///
+    /// It is guaranteed to be a valid [`Status`].
+    pub status: String,
+}
+
+impl TicketApproval {
+    /// Gets the status of the approval.
+    pub fn status(&self) -> Status {
+        self.status
+            .parse()
+            .expect("invalid approval status found in the database")
+    }
+
+    /// Gets the steps of the approval.
+    pub fn steps(&self) -> serde_json::Result<Vec<ApprovalStepDTO>> {
+        serde_json::from_value(self.steps.clone())
+    }
+}
+
+/// Insertable approval of a ticket.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "ticket_approval"]
+pub struct NewTicketApproval {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The serialized steps of the approval.
+    pub steps: serde_json::Value,
+}
+
+/// Structure representing an approver of a ticket in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct TicketApprover {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The index of the step of the approver.
+    pub step: i32,
+    /// The ID of the approving user.
+    pub user_id: Uuid,
+    /// The token to decide from the approval email.
+    pub token: String,
+    /// The decision of the approver, if taken.
+    ///
+    /// It is guaranteed to be a valid [`Decision`].
+    pub decision: Option<String>,
+    /// The comment of the approver about the decision.
+    pub comment: String,
+    /// The timestamp for the decision, if taken.
+    pub decided_on: Option<DateTime<Utc>>,
+    /// The timestamp for the moment the step of the approver was reached.
+    pub created_on: DateTime<Utc>,
+}
+
+impl TicketApprover {
+    /// Gets the decision of the approver, if taken.
+    pub fn decision(&self) -> Option<Decision> {
+        self.decision.as_ref().map(|decision| {
+            decision
+                .parse()
+                .expect("invalid approval decision found in the database")
+        })
+    }
+
+    /// Converts the approver to its DTO, given its username.
+    pub fn into_dto(self, username: String) -> ApproverDTO {
+        ApproverDTO {
+            step: self.step,
+            user_id: self.user_id,
+            username,
+            decision: self.decision(),
+            comment: self.comment,
+            decided_on: self.decided_on,
+        }
+    }
+}
+
+/// Insertable approver of a ticket.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "ticket_approver"]
+pub struct NewTicketApprover<'t> {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The index of the step of the approver.
+    pub step: i32,
+    /// The ID of the approving user.
+    pub user_id: Uuid,
+    /// The token to decide from the approval email.
+    pub token: &'t str,
+}
@@ -0,0 +1,170 @@
+use crate::{
+    db::schema::{article, article_category, article_revision},
+    knowledge,
+};
+use chrono::{DateTime, Utc};
+use common::article::{
+    ArticleDTO, ArticleSummaryDTO, CategoryDTO, RevisionDTO, Status, Visibility,
+};
+use uuid::Uuid;
+
+/// Structure representing a category of articles in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct ArticleCategory {
+    /// The ID of the category.
+    pub id: Uuid,
+    /// The unique name of the category.
+    pub name: String,
+    /// The description of the category.
+    pub description: String,
+    /// The timestamp for the creation of the category.
+    pub created_on: DateTime<Utc>,
+}
+
+impl From<ArticleCategory> for CategoryDTO {
+    fn from(category: ArticleCategory) -> Self {
+        Self {
+            id: category.id,
+            name: category.name,
+            description: category.description,
+        }
+    }
+}
+
+/// Insertable category of articles, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "article_category"]
+pub struct ArticleCategoryForm<'n> {
+    /// The unique name of the category.
+    pub name: &'n str,
+    /// The description of the category.
+    pub description: &'n str,
+}
+
+/// Structure representing a knowledge base article in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Article {
+    /// The ID of the article.
+    pub id: Uuid,
+    /// The ID of the category of the article.
+    pub category_id: Option<Uuid>,
+    /// The title of the latest revision.
+    pub title: String,
+    /// The Markdown body of the latest revision.
+    pub body: String,
+    /// The publication status of the article.
+    ///
+    /// It is guaranteed to be a valid [`Status`].
+    pub status: String,
+    /// The audience of the article once published.
+    ///
+    /// It is guaranteed to be a valid [`Visibility`].
+    pub visibility: String,
+    /// The number of the latest revision.
+    pub revision: i32,
+    /// The ID of the user that created the article.
+    pub author_id: Uuid,
+    /// The timestamp for the first publication of the article.
+    pub published_on: Option<DateTime<Utc>>,
+    /// The timestamp for the creation of the article.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the article record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl Article {
+    /// Gets the publication status of the article.
+    pub fn status(&self) -> Status {
+        self.status
+            .parse()
+            .expect("invalid article status found in the database")
+    }
+
+    /// Gets the audience of the article once published.
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+            .parse()
+            .expect("invalid article visibility found in the database")
+    }
+}
+
+impl From<Article> for ArticleDTO {
+    fn from(article: Article) -> Self {
+        let (status, visibility) = (article.status(), article.visibility());
+        Self {
+            id: article.id,
+            category_id: article.category_id,
+            html: knowledge::render(&article.body),
+            title: article.title,
+            body: article.body,
+            status,
+            visibility,
+            revision: article.revision,
+            author_id: article.author_id,
+            published_on: article.published_on,
+            created_on: article.created_on,
+            updated_on: article.updated_on,
+        }
+    }
+}
+
+impl From<Article> for ArticleSummaryDTO {
+    fn from(article: Article) -> Self {
+        let (status, visibility) = (article.status(), article.visibility());
+        Self {
+            id: article.id,
+            category_id: article.category_id,
+            title: article.title,
+            status,
+            visibility,
+            updated_on: article.updated_on,
+        }
+    }
+}
+
+/// Insertable article, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "article"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct ArticleForm<'n> {
+    /// The ID of the category of the article.
+    pub category_id: Option<Uuid>,
+    /// The title of the article.
+    pub title: &'n str,
+    /// The Markdown body of the article.
+    pub body: &'n str,
+    /// The publication status of the article.
+    pub status: &'n str,
+    /// The audience of the article once published.
+    pub visibility: &'n str,
+}
+
+/// Structure representing a revision of an article in the database.
+#[derive(Debug, Clone, Queryable, Insertable)]
+#[table_name = "article_revision"]
+pub struct ArticleRevision {
+    /// The ID of the article.
+    pub article_id: Uuid,
+    /// The number of the revision, starting at 1.
+    pub revision: i32,
+    /// The title of the article in this revision.
+    pub title: String,
+    /// The Markdown body of the article in this revision.
+    pub body: String,
+    /// The ID of the user that made the revision.
+    pub editor_id: Uuid,
+    /// The timestamp for the creation of the revision.
+    pub created_on: DateTime<Utc>,
+}
+
+impl From<ArticleRevision> for RevisionDTO {
+    fn from(revision: ArticleRevision) -> Self {
+        Self {
+            revision: revision.revision,
+            title: revision.title,
+            body: revision.body,
+            editor_id: revision.editor_id,
+            created_on: revision.created_on,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::db::schema::sys_audit_log;
+use chrono::{DateTime, Utc};
+use common::audit::AuditEntryDTO;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+/// Structure representing an entry of the audit log in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct AuditEntry {
+    /// The ID of the entry.
+    pub id: i64,
+    /// The kind of event.
+    pub kind: String,
+    /// The ID of the user that performed the action, if any.
+    pub actor_id: Option<Uuid>,
+    /// The IP address of the client that performed the action, if known.
+    pub ip_address: Option<String>,
+    /// The user agent of the client that performed the action, if known.
+    pub user_agent: Option<String>,
+    /// The ID of the request that generated the event.
+    pub request_id: String,
+    /// The serialized [`AuditEvent`](common::audit::AuditEvent).
+    pub details: serde_json::Value,
+    /// The timestamp of the event.
+    pub created_on: DateTime<Utc>,
+}
+
+impl TryFrom<AuditEntry> for AuditEntryDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(entry: AuditEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: entry.id,
+            created_on: entry.created_on,
+            actor_id: entry.actor_id,
+            ip_address: entry.ip_address,
+            user_agent: entry.user_agent,
+            request_id: entry.request_id,
+            event: serde_json::from_value(entry.details)?,
+        })
+    }
+}
+
+/// Insertable audit log entry.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "sys_audit_log"]
+pub struct NewAuditEntry<'n> {
+    /// The kind of event.
+    pub kind: &'n str,
+    /// The ID of the user that performed the action, if any.
+    pub actor_id: Option<Uuid>,
+    /// The IP address of the client that performed the action, if known.
+    pub ip_address: Option<&'n str>,
+    /// The user agent of the client that performed the action, if known.
+    pub user_agent: Option<&'n str>,
+    /// The ID of the request that generated the event.
+    pub request_id: &'n str,
+    /// The serialized [`AuditEvent`](common::audit::AuditEvent).
+    pub details: serde_json::Value,
+}
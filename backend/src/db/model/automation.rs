@@ -0,0 +1,118 @@
+use crate::db::schema::{automation_log, automation_rule};
+use chrono::{DateTime, Utc};
+use common::automation::{LogEntryDTO, RuleDTO};
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+/// Structure representing an automation rule in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct AutomationRule {
+    /// The ID of the rule.
+    pub id: Uuid,
+    /// The unique name of the rule.
+    pub name: String,
+    /// Whether the rule is evaluated on ticket events.
+    pub enabled: bool,
+    /// The position of the rule. Rules are evaluated in ascending order.
+    pub position: i32,
+    /// The serialized [`Condition`](common::automation::Condition) of the rule.
+    pub condition: serde_json::Value,
+    /// The serialized [`Action`](common::automation::Action)s of the rule.
+    pub actions: serde_json::Value,
+    /// The ID of the user that created the rule, if it still exists.
+    pub created_by: Option<Uuid>,
+    /// The timestamp for the creation of the rule.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the rule record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl TryFrom<AutomationRule> for RuleDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(rule: AutomationRule) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: rule.id,
+            name: rule.name,
+            enabled: rule.enabled,
+            position: rule.position,
+            condition: serde_json::from_value(rule.condition)?,
+            actions: serde_json::from_value(rule.actions)?,
+        })
+    }
+}
+
+/// Insertable automation rule, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "automation_rule"]
+pub struct AutomationRuleForm<'n> {
+    /// The unique name of the rule.
+    pub name: &'n str,
+    /// Whether the rule is evaluated on ticket events.
+    pub enabled: bool,
+    /// The position of the rule.
+    pub position: i32,
+    /// The serialized [`Condition`](common::automation::Condition) of the rule.
+    pub condition: serde_json::Value,
+    /// The serialized [`Action`](common::automation::Action)s of the rule.
+    pub actions: serde_json::Value,
+}
+
+/// Structure representing an entry of the automation execution log in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct AutomationLogEntry {
+    /// The ID of the entry.
+    pub id: i64,
+    /// The ID of the rule that was run.
+    pub rule_id: Uuid,
+    /// The ID of the ticket the rule was run on.
+    pub ticket_id: Uuid,
+    /// The ticket event that triggered the rule.
+    ///
+    /// It is guaranteed to be a valid [`Event`](common::automation::Event).
+    pub event: String,
+    /// The number of cascaded updates caused by automations before the execution.
+    pub depth: i16,
+    /// The serialized [`Action`](common::automation::Action)s that were run.
+    pub actions: serde_json::Value,
+    /// The error running the actions or their side effects, if any.
+    pub error: Option<String>,
+    /// The timestamp of the execution.
+    pub created_on: DateTime<Utc>,
+}
+
+impl TryFrom<AutomationLogEntry> for LogEntryDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(entry: AutomationLogEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: entry.id,
+            rule_id: entry.rule_id,
+            ticket_id: entry.ticket_id,
+            event: entry
+                .event
+                .parse()
+                .expect("invalid event found in the database"),
+            depth: entry.depth,
+            actions: serde_json::from_value(entry.actions)?,
+            error: entry.error,
+            created_on: entry.created_on,
+        })
+    }
+}
+
+/// Insertable automation execution log entry.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "automation_log"]
+pub struct NewAutomationLogEntry<'n> {
+    /// The ID of the rule that was run.
+    pub rule_id: Uuid,
+    /// The ID of the ticket the rule was run on.
+    pub ticket_id: Uuid,
+    /// The ticket event that triggered the rule.
+    pub event: &'n str,
+    /// The number of cascaded updates caused by automations before the execution.
+    pub depth: i16,
+    /// The serialized [`Action`](common::automation::Action)s that were run.
+    pub actions: serde_json::Value,
+}
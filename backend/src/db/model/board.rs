@@ -0,0 +1,23 @@
+use crate::db::schema::board_column;
+
+/// Structure representing the settings of a column of the ticket board in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct BoardColumn {
+    /// The key of the column, a ticket status, an assignee ID or
+    /// [`UNASSIGNED`](common::board::UNASSIGNED).
+    pub column_key: String,
+    /// The maximum number of tickets the column should hold.
+    pub wip_limit: i32,
+}
+
+/// Insertable settings of a column of the ticket board, also used to replace the stored ones.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "board_column"]
+pub struct NewBoardColumn<'n> {
+    /// The grouping of the board.
+    pub grouping: &'n str,
+    /// The key of the column.
+    pub column_key: &'n str,
+    /// The maximum number of tickets the column should hold.
+    pub wip_limit: i32,
+}
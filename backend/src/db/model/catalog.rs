@@ -0,0 +1,125 @@
+use crate::db::schema::{catalog_item, service_request};
+use chrono::{DateTime, Utc};
+use common::{
+    catalog::{CatalogItemDTO, ServiceRequestDTO},
+    ticket::Priority,
+};
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+/// Structure representing an item of the service catalog in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct CatalogItem {
+    /// The ID of the item.
+    pub id: Uuid,
+    /// The unique name of the item.
+    pub name: String,
+    /// The description of the item.
+    pub description: String,
+    /// The serialized [`FormDefinition`](common::catalog::FormDefinition) of the item.
+    pub form: serde_json::Value,
+    /// The priority of the tickets created for the item.
+    ///
+    /// It is guaranteed to be a valid [`Priority`].
+    pub priority: String,
+    /// The ID of the queue of the tickets created for the item.
+    pub queue_id: Option<Uuid>,
+    /// Whether the item can be requested.
+    pub active: bool,
+    /// The timestamp for the creation of the item.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the item record.
+    pub updated_on: DateTime<Utc>,
+    /// The ID of the approval chain of the tickets created for the item.
+    pub approval_chain_id: Option<Uuid>,
+}
+
+impl CatalogItem {
+    /// Gets the priority of the tickets created for the item.
+    pub fn priority(&self) -> Priority {
+        self.priority
+            .parse()
+            .expect("invalid priority found in the database")
+    }
+}
+
+impl TryFrom<CatalogItem> for CatalogItemDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(item: CatalogItem) -> Result<Self, Self::Error> {
+        let priority = item.priority();
+        Ok(Self {
+            id: item.id,
+            name: item.name,
+            description: item.description,
+            form: serde_json::from_value(item.form)?,
+            priority,
+            queue_id: item.queue_id,
+            active: item.active,
+            approval_chain_id: item.approval_chain_id,
+        })
+    }
+}
+
+/// Insertable catalog item, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "catalog_item"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct CatalogItemForm<'n> {
+    /// The unique name of the item.
+    pub name: &'n str,
+    /// The description of the item.
+    pub description: &'n str,
+    /// The serialized [`FormDefinition`](common::catalog::FormDefinition) of the item.
+    pub form: serde_json::Value,
+    /// The priority of the tickets created for the item.
+    pub priority: &'n str,
+    /// The ID of the queue of the tickets created for the item.
+    pub queue_id: Option<Uuid>,
+    /// Whether the item can be requested.
+    pub active: bool,
+    /// The ID of the approval chain of the tickets created for the item.
+    pub approval_chain_id: Option<Uuid>,
+}
+
+/// Structure representing the structured data of a ticket created from the catalog.
+#[derive(Debug, Clone, Queryable)]
+pub struct ServiceRequest {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the requested item, if it still exists.
+    pub catalog_item_id: Option<Uuid>,
+    /// The serialized form definition of the item at the time of the request.
+    pub form: serde_json::Value,
+    /// The serialized [`Answers`](common::catalog::Answers) of the request.
+    pub answers: serde_json::Value,
+    /// The timestamp for the creation of the request.
+    pub created_on: DateTime<Utc>,
+}
+
+impl TryFrom<ServiceRequest> for ServiceRequestDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(request: ServiceRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ticket_id: request.ticket_id,
+            catalog_item_id: request.catalog_item_id,
+            form: serde_json::from_value(request.form)?,
+            answers: serde_json::from_value(request.answers)?,
+        })
+    }
+}
+
+/// Insertable service request.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "service_request"]
+pub struct NewServiceRequest {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the requested item.
+    pub catalog_item_id: Uuid,
+    /// The serialized form definition of the item.
+    pub form: serde_json::Value,
+    /// The serialized answers of the request.
+    pub answers: serde_json::Value,
+}
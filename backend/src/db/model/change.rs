@@ -0,0 +1,124 @@
+use super::Ticket;
+use crate::db::schema::{change_request, maintenance_window};
+use chrono::{DateTime, Utc};
+use common::change::{Assessment, ChangeDTO, MaintenanceWindowDTO, Risk};
+use uuid::Uuid;
+
+/// Structure representing a change request in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct ChangeRequest {
+    /// The ID of the change.
+    pub id: Uuid,
+    /// The timestamp for the planned start of the change.
+    pub planned_start: DateTime<Utc>,
+    /// The timestamp for the planned end of the change.
+    pub planned_end: DateTime<Utc>,
+    /// The serialized [`Answers`](common::change::Answers) to the questionnaire.
+    pub answers: serde_json::Value,
+    /// The score of the risk of failure of the change.
+    pub risk_score: i32,
+    /// The score of the impact of the change.
+    pub impact_score: i32,
+    /// The risk level of the change.
+    ///
+    /// It is guaranteed to be a valid [`Risk`].
+    pub risk: String,
+    /// The timestamp for the creation of the change.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the change record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl ChangeRequest {
+    /// Gets the risk level of the change.
+    pub fn risk(&self) -> Risk {
+        self.risk
+            .parse()
+            .expect("invalid change risk found in the database")
+    }
+
+    /// Converts the change to its DTO, given its ticket and the IDs of its configuration items.
+    pub fn into_dto(self, ticket: Ticket, ci_ids: Vec<Uuid>) -> serde_json::Result<ChangeDTO> {
+        let risk = self.risk();
+        Ok(ChangeDTO {
+            id: self.id,
+            ticket: ticket.into(),
+            planned_start: self.planned_start,
+            planned_end: self.planned_end,
+            answers: serde_json::from_value(self.answers)?,
+            assessment: Assessment {
+                risk_score: self.risk_score,
+                impact_score: self.impact_score,
+                risk,
+            },
+            ci_ids,
+            created_on: self.created_on,
+            updated_on: self.updated_on,
+        })
+    }
+}
+
+/// Plan of a change, inserted along with its ticket and also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "change_request"]
+pub struct ChangePlan<'p> {
+    /// The timestamp for the planned start of the change.
+    pub planned_start: DateTime<Utc>,
+    /// The timestamp for the planned end of the change.
+    pub planned_end: DateTime<Utc>,
+    /// The serialized [`Answers`](common::change::Answers) to the questionnaire.
+    pub answers: serde_json::Value,
+    /// The score of the risk of failure of the change.
+    pub risk_score: i32,
+    /// The score of the impact of the change.
+    pub impact_score: i32,
+    /// The risk level of the change.
+    pub risk: &'p str,
+}
+
+/// Structure representing a maintenance window in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct MaintenanceWindow {
+    /// The ID of the window.
+    pub id: Uuid,
+    /// The title of the window.
+    pub title: String,
+    /// The timestamp for the start of the window.
+    pub starts_on: DateTime<Utc>,
+    /// The timestamp for the end of the window.
+    pub ends_on: DateTime<Utc>,
+    /// The ID of the user that scheduled the window.
+    pub created_by: Uuid,
+    /// The timestamp for the creation of the window.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the window record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl MaintenanceWindow {
+    /// Converts the window to its DTO, given the IDs of its configuration items.
+    pub fn into_dto(self, ci_ids: Vec<Uuid>) -> MaintenanceWindowDTO {
+        MaintenanceWindowDTO {
+            id: self.id,
+            title: self.title,
+            starts_on: self.starts_on,
+            ends_on: self.ends_on,
+            ci_ids,
+            created_by: self.created_by,
+            created_on: self.created_on,
+            updated_on: self.updated_on,
+        }
+    }
+}
+
+/// Insertable maintenance window, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "maintenance_window"]
+pub struct MaintenanceWindowForm<'n> {
+    /// The title of the window.
+    pub title: &'n str,
+    /// The timestamp for the start of the window.
+    pub starts_on: DateTime<Utc>,
+    /// The timestamp for the end of the window.
+    pub ends_on: DateTime<Utc>,
+}
@@ -0,0 +1,161 @@
+use crate::db::schema::{ci_relationship, ci_type, configuration_item};
+use chrono::{DateTime, Utc};
+use common::cmdb::{CiTypeDTO, ConfigurationItemDTO, ImpactedItemDTO, RelationshipDTO};
+use diesel::sql_types::{Int4, Uuid as SqlUuid, Varchar};
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+/// Structure representing a type of configuration items in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct CiType {
+    /// The ID of the type.
+    pub id: Uuid,
+    /// The unique name of the type.
+    pub name: String,
+    /// The serialized [`AttributeDefinition`](common::cmdb::AttributeDefinition)s of the items.
+    pub attributes: serde_json::Value,
+    /// The timestamp for the creation of the type.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the type record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl TryFrom<CiType> for CiTypeDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(ci_type: CiType) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: ci_type.id,
+            name: ci_type.name,
+            attributes: serde_json::from_value(ci_type.attributes)?,
+            created_on: ci_type.created_on,
+            updated_on: ci_type.updated_on,
+        })
+    }
+}
+
+/// Insertable type of configuration items, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "ci_type"]
+pub struct CiTypeForm<'n> {
+    /// The unique name of the type.
+    pub name: &'n str,
+    /// The serialized [`AttributeDefinition`](common::cmdb::AttributeDefinition)s of the items.
+    pub attributes: serde_json::Value,
+}
+
+/// Structure representing a configuration item in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct ConfigurationItem {
+    /// The ID of the item.
+    pub id: Uuid,
+    /// The ID of the type of the item.
+    pub type_id: Uuid,
+    /// The unique name of the item.
+    pub name: String,
+    /// The serialized [`Values`](common::custom_field::Values) of the attributes of the item.
+    pub attributes: serde_json::Value,
+    /// The timestamp for the creation of the item.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the item record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl TryFrom<ConfigurationItem> for ConfigurationItemDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(item: ConfigurationItem) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: item.id,
+            type_id: item.type_id,
+            name: item.name,
+            attributes: serde_json::from_value(item.attributes)?,
+            created_on: item.created_on,
+            updated_on: item.updated_on,
+        })
+    }
+}
+
+/// Insertable configuration item, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "configuration_item"]
+pub struct ConfigurationItemForm<'n> {
+    /// The ID of the type of the item.
+    pub type_id: Uuid,
+    /// The unique name of the item.
+    pub name: &'n str,
+    /// The serialized [`Values`](common::custom_field::Values) of the attributes of the item.
+    pub attributes: serde_json::Value,
+}
+
+/// Structure representing a relationship between configuration items in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct CiRelationship {
+    /// The ID of the relationship.
+    pub id: Uuid,
+    /// The ID of the item depending on, or running on, the target.
+    pub source_id: Uuid,
+    /// The ID of the target item.
+    pub target_id: Uuid,
+    /// The kind of the relationship.
+    ///
+    /// It is guaranteed to be a valid [`RelationKind`](common::cmdb::RelationKind).
+    pub kind: String,
+    /// The timestamp for the creation of the relationship.
+    pub created_on: DateTime<Utc>,
+}
+
+impl From<CiRelationship> for RelationshipDTO {
+    fn from(relationship: CiRelationship) -> Self {
+        Self {
+            id: relationship.id,
+            source_id: relationship.source_id,
+            target_id: relationship.target_id,
+            kind: relationship
+                .kind
+                .parse()
+                .expect("invalid relationship kind found in the database"),
+            created_on: relationship.created_on,
+        }
+    }
+}
+
+/// Insertable relationship between configuration items.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "ci_relationship"]
+pub struct NewCiRelationship<'k> {
+    /// The ID of the item depending on, or running on, the target.
+    pub source_id: Uuid,
+    /// The ID of the target item.
+    pub target_id: Uuid,
+    /// The kind of the relationship.
+    pub kind: &'k str,
+}
+
+/// Configuration item impacted by an outage of another one.
+#[derive(Debug, Clone, QueryableByName)]
+pub struct ImpactedItem {
+    /// The ID of the item.
+    #[sql_type = "SqlUuid"]
+    pub id: Uuid,
+    /// The ID of the type of the item.
+    #[sql_type = "SqlUuid"]
+    pub type_id: Uuid,
+    /// The name of the item.
+    #[sql_type = "Varchar"]
+    pub name: String,
+    /// The length of the shortest path of relationships to the item in outage.
+    #[sql_type = "Int4"]
+    pub depth: i32,
+}
+
+impl From<ImpactedItem> for ImpactedItemDTO {
+    fn from(item: ImpactedItem) -> Self {
+        Self {
+            id: item.id,
+            type_id: item.type_id,
+            name: item.name,
+            depth: item.depth,
+        }
+    }
+}
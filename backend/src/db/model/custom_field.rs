@@ -0,0 +1,62 @@
+use crate::db::schema::custom_field;
+use chrono::{DateTime, Utc};
+use common::custom_field::CustomFieldDTO;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+/// Structure representing a custom field of tickets in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct CustomField {
+    /// The ID of the field.
+    pub id: Uuid,
+    /// The unique key of the field in the values of tickets.
+    pub key: String,
+    /// The label of the field.
+    pub label: String,
+    /// The serialized [`Kind`](common::custom_field::Kind) of the field.
+    pub kind: serde_json::Value,
+    /// Whether tickets need a value for the field.
+    pub required: bool,
+    /// The categories of the tickets that have the field, or empty if all tickets have it.
+    pub categories: Vec<String>,
+    /// The position of the field in forms.
+    pub position: i32,
+    /// The timestamp for the creation of the field.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the field record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl TryFrom<CustomField> for CustomFieldDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(field: CustomField) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: field.id,
+            key: field.key,
+            label: field.label,
+            kind: serde_json::from_value(field.kind)?,
+            required: field.required,
+            categories: field.categories,
+            position: field.position,
+        })
+    }
+}
+
+/// Insertable custom field, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "custom_field"]
+pub struct CustomFieldForm<'n> {
+    /// The unique key of the field in the values of tickets.
+    pub key: &'n str,
+    /// The label of the field.
+    pub label: &'n str,
+    /// The serialized [`Kind`](common::custom_field::Kind) of the field.
+    pub kind: serde_json::Value,
+    /// Whether tickets need a value for the field.
+    pub required: bool,
+    /// The categories of the tickets that have the field, or empty if all tickets have it.
+    pub categories: &'n [String],
+    /// The position of the field in forms.
+    pub position: i32,
+}
@@ -0,0 +1,108 @@
+use crate::db::schema::dashboard;
+use chrono::{DateTime, Utc};
+use common::dashboard::{ActivityDTO, DashboardDTO, SlaTarget};
+use diesel::sql_types::{BigInt, Nullable, Text, Timestamptz, Uuid as SqlUuid, Varchar};
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+/// Structure representing the dashboard layout of an agent in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Dashboard {
+    /// The ID of the agent.
+    pub user_id: Uuid,
+    /// The serialized list of [`WidgetDTO`](common::dashboard::WidgetDTO), in order.
+    pub widgets: serde_json::Value,
+    /// The timestamp for the last update of the layout.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl TryFrom<Dashboard> for DashboardDTO {
+    type Error = serde_json::Error;
+
+    fn try_from(dashboard: Dashboard) -> Result<Self, Self::Error> {
+        Ok(Self {
+            widgets: serde_json::from_value(dashboard.widgets)?,
+        })
+    }
+}
+
+/// Insertable dashboard layout, also used to replace the stored one.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "dashboard"]
+pub struct NewDashboard {
+    /// The ID of the agent.
+    pub user_id: Uuid,
+    /// The serialized list of [`WidgetDTO`](common::dashboard::WidgetDTO), in order.
+    pub widgets: serde_json::Value,
+}
+
+/// Next SLA target of an unresolved ticket.
+#[derive(Debug, Clone, QueryableByName)]
+pub struct SlaRisk {
+    /// The ID of the ticket.
+    #[sql_type = "SqlUuid"]
+    pub ticket_id: Uuid,
+    /// The target, guaranteed to be a valid [`SlaTarget`].
+    #[sql_type = "Text"]
+    pub target: String,
+    /// When the target is due.
+    #[sql_type = "Timestamptz"]
+    pub due_on: DateTime<Utc>,
+}
+
+impl SlaRisk {
+    /// Gets the target.
+    pub fn target(&self) -> SlaTarget {
+        self.target
+            .parse()
+            .expect("invalid SLA target found in the database")
+    }
+}
+
+/// Activity on a ticket.
+#[derive(Debug, Clone, QueryableByName)]
+pub struct Activity {
+    /// The ID of the ticket.
+    #[sql_type = "SqlUuid"]
+    pub ticket_id: Uuid,
+    /// The number of the ticket.
+    #[sql_type = "BigInt"]
+    pub number: i64,
+    /// The title of the ticket.
+    #[sql_type = "Varchar"]
+    pub title: String,
+    /// The kind of activity, guaranteed to be a valid
+    /// [`ActivityKind`](common::dashboard::ActivityKind).
+    #[sql_type = "Text"]
+    pub kind: String,
+    /// The full name of the user that acted, if any.
+    #[sql_type = "Nullable<Text>"]
+    pub actor: Option<String>,
+    /// The field that changed, for changes.
+    #[sql_type = "Nullable<Text>"]
+    pub field: Option<String>,
+    /// The new value of the field that changed, for changes.
+    #[sql_type = "Nullable<Text>"]
+    pub value: Option<String>,
+    /// The timestamp of the activity.
+    #[sql_type = "Timestamptz"]
+    pub happened_on: DateTime<Utc>,
+}
+
+impl From<Activity> for ActivityDTO {
+    fn from(activity: Activity) -> Self {
+        Self {
+            ticket_id: activity.ticket_id,
+            number: activity.number,
+            title: activity.title,
+            kind: activity
+                .kind
+                .parse()
+                .expect("invalid activity kind found in the database"),
+            actor: activity.actor,
+            field: activity.field,
+            value: activity.value,
+            happened_on: activity.happened_on,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::db::schema::inbound_email;
+use chrono::{DateTime, Utc};
+use common::inbound::InboundEmailDTO;
+use uuid::Uuid;
+
+/// Structure representing an entry of the inbound email log in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct InboundEmail {
+    /// The ID of the entry.
+    pub id: i64,
+    /// The `Message-ID` of the email, without angle brackets, if it had one.
+    pub message_id: Option<String>,
+    /// The address of the sender.
+    pub from_address: String,
+    /// The subject of the email.
+    pub subject: String,
+    /// The outcome of the ingestion.
+    ///
+    /// It is guaranteed to be a valid [`Outcome`](common::inbound::Outcome).
+    pub outcome: String,
+    /// The reason of the outcome, for ignored and rejected emails.
+    pub reason: Option<String>,
+    /// The ID of the ticket created or commented, if any.
+    pub ticket_id: Option<Uuid>,
+    /// The ID of the comment created, if any.
+    pub comment_id: Option<Uuid>,
+    /// The timestamp of the ingestion.
+    pub created_on: DateTime<Utc>,
+}
+
+impl From<InboundEmail> for InboundEmailDTO {
+    fn from(email: InboundEmail) -> Self {
+        Self {
+            id: email.id,
+            message_id: email.message_id,
+            from_address: email.from_address,
+            subject: email.subject,
+            outcome: email
+                .outcome
+                .parse()
+                .expect("invalid outcome found in the database"),
+            reason: email.reason,
+            ticket_id: email.ticket_id,
+            comment_id: email.comment_id,
+            created_on: email.created_on,
+        }
+    }
+}
+
+/// Insertable entry of the inbound email log.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "inbound_email"]
+pub struct NewInboundEmail<'n> {
+    /// The `Message-ID` of the email, without angle brackets, if it had one.
+    pub message_id: Option<&'n str>,
+    /// The address of the sender.
+    pub from_address: &'n str,
+    /// The subject of the email.
+    pub subject: &'n str,
+    /// The outcome of the ingestion.
+    pub outcome: &'n str,
+    /// The reason of the outcome, for ignored and rejected emails.
+    pub reason: Option<&'n str>,
+    /// The ID of the ticket created or commented, if any.
+    pub ticket_id: Option<Uuid>,
+    /// The ID of the comment created, if any.
+    pub comment_id: Option<Uuid>,
+}
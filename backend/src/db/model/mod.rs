@@ -1,2 +1,46 @@
+pub mod approval;
+pub mod article;
+pub mod audit;
+pub mod automation;
+pub mod board;
+pub mod catalog;
+pub mod change;
+pub mod cmdb;
+pub mod custom_field;
+pub mod dashboard;
+pub mod inbound;
+pub mod notification;
+pub mod organisation;
+pub mod problem;
+pub mod report;
+pub mod search;
+pub mod survey;
+pub mod team;
+pub mod ticket;
 pub mod user;
+pub mod view;
+pub mod webhook;
+pub mod work_log;
+pub use approval::*;
+pub use article::*;
+pub use audit::*;
+pub use automation::*;
+pub use board::*;
+pub use catalog::*;
+pub use change::*;
+pub use cmdb::*;
+pub use custom_field::*;
+pub use dashboard::*;
+pub use inbound::*;
+pub use notification::*;
+pub use organisation::*;
+pub use problem::*;
+pub use report::*;
+pub use search::*;
+pub use survey::*;
+pub use team::*;
+pub use ticket::*;
 pub use user::*;
+pub use view::*;
+pub use webhook::*;
+pub use work_log::*;
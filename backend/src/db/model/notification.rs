@@ -0,0 +1,121 @@
+use crate::db::schema::{notification, notification_preference};
+use chrono::{DateTime, Utc};
+use common::notification::{Kind, NotificationDTO, PreferenceDTO};
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Structure representing a notification in the database.
+///
+/// It can also be deserialized from the JSON payload pushed by the database for new notifications.
+#[derive(Debug, Clone, Queryable, Deserialize)]
+pub struct Notification {
+    /// The ID of the notification.
+    pub id: Uuid,
+    /// The ID of the notified user.
+    pub user_id: Uuid,
+    /// The kind of the notification.
+    ///
+    /// It is guaranteed to be a valid [`Kind`].
+    pub kind: String,
+    /// The title of the notification.
+    pub title: String,
+    /// The body of the notification.
+    pub body: String,
+    /// The ID of the ticket the notification is about, if any.
+    pub ticket_id: Option<Uuid>,
+    /// The timestamp for the moment the user read the notification, if they did.
+    pub read_on: Option<DateTime<Utc>>,
+    /// The timestamp for the creation of the notification.
+    pub created_on: DateTime<Utc>,
+    /// How the notification is sent by email, if it is.
+    ///
+    /// It is guaranteed to be `immediate` or `digest`.
+    pub email: Option<String>,
+    /// The timestamp for the moment the notification was sent by email, if it was.
+    pub emailed_on: Option<DateTime<Utc>>,
+}
+
+impl Notification {
+    /// Gets the kind of the notification.
+    pub fn kind(&self) -> Kind {
+        self.kind
+            .parse()
+            .expect("invalid notification kind found in the database")
+    }
+}
+
+impl From<Notification> for NotificationDTO {
+    fn from(notification: Notification) -> Self {
+        Self {
+            id: notification.id,
+            kind: notification.kind(),
+            title: notification.title,
+            body: notification.body,
+            ticket_id: notification.ticket_id,
+            read: notification.read_on.is_some(),
+            created_on: notification.created_on,
+        }
+    }
+}
+
+/// Insertable notification.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "notification"]
+pub struct NewNotification<'n> {
+    /// The ID of the notified user.
+    pub user_id: Uuid,
+    /// The kind of the notification.
+    pub kind: &'n str,
+    /// The title of the notification.
+    pub title: &'n str,
+    /// The body of the notification.
+    pub body: &'n str,
+    /// The ID of the ticket the notification is about, if any.
+    pub ticket_id: Option<Uuid>,
+    /// How the notification is sent by email, if it is.
+    pub email: Option<&'n str>,
+}
+
+/// Structure representing a notification preference of a user in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct NotificationPreference {
+    /// The ID of the user.
+    pub user_id: Uuid,
+    /// The kind of notification.
+    ///
+    /// It is guaranteed to be a valid [`Kind`].
+    pub kind: String,
+    /// How the user receives the notifications of the kind.
+    ///
+    /// It is guaranteed to be a valid [`Delivery`](common::notification::Delivery).
+    pub delivery: String,
+    /// The timestamp for the last update of the preference.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl From<NotificationPreference> for PreferenceDTO {
+    fn from(preference: NotificationPreference) -> Self {
+        Self {
+            kind: preference
+                .kind
+                .parse()
+                .expect("invalid notification kind found in the database"),
+            delivery: preference
+                .delivery
+                .parse()
+                .expect("invalid notification delivery found in the database"),
+        }
+    }
+}
+
+/// Insertable notification preference.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "notification_preference"]
+pub struct NewNotificationPreference<'n> {
+    /// The ID of the user.
+    pub user_id: Uuid,
+    /// The kind of notification.
+    pub kind: &'n str,
+    /// How the user receives the notifications of the kind.
+    pub delivery: &'n str,
+}
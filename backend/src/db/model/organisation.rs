@@ -0,0 +1,88 @@
+use crate::db::schema::{
+    organisation, organisation_branding, organisation_domain, organisation_member,
+};
+use chrono::{DateTime, Utc};
+use common::organisation::BrandingDTO;
+use uuid::Uuid;
+
+/// Structure representing a customer organisation in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Organisation {
+    /// The ID of the organisation.
+    pub id: Uuid,
+    /// The unique name of the organisation.
+    pub name: String,
+}
+
+/// Insertable organisation.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "organisation"]
+pub struct NewOrganisation<'n> {
+    /// The unique name of the organisation.
+    pub name: &'n str,
+}
+
+/// Insertable organisation email domain.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "organisation_domain"]
+pub struct NewOrganisationDomain<'n> {
+    /// The email domain, in lowercase.
+    pub domain: &'n str,
+    /// The ID of the organisation.
+    pub organisation_id: Uuid,
+}
+
+/// Insertable organisation membership.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "organisation_member"]
+pub struct NewOrganisationMember {
+    /// The ID of the organisation.
+    pub organisation_id: Uuid,
+    /// The ID of the member.
+    pub user_id: Uuid,
+}
+
+/// Structure representing the customer portal branding of an organisation in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct OrganisationBranding {
+    /// The ID of the organisation.
+    pub organisation_id: Uuid,
+    /// The URL of the logo, either HTTPS or relative to the application, if any.
+    pub logo_url: Option<String>,
+    /// The colour of the header of the portal, as `#rrggbb`.
+    pub primary_colour: String,
+    /// The colour of the buttons and links of the portal, as `#rrggbb`.
+    pub accent_colour: String,
+    /// The text shown on the home page of the portal.
+    pub welcome_text: String,
+    /// The timestamp for the last update of the branding.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl From<OrganisationBranding> for BrandingDTO {
+    fn from(branding: OrganisationBranding) -> Self {
+        Self {
+            logo_url: branding.logo_url,
+            primary_colour: branding.primary_colour,
+            accent_colour: branding.accent_colour,
+            welcome_text: branding.welcome_text,
+        }
+    }
+}
+
+/// Insertable customer portal branding, also used to replace the stored one.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "organisation_branding"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct NewOrganisationBranding<'n> {
+    /// The ID of the organisation.
+    pub organisation_id: Uuid,
+    /// The URL of the logo, either HTTPS or relative to the application, if any.
+    pub logo_url: Option<&'n str>,
+    /// The colour of the header of the portal, as `#rrggbb`.
+    pub primary_colour: &'n str,
+    /// The colour of the buttons and links of the portal, as `#rrggbb`.
+    pub accent_colour: &'n str,
+    /// The text shown on the home page of the portal.
+    pub welcome_text: &'n str,
+}
@@ -0,0 +1,88 @@
+use crate::db::schema::problem;
+use chrono::{DateTime, Utc};
+use common::problem::{ProblemDTO, Status};
+use uuid::Uuid;
+
+/// Structure representing a problem in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Problem {
+    /// The ID of the problem.
+    pub id: Uuid,
+    /// The human readable number of the problem.
+    pub number: i64,
+    /// The title of the problem.
+    pub title: String,
+    /// The description of the problem.
+    pub description: String,
+    /// The status of the problem.
+    ///
+    /// It is guaranteed to be a valid [`Status`].
+    pub status: String,
+    /// The root cause of the problem, once known.
+    pub root_cause: String,
+    /// The workaround for the incidents of the problem, if any.
+    pub workaround: String,
+    /// The ID of the agent in charge of the problem.
+    pub owner_id: Option<Uuid>,
+    /// The ID of the knowledge base article of the known error, if published.
+    pub article_id: Option<Uuid>,
+    /// The ID of the user that created the problem.
+    pub created_by: Uuid,
+    /// The timestamp for the resolution of the problem.
+    pub resolved_on: Option<DateTime<Utc>>,
+    /// The timestamp for the creation of the problem.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the problem record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl Problem {
+    /// Gets the status of the problem.
+    pub fn status(&self) -> Status {
+        self.status
+            .parse()
+            .expect("invalid problem status found in the database")
+    }
+}
+
+impl From<Problem> for ProblemDTO {
+    fn from(problem: Problem) -> Self {
+        let status = problem.status();
+        Self {
+            id: problem.id,
+            number: problem.number,
+            title: problem.title,
+            description: problem.description,
+            status,
+            root_cause: problem.root_cause,
+            workaround: problem.workaround,
+            owner_id: problem.owner_id,
+            article_id: problem.article_id,
+            created_by: problem.created_by,
+            resolved_on: problem.resolved_on,
+            created_on: problem.created_on,
+            updated_on: problem.updated_on,
+        }
+    }
+}
+
+/// Insertable problem, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "problem"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct ProblemForm<'n> {
+    /// The title of the problem.
+    pub title: &'n str,
+    /// The description of the problem.
+    pub description: &'n str,
+    /// The status of the problem.
+    pub status: &'n str,
+    /// The root cause of the problem, once known.
+    pub root_cause: &'n str,
+    /// The workaround for the incidents of the problem, if any.
+    pub workaround: &'n str,
+    /// The ID of the agent in charge of the problem.
+    pub owner_id: Option<Uuid>,
+    /// The timestamp for the resolution of the problem.
+    pub resolved_on: Option<DateTime<Utc>>,
+}
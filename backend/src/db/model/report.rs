@@ -0,0 +1,84 @@
+use crate::db::schema::sla_policy;
+use chrono::{DateTime, NaiveDate, Utc};
+use common::{
+    report::{ReportRowDTO, SlaPolicyDTO},
+    ticket::Priority,
+};
+use diesel::sql_types::{BigInt, Date, Double, Nullable, Uuid as SqlUuid, Varchar};
+use uuid::Uuid;
+
+/// Value of a report for a period and group.
+#[derive(Debug, Clone, QueryableByName)]
+pub struct ReportRow {
+    /// The first day of the period, in the timezone of the report.
+    #[sql_type = "Date"]
+    pub period: NaiveDate,
+    /// The ID of the team or agent of the group, if any.
+    #[sql_type = "Nullable<SqlUuid>"]
+    pub group_id: Option<Uuid>,
+    /// The name of the group, if any.
+    #[sql_type = "Nullable<Varchar>"]
+    pub group_name: Option<String>,
+    /// The value of the metric, `None` if there was nothing to average.
+    #[sql_type = "Nullable<Double>"]
+    pub value: Option<f64>,
+    /// The number of tickets or surveys the value is computed from.
+    #[sql_type = "BigInt"]
+    pub count: i64,
+}
+
+impl From<ReportRow> for ReportRowDTO {
+    fn from(row: ReportRow) -> Self {
+        Self {
+            period: row.period,
+            group_id: row.group_id,
+            group: row.group_name,
+            value: row.value,
+            count: row.count,
+        }
+    }
+}
+
+/// Structure representing the service level targets of a ticket priority in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct SlaPolicy {
+    /// The ticket priority the targets are for.
+    ///
+    /// It is guaranteed to be a valid [`Priority`].
+    pub priority: String,
+    /// The minutes from the creation of a ticket to the first response of the staff.
+    pub response_minutes: i32,
+    /// The minutes from the creation of a ticket to its resolution.
+    pub resolution_minutes: i32,
+    /// The timestamp for the last update of the targets.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl SlaPolicy {
+    /// Gets the ticket priority the targets are for.
+    pub fn priority(&self) -> Priority {
+        self.priority
+            .parse()
+            .expect("invalid ticket priority found in the database")
+    }
+}
+
+impl From<SlaPolicy> for SlaPolicyDTO {
+    fn from(policy: SlaPolicy) -> Self {
+        Self {
+            priority: policy.priority(),
+            response_minutes: policy.response_minutes,
+            resolution_minutes: policy.resolution_minutes,
+        }
+    }
+}
+
+/// Changes to the service level targets of a ticket priority.
+#[derive(Debug, Clone, AsChangeset)]
+#[table_name = "sla_policy"]
+pub struct SlaPolicyForm {
+    /// The minutes from the creation of a ticket to the first response of the staff.
+    pub response_minutes: i32,
+    /// The minutes from the creation of a ticket to its resolution.
+    pub resolution_minutes: i32,
+}
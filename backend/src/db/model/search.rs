@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use common::search::{FragmentDTO, TicketHitDTO};
+use diesel::sql_types::{Float4, Int8, Nullable, Text, Timestamptz, Uuid as SqlUuid, Varchar};
+use uuid::Uuid;
+
+/// Marks the start of a highlighted piece of text.
+pub const HIGHLIGHT_START: char = '\u{E000}';
+
+/// Marks the end of a highlighted piece of text.
+pub const HIGHLIGHT_END: char = '\u{E001}';
+
+/// Ticket matching a full-text search.
+#[derive(Debug, Clone, QueryableByName)]
+pub struct TicketHit {
+    /// The ID of the ticket.
+    #[sql_type = "SqlUuid"]
+    pub id: Uuid,
+    /// The human readable number of the ticket.
+    #[sql_type = "Int8"]
+    pub number: i64,
+    /// The title of the ticket, with the matching words between [`HIGHLIGHT_START`] and
+    /// [`HIGHLIGHT_END`].
+    #[sql_type = "Text"]
+    pub title: String,
+    /// The excerpt of the ticket, with the matching words between [`HIGHLIGHT_START`] and
+    /// [`HIGHLIGHT_END`].
+    #[sql_type = "Text"]
+    pub snippet: String,
+    /// The status of the ticket.
+    ///
+    /// It is guaranteed to be a valid [`Status`](common::ticket::Status).
+    #[sql_type = "Varchar"]
+    pub status: String,
+    /// The priority of the ticket.
+    ///
+    /// It is guaranteed to be a valid [`Priority`](common::ticket::Priority).
+    #[sql_type = "Varchar"]
+    pub priority: String,
+    /// The ID of the agent assigned to the ticket, if any.
+    #[sql_type = "Nullable<SqlUuid>"]
+    pub assignee_id: Option<Uuid>,
+    /// The ID of the organisation of the ticket, if any.
+    #[sql_type = "Nullable<SqlUuid>"]
+    pub organisation_id: Option<Uuid>,
+    /// The timestamp for the creation of the ticket.
+    #[sql_type = "Timestamptz"]
+    pub created_on: DateTime<Utc>,
+    /// The relevance of the ticket for the search.
+    #[sql_type = "Float4"]
+    pub rank: f32,
+}
+
+impl From<TicketHit> for TicketHitDTO {
+    fn from(hit: TicketHit) -> Self {
+        Self {
+            id: hit.id,
+            number: hit.number,
+            title: fragments(&hit.title),
+            snippet: fragments(&hit.snippet),
+            status: hit
+                .status
+                .parse()
+                .expect("invalid status found in the database"),
+            priority: hit
+                .priority
+                .parse()
+                .expect("invalid priority found in the database"),
+            assignee_id: hit.assignee_id,
+            organisation_id: hit.organisation_id,
+            created_on: hit.created_on,
+            rank: hit.rank,
+        }
+    }
+}
+
+/// Splits a highlighted text in its highlighted and plain pieces.
+pub fn fragments(text: &str) -> Vec<FragmentDTO> {
+    let mut fragments = Vec::new();
+    let mut highlighted = false;
+
+    for piece in text.split(|c| c == HIGHLIGHT_START || c == HIGHLIGHT_END) {
+        if !piece.is_empty() {
+            fragments.push(FragmentDTO {
+                text: piece.to_owned(),
+                highlighted,
+            });
+        }
+        highlighted = !highlighted;
+    }
+
+    fragments
+}
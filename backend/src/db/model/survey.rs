@@ -0,0 +1,67 @@
+use crate::db::schema::survey;
+use chrono::{DateTime, Utc};
+use common::survey::{Rating, SurveyResultDTO};
+use uuid::Uuid;
+
+/// Structure representing a satisfaction survey of a ticket in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Survey {
+    /// The ID of the survey.
+    pub id: Uuid,
+    /// The ID of the resolved ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the agent assigned to the ticket when it was resolved, if any.
+    pub agent_id: Option<Uuid>,
+    /// The token to answer from the survey email.
+    pub token: String,
+    /// The rating given by the requester, if answered.
+    ///
+    /// It is guaranteed to be a valid [`Rating`].
+    pub rating: Option<String>,
+    /// The comment of the requester about the resolution.
+    pub comment: String,
+    /// The timestamp for the moment the survey was emailed, if it was.
+    pub sent_on: Option<DateTime<Utc>>,
+    /// The timestamp for the last answer, if answered.
+    pub answered_on: Option<DateTime<Utc>>,
+    /// The timestamp for the resolution of the ticket.
+    pub created_on: DateTime<Utc>,
+}
+
+impl Survey {
+    /// Gets the rating given by the requester, if answered.
+    pub fn rating(&self) -> Option<Rating> {
+        self.rating.as_ref().map(|rating| {
+            rating
+                .parse()
+                .expect("invalid survey rating found in the database")
+        })
+    }
+}
+
+impl From<Survey> for SurveyResultDTO {
+    fn from(survey: Survey) -> Self {
+        Self {
+            rating: survey.rating(),
+            id: survey.id,
+            ticket_id: survey.ticket_id,
+            agent_id: survey.agent_id,
+            comment: survey.comment,
+            sent_on: survey.sent_on,
+            answered_on: survey.answered_on,
+            created_on: survey.created_on,
+        }
+    }
+}
+
+/// Insertable satisfaction survey.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "survey"]
+pub struct NewSurvey<'t> {
+    /// The ID of the resolved ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the agent assigned to the ticket, if any.
+    pub agent_id: Option<Uuid>,
+    /// The token to answer from the survey email.
+    pub token: &'t str,
+}
@@ -0,0 +1,108 @@
+use crate::db::schema::{queue, team, team_member};
+use chrono::{DateTime, Utc};
+use common::team::{QueueDTO, Strategy};
+use uuid::Uuid;
+
+/// Structure representing a team of agents in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Team {
+    /// The ID of the team.
+    pub id: Uuid,
+    /// The unique name of the team.
+    pub name: String,
+}
+
+/// Insertable team.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "team"]
+pub struct NewTeam<'n> {
+    /// The unique name of the team.
+    pub name: &'n str,
+}
+
+/// Structure representing a team membership in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct TeamMember {
+    /// The ID of the team.
+    pub team_id: Uuid,
+    /// The ID of the member.
+    pub user_id: Uuid,
+    /// The skills of the member.
+    pub skills: Vec<String>,
+}
+
+/// Insertable team membership.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "team_member"]
+pub struct NewTeamMember<'n> {
+    /// The ID of the team.
+    pub team_id: Uuid,
+    /// The ID of the member.
+    pub user_id: Uuid,
+    /// The skills of the member.
+    pub skills: &'n [String],
+}
+
+/// Structure representing a queue of tickets in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Queue {
+    /// The ID of the queue.
+    pub id: Uuid,
+    /// The unique name of the queue.
+    pub name: String,
+    /// The ID of the team handling the tickets of the queue.
+    pub team_id: Uuid,
+    /// The assignment strategy of the queue.
+    ///
+    /// It is guaranteed to be a valid [`Strategy`].
+    pub strategy: String,
+    /// The skills required by the skills-based strategy.
+    pub skills: Vec<String>,
+    /// Whether new tickets without a queue enter this queue.
+    pub is_default: bool,
+    /// The ID of the last agent assigned by the round-robin strategy.
+    pub last_assignee_id: Option<Uuid>,
+    /// The timestamp for the creation of the queue.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the queue record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl Queue {
+    /// Gets the assignment strategy of the queue.
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+            .parse()
+            .expect("invalid strategy found in the database")
+    }
+}
+
+impl From<Queue> for QueueDTO {
+    fn from(queue: Queue) -> Self {
+        let strategy = queue.strategy();
+        Self {
+            id: queue.id,
+            name: queue.name,
+            team_id: queue.team_id,
+            strategy,
+            skills: queue.skills,
+            is_default: queue.is_default,
+        }
+    }
+}
+
+/// Insertable queue, also used to update all its settings.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "queue"]
+pub struct QueueForm<'n> {
+    /// The unique name of the queue.
+    pub name: &'n str,
+    /// The ID of the team handling the tickets of the queue.
+    pub team_id: Uuid,
+    /// The assignment strategy of the queue.
+    pub strategy: &'n str,
+    /// The skills required by the skills-based strategy.
+    pub skills: &'n [String],
+    /// Whether new tickets without a queue enter this queue.
+    pub is_default: bool,
+}
@@ -0,0 +1,361 @@
+use crate::db::schema::{
+    ticket, ticket_assignment, ticket_attachment, ticket_comment, ticket_link,
+};
+use chrono::{DateTime, Utc};
+use common::{
+    custom_field::Values,
+    team::AssignmentDTO,
+    ticket::{AttachmentDTO, CommentSource, LinkKind, Priority, Status, TicketDTO},
+};
+use uuid::Uuid;
+
+/// Structure representing a ticket in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Ticket {
+    /// The ID of the ticket.
+    pub id: Uuid,
+    /// The human readable number of the ticket.
+    pub number: i64,
+    /// The title of the ticket.
+    pub title: String,
+    /// The description of the ticket.
+    pub description: String,
+    /// The status of the ticket.
+    ///
+    /// It is guaranteed to be a valid [`Status`].
+    pub status: String,
+    /// The priority of the ticket.
+    ///
+    /// It is guaranteed to be a valid [`Priority`].
+    pub priority: String,
+    /// The ID of the user that requested the ticket.
+    pub requester_id: Uuid,
+    /// The ID of the agent assigned to the ticket, if any.
+    pub assignee_id: Option<Uuid>,
+    /// The ID of the organisation of the ticket, if any.
+    pub organisation_id: Option<Uuid>,
+    /// The timestamp for the creation of the ticket.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the ticket record.
+    pub updated_on: DateTime<Utc>,
+    /// The ID of the queue of the ticket, if any.
+    pub queue_id: Option<Uuid>,
+    /// The category of the ticket, if any.
+    pub category: Option<String>,
+    /// The tags of the ticket, normalized and sorted.
+    pub tags: Vec<String>,
+    /// The serialized [`Values`](common::custom_field::Values) of the custom fields of the ticket.
+    pub custom_fields: serde_json::Value,
+    /// The ID of the ticket this ticket was merged into, if it was.
+    pub merged_into_id: Option<Uuid>,
+    /// The ID of the parent ticket, if the ticket is a task of another one.
+    pub parent_id: Option<Uuid>,
+}
+
+impl Ticket {
+    /// Gets the status of the ticket.
+    pub fn status(&self) -> Status {
+        self.status
+            .parse()
+            .expect("invalid status found in the database")
+    }
+
+    /// Gets the priority of the ticket.
+    pub fn priority(&self) -> Priority {
+        self.priority
+            .parse()
+            .expect("invalid priority found in the database")
+    }
+
+    /// Gets the values of the custom fields of the ticket.
+    pub fn custom_fields(&self) -> Values {
+        serde_json::from_value(self.custom_fields.clone())
+            .expect("invalid custom fields found in the database")
+    }
+}
+
+impl From<Ticket> for TicketDTO {
+    fn from(ticket: Ticket) -> Self {
+        let (status, priority) = (ticket.status(), ticket.priority());
+        let custom_fields = ticket.custom_fields();
+        Self {
+            id: ticket.id,
+            number: ticket.number,
+            title: ticket.title,
+            description: ticket.description,
+            status,
+            priority,
+            requester_id: ticket.requester_id,
+            assignee_id: ticket.assignee_id,
+            organisation_id: ticket.organisation_id,
+            queue_id: ticket.queue_id,
+            category: ticket.category,
+            tags: ticket.tags,
+            custom_fields,
+            merged_into_id: ticket.merged_into_id,
+            parent_id: ticket.parent_id,
+            created_on: ticket.created_on,
+            updated_on: ticket.updated_on,
+        }
+    }
+}
+
+/// Insertable ticket.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "ticket"]
+pub struct NewTicket<'n> {
+    /// The title of the ticket.
+    pub title: &'n str,
+    /// The description of the ticket.
+    pub description: &'n str,
+    /// The priority of the ticket.
+    pub priority: &'n str,
+    /// The ID of the user that requested the ticket.
+    pub requester_id: Uuid,
+    /// The ID of the organisation of the ticket, if any.
+    pub organisation_id: Option<Uuid>,
+    /// The ID of the queue of the ticket, if any.
+    pub queue_id: Option<Uuid>,
+    /// The category of the ticket, if any.
+    pub category: Option<&'n str>,
+    /// The serialized values of the custom fields of the ticket, if it has any.
+    pub custom_fields: Option<serde_json::Value>,
+}
+
+/// Changes to a ticket.
+///
+/// Only the fields that are `Some` will be updated.
+#[derive(Debug, Clone, Default, AsChangeset)]
+#[table_name = "ticket"]
+pub struct TicketChanges<'n> {
+    /// The new title of the ticket.
+    pub title: Option<&'n str>,
+    /// The new status of the ticket.
+    pub status: Option<&'n str>,
+    /// The new priority of the ticket.
+    pub priority: Option<&'n str>,
+    /// The new assignee of the ticket. `Some(None)` removes the assignee.
+    pub assignee_id: Option<Option<Uuid>>,
+    /// The new queue of the ticket. `Some(None)` removes the queue.
+    pub queue_id: Option<Option<Uuid>>,
+    /// The new category of the ticket. `Some(None)` removes the category.
+    pub category: Option<Option<&'n str>>,
+    /// The new tags of the ticket.
+    pub tags: Option<&'n [String]>,
+    /// The new serialized values of the custom fields of the ticket.
+    pub custom_fields: Option<serde_json::Value>,
+}
+
+/// Structure representing a change in the assignment of a ticket in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct TicketAssignment {
+    /// The ID of the assignment change.
+    pub id: i64,
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the agent assigned before the change, if any.
+    pub previous_assignee_id: Option<Uuid>,
+    /// The ID of the agent assigned after the change, if any.
+    pub assignee_id: Option<Uuid>,
+    /// The ID of the queue of the ticket at the time of the change, if any.
+    pub queue_id: Option<Uuid>,
+    /// The strategy used, for automatic assignments.
+    ///
+    /// It is guaranteed to be a valid [`Strategy`](common::team::Strategy).
+    pub strategy: Option<String>,
+    /// The ID of the user that made the change, for manual assignments.
+    pub assigned_by: Option<Uuid>,
+    /// The timestamp of the change.
+    pub created_on: DateTime<Utc>,
+}
+
+impl From<TicketAssignment> for AssignmentDTO {
+    fn from(assignment: TicketAssignment) -> Self {
+        Self {
+            id: assignment.id,
+            ticket_id: assignment.ticket_id,
+            previous_assignee_id: assignment.previous_assignee_id,
+            assignee_id: assignment.assignee_id,
+            queue_id: assignment.queue_id,
+            strategy: assignment.strategy.map(|strategy| {
+                strategy
+                    .parse()
+                    .expect("invalid strategy found in the database")
+            }),
+            assigned_by: assignment.assigned_by,
+            created_on: assignment.created_on,
+        }
+    }
+}
+
+/// Insertable change in the assignment of a ticket.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "ticket_assignment"]
+pub struct NewTicketAssignment<'n> {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the agent assigned before the change, if any.
+    pub previous_assignee_id: Option<Uuid>,
+    /// The ID of the agent assigned after the change, if any.
+    pub assignee_id: Option<Uuid>,
+    /// The ID of the queue of the ticket at the time of the change, if any.
+    pub queue_id: Option<Uuid>,
+    /// The strategy used, for automatic assignments.
+    pub strategy: Option<&'n str>,
+    /// The ID of the user that made the change, for manual assignments.
+    pub assigned_by: Option<Uuid>,
+}
+
+/// Structure representing a comment of a ticket in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct TicketComment {
+    /// The ID of the comment.
+    pub id: Uuid,
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the author of the comment, if it still exists.
+    pub author_id: Option<Uuid>,
+    /// The body of the comment.
+    pub body: String,
+    /// The channel the comment was received from.
+    ///
+    /// It is guaranteed to be a valid [`CommentSource`].
+    pub source: String,
+    /// The timestamp for the creation of the comment.
+    pub created_on: DateTime<Utc>,
+}
+
+impl TicketComment {
+    /// Gets the channel the comment was received from.
+    pub fn source(&self) -> CommentSource {
+        self.source
+            .parse()
+            .expect("invalid comment source found in the database")
+    }
+}
+
+/// Insertable comment of a ticket.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "ticket_comment"]
+pub struct NewTicketComment<'n> {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the author of the comment.
+    pub author_id: Uuid,
+    /// The body of the comment.
+    pub body: &'n str,
+    /// The channel the comment was received from.
+    pub source: &'n str,
+}
+
+/// Structure representing an attachment of a ticket in the database, without its contents.
+#[derive(Debug, Clone, Queryable)]
+pub struct TicketAttachmentInfo {
+    /// The ID of the attachment.
+    pub id: Uuid,
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the comment the attachment was sent with, if any.
+    pub comment_id: Option<Uuid>,
+    /// The file name of the attachment.
+    pub filename: String,
+    /// The MIME type of the attachment.
+    pub content_type: String,
+    /// The size of the attachment, in bytes.
+    pub size: i32,
+}
+
+impl From<TicketAttachmentInfo> for AttachmentDTO {
+    fn from(attachment: TicketAttachmentInfo) -> Self {
+        Self {
+            id: attachment.id,
+            comment_id: attachment.comment_id,
+            filename: attachment.filename,
+            content_type: attachment.content_type,
+            size: attachment.size,
+        }
+    }
+}
+
+/// Insertable attachment of a ticket.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "ticket_attachment"]
+pub struct NewTicketAttachment<'n> {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The ID of the comment the attachment was sent with, if any.
+    pub comment_id: Option<Uuid>,
+    /// The file name of the attachment.
+    pub filename: &'n str,
+    /// The MIME type of the attachment.
+    pub content_type: &'n str,
+    /// The size of the attachment, in bytes.
+    pub size: i32,
+    /// The contents of the attachment.
+    pub data: &'n [u8],
+}
+
+/// Structure representing a link between two tickets in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct TicketLink {
+    /// The ID of the ticket the link was made from.
+    pub ticket_id: Uuid,
+    /// The ID of the linked ticket.
+    pub linked_id: Uuid,
+    /// The kind of the link, `related` or `duplicate` when the ticket duplicates the linked one.
+    pub kind: String,
+    /// The ID of the user that linked the tickets.
+    pub created_by: Uuid,
+    /// The timestamp for the creation of the link.
+    pub created_on: DateTime<Utc>,
+}
+
+impl TicketLink {
+    /// Kind of the stored related links.
+    pub const RELATED: &'static str = "related";
+
+    /// Kind of the stored duplicate links.
+    pub const DUPLICATE: &'static str = "duplicate";
+
+    /// Gets the kind of the link, seen from a ticket of the link.
+    pub fn kind_from(&self, ticket_id: Uuid) -> LinkKind {
+        match (self.kind.as_str(), self.ticket_id == ticket_id) {
+            (Self::DUPLICATE, true) => LinkKind::DuplicateOf,
+            (Self::DUPLICATE, false) => LinkKind::DuplicatedBy,
+            _ => LinkKind::Related,
+        }
+    }
+}
+
+/// Insertable link between two tickets.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "ticket_link"]
+pub struct NewTicketLink<'n> {
+    /// The ID of the ticket the link is made from.
+    pub ticket_id: Uuid,
+    /// The ID of the linked ticket.
+    pub linked_id: Uuid,
+    /// The kind of the link, `related` or `duplicate` when the ticket duplicates the linked one.
+    pub kind: &'n str,
+    /// The ID of the user linking the tickets.
+    pub created_by: Uuid,
+}
+
+impl<'n> NewTicketLink<'n> {
+    /// Creates the link of a kind from a ticket to another one, stored in the direction of
+    /// duplicates.
+    pub fn new(ticket_id: Uuid, linked_id: Uuid, kind: LinkKind, created_by: Uuid) -> Self {
+        let (ticket_id, linked_id, kind) = match kind {
+            LinkKind::Related => (ticket_id, linked_id, TicketLink::RELATED),
+            LinkKind::DuplicateOf => (ticket_id, linked_id, TicketLink::DUPLICATE),
+            LinkKind::DuplicatedBy => (linked_id, ticket_id, TicketLink::DUPLICATE),
+        };
+
+        Self {
+            ticket_id,
+            linked_id,
+            kind,
+            created_by,
+        }
+    }
+}
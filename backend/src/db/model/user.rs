@@ -1,5 +1,6 @@
-use crate::db::schema::{sys_email_registration, sys_user};
+use crate::db::schema::{sys_email_registration, sys_invitation, sys_session, sys_user};
 use chrono::{DateTime, Utc};
+use common::user::{Role, UserDTO};
 use uuid::Uuid;
 
 /// Structure representing a user in the database.
@@ -25,6 +26,33 @@ pub struct User {
     pub created_on: DateTime<Utc>,
     /// The timestamp for the last update of the user record.
     pub updated_on: DateTime<Utc>,
+    /// The role of the user.
+    ///
+    /// It is guaranteed to be a valid [`Role`].
+    pub role: String,
+}
+
+impl User {
+    /// Gets the role of the user.
+    pub fn role(&self) -> Role {
+        self.role
+            .parse()
+            .expect("invalid role found in the database")
+    }
+}
+
+impl From<User> for UserDTO {
+    fn from(user: User) -> Self {
+        let role = user.role();
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            role,
+        }
+    }
 }
 
 /// Insertable user.
@@ -45,6 +73,8 @@ pub struct NewUser<'n> {
     pub first_name: &'n str,
     /// The last name(s) of the user.
     pub last_name: &'n str,
+    /// The role of the user.
+    pub role: &'n str,
 }
 
 /// Structure representing an email registration in the database.
@@ -56,6 +86,8 @@ pub struct EmailRegistration {
     pub email: String,
     /// Creation date of the email registration.
     pub created_on: DateTime<Utc>,
+    /// Organisation matching the domain of the email, if any.
+    pub organisation_id: Option<Uuid>,
 }
 
 /// Insertable email registration.
@@ -66,4 +98,56 @@ pub struct NewEmailRegistration<'n> {
     pub code: &'n str,
     /// Email for the email registration.
     pub email: &'n str,
+    /// Organisation matching the domain of the email, if any.
+    pub organisation_id: Option<Uuid>,
+}
+
+/// Insertable login session.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "sys_session"]
+pub struct NewSession<'n> {
+    /// SHA3-256 hash of the unique session token.
+    pub token_hash: &'n [u8],
+    /// ID of the logged in user.
+    pub user_id: Uuid,
+}
+
+/// Structure representing an invitation in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Invitation {
+    /// Unique invitation code.
+    pub code: String,
+    /// Email of the invited user.
+    pub email: String,
+    /// Role that will be assigned to the invited user.
+    ///
+    /// It is guaranteed to be a valid [`Role`].
+    pub role: String,
+    /// Organisation the invited user will be a member of, if any.
+    pub organisation_id: Option<Uuid>,
+}
+
+impl Invitation {
+    /// Gets the role that will be assigned to the invited user.
+    pub fn role(&self) -> Role {
+        self.role
+            .parse()
+            .expect("invalid role found in the database")
+    }
+}
+
+/// Insertable invitation.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "sys_invitation"]
+pub struct NewInvitation<'n> {
+    /// Unique invitation code.
+    pub code: &'n str,
+    /// Email of the invited user.
+    pub email: &'n str,
+    /// Role that will be assigned to the invited user.
+    pub role: &'n str,
+    /// ID of the user that sent the invitation.
+    pub invited_by: Uuid,
+    /// Organisation the invited user will be a member of, if any.
+    pub organisation_id: Option<Uuid>,
 }
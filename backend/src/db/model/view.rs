@@ -0,0 +1,53 @@
+use crate::db::schema::ticket_view;
+use chrono::{DateTime, Utc};
+use common::view::ViewDTO;
+use uuid::Uuid;
+
+/// Structure representing a saved ticket view in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct TicketView {
+    /// The ID of the view.
+    pub id: Uuid,
+    /// The ID of the user that created the view.
+    pub owner_id: Uuid,
+    /// The name of the view, unique among the views of its owner.
+    pub name: String,
+    /// The query of the view.
+    ///
+    /// It is guaranteed to be a valid [`Query`](common::query::Query).
+    pub query: String,
+    /// Whether the view is listed for everyone.
+    pub shared: bool,
+    /// The timestamp for the creation of the view.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the view record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl From<TicketView> for ViewDTO {
+    fn from(view: TicketView) -> Self {
+        Self {
+            id: view.id,
+            owner_id: view.owner_id,
+            name: view.name,
+            query: view.query,
+            shared: view.shared,
+            created_on: view.created_on,
+            updated_on: view.updated_on,
+        }
+    }
+}
+
+/// Insertable ticket view, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "ticket_view"]
+pub struct TicketViewForm<'n> {
+    /// The ID of the user that created the view.
+    pub owner_id: Uuid,
+    /// The name of the view.
+    pub name: &'n str,
+    /// The query of the view.
+    pub query: &'n str,
+    /// Whether the view is listed for everyone.
+    pub shared: bool,
+}
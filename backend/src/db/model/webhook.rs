@@ -0,0 +1,158 @@
+use crate::db::schema::{webhook_delivery, webhook_subscription};
+use chrono::{DateTime, Utc};
+use common::webhook::{DeliveryDTO, DeliveryStatus, Event, SubscriptionDTO};
+use uuid::Uuid;
+
+/// Structure representing a webhook subscription in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct WebhookSubscription {
+    /// The ID of the subscription.
+    pub id: Uuid,
+    /// The URL the events are sent to.
+    pub url: String,
+    /// The events sent to the subscription, or all of them if empty.
+    ///
+    /// They are guaranteed to be valid [`Event`]s.
+    pub events: Vec<String>,
+    /// The secret used to sign the payloads.
+    pub secret: String,
+    /// Whether events are sent to the subscription.
+    pub enabled: bool,
+    /// The number of failed delivery attempts since the last successful one.
+    pub consecutive_failures: i32,
+    /// The ID of the user that created the subscription, if it still exists.
+    pub created_by: Option<Uuid>,
+    /// The timestamp for the creation of the subscription.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the subscription record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    /// Checks if an event is sent to the subscription.
+    pub fn accepts(&self, event: Event) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event.as_str())
+    }
+}
+
+impl From<WebhookSubscription> for SubscriptionDTO {
+    fn from(subscription: WebhookSubscription) -> Self {
+        Self {
+            id: subscription.id,
+            url: subscription.url,
+            events: subscription
+                .events
+                .iter()
+                .map(|event| event.parse().expect("invalid event found in the database"))
+                .collect(),
+            enabled: subscription.enabled,
+            consecutive_failures: subscription.consecutive_failures,
+            secret: None,
+        }
+    }
+}
+
+/// Insertable webhook subscription, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "webhook_subscription"]
+pub struct WebhookSubscriptionForm<'n> {
+    /// The URL the events are sent to.
+    pub url: &'n str,
+    /// The events sent to the subscription, or all of them if empty.
+    pub events: &'n [String],
+    /// The secret used to sign the payloads. It's kept unchanged on updates if `None`.
+    pub secret: Option<&'n str>,
+    /// Whether events are sent to the subscription.
+    pub enabled: bool,
+}
+
+/// Structure representing a delivery of an event to a webhook subscription in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct WebhookDelivery {
+    /// The ID of the delivery.
+    pub id: i64,
+    /// The ID of the subscription the event is delivered to.
+    pub subscription_id: Uuid,
+    /// The delivered event.
+    ///
+    /// It is guaranteed to be a valid [`Event`].
+    pub event: String,
+    /// The payload sent to the subscription.
+    pub payload: serde_json::Value,
+    /// The status of the delivery.
+    ///
+    /// It is guaranteed to be a valid [`DeliveryStatus`].
+    pub status: String,
+    /// The number of attempts made.
+    pub attempts: i32,
+    /// The timestamp of the next attempt, for pending deliveries.
+    pub next_attempt_on: DateTime<Utc>,
+    /// The HTTP status code of the last response, if any.
+    pub response_code: Option<i16>,
+    /// The error of the last attempt, if any.
+    pub error: Option<String>,
+    /// The timestamp for the creation of the delivery.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the delivery record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    /// Gets the status of the delivery.
+    pub fn status(&self) -> DeliveryStatus {
+        self.status
+            .parse()
+            .expect("invalid delivery status found in the database")
+    }
+}
+
+impl From<WebhookDelivery> for DeliveryDTO {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            id: delivery.id,
+            subscription_id: delivery.subscription_id,
+            event: delivery
+                .event
+                .parse()
+                .expect("invalid event found in the database"),
+            status: delivery.status(),
+            attempts: delivery.attempts,
+            next_attempt_on: delivery.next_attempt_on,
+            response_code: delivery.response_code,
+            error: delivery.error,
+            created_on: delivery.created_on,
+            updated_on: delivery.updated_on,
+        }
+    }
+}
+
+/// Insertable webhook delivery.
+#[derive(Debug, Clone, Insertable)]
+#[table_name = "webhook_delivery"]
+pub struct NewWebhookDelivery<'n> {
+    /// The ID of the subscription the event is delivered to.
+    pub subscription_id: Uuid,
+    /// The delivered event.
+    pub event: &'n str,
+    /// The payload sent to the subscription.
+    pub payload: &'n serde_json::Value,
+}
+
+/// Outcome of a delivery attempt, to be stored in the database.
+#[derive(Debug, Clone, AsChangeset)]
+#[table_name = "webhook_delivery"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct WebhookAttempt<'n> {
+    /// The status of the delivery after the attempt.
+    pub status: &'n str,
+    /// The number of attempts made, including this one.
+    pub attempts: i32,
+    /// The timestamp of the next attempt.
+    pub next_attempt_on: DateTime<Utc>,
+    /// The HTTP status code of the response, if any.
+    pub response_code: Option<i16>,
+    /// The error of the attempt, if any.
+    pub error: Option<&'n str>,
+    /// The timestamp of the attempt.
+    pub updated_on: DateTime<Utc>,
+}
@@ -0,0 +1,161 @@
+use crate::db::schema::{contract, work_log};
+use chrono::{DateTime, Utc};
+use common::work_log::{Activity, ContractDTO, TimeTotalDTO, WorkLogDTO};
+use diesel::sql_types::{BigInt, Nullable, Uuid as SqlUuid, Varchar};
+use uuid::Uuid;
+
+/// Structure representing a work log entry in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct WorkLog {
+    /// The ID of the entry.
+    pub id: Uuid,
+    /// The ID of the ticket the work was done on.
+    pub ticket_id: Uuid,
+    /// The ID of the agent that did the work.
+    pub agent_id: Uuid,
+    /// The duration of the work, in minutes.
+    pub minutes: i32,
+    /// Whether the work is charged to the organisation of the ticket.
+    pub billable: bool,
+    /// The kind of work.
+    ///
+    /// It is guaranteed to be a valid [`Activity`].
+    pub activity: String,
+    /// The description of the work.
+    pub description: String,
+    /// The timestamp for when the work was done.
+    pub worked_on: DateTime<Utc>,
+    /// The timestamp for the creation of the entry.
+    pub created_on: DateTime<Utc>,
+    /// The timestamp for the last update of the entry record.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl WorkLog {
+    /// Gets the kind of work.
+    pub fn activity(&self) -> Activity {
+        self.activity
+            .parse()
+            .expect("invalid work activity found in the database")
+    }
+}
+
+impl From<WorkLog> for WorkLogDTO {
+    fn from(log: WorkLog) -> Self {
+        Self {
+            activity: log.activity(),
+            id: log.id,
+            ticket_id: log.ticket_id,
+            agent_id: log.agent_id,
+            minutes: log.minutes,
+            billable: log.billable,
+            description: log.description,
+            worked_on: log.worked_on,
+        }
+    }
+}
+
+/// Work log entry, with the ticket, organisation and agent it is exported with.
+#[derive(Debug, Clone, Queryable)]
+pub struct WorkLogExport {
+    /// The entry.
+    pub log: WorkLog,
+    /// The number of the ticket the work was done on.
+    pub ticket_number: i64,
+    /// The name of the organisation of the ticket, if any.
+    pub organisation: Option<String>,
+    /// The username of the agent that did the work.
+    pub agent: String,
+}
+
+/// Insertable work log entry, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "work_log"]
+pub struct WorkLogForm<'n> {
+    /// The duration of the work, in minutes.
+    pub minutes: i32,
+    /// Whether the work is charged to the organisation of the ticket.
+    pub billable: bool,
+    /// The kind of work.
+    pub activity: &'n str,
+    /// The description of the work.
+    pub description: &'n str,
+    /// The timestamp for when the work was done.
+    pub worked_on: DateTime<Utc>,
+}
+
+/// Time spent for an organisation or by an agent over a period.
+#[derive(Debug, Clone, QueryableByName)]
+pub struct TimeTotal {
+    /// The ID of the organisation or agent, `None` for the tickets without organisation.
+    #[sql_type = "Nullable<SqlUuid>"]
+    pub id: Option<Uuid>,
+    /// The name of the organisation or agent.
+    #[sql_type = "Nullable<Varchar>"]
+    pub name: Option<String>,
+    /// The minutes spent.
+    #[sql_type = "BigInt"]
+    pub minutes: i64,
+    /// The billable minutes spent.
+    #[sql_type = "BigInt"]
+    pub billable_minutes: i64,
+}
+
+impl From<TimeTotal> for TimeTotalDTO {
+    fn from(total: TimeTotal) -> Self {
+        Self {
+            id: total.id,
+            name: total.name,
+            minutes: total.minutes,
+            billable_minutes: total.billable_minutes,
+        }
+    }
+}
+
+/// Structure representing a contract of an organisation in the database.
+#[derive(Debug, Clone, Queryable)]
+pub struct Contract {
+    /// The ID of the contract.
+    pub id: Uuid,
+    /// The ID of the organisation of the contract.
+    pub organisation_id: Uuid,
+    /// The name of the contract.
+    pub name: String,
+    /// The minutes of work prepaid by the organisation.
+    pub prepaid_minutes: i32,
+    /// The timestamp for the start of the contract.
+    pub starts_on: DateTime<Utc>,
+    /// The timestamp for the end of the contract, if it has one.
+    pub ends_on: Option<DateTime<Utc>>,
+}
+
+impl Contract {
+    /// Converts the contract to its DTO, given the billable minutes logged during the contract.
+    pub fn into_dto(self, used_minutes: i64) -> ContractDTO {
+        ContractDTO {
+            id: self.id,
+            organisation_id: self.organisation_id,
+            name: self.name,
+            prepaid_minutes: self.prepaid_minutes,
+            starts_on: self.starts_on,
+            ends_on: self.ends_on,
+            used_minutes,
+            balance_minutes: i64::from(self.prepaid_minutes) - used_minutes,
+        }
+    }
+}
+
+/// Insertable contract, also used to update it.
+#[derive(Debug, Clone, Insertable, AsChangeset)]
+#[table_name = "contract"]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct ContractForm<'n> {
+    /// The name of the contract.
+    pub name: &'n str,
+    /// The minutes of work prepaid by the organisation.
+    pub prepaid_minutes: i32,
+    /// The timestamp for the start of the contract.
+    pub starts_on: DateTime<Utc>,
+    /// The timestamp for the end of the contract, if it has one.
+    pub ends_on: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,255 @@
+use super::{model, schema::*};
+use crate::into_io_err;
+use chrono::{DateTime, Utc};
+use diesel::{pg::upsert::excluded, prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Inserts new notifications.
+///
+/// The database pushes them to the listening application instances.
+pub fn insert(
+    conn: &mut PgConnection,
+    notifications: &[model::NewNotification<'_>],
+) -> io::Result<Vec<model::Notification>> {
+    diesel::insert_into(notification::table)
+        .values(notifications)
+        .get_results(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the notifications of a user, newest first.
+pub fn get_for_user(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    unread_only: bool,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::Notification>> {
+    let mut query = notification::table
+        .filter(notification::user_id.eq(user_id))
+        .into_boxed();
+    if unread_only {
+        query = query.filter(notification::read_on.is_null());
+    }
+
+    query
+        .order((notification::created_on.desc(), notification::id))
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Counts the unread notifications of a user.
+pub fn count_unread(conn: &mut PgConnection, user_id: Uuid) -> io::Result<i64> {
+    notification::table
+        .filter(notification::user_id.eq(user_id))
+        .filter(notification::read_on.is_null())
+        .count()
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Marks a notification of a user as read.
+///
+/// Returns whether the notification exists. Notifications already read keep their read time.
+pub fn mark_read(conn: &mut PgConnection, user_id: Uuid, id: Uuid) -> io::Result<bool> {
+    let target = notification::table
+        .filter(notification::id.eq(id))
+        .filter(notification::user_id.eq(user_id));
+
+    let updated = diesel::update(target.filter(notification::read_on.is_null()))
+        .set(notification::read_on.eq(Utc::now()))
+        .execute(conn)
+        .map_err(into_io_err)?;
+    if updated > 0 {
+        return Ok(true);
+    }
+
+    target
+        .count()
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Marks all the notifications of a user as read.
+///
+/// Returns the number of notifications marked.
+pub fn mark_all_read(conn: &mut PgConnection, user_id: Uuid) -> io::Result<usize> {
+    diesel::update(
+        notification::table
+            .filter(notification::user_id.eq(user_id))
+            .filter(notification::read_on.is_null()),
+    )
+    .set(notification::read_on.eq(Utc::now()))
+    .execute(conn)
+    .map_err(into_io_err)
+}
+
+/// Retrieves the notification preferences of a user.
+///
+/// Kinds of notifications without a preference are not returned.
+pub fn get_preferences(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> io::Result<Vec<model::NotificationPreference>> {
+    notification_preference::table
+        .filter(notification_preference::user_id.eq(user_id))
+        .order(notification_preference::kind)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves how the given users receive a kind of notification.
+///
+/// Users without a preference for the kind are not returned.
+pub fn get_deliveries(
+    conn: &mut PgConnection,
+    user_ids: &[Uuid],
+    kind: &str,
+) -> io::Result<Vec<(Uuid, String)>> {
+    notification_preference::table
+        .select((
+            notification_preference::user_id,
+            notification_preference::delivery,
+        ))
+        .filter(notification_preference::user_id.eq_any(user_ids))
+        .filter(notification_preference::kind.eq(kind))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Inserts or updates notification preferences.
+pub fn upsert_preferences(
+    conn: &mut PgConnection,
+    preferences: &[model::NewNotificationPreference<'_>],
+) -> io::Result<()> {
+    diesel::insert_into(notification_preference::table)
+        .values(preferences)
+        .on_conflict((
+            notification_preference::user_id,
+            notification_preference::kind,
+        ))
+        .do_update()
+        .set((
+            notification_preference::delivery.eq(excluded(notification_preference::delivery)),
+            notification_preference::updated_on.eq(Utc::now()),
+        ))
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Claims the notifications pending to be sent by email in the given way, created up to a given
+/// time, oldest first.
+///
+/// The claimed notifications are marked as emailed at the given time, so that other workers
+/// don't send them again. They must be [released](release_emails) if sending them fails.
+pub fn claim_pending_emails(
+    conn: &mut PgConnection,
+    email: &str,
+    created_until: DateTime<Utc>,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> io::Result<Vec<model::Notification>> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let pending = notification::table
+            .filter(notification::email.eq(email))
+            .filter(notification::emailed_on.is_null())
+            .filter(notification::created_on.le(created_until))
+            .order((notification::created_on, notification::id))
+            .limit(limit)
+            .for_update()
+            .skip_locked()
+            .load::<model::Notification>(conn)?;
+
+        mark_emailed(conn, pending, now)
+    })
+    .map_err(into_io_err)
+}
+
+/// Retrieves the users with notifications pending to be sent by email in the given way, created
+/// up to a given time.
+pub fn get_pending_email_users(
+    conn: &mut PgConnection,
+    email: &str,
+    created_until: DateTime<Utc>,
+    limit: i64,
+) -> io::Result<Vec<Uuid>> {
+    notification::table
+        .filter(notification::email.eq(email))
+        .filter(notification::emailed_on.is_null())
+        .filter(notification::created_on.le(created_until))
+        .select(notification::user_id)
+        .distinct()
+        .order(notification::user_id)
+        .limit(limit)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Claims all the notifications of a user pending to be sent by email in the given way, created
+/// up to a given time, oldest first.
+///
+/// Like [`claim_pending_emails`], they must be [released](release_emails) if sending them fails.
+pub fn claim_pending_user_emails(
+    conn: &mut PgConnection,
+    email: &str,
+    user_id: Uuid,
+    created_until: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> io::Result<Vec<model::Notification>> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let pending = notification::table
+            .filter(notification::email.eq(email))
+            .filter(notification::user_id.eq(user_id))
+            .filter(notification::emailed_on.is_null())
+            .filter(notification::created_on.le(created_until))
+            .order((notification::created_on, notification::id))
+            .for_update()
+            .skip_locked()
+            .load::<model::Notification>(conn)?;
+
+        mark_emailed(conn, pending, now)
+    })
+    .map_err(into_io_err)
+}
+
+/// Marks notifications that were just claimed as emailed at the given time, returning them.
+fn mark_emailed(
+    conn: &PgConnection,
+    pending: Vec<model::Notification>,
+    now: DateTime<Utc>,
+) -> QueryResult<Vec<model::Notification>> {
+    let ids = pending
+        .iter()
+        .map(|notification| notification.id)
+        .collect::<Vec<_>>();
+    let _ = diesel::update(notification::table.filter(notification::id.eq_any(ids)))
+        .set(notification::emailed_on.eq(now))
+        .execute(conn)?;
+
+    Ok(pending
+        .into_iter()
+        .map(|notification| model::Notification {
+            emailed_on: Some(now),
+            ..notification
+        })
+        .collect())
+}
+
+/// Releases claimed notifications that could not be sent by email, so that they are sent again.
+pub fn release_emails(conn: &mut PgConnection, ids: &[Uuid]) -> io::Result<()> {
+    diesel::update(notification::table.filter(notification::id.eq_any(ids)))
+        .set(notification::emailed_on.eq(None::<DateTime<Utc>>))
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
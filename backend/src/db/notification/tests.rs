@@ -0,0 +1,138 @@
+use super::*;
+use crate::db::{establish_connection, user};
+use diesel::Connection;
+
+/// Inserts a notification for a user.
+fn insert_one(conn: &mut PgConnection, user_id: Uuid, title: &str) -> model::Notification {
+    insert(
+        conn,
+        &[model::NewNotification {
+            user_id,
+            kind: "ticket.commented",
+            title,
+            body: "",
+            ticket_id: None,
+            email: None,
+        }],
+    )
+    .expect("error inserting notification")
+    .remove(0)
+}
+
+/// Sunny day unit test for the notification functions.
+#[test]
+fn ut_sunny_notifications() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("Bob was not in the database");
+    let _ = mark_all_read(&mut conn, bob.id).expect("error marking notifications as read");
+
+    let first = insert_one(&mut conn, bob.id, "UT first");
+    let second = insert_one(&mut conn, bob.id, "UT second");
+    assert!(first.read_on.is_none());
+    assert_eq!(
+        count_unread(&mut conn, bob.id).expect("error counting notifications"),
+        2
+    );
+
+    assert!(mark_read(&mut conn, bob.id, first.id).expect("error marking notification"));
+    // Marking it again keeps the read time
+    assert!(mark_read(&mut conn, bob.id, first.id).expect("error marking notification"));
+    let unread = get_for_user(&mut conn, bob.id, true, 10, 0)
+        .expect("error retrieving notifications")
+        .into_iter()
+        .map(|notification| notification.id)
+        .collect::<Vec<_>>();
+    assert_eq!(unread, vec![second.id]);
+
+    assert_eq!(
+        mark_all_read(&mut conn, bob.id).expect("error marking notifications as read"),
+        1
+    );
+    assert_eq!(
+        count_unread(&mut conn, bob.id).expect("error counting notifications"),
+        0
+    );
+}
+
+/// Rainy day unit test for the notification functions.
+#[test]
+fn ut_rainy_notifications() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("Bob was not in the database");
+    let alice = user::get_with_username(&mut conn, "alice")
+        .expect("error retrieving user from database")
+        .expect("Alice was not in the database");
+
+    // Users can't mark the notifications of others
+    let notification = insert_one(&mut conn, bob.id, "UT private");
+    assert!(!mark_read(&mut conn, alice.id, notification.id).expect("error marking notification"));
+    assert!(!mark_read(&mut conn, bob.id, Uuid::new_v4()).expect("error marking notification"));
+    assert!(get_for_user(&mut conn, alice.id, false, 500, 0)
+        .expect("error retrieving notifications")
+        .iter()
+        .all(|n| n.id != notification.id));
+}
+
+/// Sunny day unit test for the notification preferences functions.
+#[test]
+fn ut_sunny_notification_preferences() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("Bob was not in the database");
+    let preference = |delivery| model::NewNotificationPreference {
+        user_id: bob.id,
+        kind: "ticket.assigned",
+        delivery,
+    };
+
+    upsert_preferences(&mut conn, &[preference("digest")]).expect("error storing preferences");
+    upsert_preferences(&mut conn, &[preference("off")]).expect("error storing preferences");
+    let stored = get_preferences(&mut conn, bob.id).expect("error retrieving preferences");
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].delivery, "off");
+
+    assert_eq!(
+        get_deliveries(&mut conn, &[bob.id], "ticket.assigned")
+            .expect("error retrieving deliveries"),
+        vec![(bob.id, "off".to_owned())]
+    );
+    assert!(get_deliveries(&mut conn, &[bob.id], "ticket.commented")
+        .expect("error retrieving deliveries")
+        .is_empty());
+}
+
+/// Rainy day unit test for the notification preferences functions: invalid values are rejected.
+#[test]
+fn ut_rainy_notification_preferences() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("Bob was not in the database");
+
+    assert!(upsert_preferences(
+        &mut conn,
+        &[model::NewNotificationPreference {
+            user_id: bob.id,
+            kind: "ticket.assigned",
+            delivery: "carrier pigeon",
+        }],
+    )
+    .is_err());
+}
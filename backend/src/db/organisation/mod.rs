@@ -0,0 +1,241 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+type OrganisationColumns = (organisation::id, organisation::name);
+
+/// Columns of the organisations, except their timestamps.
+const ORGANISATION_COLUMNS: OrganisationColumns = (organisation::id, organisation::name);
+
+/// Retrieves all organisations, along with their email domains, ordered by name.
+pub fn get_all(conn: &mut PgConnection) -> io::Result<Vec<(model::Organisation, Vec<String>)>> {
+    let organisations = organisation::table
+        .select(ORGANISATION_COLUMNS)
+        .order(organisation::name)
+        .load::<model::Organisation>(conn)
+        .map_err(into_io_err)?;
+    let domains = organisation_domain::table
+        .order(organisation_domain::domain)
+        .load::<(String, Uuid)>(conn)
+        .map_err(into_io_err)?;
+
+    Ok(organisations
+        .into_iter()
+        .map(|org| {
+            let org_domains = domains
+                .iter()
+                .filter(|(_, org_id)| *org_id == org.id)
+                .map(|(domain, _)| domain.clone())
+                .collect();
+            (org, org_domains)
+        })
+        .collect())
+}
+
+/// Retrieves an organisation with an ID, along with its email domains, if it exists.
+pub fn get_with_id(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> io::Result<Option<(model::Organisation, Vec<String>)>> {
+    let org = into_option(
+        organisation::table
+            .find(id)
+            .select(ORGANISATION_COLUMNS)
+            .first::<model::Organisation>(conn),
+    )?;
+
+    match org {
+        Some(org) => {
+            let domains = organisation_domain::table
+                .filter(organisation_domain::organisation_id.eq(org.id))
+                .order(organisation_domain::domain)
+                .select(organisation_domain::domain)
+                .load(conn)
+                .map_err(into_io_err)?;
+            Ok(Some((org, domains)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Retrieves the organisation matching an email domain, if any.
+pub fn get_id_with_domain(conn: &mut PgConnection, domain: &str) -> io::Result<Option<Uuid>> {
+    let org_id = organisation_domain::table
+        .find(domain.to_lowercase())
+        .select(organisation_domain::organisation_id)
+        .first(conn);
+
+    into_option(org_id)
+}
+
+/// Inserts a new organisation with the given email domains.
+///
+/// Domains are stored in lowercase.
+pub fn insert(
+    conn: &mut PgConnection,
+    name: &str,
+    domains: &[&str],
+) -> io::Result<model::Organisation> {
+    let conn: &PgConnection = conn;
+
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let org = diesel::insert_into(organisation::table)
+            .values(&model::NewOrganisation { name })
+            .returning(ORGANISATION_COLUMNS)
+            .get_result::<model::Organisation>(conn)?;
+        insert_domains(conn, org.id, domains)?;
+
+        Ok(org)
+    })
+    .map_err(into_io_err)
+}
+
+/// Updates the name and email domains of an organisation, returning it if it exists.
+pub fn update(
+    conn: &mut PgConnection,
+    id: Uuid,
+    name: &str,
+    domains: &[&str],
+) -> io::Result<Option<model::Organisation>> {
+    let conn: &PgConnection = conn;
+
+    let org = conn.transaction(|| {
+        let org = diesel::update(organisation::table.find(id))
+            .set((
+                organisation::name.eq(name),
+                organisation::updated_on.eq(Utc::now()),
+            ))
+            .returning(ORGANISATION_COLUMNS)
+            .get_result::<model::Organisation>(conn)?;
+
+        diesel::delete(
+            organisation_domain::table.filter(organisation_domain::organisation_id.eq(id)),
+        )
+        .execute(conn)?;
+        insert_domains(conn, id, domains)?;
+
+        Ok(org)
+    });
+
+    into_option(org)
+}
+
+/// Inserts the email domains of an organisation.
+fn insert_domains(conn: &PgConnection, organisation_id: Uuid, domains: &[&str]) -> QueryResult<()> {
+    let domains = domains
+        .iter()
+        .map(|domain| domain.trim().to_lowercase())
+        .collect::<Vec<_>>();
+    let new_records = domains
+        .iter()
+        .map(|domain| model::NewOrganisationDomain {
+            domain,
+            organisation_id,
+        })
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(organisation_domain::table)
+        .values(&new_records)
+        .execute(conn)
+        .map(|_count| ())
+}
+
+/// Retrieves the IDs of the organisations a user is a member of.
+pub fn get_user_organisation_ids(conn: &mut PgConnection, user_id: Uuid) -> io::Result<Vec<Uuid>> {
+    organisation_member::table
+        .filter(organisation_member::user_id.eq(user_id))
+        .select(organisation_member::organisation_id)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the members of an organisation, ordered by username.
+pub fn get_members(conn: &mut PgConnection, id: Uuid) -> io::Result<Vec<model::User>> {
+    organisation_member::table
+        .inner_join(sys_user::table)
+        .filter(organisation_member::organisation_id.eq(id))
+        .order(sys_user::username)
+        .select(sys_user::all_columns)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Adds a user to an organisation.
+///
+/// Adding an existing member does nothing.
+pub fn insert_member(
+    conn: &mut PgConnection,
+    organisation_id: Uuid,
+    user_id: Uuid,
+) -> io::Result<()> {
+    diesel::insert_into(organisation_member::table)
+        .values(&model::NewOrganisationMember {
+            organisation_id,
+            user_id,
+        })
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Removes a user from an organisation.
+///
+/// Returns `false` if the user was not a member.
+pub fn delete_member(
+    conn: &mut PgConnection,
+    organisation_id: Uuid,
+    user_id: Uuid,
+) -> io::Result<bool> {
+    diesel::delete(organisation_member::table.find((organisation_id, user_id)))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the customer portal branding of an organisation, if it has one.
+pub fn get_branding(
+    conn: &mut PgConnection,
+    organisation_id: Uuid,
+) -> io::Result<Option<model::OrganisationBranding>> {
+    into_option(
+        organisation_branding::table
+            .find(organisation_id)
+            .first(conn),
+    )
+}
+
+/// Retrieves the customer portal branding of the first organisation of a user with one, by name.
+pub fn get_branding_for_user(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> io::Result<Option<model::OrganisationBranding>> {
+    into_option(
+        organisation_branding::table
+            .inner_join(organisation::table.inner_join(organisation_member::table))
+            .filter(organisation_member::user_id.eq(user_id))
+            .order(organisation::name)
+            .select(organisation_branding::all_columns)
+            .first(conn),
+    )
+}
+
+/// Stores the customer portal branding of an organisation, replacing the current one.
+pub fn upsert_branding(
+    conn: &mut PgConnection,
+    branding: &model::NewOrganisationBranding<'_>,
+) -> io::Result<model::OrganisationBranding> {
+    diesel::insert_into(organisation_branding::table)
+        .values(branding)
+        .on_conflict(organisation_branding::organisation_id)
+        .do_update()
+        .set((branding, organisation_branding::updated_on.eq(Utc::now())))
+        .get_result(conn)
+        .map_err(into_io_err)
+}
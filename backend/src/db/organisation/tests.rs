@@ -0,0 +1,96 @@
+use super::*;
+use crate::db::{establish_connection, user};
+
+/// Sunny day unit test for the `get_id_with_domain()` function.
+#[test]
+fn ut_sunny_get_id_with_domain() {
+    let mut conn = establish_connection();
+
+    let org_id = get_id_with_domain(&mut conn, "ACME.test")
+        .expect("error retrieving organisation from database")
+        .expect("Acme was not found from its domain");
+    let (org, domains) = get_with_id(&mut conn, org_id)
+        .expect("error retrieving organisation from database")
+        .expect("Acme was not in the database");
+    assert_eq!(org.name, "Acme");
+    assert_eq!(domains, vec!["acme.test".to_owned()]);
+}
+
+/// Rainy day unit test for the `get_id_with_domain()` function.
+#[test]
+fn ut_rainy_get_id_with_domain() {
+    let mut conn = establish_connection();
+
+    let org_id = get_id_with_domain(&mut conn, "example.com")
+        .expect("error retrieving organisation from database");
+    assert!(
+        org_id.is_none(),
+        "an organisation was found for example.com"
+    );
+}
+
+/// Sunny day unit test for the `insert()` and `update()` functions.
+#[test]
+fn ut_sunny_insert_update() {
+    let mut conn = establish_connection();
+
+    let org = insert(&mut conn, "UT Initech", &["UT-initech.test"])
+        .expect("error inserting organisation");
+    assert_eq!(
+        get_id_with_domain(&mut conn, "ut-initech.test").expect("error retrieving organisation"),
+        Some(org.id),
+        "domain was not stored in lowercase"
+    );
+
+    let updated = update(&mut conn, org.id, "UT Initrode", &["ut-initrode.test"])
+        .expect("error updating organisation")
+        .expect("organisation disappeared");
+    assert_eq!(updated.name, "UT Initrode");
+    assert_eq!(
+        get_id_with_domain(&mut conn, "ut-initech.test").expect("error retrieving organisation"),
+        None,
+        "old domain was not removed"
+    );
+
+    let _ = diesel::delete(organisation::table.find(org.id))
+        .execute(&conn)
+        .expect("error cleaning up the organisation");
+}
+
+/// Rainy day unit test for the `update()` function.
+#[test]
+fn ut_rainy_update() {
+    let mut conn = establish_connection();
+
+    let org =
+        update(&mut conn, Uuid::new_v4(), "UT Nobody", &[]).expect("error updating organisation");
+    assert!(org.is_none(), "a nonexistent organisation was updated");
+}
+
+/// Sunny day unit test for the membership functions.
+#[test]
+fn ut_sunny_members() {
+    let mut conn = establish_connection();
+
+    let org_id = get_id_with_domain(&mut conn, "globex.test")
+        .expect("error retrieving organisation from database")
+        .expect("Globex was not in the database");
+    let alice = user::get_with_username(&mut conn, "alice")
+        .expect("error retrieving user from database")
+        .expect("Alice was not in the database");
+
+    insert_member(&mut conn, org_id, alice.id).expect("error adding member");
+    insert_member(&mut conn, org_id, alice.id).expect("adding an existing member failed");
+    let members = get_members(&mut conn, org_id).expect("error retrieving members");
+    assert!(members.iter().any(|member| member.username == "alice"));
+    assert!(members.iter().any(|member| member.username == "dave"));
+    assert!(get_user_organisation_ids(&mut conn, alice.id)
+        .expect("error retrieving organisations")
+        .contains(&org_id));
+
+    assert!(delete_member(&mut conn, org_id, alice.id).expect("error removing member"));
+    assert!(
+        !delete_member(&mut conn, org_id, alice.id).expect("error removing member"),
+        "a missing member was removed"
+    );
+}
@@ -0,0 +1,190 @@
+use super::{
+    into_option, model,
+    schema::*,
+    tenant::{self, Viewer},
+};
+use crate::into_io_err;
+use chrono::Utc;
+use common::{problem::Status, ticket::Status as TicketStatus};
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Retrieves the problems, optionally with a status, newest first.
+pub fn get_all(
+    conn: &mut PgConnection,
+    status: Option<Status>,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::Problem>> {
+    let mut query = problem::table.into_boxed();
+    if let Some(status) = status {
+        query = query.filter(problem::status.eq(status.as_str()));
+    }
+
+    query
+        .order(problem::number.desc())
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a problem with an ID, if it exists.
+pub fn get_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::Problem>> {
+    into_option(problem::table.find(id).first(conn))
+}
+
+/// Inserts a new problem.
+pub fn insert(
+    conn: &mut PgConnection,
+    form: &model::ProblemForm<'_>,
+    created_by: Uuid,
+) -> io::Result<model::Problem> {
+    diesel::insert_into(problem::table)
+        .values((form, problem::created_by.eq(created_by)))
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a problem, returning it if it exists.
+pub fn update(
+    conn: &mut PgConnection,
+    id: Uuid,
+    form: &model::ProblemForm<'_>,
+) -> io::Result<Option<model::Problem>> {
+    into_option(
+        diesel::update(problem::table.find(id))
+            .set((form, problem::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
+
+/// Marks a problem as resolved, returning it if it exists.
+pub fn resolve(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::Problem>> {
+    let now = Utc::now();
+    into_option(
+        diesel::update(problem::table.find(id))
+            .set((
+                problem::status.eq(Status::Resolved.as_str()),
+                problem::resolved_on.eq(now),
+                problem::updated_on.eq(now),
+            ))
+            .get_result(conn),
+    )
+}
+
+/// Records the knowledge base article of the known error of a problem, returning the problem if
+/// it exists.
+pub fn set_article(
+    conn: &mut PgConnection,
+    id: Uuid,
+    article_id: Uuid,
+) -> io::Result<Option<model::Problem>> {
+    into_option(
+        diesel::update(problem::table.find(id))
+            .set((
+                problem::article_id.eq(article_id),
+                problem::updated_on.eq(Utc::now()),
+            ))
+            .get_result(conn),
+    )
+}
+
+/// Retrieves the incident tickets of a problem visible to a viewer, newest first.
+pub fn get_linked_tickets(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    problem_id: Uuid,
+) -> io::Result<Vec<model::Ticket>> {
+    tenant::run(conn, viewer, |conn| {
+        let mut query = ticket::table
+            .filter(
+                ticket::id.eq_any(
+                    problem_ticket::table
+                        .select(problem_ticket::ticket_id)
+                        .filter(problem_ticket::problem_id.eq(problem_id)),
+                ),
+            )
+            .into_boxed();
+        if !viewer.sees_all() {
+            query = query.filter(
+                ticket::requester_id
+                    .eq(viewer.user_id)
+                    .or(ticket::organisation_id.eq_any(&viewer.organisation_ids)),
+            );
+        }
+
+        query.order(ticket::number.desc()).load(conn)
+    })
+}
+
+/// Retrieves the incident tickets of a problem that still require work, oldest first.
+pub fn get_active_tickets(
+    conn: &mut PgConnection,
+    problem_id: Uuid,
+) -> io::Result<Vec<model::Ticket>> {
+    let active = [TicketStatus::New, TicketStatus::Open, TicketStatus::Pending];
+
+    ticket::table
+        .filter(
+            ticket::id.eq_any(
+                problem_ticket::table
+                    .select(problem_ticket::ticket_id)
+                    .filter(problem_ticket::problem_id.eq(problem_id)),
+            ),
+        )
+        .filter(ticket::status.eq_any(active.iter().map(|status| status.as_str())))
+        .order(ticket::number)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the problems a ticket is linked to, newest first.
+pub fn get_for_ticket(conn: &mut PgConnection, ticket_id: Uuid) -> io::Result<Vec<model::Problem>> {
+    problem::table
+        .filter(
+            problem::id.eq_any(
+                problem_ticket::table
+                    .select(problem_ticket::problem_id)
+                    .filter(problem_ticket::ticket_id.eq(ticket_id)),
+            ),
+        )
+        .order(problem::number.desc())
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Links an incident ticket to a problem, returning whether it was not linked yet.
+pub fn link(
+    conn: &mut PgConnection,
+    problem_id: Uuid,
+    ticket_id: Uuid,
+    linked_by: Uuid,
+) -> io::Result<bool> {
+    diesel::insert_into(problem_ticket::table)
+        .values((
+            problem_ticket::problem_id.eq(problem_id),
+            problem_ticket::ticket_id.eq(ticket_id),
+            problem_ticket::linked_by.eq(linked_by),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Unlinks an incident ticket from a problem, returning whether it was linked.
+pub fn unlink(conn: &mut PgConnection, problem_id: Uuid, ticket_id: Uuid) -> io::Result<bool> {
+    diesel::delete(
+        problem_ticket::table
+            .filter(problem_ticket::problem_id.eq(problem_id))
+            .filter(problem_ticket::ticket_id.eq(ticket_id)),
+    )
+    .execute(conn)
+    .map(|count| count > 0)
+    .map_err(into_io_err)
+}
@@ -0,0 +1,111 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, viewer},
+    ticket,
+};
+use diesel::Connection;
+
+/// Inserts a problem created by a user.
+fn insert_problem(conn: &mut PgConnection, title: &str, created_by: Uuid) -> model::Problem {
+    insert(
+        conn,
+        &model::ProblemForm {
+            title,
+            description: "",
+            status: Status::Investigating.as_str(),
+            root_cause: "",
+            workaround: "",
+            owner_id: None,
+            resolved_on: None,
+        },
+        created_by,
+    )
+    .expect("error inserting problem")
+}
+
+/// Sunny day unit test for incident links: only the active incidents are left to resolve.
+#[test]
+fn ut_sunny_link() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (bob, carol) = (viewer(&mut conn, "bob"), viewer(&mut conn, "carol"));
+    let problem = insert_problem(&mut conn, "UT mail outage", bob.user_id);
+    let first = insert_ticket(&mut conn, &new_ticket(carol.user_id, "UT no mail"));
+    let second = insert_ticket(&mut conn, &new_ticket(carol.user_id, "UT still no mail"));
+    for ticket in [&first, &second] {
+        assert!(link(&mut conn, problem.id, ticket.id, bob.user_id).expect("error linking"));
+    }
+    assert!(!link(&mut conn, problem.id, first.id, bob.user_id).expect("error linking"));
+    let _ = ticket::update(
+        &mut conn,
+        &bob,
+        second.id,
+        &model::TicketChanges {
+            status: Some(TicketStatus::Closed.as_str()),
+            ..Default::default()
+        },
+    )
+    .expect("error updating ticket");
+
+    assert_eq!(
+        get_linked_tickets(&mut conn, &carol, problem.id)
+            .expect("error retrieving linked tickets")
+            .len(),
+        2
+    );
+    assert_eq!(
+        get_active_tickets(&mut conn, problem.id)
+            .expect("error retrieving active tickets")
+            .iter()
+            .map(|ticket| ticket.id)
+            .collect::<Vec<_>>(),
+        [first.id]
+    );
+    assert_eq!(
+        get_for_ticket(&mut conn, first.id)
+            .expect("error retrieving problems")
+            .iter()
+            .map(|problem| problem.id)
+            .collect::<Vec<_>>(),
+        [problem.id]
+    );
+
+    let resolved = resolve(&mut conn, problem.id)
+        .expect("error resolving problem")
+        .expect("problem was not found");
+    assert_eq!(resolved.status(), Status::Resolved);
+    assert!(resolved.resolved_on.is_some());
+    assert!(get_all(&mut conn, Some(Status::Resolved), 500, 0)
+        .expect("error retrieving problems")
+        .iter()
+        .any(|p| p.id == problem.id));
+}
+
+/// Rainy day unit test for incident links: customers only see their own incidents, and unknown
+/// links can't be removed.
+#[test]
+fn ut_rainy_link() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (bob, carol) = (viewer(&mut conn, "bob"), viewer(&mut conn, "carol"));
+    let problem = insert_problem(&mut conn, "UT printer jam", bob.user_id);
+    let ticket = insert_ticket(&mut conn, &new_ticket(carol.user_id, "UT printer jammed"));
+    assert!(link(&mut conn, problem.id, ticket.id, bob.user_id).expect("error linking"));
+
+    let dave = viewer(&mut conn, "dave");
+    assert!(get_linked_tickets(&mut conn, &dave, problem.id)
+        .expect("error retrieving linked tickets")
+        .is_empty());
+    assert!(get_all(&mut conn, Some(Status::KnownError), 500, 0)
+        .expect("error retrieving problems")
+        .iter()
+        .all(|p| p.id != problem.id));
+    assert!(!unlink(&mut conn, problem.id, Uuid::new_v4()).expect("error unlinking"));
+    assert!(unlink(&mut conn, problem.id, ticket.id).expect("error unlinking"));
+    assert!(resolve(&mut conn, Uuid::new_v4())
+        .expect("error resolving problem")
+        .is_none());
+}
@@ -0,0 +1,76 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Retrieves all queues, ordered by name.
+pub fn get_all(conn: &mut PgConnection) -> io::Result<Vec<model::Queue>> {
+    queue::table
+        .order(queue::name)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a queue with an ID, if it exists.
+pub fn get_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::Queue>> {
+    into_option(queue::table.find(id).first(conn))
+}
+
+/// Retrieves the default queue, if there is one.
+pub fn get_default(conn: &mut PgConnection) -> io::Result<Option<model::Queue>> {
+    into_option(queue::table.filter(queue::is_default).first(conn))
+}
+
+/// Inserts a new queue.
+///
+/// If the new queue is the default one, the previous default queue stops being so.
+pub fn insert(conn: &mut PgConnection, form: &model::QueueForm<'_>) -> io::Result<model::Queue> {
+    let conn: &PgConnection = conn;
+
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        if form.is_default {
+            clear_default(conn)?;
+        }
+
+        diesel::insert_into(queue::table)
+            .values(form)
+            .get_result(conn)
+    })
+    .map_err(into_io_err)
+}
+
+/// Updates the settings of a queue, returning it if it exists.
+///
+/// If the queue becomes the default one, the previous default queue stops being so.
+pub fn update(
+    conn: &mut PgConnection,
+    id: Uuid,
+    form: &model::QueueForm<'_>,
+) -> io::Result<Option<model::Queue>> {
+    let conn: &PgConnection = conn;
+
+    let queue = conn.transaction(|| {
+        if form.is_default {
+            clear_default(conn)?;
+        }
+
+        diesel::update(queue::table.find(id))
+            .set((form, queue::updated_on.eq(Utc::now())))
+            .get_result(conn)
+    });
+
+    into_option(queue)
+}
+
+/// Unsets the current default queue, if any.
+fn clear_default(conn: &PgConnection) -> QueryResult<()> {
+    diesel::update(queue::table.filter(queue::is_default))
+        .set(queue::is_default.eq(false))
+        .execute(conn)
+        .map(|_count| ())
+}
@@ -0,0 +1,71 @@
+use super::*;
+use crate::db::{establish_connection, team};
+use diesel::Connection;
+
+/// Sunny day unit test for the `insert()` and `update()` functions, for the default queue.
+#[test]
+fn ut_sunny_default_queue() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let team = team::insert(&mut conn, "UT Front desk").expect("error inserting team");
+    let form = model::QueueForm {
+        name: "UT General",
+        team_id: team.id,
+        strategy: "manual",
+        skills: &[],
+        is_default: true,
+    };
+    let general = insert(&mut conn, &form).expect("error inserting queue");
+    let billing = insert(
+        &mut conn,
+        &model::QueueForm {
+            name: "UT Billing",
+            ..form.clone()
+        },
+    )
+    .expect("error inserting queue");
+
+    let default = get_default(&mut conn)
+        .expect("error retrieving default queue")
+        .expect("there was no default queue");
+    assert_eq!(
+        default.id, billing.id,
+        "the newest default queue is not the default"
+    );
+
+    let _ = update(&mut conn, general.id, &form)
+        .expect("error updating queue")
+        .expect("queue disappeared");
+    let default = get_default(&mut conn)
+        .expect("error retrieving default queue")
+        .expect("there was no default queue");
+    assert_eq!(
+        default.id, general.id,
+        "the updated queue is not the default"
+    );
+}
+
+/// Rainy day unit test for the `update()` function.
+#[test]
+fn ut_rainy_update() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let team = team::insert(&mut conn, "UT Back office").expect("error inserting team");
+    let queue = update(
+        &mut conn,
+        Uuid::new_v4(),
+        &model::QueueForm {
+            name: "UT Nowhere",
+            team_id: team.id,
+            strategy: "manual",
+            skills: &[],
+            is_default: false,
+        },
+    )
+    .expect("error updating queue");
+    assert!(queue.is_none(), "a nonexistent queue was updated");
+}
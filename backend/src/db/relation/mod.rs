@@ -0,0 +1,324 @@
+use super::{
+    into_option, model,
+    schema::*,
+    tenant::{self, Viewer},
+};
+use crate::into_io_err;
+use chrono::Utc;
+use common::ticket::Status as TicketStatus;
+use diesel::{
+    expression::BoxableExpression, pg::Pg, prelude::*, result::Error as DieselError,
+    sql_types::Bool, PgConnection,
+};
+use std::{collections::HashSet, io};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Filter of links, boxed so that it can be used in several queries.
+type LinkPredicate = Box<dyn BoxableExpression<ticket_link::table, Pg, SqlType = Bool>>;
+
+/// Maximum number of merged tickets followed to find the ticket surviving a merge.
+const MAX_MERGE_DEPTH: usize = 20;
+
+/// Retrieves the ticket a ticket was merged into, following merges, or the ticket itself if it
+/// wasn't merged.
+///
+/// Merges are followed as long as the tickets are visible to the viewer.
+pub fn get_survivor(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    ticket: model::Ticket,
+) -> io::Result<model::Ticket> {
+    let mut survivor = ticket;
+    for _ in 0..MAX_MERGE_DEPTH {
+        let merged_into_id = match survivor.merged_into_id {
+            Some(merged_into_id) => merged_into_id,
+            None => break,
+        };
+        match super::ticket::get_with_id(conn, viewer, merged_into_id)? {
+            Some(ticket) => survivor = ticket,
+            None => break,
+        }
+    }
+
+    Ok(survivor)
+}
+
+/// Merges a ticket into another one, returning the merged ticket.
+///
+/// The comments, attachments, watchers and child tickets of the merged ticket move to the other
+/// ticket, and the requester of the merged ticket watches the other one. The merged ticket is
+/// closed.
+pub fn merge(
+    conn: &mut PgConnection,
+    source: &model::Ticket,
+    target: &model::Ticket,
+) -> io::Result<model::Ticket> {
+    let target_descends = is_ancestor(conn, source.id, target.id)?;
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        let _ =
+            diesel::update(ticket_comment::table.filter(ticket_comment::ticket_id.eq(source.id)))
+                .set(ticket_comment::ticket_id.eq(target.id))
+                .execute(conn)?;
+        let _ = diesel::update(
+            ticket_attachment::table.filter(ticket_attachment::ticket_id.eq(source.id)),
+        )
+        .set(ticket_attachment::ticket_id.eq(target.id))
+        .execute(conn)?;
+
+        let mut watcher_ids = ticket_watcher::table
+            .select(ticket_watcher::user_id)
+            .filter(ticket_watcher::ticket_id.eq(source.id))
+            .load::<Uuid>(conn)?;
+        watcher_ids.push(source.requester_id);
+        let watchers = watcher_ids
+            .into_iter()
+            .filter(|user_id| *user_id != target.requester_id)
+            .map(|user_id| {
+                (
+                    ticket_watcher::ticket_id.eq(target.id),
+                    ticket_watcher::user_id.eq(user_id),
+                )
+            })
+            .collect::<Vec<_>>();
+        let _ = diesel::insert_into(ticket_watcher::table)
+            .values(&watchers)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        let _ =
+            diesel::delete(ticket_watcher::table.filter(ticket_watcher::ticket_id.eq(source.id)))
+                .execute(conn)?;
+
+        // Tickets can't descend from themselves, so the other ticket takes the parent of the merged
+        // one if it descends from it
+        if target_descends {
+            let _ = diesel::update(ticket::table.find(target.id))
+                .set(ticket::parent_id.eq(source.parent_id))
+                .execute(conn)?;
+        }
+        let _ = diesel::update(
+            ticket::table
+                .filter(ticket::parent_id.eq(source.id))
+                .filter(ticket::id.ne(target.id)),
+        )
+        .set(ticket::parent_id.eq(target.id))
+        .execute(conn)?;
+
+        diesel::update(ticket::table.find(source.id))
+            .set((
+                ticket::merged_into_id.eq(target.id),
+                ticket::status.eq(TicketStatus::Closed.as_str()),
+                ticket::updated_on.eq(Utc::now()),
+            ))
+            .get_result(conn)
+    })
+    .map_err(into_io_err)
+}
+
+/// Moves a comment of a ticket, along with its attachments, to another ticket.
+///
+/// Returns `false` if the comment is not a comment of the ticket.
+pub fn move_comment(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    comment_id: Uuid,
+    to_ticket_id: Uuid,
+) -> io::Result<bool> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        let moved = diesel::update(
+            ticket_comment::table
+                .filter(ticket_comment::id.eq(comment_id))
+                .filter(ticket_comment::ticket_id.eq(ticket_id)),
+        )
+        .set(ticket_comment::ticket_id.eq(to_ticket_id))
+        .execute(conn)?;
+        let _ = diesel::update(
+            ticket_attachment::table.filter(ticket_attachment::comment_id.eq(comment_id)),
+        )
+        .set(ticket_attachment::ticket_id.eq(to_ticket_id))
+        .execute(conn)?;
+
+        Ok(moved > 0)
+    })
+    .map_err(into_io_err)
+}
+
+/// Retrieves the child tickets of a ticket visible to the viewer, oldest first.
+pub fn get_children(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    parent_id: Uuid,
+) -> io::Result<Vec<model::Ticket>> {
+    tenant::run(conn, viewer, |conn| {
+        let mut query = ticket::table
+            .filter(ticket::parent_id.eq(parent_id))
+            .into_boxed();
+        if !viewer.sees_all() {
+            query = query.filter(
+                ticket::requester_id
+                    .eq(viewer.user_id)
+                    .or(ticket::organisation_id.eq_any(&viewer.organisation_ids)),
+            );
+        }
+
+        query.order(ticket::number).load(conn)
+    })
+}
+
+/// Counts the child tickets of a ticket that still require work.
+pub fn count_active_children(conn: &mut PgConnection, parent_id: Uuid) -> io::Result<i64> {
+    let active = TicketStatus::ALL
+        .iter()
+        .filter(|status| status.is_active())
+        .map(|status| status.as_str())
+        .collect::<Vec<_>>();
+
+    ticket::table
+        .filter(ticket::parent_id.eq(parent_id))
+        .filter(ticket::status.eq_any(active))
+        .count()
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Checks if a ticket is the given ticket or one of its ancestors.
+pub fn is_ancestor(conn: &mut PgConnection, ancestor_id: Uuid, id: Uuid) -> io::Result<bool> {
+    let mut visited = HashSet::new();
+    let mut current = Some(id);
+    while let Some(id) = current {
+        if id == ancestor_id {
+            return Ok(true);
+        }
+        if !visited.insert(id) {
+            break;
+        }
+
+        current = into_option(
+            ticket::table
+                .select(ticket::parent_id)
+                .find(id)
+                .first::<Option<Uuid>>(conn),
+        )?
+        .flatten();
+    }
+
+    Ok(false)
+}
+
+/// Sets or removes the parent of a ticket, returning the ticket if it exists.
+///
+/// The caller must check that the parent doesn't descend from the ticket.
+pub fn set_parent(
+    conn: &mut PgConnection,
+    id: Uuid,
+    parent_id: Option<Uuid>,
+) -> io::Result<Option<model::Ticket>> {
+    into_option(
+        diesel::update(ticket::table.find(id))
+            .set((
+                ticket::parent_id.eq(parent_id),
+                ticket::updated_on.eq(Utc::now()),
+            ))
+            .get_result(conn),
+    )
+}
+
+/// Retrieves the links of a ticket to the tickets visible to the viewer, with the linked
+/// tickets, oldest first.
+pub fn get_links(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    ticket_id: Uuid,
+) -> io::Result<Vec<(model::TicketLink, model::Ticket)>> {
+    tenant::run(conn, viewer, |conn| {
+        let links = ticket_link::table
+            .filter(
+                ticket_link::ticket_id
+                    .eq(ticket_id)
+                    .or(ticket_link::linked_id.eq(ticket_id)),
+            )
+            .order(ticket_link::created_on)
+            .load::<model::TicketLink>(conn)?;
+        let other_ids = links
+            .iter()
+            .map(|link| other_id(link, ticket_id))
+            .collect::<Vec<_>>();
+
+        let mut query = ticket::table
+            .filter(ticket::id.eq_any(&other_ids))
+            .into_boxed();
+        if !viewer.sees_all() {
+            query = query.filter(
+                ticket::requester_id
+                    .eq(viewer.user_id)
+                    .or(ticket::organisation_id.eq_any(&viewer.organisation_ids)),
+            );
+        }
+        let mut tickets = query.load::<model::Ticket>(conn)?;
+
+        Ok(links
+            .into_iter()
+            .filter_map(|link| {
+                let other_id = other_id(&link, ticket_id);
+                let index = tickets.iter().position(|ticket| ticket.id == other_id)?;
+                Some((link, tickets.swap_remove(index)))
+            })
+            .collect())
+    })
+}
+
+/// Retrieves the link between two tickets, whatever its direction, if they are linked.
+pub fn get_link(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    other_id: Uuid,
+) -> io::Result<Option<model::TicketLink>> {
+    into_option(
+        ticket_link::table
+            .filter(between(ticket_id, other_id))
+            .first(conn),
+    )
+}
+
+/// Links two tickets, returning `false` if they were already linked.
+pub fn insert_link(conn: &mut PgConnection, link: &model::NewTicketLink<'_>) -> io::Result<bool> {
+    diesel::insert_into(ticket_link::table)
+        .values(link)
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Unlinks two tickets, returning `false` if they were not linked.
+pub fn delete_link(conn: &mut PgConnection, ticket_id: Uuid, other_id: Uuid) -> io::Result<bool> {
+    diesel::delete(ticket_link::table.filter(between(ticket_id, other_id)))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Filters the link between two tickets, whatever its direction.
+fn between(ticket_id: Uuid, other_id: Uuid) -> LinkPredicate {
+    Box::new(
+        ticket_link::ticket_id
+            .eq(ticket_id)
+            .and(ticket_link::linked_id.eq(other_id))
+            .or(ticket_link::ticket_id
+                .eq(other_id)
+                .and(ticket_link::linked_id.eq(ticket_id))),
+    )
+}
+
+/// Gets the ID of the ticket linked to a ticket by a link.
+fn other_id(link: &model::TicketLink, ticket_id: Uuid) -> Uuid {
+    if link.ticket_id == ticket_id {
+        link.linked_id
+    } else {
+        link.ticket_id
+    }
+}
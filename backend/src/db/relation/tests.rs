@@ -0,0 +1,191 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, viewer},
+    ticket,
+};
+use common::ticket::{CommentSource, LinkKind};
+use diesel::Connection;
+
+/// Inserts a comment in a ticket.
+fn insert_comment(conn: &mut PgConnection, ticket_id: Uuid, author_id: Uuid) -> Uuid {
+    ticket::insert_comment(
+        conn,
+        &model::NewTicketComment {
+            ticket_id,
+            author_id,
+            body: "UT comment",
+            source: CommentSource::Web.as_str(),
+        },
+    )
+    .expect("error inserting comment")
+    .id
+}
+
+/// Sunny day unit test for merges: comments, watchers and children move to the surviving ticket,
+/// which is found from the merged one.
+#[test]
+fn ut_sunny_merge() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (bob, carol) = (viewer(&mut conn, "bob"), viewer(&mut conn, "carol"));
+    let target = insert_ticket(&mut conn, &new_ticket(carol.user_id, "UT printer jammed"));
+    let source = insert_ticket(
+        &mut conn,
+        &new_ticket(carol.user_id, "UT printer still jammed"),
+    );
+    let child = insert_ticket(&mut conn, &new_ticket(bob.user_id, "UT order toner"));
+    let _ = set_parent(&mut conn, child.id, Some(source.id)).expect("error setting parent");
+    let comment_id = insert_comment(&mut conn, source.id, carol.user_id);
+    let _ = ticket::insert_watcher(&mut conn, source.id, bob.user_id).expect("error watching");
+
+    let merged = merge(&mut conn, &source, &target).expect("error merging tickets");
+    assert_eq!(merged.merged_into_id, Some(target.id));
+    assert_eq!(merged.status(), TicketStatus::Closed);
+    assert_eq!(
+        ticket::get_comments(&mut conn, target.id)
+            .expect("error retrieving comments")
+            .iter()
+            .map(|comment| comment.id)
+            .collect::<Vec<_>>(),
+        [comment_id]
+    );
+    // Carol requested both tickets, so she doesn't need to watch the surviving one
+    assert_eq!(
+        ticket::get_watcher_ids(&mut conn, target.id).expect("error retrieving watchers"),
+        [bob.user_id]
+    );
+    assert!(ticket::get_watcher_ids(&mut conn, source.id)
+        .expect("error retrieving watchers")
+        .is_empty());
+    assert_eq!(
+        get_children(&mut conn, &bob, target.id)
+            .expect("error retrieving children")
+            .iter()
+            .map(|ticket| ticket.id)
+            .collect::<Vec<_>>(),
+        [child.id]
+    );
+
+    let survivor = get_survivor(&mut conn, &carol, merged).expect("error following merges");
+    assert_eq!(survivor.id, target.id);
+}
+
+/// Sunny day unit test for parent tickets: active children are counted, and ancestors are found.
+#[test]
+fn ut_sunny_parent() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let bob = viewer(&mut conn, "bob");
+    let parent = insert_ticket(&mut conn, &new_ticket(bob.user_id, "UT office move"));
+    let child = insert_ticket(&mut conn, &new_ticket(bob.user_id, "UT move desks"));
+    let grandchild = insert_ticket(&mut conn, &new_ticket(bob.user_id, "UT move screens"));
+    let _ = set_parent(&mut conn, child.id, Some(parent.id)).expect("error setting parent");
+    let _ = set_parent(&mut conn, grandchild.id, Some(child.id)).expect("error setting parent");
+
+    assert!(is_ancestor(&mut conn, parent.id, grandchild.id).expect("error walking ancestors"));
+    assert!(is_ancestor(&mut conn, child.id, child.id).expect("error walking ancestors"));
+    assert!(!is_ancestor(&mut conn, grandchild.id, parent.id).expect("error walking ancestors"));
+
+    assert_eq!(
+        count_active_children(&mut conn, parent.id).expect("error counting children"),
+        1
+    );
+    let _ = ticket::update(
+        &mut conn,
+        &bob,
+        child.id,
+        &model::TicketChanges {
+            status: Some(TicketStatus::Resolved.as_str()),
+            ..Default::default()
+        },
+    )
+    .expect("error resolving ticket");
+    assert_eq!(
+        count_active_children(&mut conn, parent.id).expect("error counting children"),
+        0
+    );
+
+    let detached = set_parent(&mut conn, grandchild.id, None)
+        .expect("error removing parent")
+        .expect("ticket was not found");
+    assert_eq!(detached.parent_id, None);
+}
+
+/// Sunny day unit test for links: links are seen from both tickets, in their direction.
+#[test]
+fn ut_sunny_links() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (bob, carol) = (viewer(&mut conn, "bob"), viewer(&mut conn, "carol"));
+    let original = insert_ticket(&mut conn, &new_ticket(carol.user_id, "UT VPN down"));
+    let duplicate = insert_ticket(&mut conn, &new_ticket(carol.user_id, "UT no VPN"));
+    let related = insert_ticket(&mut conn, &new_ticket(carol.user_id, "UT slow network"));
+
+    let link = model::NewTicketLink::new(
+        original.id,
+        duplicate.id,
+        LinkKind::DuplicatedBy,
+        bob.user_id,
+    );
+    assert!(insert_link(&mut conn, &link).expect("error linking tickets"));
+    let link = model::NewTicketLink::new(related.id, original.id, LinkKind::Related, bob.user_id);
+    assert!(insert_link(&mut conn, &link).expect("error linking tickets"));
+
+    let links = get_links(&mut conn, &carol, original.id).expect("error retrieving links");
+    assert_eq!(
+        links
+            .iter()
+            .map(|(link, ticket)| (ticket.id, link.kind_from(original.id)))
+            .collect::<Vec<_>>(),
+        [
+            (duplicate.id, LinkKind::DuplicatedBy),
+            (related.id, LinkKind::Related)
+        ]
+    );
+    let link = get_link(&mut conn, original.id, duplicate.id)
+        .expect("error retrieving link")
+        .expect("tickets were not linked");
+    assert_eq!(link.kind_from(duplicate.id), LinkKind::DuplicateOf);
+
+    assert!(delete_link(&mut conn, original.id, duplicate.id).expect("error unlinking tickets"));
+    assert!(get_link(&mut conn, duplicate.id, original.id)
+        .expect("error retrieving link")
+        .is_none());
+}
+
+/// Rainy day unit test for links: tickets are linked once whatever the direction, and links to
+/// hidden tickets are left out.
+#[test]
+fn ut_rainy_links() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (bob, carol, dave) = (
+        viewer(&mut conn, "bob"),
+        viewer(&mut conn, "carol"),
+        viewer(&mut conn, "dave"),
+    );
+    let mine = insert_ticket(&mut conn, &new_ticket(dave.user_id, "UT my laptop"));
+    let hidden = insert_ticket(&mut conn, &new_ticket(carol.user_id, "UT her laptop"));
+
+    let link = model::NewTicketLink::new(mine.id, hidden.id, LinkKind::Related, bob.user_id);
+    assert!(insert_link(&mut conn, &link).expect("error linking tickets"));
+    let reversed =
+        model::NewTicketLink::new(hidden.id, mine.id, LinkKind::DuplicateOf, bob.user_id);
+    assert!(!insert_link(&mut conn, &reversed).expect("error linking tickets"));
+
+    assert!(get_links(&mut conn, &dave, mine.id)
+        .expect("error retrieving links")
+        .is_empty());
+    assert_eq!(
+        get_links(&mut conn, &bob, mine.id)
+            .expect("error retrieving links")
+            .len(),
+        1
+    );
+    assert!(!delete_link(&mut conn, mine.id, Uuid::new_v4()).expect("error unlinking tickets"));
+}
@@ -0,0 +1,224 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::{DateTime, Utc};
+use common::{
+    report::{Dimension, Metric, Period},
+    ticket::Priority,
+};
+use diesel::{
+    dsl::sql,
+    prelude::*,
+    result::Error as DieselError,
+    select, sql_query,
+    sql_types::{Bool, Text, Timestamptz},
+    PgConnection,
+};
+use std::io;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of report rows fetched from the database at once.
+const FETCH_SIZE: usize = 500;
+
+/// Query aggregating a metric of the tickets by the period of an event.
+///
+/// The parameters are the period (`$1`), the time range of the events (`$2` and `$3`) and the
+/// timezone (`$4`). The placeholders are filled in with fixed fragments for the metric and
+/// dimension, never with user input.
+const EVENT_QUERY: &str = "\
+SELECT date_trunc($1, {event} AT TIME ZONE $4)::date AS period,
+    {group_id} AS group_id, {group_name} AS group_name,
+    ({value})::float8 AS value, COUNT(*) AS count
+FROM {source}
+{joins}
+WHERE t.merged_into_id IS NULL AND {event} >= $2 AND {event} < $3 {filter}
+GROUP BY 1, 2, 3
+ORDER BY 1, 3, 2";
+
+/// Query counting the unresolved tickets at the end of each period, with the same parameters and
+/// placeholders as [`EVENT_QUERY`].
+const BACKLOG_QUERY: &str = "\
+WITH periods AS (
+    SELECT start, (start + ('1 ' || $1)::interval) AT TIME ZONE $4 AS end_on
+    FROM generate_series(
+        date_trunc($1, $2 AT TIME ZONE $4),
+        ($3 AT TIME ZONE $4) - INTERVAL '1 microsecond',
+        ('1 ' || $1)::interval
+    ) AS start
+)
+SELECT p.start::date AS period,
+    {group_id} AS group_id, {group_name} AS group_name,
+    COUNT(*)::float8 AS value, COUNT(*) AS count
+FROM periods p
+JOIN ticket t ON t.created_on < p.end_on
+JOIN ticket_metric m ON m.ticket_id = t.id
+{joins}
+WHERE t.merged_into_id IS NULL AND (m.resolved_on IS NULL OR m.resolved_on >= p.end_on)
+GROUP BY 1, 2, 3
+ORDER BY 1, 3, 2";
+
+/// Tickets with their milestones.
+const TICKET_SOURCE: &str = "ticket t JOIN ticket_metric m ON m.ticket_id = t.id";
+
+/// Answered surveys with their tickets.
+const SURVEY_SOURCE: &str = "survey s JOIN ticket t ON t.id = s.ticket_id";
+
+/// Whether a resolved ticket met the targets of the SLA policy of its priority. Tickets resolved
+/// without a response count as responded when resolved.
+const SLA_MET: &str = "\
+m.resolved_on <= t.created_on + p.resolution_minutes * INTERVAL '1 minute'
+    AND COALESCE(m.first_response_on, m.resolved_on)
+        <= t.created_on + p.response_minutes * INTERVAL '1 minute'";
+
+/// Parameters of a report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub metric: Metric,
+    pub by: Dimension,
+    pub period: Period,
+    /// The name of the timezone periods start in, as known by the database.
+    pub timezone: String,
+    /// The start of the time range of the report.
+    pub from: DateTime<Utc>,
+    /// The end of the time range of the report, not included.
+    pub to: DateTime<Utc>,
+}
+
+impl Query {
+    /// Builds the SQL of the report.
+    fn sql(&self) -> String {
+        // The agent of surveys is the one rated, which may no longer be the assignee
+        let agent = match self.metric {
+            Metric::Csat => "s.agent_id",
+            _ => "t.assignee_id",
+        };
+        let (group_id, group_name, joins) = match self.by {
+            Dimension::Total => ("NULL::uuid", "NULL::varchar", String::new()),
+            Dimension::Team => (
+                "g.id",
+                "g.name",
+                "LEFT JOIN queue q ON q.id = t.queue_id LEFT JOIN team g ON g.id = q.team_id"
+                    .to_owned(),
+            ),
+            Dimension::Agent => (
+                "g.id",
+                "(g.first_name || ' ' || g.last_name)::varchar",
+                format!("LEFT JOIN sys_user g ON g.id = {}", agent),
+            ),
+            Dimension::Category => ("NULL::uuid", "t.category", String::new()),
+        };
+
+        let (event, value, source, filter) = match self.metric {
+            Metric::Backlog => {
+                return BACKLOG_QUERY
+                    .replace("{group_id}", group_id)
+                    .replace("{group_name}", group_name)
+                    .replace("{joins}", &joins)
+            }
+            Metric::Volume => ("t.created_on", "COUNT(*)", TICKET_SOURCE, ""),
+            Metric::FirstResponse => (
+                "t.created_on",
+                "AVG(EXTRACT(EPOCH FROM m.first_response_on - t.created_on) / 60)",
+                TICKET_SOURCE,
+                "AND m.first_response_on IS NOT NULL",
+            ),
+            Metric::Resolution => (
+                "m.resolved_on",
+                "AVG(EXTRACT(EPOCH FROM m.resolved_on - t.created_on) / 60)",
+                TICKET_SOURCE,
+                "",
+            ),
+            Metric::SlaCompliance => (
+                "m.resolved_on",
+                "100 * AVG(CASE WHEN {met} THEN 1 ELSE 0 END)",
+                "ticket t JOIN ticket_metric m ON m.ticket_id = t.id \
+                    JOIN sla_policy p ON p.priority = t.priority",
+                "",
+            ),
+            Metric::Csat => (
+                "s.answered_on",
+                "100 * AVG(CASE WHEN s.rating = 'good' THEN 1 ELSE 0 END)",
+                SURVEY_SOURCE,
+                "",
+            ),
+        };
+
+        EVENT_QUERY
+            .replace("{event}", event)
+            .replace("{value}", &value.replace("{met}", SLA_MET))
+            .replace("{source}", source)
+            .replace("{group_id}", group_id)
+            .replace("{group_name}", group_name)
+            .replace("{joins}", &joins)
+            .replace("{filter}", filter)
+    }
+}
+
+/// Checks whether the database knows a timezone.
+pub fn is_timezone(conn: &mut PgConnection, name: &str) -> io::Result<bool> {
+    select(
+        sql::<Bool>("EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = ")
+            .bind::<Text, _>(name)
+            .sql(")"),
+    )
+    .get_result(conn)
+    .map_err(into_io_err)
+}
+
+/// Runs a report, passing its rows in chunks to the given function until it returns `false`.
+///
+/// Rows are fetched through a cursor, so that large reports are never held in memory.
+pub fn get_rows<F>(conn: &mut PgConnection, query: &Query, mut chunk: F) -> io::Result<()>
+where
+    F: FnMut(Vec<model::ReportRow>) -> bool,
+{
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, DieselError, _>(|| {
+        let _ = sql_query(format!(
+            "DECLARE report_rows NO SCROLL CURSOR FOR {}",
+            query.sql()
+        ))
+        .bind::<Text, _>(query.period.as_str())
+        .bind::<Timestamptz, _>(query.from)
+        .bind::<Timestamptz, _>(query.to)
+        .bind::<Text, _>(&query.timezone)
+        .execute(conn)?;
+
+        loop {
+            let rows = sql_query(format!("FETCH {} FROM report_rows", FETCH_SIZE))
+                .load::<model::ReportRow>(conn)?;
+            let last = rows.len() < FETCH_SIZE;
+            if !chunk(rows) || last {
+                break;
+            }
+        }
+
+        // Cursors outlive savepoints, so it's closed for the calls made within a transaction
+        sql_query("CLOSE report_rows").execute(conn).map(|_| ())
+    })
+    .map_err(into_io_err)
+}
+
+/// Retrieves the SLA policies of all the ticket priorities, from the lowest priority.
+pub fn get_sla_policies(conn: &mut PgConnection) -> io::Result<Vec<model::SlaPolicy>> {
+    let mut policies = sla_policy::table
+        .load::<model::SlaPolicy>(conn)
+        .map_err(into_io_err)?;
+    policies.sort_by_key(model::SlaPolicy::priority);
+
+    Ok(policies)
+}
+
+/// Updates the SLA policy of a ticket priority, returning it.
+pub fn update_sla_policy(
+    conn: &mut PgConnection,
+    priority: Priority,
+    form: &model::SlaPolicyForm,
+) -> io::Result<Option<model::SlaPolicy>> {
+    into_option(
+        diesel::update(sla_policy::table.find(priority.as_str()))
+            .set((form, sla_policy::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
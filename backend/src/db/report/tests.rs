@@ -0,0 +1,169 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+    tenant::Viewer,
+    ticket, user,
+};
+use chrono::Duration;
+use common::ticket::Status;
+use diesel::Connection;
+
+/// Category of the tickets of the tests, so that other tickets are left out.
+const CATEGORY: &str = "ut-report";
+
+/// Runs a report by category over the last and next minute, returning the rows of the tickets of
+/// the tests.
+fn report(conn: &mut PgConnection, metric: Metric) -> Vec<model::ReportRow> {
+    let now = Utc::now();
+    let query = Query {
+        metric,
+        by: Dimension::Category,
+        period: Period::Day,
+        timezone: "Europe/Madrid".to_owned(),
+        from: now - Duration::minutes(1),
+        to: now + Duration::minutes(1),
+    };
+
+    let mut rows = Vec::new();
+    get_rows(conn, &query, |chunk| {
+        rows.extend(chunk);
+        true
+    })
+    .expect("error running report");
+    rows.retain(|row| row.group_name.as_deref() == Some(CATEGORY));
+
+    rows
+}
+
+/// Sunny day unit test for reports: the milestones of the tickets are kept by the database, and
+/// aggregated per metric.
+#[test]
+fn ut_sunny_report() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("test user was not in the database");
+    let carol = user_id(&mut conn, "carol");
+    let responded = insert_ticket(
+        &mut conn,
+        &model::NewTicket {
+            category: Some(CATEGORY),
+            ..new_ticket(carol, "UT report responded")
+        },
+    )
+    .id;
+    let resolved = insert_ticket(
+        &mut conn,
+        &model::NewTicket {
+            category: Some(CATEGORY),
+            ..new_ticket(carol, "UT report resolved")
+        },
+    )
+    .id;
+
+    let _ = ticket::insert_comment(
+        &mut conn,
+        &model::NewTicketComment {
+            ticket_id: responded,
+            author_id: bob.id,
+            body: "Looking into it",
+            source: "web",
+        },
+    )
+    .expect("error inserting comment");
+    let _ = ticket::update(
+        &mut conn,
+        &Viewer::system(),
+        resolved,
+        &model::TicketChanges {
+            status: Some(Status::Resolved.as_str()),
+            ..Default::default()
+        },
+    )
+    .expect("error updating ticket");
+
+    let volume = report(&mut conn, Metric::Volume);
+    assert_eq!(volume.len(), 1);
+    assert_eq!(volume[0].value, Some(2.0));
+    let backlog = report(&mut conn, Metric::Backlog);
+    assert_eq!(backlog.last().map(|row| row.count), Some(1));
+    let first_response = report(&mut conn, Metric::FirstResponse);
+    assert_eq!(first_response[0].count, 1);
+    let resolution = report(&mut conn, Metric::Resolution);
+    assert_eq!(resolution[0].count, 1);
+    let sla = report(&mut conn, Metric::SlaCompliance);
+    assert_eq!(sla[0].value, Some(100.0));
+
+    let policies = get_sla_policies(&mut conn).expect("error retrieving SLA policies");
+    assert_eq!(
+        policies
+            .iter()
+            .map(model::SlaPolicy::priority)
+            .collect::<Vec<_>>(),
+        Priority::ALL
+    );
+    let policy = update_sla_policy(
+        &mut conn,
+        Priority::Urgent,
+        &model::SlaPolicyForm {
+            response_minutes: 30,
+            resolution_minutes: 120,
+        },
+    )
+    .expect("error updating SLA policy")
+    .expect("SLA policy was not found");
+    assert_eq!(policy.response_minutes, 30);
+    assert!(is_timezone(&mut conn, "Europe/Madrid").expect("error checking timezone"));
+}
+
+/// Rainy day unit test for reports: reopened tickets are not resolved, unknown timezones are
+/// rejected and SLA policies must be consistent.
+#[test]
+fn ut_rainy_report() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let carol = user_id(&mut conn, "carol");
+    let reopened = insert_ticket(
+        &mut conn,
+        &model::NewTicket {
+            category: Some(CATEGORY),
+            ..new_ticket(carol, "UT report reopened")
+        },
+    )
+    .id;
+    for status in [Status::Resolved, Status::Open] {
+        let _ = ticket::update(
+            &mut conn,
+            &Viewer::system(),
+            reopened,
+            &model::TicketChanges {
+                status: Some(status.as_str()),
+                ..Default::default()
+            },
+        )
+        .expect("error updating ticket");
+    }
+
+    assert!(report(&mut conn, Metric::Resolution).is_empty());
+    assert!(report(&mut conn, Metric::Csat).is_empty());
+    assert_eq!(
+        report(&mut conn, Metric::Backlog)
+            .last()
+            .map(|row| row.count),
+        Some(1)
+    );
+    assert!(!is_timezone(&mut conn, "Mars/Olympus_Mons").expect("error checking timezone"));
+    assert!(update_sla_policy(
+        &mut conn,
+        Priority::Low,
+        &model::SlaPolicyForm {
+            response_minutes: 60,
+            resolution_minutes: 30,
+        },
+    )
+    .is_err());
+}
@@ -1,22 +1,2227 @@
+table! {
+
+    /// Representation of the `approval_chain` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    approval_chain (id) {
+        /// The `id` column of the `approval_chain` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `name` column of the `approval_chain` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `steps` column of the `approval_chain` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        steps -> Jsonb,
+        /// The `created_on` column of the `approval_chain` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `approval_chain` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `article` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    article (id) {
+        /// The `id` column of the `article` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `category_id` column of the `article` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        category_id -> Nullable<Uuid>,
+        /// The `title` column of the `article` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title -> Varchar,
+        /// The `body` column of the `article` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        body -> Text,
+        /// The `status` column of the `article` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        status -> Varchar,
+        /// The `visibility` column of the `article` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        visibility -> Varchar,
+        /// The `revision` column of the `article` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        revision -> Int4,
+        /// The `author_id` column of the `article` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        author_id -> Uuid,
+        /// The `published_on` column of the `article` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        published_on -> Nullable<Timestamptz>,
+        /// The `created_on` column of the `article` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `article` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `article_category` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    article_category (id) {
+        /// The `id` column of the `article_category` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `name` column of the `article_category` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `description` column of the `article_category` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// The `created_on` column of the `article_category` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `article_revision` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    article_revision (article_id, revision) {
+        /// The `article_id` column of the `article_revision` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        article_id -> Uuid,
+        /// The `revision` column of the `article_revision` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        revision -> Int4,
+        /// The `title` column of the `article_revision` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title -> Varchar,
+        /// The `body` column of the `article_revision` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        body -> Text,
+        /// The `editor_id` column of the `article_revision` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        editor_id -> Uuid,
+        /// The `created_on` column of the `article_revision` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `automation_log` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    automation_log (id) {
+        /// The `id` column of the `automation_log` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `rule_id` column of the `automation_log` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rule_id -> Uuid,
+        /// The `ticket_id` column of the `automation_log` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `event` column of the `automation_log` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        event -> Varchar,
+        /// The `depth` column of the `automation_log` table.
+        ///
+        /// Its SQL type is `Int2`.
+        ///
+        /// (Automatically generated by Diesel.)
+        depth -> Int2,
+        /// The `actions` column of the `automation_log` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        actions -> Jsonb,
+        /// The `error` column of the `automation_log` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        error -> Nullable<Text>,
+        /// The `created_on` column of the `automation_log` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `automation_rule` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    automation_rule (id) {
+        /// The `id` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `name` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `enabled` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        enabled -> Bool,
+        /// The `position` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        position -> Int4,
+        /// The `condition` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        condition -> Jsonb,
+        /// The `actions` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        actions -> Jsonb,
+        /// The `created_by` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Nullable<Uuid>,
+        /// The `created_on` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `automation_rule` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `board_column` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    board_column (grouping, column_key) {
+        /// The `grouping` column of the `board_column` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        grouping -> Varchar,
+        /// The `column_key` column of the `board_column` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        column_key -> Varchar,
+        /// The `wip_limit` column of the `board_column` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        wip_limit -> Int4,
+        /// The `updated_on` column of the `board_column` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `cab_chain` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    cab_chain (risk) {
+        /// The `risk` column of the `cab_chain` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        risk -> Varchar,
+        /// The `approval_chain_id` column of the `cab_chain` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        approval_chain_id -> Uuid,
+    }
+}
+
+table! {
+
+    /// Representation of the `catalog_item` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    catalog_item (id) {
+        /// The `id` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `name` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `description` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// The `form` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        form -> Jsonb,
+        /// The `priority` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        priority -> Varchar,
+        /// The `queue_id` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        queue_id -> Nullable<Uuid>,
+        /// The `active` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        active -> Bool,
+        /// The `created_on` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+        /// The `approval_chain_id` column of the `catalog_item` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        approval_chain_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+
+    /// Representation of the `change_ci` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    change_ci (change_id, ci_id) {
+        /// The `change_id` column of the `change_ci` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        change_id -> Uuid,
+        /// The `ci_id` column of the `change_ci` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ci_id -> Uuid,
+    }
+}
+
+table! {
+
+    /// Representation of the `change_request` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    change_request (id) {
+        /// The `id` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `ticket_id` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `planned_start` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        planned_start -> Timestamptz,
+        /// The `planned_end` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        planned_end -> Timestamptz,
+        /// The `answers` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        answers -> Jsonb,
+        /// The `risk_score` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        risk_score -> Int4,
+        /// The `impact_score` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        impact_score -> Int4,
+        /// The `risk` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        risk -> Varchar,
+        /// The `created_on` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `change_request` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ci_relationship` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ci_relationship (id) {
+        /// The `id` column of the `ci_relationship` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `source_id` column of the `ci_relationship` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        source_id -> Uuid,
+        /// The `target_id` column of the `ci_relationship` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        target_id -> Uuid,
+        /// The `kind` column of the `ci_relationship` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Varchar,
+        /// The `created_on` column of the `ci_relationship` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ci_type` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ci_type (id) {
+        /// The `id` column of the `ci_type` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `name` column of the `ci_type` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `attributes` column of the `ci_type` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        attributes -> Jsonb,
+        /// The `created_on` column of the `ci_type` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `ci_type` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `configuration_item` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    configuration_item (id) {
+        /// The `id` column of the `configuration_item` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `type_id` column of the `configuration_item` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        type_id -> Uuid,
+        /// The `name` column of the `configuration_item` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `attributes` column of the `configuration_item` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        attributes -> Jsonb,
+        /// The `created_on` column of the `configuration_item` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `configuration_item` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `contract` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    contract (id) {
+        /// The `id` column of the `contract` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `organisation_id` column of the `contract` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        organisation_id -> Uuid,
+        /// The `name` column of the `contract` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `prepaid_minutes` column of the `contract` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        prepaid_minutes -> Int4,
+        /// The `starts_on` column of the `contract` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        starts_on -> Timestamptz,
+        /// The `ends_on` column of the `contract` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ends_on -> Nullable<Timestamptz>,
+        /// The `created_on` column of the `contract` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `contract` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `custom_field` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    custom_field (id) {
+        /// The `id` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `key` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        key -> Varchar,
+        /// The `label` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        label -> Varchar,
+        /// The `kind` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Jsonb,
+        /// The `required` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        required -> Bool,
+        /// The `categories` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Array<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        categories -> Array<Text>,
+        /// The `position` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        position -> Int4,
+        /// The `created_on` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `custom_field` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `dashboard` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    dashboard (user_id) {
+        /// The `user_id` column of the `dashboard` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Uuid,
+        /// The `widgets` column of the `dashboard` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        widgets -> Jsonb,
+        /// The `updated_on` column of the `dashboard` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `inbound_email` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    inbound_email (id) {
+        /// The `id` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `message_id` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        message_id -> Nullable<Varchar>,
+        /// The `from_address` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        from_address -> Varchar,
+        /// The `subject` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        subject -> Text,
+        /// The `outcome` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        outcome -> Varchar,
+        /// The `reason` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        reason -> Nullable<Text>,
+        /// The `ticket_id` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Nullable<Uuid>,
+        /// The `comment_id` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        comment_id -> Nullable<Uuid>,
+        /// The `created_on` column of the `inbound_email` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `maintenance_window` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    maintenance_window (id) {
+        /// The `id` column of the `maintenance_window` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `title` column of the `maintenance_window` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title -> Varchar,
+        /// The `starts_on` column of the `maintenance_window` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        starts_on -> Timestamptz,
+        /// The `ends_on` column of the `maintenance_window` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ends_on -> Timestamptz,
+        /// The `created_by` column of the `maintenance_window` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Uuid,
+        /// The `created_on` column of the `maintenance_window` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `maintenance_window` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `maintenance_window_ci` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    maintenance_window_ci (window_id, ci_id) {
+        /// The `window_id` column of the `maintenance_window_ci` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        window_id -> Uuid,
+        /// The `ci_id` column of the `maintenance_window_ci` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ci_id -> Uuid,
+    }
+}
+
+table! {
+
+    /// Representation of the `notification` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    notification (id) {
+        /// The `id` column of the `notification` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `user_id` column of the `notification` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Uuid,
+        /// The `kind` column of the `notification` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Varchar,
+        /// The `title` column of the `notification` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title -> Varchar,
+        /// The `body` column of the `notification` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        body -> Varchar,
+        /// The `ticket_id` column of the `notification` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Nullable<Uuid>,
+        /// The `read_on` column of the `notification` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        read_on -> Nullable<Timestamptz>,
+        /// The `created_on` column of the `notification` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `email` column of the `notification` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        email -> Nullable<Varchar>,
+        /// The `emailed_on` column of the `notification` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        emailed_on -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+
+    /// Representation of the `notification_preference` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    notification_preference (user_id, kind) {
+        /// The `user_id` column of the `notification_preference` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Uuid,
+        /// The `kind` column of the `notification_preference` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Varchar,
+        /// The `delivery` column of the `notification_preference` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        delivery -> Varchar,
+        /// The `updated_on` column of the `notification_preference` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `organisation` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    organisation (id) {
+        /// The `id` column of the `organisation` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `name` column of the `organisation` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `created_on` column of the `organisation` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `organisation` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `organisation_branding` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    organisation_branding (organisation_id) {
+        /// The `organisation_id` column of the `organisation_branding` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        organisation_id -> Uuid,
+        /// The `logo_url` column of the `organisation_branding` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        logo_url -> Nullable<Varchar>,
+        /// The `primary_colour` column of the `organisation_branding` table.
+        ///
+        /// Its SQL type is `Bpchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        primary_colour -> Bpchar,
+        /// The `accent_colour` column of the `organisation_branding` table.
+        ///
+        /// Its SQL type is `Bpchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        accent_colour -> Bpchar,
+        /// The `welcome_text` column of the `organisation_branding` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        welcome_text -> Varchar,
+        /// The `updated_on` column of the `organisation_branding` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `organisation_domain` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    organisation_domain (domain) {
+        /// The `domain` column of the `organisation_domain` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        domain -> Varchar,
+        /// The `organisation_id` column of the `organisation_domain` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        organisation_id -> Uuid,
+    }
+}
+
+table! {
+
+    /// Representation of the `organisation_member` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    organisation_member (organisation_id, user_id) {
+        /// The `organisation_id` column of the `organisation_member` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        organisation_id -> Uuid,
+        /// The `user_id` column of the `organisation_member` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Uuid,
+        /// The `created_on` column of the `organisation_member` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `problem` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    problem (id) {
+        /// The `id` column of the `problem` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `number` column of the `problem` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        number -> Int8,
+        /// The `title` column of the `problem` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title -> Varchar,
+        /// The `description` column of the `problem` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// The `status` column of the `problem` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        status -> Varchar,
+        /// The `root_cause` column of the `problem` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        root_cause -> Text,
+        /// The `workaround` column of the `problem` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        workaround -> Text,
+        /// The `owner_id` column of the `problem` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        owner_id -> Nullable<Uuid>,
+        /// The `article_id` column of the `problem` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        article_id -> Nullable<Uuid>,
+        /// The `created_by` column of the `problem` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Uuid,
+        /// The `resolved_on` column of the `problem` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        resolved_on -> Nullable<Timestamptz>,
+        /// The `created_on` column of the `problem` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `problem` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `problem_ticket` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    problem_ticket (problem_id, ticket_id) {
+        /// The `problem_id` column of the `problem_ticket` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        problem_id -> Uuid,
+        /// The `ticket_id` column of the `problem_ticket` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `linked_by` column of the `problem_ticket` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        linked_by -> Uuid,
+        /// The `created_on` column of the `problem_ticket` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `queue` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    queue (id) {
+        /// The `id` column of the `queue` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `name` column of the `queue` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `team_id` column of the `queue` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        team_id -> Uuid,
+        /// The `strategy` column of the `queue` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        strategy -> Varchar,
+        /// The `skills` column of the `queue` table.
+        ///
+        /// Its SQL type is `Array<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        skills -> Array<Text>,
+        /// The `is_default` column of the `queue` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        is_default -> Bool,
+        /// The `last_assignee_id` column of the `queue` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_assignee_id -> Nullable<Uuid>,
+        /// The `created_on` column of the `queue` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `queue` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `service_request` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    service_request (ticket_id) {
+        /// The `ticket_id` column of the `service_request` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `catalog_item_id` column of the `service_request` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        catalog_item_id -> Nullable<Uuid>,
+        /// The `form` column of the `service_request` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        form -> Jsonb,
+        /// The `answers` column of the `service_request` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        answers -> Jsonb,
+        /// The `created_on` column of the `service_request` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `sla_policy` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    sla_policy (priority) {
+        /// The `priority` column of the `sla_policy` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        priority -> Varchar,
+        /// The `response_minutes` column of the `sla_policy` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        response_minutes -> Int4,
+        /// The `resolution_minutes` column of the `sla_policy` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        resolution_minutes -> Int4,
+        /// The `updated_on` column of the `sla_policy` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `survey` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    survey (id) {
+        /// The `id` column of the `survey` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `ticket_id` column of the `survey` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `agent_id` column of the `survey` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        agent_id -> Nullable<Uuid>,
+        /// The `token` column of the `survey` table.
+        ///
+        /// Its SQL type is `Bpchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        token -> Bpchar,
+        /// The `rating` column of the `survey` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        rating -> Nullable<Varchar>,
+        /// The `comment` column of the `survey` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        comment -> Text,
+        /// The `sent_on` column of the `survey` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        sent_on -> Nullable<Timestamptz>,
+        /// The `answered_on` column of the `survey` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        answered_on -> Nullable<Timestamptz>,
+        /// The `created_on` column of the `survey` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `survey_suppression` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    survey_suppression (category) {
+        /// The `category` column of the `survey_suppression` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        category -> Varchar,
+        /// The `created_on` column of the `survey_suppression` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `sys_audit_log` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    sys_audit_log (id) {
+        /// The `id` column of the `sys_audit_log` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `kind` column of the `sys_audit_log` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Varchar,
+        /// The `actor_id` column of the `sys_audit_log` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        actor_id -> Nullable<Uuid>,
+        /// The `ip_address` column of the `sys_audit_log` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ip_address -> Nullable<Varchar>,
+        /// The `user_agent` column of the `sys_audit_log` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_agent -> Nullable<Text>,
+        /// The `request_id` column of the `sys_audit_log` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        request_id -> Varchar,
+        /// The `details` column of the `sys_audit_log` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        details -> Jsonb,
+        /// The `created_on` column of the `sys_audit_log` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
 table! {
 
     /// Representation of the `sys_email_registration` table.
     ///
     /// (Automatically generated by Diesel.)
-    sys_email_registration (code) {
-        /// The `code` column of the `sys_email_registration` table.
+    sys_email_registration (code) {
+        /// The `code` column of the `sys_email_registration` table.
+        ///
+        /// Its SQL type is `Bpchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        code -> Bpchar,
+        /// The `email` column of the `sys_email_registration` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        email -> Varchar,
+        /// The `created_on` column of the `sys_email_registration` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `organisation_id` column of the `sys_email_registration` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        organisation_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+
+    /// Representation of the `sys_invitation` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    sys_invitation (code) {
+        /// The `code` column of the `sys_invitation` table.
+        ///
+        /// Its SQL type is `Bpchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        code -> Bpchar,
+        /// The `email` column of the `sys_invitation` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        email -> Varchar,
+        /// The `role` column of the `sys_invitation` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        role -> Varchar,
+        /// The `invited_by` column of the `sys_invitation` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        invited_by -> Uuid,
+        /// The `created_on` column of the `sys_invitation` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `organisation_id` column of the `sys_invitation` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        organisation_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+
+    /// Representation of the `sys_session` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    sys_session (token_hash) {
+        /// The `token_hash` column of the `sys_session` table.
+        ///
+        /// Its SQL type is `Bytea`.
+        ///
+        /// (Automatically generated by Diesel.)
+        token_hash -> Bytea,
+        /// The `user_id` column of the `sys_session` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Uuid,
+        /// The `created_on` column of the `sys_session` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `sys_user` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    sys_user (id) {
+        /// The `id` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `active` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        active -> Bool,
+        /// The `username` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        username -> Varchar,
+        /// The `email` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        email -> Varchar,
+        /// The `password` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Bytea`.
+        ///
+        /// (Automatically generated by Diesel.)
+        password -> Bytea,
+        /// The `first_name` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        first_name -> Varchar,
+        /// The `last_name` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        last_name -> Varchar,
+        /// The `created_on` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+        /// The `role` column of the `sys_user` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        role -> Varchar,
+    }
+}
+
+table! {
+
+    /// Representation of the `team` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    team (id) {
+        /// The `id` column of the `team` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `name` column of the `team` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `created_on` column of the `team` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `team` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `team_member` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    team_member (team_id, user_id) {
+        /// The `team_id` column of the `team_member` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        team_id -> Uuid,
+        /// The `user_id` column of the `team_member` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Uuid,
+        /// The `skills` column of the `team_member` table.
+        ///
+        /// Its SQL type is `Array<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        skills -> Array<Text>,
+        /// The `created_on` column of the `team_member` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket (id) {
+        /// The `id` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `number` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        number -> Int8,
+        /// The `title` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        title -> Varchar,
+        /// The `description` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// The `status` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        status -> Varchar,
+        /// The `priority` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        priority -> Varchar,
+        /// The `requester_id` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        requester_id -> Uuid,
+        /// The `assignee_id` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        assignee_id -> Nullable<Uuid>,
+        /// The `organisation_id` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        organisation_id -> Nullable<Uuid>,
+        /// The `created_on` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+        /// The `queue_id` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        queue_id -> Nullable<Uuid>,
+        /// The `category` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        category -> Nullable<Varchar>,
+        /// The `tags` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Array<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        tags -> Array<Text>,
+        /// The `custom_fields` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        custom_fields -> Jsonb,
+        /// The `merged_into_id` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        merged_into_id -> Nullable<Uuid>,
+        /// The `parent_id` column of the `ticket` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        parent_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_approval` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_approval (ticket_id) {
+        /// The `ticket_id` column of the `ticket_approval` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `steps` column of the `ticket_approval` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        steps -> Jsonb,
+        /// The `step` column of the `ticket_approval` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        step -> Int4,
+        /// The `status` column of the `ticket_approval` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        status -> Varchar,
+        /// The `created_on` column of the `ticket_approval` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `ticket_approval` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_approver` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_approver (ticket_id, step, user_id) {
+        /// The `ticket_id` column of the `ticket_approver` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `step` column of the `ticket_approver` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        step -> Int4,
+        /// The `user_id` column of the `ticket_approver` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Uuid,
+        /// The `token` column of the `ticket_approver` table.
+        ///
+        /// Its SQL type is `Bpchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        token -> Bpchar,
+        /// The `decision` column of the `ticket_approver` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        decision -> Nullable<Varchar>,
+        /// The `comment` column of the `ticket_approver` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        comment -> Text,
+        /// The `decided_on` column of the `ticket_approver` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        decided_on -> Nullable<Timestamptz>,
+        /// The `created_on` column of the `ticket_approver` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_article` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_article (ticket_id, article_id) {
+        /// The `ticket_id` column of the `ticket_article` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `article_id` column of the `ticket_article` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        article_id -> Uuid,
+        /// The `linked_by` column of the `ticket_article` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        linked_by -> Uuid,
+        /// The `created_on` column of the `ticket_article` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_assignment` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_assignment (id) {
+        /// The `id` column of the `ticket_assignment` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `ticket_id` column of the `ticket_assignment` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `previous_assignee_id` column of the `ticket_assignment` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        previous_assignee_id -> Nullable<Uuid>,
+        /// The `assignee_id` column of the `ticket_assignment` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        assignee_id -> Nullable<Uuid>,
+        /// The `queue_id` column of the `ticket_assignment` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        queue_id -> Nullable<Uuid>,
+        /// The `strategy` column of the `ticket_assignment` table.
+        ///
+        /// Its SQL type is `Nullable<Varchar>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        strategy -> Nullable<Varchar>,
+        /// The `assigned_by` column of the `ticket_assignment` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        assigned_by -> Nullable<Uuid>,
+        /// The `created_on` column of the `ticket_assignment` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_attachment` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_attachment (id) {
+        /// The `id` column of the `ticket_attachment` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `ticket_id` column of the `ticket_attachment` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `comment_id` column of the `ticket_attachment` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        comment_id -> Nullable<Uuid>,
+        /// The `filename` column of the `ticket_attachment` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        filename -> Varchar,
+        /// The `content_type` column of the `ticket_attachment` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        content_type -> Varchar,
+        /// The `size` column of the `ticket_attachment` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        size -> Int4,
+        /// The `data` column of the `ticket_attachment` table.
+        ///
+        /// Its SQL type is `Bytea`.
+        ///
+        /// (Automatically generated by Diesel.)
+        data -> Bytea,
+        /// The `created_on` column of the `ticket_attachment` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_ci` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_ci (ticket_id, ci_id) {
+        /// The `ticket_id` column of the `ticket_ci` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `ci_id` column of the `ticket_ci` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ci_id -> Uuid,
+        /// The `linked_by` column of the `ticket_ci` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        linked_by -> Uuid,
+        /// The `created_on` column of the `ticket_ci` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_comment` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_comment (id) {
+        /// The `id` column of the `ticket_comment` table.
         ///
-        /// Its SQL type is `Bpchar`.
+        /// Its SQL type is `Uuid`.
         ///
         /// (Automatically generated by Diesel.)
-        code -> Bpchar,
-        /// The `email` column of the `sys_email_registration` table.
+        id -> Uuid,
+        /// The `ticket_id` column of the `ticket_comment` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `author_id` column of the `ticket_comment` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        author_id -> Nullable<Uuid>,
+        /// The `body` column of the `ticket_comment` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        body -> Text,
+        /// The `source` column of the `ticket_comment` table.
         ///
         /// Its SQL type is `Varchar`.
         ///
         /// (Automatically generated by Diesel.)
-        email -> Varchar,
-        /// The `created_on` column of the `sys_email_registration` table.
+        source -> Varchar,
+        /// The `created_on` column of the `ticket_comment` table.
         ///
         /// Its SQL type is `Timestamptz`.
         ///
@@ -27,59 +2232,347 @@ table! {
 
 table! {
 
-    /// Representation of the `sys_user` table.
+    /// Representation of the `ticket_link` table.
     ///
     /// (Automatically generated by Diesel.)
-    sys_user (id) {
-        /// The `id` column of the `sys_user` table.
+    ticket_link (ticket_id, linked_id) {
+        /// The `ticket_id` column of the `ticket_link` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `linked_id` column of the `ticket_link` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        linked_id -> Uuid,
+        /// The `kind` column of the `ticket_link` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        kind -> Varchar,
+        /// The `created_by` column of the `ticket_link` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Uuid,
+        /// The `created_on` column of the `ticket_link` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_metric` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_metric (ticket_id) {
+        /// The `ticket_id` column of the `ticket_metric` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `first_response_on` column of the `ticket_metric` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        first_response_on -> Nullable<Timestamptz>,
+        /// The `resolved_on` column of the `ticket_metric` table.
+        ///
+        /// Its SQL type is `Nullable<Timestamptz>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        resolved_on -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_view` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_view (id) {
+        /// The `id` column of the `ticket_view` table.
         ///
         /// Its SQL type is `Uuid`.
         ///
         /// (Automatically generated by Diesel.)
         id -> Uuid,
-        /// The `active` column of the `sys_user` table.
+        /// The `owner_id` column of the `ticket_view` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        owner_id -> Uuid,
+        /// The `name` column of the `ticket_view` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        name -> Varchar,
+        /// The `query` column of the `ticket_view` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        query -> Varchar,
+        /// The `shared` column of the `ticket_view` table.
         ///
         /// Its SQL type is `Bool`.
         ///
         /// (Automatically generated by Diesel.)
-        active -> Bool,
-        /// The `username` column of the `sys_user` table.
+        shared -> Bool,
+        /// The `created_on` column of the `ticket_view` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `ticket_view` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `ticket_watcher` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    ticket_watcher (ticket_id, user_id) {
+        /// The `ticket_id` column of the `ticket_watcher` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `user_id` column of the `ticket_watcher` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        user_id -> Uuid,
+        /// The `created_on` column of the `ticket_watcher` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `webhook_delivery` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    webhook_delivery (id) {
+        /// The `id` column of the `webhook_delivery` table.
+        ///
+        /// Its SQL type is `Int8`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Int8,
+        /// The `subscription_id` column of the `webhook_delivery` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        subscription_id -> Uuid,
+        /// The `event` column of the `webhook_delivery` table.
         ///
         /// Its SQL type is `Varchar`.
         ///
         /// (Automatically generated by Diesel.)
-        username -> Varchar,
-        /// The `email` column of the `sys_user` table.
+        event -> Varchar,
+        /// The `payload` column of the `webhook_delivery` table.
+        ///
+        /// Its SQL type is `Jsonb`.
+        ///
+        /// (Automatically generated by Diesel.)
+        payload -> Jsonb,
+        /// The `status` column of the `webhook_delivery` table.
         ///
         /// Its SQL type is `Varchar`.
         ///
         /// (Automatically generated by Diesel.)
-        email -> Varchar,
-        /// The `password` column of the `sys_user` table.
+        status -> Varchar,
+        /// The `attempts` column of the `webhook_delivery` table.
         ///
-        /// Its SQL type is `Bytea`.
+        /// Its SQL type is `Int4`.
         ///
         /// (Automatically generated by Diesel.)
-        password -> Bytea,
-        /// The `first_name` column of the `sys_user` table.
+        attempts -> Int4,
+        /// The `next_attempt_on` column of the `webhook_delivery` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        next_attempt_on -> Timestamptz,
+        /// The `response_code` column of the `webhook_delivery` table.
+        ///
+        /// Its SQL type is `Nullable<Int2>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        response_code -> Nullable<Int2>,
+        /// The `error` column of the `webhook_delivery` table.
+        ///
+        /// Its SQL type is `Nullable<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        error -> Nullable<Text>,
+        /// The `created_on` column of the `webhook_delivery` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `webhook_delivery` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `webhook_subscription` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    webhook_subscription (id) {
+        /// The `id` column of the `webhook_subscription` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `url` column of the `webhook_subscription` table.
         ///
         /// Its SQL type is `Varchar`.
         ///
         /// (Automatically generated by Diesel.)
-        first_name -> Varchar,
-        /// The `last_name` column of the `sys_user` table.
+        url -> Varchar,
+        /// The `events` column of the `webhook_subscription` table.
+        ///
+        /// Its SQL type is `Array<Text>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        events -> Array<Text>,
+        /// The `secret` column of the `webhook_subscription` table.
         ///
         /// Its SQL type is `Varchar`.
         ///
         /// (Automatically generated by Diesel.)
-        last_name -> Varchar,
-        /// The `created_on` column of the `sys_user` table.
+        secret -> Varchar,
+        /// The `enabled` column of the `webhook_subscription` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        enabled -> Bool,
+        /// The `consecutive_failures` column of the `webhook_subscription` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        consecutive_failures -> Int4,
+        /// The `created_by` column of the `webhook_subscription` table.
+        ///
+        /// Its SQL type is `Nullable<Uuid>`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_by -> Nullable<Uuid>,
+        /// The `created_on` column of the `webhook_subscription` table.
         ///
         /// Its SQL type is `Timestamptz`.
         ///
         /// (Automatically generated by Diesel.)
         created_on -> Timestamptz,
-        /// The `updated_on` column of the `sys_user` table.
+        /// The `updated_on` column of the `webhook_subscription` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        updated_on -> Timestamptz,
+    }
+}
+
+table! {
+
+    /// Representation of the `work_log` table.
+    ///
+    /// (Automatically generated by Diesel.)
+    work_log (id) {
+        /// The `id` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        id -> Uuid,
+        /// The `ticket_id` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        ticket_id -> Uuid,
+        /// The `agent_id` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Uuid`.
+        ///
+        /// (Automatically generated by Diesel.)
+        agent_id -> Uuid,
+        /// The `minutes` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Int4`.
+        ///
+        /// (Automatically generated by Diesel.)
+        minutes -> Int4,
+        /// The `billable` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Bool`.
+        ///
+        /// (Automatically generated by Diesel.)
+        billable -> Bool,
+        /// The `activity` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Varchar`.
+        ///
+        /// (Automatically generated by Diesel.)
+        activity -> Varchar,
+        /// The `description` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Text`.
+        ///
+        /// (Automatically generated by Diesel.)
+        description -> Text,
+        /// The `worked_on` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        worked_on -> Timestamptz,
+        /// The `created_on` column of the `work_log` table.
+        ///
+        /// Its SQL type is `Timestamptz`.
+        ///
+        /// (Automatically generated by Diesel.)
+        created_on -> Timestamptz,
+        /// The `updated_on` column of the `work_log` table.
         ///
         /// Its SQL type is `Timestamptz`.
         ///
@@ -88,7 +2581,131 @@ table! {
     }
 }
 
+joinable!(article -> article_category (category_id));
+joinable!(article -> sys_user (author_id));
+joinable!(article_revision -> article (article_id));
+joinable!(article_revision -> sys_user (editor_id));
+joinable!(automation_log -> automation_rule (rule_id));
+joinable!(automation_log -> ticket (ticket_id));
+joinable!(automation_rule -> sys_user (created_by));
+joinable!(cab_chain -> approval_chain (approval_chain_id));
+joinable!(catalog_item -> approval_chain (approval_chain_id));
+joinable!(catalog_item -> queue (queue_id));
+joinable!(change_ci -> change_request (change_id));
+joinable!(change_ci -> configuration_item (ci_id));
+joinable!(change_request -> ticket (ticket_id));
+joinable!(configuration_item -> ci_type (type_id));
+joinable!(contract -> organisation (organisation_id));
+joinable!(dashboard -> sys_user (user_id));
+joinable!(inbound_email -> ticket (ticket_id));
+joinable!(inbound_email -> ticket_comment (comment_id));
+joinable!(maintenance_window -> sys_user (created_by));
+joinable!(maintenance_window_ci -> configuration_item (ci_id));
+joinable!(maintenance_window_ci -> maintenance_window (window_id));
+joinable!(notification -> sys_user (user_id));
+joinable!(notification -> ticket (ticket_id));
+joinable!(notification_preference -> sys_user (user_id));
+joinable!(organisation_branding -> organisation (organisation_id));
+joinable!(organisation_domain -> organisation (organisation_id));
+joinable!(organisation_member -> organisation (organisation_id));
+joinable!(organisation_member -> sys_user (user_id));
+joinable!(problem -> article (article_id));
+joinable!(problem_ticket -> problem (problem_id));
+joinable!(problem_ticket -> sys_user (linked_by));
+joinable!(problem_ticket -> ticket (ticket_id));
+joinable!(queue -> sys_user (last_assignee_id));
+joinable!(queue -> team (team_id));
+joinable!(service_request -> catalog_item (catalog_item_id));
+joinable!(service_request -> ticket (ticket_id));
+joinable!(survey -> sys_user (agent_id));
+joinable!(survey -> ticket (ticket_id));
+joinable!(sys_email_registration -> organisation (organisation_id));
+joinable!(sys_invitation -> organisation (organisation_id));
+joinable!(sys_invitation -> sys_user (invited_by));
+joinable!(sys_session -> sys_user (user_id));
+joinable!(team_member -> sys_user (user_id));
+joinable!(team_member -> team (team_id));
+joinable!(ticket -> organisation (organisation_id));
+joinable!(ticket -> queue (queue_id));
+joinable!(ticket_approval -> ticket (ticket_id));
+joinable!(ticket_approver -> sys_user (user_id));
+joinable!(ticket_approver -> ticket_approval (ticket_id));
+joinable!(ticket_article -> article (article_id));
+joinable!(ticket_article -> sys_user (linked_by));
+joinable!(ticket_article -> ticket (ticket_id));
+joinable!(ticket_assignment -> queue (queue_id));
+joinable!(ticket_assignment -> ticket (ticket_id));
+joinable!(ticket_attachment -> ticket (ticket_id));
+joinable!(ticket_attachment -> ticket_comment (comment_id));
+joinable!(ticket_ci -> configuration_item (ci_id));
+joinable!(ticket_ci -> sys_user (linked_by));
+joinable!(ticket_ci -> ticket (ticket_id));
+joinable!(ticket_comment -> sys_user (author_id));
+joinable!(ticket_comment -> ticket (ticket_id));
+joinable!(ticket_link -> sys_user (created_by));
+joinable!(ticket_metric -> ticket (ticket_id));
+joinable!(ticket_view -> sys_user (owner_id));
+joinable!(ticket_watcher -> sys_user (user_id));
+joinable!(ticket_watcher -> ticket (ticket_id));
+joinable!(webhook_delivery -> webhook_subscription (subscription_id));
+joinable!(webhook_subscription -> sys_user (created_by));
+joinable!(work_log -> sys_user (agent_id));
+joinable!(work_log -> ticket (ticket_id));
+
 allow_tables_to_appear_in_same_query!(
+    approval_chain,
+    article,
+    article_category,
+    article_revision,
+    automation_log,
+    automation_rule,
+    board_column,
+    cab_chain,
+    catalog_item,
+    change_ci,
+    change_request,
+    ci_relationship,
+    ci_type,
+    configuration_item,
+    contract,
+    custom_field,
+    dashboard,
+    inbound_email,
+    maintenance_window,
+    maintenance_window_ci,
+    notification,
+    notification_preference,
+    organisation,
+    organisation_branding,
+    organisation_domain,
+    organisation_member,
+    problem,
+    problem_ticket,
+    queue,
+    service_request,
+    sla_policy,
+    survey,
+    survey_suppression,
+    sys_audit_log,
     sys_email_registration,
+    sys_invitation,
+    sys_session,
     sys_user,
+    team,
+    team_member,
+    ticket,
+    ticket_approval,
+    ticket_approver,
+    ticket_article,
+    ticket_assignment,
+    ticket_attachment,
+    ticket_ci,
+    ticket_comment,
+    ticket_link,
+    ticket_metric,
+    ticket_view,
+    ticket_watcher,
+    webhook_delivery,
+    webhook_subscription,
+    work_log,
 );
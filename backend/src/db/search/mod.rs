@@ -0,0 +1,109 @@
+use super::{
+    model::{self, HIGHLIGHT_END, HIGHLIGHT_START},
+    tenant::{self, Viewer},
+};
+use chrono::{DateTime, Utc};
+use diesel::{
+    pg::types::sql_types::Array,
+    sql_query,
+    sql_types::{Bool, Int8, Jsonb, Nullable, Text, Timestamptz, Uuid as SqlUuid, Varchar},
+    PgConnection, RunQueryDsl,
+};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Text search configuration used for the search documents and queries.
+///
+/// It must match the one of the generated `search` columns.
+const CONFIG: &str = "english";
+
+/// Query searching the tickets, and the comments of the tickets, visible to a viewer.
+///
+/// Tickets are ranked by the relevance of their title and description plus the relevance of their
+/// best matching comment. The snippet comes from that comment only if the ticket itself does not
+/// match.
+const TICKET_QUERY: &str = "\
+SELECT t.id, t.number, t.status, t.priority, t.assignee_id, t.organisation_id, t.created_on,
+    ts_headline($1::regconfig, t.title, q.query, $3 || ', HighlightAll=true') AS title,
+    ts_headline(
+        $1::regconfig,
+        CASE WHEN t.search @@ q.query OR c.body IS NULL THEN t.description ELSE c.body END,
+        q.query,
+        $3
+    ) AS snippet,
+    ts_rank_cd(t.search, q.query) + coalesce(c.rank, 0) AS rank
+FROM ticket t
+CROSS JOIN websearch_to_tsquery($1::regconfig, $2) AS q(query)
+LEFT JOIN LATERAL (
+    SELECT tc.body, ts_rank_cd(tc.search, q.query) AS rank
+    FROM ticket_comment tc
+    WHERE tc.ticket_id = t.id AND tc.search @@ q.query
+    ORDER BY rank DESC
+    LIMIT 1
+) c ON TRUE
+WHERE (t.search @@ q.query OR c.body IS NOT NULL)
+    AND ($4 OR t.requester_id = $5 OR t.organisation_id = ANY ($6))
+    AND ($7::varchar IS NULL OR t.status = $7)
+    AND ($8::uuid IS NULL OR t.assignee_id = $8)
+    AND ($9::uuid IS NULL OR t.organisation_id = $9)
+    AND ($10::timestamptz IS NULL OR t.created_on >= $10)
+    AND ($11::timestamptz IS NULL OR t.created_on < $11)
+    AND ($12::jsonb IS NULL OR t.custom_fields @> $12)
+ORDER BY rank DESC, t.number DESC
+LIMIT $13 OFFSET $14";
+
+/// Filter for ticket searches.
+#[derive(Debug, Clone, Default)]
+pub struct TicketFilter {
+    /// Only retrieve tickets with this status.
+    pub status: Option<String>,
+    /// Only retrieve tickets assigned to this agent.
+    pub assignee_id: Option<Uuid>,
+    /// Only retrieve tickets of this organisation.
+    pub organisation_id: Option<Uuid>,
+    /// Only retrieve tickets created at or after this moment.
+    pub since: Option<DateTime<Utc>>,
+    /// Only retrieve tickets created before this moment.
+    pub until: Option<DateTime<Utc>>,
+    /// Only retrieve tickets with these values of custom fields, as a JSON object.
+    pub custom_fields: Option<serde_json::Value>,
+}
+
+/// Searches the tickets visible to the viewer, most relevant first.
+///
+/// The query supports the web search syntax: quoted phrases, `or` and `-` to exclude words.
+pub fn tickets(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    query: &str,
+    filter: &TicketFilter,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::TicketHit>> {
+    let headline_options = format!(
+        "StartSel={}, StopSel={}, MaxFragments=2, MinWords=5, MaxWords=20",
+        HIGHLIGHT_START, HIGHLIGHT_END
+    );
+
+    tenant::run(conn, viewer, |conn| {
+        sql_query(TICKET_QUERY)
+            .bind::<Text, _>(CONFIG)
+            .bind::<Text, _>(query)
+            .bind::<Text, _>(&headline_options)
+            .bind::<Bool, _>(viewer.sees_all())
+            .bind::<SqlUuid, _>(viewer.user_id)
+            .bind::<Array<SqlUuid>, _>(&viewer.organisation_ids)
+            .bind::<Nullable<Varchar>, _>(filter.status.as_deref())
+            .bind::<Nullable<SqlUuid>, _>(filter.assignee_id)
+            .bind::<Nullable<SqlUuid>, _>(filter.organisation_id)
+            .bind::<Nullable<Timestamptz>, _>(filter.since)
+            .bind::<Nullable<Timestamptz>, _>(filter.until)
+            .bind::<Nullable<Jsonb>, _>(&filter.custom_fields)
+            .bind::<Int8, _>(limit)
+            .bind::<Int8, _>(offset)
+            .load(conn)
+    })
+}
@@ -0,0 +1,151 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, viewer},
+    ticket,
+};
+use common::ticket::Status;
+use diesel::sql_types::BigInt;
+
+/// Inserts a ticket for Carol's organisation.
+fn insert_acme_ticket(conn: &mut PgConnection, title: &str, description: &str) -> model::Ticket {
+    let carol = viewer(conn, "carol");
+    insert_ticket(
+        conn,
+        &model::NewTicket {
+            description,
+            organisation_id: carol.organisation_ids.first().copied(),
+            ..new_ticket(carol.user_id, title)
+        },
+    )
+}
+
+/// Searches the tickets, without filters.
+fn search(conn: &mut PgConnection, viewer: &Viewer, query: &str) -> Vec<model::TicketHit> {
+    tickets(conn, viewer, query, &TicketFilter::default(), 500, 0).expect("error searching tickets")
+}
+
+/// Sunny day unit test for the ticket search.
+#[test]
+fn ut_sunny_search_tickets() {
+    let mut conn = establish_connection();
+    let in_title = insert_acme_ticket(&mut conn, "UT search flux capacitor", "It stopped working.");
+    let in_description = insert_acme_ticket(
+        &mut conn,
+        "UT search time machine",
+        "The flux capacitor is leaking.",
+    );
+    let in_comment = insert_acme_ticket(&mut conn, "UT search DeLorean", "Won't start.");
+    let bob = viewer(&mut conn, "bob");
+    let _ = ticket::insert_comment(
+        &mut conn,
+        &model::NewTicketComment {
+            ticket_id: in_comment.id,
+            author_id: bob.user_id,
+            body: "Replaced the flux capacitors of the car.",
+            source: "web",
+        },
+    )
+    .expect("error inserting comment");
+
+    let ids = [in_title.id, in_description.id, in_comment.id];
+    let hits = search(&mut conn, &bob, "flux capacitor")
+        .into_iter()
+        .filter(|hit| ids.contains(&hit.id))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hits.iter().map(|hit| hit.id).collect::<Vec<_>>(),
+        ids,
+        "titles should rank above descriptions and comments"
+    );
+
+    let title = model::fragments(&hits[0].title);
+    assert!(title
+        .iter()
+        .any(|fragment| fragment.highlighted && fragment.text == "flux"));
+    assert!(hits[2].snippet.contains("Replaced the"));
+
+    // Filters
+    let filter = TicketFilter {
+        status: Some(Status::Closed.as_str().to_owned()),
+        ..TicketFilter::default()
+    };
+    assert!(
+        tickets(&mut conn, &bob, "flux capacitor", &filter, 500, 0)
+            .expect("error searching tickets")
+            .iter()
+            .all(|hit| !ids.contains(&hit.id)),
+        "new tickets were found when searching closed ones"
+    );
+    let filter = TicketFilter {
+        organisation_id: in_title.organisation_id,
+        since: Some(in_title.created_on),
+        ..TicketFilter::default()
+    };
+    assert!(tickets(&mut conn, &bob, "flux capacitor", &filter, 500, 0)
+        .expect("error searching tickets")
+        .iter()
+        .any(|hit| hit.id == in_title.id));
+}
+
+/// Rainy day unit test for the ticket search: customers only find the tickets they can see.
+#[test]
+fn ut_rainy_search_tickets() {
+    let mut conn = establish_connection();
+    let ticket = insert_acme_ticket(&mut conn, "UT search gravitational anomaly", "");
+
+    let dave = viewer(&mut conn, "dave");
+    assert!(search(&mut conn, &dave, "gravitational anomaly")
+        .iter()
+        .all(|hit| hit.id != ticket.id));
+    let carol = viewer(&mut conn, "carol");
+    assert!(search(&mut conn, &carol, "gravitational anomaly")
+        .iter()
+        .any(|hit| hit.id == ticket.id));
+
+    // Queries without words match nothing
+    assert!(search(&mut conn, &carol, " the ").is_empty());
+}
+
+/// Unit test for the row-level security policies of the comments, without the filters of the
+/// queries.
+#[test]
+fn ut_rainy_comment_row_level_security() {
+    let mut conn = establish_connection();
+    let ticket = insert_acme_ticket(&mut conn, "UT search row-level security", "");
+    let carol = viewer(&mut conn, "carol");
+    let _ = ticket::insert_comment(
+        &mut conn,
+        &model::NewTicketComment {
+            ticket_id: ticket.id,
+            author_id: carol.user_id,
+            body: "Private",
+            source: "web",
+        },
+    )
+    .expect("error inserting comment");
+
+    let count = |conn: &mut PgConnection, viewer: &Viewer| {
+        tenant::scoped(conn, viewer, |conn| {
+            #[derive(QueryableByName)]
+            struct Count {
+                #[sql_type = "BigInt"]
+                count: i64,
+            }
+
+            sql_query("SELECT COUNT(*) AS count FROM ticket_comment WHERE ticket_id = $1")
+                .bind::<SqlUuid, _>(ticket.id)
+                .get_result::<Count>(conn)
+                .map(|row| row.count)
+        })
+        .expect("error counting comments")
+    };
+
+    let dave = viewer(&mut conn, "dave");
+    assert_eq!(
+        count(&mut conn, &dave),
+        0,
+        "Dave can see comments from Acme"
+    );
+    assert_eq!(count(&mut conn, &carol), 1, "Carol can't see her comment");
+}
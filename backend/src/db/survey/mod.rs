@@ -0,0 +1,153 @@
+use super::{into_option, model, schema::*};
+use crate::{into_io_err, rand_code};
+use chrono::{DateTime, Duration, Utc};
+use common::survey::Rating;
+use diesel::{dsl::count_star, prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Timeout for survey tokens, in seconds, counted from the resolution of the ticket.
+const SURVEY_TOKEN_TIMEOUT: i64 = 30 * 24 * 60 * 60;
+
+/// Length of survey tokens.
+const TOKEN_LEN: usize = 32;
+
+/// Inserts a new survey for a resolved ticket, to be emailed to its requester.
+pub fn insert(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    agent_id: Option<Uuid>,
+) -> io::Result<model::Survey> {
+    diesel::insert_into(survey::table)
+        .values(&model::NewSurvey {
+            ticket_id,
+            agent_id,
+            token: &rand_code(TOKEN_LEN),
+        })
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the surveys of a ticket, oldest first.
+pub fn get_for_ticket(conn: &mut PgConnection, ticket_id: Uuid) -> io::Result<Vec<model::Survey>> {
+    survey::table
+        .filter(survey::ticket_id.eq(ticket_id))
+        .order(survey::created_on)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the survey with a token, if it exists and hasn't expired.
+pub fn get_with_token(conn: &mut PgConnection, token: &str) -> io::Result<Option<model::Survey>> {
+    let limit = Utc::now() - Duration::seconds(SURVEY_TOKEN_TIMEOUT);
+
+    into_option(
+        survey::table
+            .filter(survey::token.eq(token).and(survey::created_on.ge(limit)))
+            .first(conn),
+    )
+}
+
+/// Records the answer to a survey, replacing any previous one, and returns the survey if it
+/// exists.
+pub fn answer(
+    conn: &mut PgConnection,
+    id: Uuid,
+    rating: Rating,
+    comment: &str,
+) -> io::Result<Option<model::Survey>> {
+    into_option(
+        diesel::update(survey::table.find(id))
+            .set((
+                survey::rating.eq(rating.as_str()),
+                survey::comment.eq(comment),
+                survey::answered_on.eq(Utc::now()),
+            ))
+            .get_result(conn),
+    )
+}
+
+/// Claims the surveys pending to be emailed, oldest first.
+///
+/// The claimed surveys are marked as sent at the given time, so that other workers don't send them
+/// again. They must be [released](release) if sending them fails.
+pub fn claim_pending(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> io::Result<Vec<model::Survey>> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let pending = survey::table
+            .filter(survey::sent_on.is_null())
+            .order((survey::created_on, survey::id))
+            .limit(limit)
+            .for_update()
+            .skip_locked()
+            .load::<model::Survey>(conn)?;
+
+        let ids = pending.iter().map(|survey| survey.id).collect::<Vec<_>>();
+        let _ = diesel::update(survey::table.filter(survey::id.eq_any(ids)))
+            .set(survey::sent_on.eq(now))
+            .execute(conn)?;
+
+        Ok(pending
+            .into_iter()
+            .map(|survey| model::Survey {
+                sent_on: Some(now),
+                ..survey
+            })
+            .collect())
+    })
+    .map_err(into_io_err)
+}
+
+/// Releases a claimed survey that could not be emailed, so that it's sent again.
+pub fn release(conn: &mut PgConnection, id: Uuid) -> io::Result<()> {
+    diesel::update(survey::table.find(id))
+        .set(survey::sent_on.eq(None::<DateTime<Utc>>))
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Retrieves the ticket categories whose tickets don't get surveys, ordered by name.
+pub fn get_suppressions(conn: &mut PgConnection) -> io::Result<Vec<String>> {
+    survey_suppression::table
+        .select(survey_suppression::category)
+        .order(survey_suppression::category)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Checks whether the tickets of a category don't get surveys.
+pub fn is_suppressed(conn: &mut PgConnection, category: &str) -> io::Result<bool> {
+    survey_suppression::table
+        .select(count_star())
+        .filter(survey_suppression::category.eq(category))
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Stops sending surveys for the tickets of a category, returning whether they were sent until
+/// now.
+pub fn insert_suppression(conn: &mut PgConnection, category: &str) -> io::Result<bool> {
+    diesel::insert_into(survey_suppression::table)
+        .values(survey_suppression::category.eq(category))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Sends surveys again for the tickets of a category, returning whether they were suppressed.
+pub fn delete_suppression(conn: &mut PgConnection, category: &str) -> io::Result<bool> {
+    diesel::delete(survey_suppression::table.find(category))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
@@ -0,0 +1,65 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+};
+use diesel::Connection;
+
+/// Sunny day unit test for answering surveys: answers replace the previous ones.
+#[test]
+fn ut_sunny_answer() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let carol = user_id(&mut conn, "carol");
+    let ticket_id = insert_ticket(&mut conn, &new_ticket(carol, "UT slow network")).id;
+    let survey = insert(&mut conn, ticket_id, None).expect("error inserting survey");
+    assert_eq!(survey.token.len(), TOKEN_LEN);
+
+    let found = get_with_token(&mut conn, &survey.token)
+        .expect("error retrieving survey")
+        .expect("survey was not found");
+    assert_eq!(found.rating(), None);
+    let _ = answer(&mut conn, survey.id, Rating::Bad, "").expect("error answering survey");
+    let answered = answer(&mut conn, survey.id, Rating::Good, "Fixed in no time")
+        .expect("error answering survey")
+        .expect("survey was not found");
+    assert_eq!(answered.rating(), Some(Rating::Good));
+    assert_eq!(answered.comment, "Fixed in no time");
+    assert!(answered.answered_on.is_some());
+
+    assert!(insert_suppression(&mut conn, "ut-internal").expect("error suppressing surveys"));
+    assert!(!insert_suppression(&mut conn, "ut-internal").expect("error suppressing surveys"));
+    assert!(is_suppressed(&mut conn, "ut-internal").expect("error checking suppression"));
+    assert!(get_suppressions(&mut conn)
+        .expect("error retrieving suppressions")
+        .contains(&"ut-internal".to_owned()));
+    assert!(delete_suppression(&mut conn, "ut-internal").expect("error deleting suppression"));
+    assert!(!is_suppressed(&mut conn, "ut-internal").expect("error checking suppression"));
+}
+
+/// Rainy day unit test for answering surveys: unknown and expired tokens are not found.
+#[test]
+fn ut_rainy_answer() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let carol = user_id(&mut conn, "carol");
+    let ticket_id = insert_ticket(&mut conn, &new_ticket(carol, "UT slow network, again")).id;
+    let survey = insert(&mut conn, ticket_id, None).expect("error inserting survey");
+
+    assert!(get_with_token(&mut conn, "not-a-token")
+        .expect("error retrieving survey")
+        .is_none());
+    let _ = diesel::update(survey::table.find(survey.id))
+        .set(survey::created_on.eq(Utc::now() - Duration::seconds(SURVEY_TOKEN_TIMEOUT + 1)))
+        .execute(&conn)
+        .expect("error backdating survey");
+    assert!(get_with_token(&mut conn, &survey.token)
+        .expect("error retrieving survey")
+        .is_none());
+    assert!(answer(&mut conn, Uuid::new_v4(), Rating::Good, "")
+        .expect("error answering survey")
+        .is_none());
+    assert!(!delete_suppression(&mut conn, "ut-unknown").expect("error deleting suppression"));
+}
@@ -0,0 +1,112 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{pg::upsert::excluded, prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Team along with its members and their usernames.
+pub type TeamWithMembers = (model::Team, Vec<(model::TeamMember, String)>);
+
+type TeamColumns = (team::id, team::name);
+
+/// Columns of the teams, except their timestamps.
+const TEAM_COLUMNS: TeamColumns = (team::id, team::name);
+
+type MemberColumns = (
+    team_member::team_id,
+    team_member::user_id,
+    team_member::skills,
+);
+
+/// Columns of the team memberships, except their timestamp.
+const MEMBER_COLUMNS: MemberColumns = (
+    team_member::team_id,
+    team_member::user_id,
+    team_member::skills,
+);
+
+/// Retrieves all teams ordered by name, along with their members and usernames.
+pub fn get_all(conn: &mut PgConnection) -> io::Result<Vec<TeamWithMembers>> {
+    let teams = team::table
+        .select(TEAM_COLUMNS)
+        .order(team::name)
+        .load::<model::Team>(conn)
+        .map_err(into_io_err)?;
+    let members = team_member::table
+        .inner_join(sys_user::table)
+        .order(sys_user::username)
+        .select((MEMBER_COLUMNS, sys_user::username))
+        .load::<(model::TeamMember, String)>(conn)
+        .map_err(into_io_err)?;
+
+    Ok(teams
+        .into_iter()
+        .map(|team| {
+            let team_members = members
+                .iter()
+                .filter(|(member, _)| member.team_id == team.id)
+                .cloned()
+                .collect();
+            (team, team_members)
+        })
+        .collect())
+}
+
+/// Retrieves a team with an ID, if it exists.
+pub fn get_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::Team>> {
+    into_option(team::table.find(id).select(TEAM_COLUMNS).first(conn))
+}
+
+/// Inserts a new team.
+pub fn insert(conn: &mut PgConnection, name: &str) -> io::Result<model::Team> {
+    diesel::insert_into(team::table)
+        .values(&model::NewTeam { name })
+        .returning(TEAM_COLUMNS)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Renames a team, returning it if it exists.
+pub fn rename(conn: &mut PgConnection, id: Uuid, name: &str) -> io::Result<Option<model::Team>> {
+    let team = diesel::update(team::table.find(id))
+        .set((team::name.eq(name), team::updated_on.eq(Utc::now())))
+        .returning(TEAM_COLUMNS)
+        .get_result(conn);
+
+    into_option(team)
+}
+
+/// Adds an agent to a team, or updates their skills if they are already a member.
+pub fn upsert_member(
+    conn: &mut PgConnection,
+    team_id: Uuid,
+    user_id: Uuid,
+    skills: &[String],
+) -> io::Result<()> {
+    diesel::insert_into(team_member::table)
+        .values(&model::NewTeamMember {
+            team_id,
+            user_id,
+            skills,
+        })
+        .on_conflict((team_member::team_id, team_member::user_id))
+        .do_update()
+        .set(team_member::skills.eq(excluded(team_member::skills)))
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Removes an agent from a team.
+///
+/// Returns `false` if the agent was not a member.
+pub fn delete_member(conn: &mut PgConnection, team_id: Uuid, user_id: Uuid) -> io::Result<bool> {
+    diesel::delete(team_member::table.find((team_id, user_id)))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
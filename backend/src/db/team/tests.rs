@@ -0,0 +1,59 @@
+use super::*;
+use crate::db::{establish_connection, user};
+use diesel::Connection;
+
+/// Sunny day unit test for the `insert()` and `rename()` functions.
+#[test]
+fn ut_sunny_insert_rename() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let team = insert(&mut conn, "UT Network").expect("error inserting team");
+    let renamed = rename(&mut conn, team.id, "UT Networking")
+        .expect("error renaming team")
+        .expect("team disappeared");
+    assert_eq!(renamed.name, "UT Networking");
+}
+
+/// Rainy day unit test for the `rename()` function.
+#[test]
+fn ut_rainy_rename() {
+    let mut conn = establish_connection();
+
+    let team = rename(&mut conn, Uuid::new_v4(), "UT Nobody").expect("error renaming team");
+    assert!(team.is_none(), "a nonexistent team was renamed");
+}
+
+/// Sunny day unit test for the membership functions.
+#[test]
+fn ut_sunny_members() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let team = insert(&mut conn, "UT Hardware").expect("error inserting team");
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("Bob was not in the database");
+
+    upsert_member(&mut conn, team.id, bob.id, &["printers".to_owned()])
+        .expect("error adding member");
+    upsert_member(&mut conn, team.id, bob.id, &["laptops".to_owned()])
+        .expect("error updating member");
+
+    let teams = get_all(&mut conn).expect("error retrieving teams");
+    let (_, members) = teams
+        .iter()
+        .find(|(t, _)| t.id == team.id)
+        .expect("team was not listed");
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].1, "bob");
+    assert_eq!(members[0].0.skills, vec!["laptops".to_owned()]);
+
+    assert!(delete_member(&mut conn, team.id, bob.id).expect("error removing member"));
+    assert!(
+        !delete_member(&mut conn, team.id, bob.id).expect("error removing member"),
+        "a missing member was removed"
+    );
+}
@@ -0,0 +1,107 @@
+//! Tenant isolation at the database layer.
+//!
+//! When the `ROW_LEVEL_SECURITY` environment variable is set to `true`, queries on tenant data run
+//! inside a transaction using the `my_support_tenant` database role, which is subject to the
+//! row-level security policies of the database. Otherwise, they run directly, and isolation relies
+//! only on the filters of the queries themselves.
+
+use crate::into_io_err;
+use common::user::Role;
+use diesel::{
+    connection::Connection, sql_query, sql_types::Text, PgConnection, QueryResult, RunQueryDsl,
+};
+use once_cell::sync::Lazy;
+use std::{env, io};
+use uuid::Uuid;
+
+/// Whether tenant isolation is enforced at the database layer.
+static ROW_LEVEL_SECURITY: Lazy<bool> = Lazy::new(|| {
+    env::var("ROW_LEVEL_SECURITY")
+        .map(|rls| rls.trim() == "true")
+        .unwrap_or(false)
+});
+
+/// User accessing tenant data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewer {
+    /// The ID of the user.
+    pub user_id: Uuid,
+    /// The role of the user.
+    pub role: Role,
+    /// The organisations the user is a member of.
+    pub organisation_ids: Vec<Uuid>,
+}
+
+impl Viewer {
+    /// Viewer for actions performed by the application itself, such as automations.
+    ///
+    /// It can see all tenants.
+    pub fn system() -> Self {
+        Self {
+            user_id: Uuid::nil(),
+            role: Role::Admin,
+            organisation_ids: Vec::new(),
+        }
+    }
+
+    /// Checks if the viewer can see all tenants.
+    pub fn sees_all(&self) -> bool {
+        self.role.is_staff()
+    }
+
+    /// Gets the organisation of the tickets opened by the viewer without choosing one.
+    ///
+    /// Customers in a single organisation open tickets for it.
+    pub fn default_organisation_id(&self) -> Option<Uuid> {
+        match self.organisation_ids[..] {
+            [organisation_id] if !self.sees_all() => Some(organisation_id),
+            _ => None,
+        }
+    }
+}
+
+/// Runs the given function with tenant isolation, if enabled.
+pub fn run<T, F>(conn: &mut PgConnection, viewer: &Viewer, f: F) -> io::Result<T>
+where
+    F: FnOnce(&PgConnection) -> QueryResult<T>,
+{
+    if *ROW_LEVEL_SECURITY {
+        scoped(conn, viewer, f)
+    } else {
+        f(conn).map_err(into_io_err)
+    }
+}
+
+/// Runs the given function in a transaction subject to row-level security policies.
+pub fn scoped<T, F>(conn: &mut PgConnection, viewer: &Viewer, f: F) -> io::Result<T>
+where
+    F: FnOnce(&PgConnection) -> QueryResult<T>,
+{
+    let conn: &PgConnection = conn;
+    let organisation_ids = viewer
+        .organisation_ids
+        .iter()
+        .map(Uuid::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let _ = sql_query("SET LOCAL ROLE my_support_tenant").execute(conn)?;
+        let _ = sql_query(
+            "SELECT set_config('my_support.user_id', $1, true), \
+                set_config('my_support.user_role', $2, true), \
+                set_config('my_support.organisation_ids', $3, true)",
+        )
+        .bind::<Text, _>(viewer.user_id.to_string())
+        .bind::<Text, _>(viewer.role.as_str())
+        .bind::<Text, _>(organisation_ids)
+        .execute(conn)?;
+
+        let result = f(conn)?;
+        // Local settings outlive savepoints, so later queries of an enclosing transaction would
+        // run with the role otherwise
+        let _ = sql_query("SET LOCAL ROLE NONE").execute(conn)?;
+        Ok(result)
+    })
+    .map_err(into_io_err)
+}
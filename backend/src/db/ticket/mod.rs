@@ -0,0 +1,240 @@
+use super::{
+    model,
+    schema::*,
+    tenant::{self, Viewer},
+};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{
+    dsl::sql, expression::BoxableExpression, pg::Pg, prelude::*, sql_types::Bool, PgConnection,
+};
+use std::io;
+use uuid::Uuid;
+
+pub mod query;
+#[cfg(test)]
+mod tests;
+
+/// Retrieves a page of the tickets visible to the viewer, newest first.
+pub fn get_visible(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::Ticket>> {
+    tenant::run(conn, viewer, |conn| {
+        ticket::table
+            .filter(visible_to(viewer))
+            .order(ticket::number.desc())
+            .limit(limit)
+            .offset(offset)
+            .load(conn)
+    })
+}
+
+/// Retrieves a ticket with an ID, if it exists and is visible to the viewer.
+pub fn get_with_id(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    id: Uuid,
+) -> io::Result<Option<model::Ticket>> {
+    tenant::run(conn, viewer, |conn| {
+        ticket::table
+            .filter(ticket::id.eq(id))
+            .filter(visible_to(viewer))
+            .first(conn)
+            .optional()
+    })
+}
+
+/// Retrieves a ticket with a number, if it exists and is visible to the viewer.
+pub fn get_with_number(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    number: i64,
+) -> io::Result<Option<model::Ticket>> {
+    tenant::run(conn, viewer, |conn| {
+        ticket::table
+            .filter(ticket::number.eq(number))
+            .filter(visible_to(viewer))
+            .first(conn)
+            .optional()
+    })
+}
+
+/// Inserts a new ticket.
+pub fn insert(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    new_ticket: &model::NewTicket<'_>,
+) -> io::Result<model::Ticket> {
+    tenant::run(conn, viewer, |conn| {
+        diesel::insert_into(ticket::table)
+            .values(new_ticket)
+            .get_result(conn)
+    })
+}
+
+/// Updates a ticket, returning it if it exists and is visible to the viewer.
+pub fn update(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    id: Uuid,
+    changes: &model::TicketChanges<'_>,
+) -> io::Result<Option<model::Ticket>> {
+    tenant::run(conn, viewer, |conn| {
+        let target = ticket::table.filter(ticket::id.eq(id));
+        if !viewer.sees_all() {
+            let visible = target.filter(visible_to(viewer));
+            if visible.count().get_result::<i64>(conn)? == 0 {
+                return Ok(None);
+            }
+        }
+
+        diesel::update(target)
+            .set((changes, ticket::updated_on.eq(Utc::now())))
+            .get_result(conn)
+            .optional()
+    })
+}
+
+/// Retrieves the comments of a ticket, oldest first.
+///
+/// The caller must check that the ticket is visible to the user.
+pub fn get_comments(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+) -> io::Result<Vec<model::TicketComment>> {
+    ticket_comment::table
+        .filter(ticket_comment::ticket_id.eq(ticket_id))
+        .order(ticket_comment::created_on)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new comment in a ticket.
+pub fn insert_comment(
+    conn: &mut PgConnection,
+    comment: &model::NewTicketComment<'_>,
+) -> io::Result<model::TicketComment> {
+    diesel::insert_into(ticket_comment::table)
+        .values(comment)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Columns of the attachments, except their contents.
+type AttachmentInfoColumns = (
+    ticket_attachment::id,
+    ticket_attachment::ticket_id,
+    ticket_attachment::comment_id,
+    ticket_attachment::filename,
+    ticket_attachment::content_type,
+    ticket_attachment::size,
+);
+
+/// Columns of the attachments, except their contents.
+const ATTACHMENT_INFO_COLUMNS: AttachmentInfoColumns = (
+    ticket_attachment::id,
+    ticket_attachment::ticket_id,
+    ticket_attachment::comment_id,
+    ticket_attachment::filename,
+    ticket_attachment::content_type,
+    ticket_attachment::size,
+);
+
+/// Retrieves the attachments of a ticket, without their contents.
+///
+/// The caller must check that the ticket is visible to the user.
+pub fn get_attachments(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+) -> io::Result<Vec<model::TicketAttachmentInfo>> {
+    ticket_attachment::table
+        .select(ATTACHMENT_INFO_COLUMNS)
+        .filter(ticket_attachment::ticket_id.eq(ticket_id))
+        .order(ticket_attachment::created_on)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves an attachment of a ticket along with its contents, if it exists.
+///
+/// The caller must check that the ticket is visible to the user.
+pub fn get_attachment_with_id(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    id: Uuid,
+) -> io::Result<Option<(model::TicketAttachmentInfo, Vec<u8>)>> {
+    ticket_attachment::table
+        .select((ATTACHMENT_INFO_COLUMNS, ticket_attachment::data))
+        .filter(ticket_attachment::ticket_id.eq(ticket_id))
+        .filter(ticket_attachment::id.eq(id))
+        .first(conn)
+        .optional()
+        .map_err(into_io_err)
+}
+
+/// Inserts new attachments in tickets.
+pub fn insert_attachments(
+    conn: &mut PgConnection,
+    attachments: &[model::NewTicketAttachment<'_>],
+) -> io::Result<()> {
+    diesel::insert_into(ticket_attachment::table)
+        .values(attachments)
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Retrieves the IDs of the users watching a ticket.
+pub fn get_watcher_ids(conn: &mut PgConnection, ticket_id: Uuid) -> io::Result<Vec<Uuid>> {
+    ticket_watcher::table
+        .select(ticket_watcher::user_id)
+        .filter(ticket_watcher::ticket_id.eq(ticket_id))
+        .order(ticket_watcher::created_on)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Makes a user watch a ticket, returning `false` if they were already watching it.
+///
+/// The caller must check that the ticket is visible to the user.
+pub fn insert_watcher(conn: &mut PgConnection, ticket_id: Uuid, user_id: Uuid) -> io::Result<bool> {
+    diesel::insert_into(ticket_watcher::table)
+        .values((
+            ticket_watcher::ticket_id.eq(ticket_id),
+            ticket_watcher::user_id.eq(user_id),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Stops a user watching a ticket, returning `false` if they were not watching it.
+pub fn delete_watcher(conn: &mut PgConnection, ticket_id: Uuid, user_id: Uuid) -> io::Result<bool> {
+    diesel::delete(
+        ticket_watcher::table
+            .filter(ticket_watcher::ticket_id.eq(ticket_id))
+            .filter(ticket_watcher::user_id.eq(user_id)),
+    )
+    .execute(conn)
+    .map(|count| count > 0)
+    .map_err(into_io_err)
+}
+
+/// Condition on the tickets visible to the viewer.
+fn visible_to(
+    viewer: &Viewer,
+) -> Box<dyn BoxableExpression<ticket::table, Pg, SqlType = Bool> + '_> {
+    if viewer.sees_all() {
+        Box::new(sql::<Bool>("TRUE"))
+    } else {
+        Box::new(
+            ticket::requester_id
+                .eq(viewer.user_id)
+                .or(ticket::organisation_id.eq_any(&viewer.organisation_ids)),
+        )
+    }
+}
@@ -0,0 +1,277 @@
+//! Translation of the [query language](common::query) to database queries.
+
+use super::super::{
+    custom_field, escape_like, model,
+    schema::*,
+    tenant::{self, Viewer},
+};
+use crate::into_io_err;
+use chrono::{DateTime, Utc};
+use common::{
+    custom_field::{CustomFieldDTO, Kind},
+    query::{Filter, Moment, Operator, Query, Term, UserRef, ME},
+    ticket::Priority,
+};
+use diesel::{
+    dsl::{not, sql},
+    expression::BoxableExpression,
+    pg::Pg,
+    prelude::*,
+    sql_types::{Bool, Jsonb, Text, Timestamptz},
+    PgConnection,
+};
+use serde_json::json;
+use std::{convert::TryFrom, io};
+use uuid::Uuid;
+
+/// Condition on the tickets.
+type Predicate = Box<dyn BoxableExpression<ticket::table, Pg, SqlType = Bool>>;
+
+sql_function! {
+    /// Converts a text to lowercase.
+    fn lower(text: Text) -> Text;
+}
+
+/// Retrieves the tickets visible to the viewer that match a query, newest first.
+///
+/// Relative times in the query are counted back from `now`.
+pub fn get_matching(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    query: &Query,
+    now: DateTime<Utc>,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::Ticket>> {
+    let fields = if query
+        .terms
+        .iter()
+        .any(|term| matches!(term.filter, Filter::Custom { .. }))
+    {
+        custom_field::get_all(conn)?
+            .into_iter()
+            .map(CustomFieldDTO::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(into_io_err)?
+    } else {
+        Vec::new()
+    };
+
+    tenant::run(conn, viewer, |conn| {
+        let mut tickets = ticket::table.filter(super::visible_to(viewer)).into_boxed();
+        for term in &query.terms {
+            tickets = tickets.filter(predicate(term, &fields, viewer.user_id, now));
+        }
+
+        tickets
+            .order(ticket::number.desc())
+            .limit(limit)
+            .offset(offset)
+            .load(conn)
+    })
+}
+
+/// Translates a term of a query.
+fn predicate(
+    term: &Term,
+    fields: &[CustomFieldDTO],
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Predicate {
+    let predicate = filter(&term.filter, fields, user_id, now);
+
+    if term.negated {
+        Box::new(not(predicate))
+    } else {
+        predicate
+    }
+}
+
+/// Translates a filter of a query.
+///
+/// Filters on optional columns never evaluate to `NULL`, so that they can be negated.
+fn filter(
+    filter: &Filter,
+    fields: &[CustomFieldDTO],
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Predicate {
+    match filter {
+        Filter::Status(statuses) => Box::new(
+            ticket::status.eq_any(
+                statuses
+                    .iter()
+                    .map(|status| status.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+        Filter::Priority(operator, target) => Box::new(
+            ticket::priority.eq_any(
+                Priority::ALL
+                    .iter()
+                    .filter(|priority| operator.matches(**priority, *target))
+                    .map(|priority| priority.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+        Filter::Assignee(UserRef::Me) => Box::new(
+            ticket::assignee_id
+                .is_not_null()
+                .and(ticket::assignee_id.eq(user_id)),
+        ),
+        Filter::Assignee(UserRef::Nobody) => Box::new(ticket::assignee_id.is_null()),
+        Filter::Assignee(UserRef::Username(username)) => Box::new(
+            ticket::assignee_id.is_not_null().and(
+                ticket::assignee_id.eq_any(
+                    sys_user::table
+                        .select(sys_user::id.nullable())
+                        .filter(sys_user::username.eq(username.clone())),
+                ),
+            ),
+        ),
+        Filter::Requester(UserRef::Me) => Box::new(ticket::requester_id.eq(user_id)),
+        // Tickets always have a requester
+        Filter::Requester(UserRef::Nobody) => Box::new(ticket::requester_id.is_null()),
+        Filter::Requester(UserRef::Username(username)) => Box::new(
+            ticket::requester_id.eq_any(
+                sys_user::table
+                    .select(sys_user::id)
+                    .filter(sys_user::username.eq(username.clone())),
+            ),
+        ),
+        Filter::Organisation(None) => Box::new(ticket::organisation_id.is_null()),
+        Filter::Organisation(Some(name)) => Box::new(
+            ticket::organisation_id.is_not_null().and(
+                ticket::organisation_id.eq_any(
+                    organisation::table
+                        .select(organisation::id.nullable())
+                        .filter(lower(organisation::name).eq(name.to_lowercase())),
+                ),
+            ),
+        ),
+        Filter::Queue(None) => Box::new(ticket::queue_id.is_null()),
+        Filter::Queue(Some(name)) => Box::new(
+            ticket::queue_id.is_not_null().and(
+                ticket::queue_id.eq_any(
+                    queue::table
+                        .select(queue::id.nullable())
+                        .filter(lower(queue::name).eq(name.to_lowercase())),
+                ),
+            ),
+        ),
+        Filter::Category(None) => Box::new(ticket::category.is_null()),
+        Filter::Category(Some(category)) => Box::new(
+            ticket::category
+                .is_not_null()
+                .and(ticket::category.eq(category.clone())),
+        ),
+        Filter::Tag(tag) => Box::new(ticket::tags.contains(vec![tag.clone()])),
+        Filter::Created(operator, moment) => compare(ticket::created_on, *operator, *moment, now),
+        Filter::Updated(operator, moment) => compare(ticket::updated_on, *operator, *moment, now),
+        Filter::Custom {
+            key,
+            operator,
+            value,
+        } => custom(fields, key, *operator, value.as_deref(), user_id),
+        Filter::Text(text) => {
+            let pattern = format!("%{}%", escape_like(text));
+            Box::new(
+                ticket::title
+                    .ilike(pattern.clone())
+                    .or(ticket::description.ilike(pattern)),
+            )
+        }
+    }
+}
+
+/// Translates a filter on a custom field.
+///
+/// Filters that don't fit the definition of the field match no tickets, as fields can change after
+/// a view is saved. Users are given by username, or `me`.
+fn custom(
+    fields: &[CustomFieldDTO],
+    key: &str,
+    operator: Operator,
+    value: Option<&str>,
+    user_id: Uuid,
+) -> Predicate {
+    let field = match fields.iter().find(|field| field.key == key) {
+        Some(field) if operator == Operator::Is || field.kind.is_ordered() => field,
+        _ => return Box::new(sql::<Bool>("FALSE")),
+    };
+    let value = match value {
+        Some(value) => value,
+        None => {
+            return Box::new(
+                sql::<Bool>("NOT (ticket.custom_fields ? ")
+                    .bind::<Text, _>(key.to_owned())
+                    .sql(")"),
+            )
+        }
+    };
+
+    if field.kind == Kind::User && value != ME {
+        return Box::new(
+            sql::<Bool>("EXISTS (SELECT 1 FROM sys_user WHERE sys_user.username = ")
+                .bind::<Text, _>(value.to_owned())
+                .sql(" AND ticket.custom_fields @> jsonb_build_object(")
+                .bind::<Text, _>(key.to_owned())
+                .sql(", sys_user.id::text))"),
+        );
+    }
+    let value = match field.kind {
+        Kind::User => json!(user_id.to_string()),
+        _ => match field.kind.parse(value) {
+            Some(value) => json!(value),
+            None => return Box::new(sql::<Bool>("FALSE")),
+        },
+    };
+
+    if operator == Operator::Is {
+        return Box::new(
+            sql::<Bool>("ticket.custom_fields @> ").bind::<Jsonb, _>(json!({ key: value })),
+        );
+    }
+    // Values of another type, kept from an earlier definition of the field, never match
+    let comparison = format!(" {} ", operator.as_str());
+    Box::new(
+        sql::<Bool>("COALESCE(jsonb_typeof(ticket.custom_fields -> ")
+            .bind::<Text, _>(key.to_owned())
+            .sql(") = jsonb_typeof(")
+            .bind::<Jsonb, _>(value.clone())
+            .sql(") AND ticket.custom_fields -> ")
+            .bind::<Text, _>(key.to_owned())
+            .sql(&comparison)
+            .bind::<Jsonb, _>(value)
+            .sql(", FALSE)"),
+    )
+}
+
+/// Compares a timestamp column with a moment.
+///
+/// Dates cover whole days: `created:2022-05-01` matches the whole day, and `created>2022-05-01`
+/// starts the next day. Relative times are instants. Moments out of the range of dates match no
+/// tickets.
+fn compare<C>(column: C, operator: Operator, moment: Moment, now: DateTime<Utc>) -> Predicate
+where
+    C: Column<Table = ticket::table, SqlType = Timestamptz>
+        + ExpressionMethods
+        + BoxableExpression<ticket::table, Pg, SqlType = Timestamptz>
+        + Copy
+        + 'static,
+{
+    let (start, end) = match moment.bounds(now) {
+        Some(bounds) => bounds,
+        None => return Box::new(sql::<Bool>("FALSE")),
+    };
+
+    match (operator, moment) {
+        (Operator::Is, Moment::Date(_)) => Box::new(column.ge(start).and(column.lt(end))),
+        (Operator::Is, Moment::Ago(_)) | (Operator::Ge, _) => Box::new(column.ge(start)),
+        (Operator::Lt, _) => Box::new(column.lt(start)),
+        (Operator::Le, Moment::Date(_)) => Box::new(column.lt(end)),
+        (Operator::Le, Moment::Ago(_)) => Box::new(column.le(end)),
+        (Operator::Gt, Moment::Date(_)) => Box::new(column.ge(end)),
+        (Operator::Gt, Moment::Ago(_)) => Box::new(column.gt(end)),
+    }
+}
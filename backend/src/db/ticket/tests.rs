@@ -0,0 +1,321 @@
+use super::*;
+use crate::db::{custom_field, establish_connection, fixtures::viewer};
+use common::user::Role;
+use diesel::{sql_query, sql_types::Uuid as SqlUuid, Connection};
+use serde_json::json;
+
+/// Inserts a ticket for Carol's organisation.
+fn insert_acme_ticket(conn: &mut PgConnection, title: &str) -> model::Ticket {
+    let carol = viewer(conn, "carol");
+    insert(
+        conn,
+        &carol,
+        &model::NewTicket {
+            title,
+            description: "The anvil fell on the wrong bird.",
+            priority: "high",
+            requester_id: carol.user_id,
+            organisation_id: carol.organisation_ids.first().copied(),
+            queue_id: None,
+            category: None,
+            custom_fields: None,
+        },
+    )
+    .expect("error inserting ticket")
+}
+
+/// Sunny day unit test for the `get_visible()` and `get_with_id()` functions.
+#[test]
+fn ut_sunny_get_visible() {
+    let mut conn = establish_connection();
+    let ticket = insert_acme_ticket(&mut conn, "UT visible ticket");
+
+    for username in ["carol", "bob", "alice"] {
+        let viewer = viewer(&mut conn, username);
+        let tickets = get_visible(&mut conn, &viewer, 1, 0).expect("error retrieving tickets");
+        assert!(
+            tickets.iter().any(|t| t.id == ticket.id),
+            "{} can't see the ticket",
+            username
+        );
+        let tickets = get_visible(&mut conn, &viewer, 1, 1).expect("error retrieving tickets");
+        assert!(tickets.iter().all(|t| t.id != ticket.id));
+        assert!(get_with_id(&mut conn, &viewer, ticket.id)
+            .expect("error retrieving ticket")
+            .is_some());
+    }
+}
+
+/// Rainy day unit test for the `get_visible()` and `get_with_id()` functions.
+#[test]
+fn ut_rainy_get_visible() {
+    let mut conn = establish_connection();
+    let ticket = insert_acme_ticket(&mut conn, "UT hidden ticket");
+
+    let dave = viewer(&mut conn, "dave");
+    let tickets = get_visible(&mut conn, &dave, 500, 0).expect("error retrieving tickets");
+    assert!(
+        tickets
+            .iter()
+            .all(|t| t.organisation_id != Some(ticket.organisation_id.unwrap())),
+        "Dave can see tickets from Acme"
+    );
+    assert!(get_with_id(&mut conn, &dave, ticket.id)
+        .expect("error retrieving ticket")
+        .is_none());
+}
+
+/// Sunny day unit test for the `update()` function.
+#[test]
+fn ut_sunny_update() {
+    let mut conn = establish_connection();
+    let ticket = insert_acme_ticket(&mut conn, "UT update ticket");
+    let bob = viewer(&mut conn, "bob");
+
+    let updated = update(
+        &mut conn,
+        &bob,
+        ticket.id,
+        &model::TicketChanges {
+            status: Some("open"),
+            assignee_id: Some(Some(bob.user_id)),
+            ..model::TicketChanges::default()
+        },
+    )
+    .expect("error updating ticket")
+    .expect("ticket disappeared");
+    assert_eq!(updated.status(), common::ticket::Status::Open);
+    assert_eq!(updated.assignee_id, Some(bob.user_id));
+    assert_eq!(updated.title, ticket.title, "title was changed");
+}
+
+/// Rainy day unit test for the `update()` function, from a different organisation.
+#[test]
+fn ut_rainy_update() {
+    let mut conn = establish_connection();
+    let ticket = insert_acme_ticket(&mut conn, "UT foreign update ticket");
+    let dave = viewer(&mut conn, "dave");
+
+    let updated = update(
+        &mut conn,
+        &dave,
+        ticket.id,
+        &model::TicketChanges {
+            title: Some("Hijacked"),
+            ..model::TicketChanges::default()
+        },
+    )
+    .expect("error updating ticket");
+    assert!(updated.is_none(), "Dave updated a ticket from Acme");
+}
+
+/// Unit test for the row-level security policies, without the filters of the queries.
+#[test]
+fn ut_rainy_row_level_security() {
+    let mut conn = establish_connection();
+    let ticket = insert_acme_ticket(&mut conn, "UT row-level security ticket");
+
+    let count = |conn: &mut PgConnection, viewer: &Viewer| {
+        tenant::scoped(conn, viewer, |conn| {
+            #[derive(QueryableByName)]
+            struct Count {
+                #[sql_type = "diesel::sql_types::BigInt"]
+                count: i64,
+            }
+
+            sql_query("SELECT COUNT(*) AS count FROM ticket WHERE id = $1")
+                .bind::<SqlUuid, _>(ticket.id)
+                .get_result::<Count>(conn)
+                .map(|row| row.count)
+        })
+        .expect("error counting tickets")
+    };
+
+    let dave = viewer(&mut conn, "dave");
+    assert_eq!(count(&mut conn, &dave), 0, "Dave can see tickets from Acme");
+    let carol = viewer(&mut conn, "carol");
+    assert_eq!(count(&mut conn, &carol), 1, "Carol can't see her ticket");
+    let bob = viewer(&mut conn, "bob");
+    assert_eq!(bob.role, Role::Agent);
+    assert_eq!(count(&mut conn, &bob), 1, "Bob can't see the ticket");
+}
+
+/// Checks if a ticket matches a query, for a viewer.
+fn matches(conn: &mut PgConnection, viewer: &Viewer, ticket: &model::Ticket, query: &str) -> bool {
+    let query = query.parse().expect("error parsing query");
+    query::get_matching(conn, viewer, &query, Utc::now(), 500, 0)
+        .expect("error retrieving matching tickets")
+        .iter()
+        .any(|t| t.id == ticket.id)
+}
+
+/// Sunny day unit test for the translation of queries.
+#[test]
+fn ut_sunny_get_matching() {
+    let mut conn = establish_connection();
+    let token = Uuid::new_v4().to_simple().to_string();
+    let ticket = insert_acme_ticket(&mut conn, &format!("UT query {}", token));
+    let bob = viewer(&mut conn, "bob");
+
+    for query in [
+        format!("{} priority>=high organisation:acme assignee:none", token),
+        format!(
+            "{} status:new,open created>-1h updated:{}",
+            token,
+            Utc::now().format("%Y-%m-%d")
+        ),
+        format!(
+            "{} requester:carol category:none -tag:vpn -queue:\"Level 2\"",
+            token
+        ),
+        format!("\"UT QUERY {}\" -priority:low anvil", token),
+    ] {
+        assert!(
+            matches(&mut conn, &bob, &ticket, &query),
+            "{} didn't match",
+            query
+        );
+    }
+    let carol = viewer(&mut conn, "carol");
+    assert!(matches(
+        &mut conn,
+        &carol,
+        &ticket,
+        &format!("{} requester:me", token)
+    ));
+}
+
+/// Rainy day unit test for the translation of queries.
+#[test]
+fn ut_rainy_get_matching() {
+    let mut conn = establish_connection();
+    let token = Uuid::new_v4().to_simple().to_string();
+    let ticket = insert_acme_ticket(&mut conn, &format!("UT query {}", token));
+    let bob = viewer(&mut conn, "bob");
+
+    for query in [
+        format!("{} priority:urgent", token),
+        format!("{} assignee:me", token),
+        format!("{} -assignee:none", token),
+        format!("{} created<-1h", token),
+        format!("{} -organisation:ACME", token),
+        format!("{} requester:dave", token),
+        format!("{} tag:vpn", token),
+        format!("{} status:closed", token),
+        format!("{}%", token),
+    ] {
+        assert!(
+            !matches(&mut conn, &bob, &ticket, &query),
+            "{} matched",
+            query
+        );
+    }
+    let dave = viewer(&mut conn, "dave");
+    assert!(!matches(&mut conn, &dave, &ticket, &token));
+}
+
+/// Inserts custom fields and a ticket with values for them, in a test transaction.
+fn insert_custom_ticket(conn: &mut PgConnection, token: &str) -> model::Ticket {
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    for (key, kind) in [
+        ("ut_cores", json!({"type": "number"})),
+        ("ut_bought", json!({"type": "date"})),
+        ("ut_owner", json!({"type": "user"})),
+        (
+            "ut_region",
+            json!({"type": "enum", "options": ["emea", "apac"]}),
+        ),
+    ] {
+        let _ = custom_field::insert(
+            conn,
+            &model::CustomFieldForm {
+                key,
+                label: key,
+                kind,
+                required: false,
+                categories: &[],
+                position: 0,
+            },
+        )
+        .expect("error inserting custom field");
+    }
+
+    let (carol, bob) = (viewer(conn, "carol"), viewer(conn, "bob"));
+    insert(
+        conn,
+        &carol,
+        &model::NewTicket {
+            title: &format!("UT query {}", token),
+            description: "",
+            priority: "normal",
+            requester_id: carol.user_id,
+            organisation_id: carol.organisation_ids.first().copied(),
+            queue_id: None,
+            category: None,
+            custom_fields: Some(json!({
+                "ut_cores": 8,
+                "ut_bought": "2022-03-15",
+                "ut_owner": bob.user_id.to_string(),
+                "ut_region": "emea",
+            })),
+        },
+    )
+    .expect("error inserting ticket")
+}
+
+/// Sunny day unit test for the translation of queries on custom fields.
+#[test]
+fn ut_sunny_get_matching_custom() {
+    let mut conn = establish_connection();
+    let token = Uuid::new_v4().to_simple().to_string();
+    let ticket = insert_custom_ticket(&mut conn, &token);
+    let bob = viewer(&mut conn, "bob");
+
+    for query in [
+        format!("{} cf.ut_cores:8 cf.ut_cores>4 cf.ut_cores<=8", token),
+        format!("{} cf.ut_bought>=2022-03-15 cf.ut_bought<2022-04-01", token),
+        format!("{} cf.ut_owner:me cf.ut_owner:bob", token),
+        format!("{} cf.ut_region:emea -cf.ut_region:none", token),
+    ] {
+        assert!(
+            matches(&mut conn, &bob, &ticket, &query),
+            "{} didn't match",
+            query
+        );
+    }
+}
+
+/// Rainy day unit test for the translation of queries on custom fields.
+#[test]
+fn ut_rainy_get_matching_custom() {
+    let mut conn = establish_connection();
+    let token = Uuid::new_v4().to_simple().to_string();
+    let ticket = insert_custom_ticket(&mut conn, &token);
+    let bob = viewer(&mut conn, "bob");
+
+    for query in [
+        format!("{} cf.ut_cores>8", token),
+        format!("{} cf.ut_cores:eight", token),
+        format!("{} cf.ut_bought<2022-03-15", token),
+        format!("{} cf.ut_owner:alice", token),
+        format!("{} cf.ut_owner:nobody_at_all", token),
+        format!("{} cf.ut_region:apac", token),
+        format!("{} cf.ut_region>emea", token),
+        format!("{} cf.ut_region:none", token),
+        format!("{} cf.ut_missing:1", token),
+    ] {
+        assert!(
+            !matches(&mut conn, &bob, &ticket, &query),
+            "{} matched",
+            query
+        );
+    }
+    let carol = viewer(&mut conn, "carol");
+    assert!(!matches(
+        &mut conn,
+        &carol,
+        &ticket,
+        &format!("{} cf.ut_owner:me", token)
+    ));
+}
@@ -1,8 +1,10 @@
-use super::{into_option, model, schema::*};
+use super::{escape_like, into_option, model, schema::*};
 use crate::into_io_err;
 use chrono::{Duration, Utc};
+use common::user::Role;
 use diesel::{prelude::*, PgConnection};
 use std::io;
+use uuid::Uuid;
 
 #[cfg(test)]
 mod tests;
@@ -10,6 +12,12 @@ mod tests;
 /// Timeout for email registration codes, in seconds.
 const EMAIL_CODE_TIMEOUT: i64 = 2 * 60 * 60;
 
+/// Timeout for invitation codes, in seconds.
+const INVITATION_CODE_TIMEOUT: i64 = 7 * 24 * 60 * 60;
+
+/// Timeout for login sessions, in seconds.
+const SESSION_TIMEOUT: i64 = 30 * 24 * 60 * 60;
+
 /// Retrieves a user with an email, if it exists.
 pub fn get_with_email(conn: &mut PgConnection, email: &str) -> io::Result<Option<model::User>> {
     let user = sys_user::table
@@ -19,6 +27,106 @@ pub fn get_with_email(conn: &mut PgConnection, email: &str) -> io::Result<Option
     into_option(user)
 }
 
+/// Retrieves a user with a username, if it exists.
+pub fn get_with_username(
+    conn: &mut PgConnection,
+    username: &str,
+) -> io::Result<Option<model::User>> {
+    let user = sys_user::table
+        .filter(sys_user::username.eq(username))
+        .first(conn);
+
+    into_option(user)
+}
+
+/// Retrieves a user with an ID, if it exists.
+pub fn get_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::User>> {
+    let user = sys_user::table.find(id).first(conn);
+
+    into_option(user)
+}
+
+/// Retrieves the IDs of the given users that are active.
+pub fn get_active_ids(conn: &mut PgConnection, ids: &[Uuid]) -> io::Result<Vec<Uuid>> {
+    sys_user::table
+        .filter(sys_user::active)
+        .filter(sys_user::id.eq_any(ids))
+        .select(sys_user::id)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the active users that a user can pick in forms, ordered by username.
+///
+/// Users can pick themselves and the members of the given organisations, or anyone if
+/// `organisation_ids` is `None`. Only users with the given ID, or with a username or name
+/// containing the given text, are returned.
+pub fn get_pickable(
+    conn: &mut PgConnection,
+    picker_id: Uuid,
+    organisation_ids: Option<&[Uuid]>,
+    filter: PickFilter<'_>,
+    limit: i64,
+) -> io::Result<Vec<model::User>> {
+    let mut query = sys_user::table.filter(sys_user::active).into_boxed();
+    if let Some(organisation_ids) = organisation_ids {
+        query = query.filter(
+            sys_user::id.eq(picker_id).or(sys_user::id.eq_any(
+                organisation_member::table
+                    .select(organisation_member::user_id)
+                    .filter(organisation_member::organisation_id.eq_any(organisation_ids)),
+            )),
+        );
+    }
+    match filter {
+        PickFilter::Id(id) => query = query.filter(sys_user::id.eq(id)),
+        PickFilter::Text(text) => {
+            let pattern = format!("%{}%", escape_like(text.trim()));
+            query = query.filter(
+                sys_user::username
+                    .ilike(pattern.clone())
+                    .or(sys_user::first_name.ilike(pattern.clone()))
+                    .or(sys_user::last_name.ilike(pattern)),
+            );
+        }
+    }
+
+    query
+        .order(sys_user::username)
+        .limit(limit)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the active agents and administrators, ordered by name.
+pub fn get_staff(conn: &mut PgConnection) -> io::Result<Vec<model::User>> {
+    let roles = Role::ALL
+        .iter()
+        .filter(|role| role.is_staff())
+        .map(|role| role.as_str())
+        .collect::<Vec<_>>();
+
+    sys_user::table
+        .filter(sys_user::active)
+        .filter(sys_user::role.eq_any(roles))
+        .order((
+            sys_user::first_name,
+            sys_user::last_name,
+            sys_user::username,
+        ))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Filter of the users that can be picked in forms.
+#[derive(Debug, Clone, Copy)]
+pub enum PickFilter<'t> {
+    /// Only the user with this ID.
+    Id(Uuid),
+    /// Users with a username or name containing this text.
+    Text(&'t str),
+}
+
 /// Inserts a new user into the database.
 pub fn insert_user(
     conn: &mut PgConnection,
@@ -27,7 +135,8 @@ pub fn insert_user(
     password: &[u8],
     first_name: &str,
     last_name: &str,
-) -> io::Result<()> {
+    role: Role,
+) -> io::Result<model::User> {
     let new_record = model::NewUser {
         active: true,
         username,
@@ -35,10 +144,67 @@ pub fn insert_user(
         password,
         first_name,
         last_name,
+        role: role.as_str(),
     };
 
     diesel::insert_into(sys_user::table)
         .values(&new_record)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Changes the role of a user, returning the updated user if it exists.
+pub fn update_role(
+    conn: &mut PgConnection,
+    id: Uuid,
+    role: Role,
+) -> io::Result<Option<model::User>> {
+    let user = diesel::update(sys_user::table.find(id))
+        .set(sys_user::role.eq(role.as_str()))
+        .get_result(conn);
+
+    into_option(user)
+}
+
+/// Inserts a new login session for the given user, with the hash of its token.
+pub fn insert_session(conn: &mut PgConnection, token_hash: &[u8], user_id: Uuid) -> io::Result<()> {
+    let new_record = model::NewSession {
+        token_hash,
+        user_id,
+    };
+    diesel::insert_into(sys_session::table)
+        .values(&new_record)
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Retrieves the active user logged in with the session with the given token hash, if it exists.
+pub fn get_with_session(
+    conn: &mut PgConnection,
+    token_hash: &[u8],
+) -> io::Result<Option<model::User>> {
+    let now = Utc::now();
+    let timeout = Duration::seconds(SESSION_TIMEOUT);
+    let limit = now - timeout;
+
+    let user = sys_session::table
+        .inner_join(sys_user::table)
+        .filter(
+            sys_session::token_hash
+                .eq(token_hash)
+                .and(sys_session::created_on.ge(limit))
+                .and(sys_user::active.eq(true)),
+        )
+        .select(sys_user::all_columns)
+        .first(conn);
+
+    into_option(user)
+}
+
+/// Deletes the login session with the given token hash.
+pub fn delete_session(conn: &mut PgConnection, token_hash: &[u8]) -> io::Result<()> {
+    diesel::delete(sys_session::table.find(token_hash))
         .execute(conn)
         .map(|_count| ())
         .map_err(into_io_err)
@@ -69,8 +235,13 @@ pub fn insert_email_registration(
     conn: &mut PgConnection,
     email: &str,
     code: &str,
+    organisation_id: Option<Uuid>,
 ) -> io::Result<()> {
-    let new_record = model::NewEmailRegistration { email, code };
+    let new_record = model::NewEmailRegistration {
+        email,
+        code,
+        organisation_id,
+    };
     diesel::insert_into(sys_email_registration::table)
         .values(&new_record)
         .execute(conn)
@@ -79,11 +250,108 @@ pub fn insert_email_registration(
 }
 
 /// Deletes all email registrations for the given email.
+///
+/// Returns `false` if there were no email registrations for that email.
 pub fn delete_email_registrations_for_email(
     conn: &mut PgConnection,
     email: &str,
-) -> io::Result<()> {
+) -> io::Result<bool> {
     diesel::delete(sys_email_registration::table.filter(sys_email_registration::email.eq(email)))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+type InvitationColumns = (
+    sys_invitation::code,
+    sys_invitation::email,
+    sys_invitation::role,
+    sys_invitation::organisation_id,
+);
+
+/// Columns of the invitations, except who sent them and when.
+const INVITATION_COLUMNS: InvitationColumns = (
+    sys_invitation::code,
+    sys_invitation::email,
+    sys_invitation::role,
+    sys_invitation::organisation_id,
+);
+
+/// Retrieves an invitation with a given code, if it exists and has not expired.
+pub fn get_invitation_with_code(
+    conn: &mut PgConnection,
+    code: &str,
+) -> io::Result<Option<model::Invitation>> {
+    let now = Utc::now();
+    let timeout = Duration::seconds(INVITATION_CODE_TIMEOUT);
+    let limit = now - timeout;
+
+    let invitation = sys_invitation::table
+        .select(INVITATION_COLUMNS)
+        .filter(
+            sys_invitation::code
+                .eq(code)
+                .and(sys_invitation::created_on.ge(limit)),
+        )
+        .first::<model::Invitation>(conn);
+
+    into_option(invitation)
+}
+
+/// Retrieves all the invitations that have not expired, along with the username of the user that
+/// sent each of them.
+pub fn get_pending_invitations(
+    conn: &mut PgConnection,
+) -> io::Result<Vec<(model::Invitation, String)>> {
+    let now = Utc::now();
+    let timeout = Duration::seconds(INVITATION_CODE_TIMEOUT);
+    let limit = now - timeout;
+
+    sys_invitation::table
+        .inner_join(sys_user::table)
+        .filter(sys_invitation::created_on.ge(limit))
+        .order(sys_invitation::created_on.desc())
+        .select((INVITATION_COLUMNS, sys_user::username))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Inserts a new invitation.
+pub fn insert_invitation(
+    conn: &mut PgConnection,
+    email: &str,
+    code: &str,
+    role: Role,
+    invited_by: Uuid,
+    organisation_id: Option<Uuid>,
+) -> io::Result<()> {
+    let new_record = model::NewInvitation {
+        code,
+        email,
+        role: role.as_str(),
+        invited_by,
+        organisation_id,
+    };
+    diesel::insert_into(sys_invitation::table)
+        .values(&new_record)
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Deletes the invitation with the given code.
+///
+/// Returns `false` if no invitation had that code.
+pub fn delete_invitation(conn: &mut PgConnection, code: &str) -> io::Result<bool> {
+    diesel::delete(sys_invitation::table.find(code))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Deletes all invitations for the given email.
+pub fn delete_invitations_for_email(conn: &mut PgConnection, email: &str) -> io::Result<()> {
+    diesel::delete(sys_invitation::table.filter(sys_invitation::email.eq(email)))
         .execute(conn)
         .map(|_count| ())
         .map_err(into_io_err)
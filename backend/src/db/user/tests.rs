@@ -1,14 +1,5 @@
 use super::*;
-use std::env;
-
-/// Helper function to stablish database connections.
-fn establish_connection() -> PgConnection {
-    let _ = dotenv::dotenv().ok();
-
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    PgConnection::establish(&database_url)
-        .unwrap_or_else(|e| panic!("error connecting to {}: {}", database_url, e))
-}
+use crate::db::establish_connection;
 
 /// Sunny day unit test for the `get_with_email()` function.
 #[test]
@@ -53,3 +44,102 @@ fn ut_rainy_user_get_with_email() {
         "some user was found with an incorrect email"
     );
 }
+
+/// Sunny day unit test for the `get_with_username()` function.
+#[test]
+fn ut_sunny_get_with_username() {
+    let mut conn = establish_connection();
+
+    let user = get_with_username(&mut conn, "alice").expect("error retrieving user from database");
+    assert!(user.is_some(), "Alice was not in the database");
+    assert_eq!(
+        user.unwrap().role(),
+        Role::Admin,
+        "Alice is not an administrator"
+    );
+}
+
+/// Sunny day unit test for `get_staff`, which lists agents and administrators only.
+#[test]
+fn ut_sunny_get_staff() {
+    let mut conn = establish_connection();
+
+    let staff = get_staff(&mut conn).expect("error retrieving staff from database");
+    assert!(staff.iter().any(|user| user.username == "alice"));
+    assert!(staff.iter().any(|user| user.username == "bob"));
+    assert!(staff
+        .iter()
+        .all(|user| user.role().is_staff() && user.active));
+}
+
+/// Sunny day unit test for the invitation functions.
+#[test]
+fn ut_sunny_invitation() {
+    let mut conn = establish_connection();
+
+    let bob = get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("Bob was not in the database");
+
+    delete_invitations_for_email(&mut conn, "invited@my-support.com")
+        .expect("error deleting old invitations");
+    insert_invitation(
+        &mut conn,
+        "invited@my-support.com",
+        "ut_invite0",
+        Role::Agent,
+        bob.id,
+        None,
+    )
+    .expect("error inserting invitation");
+
+    let invitation = get_invitation_with_code(&mut conn, "ut_invite0")
+        .expect("error retrieving invitation from database")
+        .expect("invitation was not found");
+    assert_eq!(invitation.email, "invited@my-support.com");
+    assert_eq!(invitation.role(), Role::Agent);
+
+    let pending = get_pending_invitations(&mut conn).expect("error retrieving invitations");
+    assert!(
+        pending
+            .iter()
+            .any(|(inv, by)| inv.code == "ut_invite0" && by == "bob"),
+        "the invitation was not pending"
+    );
+
+    assert!(delete_invitation(&mut conn, "ut_invite0").expect("error deleting invitation"));
+    assert!(get_invitation_with_code(&mut conn, "ut_invite0")
+        .expect("error retrieving invitation from database")
+        .is_none());
+}
+
+/// Rainy day unit test for the `delete_invitation()` function.
+#[test]
+fn ut_rainy_delete_invitation() {
+    let mut conn = establish_connection();
+
+    assert!(
+        !delete_invitation(&mut conn, "not_a_code").expect("error deleting invitation"),
+        "a nonexistant invitation was deleted"
+    );
+}
+
+/// Sunny day unit test for the session functions.
+#[test]
+fn ut_sunny_session() {
+    let mut conn = establish_connection();
+
+    let alice = get_with_username(&mut conn, "alice")
+        .expect("error retrieving user from database")
+        .expect("Alice was not in the database");
+
+    let token = b"ut_sunny_session_token_hash";
+    insert_session(&mut conn, token, alice.id).expect("error inserting session");
+
+    let user = get_with_session(&mut conn, token).expect("error retrieving session user");
+    assert_eq!(user.map(|u| u.id), Some(alice.id));
+
+    delete_session(&mut conn, token).expect("error deleting session");
+    let user = get_with_session(&mut conn, token).expect("error retrieving session user");
+    assert!(user.is_none(), "the session was not deleted");
+}
@@ -0,0 +1,78 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::Utc;
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Retrieves the views listed for a user: their own ones and the shared ones, ordered by name.
+pub fn get_listed(conn: &mut PgConnection, user_id: Uuid) -> io::Result<Vec<model::TicketView>> {
+    ticket_view::table
+        .filter(ticket_view::owner_id.eq(user_id).or(ticket_view::shared))
+        .order((ticket_view::name, ticket_view::id))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a view with an ID, if it exists.
+pub fn get_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::TicketView>> {
+    into_option(ticket_view::table.find(id).first(conn))
+}
+
+/// Checks if a user has a view with the given name, other than the given one.
+pub fn name_taken(
+    conn: &mut PgConnection,
+    owner_id: Uuid,
+    name: &str,
+    except: Option<Uuid>,
+) -> io::Result<bool> {
+    let mut query = ticket_view::table
+        .select(ticket_view::id)
+        .filter(ticket_view::owner_id.eq(owner_id))
+        .filter(ticket_view::name.eq(name))
+        .into_boxed();
+    if let Some(id) = except {
+        query = query.filter(ticket_view::id.ne(id));
+    }
+
+    query
+        .first::<Uuid>(conn)
+        .optional()
+        .map(|id| id.is_some())
+        .map_err(into_io_err)
+}
+
+/// Inserts a new view.
+pub fn insert(
+    conn: &mut PgConnection,
+    form: &model::TicketViewForm<'_>,
+) -> io::Result<model::TicketView> {
+    diesel::insert_into(ticket_view::table)
+        .values(form)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a view, returning it if it exists.
+pub fn update(
+    conn: &mut PgConnection,
+    id: Uuid,
+    form: &model::TicketViewForm<'_>,
+) -> io::Result<Option<model::TicketView>> {
+    into_option(
+        diesel::update(ticket_view::table.find(id))
+            .set((form, ticket_view::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
+
+/// Deletes a view, returning whether it existed.
+pub fn delete(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(ticket_view::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
@@ -0,0 +1,108 @@
+use super::*;
+use crate::db::{establish_connection, user};
+use diesel::Connection;
+
+/// Sunny day unit test for the view functions.
+#[test]
+fn ut_sunny_views() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("Bob was not in the database");
+    let carol = user::get_with_username(&mut conn, "carol")
+        .expect("error retrieving user from database")
+        .expect("Carol was not in the database");
+
+    let personal = insert(
+        &mut conn,
+        &model::TicketViewForm {
+            owner_id: bob.id,
+            name: "UT my open tickets",
+            query: "status:open assignee:me",
+            shared: false,
+        },
+    )
+    .expect("error inserting view");
+    let shared = insert(
+        &mut conn,
+        &model::TicketViewForm {
+            owner_id: bob.id,
+            name: "UT urgent tickets",
+            query: "priority:urgent",
+            shared: true,
+        },
+    )
+    .expect("error inserting view");
+
+    let listed = |conn: &mut PgConnection, user_id| {
+        get_listed(conn, user_id)
+            .expect("error retrieving views")
+            .into_iter()
+            .map(|view| view.id)
+            .collect::<Vec<_>>()
+    };
+    let bobs = listed(&mut conn, bob.id);
+    assert!(bobs.contains(&personal.id) && bobs.contains(&shared.id));
+    let carols = listed(&mut conn, carol.id);
+    assert!(!carols.contains(&personal.id) && carols.contains(&shared.id));
+
+    assert!(name_taken(&mut conn, bob.id, "UT urgent tickets", None).expect("error checking name"));
+    assert!(
+        !name_taken(&mut conn, bob.id, "UT urgent tickets", Some(shared.id))
+            .expect("error checking name")
+    );
+    assert!(
+        !name_taken(&mut conn, carol.id, "UT urgent tickets", None).expect("error checking name")
+    );
+
+    let updated = update(
+        &mut conn,
+        personal.id,
+        &model::TicketViewForm {
+            owner_id: bob.id,
+            name: "UT my pending tickets",
+            query: "status:pending assignee:me",
+            shared: false,
+        },
+    )
+    .expect("error updating view")
+    .expect("view was not found");
+    assert_eq!(updated.name, "UT my pending tickets");
+    assert!(updated.updated_on >= personal.updated_on);
+
+    assert!(delete(&mut conn, personal.id).expect("error deleting view"));
+    assert!(get_with_id(&mut conn, personal.id)
+        .expect("error retrieving view")
+        .is_none());
+}
+
+/// Rainy day unit test for the view functions.
+#[test]
+fn ut_rainy_views() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob = user::get_with_username(&mut conn, "bob")
+        .expect("error retrieving user from database")
+        .expect("Bob was not in the database");
+    let form = model::TicketViewForm {
+        owner_id: bob.id,
+        name: "",
+        query: "status:open",
+        shared: false,
+    };
+
+    assert!(update(&mut conn, Uuid::new_v4(), &form)
+        .expect("error updating view")
+        .is_none());
+    assert!(!delete(&mut conn, Uuid::new_v4()).expect("error deleting view"));
+    // This aborts the test transaction, so it must be the last check
+    assert!(
+        insert(&mut conn, &form).is_err(),
+        "view without name was inserted"
+    );
+}
@@ -0,0 +1,222 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::{DateTime, Utc};
+use common::webhook::DeliveryStatus;
+use diesel::{prelude::*, PgConnection};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Retrieves all webhook subscriptions, oldest first.
+pub fn get_subscriptions(conn: &mut PgConnection) -> io::Result<Vec<model::WebhookSubscription>> {
+    webhook_subscription::table
+        .order(webhook_subscription::created_on)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the enabled webhook subscriptions.
+pub fn get_enabled_subscriptions(
+    conn: &mut PgConnection,
+) -> io::Result<Vec<model::WebhookSubscription>> {
+    webhook_subscription::table
+        .filter(webhook_subscription::enabled)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a webhook subscription with an ID, if it exists.
+pub fn get_subscription_with_id(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> io::Result<Option<model::WebhookSubscription>> {
+    into_option(webhook_subscription::table.find(id).first(conn))
+}
+
+/// Inserts a new webhook subscription.
+///
+/// The form must contain a secret.
+pub fn insert_subscription(
+    conn: &mut PgConnection,
+    subscription: &model::WebhookSubscriptionForm<'_>,
+    created_by: Uuid,
+) -> io::Result<model::WebhookSubscription> {
+    debug_assert!(subscription.secret.is_some(), "subscriptions need a secret");
+
+    diesel::insert_into(webhook_subscription::table)
+        .values((
+            subscription,
+            webhook_subscription::created_by.eq(created_by),
+        ))
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a webhook subscription, returning it if it exists.
+///
+/// Enabled subscriptions get their failure count reset.
+pub fn update_subscription(
+    conn: &mut PgConnection,
+    id: Uuid,
+    subscription: &model::WebhookSubscriptionForm<'_>,
+) -> io::Result<Option<model::WebhookSubscription>> {
+    let query = diesel::update(webhook_subscription::table.find(id));
+    let updated_on = webhook_subscription::updated_on.eq(Utc::now());
+
+    let subscription = if subscription.enabled {
+        query
+            .set((
+                subscription,
+                updated_on,
+                webhook_subscription::consecutive_failures.eq(0),
+            ))
+            .get_result(conn)
+    } else {
+        query.set((subscription, updated_on)).get_result(conn)
+    };
+
+    into_option(subscription)
+}
+
+/// Deletes a webhook subscription, along with its deliveries.
+///
+/// Returns `false` if the subscription didn't exist.
+pub fn delete_subscription(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(webhook_subscription::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Resets the failure count of a webhook subscription, after a successful delivery.
+pub fn reset_failures(conn: &mut PgConnection, id: Uuid) -> io::Result<()> {
+    diesel::update(webhook_subscription::table.find(id))
+        .set(webhook_subscription::consecutive_failures.eq(0))
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Increments the failure count of a webhook subscription, returning the new count.
+pub fn increment_failures(conn: &mut PgConnection, id: Uuid) -> io::Result<i32> {
+    diesel::update(webhook_subscription::table.find(id))
+        .set(
+            webhook_subscription::consecutive_failures
+                .eq(webhook_subscription::consecutive_failures + 1),
+        )
+        .returning(webhook_subscription::consecutive_failures)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Disables a webhook subscription, failing its pending deliveries.
+pub fn disable_subscription(
+    conn: &mut PgConnection,
+    id: Uuid,
+    now: DateTime<Utc>,
+) -> io::Result<()> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let _ = diesel::update(webhook_subscription::table.find(id))
+            .set((
+                webhook_subscription::enabled.eq(false),
+                webhook_subscription::updated_on.eq(now),
+            ))
+            .execute(conn)?;
+
+        let _ = diesel::update(
+            webhook_delivery::table
+                .filter(webhook_delivery::subscription_id.eq(id))
+                .filter(webhook_delivery::status.eq(DeliveryStatus::Pending.as_str())),
+        )
+        .set((
+            webhook_delivery::status.eq(DeliveryStatus::Failed.as_str()),
+            webhook_delivery::error.eq("subscription disabled after too many failures"),
+            webhook_delivery::updated_on.eq(now),
+        ))
+        .execute(conn)?;
+
+        Ok(())
+    })
+    .map_err(into_io_err)
+}
+
+/// Inserts new pending webhook deliveries.
+pub fn insert_deliveries(
+    conn: &mut PgConnection,
+    deliveries: &[model::NewWebhookDelivery<'_>],
+) -> io::Result<()> {
+    diesel::insert_into(webhook_delivery::table)
+        .values(deliveries)
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
+
+/// Retrieves the deliveries of a webhook subscription, newest first.
+pub fn get_deliveries(
+    conn: &mut PgConnection,
+    subscription_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> io::Result<Vec<model::WebhookDelivery>> {
+    webhook_delivery::table
+        .filter(webhook_delivery::subscription_id.eq(subscription_id))
+        .order(webhook_delivery::id.desc())
+        .limit(limit)
+        .offset(offset)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Claims the pending deliveries of enabled subscriptions that are due, oldest first.
+///
+/// The next attempt of the claimed deliveries is postponed until `lease_until`, so that other
+/// workers don't attempt them at the same time. Deliveries being claimed by other workers are
+/// skipped.
+pub fn claim_due_deliveries(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+    lease_until: DateTime<Utc>,
+    limit: i64,
+) -> io::Result<Vec<(model::WebhookDelivery, model::WebhookSubscription)>> {
+    let conn: &PgConnection = conn;
+    conn.transaction::<_, diesel::result::Error, _>(|| {
+        let due = webhook_delivery::table
+            .inner_join(webhook_subscription::table)
+            .filter(webhook_delivery::status.eq(DeliveryStatus::Pending.as_str()))
+            .filter(webhook_delivery::next_attempt_on.le(now))
+            .filter(webhook_subscription::enabled)
+            .order(webhook_delivery::next_attempt_on)
+            .limit(limit)
+            .for_update()
+            .skip_locked()
+            .load::<(model::WebhookDelivery, model::WebhookSubscription)>(conn)?;
+
+        let ids = due
+            .iter()
+            .map(|(delivery, _)| delivery.id)
+            .collect::<Vec<_>>();
+        let _ = diesel::update(webhook_delivery::table.filter(webhook_delivery::id.eq_any(ids)))
+            .set(webhook_delivery::next_attempt_on.eq(lease_until))
+            .execute(conn)?;
+
+        Ok(due)
+    })
+    .map_err(into_io_err)
+}
+
+/// Stores the outcome of a delivery attempt.
+pub fn update_delivery(
+    conn: &mut PgConnection,
+    id: i64,
+    attempt: &model::WebhookAttempt<'_>,
+) -> io::Result<()> {
+    diesel::update(webhook_delivery::table.find(id))
+        .set(attempt)
+        .execute(conn)
+        .map(|_count| ())
+        .map_err(into_io_err)
+}
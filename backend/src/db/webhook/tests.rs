@@ -0,0 +1,127 @@
+use super::*;
+use crate::db::establish_connection;
+use chrono::Duration;
+use diesel::Connection;
+
+/// Inserts a test subscription.
+fn insert(conn: &mut PgConnection, url: &str) -> model::WebhookSubscription {
+    let alice = crate::db::user::get_with_username(conn, "alice")
+        .expect("error retrieving user from database")
+        .expect("Alice was not in the database");
+
+    insert_subscription(
+        conn,
+        &model::WebhookSubscriptionForm {
+            url,
+            events: &["ticket.created".to_owned()],
+            secret: Some("ut_subscription_secret"),
+            enabled: true,
+        },
+        alice.id,
+    )
+    .expect("error inserting subscription")
+}
+
+/// Sunny day unit test for the subscription functions.
+#[test]
+fn ut_sunny_subscriptions() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let subscription = insert(&mut conn, "http://127.0.0.1/ut");
+    assert!(get_enabled_subscriptions(&mut conn)
+        .expect("error retrieving subscriptions")
+        .iter()
+        .any(|s| s.id == subscription.id));
+    assert_eq!(
+        increment_failures(&mut conn, subscription.id).expect("error incrementing failures"),
+        1
+    );
+
+    // The secret is kept if not provided, and enabling resets the failures
+    let updated = update_subscription(
+        &mut conn,
+        subscription.id,
+        &model::WebhookSubscriptionForm {
+            url: "https://127.0.0.1/ut",
+            events: &[],
+            secret: None,
+            enabled: true,
+        },
+    )
+    .expect("error updating subscription")
+    .expect("subscription disappeared");
+    assert_eq!(updated.url, "https://127.0.0.1/ut");
+    assert_eq!(updated.secret, "ut_subscription_secret");
+    assert_eq!(updated.consecutive_failures, 0);
+    assert!(updated.events.is_empty());
+
+    assert!(delete_subscription(&mut conn, subscription.id).expect("error deleting"));
+    assert!(get_subscription_with_id(&mut conn, subscription.id)
+        .expect("error retrieving subscription")
+        .is_none());
+}
+
+/// Sunny day unit test for claiming deliveries.
+#[test]
+fn ut_sunny_claim_due_deliveries() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let subscription = insert(&mut conn, "http://127.0.0.1/ut");
+    let payload = serde_json::json!({});
+    insert_deliveries(
+        &mut conn,
+        &[model::NewWebhookDelivery {
+            subscription_id: subscription.id,
+            event: "ticket.created",
+            payload: &payload,
+        }],
+    )
+    .expect("error inserting delivery");
+
+    let now = Utc::now() + Duration::seconds(1);
+    let lease_until = now + Duration::minutes(10);
+    let claimed =
+        claim_due_deliveries(&mut conn, now, lease_until, 1000).expect("error claiming deliveries");
+    assert!(claimed.iter().any(|(_, s)| s.id == subscription.id));
+
+    // Claimed deliveries are not claimed again until the lease expires
+    let claimed =
+        claim_due_deliveries(&mut conn, now, lease_until, 1000).expect("error claiming deliveries");
+    assert!(!claimed.iter().any(|(_, s)| s.id == subscription.id));
+    let claimed = claim_due_deliveries(&mut conn, lease_until, lease_until, 1000)
+        .expect("error claiming deliveries");
+    assert!(claimed.iter().any(|(_, s)| s.id == subscription.id));
+}
+
+/// Rainy day unit test for claiming deliveries of disabled subscriptions.
+#[test]
+fn ut_rainy_claim_due_deliveries_disabled() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let subscription = insert(&mut conn, "http://127.0.0.1/ut");
+    let payload = serde_json::json!({});
+    insert_deliveries(
+        &mut conn,
+        &[model::NewWebhookDelivery {
+            subscription_id: subscription.id,
+            event: "ticket.created",
+            payload: &payload,
+        }],
+    )
+    .expect("error inserting delivery");
+    let now = Utc::now() + Duration::seconds(1);
+    disable_subscription(&mut conn, subscription.id, now).expect("error disabling subscription");
+
+    let claimed = claim_due_deliveries(&mut conn, now, now, 1000).expect("error claiming");
+    assert!(!claimed.iter().any(|(_, s)| s.id == subscription.id));
+
+    let deliveries =
+        get_deliveries(&mut conn, subscription.id, 10, 0).expect("error retrieving deliveries");
+    assert_eq!(deliveries[0].status(), DeliveryStatus::Failed);
+}
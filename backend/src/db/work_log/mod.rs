@@ -0,0 +1,249 @@
+use super::{into_option, model, schema::*};
+use crate::into_io_err;
+use chrono::{DateTime, Utc};
+use common::work_log::Grouping;
+use diesel::{
+    prelude::*,
+    sql_query,
+    sql_types::{Nullable, Timestamptz},
+    PgConnection,
+};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Query totalling the time logged in a period per organisation of the tickets, the tickets
+/// without organisation being totalled together.
+const ORGANISATION_TOTALS_QUERY: &str = "\
+SELECT o.id, o.name,
+    SUM(w.minutes)::bigint AS minutes,
+    COALESCE(SUM(w.minutes) FILTER (WHERE w.billable), 0)::bigint AS billable_minutes
+FROM work_log w
+JOIN ticket t ON t.id = w.ticket_id
+LEFT JOIN organisation o ON o.id = t.organisation_id
+WHERE ($1::timestamptz IS NULL OR w.worked_on >= $1)
+    AND ($2::timestamptz IS NULL OR w.worked_on < $2)
+GROUP BY o.id, o.name
+ORDER BY o.name NULLS LAST";
+
+/// Query totalling the time logged in a period per agent.
+const AGENT_TOTALS_QUERY: &str = "\
+SELECT u.id, (u.first_name || ' ' || u.last_name)::varchar AS name,
+    SUM(w.minutes)::bigint AS minutes,
+    COALESCE(SUM(w.minutes) FILTER (WHERE w.billable), 0)::bigint AS billable_minutes
+FROM work_log w
+JOIN sys_user u ON u.id = w.agent_id
+WHERE ($1::timestamptz IS NULL OR w.worked_on >= $1)
+    AND ($2::timestamptz IS NULL OR w.worked_on < $2)
+GROUP BY u.id, u.first_name, u.last_name
+ORDER BY name";
+
+/// Retrieves the work logged on a ticket, by time of work.
+pub fn get_for_ticket(conn: &mut PgConnection, ticket_id: Uuid) -> io::Result<Vec<model::WorkLog>> {
+    work_log::table
+        .filter(work_log::ticket_id.eq(ticket_id))
+        .order((work_log::worked_on, work_log::created_on))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves a work log entry with an ID, if it exists.
+pub fn get_with_id(conn: &mut PgConnection, id: Uuid) -> io::Result<Option<model::WorkLog>> {
+    into_option(work_log::table.find(id).first(conn))
+}
+
+/// Inserts a new work log entry for the work of an agent on a ticket.
+pub fn insert(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    agent_id: Uuid,
+    form: &model::WorkLogForm<'_>,
+) -> io::Result<model::WorkLog> {
+    diesel::insert_into(work_log::table)
+        .values((
+            form,
+            work_log::ticket_id.eq(ticket_id),
+            work_log::agent_id.eq(agent_id),
+        ))
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a work log entry, returning it if it exists.
+pub fn update(
+    conn: &mut PgConnection,
+    id: Uuid,
+    form: &model::WorkLogForm<'_>,
+) -> io::Result<Option<model::WorkLog>> {
+    into_option(
+        diesel::update(work_log::table.find(id))
+            .set((form, work_log::updated_on.eq(Utc::now())))
+            .get_result(conn),
+    )
+}
+
+/// Deletes a work log entry, returning whether it existed.
+pub fn delete(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(work_log::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Totals the time logged in a period, per organisation or agent.
+///
+/// The period is unbounded on missing sides.
+pub fn get_totals(
+    conn: &mut PgConnection,
+    grouping: Grouping,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> io::Result<Vec<model::TimeTotal>> {
+    let query = match grouping {
+        Grouping::Organisation => ORGANISATION_TOTALS_QUERY,
+        Grouping::Agent => AGENT_TOTALS_QUERY,
+    };
+
+    sql_query(query)
+        .bind::<Nullable<Timestamptz>, _>(from)
+        .bind::<Nullable<Timestamptz>, _>(to)
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+/// Retrieves the work logged in a period, optionally for an organisation or by an agent, with the
+/// tickets, organisations and agents it is exported with, by time of work.
+///
+/// The period is unbounded on missing sides.
+pub fn get_export(
+    conn: &mut PgConnection,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    organisation_id: Option<Uuid>,
+    agent_id: Option<Uuid>,
+) -> io::Result<Vec<model::WorkLogExport>> {
+    let mut query = work_log::table
+        .inner_join(ticket::table.left_join(organisation::table))
+        .inner_join(sys_user::table)
+        .select((
+            work_log::all_columns,
+            ticket::number,
+            organisation::name.nullable(),
+            sys_user::username,
+        ))
+        .into_boxed();
+    if let Some(from) = from {
+        query = query.filter(work_log::worked_on.ge(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(work_log::worked_on.lt(to));
+    }
+    if let Some(organisation_id) = organisation_id {
+        query = query.filter(ticket::organisation_id.eq(organisation_id));
+    }
+    if let Some(agent_id) = agent_id {
+        query = query.filter(work_log::agent_id.eq(agent_id));
+    }
+
+    query
+        .order((work_log::worked_on, work_log::created_on))
+        .load(conn)
+        .map_err(into_io_err)
+}
+
+type ContractColumns = (
+    contract::id,
+    contract::organisation_id,
+    contract::name,
+    contract::prepaid_minutes,
+    contract::starts_on,
+    contract::ends_on,
+);
+
+/// Columns of the contracts, except their timestamps.
+const CONTRACT_COLUMNS: ContractColumns = (
+    contract::id,
+    contract::organisation_id,
+    contract::name,
+    contract::prepaid_minutes,
+    contract::starts_on,
+    contract::ends_on,
+);
+
+/// Retrieves the contracts of an organisation, with the billable minutes logged during each of
+/// them, newest first.
+pub fn get_contracts(
+    conn: &mut PgConnection,
+    organisation_id: Uuid,
+) -> io::Result<Vec<(model::Contract, i64)>> {
+    let contracts = contract::table
+        .select(CONTRACT_COLUMNS)
+        .filter(contract::organisation_id.eq(organisation_id))
+        .order((contract::starts_on.desc(), contract::name))
+        .load::<model::Contract>(conn)
+        .map_err(into_io_err)?;
+
+    contracts
+        .into_iter()
+        .map(|contract| {
+            let used = get_used_minutes(conn, &contract)?;
+            Ok((contract, used))
+        })
+        .collect()
+}
+
+/// Inserts a new contract for an organisation.
+pub fn insert_contract(
+    conn: &mut PgConnection,
+    organisation_id: Uuid,
+    form: &model::ContractForm<'_>,
+) -> io::Result<model::Contract> {
+    diesel::insert_into(contract::table)
+        .values((form, contract::organisation_id.eq(organisation_id)))
+        .returning(CONTRACT_COLUMNS)
+        .get_result(conn)
+        .map_err(into_io_err)
+}
+
+/// Updates a contract, returning it if it exists.
+pub fn update_contract(
+    conn: &mut PgConnection,
+    id: Uuid,
+    form: &model::ContractForm<'_>,
+) -> io::Result<Option<model::Contract>> {
+    into_option(
+        diesel::update(contract::table.find(id))
+            .set((form, contract::updated_on.eq(Utc::now())))
+            .returning(CONTRACT_COLUMNS)
+            .get_result(conn),
+    )
+}
+
+/// Deletes a contract, returning whether it existed.
+pub fn delete_contract(conn: &mut PgConnection, id: Uuid) -> io::Result<bool> {
+    diesel::delete(contract::table.find(id))
+        .execute(conn)
+        .map(|count| count > 0)
+        .map_err(into_io_err)
+}
+
+/// Sums the billable minutes logged on the tickets of the organisation of a contract during it.
+pub fn get_used_minutes(conn: &mut PgConnection, contract: &model::Contract) -> io::Result<i64> {
+    let mut query = work_log::table
+        .inner_join(ticket::table)
+        .filter(ticket::organisation_id.eq(contract.organisation_id))
+        .filter(work_log::billable)
+        .filter(work_log::worked_on.ge(contract.starts_on))
+        .select(diesel::dsl::sum(work_log::minutes))
+        .into_boxed();
+    if let Some(ends_on) = contract.ends_on {
+        query = query.filter(work_log::worked_on.lt(ends_on));
+    }
+
+    query
+        .first::<Option<i64>>(conn)
+        .map(Option::unwrap_or_default)
+        .map_err(into_io_err)
+}
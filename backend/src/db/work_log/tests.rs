@@ -0,0 +1,220 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+    organisation,
+};
+use chrono::{Duration, TimeZone};
+use common::work_log::Activity;
+use diesel::Connection;
+
+/// Logs some minutes of work of an agent on a ticket at a time.
+fn log_work(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    agent_id: Uuid,
+    minutes: i32,
+    billable: bool,
+    worked_on: DateTime<Utc>,
+) -> model::WorkLog {
+    insert(
+        conn,
+        ticket_id,
+        agent_id,
+        &model::WorkLogForm {
+            minutes,
+            billable,
+            activity: Activity::Support.as_str(),
+            description: "",
+            worked_on,
+        },
+    )
+    .expect("error inserting work log")
+}
+
+/// Sunny day unit test for work logs: entries are logged, corrected and totalled per organisation
+/// and agent over a period.
+#[test]
+fn ut_sunny_work_log() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (alice, bob) = (user_id(&mut conn, "alice"), user_id(&mut conn, "bob"));
+    let carol = user_id(&mut conn, "carol");
+    let acme = organisation::insert(&mut conn, "UT Acme", &[])
+        .expect("error inserting organisation")
+        .id;
+    let acme_ticket = insert_ticket(
+        &mut conn,
+        &model::NewTicket {
+            organisation_id: Some(acme),
+            ..new_ticket(carol, "UT anvil")
+        },
+    )
+    .id;
+    let other_ticket = insert_ticket(&mut conn, &new_ticket(carol, "UT rocket")).id;
+    let start = Utc.ymd(2041, 5, 1).and_hms(9, 0, 0);
+
+    let log = log_work(&mut conn, acme_ticket, bob, 30, true, start);
+    let _ = log_work(&mut conn, acme_ticket, alice, 15, false, start);
+    let _ = log_work(&mut conn, other_ticket, bob, 60, true, start);
+    let updated = update(
+        &mut conn,
+        log.id,
+        &model::WorkLogForm {
+            minutes: 45,
+            billable: true,
+            activity: Activity::Travel.as_str(),
+            description: "Drove to the canyon",
+            worked_on: start,
+        },
+    )
+    .expect("error updating work log")
+    .expect("work log was not found");
+    assert_eq!(updated.activity(), Activity::Travel);
+    let logs = get_for_ticket(&mut conn, acme_ticket).expect("error retrieving work logs");
+    assert_eq!(logs.len(), 2);
+
+    let (from, to) = (Some(start), Some(start + Duration::days(1)));
+    let totals = get_totals(&mut conn, Grouping::Organisation, from, to)
+        .expect("error totalling by organisation");
+    assert_eq!(
+        totals
+            .iter()
+            .map(|t| (t.id, t.minutes, t.billable_minutes))
+            .collect::<Vec<_>>(),
+        [(Some(acme), 60, 45), (None, 60, 60)]
+    );
+    let totals =
+        get_totals(&mut conn, Grouping::Agent, from, to).expect("error totalling by agent");
+    let bob_total = totals
+        .iter()
+        .find(|t| t.id == Some(bob))
+        .expect("agent was not totalled");
+    assert_eq!((bob_total.minutes, bob_total.billable_minutes), (105, 105));
+
+    let export =
+        get_export(&mut conn, from, to, Some(acme), Some(bob)).expect("error retrieving export");
+    assert_eq!(export.len(), 1);
+    assert_eq!(export[0].organisation.as_deref(), Some("UT Acme"));
+    assert_eq!(export[0].agent, "bob");
+}
+
+/// Rainy day unit test for work logs: entries outside the period are not totalled, and missing
+/// entries are neither updated nor deleted.
+#[test]
+fn ut_rainy_work_log() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (bob, carol) = (user_id(&mut conn, "bob"), user_id(&mut conn, "carol"));
+    let ticket_id = insert_ticket(&mut conn, &new_ticket(carol, "UT jet pack")).id;
+    let start = Utc.ymd(2041, 6, 1).and_hms(9, 0, 0);
+    let _ = log_work(
+        &mut conn,
+        ticket_id,
+        bob,
+        30,
+        true,
+        start - Duration::seconds(1),
+    );
+    let _ = log_work(
+        &mut conn,
+        ticket_id,
+        bob,
+        30,
+        true,
+        start + Duration::days(1),
+    );
+
+    let totals = get_totals(
+        &mut conn,
+        Grouping::Agent,
+        Some(start),
+        Some(start + Duration::days(1)),
+    )
+    .expect("error totalling by agent");
+    assert!(totals.is_empty());
+
+    let form = model::WorkLogForm {
+        minutes: 30,
+        billable: true,
+        activity: Activity::Other.as_str(),
+        description: "",
+        worked_on: start,
+    };
+    let updated = update(&mut conn, Uuid::new_v4(), &form).expect("error updating work log");
+    assert!(updated.is_none());
+    let deleted = delete(&mut conn, Uuid::new_v4()).expect("error deleting work log");
+    assert!(!deleted);
+}
+
+/// Sunny day unit test for contracts: the balance counts the billable work on the tickets of the
+/// organisation during the contract only.
+#[test]
+fn ut_sunny_contract() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (bob, carol) = (user_id(&mut conn, "bob"), user_id(&mut conn, "carol"));
+    let acme = organisation::insert(&mut conn, "UT Acme", &[])
+        .expect("error inserting organisation")
+        .id;
+    let ticket_id = insert_ticket(
+        &mut conn,
+        &model::NewTicket {
+            organisation_id: Some(acme),
+            ..new_ticket(carol, "UT anvil")
+        },
+    )
+    .id;
+    let other_ticket = insert_ticket(&mut conn, &new_ticket(carol, "UT rocket")).id;
+    let start = Utc.ymd(2041, 1, 1).and_hms(0, 0, 0);
+    let end = start + Duration::days(31);
+
+    let contract = insert_contract(
+        &mut conn,
+        acme,
+        &model::ContractForm {
+            name: "UT gold",
+            prepaid_minutes: 600,
+            starts_on: start,
+            ends_on: Some(end),
+        },
+    )
+    .expect("error inserting contract");
+    let _ = log_work(&mut conn, ticket_id, bob, 90, true, start);
+    let _ = log_work(&mut conn, ticket_id, bob, 60, false, start);
+    let _ = log_work(&mut conn, ticket_id, bob, 30, true, end);
+    let _ = log_work(&mut conn, other_ticket, bob, 30, true, start);
+
+    let used = get_used_minutes(&mut conn, &contract).expect("error totalling used time");
+    assert_eq!(used, 90);
+    assert_eq!(contract.clone().into_dto(used).balance_minutes, 510);
+
+    // Without an end, the contract runs on
+    let _ = update_contract(
+        &mut conn,
+        contract.id,
+        &model::ContractForm {
+            name: "UT gold",
+            prepaid_minutes: 600,
+            starts_on: start,
+            ends_on: None,
+        },
+    )
+    .expect("error updating contract")
+    .expect("contract was not found");
+    let contracts = get_contracts(&mut conn, acme).expect("error retrieving contracts");
+    assert_eq!(
+        contracts
+            .iter()
+            .map(|(contract, used)| (contract.id, *used))
+            .collect::<Vec<_>>(),
+        [(contract.id, 120)]
+    );
+    assert!(delete_contract(&mut conn, contract.id).expect("error deleting contract"));
+    assert!(get_contracts(&mut conn, acme)
+        .expect("error retrieving contracts")
+        .is_empty());
+}
@@ -0,0 +1,94 @@
+//! iCalendar.
+//!
+//! Calendars are [written](write) following RFC 5545: lines end with `\r\n` and are folded at 75
+//! bytes, texts are escaped, and times are written in UTC.
+
+use chrono::{DateTime, Utc};
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum length of a line before it's folded, in bytes.
+const MAX_LINE_LEN: usize = 75;
+
+/// Event of a calendar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event<'e> {
+    /// Globally unique identifier of the event, kept across exports.
+    pub uid: String,
+    pub summary: &'e str,
+    pub description: &'e str,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Writes a calendar with a name and its events, stamped with the given time.
+pub fn write(name: &str, events: &[Event<'_>], now: DateTime<Utc>) -> String {
+    let mut calendar = String::new();
+    push_line(&mut calendar, "BEGIN:VCALENDAR");
+    push_line(&mut calendar, "VERSION:2.0");
+    push_line(&mut calendar, "PRODID:-//MySupport//MySupport//EN");
+    push_line(&mut calendar, "CALSCALE:GREGORIAN");
+    push_line(&mut calendar, &format!("X-WR-CALNAME:{}", escape(name)));
+
+    for event in events {
+        push_line(&mut calendar, "BEGIN:VEVENT");
+        push_line(&mut calendar, &format!("UID:{}", escape(&event.uid)));
+        push_line(&mut calendar, &format!("DTSTAMP:{}", format_time(now)));
+        push_line(
+            &mut calendar,
+            &format!("DTSTART:{}", format_time(event.start)),
+        );
+        push_line(&mut calendar, &format!("DTEND:{}", format_time(event.end)));
+        push_line(&mut calendar, &format!("SUMMARY:{}", escape(event.summary)));
+        if !event.description.is_empty() {
+            push_line(
+                &mut calendar,
+                &format!("DESCRIPTION:{}", escape(event.description)),
+            );
+        }
+        push_line(&mut calendar, "END:VEVENT");
+    }
+
+    push_line(&mut calendar, "END:VCALENDAR");
+    calendar
+}
+
+/// Formats a time in UTC, as `YYYYMMDDTHHMMSSZ`.
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a text value, so that it can't end its property or start a new one.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Pushes a content line, folding it so that no line is longer than 75 bytes.
+///
+/// Continuation lines start with a space, and characters are never split.
+fn push_line(calendar: &mut String, line: &str) {
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > MAX_LINE_LEN {
+            calendar.push_str("\r\n ");
+            len = 1;
+        }
+        calendar.push(c);
+        len += c.len_utf8();
+    }
+    calendar.push_str("\r\n");
+}
@@ -0,0 +1,50 @@
+use super::*;
+use chrono::TimeZone;
+
+/// Events are written with their times in UTC, and calendars without events are still valid.
+#[test]
+fn ut_sunny_write() {
+    let now = Utc.ymd(2022, 7, 30).and_hms(9, 0, 0);
+    let event = Event {
+        uid: "change-1@mysupport".to_owned(),
+        summary: "Upgrade the database",
+        description: "",
+        start: Utc.ymd(2022, 8, 1).and_hms(22, 0, 0),
+        end: Utc.ymd(2022, 8, 2).and_hms(1, 30, 0),
+    };
+
+    assert_eq!(
+        write("Changes", &[event], now),
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//MySupport//MySupport//EN\r\n\
+         CALSCALE:GREGORIAN\r\nX-WR-CALNAME:Changes\r\nBEGIN:VEVENT\r\n\
+         UID:change-1@mysupport\r\nDTSTAMP:20220730T090000Z\r\nDTSTART:20220801T220000Z\r\n\
+         DTEND:20220802T013000Z\r\nSUMMARY:Upgrade the database\r\nEND:VEVENT\r\n\
+         END:VCALENDAR\r\n"
+    );
+    assert_eq!(
+        write("Empty", &[], now),
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//MySupport//MySupport//EN\r\n\
+         CALSCALE:GREGORIAN\r\nX-WR-CALNAME:Empty\r\nEND:VCALENDAR\r\n"
+    );
+}
+
+/// Special characters are escaped, and long lines are folded without splitting characters.
+#[test]
+fn ut_rainy_write() {
+    let now = Utc.ymd(2022, 7, 30).and_hms(9, 0, 0);
+    let summary = format!("{}é", "x".repeat(66));
+    let event = Event {
+        uid: "change-2@mysupport".to_owned(),
+        summary: &summary,
+        description: "Stop; back up, then\r\nrestart \\ check",
+        start: now,
+        end: now,
+    };
+    let calendar = write("Changes", &[event], now);
+
+    assert!(calendar.contains("DESCRIPTION:Stop\\; back up\\, then\\nrestart \\\\ check\r\n"));
+    assert!(calendar.contains(&format!("SUMMARY:{}\r\n é\r\n", "x".repeat(66))));
+    assert!(calendar
+        .split("\r\n")
+        .all(|line| line.len() <= MAX_LINE_LEN));
+}
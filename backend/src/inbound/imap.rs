@@ -0,0 +1,85 @@
+//! Fetching of emails from an IMAP mailbox.
+
+use crate::into_io_err;
+use std::{env, io};
+
+/// Connection information of the IMAP mailbox.
+#[derive(Debug, Clone)]
+pub struct Config {
+    host: String,
+    port: u16,
+    user: String,
+    password: String,
+    mailbox: String,
+}
+
+impl Config {
+    /// Reads the configuration from the `INBOUND_IMAP_*` environment variables.
+    ///
+    /// Returns `None` if `INBOUND_IMAP_HOST` is not set. The port defaults to 993, and the mailbox
+    /// to `INBOX`.
+    pub fn from_env() -> Option<Self> {
+        let host = env::var("INBOUND_IMAP_HOST")
+            .ok()
+            .filter(|host| !host.trim().is_empty())?;
+
+        Some(Self {
+            host,
+            port: env::var("INBOUND_IMAP_PORT")
+                .ok()
+                .map(|port| port.parse().expect("invalid INBOUND_IMAP_PORT found"))
+                .unwrap_or(993),
+            user: env::var("INBOUND_IMAP_USER")
+                .expect("INBOUND_IMAP_USER environment variable not supplied"),
+            password: env::var("INBOUND_IMAP_PASSWORD")
+                .expect("INBOUND_IMAP_PASSWORD environment variable not supplied"),
+            mailbox: env::var("INBOUND_IMAP_MAILBOX").unwrap_or_else(|_| "INBOX".to_owned()),
+        })
+    }
+
+    /// Ingests the unseen emails of the mailbox, flagging them as seen once ingested.
+    ///
+    /// Returns the number of ingested emails. If the ingestion of an email fails, it stays unseen,
+    /// to be retried later.
+    pub fn fetch<F>(&self, ingest: &mut F) -> io::Result<usize>
+    where
+        F: FnMut(&[u8]) -> io::Result<()>,
+    {
+        let tls = native_tls::TlsConnector::new().map_err(into_io_err)?;
+        let client = imap::connect((self.host.as_str(), self.port), &self.host, &tls)
+            .map_err(into_io_err)?;
+        let mut session = client
+            .login(&self.user, &self.password)
+            .map_err(|(e, _client)| into_io_err(e))?;
+        let _ = session.select(&self.mailbox).map_err(into_io_err)?;
+
+        let mut uids = session
+            .uid_search("UNSEEN")
+            .map_err(into_io_err)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        uids.sort_unstable();
+
+        let mut count = 0;
+        for uid in uids {
+            // Peeking does not flag the email as seen, in case its ingestion fails
+            let messages = session
+                .uid_fetch(uid.to_string(), "BODY.PEEK[]")
+                .map_err(into_io_err)?;
+            for message in messages.iter() {
+                if let Some(raw) = message.body() {
+                    ingest(raw)?;
+                    count += 1;
+                }
+            }
+
+            let _ = session
+                .uid_store(uid.to_string(), "+FLAGS (\\Seen)")
+                .map_err(into_io_err)?;
+        }
+
+        session.logout().map_err(into_io_err)?;
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,34 @@
+//! Fetching of emails from a local maildir.
+
+use std::{fs, io, path::Path};
+
+/// Ingests the new emails of a maildir, moving them to its `cur` directory once ingested.
+///
+/// Returns the number of ingested emails. If the ingestion of an email fails, it stays in the
+/// `new` directory, to be retried later.
+pub fn fetch<F>(dir: &Path, ingest: &mut F) -> io::Result<usize>
+where
+    F: FnMut(&[u8]) -> io::Result<()>,
+{
+    let (new_dir, cur_dir) = (dir.join("new"), dir.join("cur"));
+    fs::create_dir_all(&cur_dir)?;
+
+    let mut entries = fs::read_dir(&new_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .collect::<Vec<_>>();
+    // Maildir file names start with the delivery timestamp
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in &entries {
+        let raw = fs::read(entry.path())?;
+        ingest(&raw)?;
+
+        // Ingested emails are flagged as seen
+        let mut name = entry.file_name();
+        name.push(":2,S");
+        fs::rename(entry.path(), cur_dir.join(name))?;
+    }
+
+    Ok(entries.len())
+}
@@ -0,0 +1,408 @@
+//! Inbound email-to-ticket ingestion.
+//!
+//! Emails are received from an IMAP mailbox, a local maildir or an HTTP endpoint fed by the mail
+//! server. Replies to the emails of a ticket, found through their `In-Reply-To` and `References`
+//! headers or the ticket tag in their subject, are added as comments to the ticket. Any other email
+//! opens a new ticket.
+//!
+//! Auto-replies, emails sent by the application itself and floods from a single sender are ignored,
+//! to avoid mail loops. Every received email is recorded in the inbound email log, which is also
+//! used to discard duplicates.
+
+use crate::{
+    automation::{self, Effect},
+    db::{self, model, tenant::Viewer},
+    notification::{centre, email},
+    worker,
+};
+use chrono::{Duration, Utc};
+use common::{
+    inbound::Outcome,
+    ticket::{CommentSource, Priority},
+};
+use diesel::PgConnection;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rocket::fairing::Fairing;
+use std::{env, io, path::PathBuf, sync::atomic::AtomicBool, time};
+use uuid::Uuid;
+
+mod imap;
+mod maildir;
+pub mod parse;
+
+#[cfg(test)]
+mod tests;
+
+/// Token that the mail server must send in the `X-Inbound-Token` header to post emails to the
+/// HTTP endpoint, if set in the `INBOUND_EMAIL_TOKEN` environment variable.
+pub static INBOUND_EMAIL_TOKEN: Lazy<Option<String>> = Lazy::new(|| {
+    env::var("INBOUND_EMAIL_TOKEN")
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+});
+
+/// Maildir to fetch emails from, if set in the `INBOUND_MAILDIR` environment variable.
+static INBOUND_MAILDIR: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    env::var("INBOUND_MAILDIR")
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from)
+});
+
+/// IMAP mailbox to fetch emails from, if the `INBOUND_IMAP_HOST` environment variable is set.
+static INBOUND_IMAP: Lazy<Option<imap::Config>> = Lazy::new(imap::Config::from_env);
+
+/// Subject tag of the emails of a ticket: `[#<number>]`.
+static SUBJECT_TAG: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[#(\d+)\]").expect("could not compile the subject tag regular expression")
+});
+
+/// Maximum number of emails accepted from a single sender in [`RATE_LIMIT_WINDOW_MINUTES`].
+pub const RATE_LIMIT: i64 = 20;
+
+/// Window of the rate limit of emails from a single sender, in minutes.
+pub const RATE_LIMIT_WINDOW_MINUTES: i64 = 10;
+
+/// Title of the tickets created from emails without a subject.
+const NO_SUBJECT_TITLE: &str = "(no subject)";
+
+/// Interval between checks of the maildir and the IMAP mailbox.
+const POLL_INTERVAL: time::Duration = time::Duration::from_secs(30);
+
+/// Whether the inbound email worker has been started.
+static WORKER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Result of the ingestion of an email.
+#[derive(Debug)]
+pub struct Ingested {
+    /// The entry of the inbound email log, or `None` if the email had already been ingested.
+    pub entry: Option<model::InboundEmail>,
+    /// The side effects of the automation rules run for new tickets, to be dispatched.
+    pub effects: Vec<Effect>,
+}
+
+/// Ingests a raw RFC 822 email.
+pub fn ingest(conn: &mut PgConnection, raw: &[u8]) -> io::Result<Ingested> {
+    let parsed = parse::parse(raw);
+
+    if let Some(message_id) = parsed
+        .as_ref()
+        .and_then(|email| email.message_id.as_deref())
+    {
+        if db::inbound::get_with_message_id(conn, message_id)?.is_some() {
+            return Ok(Ingested {
+                entry: None,
+                effects: Vec::new(),
+            });
+        }
+    }
+
+    let email = match parsed {
+        Some(email) => email,
+        None => {
+            let entry = log(conn, None, "", "", Entry::rejected("invalid email"))?;
+            return Ok(Ingested {
+                entry: Some(entry),
+                effects: Vec::new(),
+            });
+        }
+    };
+
+    let (entry, effects) = match decide(conn, &email)? {
+        Decision::Accepted(sender) => match &sender.ticket {
+            Some(ticket) => (add_comment(conn, &email, &sender.user, ticket)?, Vec::new()),
+            None => {
+                let (ticket, effects) = open_ticket(conn, &email, &sender.user, &sender.viewer)?;
+                (Entry::done(Outcome::Created, ticket.id, None), effects)
+            }
+        },
+        Decision::Skipped(entry) => (entry, Vec::new()),
+    };
+
+    let entry = log(
+        conn,
+        email.message_id.as_deref(),
+        &email.from,
+        &email.subject,
+        entry,
+    )?;
+
+    Ok(Ingested {
+        entry: Some(entry),
+        effects,
+    })
+}
+
+/// Decision about what to do with an email.
+#[derive(Debug)]
+enum Decision {
+    /// The email must be added as a comment to the ticket, or open a new ticket if there is none.
+    Accepted(Box<Sender>),
+    /// The email must be ignored or rejected.
+    Skipped(Entry),
+}
+
+/// Known sender of an accepted email.
+#[derive(Debug)]
+struct Sender {
+    user: model::User,
+    viewer: Viewer,
+    /// The ticket the email replies to, if any.
+    ticket: Option<model::Ticket>,
+}
+
+/// Outcome of the ingestion of an email, to be recorded in the inbound email log.
+#[derive(Debug)]
+struct Entry {
+    outcome: Outcome,
+    reason: Option<&'static str>,
+    ticket_id: Option<Uuid>,
+    comment_id: Option<Uuid>,
+}
+
+impl Entry {
+    fn done(outcome: Outcome, ticket_id: Uuid, comment_id: Option<Uuid>) -> Self {
+        Self {
+            outcome,
+            reason: None,
+            ticket_id: Some(ticket_id),
+            comment_id,
+        }
+    }
+
+    fn ignored(reason: &'static str) -> Self {
+        Self {
+            outcome: Outcome::Ignored,
+            reason: Some(reason),
+            ticket_id: None,
+            comment_id: None,
+        }
+    }
+
+    fn rejected(reason: &'static str) -> Self {
+        Self {
+            outcome: Outcome::Rejected,
+            reason: Some(reason),
+            ticket_id: None,
+            comment_id: None,
+        }
+    }
+}
+
+/// Decides what to do with an email: whether to ignore it or reject it, and otherwise, which
+/// ticket it belongs to.
+fn decide(conn: &mut PgConnection, email: &parse::Email) -> io::Result<Decision> {
+    if email.auto_submitted {
+        return Ok(Decision::Skipped(Entry::ignored("auto-reply")));
+    }
+
+    let own_message = email
+        .message_id
+        .as_deref()
+        .and_then(email::thread_ticket_id)
+        .is_some();
+    if own_message || email::from_address().as_deref() == Some(email.from.as_str()) {
+        return Ok(Decision::Skipped(Entry::ignored("sent by the application")));
+    }
+
+    let since = Utc::now() - Duration::minutes(RATE_LIMIT_WINDOW_MINUTES);
+    if db::inbound::count_from_address_since(conn, &email.from, since)? >= RATE_LIMIT {
+        return Ok(Decision::Skipped(Entry::ignored(
+            "too many emails from the sender",
+        )));
+    }
+
+    let user = match db::user::get_with_email(conn, &email.from)? {
+        Some(user) => user,
+        None => return Ok(Decision::Skipped(Entry::rejected("unknown sender"))),
+    };
+    let viewer = Viewer {
+        user_id: user.id,
+        role: user.role(),
+        organisation_ids: db::organisation::get_user_organisation_ids(conn, user.id)?,
+    };
+    let ticket = find_ticket(conn, &viewer, email)?;
+
+    Ok(Decision::Accepted(Box::new(Sender {
+        user,
+        viewer,
+        ticket,
+    })))
+}
+
+/// Finds the ticket an email replies to, if it is visible to the sender.
+///
+/// Replies to merged tickets go to the ticket they were merged into.
+fn find_ticket(
+    conn: &mut PgConnection,
+    viewer: &Viewer,
+    email: &parse::Email,
+) -> io::Result<Option<model::Ticket>> {
+    let mut ticket_ids = email
+        .references
+        .iter()
+        .filter_map(|message_id| email::thread_ticket_id(message_id))
+        .collect::<Vec<_>>();
+    if ticket_ids.is_empty() && !email.references.is_empty() {
+        ticket_ids.extend(db::inbound::get_ticket_id_with_message_ids(
+            conn,
+            &email.references,
+        )?);
+    }
+
+    for ticket_id in ticket_ids {
+        if let Some(ticket) = db::ticket::get_with_id(conn, viewer, ticket_id)? {
+            return db::relation::get_survivor(conn, viewer, ticket).map(Some);
+        }
+    }
+
+    let ticket = match subject_tag(&email.subject) {
+        Some(number) => db::ticket::get_with_number(conn, viewer, number)?,
+        None => None,
+    };
+    ticket
+        .map(|ticket| db::relation::get_survivor(conn, viewer, ticket))
+        .transpose()
+}
+
+/// Gets the ticket number of the tag in a subject, if any.
+fn subject_tag(subject: &str) -> Option<i64> {
+    SUBJECT_TAG.captures(subject)?.get(1)?.as_str().parse().ok()
+}
+
+/// Adds an email as a comment to its ticket.
+fn add_comment(
+    conn: &mut PgConnection,
+    email: &parse::Email,
+    user: &model::User,
+    ticket: &model::Ticket,
+) -> io::Result<Entry> {
+    if email.body.is_empty() && email.attachments.is_empty() {
+        return Ok(Entry::ignored("empty reply"));
+    }
+
+    let comment = db::ticket::insert_comment(
+        conn,
+        &model::NewTicketComment {
+            ticket_id: ticket.id,
+            author_id: user.id,
+            body: &email.body,
+            source: CommentSource::Email.as_str(),
+        },
+    )?;
+    store_attachments(conn, email, ticket.id, Some(comment.id))?;
+    centre::ticket_commented(conn, ticket, user.id, &comment.body)?;
+
+    Ok(Entry::done(Outcome::Commented, ticket.id, Some(comment.id)))
+}
+
+/// Opens a new ticket with an email, following the same steps as tickets opened in the
+/// application.
+fn open_ticket(
+    conn: &mut PgConnection,
+    email: &parse::Email,
+    user: &model::User,
+    viewer: &Viewer,
+) -> io::Result<(model::Ticket, Vec<Effect>)> {
+    let queue_id = db::queue::get_default(conn)?.map(|queue| queue.id);
+    let title = if email.title.is_empty() {
+        NO_SUBJECT_TITLE
+    } else {
+        &email.title
+    };
+    let ticket = db::ticket::insert(
+        conn,
+        viewer,
+        &model::NewTicket {
+            title,
+            description: &email.body,
+            priority: Priority::Normal.as_str(),
+            requester_id: user.id,
+            organisation_id: viewer.default_organisation_id(),
+            queue_id,
+            category: None,
+            custom_fields: None,
+        },
+    )?;
+    store_attachments(conn, email, ticket.id, None)?;
+
+    crate::ticket::opened(conn, user.id, ticket)
+}
+
+/// Stores the attachments of an email in a ticket.
+fn store_attachments(
+    conn: &mut PgConnection,
+    email: &parse::Email,
+    ticket_id: Uuid,
+    comment_id: Option<Uuid>,
+) -> io::Result<()> {
+    if email.attachments.is_empty() {
+        return Ok(());
+    }
+
+    let attachments = email
+        .attachments
+        .iter()
+        .map(|attachment| model::NewTicketAttachment {
+            ticket_id,
+            comment_id,
+            filename: &attachment.filename,
+            content_type: &attachment.content_type,
+            size: attachment.data.len() as i32,
+            data: &attachment.data,
+        })
+        .collect::<Vec<_>>();
+
+    db::ticket::insert_attachments(conn, &attachments)
+}
+
+/// Records an email in the inbound email log.
+fn log(
+    conn: &mut PgConnection,
+    message_id: Option<&str>,
+    from_address: &str,
+    subject: &str,
+    entry: Entry,
+) -> io::Result<model::InboundEmail> {
+    db::inbound::insert(
+        conn,
+        &model::NewInboundEmail {
+            message_id,
+            from_address,
+            subject,
+            outcome: entry.outcome.as_str(),
+            reason: entry.reason,
+            ticket_id: entry.ticket_id,
+            comment_id: entry.comment_id,
+        },
+    )
+}
+
+/// Fairing starting the worker that fetches emails from the maildir and the IMAP mailbox, if
+/// configured.
+pub fn worker() -> impl Fairing {
+    worker::fairing(
+        "Inbound email worker",
+        &WORKER_STARTED,
+        POLL_INTERVAL,
+        fetch,
+    )
+}
+
+/// Fetches and ingests new emails from the maildir and the IMAP mailbox.
+fn fetch(conn: &mut PgConnection) -> io::Result<bool> {
+    let mut ingest_and_dispatch = |raw: &[u8]| {
+        let ingested = ingest(conn, raw)?;
+        automation::dispatch_blocking(conn, ingested.effects)
+    };
+
+    if let Some(dir) = &*INBOUND_MAILDIR {
+        let _ = maildir::fetch(dir, &mut ingest_and_dispatch)?;
+    }
+    if let Some(config) = &*INBOUND_IMAP {
+        let _ = config.fetch(&mut ingest_and_dispatch)?;
+    }
+
+    Ok(false)
+}
@@ -0,0 +1,143 @@
+//! Parsing of RFC 822 email messages.
+
+use mail_parser::{HeaderValue, MessageParser, MimeHeaders};
+
+/// Maximum length of the title of tickets created from emails.
+const MAX_TITLE_LEN: usize = 200;
+
+/// Email message, as relevant for ticket ingestion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Email {
+    /// The `Message-ID` of the email, without angle brackets, if it has one.
+    pub message_id: Option<String>,
+    /// The lowercase address of the sender.
+    pub from: String,
+    /// The subject of the email.
+    pub subject: String,
+    /// The subject without reply and forward prefixes, to be used as a ticket title.
+    pub title: String,
+    /// The `Message-ID`s of the `In-Reply-To` and `References` headers, without angle brackets.
+    pub references: Vec<String>,
+    /// The text body of the email, without quoted replies and signatures.
+    pub body: String,
+    /// The attachments of the email.
+    pub attachments: Vec<Attachment>,
+    /// Whether the email was sent automatically, such as out of office replies and bounces.
+    pub auto_submitted: bool,
+}
+
+/// Attachment of an email.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Parses an RFC 822 message.
+///
+/// Returns `None` if the message is not valid, or if it has no sender.
+pub fn parse(raw: &[u8]) -> Option<Email> {
+    let message = MessageParser::default().parse(raw)?;
+
+    let from = message
+        .from()
+        .and_then(|from| from.first())
+        .and_then(|from| from.address())?
+        .trim()
+        .to_lowercase();
+    let subject = message.subject().unwrap_or_default().trim().to_owned();
+    let title = message
+        .thread_name()
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_TITLE_LEN)
+        .collect();
+
+    let mut references = Vec::new();
+    for header in [message.in_reply_to(), message.references()] {
+        for id in header.as_text_list().unwrap_or_default() {
+            let id = id.trim().trim_start_matches('<').trim_end_matches('>');
+            if !id.is_empty() && !references.iter().any(|r| r == id) {
+                references.push(id.to_owned());
+            }
+        }
+    }
+
+    let body = message
+        .body_text(0)
+        .map(|body| strip_quoted(&body))
+        .unwrap_or_default();
+
+    let attachments = message
+        .attachments()
+        .map(|part| Attachment {
+            filename: part.attachment_name().unwrap_or("attachment").to_owned(),
+            content_type: part
+                .content_type()
+                .map(|ct| match &ct.c_subtype {
+                    Some(subtype) => format!("{}/{}", ct.c_type, subtype),
+                    None => ct.c_type.to_string(),
+                })
+                .unwrap_or_else(|| "application/octet-stream".to_owned()),
+            data: part.contents().to_vec(),
+        })
+        .collect();
+
+    let header_text = |name: &str| {
+        message
+            .header(name)
+            .and_then(HeaderValue::as_text)
+            .map(|value| value.trim().to_lowercase())
+    };
+    // See RFC 3834, and the non-standard headers used by common auto-responders
+    let auto_submitted = matches!(header_text("Auto-Submitted"), Some(value) if value != "no")
+        || matches!(
+            header_text("Precedence").as_deref(),
+            Some("bulk" | "junk" | "list" | "auto_reply")
+        )
+        || message.header("X-Autoreply").is_some()
+        || message.header("X-Autorespond").is_some()
+        || message.header_raw("Return-Path").map(str::trim) == Some("<>");
+
+    Some(Email {
+        message_id: message.message_id().map(ToOwned::to_owned),
+        from,
+        subject,
+        title,
+        references,
+        body,
+        attachments,
+        auto_submitted,
+    })
+}
+
+/// Strips quoted replies and signatures from a text body.
+///
+/// Everything after the usual reply headers of mail clients (such as `On ... wrote:`) or the
+/// signature delimiter is removed, along with lines quoted with `>`.
+pub fn strip_quoted(body: &str) -> String {
+    let mut lines = Vec::new();
+
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if is_reply_header(trimmed) || line.trim_end() == "--" {
+            break;
+        }
+        if trimmed.starts_with('>') {
+            continue;
+        }
+        lines.push(line.trim_end());
+    }
+
+    lines.join("\n").trim().to_owned()
+}
+
+/// Checks if a line is the header of a quoted reply.
+fn is_reply_header(line: &str) -> bool {
+    (line.starts_with("On ") && line.ends_with("wrote:"))
+        || (line.starts_with("El ") && line.ends_with("escribió:"))
+        || (line.starts_with("-----") && line.ends_with("-----") && line.contains("Original"))
+        || line.starts_with("________________________________")
+}
@@ -0,0 +1,319 @@
+use super::*;
+use crate::{db::establish_connection, rand_code};
+use diesel::Connection;
+use std::fs;
+
+/// Builds a raw email from carol, with a unique `Message-ID`.
+fn raw_email(message_id: &str, headers: &str, subject: &str, body: &str) -> Vec<u8> {
+    format!(
+        "From: Carol Jones <Carol@acme.test>\r\n\
+         To: support@mysupport.test\r\n\
+         Subject: {}\r\n\
+         Message-ID: <{}>\r\n\
+         {}\
+         Content-Type: text/plain; charset=utf-8\r\n\
+         \r\n\
+         {}",
+        subject, message_id, headers, body
+    )
+    .into_bytes()
+}
+
+/// Generates a unique `Message-ID`.
+fn message_id() -> String {
+    format!("{}@mail.acme.test", rand_code(16))
+}
+
+/// Ingests an email, expecting it to be logged.
+fn ingest_logged(conn: &mut PgConnection, raw: &[u8]) -> model::InboundEmail {
+    ingest(conn, raw)
+        .expect("error ingesting email")
+        .entry
+        .expect("email was not logged")
+}
+
+/// Sunny day unit test for the `parse()` function.
+#[test]
+fn ut_sunny_parse() {
+    let raw = "From: Carol Jones <Carol@Acme.test>\r\n\
+               Subject: Re: [#12] Printer on fire\r\n\
+               Message-ID: <abc@mail.acme.test>\r\n\
+               In-Reply-To: <ticket-1@mysupport.test>\r\n\
+               References: <root@mysupport.test> <ticket-1@mysupport.test>\r\n\
+               MIME-Version: 1.0\r\n\
+               Content-Type: multipart/mixed; boundary=\"sep\"\r\n\
+               \r\n\
+               --sep\r\n\
+               Content-Type: text/plain\r\n\
+               \r\n\
+               It is still burning.\r\n\
+               \r\n\
+               On Mon, 2 May 2022, Support <support@mysupport.test> wrote:\r\n\
+               > Have you tried turning it off?\r\n\
+               --sep\r\n\
+               Content-Type: image/png\r\n\
+               Content-Disposition: attachment; filename=\"fire.png\"\r\n\
+               Content-Transfer-Encoding: base64\r\n\
+               \r\n\
+               iVBORw0K\r\n\
+               --sep--\r\n";
+
+    let email = parse::parse(raw.as_bytes()).expect("email was not parsed");
+    assert_eq!(email.message_id.as_deref(), Some("abc@mail.acme.test"));
+    assert_eq!(email.from, "carol@acme.test");
+    assert_eq!(email.subject, "Re: [#12] Printer on fire");
+    assert_eq!(email.title, "Printer on fire");
+    assert_eq!(
+        email.references,
+        vec!["ticket-1@mysupport.test", "root@mysupport.test"]
+    );
+    assert_eq!(email.body, "It is still burning.");
+    assert_eq!(email.attachments.len(), 1);
+    assert_eq!(email.attachments[0].filename, "fire.png");
+    assert_eq!(email.attachments[0].content_type, "image/png");
+    assert_eq!(email.attachments[0].data, b"\x89PNG\r\n");
+    assert!(!email.auto_submitted);
+}
+
+/// Sunny day unit test for the detection of auto-replies.
+#[test]
+fn ut_sunny_parse_auto_submitted() {
+    for header in [
+        "Auto-Submitted: auto-replied\r\n",
+        "Precedence: bulk\r\n",
+        "X-Autoreply: yes\r\n",
+        "Return-Path: <>\r\n",
+    ] {
+        let email = parse::parse(&raw_email("a@b", header, "Out of office", "Away"))
+            .expect("email was not parsed");
+        assert!(email.auto_submitted, "{:?} was not an auto-reply", header);
+    }
+
+    let email = parse::parse(&raw_email("a@b", "Auto-Submitted: no\r\n", "Hi", "Hello"))
+        .expect("email was not parsed");
+    assert!(!email.auto_submitted);
+}
+
+/// Rainy day unit test for the `parse()` function.
+#[test]
+fn ut_rainy_parse() {
+    assert!(parse::parse(b"Subject: no sender\r\n\r\nHello").is_none());
+}
+
+/// Sunny day unit test for the `strip_quoted()` function.
+#[test]
+fn ut_sunny_strip_quoted() {
+    assert_eq!(
+        parse::strip_quoted("Thanks!\n> quoted\nBye\n\n-- \nCarol\nAcme"),
+        "Thanks!\nBye"
+    );
+    assert_eq!(
+        parse::strip_quoted("Fixed.\n\n-----Original Message-----\nFrom: Support"),
+        "Fixed."
+    );
+    assert_eq!(
+        parse::strip_quoted("Done\n________________________________\nFrom: Support"),
+        "Done"
+    );
+}
+
+/// Sunny day unit test for the ingestion of new threads and replies.
+#[test]
+fn ut_sunny_ingest() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let first_id = message_id();
+    let created = ingest_logged(
+        &mut conn,
+        &raw_email(
+            &first_id,
+            "",
+            "UT inbound printer",
+            "The printer is on fire",
+        ),
+    );
+    assert_eq!(created.outcome, Outcome::Created.as_str());
+    let ticket_id = created.ticket_id.expect("ticket was not created");
+    let ticket = db::ticket::get_with_id(&mut conn, &Viewer::system(), ticket_id)
+        .expect("error retrieving ticket")
+        .expect("ticket was not stored");
+    assert_eq!(ticket.title, "UT inbound printer");
+    assert_eq!(ticket.description, "The printer is on fire");
+    assert!(ticket.organisation_id.is_some(), "Acme was not set");
+
+    // Replies to the email of the customer
+    let reply = ingest_logged(
+        &mut conn,
+        &raw_email(
+            &message_id(),
+            &format!("In-Reply-To: <{}>\r\n", first_id),
+            "Re: UT inbound printer",
+            "It is still burning\n> The printer is on fire",
+        ),
+    );
+    assert_eq!(reply.outcome, Outcome::Commented.as_str());
+    assert_eq!(reply.ticket_id, Some(ticket_id));
+
+    // Replies to the emails of the application
+    let reply = ingest_logged(
+        &mut conn,
+        &raw_email(
+            &message_id(),
+            &format!("References: <ticket-{}@mysupport.test>\r\n", ticket_id),
+            "Re: notification",
+            "Any news?",
+        ),
+    );
+    assert_eq!(reply.ticket_id, Some(ticket_id));
+
+    // Replies with the ticket tag
+    let reply = ingest_logged(
+        &mut conn,
+        &raw_email(
+            &message_id(),
+            "",
+            &format!("Re: [#{}] UT inbound printer", ticket.number),
+            "Fixed",
+        ),
+    );
+    assert_eq!(reply.ticket_id, Some(ticket_id));
+
+    let comments =
+        db::ticket::get_comments(&mut conn, ticket_id).expect("error retrieving comments");
+    assert_eq!(
+        comments
+            .iter()
+            .map(|comment| comment.body.as_str())
+            .collect::<Vec<_>>(),
+        vec!["It is still burning", "Any news?", "Fixed"]
+    );
+    assert!(comments
+        .iter()
+        .all(|comment| comment.source() == CommentSource::Email));
+}
+
+/// Sunny day unit test for the detection of duplicates and loops.
+#[test]
+fn ut_sunny_ingest_ignored() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let id = message_id();
+    let raw = raw_email(
+        &id,
+        "Auto-Submitted: auto-replied\r\n",
+        "Out of office",
+        "Away",
+    );
+    let entry = ingest_logged(&mut conn, &raw);
+    assert_eq!(entry.outcome, Outcome::Ignored.as_str());
+    assert_eq!(entry.reason.as_deref(), Some("auto-reply"));
+    assert!(entry.ticket_id.is_none());
+
+    assert!(
+        ingest(&mut conn, &raw)
+            .expect("error ingesting email")
+            .entry
+            .is_none(),
+        "duplicate was ingested"
+    );
+
+    let own = raw_email(
+        &format!("ticket-{}.abc@mysupport.test", Uuid::new_v4()),
+        "",
+        "Bounced",
+        "Bounced",
+    );
+    let entry = ingest_logged(&mut conn, &own);
+    assert_eq!(entry.reason.as_deref(), Some("sent by the application"));
+}
+
+/// Rainy day unit test for the ingestion of emails.
+#[test]
+fn ut_rainy_ingest() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let entry = ingest_logged(&mut conn, b"not an email");
+    assert_eq!(entry.outcome, Outcome::Rejected.as_str());
+
+    let unknown = format!(
+        "From: mallory@evil.test\r\nMessage-ID: <{}>\r\nSubject: Hi\r\n\r\nHello",
+        message_id()
+    );
+    let entry = ingest_logged(&mut conn, unknown.as_bytes());
+    assert_eq!(entry.outcome, Outcome::Rejected.as_str());
+    assert_eq!(entry.reason.as_deref(), Some("unknown sender"));
+
+    // Tickets of other organisations can't be commented by tag
+    let dave = db::user::get_with_username(&mut conn, "dave")
+        .expect("error retrieving user from database")
+        .expect("Dave was not in the database");
+    let ticket = db::ticket::insert(
+        &mut conn,
+        &Viewer::system(),
+        &model::NewTicket {
+            title: "UT inbound Globex",
+            description: "",
+            priority: "low",
+            requester_id: dave.id,
+            organisation_id: None,
+            queue_id: None,
+            category: None,
+            custom_fields: None,
+        },
+    )
+    .expect("error inserting ticket");
+    let entry = ingest_logged(
+        &mut conn,
+        &raw_email(
+            &message_id(),
+            &format!("References: <ticket-{}@mysupport.test>\r\n", ticket.id),
+            &format!("Re: [#{}] UT inbound Globex", ticket.number),
+            "Let me in",
+        ),
+    );
+    assert_eq!(entry.outcome, Outcome::Created.as_str());
+    assert_ne!(entry.ticket_id, Some(ticket.id));
+
+    // Floods from a single sender are ignored
+    for _ in 0..RATE_LIMIT {
+        let _ = ingest_logged(&mut conn, &raw_email(&message_id(), "", "Spam", "Spam"));
+    }
+    let entry = ingest_logged(&mut conn, &raw_email(&message_id(), "", "Spam", "Spam"));
+    assert_eq!(
+        entry.reason.as_deref(),
+        Some("too many emails from the sender")
+    );
+}
+
+/// Sunny day unit test for the maildir fetcher.
+#[test]
+fn ut_sunny_maildir() {
+    let dir = env::temp_dir().join(format!("mysupport-ut-{}", rand_code(8)));
+    fs::create_dir_all(dir.join("new")).expect("error creating maildir");
+    fs::write(dir.join("new").join("1.mail"), b"first").expect("error writing email");
+    fs::write(dir.join("new").join("2.mail"), b"second").expect("error writing email");
+
+    let mut received = Vec::new();
+    let count = maildir::fetch(&dir, &mut |raw: &[u8]| {
+        received.push(raw.to_vec());
+        Ok(())
+    })
+    .expect("error fetching maildir");
+    assert_eq!(count, 2);
+    assert_eq!(received, vec![b"first".to_vec(), b"second".to_vec()]);
+    assert!(dir.join("cur").join("1.mail:2,S").exists());
+    assert_eq!(
+        fs::read_dir(dir.join("new"))
+            .expect("error reading maildir")
+            .count(),
+        0
+    );
+
+    fs::remove_dir_all(&dir).expect("error removing maildir");
+}
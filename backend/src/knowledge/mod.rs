@@ -0,0 +1,40 @@
+//! Knowledge base.
+//!
+//! Articles are written in Markdown. They are [rendered](render) to HTML on the server, and the
+//! result is sanitised, since article bodies could contain raw HTML, such as scripts or event
+//! handlers. Revisions are compared line by line with [`diff()`].
+
+use common::article::{Change, DiffLineDTO};
+use pulldown_cmark::{html, Options, Parser};
+use similar::{ChangeTag, TextDiff};
+
+#[cfg(test)]
+mod tests;
+
+/// Renders a Markdown text to sanitised HTML.
+pub fn render(markdown: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut unsafe_html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options));
+
+    ammonia::clean(&unsafe_html)
+}
+
+/// Compares two texts line by line.
+pub fn diff(old: &str, new: &str) -> Vec<DiffLineDTO> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLineDTO {
+            change: match change.tag() {
+                ChangeTag::Equal => Change::Equal,
+                ChangeTag::Insert => Change::Insert,
+                ChangeTag::Delete => Change::Delete,
+            },
+            text: change
+                .value()
+                .trim_end_matches(&['\r', '\n'][..])
+                .to_owned(),
+        })
+        .collect()
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+/// Markdown is rendered to HTML.
+#[test]
+fn ut_sunny_render() {
+    assert_eq!(
+        render("# Printers\n\nTurn it **off** and [on](https://example.com) again."),
+        "<h1>Printers</h1>\n<p>Turn it <strong>off</strong> and \
+         <a href=\"https://example.com\" rel=\"noopener noreferrer\">on</a> again.</p>\n"
+    );
+    assert!(render("| a | b |\n|---|---|\n| 1 | 2 |").contains("<td>1</td>"));
+}
+
+/// Dangerous HTML is removed from the rendered articles.
+#[test]
+fn ut_rainy_render() {
+    let html = render(
+        "<script>alert(1)</script>\n\n<img src=\"x.png\" onerror=\"alert(2)\">\n\n\
+         [link](javascript:alert(3))",
+    );
+
+    assert!(!html.contains("script"), "{}", html);
+    assert!(!html.contains("onerror"), "{}", html);
+    assert!(!html.contains("javascript"), "{}", html);
+    assert!(html.contains("<img src=\"x.png\">"), "{}", html);
+}
+
+/// Texts are compared line by line.
+#[test]
+fn ut_sunny_diff() {
+    let line = |change, text: &str| DiffLineDTO {
+        change,
+        text: text.to_owned(),
+    };
+
+    assert_eq!(
+        diff("Restart it.\nWait.\n", "Unplug it.\nWait.\nCall us."),
+        vec![
+            line(Change::Delete, "Restart it."),
+            line(Change::Insert, "Unplug it."),
+            line(Change::Equal, "Wait."),
+            line(Change::Insert, "Call us."),
+        ]
+    );
+    assert!(diff("", "").is_empty());
+}
@@ -2,27 +2,62 @@
 //!
 //! This crate defines the API, database glue and frontend glue of the MySupport application.
 
+// The `ticket` table has too many documented columns for the default limit of Diesel's macros
+#![recursion_limit = "256"]
+
 mod api;
+mod approval;
+mod audit;
+mod automation;
+mod csv;
 mod db;
 mod frontend;
+mod ical;
+mod inbound;
+mod knowledge;
 mod notification;
+mod problem;
+mod survey;
+mod ticket;
+mod webhook;
+mod worker;
+mod xlsx;
 
 #[macro_use]
 extern crate diesel;
 
 use once_cell::sync::Lazy;
-use rocket::{Build, Rocket};
+use rand::{distributions, thread_rng, Rng};
+use rocket::{
+    figment::{providers::Env, Figment},
+    Build, Config, Rocket,
+};
 use std::{env, error::Error, io};
 
 static BASE_URL: Lazy<String> =
     Lazy::new(|| env::var("BASE_URL").unwrap_or_else(|_| "http://localhost:8000".to_owned()));
 
 /// Initializes the rocket.
+///
+/// Besides Rocket's own configuration sources, the `OPEN_REGISTRATION` environment variable sets
+/// whether users can register without an invitation.
 pub fn initialize() -> Rocket<Build> {
-    rocket::build()
+    initialize_with(Config::figment().merge(Env::raw().only(&["open_registration"])))
+}
+
+/// Initializes the rocket with the given configuration.
+pub fn initialize_with(figment: Figment) -> Rocket<Build> {
+    rocket::custom(figment)
         .mount("/", frontend::routes())
         .mount("/api/v1", api::routes())
+        .attach(api::config())
         .attach(db::Connection::fairing())
+        .attach(webhook::worker())
+        .attach(inbound::worker())
+        .attach(notification::centre::listener())
+        .attach(notification::activity::listener())
+        .attach(notification::mailing::worker())
+        .attach(survey::worker())
 }
 
 /// Converts any error into an I/O error.
@@ -32,3 +67,14 @@ where
 {
     io::Error::new(io::ErrorKind::Other, err)
 }
+
+/// Creates a random alphanumeric code of the given length.
+fn rand_code(len: usize) -> String {
+    let vec = thread_rng()
+        .sample_iter(distributions::Alphanumeric)
+        .take(len)
+        .collect::<Vec<u8>>();
+
+    // We know that the code is ASCII
+    String::from_utf8(vec).expect("invalid code generated")
+}
@@ -0,0 +1,22 @@
+//! Live ticket activity.
+//!
+//! The database pushes the tickets created, changed or commented on to the `ticket_activity`
+//! [channel](super::channel), which is broadcast to the event streams of the connected agents.
+
+use super::channel::Channel;
+use common::dashboard::TicketActivityDTO;
+use rocket::{fairing::Fairing, tokio::sync::broadcast};
+
+/// Database channel ticket activity is pushed to.
+static CHANNEL: Channel<TicketActivityDTO> =
+    Channel::new("ticket_activity", "Ticket activity listener");
+
+/// Subscribes to the activity of all tickets.
+pub fn subscribe() -> broadcast::Receiver<TicketActivityDTO> {
+    CHANNEL.subscribe()
+}
+
+/// Fairing starting the listener of ticket activity once the application launches.
+pub fn listener() -> impl Fairing {
+    CHANNEL.listener()
+}
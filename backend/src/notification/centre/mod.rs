@@ -0,0 +1,159 @@
+//! In-app notification centre.
+//!
+//! Notifications are stored in the database, which pushes the new ones on the `notification`
+//! [channel](super::channel), which is broadcast to the event streams of the connected users.
+
+use super::channel::Channel;
+use crate::db::{self, model};
+use common::notification::{Delivery, Kind};
+use diesel::PgConnection;
+use rocket::{fairing::Fairing, tokio::sync::broadcast};
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum length of the title of notifications.
+const MAX_TITLE_LEN: usize = 200;
+
+/// Maximum length of the body of notifications.
+const MAX_BODY_LEN: usize = 1000;
+
+/// Database channel new notifications are pushed to.
+static CHANNEL: Channel<model::Notification> =
+    Channel::new("notification", "Notification listener");
+
+/// Subscribes to the new notifications of all users.
+pub fn subscribe() -> broadcast::Receiver<model::Notification> {
+    CHANNEL.subscribe()
+}
+
+/// Notifies the given users.
+///
+/// Duplicated users are notified once, according to their preferences for the kind of notification.
+/// The title and body are truncated if they are too long.
+pub fn notify(
+    conn: &mut PgConnection,
+    user_ids: &[Uuid],
+    kind: Kind,
+    title: &str,
+    body: &str,
+    ticket_id: Option<Uuid>,
+) -> io::Result<()> {
+    let mut user_ids = user_ids.to_vec();
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    let deliveries = db::notification::get_deliveries(conn, &user_ids, kind.as_str())?;
+    let delivery = |user_id: Uuid| {
+        deliveries
+            .iter()
+            .find(|(id, _delivery)| *id == user_id)
+            .and_then(|(_id, delivery)| delivery.parse().ok())
+            .unwrap_or_else(|| kind.default_delivery())
+    };
+
+    let (title, body) = (truncate(title, MAX_TITLE_LEN), truncate(body, MAX_BODY_LEN));
+    let notifications = user_ids
+        .into_iter()
+        .filter_map(|user_id| {
+            let email = match delivery(user_id) {
+                Delivery::Off => return None,
+                Delivery::InApp => None,
+                email @ (Delivery::Immediate | Delivery::Digest) => Some(email.as_str()),
+            };
+            Some(model::NewNotification {
+                user_id,
+                kind: kind.as_str(),
+                title: &title,
+                body: &body,
+                ticket_id,
+                email,
+            })
+        })
+        .collect::<Vec<_>>();
+    if notifications.is_empty() {
+        return Ok(());
+    }
+
+    db::notification::insert(conn, &notifications).map(|_notifications| ())
+}
+
+/// Truncates a text to a maximum number of characters.
+fn truncate(text: &str, max_len: usize) -> String {
+    text.trim().chars().take(max_len).collect()
+}
+
+/// Notifies the assignee of a ticket, unless they assigned it to themselves.
+pub fn ticket_assigned(
+    conn: &mut PgConnection,
+    ticket: &model::Ticket,
+    actor_id: Option<Uuid>,
+) -> io::Result<()> {
+    match ticket.assignee_id {
+        Some(assignee_id) if Some(assignee_id) != actor_id => notify(
+            conn,
+            &[assignee_id],
+            Kind::TicketAssigned,
+            &format!("Ticket #{} assigned to you", ticket.number),
+            &ticket.title,
+            Some(ticket.id),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Notifies the requester, the assignee and the watchers of a ticket about a new comment, except
+/// its author.
+pub fn ticket_commented(
+    conn: &mut PgConnection,
+    ticket: &model::Ticket,
+    author_id: Uuid,
+    body: &str,
+) -> io::Result<()> {
+    let user_ids = [Some(ticket.requester_id), ticket.assignee_id]
+        .into_iter()
+        .flatten()
+        .chain(db::ticket::get_watcher_ids(conn, ticket.id)?)
+        .filter(|user_id| *user_id != author_id)
+        .collect::<Vec<_>>();
+
+    notify(
+        conn,
+        &user_ids,
+        Kind::TicketCommented,
+        &format!("New comment on ticket #{}", ticket.number),
+        body,
+        Some(ticket.id),
+    )
+}
+
+/// Notifies the requester and the watchers of a ticket about a change of its status, except the
+/// user that changed it.
+pub fn ticket_status_changed(
+    conn: &mut PgConnection,
+    ticket: &model::Ticket,
+    actor_id: Option<Uuid>,
+) -> io::Result<()> {
+    let mut user_ids = db::ticket::get_watcher_ids(conn, ticket.id)?;
+    user_ids.push(ticket.requester_id);
+    user_ids.retain(|user_id| Some(*user_id) != actor_id);
+
+    notify(
+        conn,
+        &user_ids,
+        Kind::TicketStatusChanged,
+        &format!("Ticket #{} is now {}", ticket.number, ticket.status),
+        &ticket.title,
+        Some(ticket.id),
+    )
+}
+
+/// Fairing starting the listener of new notifications once the application launches.
+pub fn listener() -> impl Fairing {
+    CHANNEL.listener()
+}
@@ -0,0 +1,159 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+};
+use diesel::{sql_types::Text, Connection, RunQueryDsl};
+
+/// Payload the database pushes for a notification.
+#[derive(QueryableByName)]
+struct Payload {
+    #[sql_type = "Text"]
+    payload: String,
+}
+
+/// Inserts a ticket requested by carol and assigned to bob.
+fn insert_assigned_ticket(conn: &mut PgConnection) -> (model::Ticket, Uuid, Uuid) {
+    let (carol_id, bob_id) = (user_id(conn, "carol"), user_id(conn, "bob"));
+    let mut ticket = insert_ticket(conn, &new_ticket(carol_id, "UT notification centre"));
+    ticket.assignee_id = Some(bob_id);
+
+    (ticket, carol_id, bob_id)
+}
+
+/// Gets the notifications of a user about a ticket.
+fn notifications_of(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    ticket_id: Uuid,
+) -> Vec<model::Notification> {
+    db::notification::get_for_user(conn, user_id, false, 500, 0)
+        .expect("error retrieving notifications")
+        .into_iter()
+        .filter(|notification| notification.ticket_id == Some(ticket_id))
+        .collect()
+}
+
+/// Sunny day unit test for the ticket notifications.
+#[test]
+fn ut_sunny_ticket_notifications() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let (ticket, carol_id, bob_id) = insert_assigned_ticket(&mut conn);
+
+    ticket_assigned(&mut conn, &ticket, Some(carol_id)).expect("error notifying assignment");
+    ticket_commented(&mut conn, &ticket, bob_id, &"a".repeat(MAX_BODY_LEN + 10))
+        .expect("error notifying comment");
+    ticket_status_changed(&mut conn, &ticket, Some(bob_id)).expect("error notifying status");
+
+    let bob = notifications_of(&mut conn, bob_id, ticket.id);
+    assert_eq!(bob.len(), 1);
+    assert_eq!(bob[0].kind(), Kind::TicketAssigned);
+    assert_eq!(
+        bob[0].title,
+        format!("Ticket #{} assigned to you", ticket.number)
+    );
+
+    let carol = notifications_of(&mut conn, carol_id, ticket.id);
+    assert_eq!(carol.len(), 2);
+    let comment = carol
+        .iter()
+        .find(|notification| notification.kind() == Kind::TicketCommented)
+        .expect("comment was not notified");
+    assert_eq!(comment.body.chars().count(), MAX_BODY_LEN);
+}
+
+/// Rainy day unit test for the ticket notifications: users are not notified of their own actions.
+#[test]
+fn ut_rainy_ticket_notifications() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let (ticket, carol_id, bob_id) = insert_assigned_ticket(&mut conn);
+
+    ticket_assigned(&mut conn, &ticket, Some(bob_id)).expect("error notifying assignment");
+    ticket_status_changed(&mut conn, &ticket, Some(carol_id)).expect("error notifying status");
+    notify(&mut conn, &[], Kind::TicketCommented, "", "", None).expect("error notifying");
+
+    assert!(notifications_of(&mut conn, bob_id, ticket.id).is_empty());
+    assert!(notifications_of(&mut conn, carol_id, ticket.id).is_empty());
+}
+
+/// Sunny day unit test for the parsing of the payloads pushed by the database.
+#[test]
+fn ut_sunny_parse_payload() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let (ticket, carol_id, _) = insert_assigned_ticket(&mut conn);
+    ticket_status_changed(&mut conn, &ticket, None).expect("error notifying status");
+    let stored = notifications_of(&mut conn, carol_id, ticket.id).remove(0);
+
+    let Payload { payload } = diesel::sql_query(format!(
+        "SELECT row_to_json(notification)::text AS payload FROM notification WHERE id = '{}'",
+        stored.id
+    ))
+    .get_result(&conn)
+    .expect("error building payload");
+    let parsed: model::Notification =
+        serde_json::from_str(&payload).expect("error parsing payload");
+
+    assert_eq!(parsed.id, stored.id);
+    assert_eq!(parsed.user_id, carol_id);
+    assert_eq!(parsed.kind(), Kind::TicketStatusChanged);
+    assert_eq!(parsed.ticket_id, Some(ticket.id));
+    assert_eq!(parsed.created_on, stored.created_on);
+    assert!(parsed.read_on.is_none());
+}
+
+/// Sunny day unit test for the notification preferences of users.
+#[test]
+fn ut_sunny_notification_preferences() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let (ticket, carol_id, bob_id) = insert_assigned_ticket(&mut conn);
+    db::notification::upsert_preferences(
+        &mut conn,
+        &[
+            model::NewNotificationPreference {
+                user_id: bob_id,
+                kind: Kind::TicketAssigned.as_str(),
+                delivery: Delivery::Off.as_str(),
+            },
+            model::NewNotificationPreference {
+                user_id: carol_id,
+                kind: Kind::TicketCommented.as_str(),
+                delivery: Delivery::Digest.as_str(),
+            },
+        ],
+    )
+    .expect("error storing preferences");
+
+    ticket_assigned(&mut conn, &ticket, None).expect("error notifying assignment");
+    ticket_commented(
+        &mut conn,
+        &ticket,
+        bob_id,
+        "Have you tried turning it off and on again?",
+    )
+    .expect("error notifying comment");
+    ticket_status_changed(&mut conn, &ticket, None).expect("error notifying status");
+
+    assert!(notifications_of(&mut conn, bob_id, ticket.id).is_empty());
+    let carol = notifications_of(&mut conn, carol_id, ticket.id);
+    assert_eq!(carol.len(), 2);
+    for notification in carol {
+        let expected = match notification.kind() {
+            Kind::TicketCommented => Some("digest"),
+            _ => None,
+        };
+        assert_eq!(notification.email.as_deref(), expected);
+        assert!(notification.emailed_on.is_none());
+    }
+}
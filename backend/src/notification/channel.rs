@@ -0,0 +1,90 @@
+//! Database channels broadcast to event streams.
+//!
+//! The database pushes JSON payloads to a channel with `NOTIFY`. Every application instance
+//! listens on that channel and broadcasts the parsed payloads to the event streams of its
+//! connected users, so that they receive them regardless of the instance that sent them.
+
+use crate::worker;
+use once_cell::sync::OnceCell;
+use postgres::{fallible_iterator::FallibleIterator, Client, NoTls};
+use rocket::{error, fairing::Fairing, tokio::sync::broadcast};
+use serde::de::DeserializeOwned;
+use std::{sync::atomic::AtomicBool, thread, time::Duration};
+
+/// Number of payloads kept for event streams that fall behind.
+const CAPACITY: usize = 256;
+
+/// Time to wait before listening again after a database error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Database channel broadcasting its payloads to the event streams of this instance.
+pub struct Channel<T> {
+    /// Name of the database channel.
+    name: &'static str,
+    /// Name of the listener, used in logs.
+    listener_name: &'static str,
+    /// Sender broadcasting the payloads to the event streams.
+    sender: OnceCell<broadcast::Sender<T>>,
+    /// Whether the listener has been started.
+    started: AtomicBool,
+}
+
+impl<T> Channel<T> {
+    /// Creates a channel listening on the given database channel.
+    pub const fn new(name: &'static str, listener_name: &'static str) -> Self {
+        Self {
+            name,
+            listener_name,
+            sender: OnceCell::new(),
+            started: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Clone + Send + Sync + 'static> Channel<T> {
+    /// Subscribes to the payloads of the channel.
+    pub fn subscribe(&self) -> broadcast::Receiver<T> {
+        self.sender().subscribe()
+    }
+
+    /// Fairing starting the listener of the channel once the application launches.
+    pub fn listener(&'static self) -> impl Fairing {
+        worker::thread_fairing(
+            self.listener_name,
+            &self.started,
+            move |database_url| loop {
+                if let Err(e) = self.listen(&database_url) {
+                    error!("{} error: {}", self.listener_name, e);
+                }
+                thread::sleep(RECONNECT_DELAY);
+            },
+        )
+    }
+
+    /// Returns the sender of the channel, creating it on first use.
+    fn sender(&self) -> &broadcast::Sender<T> {
+        self.sender.get_or_init(|| broadcast::channel(CAPACITY).0)
+    }
+
+    /// Listens on the database channel, broadcasting the payloads.
+    ///
+    /// It only returns on errors.
+    fn listen(&self, database_url: &str) -> Result<(), postgres::Error> {
+        let mut client = Client::connect(database_url, NoTls)?;
+        client.batch_execute(&format!("LISTEN {}", self.name))?;
+
+        let mut notifications = client.notifications();
+        let mut messages = notifications.blocking_iter();
+        while let Some(message) = messages.next()? {
+            match serde_json::from_str(message.payload()) {
+                // Sending only fails if there are no event streams
+                Ok(payload) => {
+                    let _ = self.sender().send(payload);
+                }
+                Err(e) => error!("invalid payload on channel {}: {}", self.name, e),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,16 @@
-use crate::into_io_err;
+use crate::{into_io_err, rand_code};
 use lettre::{
-    message::{IntoBody, Mailbox},
+    message::{
+        header::{Header, HeaderName},
+        IntoBody, Mailbox, MessageBuilder,
+    },
     transport::smtp::authentication::Credentials,
     Message, SmtpTransport, Transport,
 };
 use once_cell::sync::Lazy;
-use std::{env, io};
+use regex::Regex;
+use std::{env, error::Error, io};
+use uuid::Uuid;
 
 static FROM_EMAIL: Lazy<Mailbox> = Lazy::new(|| {
     let from_str = env::var("FROM_EMAIL").expect("FROM_EMAIL environment variable not supplied");
@@ -19,17 +24,138 @@ static SMTP_PASSWORD: Lazy<String> = Lazy::new(|| {
     env::var("SMTP_PASSWORD").expect("SMTP_PASSWORD environment variable not supplied")
 });
 
+/// Message IDs generated for the emails of a ticket: `ticket-<ticket ID>[.<random>]@<domain>`.
+static THREAD_MESSAGE_ID: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^<?ticket-([0-9a-fA-F-]{36})(\.[0-9A-Za-z]+)?@")
+        .expect("could not compile the thread message ID regular expression")
+});
+
+/// `Auto-Submitted` header ([RFC 3834](https://tools.ietf.org/html/rfc3834)).
+///
+/// All the emails sent by the application are automatic, so this header prevents
+/// auto-responders from replying to them.
+#[derive(Debug, Clone)]
+struct AutoSubmitted(String);
+
+impl Header for AutoSubmitted {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("Auto-Submitted")
+    }
+
+    fn parse(s: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self(s.to_owned()))
+    }
+
+    fn display(&self) -> String {
+        self.0.clone()
+    }
+}
+
+/// Ticket an email is about.
+///
+/// Emails of the same ticket are threaded together by mail clients, and replies to them are
+/// matched to the ticket when received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thread {
+    /// The ID of the ticket.
+    pub ticket_id: Uuid,
+    /// The number of the ticket.
+    pub number: i64,
+}
+
+impl Thread {
+    /// Gets the tag of the ticket, added to the subject of its emails.
+    pub fn tag(&self) -> String {
+        format!("[#{}]", self.number)
+    }
+
+    /// Gets the `Message-ID` of the virtual first email of the thread, that all other emails
+    /// reference.
+    fn root_message_id(&self) -> String {
+        format!("<ticket-{}@{}>", self.ticket_id, FROM_EMAIL.email.domain())
+    }
+
+    /// Generates a new `Message-ID` for an email of the thread.
+    fn new_message_id(&self) -> String {
+        format!(
+            "<ticket-{}.{}@{}>",
+            self.ticket_id,
+            rand_code(12),
+            FROM_EMAIL.email.domain()
+        )
+    }
+}
+
+/// Gets the ticket ID of a `Message-ID` generated for the emails of a ticket.
+pub fn thread_ticket_id(message_id: &str) -> Option<Uuid> {
+    let cap = THREAD_MESSAGE_ID.captures(message_id.trim())?;
+    cap.get(1)?.as_str().parse().ok()
+}
+
+/// Gets the address emails are sent from, if configured.
+pub fn from_address() -> Option<String> {
+    env::var("FROM_EMAIL")
+        .ok()?
+        .parse::<Mailbox>()
+        .ok()
+        .map(|mailbox| mailbox.email.to_string().to_lowercase())
+}
+
+/// Checks whether sending emails is configured.
+pub fn is_configured() -> bool {
+    from_address().is_some() && env::var("SMTP_HOST").is_ok()
+}
+
 /// Sends an email to the given address, with the provided subject and body.
 pub fn send<S, B>(to: &str, subject: S, body: B) -> io::Result<()>
 where
     S: Into<String>,
     B: IntoBody,
 {
-    let email = Message::builder()
+    deliver(
+        Message::builder()
+            .to(to.parse().map_err(into_io_err)?)
+            .subject(subject),
+        body,
+    )
+}
+
+/// Sends an email about a ticket to the given address, with the provided subject and body.
+///
+/// The subject gets the tag of the ticket, and the email carries the headers needed to thread it
+/// with the rest of the emails of the ticket.
+pub fn send_in_thread<S, B>(to: &str, thread: Thread, subject: S, body: B) -> io::Result<()>
+where
+    S: Into<String>,
+    B: IntoBody,
+{
+    let (subject, tag) = (subject.into(), thread.tag());
+    let subject = if subject.contains(&tag) {
+        subject
+    } else {
+        format!("{} {}", tag, subject)
+    };
+
+    deliver(
+        Message::builder()
+            .to(to.parse().map_err(into_io_err)?)
+            .subject(subject)
+            .message_id(Some(thread.new_message_id()))
+            .in_reply_to(thread.root_message_id())
+            .references(thread.root_message_id()),
+        body,
+    )
+}
+
+/// Sends an email from the application address.
+fn deliver<B>(builder: MessageBuilder, body: B) -> io::Result<()>
+where
+    B: IntoBody,
+{
+    let email = builder
         .from(FROM_EMAIL.clone())
         .reply_to(FROM_EMAIL.clone())
-        .to(to.parse().unwrap())
-        .subject(subject)
+        .header(AutoSubmitted("auto-generated".to_owned()))
         .body(body)
         .expect("couldn't create message");
 
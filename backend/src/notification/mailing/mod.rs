@@ -0,0 +1,266 @@
+//! Email delivery of notifications.
+//!
+//! Users choose, for every kind of notification, whether they receive it by email right away, in
+//! a daily digest, only in the application, or not at all. Notifications to be emailed are stored
+//! with the way they have to be sent, and a background worker, started with the [`worker()`]
+//! fairing, [sends them right away](send_immediate) or [in digests](send_digests).
+
+use crate::{
+    db::{self, model, tenant::Viewer},
+    notification::email::{self, Thread},
+    worker, BASE_URL,
+};
+use chrono::{DateTime, Duration as ChronoDuration, Timelike, Utc};
+use common::notification::Delivery;
+use diesel::PgConnection;
+use once_cell::sync::Lazy;
+use rocket::{error, fairing::Fairing};
+use std::{env, io, sync::atomic::AtomicBool, time::Duration};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of notifications emailed right away, or of users sent a digest, at once.
+const BATCH_SIZE: i64 = 100;
+
+/// Time to wait between checks for notifications to email.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default hour of the day, in UTC, at which digests are sent.
+const DEFAULT_DIGEST_HOUR: u32 = 8;
+
+/// Template of the digest emails.
+///
+/// It supports the `{{first_name}}`, `{{count}}`, `{{notifications}}` and `{{base_url}}`
+/// placeholders.
+const DIGEST_TEMPLATE: &str = "Hello {{first_name}},
+
+You have {{count}} new notification(s):
+
+{{notifications}}
+You can see them at {{base_url}}, and choose how you receive them at \
+{{base_url}}/settings/notifications.
+";
+
+/// Hour of the day, in UTC, at which digests are sent.
+///
+/// It is read from the `DIGEST_HOUR` environment variable.
+static DIGEST_HOUR: Lazy<u32> = Lazy::new(|| {
+    env::var("DIGEST_HOUR")
+        .ok()
+        .and_then(|hour| hour.parse().ok())
+        .filter(|hour| *hour < 24)
+        .unwrap_or(DEFAULT_DIGEST_HOUR)
+});
+
+/// Whether the worker has been started.
+static WORKER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Email to send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outgoing {
+    /// The address to send it to.
+    pub to: String,
+    /// The ticket the email is about, if any.
+    pub thread: Option<Thread>,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Sends the notifications to email right away, returning the number of emails sent.
+///
+/// Notifications that could not be sent are released, to be sent again later.
+pub fn send_immediate<F>(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+    send: &mut F,
+) -> io::Result<usize>
+where
+    F: FnMut(&Outgoing) -> io::Result<()>,
+{
+    let claimed = db::notification::claim_pending_emails(
+        conn,
+        Delivery::Immediate.as_str(),
+        now,
+        now,
+        BATCH_SIZE,
+    )?;
+    let mut sent = 0;
+
+    for notification in claimed {
+        let user = match db::user::get_with_id(conn, notification.user_id)? {
+            Some(user) => user,
+            None => continue,
+        };
+        let thread = match notification.ticket_id {
+            Some(ticket_id) => {
+                db::ticket::get_with_id(conn, &Viewer::system(), ticket_id)?.map(|ticket| Thread {
+                    ticket_id,
+                    number: ticket.number,
+                })
+            }
+            None => None,
+        };
+        let body = if notification.body.is_empty() {
+            format!("{}\n", *BASE_URL)
+        } else {
+            format!("{}\n\n{}\n", notification.body, *BASE_URL)
+        };
+
+        let outgoing = Outgoing {
+            to: user.email,
+            thread,
+            subject: notification.title,
+            body,
+        };
+        if release_on_error(conn, send(&outgoing), &[notification.id])? {
+            sent += 1;
+        }
+    }
+
+    Ok(sent)
+}
+
+/// Sends the digests due at the given time, returning the number of emails sent.
+///
+/// Each user gets one digest with their notifications created before the last digest hour.
+/// Notifications that could not be sent are released, to be sent again later.
+pub fn send_digests<F>(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+    digest_hour: u32,
+    send: &mut F,
+) -> io::Result<usize>
+where
+    F: FnMut(&Outgoing) -> io::Result<()>,
+{
+    let cutoff = digest_cutoff(now, digest_hour);
+    let user_ids = db::notification::get_pending_email_users(
+        conn,
+        Delivery::Digest.as_str(),
+        cutoff,
+        BATCH_SIZE,
+    )?;
+
+    let mut sent = 0;
+    for user_id in user_ids {
+        // All the notifications of a user are claimed at once, for them to get a single digest
+        let notifications = db::notification::claim_pending_user_emails(
+            conn,
+            Delivery::Digest.as_str(),
+            user_id,
+            cutoff,
+            now,
+        )?;
+        // Another worker sent the digest in the meantime
+        if notifications.is_empty() {
+            continue;
+        }
+        let user = match db::user::get_with_id(conn, user_id)? {
+            Some(user) => user,
+            None => continue,
+        };
+
+        let outgoing = Outgoing {
+            to: user.email.clone(),
+            thread: None,
+            subject: format!("Your {} new notification(s)", notifications.len()),
+            body: render_digest(&user, &notifications),
+        };
+        let ids = notifications
+            .iter()
+            .map(|notification| notification.id)
+            .collect::<Vec<_>>();
+        if release_on_error(conn, send(&outgoing), &ids)? {
+            sent += 1;
+        }
+    }
+
+    Ok(sent)
+}
+
+/// Releases the given notifications if sending them failed, returning whether they were sent.
+fn release_on_error(
+    conn: &mut PgConnection,
+    result: io::Result<()>,
+    ids: &[Uuid],
+) -> io::Result<bool> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(e) => {
+            error!("could not email notifications: {}", e);
+            db::notification::release_emails(conn, ids)?;
+            Ok(false)
+        }
+    }
+}
+
+/// Gets the time of the last digest hour at or before the given time.
+pub fn digest_cutoff(now: DateTime<Utc>, digest_hour: u32) -> DateTime<Utc> {
+    let today = now.date().and_hms(digest_hour, 0, 0);
+
+    if now.hour() >= digest_hour {
+        today
+    } else {
+        today - ChronoDuration::days(1)
+    }
+}
+
+/// Renders the digest of the given notifications for a user.
+pub fn render_digest(user: &model::User, notifications: &[model::Notification]) -> String {
+    let list = notifications
+        .iter()
+        .map(|notification| {
+            if notification.body.is_empty() {
+                format!("- {}\n", notification.title)
+            } else {
+                format!("- {}\n  {}\n", notification.title, notification.body)
+            }
+        })
+        .collect::<String>();
+
+    DIGEST_TEMPLATE
+        .replace("{{first_name}}", &user.first_name)
+        .replace("{{count}}", &notifications.len().to_string())
+        .replace("{{notifications}}", &list)
+        .replace("{{base_url}}", &BASE_URL)
+}
+
+/// Sends an email through the configured mail server.
+pub fn deliver(outgoing: &Outgoing) -> io::Result<()> {
+    match outgoing.thread {
+        Some(thread) => email::send_in_thread(
+            &outgoing.to,
+            thread,
+            outgoing.subject.as_str(),
+            outgoing.body.clone(),
+        ),
+        None => email::send(
+            &outgoing.to,
+            outgoing.subject.as_str(),
+            outgoing.body.clone(),
+        ),
+    }
+}
+
+/// Fairing starting the notification email worker once the application launches.
+///
+/// The worker does nothing unless sending emails is configured.
+pub fn worker() -> impl Fairing {
+    worker::fairing(
+        "Notification email worker",
+        &WORKER_STARTED,
+        POLL_INTERVAL,
+        |conn| {
+            if !email::is_configured() {
+                return Ok(false);
+            }
+
+            let now = Utc::now();
+            let sent = send_immediate(conn, now, &mut deliver)?
+                + send_digests(conn, now, *DIGEST_HOUR, &mut deliver)?;
+            Ok(sent > 0)
+        },
+    )
+}
@@ -0,0 +1,186 @@
+use super::*;
+use crate::db::{establish_connection, fixtures::user};
+use chrono::TimeZone;
+use common::notification::Kind;
+use diesel::Connection;
+
+/// Sets how a user receives a kind of notification.
+fn prefer(conn: &mut PgConnection, user_id: Uuid, kind: Kind, delivery: Delivery) {
+    db::notification::upsert_preferences(
+        conn,
+        &[model::NewNotificationPreference {
+            user_id,
+            kind: kind.as_str(),
+            delivery: delivery.as_str(),
+        }],
+    )
+    .expect("error storing preference");
+}
+
+/// Sunny day unit test for the digest cutoff.
+#[test]
+fn ut_sunny_digest_cutoff() {
+    let before = Utc.ymd(2022, 5, 28).and_hms(7, 59, 59);
+    let at = Utc.ymd(2022, 5, 28).and_hms(8, 0, 0);
+    let after = Utc.ymd(2022, 5, 28).and_hms(23, 0, 0);
+
+    assert_eq!(
+        digest_cutoff(before, 8),
+        Utc.ymd(2022, 5, 27).and_hms(8, 0, 0)
+    );
+    assert_eq!(digest_cutoff(at, 8), at);
+    assert_eq!(digest_cutoff(after, 8), at);
+    assert_eq!(
+        digest_cutoff(before, 0),
+        Utc.ymd(2022, 5, 28).and_hms(0, 0, 0)
+    );
+}
+
+/// Sunny day unit test for the immediate emails and digests.
+#[test]
+fn ut_sunny_mailing() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob = user(&mut conn, "bob");
+    let carol = user(&mut conn, "carol");
+    prefer(&mut conn, bob.id, Kind::TicketAssigned, Delivery::Immediate);
+    prefer(&mut conn, carol.id, Kind::TicketCommented, Delivery::Digest);
+
+    crate::notification::centre::notify(
+        &mut conn,
+        &[bob.id],
+        Kind::TicketAssigned,
+        "UT mailing immediate",
+        "A printer is on fire",
+        None,
+    )
+    .expect("error notifying");
+    // More notifications than sent at once, which still make a single digest
+    let titles = ["UT mailing first", "UT mailing second"]
+        .iter()
+        .map(|&title| title.to_owned())
+        .chain((0..BATCH_SIZE).map(|i| format!("UT mailing batch {}", i)))
+        .collect::<Vec<_>>();
+    for title in &titles {
+        crate::notification::centre::notify(
+            &mut conn,
+            &[carol.id],
+            Kind::TicketCommented,
+            title,
+            "",
+            None,
+        )
+        .expect("error notifying");
+    }
+
+    let mut outbox = Vec::new();
+    let now = Utc::now();
+    let _ = send_immediate(&mut conn, now, &mut |outgoing: &Outgoing| {
+        outbox.push(outgoing.clone());
+        Ok(())
+    })
+    .expect("error sending immediate emails");
+    let immediate = outbox
+        .iter()
+        .find(|outgoing| outgoing.subject == "UT mailing immediate")
+        .expect("immediate email was not sent");
+    assert_eq!(immediate.to, bob.email);
+    assert!(immediate.body.starts_with("A printer is on fire"));
+
+    // Digests only include the notifications created before the last digest hour
+    outbox.clear();
+    let _ = send_digests(&mut conn, now, now.hour(), &mut |outgoing: &Outgoing| {
+        outbox.push(outgoing.clone());
+        Ok(())
+    })
+    .expect("error sending digests");
+    assert!(outbox.iter().all(|outgoing| outgoing.to != carol.email));
+
+    let tomorrow = now + ChronoDuration::days(1);
+    let _ = send_digests(
+        &mut conn,
+        tomorrow,
+        now.hour(),
+        &mut |outgoing: &Outgoing| {
+            outbox.push(outgoing.clone());
+            Ok(())
+        },
+    )
+    .expect("error sending digests");
+    let digests = outbox
+        .iter()
+        .filter(|outgoing| outgoing.to == carol.email)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        digests.len(),
+        1,
+        "notifications were not sent in one digest"
+    );
+    let digest = digests[0];
+    assert_eq!(
+        digest.subject,
+        format!("Your {} new notification(s)", titles.len())
+    );
+    assert!(digest
+        .body
+        .starts_with(&format!("Hello {},", carol.first_name)));
+    // Both notifications share the timestamp of the test transaction, so their order is not known
+    assert!(digest.body.contains("- UT mailing first\n"));
+    assert!(digest.body.contains("- UT mailing second\n"));
+
+    // Sent notifications are not sent again
+    outbox.clear();
+    let _ = send_digests(
+        &mut conn,
+        tomorrow,
+        now.hour(),
+        &mut |outgoing: &Outgoing| {
+            outbox.push(outgoing.clone());
+            Ok(())
+        },
+    )
+    .expect("error sending digests");
+    assert!(outbox.iter().all(|outgoing| outgoing.to != carol.email));
+}
+
+/// Rainy day unit test for the immediate emails: failed emails are sent again.
+#[test]
+fn ut_rainy_mailing() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let bob = user(&mut conn, "bob");
+    prefer(&mut conn, bob.id, Kind::TicketAssigned, Delivery::Immediate);
+    crate::notification::centre::notify(
+        &mut conn,
+        &[bob.id],
+        Kind::TicketAssigned,
+        "UT mailing failure",
+        "",
+        None,
+    )
+    .expect("error notifying");
+
+    let now = Utc::now();
+    let mut attempts = 0;
+    let _ = send_immediate(&mut conn, now, &mut |outgoing: &Outgoing| {
+        if outgoing.subject == "UT mailing failure" {
+            attempts += 1;
+            return Err(io::Error::new(io::ErrorKind::Other, "mail server down"));
+        }
+        Ok(())
+    })
+    .expect("error sending immediate emails");
+    assert_eq!(attempts, 1);
+
+    let mut sent = false;
+    let _ = send_immediate(&mut conn, now, &mut |outgoing: &Outgoing| {
+        sent |= outgoing.subject == "UT mailing failure";
+        Ok(())
+    })
+    .expect("error sending immediate emails");
+    assert!(sent);
+}
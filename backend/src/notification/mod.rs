@@ -1 +1,6 @@
+pub mod activity;
+pub mod centre;
+pub mod channel;
 pub mod email;
+pub mod mailing;
+pub mod webhook;
@@ -0,0 +1,63 @@
+use crate::into_io_err;
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use sha2::Sha256;
+use std::{io, time::Duration};
+
+/// Maximum time to wait for the response of a webhook.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header containing the signature of the payload of subscription webhooks.
+pub const SIGNATURE_HEADER: &str = "X-MySupport-Signature";
+
+/// Header containing the event of subscription webhooks.
+pub const EVENT_HEADER: &str = "X-MySupport-Event";
+
+/// Header containing the delivery ID of subscription webhooks.
+pub const DELIVERY_HEADER: &str = "X-MySupport-Delivery";
+
+/// HTTP agent for subscription webhooks.
+///
+/// Redirects are not followed, so that signed payloads are only sent to the subscribed URL.
+static AGENT: Lazy<ureq::Agent> = Lazy::new(|| {
+    ureq::AgentBuilder::new()
+        .timeout(TIMEOUT)
+        .redirects(0)
+        .build()
+});
+
+/// Signs a payload with a secret, using HMAC-SHA256.
+///
+/// The signature is the lowercase hexadecimal digest, prefixed by `sha256=`.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Sends a signed JSON body to a subscription webhook, in a `POST` request.
+///
+/// Returns the status code of the response, whatever it is. Errors are only returned if no
+/// response was received.
+pub fn post_signed(
+    url: &str,
+    secret: &str,
+    event: &str,
+    delivery_id: &str,
+    body: &str,
+) -> io::Result<u16> {
+    let response = AGENT
+        .post(url)
+        .set("Content-Type", "application/json")
+        .set(EVENT_HEADER, event)
+        .set(DELIVERY_HEADER, delivery_id)
+        .set(SIGNATURE_HEADER, &sign(secret, body))
+        .send_string(body);
+
+    match response {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => Ok(response.status()),
+        Err(e) => Err(into_io_err(e)),
+    }
+}
@@ -0,0 +1,130 @@
+//! Problem management.
+//!
+//! Resolving a problem resolves its incidents that still require work, each with the same comment,
+//! checks, auditing, automation rules and notifications as if the agent did it by hand. Known
+//! errors are published in the knowledge base as articles generated from the problem.
+
+use crate::{
+    audit,
+    automation::Effect,
+    db::{self, model, tenant::Viewer},
+    notification::centre,
+    ticket,
+};
+use common::ticket::{CommentSource, Status as TicketStatus, TicketUpdateDTO};
+use diesel::PgConnection;
+use std::io;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum length of article titles, in characters.
+const MAX_ARTICLE_TITLE_LEN: usize = 200;
+
+/// Generates the title and the Markdown body of the knowledge base article of a known error.
+///
+/// Titles too long for an article are cut with an ellipsis.
+pub fn known_error(problem: &model::Problem) -> (String, String) {
+    let mut body = String::new();
+    if !problem.description.trim().is_empty() {
+        body.push_str(problem.description.trim());
+        body.push_str("\n\n");
+    }
+    body.push_str("## Root cause\n\n");
+    body.push_str(problem.root_cause.trim());
+    body.push_str("\n\n## Workaround\n\n");
+    match problem.workaround.trim() {
+        "" => body.push_str("No workaround is known yet."),
+        workaround => body.push_str(workaround),
+    }
+    body.push('\n');
+
+    let mut title = format!("Known error: {}", problem.title);
+    if title.chars().count() > MAX_ARTICLE_TITLE_LEN {
+        title = title.chars().take(MAX_ARTICLE_TITLE_LEN - 1).collect();
+        title.push('…');
+    }
+
+    (title, body)
+}
+
+/// Result of the resolution of a problem.
+#[derive(Debug)]
+pub struct Resolution {
+    /// The resolved problem.
+    pub problem: model::Problem,
+    /// The IDs of the incidents resolved along with the problem.
+    pub resolved_ids: Vec<Uuid>,
+    /// The side effects of the automation rules run for the incidents, to be dispatched.
+    pub effects: Vec<Effect>,
+}
+
+/// Resolves a problem along with its active incidents, or returns `None` if the problem doesn't
+/// exist.
+///
+/// The comment is added to every incident resolved, unless it's empty. Incidents go through the
+/// same checks as when agents resolve them, and the ones that can't be resolved, such as parents
+/// of unfinished child tickets, stay as they were. Everything is stored in a single transaction.
+pub fn resolve(
+    conn: &mut PgConnection,
+    ctx: &audit::Context,
+    problem_id: Uuid,
+    actor_id: Uuid,
+    comment: &str,
+) -> io::Result<Option<Resolution>> {
+    db::transaction(conn, |conn| {
+        let problem = match db::problem::resolve(conn, problem_id)? {
+            Some(problem) => problem,
+            None => return Ok(None),
+        };
+
+        let comment = comment.trim();
+        let update = TicketUpdateDTO {
+            status: Some(TicketStatus::Resolved),
+            ..Default::default()
+        };
+        let (mut resolved_ids, mut effects) = (Vec::new(), Vec::new());
+        for ticket in db::problem::get_active_tickets(conn, problem_id)? {
+            if ticket::check_status(conn, &ticket, TicketStatus::Resolved)?.is_err()
+                || ticket::check_wip_limits(conn, &ticket, &update)?.is_err()
+            {
+                continue;
+            }
+
+            if !comment.is_empty() {
+                let _ = db::ticket::insert_comment(
+                    conn,
+                    &model::NewTicketComment {
+                        ticket_id: ticket.id,
+                        author_id: actor_id,
+                        body: comment,
+                        source: CommentSource::Web.as_str(),
+                    },
+                )?;
+                centre::ticket_commented(conn, &ticket, actor_id, comment)?;
+            }
+
+            let after = db::ticket::update(
+                conn,
+                &Viewer::system(),
+                ticket.id,
+                &model::TicketChanges {
+                    status: Some(TicketStatus::Resolved.as_str()),
+                    ..Default::default()
+                },
+            )?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "incident disappeared"))?;
+            let (after, incident_effects) = ticket::updated(conn, ctx, actor_id, &ticket, after)?;
+
+            resolved_ids.push(after.id);
+            effects.extend(incident_effects);
+        }
+
+        Ok(Some(Resolution {
+            problem,
+            resolved_ids,
+            effects,
+        }))
+    })
+}
@@ -0,0 +1,223 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user_id},
+};
+use chrono::Utc;
+use common::{
+    automation::{Action, Condition, Field},
+    problem::Status,
+};
+use diesel::Connection;
+
+/// Builds the context of a test request.
+fn context() -> audit::Context {
+    audit::Context {
+        ip_address: None,
+        user_agent: None,
+        request_id: "ut-problem".to_owned(),
+    }
+}
+
+/// Builds a problem with a root cause and a workaround.
+fn problem(description: &str, workaround: &str) -> model::Problem {
+    let now = Utc::now();
+    model::Problem {
+        id: Uuid::new_v4(),
+        number: 1,
+        title: "Mail outage".to_owned(),
+        description: description.to_owned(),
+        status: Status::KnownError.as_str().to_owned(),
+        root_cause: "The disk of the mail server is full.".to_owned(),
+        workaround: workaround.to_owned(),
+        owner_id: None,
+        article_id: None,
+        created_by: Uuid::new_v4(),
+        resolved_on: None,
+        created_on: now,
+        updated_on: now,
+    }
+}
+
+/// Sunny day unit test for the `known_error()` function.
+#[test]
+fn ut_sunny_known_error() {
+    let (title, body) = known_error(&problem("No mail is sent.", "Send less mail."));
+
+    assert_eq!(title, "Known error: Mail outage");
+    assert_eq!(
+        body,
+        "No mail is sent.\n\n## Root cause\n\nThe disk of the mail server is full.\n\n\
+         ## Workaround\n\nSend less mail.\n"
+    );
+}
+
+/// Rainy day unit test for the `known_error()` function: missing sections are explained or left
+/// out, and long titles are cut.
+#[test]
+fn ut_rainy_known_error() {
+    let mut long = problem(" ", "");
+    long.title = "é".repeat(200);
+    let (title, body) = known_error(&long);
+
+    assert_eq!(title.chars().count(), 200);
+    assert!(title.ends_with("é…"));
+
+    assert_eq!(
+        body,
+        "## Root cause\n\nThe disk of the mail server is full.\n\n\
+         ## Workaround\n\nNo workaround is known yet.\n"
+    );
+}
+
+/// Sunny day unit test for the `resolve()` function: active incidents are resolved with the
+/// comment and run the automation rules, the others and the ones with unfinished child tickets
+/// are left as they were.
+#[test]
+fn ut_sunny_resolve() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let (bob, carol) = (user_id(&mut conn, "bob"), user_id(&mut conn, "carol"));
+    let problem = db::problem::insert(
+        &mut conn,
+        &model::ProblemForm {
+            title: "UT mail outage",
+            description: "",
+            status: Status::KnownError.as_str(),
+            root_cause: "Full disk",
+            workaround: "",
+            owner_id: Some(bob),
+            resolved_on: None,
+        },
+        bob,
+    )
+    .expect("error inserting problem");
+    let mut tickets = Vec::new();
+    for (title, status) in [
+        ("UT no mail", "open"),
+        ("UT old mail", "closed"),
+        ("UT mail migration", "open"),
+    ] {
+        let ticket = db::ticket::insert(
+            &mut conn,
+            &Viewer::system(),
+            &model::NewTicket {
+                title,
+                description: "",
+                priority: "normal",
+                requester_id: carol,
+                organisation_id: None,
+                queue_id: None,
+                category: None,
+                custom_fields: None,
+            },
+        )
+        .expect("error inserting ticket");
+        let ticket = db::ticket::update(
+            &mut conn,
+            &Viewer::system(),
+            ticket.id,
+            &model::TicketChanges {
+                status: Some(status),
+                ..Default::default()
+            },
+        )
+        .expect("error updating ticket")
+        .expect("ticket was not found");
+        assert!(db::problem::link(&mut conn, problem.id, ticket.id, bob).expect("error linking"));
+        tickets.push(ticket);
+    }
+    let child = insert_ticket(&mut conn, &new_ticket(carol, "UT mailbox migration"));
+    let _ = db::relation::set_parent(&mut conn, child.id, Some(tickets[2].id))
+        .expect("error setting parent ticket");
+
+    let actions = [
+        Action::AddTag {
+            tag: "mail-restored".to_owned(),
+        },
+        Action::SendEmail {
+            to: "postmaster@example.com".to_owned(),
+            subject: "Resolved: {{title}}".to_owned(),
+            body: String::new(),
+        },
+    ];
+    let _ = db::automation::insert_rule(
+        &mut conn,
+        &model::AutomationRuleForm {
+            name: "UT resolved mail incident",
+            enabled: true,
+            position: 0,
+            condition: serde_json::to_value(&Condition::All {
+                conditions: vec![
+                    Condition::Equals {
+                        field: Field::Title,
+                        value: Some("UT no mail".to_owned()),
+                    },
+                    Condition::Equals {
+                        field: Field::Status,
+                        value: Some("resolved".to_owned()),
+                    },
+                ],
+            })
+            .expect("error serializing condition"),
+            actions: serde_json::to_value(&actions).expect("error serializing actions"),
+        },
+        bob,
+    )
+    .expect("error inserting rule");
+
+    let resolution = resolve(&mut conn, &context(), problem.id, bob, " Disk replaced ")
+        .expect("error resolving problem")
+        .expect("problem was not found");
+    assert_eq!(resolution.problem.status(), Status::Resolved);
+    assert_eq!(resolution.resolved_ids, [tickets[0].id]);
+    assert_eq!(resolution.effects.len(), 1);
+
+    let comments =
+        db::ticket::get_comments(&mut conn, tickets[0].id).expect("error retrieving comments");
+    assert_eq!(
+        comments
+            .iter()
+            .map(|comment| comment.body.as_str())
+            .collect::<Vec<_>>(),
+        ["Disk replaced"]
+    );
+    let statuses = tickets
+        .iter()
+        .map(|ticket| {
+            db::ticket::get_with_id(&mut conn, &Viewer::system(), ticket.id)
+                .expect("error retrieving ticket")
+                .expect("ticket was not found")
+                .status()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        db::ticket::get_with_id(&mut conn, &Viewer::system(), tickets[0].id)
+            .expect("error retrieving ticket")
+            .expect("ticket was not found")
+            .tags,
+        ["mail-restored"]
+    );
+    assert_eq!(
+        statuses,
+        [
+            TicketStatus::Resolved,
+            TicketStatus::Closed,
+            TicketStatus::Open
+        ]
+    );
+}
+
+/// Rainy day unit test for the `resolve()` function: unknown problems are not resolved.
+#[test]
+fn ut_rainy_resolve() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    let bob = user_id(&mut conn, "bob");
+
+    assert!(resolve(&mut conn, &context(), Uuid::new_v4(), bob, "")
+        .expect("error resolving problem")
+        .is_none());
+}
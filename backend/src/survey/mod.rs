@@ -0,0 +1,136 @@
+//! Customer satisfaction surveys.
+//!
+//! When a ticket is resolved, its requester is asked to rate the resolution, unless the tickets of
+//! its category are exempted. A background worker, started with the [`worker()`] fairing, emails
+//! the surveys with one link per rating, which records the rating in one click and lets the
+//! requester add a comment, without logging in. Answers are stored against the ticket and the
+//! agent it was assigned to.
+
+use crate::{
+    db::{self, model, tenant::Viewer},
+    notification::{
+        email::{self, Thread},
+        mailing::{self, Outgoing},
+    },
+    worker, BASE_URL,
+};
+use chrono::{DateTime, Utc};
+use common::{survey::Rating, ticket::Status as TicketStatus};
+use diesel::PgConnection;
+use rocket::{error, fairing::Fairing};
+use std::{io, sync::atomic::AtomicBool, time::Duration};
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of surveys claimed at once.
+const BATCH_SIZE: i64 = 100;
+
+/// Time to wait between checks for surveys to email.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Template of the survey emails.
+///
+/// It supports the `{{first_name}}`, `{{title}}`, `{{good_url}}` and `{{bad_url}}` placeholders.
+const SURVEY_TEMPLATE: &str = "Hello {{first_name}},
+
+Your ticket \"{{title}}\" has been resolved. How did we do?
+
+Good: {{good_url}}
+Bad: {{bad_url}}
+
+One click records your rating, and you can then tell us more if you like.
+";
+
+/// Whether the worker has been started.
+static WORKER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Asks the requester of a ticket to rate its resolution, if the ticket has just been resolved and
+/// its category isn't exempted from surveys.
+pub fn ticket_status_changed(conn: &mut PgConnection, ticket: &model::Ticket) -> io::Result<()> {
+    if ticket.status() != TicketStatus::Resolved {
+        return Ok(());
+    }
+    if let Some(category) = &ticket.category {
+        if db::survey::is_suppressed(conn, category)? {
+            return Ok(());
+        }
+    }
+
+    db::survey::insert(conn, ticket.id, ticket.assignee_id).map(|_survey| ())
+}
+
+/// Sends the surveys pending to be emailed, returning the number of emails sent.
+///
+/// Surveys that could not be sent are released, to be sent again later.
+pub fn send_pending<F>(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+    send: &mut F,
+) -> io::Result<usize>
+where
+    F: FnMut(&Outgoing) -> io::Result<()>,
+{
+    let claimed = db::survey::claim_pending(conn, now, BATCH_SIZE)?;
+    let mut sent = 0;
+
+    for survey in claimed {
+        let ticket = match db::ticket::get_with_id(conn, &Viewer::system(), survey.ticket_id)? {
+            Some(ticket) => ticket,
+            None => continue,
+        };
+        let requester = match db::user::get_with_id(conn, ticket.requester_id)? {
+            Some(requester) if requester.active => requester,
+            _ => continue,
+        };
+
+        let outgoing = Outgoing {
+            to: requester.email.clone(),
+            thread: Some(Thread {
+                ticket_id: ticket.id,
+                number: ticket.number,
+            }),
+            subject: "How did we do?".to_owned(),
+            body: render(&requester, &ticket, &survey.token),
+        };
+        match send(&outgoing) {
+            Ok(()) => sent += 1,
+            Err(e) => {
+                error!("could not email survey: {}", e);
+                db::survey::release(conn, survey.id)?;
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+/// Renders the survey email of a ticket for its requester.
+pub fn render(requester: &model::User, ticket: &model::Ticket, token: &str) -> String {
+    let url = |rating: Rating| format!("{}/surveys/{}/{}", *BASE_URL, token, rating);
+
+    SURVEY_TEMPLATE
+        .replace("{{first_name}}", &requester.first_name)
+        .replace("{{title}}", &ticket.title)
+        .replace("{{good_url}}", &url(Rating::Good))
+        .replace("{{bad_url}}", &url(Rating::Bad))
+}
+
+/// Fairing starting the survey email worker once the application launches.
+///
+/// The worker does nothing unless sending emails is configured.
+pub fn worker() -> impl Fairing {
+    worker::fairing(
+        "Survey email worker",
+        &WORKER_STARTED,
+        POLL_INTERVAL,
+        |conn| {
+            if !email::is_configured() {
+                return Ok(false);
+            }
+
+            let sent = send_pending(conn, Utc::now(), &mut mailing::deliver)?;
+            Ok(sent > 0)
+        },
+    )
+}
@@ -0,0 +1,133 @@
+use super::*;
+use crate::db::{
+    establish_connection,
+    fixtures::{insert_ticket, new_ticket, user, user_id},
+};
+use diesel::Connection;
+use uuid::Uuid;
+
+/// Inserts a ticket requested by Carol, assigned to Bob, in a category.
+fn insert_assigned_ticket(
+    conn: &mut PgConnection,
+    title: &str,
+    category: Option<&str>,
+) -> model::Ticket {
+    let (bob, carol) = (user_id(conn, "bob"), user_id(conn, "carol"));
+    let ticket = insert_ticket(
+        conn,
+        &model::NewTicket {
+            category,
+            ..new_ticket(carol, title)
+        },
+    );
+
+    set_status(conn, ticket.id, Some(bob), TicketStatus::Open)
+}
+
+/// Sets the status and assignee of a ticket.
+fn set_status(
+    conn: &mut PgConnection,
+    ticket_id: Uuid,
+    assignee_id: Option<Uuid>,
+    status: TicketStatus,
+) -> model::Ticket {
+    db::ticket::update(
+        conn,
+        &Viewer::system(),
+        ticket_id,
+        &model::TicketChanges {
+            status: Some(status.as_str()),
+            assignee_id: Some(assignee_id),
+            ..Default::default()
+        },
+    )
+    .expect("error updating ticket")
+    .expect("ticket was not found")
+}
+
+/// Marks the surveys left pending by other tests as sent, so that they don't fill the batches.
+fn send_others(conn: &mut PgConnection) {
+    let _ = db::survey::claim_pending(conn, Utc::now(), i64::MAX)
+        .expect("error marking surveys as sent");
+}
+
+/// Sunny day unit test for surveys: resolved tickets get one survey, emailed once to their
+/// requester in the thread of the ticket, with a link per rating.
+#[test]
+fn ut_sunny_survey() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    send_others(&mut conn);
+    let (bob, carol) = (user(&mut conn, "bob"), user(&mut conn, "carol"));
+    let ticket = insert_assigned_ticket(&mut conn, "UT printer on fire", Some("ut-hardware"));
+
+    let resolved = set_status(&mut conn, ticket.id, Some(bob.id), TicketStatus::Resolved);
+    ticket_status_changed(&mut conn, &resolved).expect("error requesting survey");
+    let surveys =
+        db::survey::get_for_ticket(&mut conn, ticket.id).expect("error retrieving surveys");
+    assert_eq!(surveys.len(), 1);
+    assert_eq!(surveys[0].agent_id, Some(bob.id));
+    assert_eq!(surveys[0].sent_on, None);
+
+    let mut outbox = Vec::new();
+    let sent = send_pending(&mut conn, Utc::now(), &mut |outgoing: &Outgoing| {
+        outbox.push(outgoing.clone());
+        Ok(())
+    })
+    .expect("error sending surveys");
+    assert_eq!(sent, 1);
+    assert_eq!(outbox[0].to, carol.email);
+    assert_eq!(
+        outbox[0].thread.map(|thread| thread.ticket_id),
+        Some(ticket.id)
+    );
+    let token = &surveys[0].token;
+    assert!(outbox[0]
+        .body
+        .contains(&format!("/surveys/{}/good\n", token)));
+    assert!(outbox[0]
+        .body
+        .contains(&format!("/surveys/{}/bad\n", token)));
+
+    // Sent surveys are not sent again
+    let sent = send_pending(&mut conn, Utc::now(), &mut |_outgoing: &Outgoing| Ok(()))
+        .expect("error sending surveys");
+    assert_eq!(sent, 0);
+}
+
+/// Rainy day unit test for surveys: tickets that are not resolved or in an exempted category don't
+/// get surveys, and failed emails are sent again.
+#[test]
+fn ut_rainy_survey() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+    send_others(&mut conn);
+    let _ = db::survey::insert_suppression(&mut conn, "ut-internal")
+        .expect("error suppressing surveys");
+
+    let internal = insert_assigned_ticket(&mut conn, "UT new laptop", Some("ut-internal"));
+    let internal = set_status(&mut conn, internal.id, None, TicketStatus::Resolved);
+    ticket_status_changed(&mut conn, &internal).expect("error requesting survey");
+    let closed = insert_assigned_ticket(&mut conn, "UT duplicate", None);
+    let closed = set_status(&mut conn, closed.id, None, TicketStatus::Closed);
+    ticket_status_changed(&mut conn, &closed).expect("error requesting survey");
+    for ticket_id in [internal.id, closed.id] {
+        let surveys =
+            db::survey::get_for_ticket(&mut conn, ticket_id).expect("error retrieving surveys");
+        assert!(surveys.is_empty());
+    }
+
+    let ticket = insert_assigned_ticket(&mut conn, "UT jammed printer", None);
+    let resolved = set_status(&mut conn, ticket.id, None, TicketStatus::Resolved);
+    ticket_status_changed(&mut conn, &resolved).expect("error requesting survey");
+    let sent = send_pending(&mut conn, Utc::now(), &mut |_outgoing: &Outgoing| {
+        Err(io::Error::new(io::ErrorKind::Other, "mail server down"))
+    })
+    .expect("error sending surveys");
+    assert_eq!(sent, 0);
+    let sent = send_pending(&mut conn, Utc::now(), &mut |_outgoing: &Outgoing| Ok(()))
+        .expect("error sending surveys");
+    assert_eq!(sent, 1);
+}
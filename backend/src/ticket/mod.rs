@@ -0,0 +1,188 @@
+//! Rules and side effects of ticket changes.
+//!
+//! Tickets are opened from the application, service requests, changes, splits and emails. Each
+//! of them goes through the same steps once the ticket is stored, so that later side effects are
+//! added in a single place. Likewise, every status change follows the same rules, whether agents,
+//! automation rules, approvals or problems make it.
+
+use crate::{
+    audit,
+    automation::{self, Effect},
+    db::{self, model},
+    notification::centre,
+    survey, webhook,
+};
+use common::{
+    audit::AuditEvent,
+    automation::Event,
+    board::{status_title, Grouping},
+    ticket::{Status, TicketDTO, TicketUpdateDTO},
+    webhook::Event as WebhookEvent,
+};
+use diesel::PgConnection;
+use std::io;
+use uuid::Uuid;
+
+/// Runs the side effects of opening a ticket that was just inserted, returning it updated along
+/// with the side effects of the automation rules, to be dispatched.
+///
+/// The ticket is assigned following the strategy of its queue, automation rules run, and the
+/// webhooks and assignee are notified.
+pub fn opened(
+    conn: &mut PgConnection,
+    requester_id: Uuid,
+    ticket: model::Ticket,
+) -> io::Result<(model::Ticket, Vec<Effect>)> {
+    let ticket = if ticket.queue_id.is_some() {
+        db::assignment::auto_assign(conn, ticket.id)?.unwrap_or(ticket)
+    } else {
+        ticket
+    };
+
+    let (ticket, effects) = automation::run(conn, Event::Created, None, ticket)?;
+    webhook::enqueue(conn, WebhookEvent::TicketCreated, &ticket)?;
+    centre::ticket_assigned(conn, &ticket, Some(requester_id))?;
+
+    Ok((ticket, effects))
+}
+
+/// Checks that a ticket can take a status, returning why it can't otherwise.
+///
+/// Parent tickets stay active until the work broken down into their children is done.
+pub fn check_status(
+    conn: &mut PgConnection,
+    ticket: &model::Ticket,
+    status: Status,
+) -> io::Result<Result<(), &'static str>> {
+    if !status.is_active()
+        && ticket.status().is_active()
+        && db::relation::count_active_children(conn, ticket.id)? > 0
+    {
+        return Ok(Err("ticket has unfinished child tickets"));
+    }
+
+    Ok(Ok(()))
+}
+
+/// Checks that an update doesn't move a ticket to a board column that reached its limit of work
+/// in progress, on the boards grouped by status and by assignee, returning why otherwise.
+pub fn check_wip_limits(
+    conn: &mut PgConnection,
+    ticket: &model::Ticket,
+    update: &TicketUpdateDTO,
+) -> io::Result<Result<(), String>> {
+    let before = TicketDTO::from(ticket.clone());
+    let mut after = before.clone();
+    if let Some(status) = update.status {
+        after.status = status;
+    }
+    if let Some(assignee_id) = update.assignee_id {
+        after.assignee_id = assignee_id;
+    }
+
+    for &grouping in Grouping::ALL {
+        let key = grouping.key(&after);
+        let moved = grouping.holds(after.status)
+            && !(grouping.holds(before.status) && grouping.key(&before) == key);
+        // Limits set before the columns of inactive tickets were refused don't apply
+        if !moved || !grouping.limits(&key) {
+            continue;
+        }
+
+        let wip_limit = match db::board::get_wip_limit(conn, grouping.as_str(), &key)? {
+            Some(wip_limit) => wip_limit,
+            None => continue,
+        };
+        let count = match grouping {
+            Grouping::Status => db::board::count_with_status(conn, &key)?,
+            Grouping::Assignee => db::board::count_assigned(conn, after.assignee_id)?,
+        };
+        if count >= i64::from(wip_limit) {
+            let title = match (grouping, after.assignee_id) {
+                (Grouping::Status, _) => status_title(after.status),
+                (Grouping::Assignee, Some(_)) => "Assignee",
+                (Grouping::Assignee, None) => "Unassigned",
+            };
+            return Ok(Err(format!("{} column is at its WIP limit", title)));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+/// Runs the side effects of a ticket update that was just stored, returning the ticket updated
+/// along with the side effects of the automation rules, to be dispatched.
+///
+/// The changed fields are audited, automation rules run, and the webhooks, the assignee and the
+/// requester are notified.
+pub fn updated(
+    conn: &mut PgConnection,
+    ctx: &audit::Context,
+    actor_id: Uuid,
+    before: &model::Ticket,
+    after: model::Ticket,
+) -> io::Result<(model::Ticket, Vec<Effect>)> {
+    let changes = [
+        (
+            "title",
+            Some(before.title.clone()),
+            Some(after.title.clone()),
+        ),
+        (
+            "status",
+            Some(before.status.clone()),
+            Some(after.status.clone()),
+        ),
+        (
+            "priority",
+            Some(before.priority.clone()),
+            Some(after.priority.clone()),
+        ),
+        (
+            "assignee_id",
+            before.assignee_id.map(|id| id.to_string()),
+            after.assignee_id.map(|id| id.to_string()),
+        ),
+        (
+            "queue_id",
+            before.queue_id.map(|id| id.to_string()),
+            after.queue_id.map(|id| id.to_string()),
+        ),
+        ("category", before.category.clone(), after.category.clone()),
+        (
+            "tags",
+            Some(before.tags.join(",")),
+            Some(after.tags.join(",")),
+        ),
+        (
+            "custom_fields",
+            Some(before.custom_fields.to_string()),
+            Some(after.custom_fields.to_string()),
+        ),
+    ];
+    for (field, old, new) in changes {
+        if old != new {
+            let event = AuditEvent::TicketFieldChange {
+                ticket_id: before.id,
+                field: field.to_owned(),
+                before: old,
+                after: new,
+            };
+            audit::record(conn, ctx, Some(actor_id), &event)?;
+        }
+    }
+
+    let (after, effects) = automation::run(conn, Event::Updated, Some(before), after)?;
+    if TicketDTO::from(after.clone()) != TicketDTO::from(before.clone()) {
+        webhook::enqueue(conn, WebhookEvent::TicketUpdated, &after)?;
+        if after.assignee_id != before.assignee_id {
+            centre::ticket_assigned(conn, &after, Some(actor_id))?;
+        }
+        if after.status != before.status {
+            centre::ticket_status_changed(conn, &after, Some(actor_id))?;
+            survey::ticket_status_changed(conn, &after)?;
+        }
+    }
+
+    Ok((after, effects))
+}
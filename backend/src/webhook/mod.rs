@@ -0,0 +1,182 @@
+//! Delivery of outbound webhooks.
+//!
+//! Ticket events are [enqueued](enqueue) as pending deliveries for every enabled subscription
+//! accepting them. A background worker, started with the [`worker()`] fairing, then
+//! [delivers](deliver_due) them as signed `POST` requests.
+//!
+//! Failed attempts are retried with exponential backoff, up to [`MAX_ATTEMPTS`] times.
+//! Subscriptions are disabled after [`MAX_CONSECUTIVE_FAILURES`] failed attempts in a row.
+
+use crate::{
+    db::{self, model},
+    notification, worker,
+};
+use chrono::{DateTime, Utc};
+use common::{
+    ticket::TicketDTO,
+    webhook::{DeliveryStatus, Event, PayloadDTO},
+};
+use diesel::PgConnection;
+use rocket::fairing::Fairing;
+use std::{collections::HashSet, io, sync::atomic::AtomicBool, time::Duration};
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of attempts for each delivery.
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// Number of failed attempts in a row after which a subscription is disabled.
+pub const MAX_CONSECUTIVE_FAILURES: i32 = 20;
+
+/// Delay before the first retry of a delivery, in seconds. It doubles on every attempt.
+const BASE_RETRY_DELAY: i64 = 30;
+
+/// Maximum delay between two attempts of a delivery, in seconds.
+const MAX_RETRY_DELAY: i64 = 6 * 60 * 60;
+
+/// Time during which a claimed delivery is not attempted by other workers, in seconds.
+const LEASE: i64 = 10 * 60;
+
+/// Maximum number of deliveries claimed at once by the worker.
+const BATCH_SIZE: i64 = 20;
+
+/// Time the worker waits when there are no deliveries due.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether the delivery worker has been started in this process.
+static WORKER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Enqueues an event of a ticket for all the enabled subscriptions accepting it.
+pub fn enqueue(conn: &mut PgConnection, event: Event, ticket: &model::Ticket) -> io::Result<()> {
+    let subscriptions = db::webhook::get_enabled_subscriptions(conn)?
+        .into_iter()
+        .filter(|subscription| subscription.accepts(event))
+        .collect::<Vec<_>>();
+    if subscriptions.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::to_value(&PayloadDTO {
+        event,
+        occurred_on: Utc::now(),
+        ticket: TicketDTO::from(ticket.clone()),
+    })?;
+    let deliveries = subscriptions
+        .iter()
+        .map(|subscription| model::NewWebhookDelivery {
+            subscription_id: subscription.id,
+            event: event.as_str(),
+            payload: &payload,
+        })
+        .collect::<Vec<_>>();
+
+    db::webhook::insert_deliveries(conn, &deliveries)
+}
+
+/// Gets the delay before the next attempt of a delivery, after the given number of attempts.
+pub fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.clamp(1, 30) - 1;
+    let delay = BASE_RETRY_DELAY
+        .saturating_mul(1 << exponent)
+        .min(MAX_RETRY_DELAY);
+
+    chrono::Duration::seconds(delay)
+}
+
+/// Attempts the deliveries that are due, returning the number of attempts made.
+///
+/// Requests are sent while holding the connection, so it should not be shared with request
+/// handlers.
+pub fn deliver_due(conn: &mut PgConnection, now: DateTime<Utc>) -> io::Result<usize> {
+    let claimed = db::webhook::claim_due_deliveries(
+        conn,
+        now,
+        now + chrono::Duration::seconds(LEASE),
+        BATCH_SIZE,
+    )?;
+    let mut attempts = 0;
+    let mut disabled = HashSet::new();
+
+    for (delivery, subscription) in claimed {
+        // Pending deliveries of disabled subscriptions have already been failed
+        if disabled.contains(&subscription.id) {
+            continue;
+        }
+
+        let result = notification::webhook::post_signed(
+            &subscription.url,
+            &subscription.secret,
+            &delivery.event,
+            &delivery.id.to_string(),
+            &delivery.payload.to_string(),
+        );
+        attempts += 1;
+        if !record_attempt(conn, &delivery, &subscription, result, now)? {
+            let _ = disabled.insert(subscription.id);
+        }
+    }
+
+    Ok(attempts)
+}
+
+/// Stores the outcome of a delivery attempt, scheduling a retry if needed.
+///
+/// The subscription is disabled if it has failed too many times in a row. Returns whether the
+/// subscription is still enabled.
+fn record_attempt(
+    conn: &mut PgConnection,
+    delivery: &model::WebhookDelivery,
+    subscription: &model::WebhookSubscription,
+    result: io::Result<u16>,
+    now: DateTime<Utc>,
+) -> io::Result<bool> {
+    let attempts = delivery.attempts + 1;
+    let (response_code, error) = match result {
+        Ok(code) if (200..300).contains(&code) => (Some(code), None),
+        Ok(code) => (
+            Some(code),
+            Some(format!("unexpected response status {}", code)),
+        ),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let status = if error.is_none() {
+        DeliveryStatus::Delivered
+    } else if attempts >= MAX_ATTEMPTS {
+        DeliveryStatus::Failed
+    } else {
+        DeliveryStatus::Pending
+    };
+
+    db::webhook::update_delivery(
+        conn,
+        delivery.id,
+        &model::WebhookAttempt {
+            status: status.as_str(),
+            attempts,
+            next_attempt_on: now + retry_delay(attempts),
+            response_code: response_code.and_then(|code| i16::try_from(code).ok()),
+            error: error.as_deref(),
+            updated_on: now,
+        },
+    )?;
+
+    if error.is_none() {
+        db::webhook::reset_failures(conn, subscription.id)?;
+    } else if db::webhook::increment_failures(conn, subscription.id)? >= MAX_CONSECUTIVE_FAILURES {
+        db::webhook::disable_subscription(conn, subscription.id, now)?;
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// Fairing starting the webhook delivery worker once the application launches.
+pub fn worker() -> impl Fairing {
+    worker::fairing(
+        "Webhook delivery worker",
+        &WORKER_STARTED,
+        POLL_INTERVAL,
+        |conn| Ok(deliver_due(conn, Utc::now())? > 0),
+    )
+}
@@ -0,0 +1,286 @@
+use super::*;
+use crate::db::{establish_connection, tenant::Viewer};
+use chrono::SubsecRound;
+use diesel::Connection;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+    thread,
+};
+use uuid::Uuid;
+
+/// Request received by the HTTP stand-in.
+#[derive(Debug)]
+struct Received {
+    /// Headers of the request, with lowercase names.
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Received {
+    /// Gets the value of a header.
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| *header == name.to_lowercase())
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Starts a local HTTP server standing in for a subscriber.
+///
+/// It answers the given number of requests with a status code, and sends the requests through
+/// the returned channel. Returns the URL of the server.
+fn stand_in(status: u16, requests: usize) -> (String, mpsc::Receiver<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("error binding the stand-in");
+    let url = format!(
+        "http://{}/hook",
+        listener.local_addr().expect("stand-in without address")
+    );
+    let (sender, receiver) = mpsc::channel();
+
+    let _ = thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.expect("error accepting connection");
+            let mut reader = BufReader::new(stream.try_clone().expect("error cloning stream"));
+
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                let _ = reader.read_line(&mut line).expect("error reading request");
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_lowercase(), value.trim().to_owned()));
+                }
+            }
+
+            let len = headers
+                .iter()
+                .find(|(name, _)| name == "content-length")
+                .map_or(0, |(_, value)| {
+                    value.parse().expect("invalid content length")
+                });
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).expect("error reading body");
+
+            write!(
+                stream,
+                "HTTP/1.1 {} Stand-in\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .expect("error writing response");
+            let _ = sender.send(Received {
+                headers,
+                body: String::from_utf8(body).expect("body was not UTF-8"),
+            });
+        }
+    });
+
+    (url, receiver)
+}
+
+/// Inserts a subscription to the given URL.
+fn insert_subscription(
+    conn: &mut PgConnection,
+    url: &str,
+    events: &[Event],
+) -> model::WebhookSubscription {
+    let admin = db::user::get_with_username(conn, "alice")
+        .expect("error retrieving user from database")
+        .expect("Alice was not in the database");
+    let events = events.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    db::webhook::insert_subscription(
+        conn,
+        &model::WebhookSubscriptionForm {
+            url,
+            events: &events,
+            secret: Some("ut_webhook_secret"),
+            enabled: true,
+        },
+        admin.id,
+    )
+    .expect("error inserting subscription")
+}
+
+/// Inserts a ticket, and enqueues its creation event.
+fn create_ticket(conn: &mut PgConnection) -> model::Ticket {
+    let requester = db::user::get_with_username(conn, "carol")
+        .expect("error retrieving user from database")
+        .expect("Carol was not in the database");
+    let ticket = db::ticket::insert(
+        conn,
+        &Viewer::system(),
+        &model::NewTicket {
+            title: "UT webhook",
+            description: "",
+            priority: "normal",
+            requester_id: requester.id,
+            organisation_id: None,
+            queue_id: None,
+            category: None,
+            custom_fields: None,
+        },
+    )
+    .expect("error inserting ticket");
+
+    enqueue(conn, Event::TicketCreated, &ticket).expect("error enqueuing event");
+    ticket
+}
+
+/// Gets the only delivery of a subscription.
+fn get_delivery(conn: &mut PgConnection, subscription_id: Uuid) -> model::WebhookDelivery {
+    let mut deliveries = db::webhook::get_deliveries(conn, subscription_id, 10, 0)
+        .expect("error retrieving deliveries");
+    assert_eq!(deliveries.len(), 1, "expected a single delivery");
+
+    deliveries.remove(0)
+}
+
+/// Unit test for the retry delays.
+#[test]
+fn ut_sunny_retry_delay() {
+    assert_eq!(retry_delay(1), chrono::Duration::seconds(30));
+    assert_eq!(retry_delay(2), chrono::Duration::seconds(60));
+    assert_eq!(
+        retry_delay(MAX_ATTEMPTS - 1),
+        chrono::Duration::seconds(1920)
+    );
+    assert_eq!(retry_delay(100), chrono::Duration::hours(6));
+}
+
+/// Sunny day unit test for the `deliver_due()` function.
+#[test]
+fn ut_sunny_deliver_due() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let (url, requests) = stand_in(200, 1);
+    let subscription = insert_subscription(&mut conn, &url, &[Event::TicketCreated]);
+    let ticket = create_ticket(&mut conn);
+
+    // Updates are not part of the subscription
+    enqueue(&mut conn, Event::TicketUpdated, &ticket).expect("error enqueuing event");
+    let delivery = get_delivery(&mut conn, subscription.id);
+    assert_eq!(delivery.status(), DeliveryStatus::Pending);
+
+    // The database stores timestamps with microsecond precision
+    let now = Utc::now().trunc_subsecs(6);
+    assert!(deliver_due(&mut conn, now).expect("error delivering") >= 1);
+
+    let request = requests
+        .recv_timeout(Duration::from_secs(5))
+        .expect("the stand-in received no request");
+    assert_eq!(
+        request.header(notification::webhook::SIGNATURE_HEADER),
+        Some(notification::webhook::sign("ut_webhook_secret", &request.body).as_str())
+    );
+    assert_eq!(
+        request.header(notification::webhook::EVENT_HEADER),
+        Some("ticket.created")
+    );
+    assert_eq!(
+        request.header(notification::webhook::DELIVERY_HEADER),
+        Some(delivery.id.to_string().as_str())
+    );
+    let payload = serde_json::from_str::<PayloadDTO>(&request.body).expect("invalid payload");
+    assert_eq!(payload.event, Event::TicketCreated);
+    assert_eq!(payload.ticket.id, ticket.id);
+
+    let delivery = get_delivery(&mut conn, subscription.id);
+    assert_eq!(delivery.status(), DeliveryStatus::Delivered);
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.response_code, Some(200));
+    assert_eq!(delivery.error, None);
+}
+
+/// Rainy day unit test for the `deliver_due()` function, with retries.
+#[test]
+fn ut_rainy_deliver_due_retry() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    let (url, requests) = stand_in(500, 2);
+    let subscription = insert_subscription(&mut conn, &url, &[]);
+    let _ = create_ticket(&mut conn);
+
+    // The database stores timestamps with microsecond precision
+    let now = Utc::now().trunc_subsecs(6);
+    let _ = deliver_due(&mut conn, now).expect("error delivering");
+    let _ = requests
+        .recv_timeout(Duration::from_secs(5))
+        .expect("the stand-in received no request");
+
+    let delivery = get_delivery(&mut conn, subscription.id);
+    assert_eq!(delivery.status(), DeliveryStatus::Pending);
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.response_code, Some(500));
+    assert!(delivery.error.is_some());
+    assert_eq!(delivery.next_attempt_on, now + retry_delay(1));
+
+    // The delivery is not retried before its time
+    let _ = deliver_due(&mut conn, now).expect("error delivering");
+    assert_eq!(get_delivery(&mut conn, subscription.id).attempts, 1);
+
+    let _ = deliver_due(&mut conn, now + retry_delay(1)).expect("error delivering");
+    let _ = requests
+        .recv_timeout(Duration::from_secs(5))
+        .expect("the stand-in received no retry");
+    assert_eq!(get_delivery(&mut conn, subscription.id).attempts, 2);
+
+    let subscription = db::webhook::get_subscription_with_id(&mut conn, subscription.id)
+        .expect("error retrieving subscription")
+        .expect("subscription disappeared");
+    assert_eq!(subscription.consecutive_failures, 2);
+    assert!(subscription.enabled);
+}
+
+/// Rainy day unit test for the automatic disabling of failing subscriptions.
+#[test]
+fn ut_rainy_deliver_due_disable() {
+    let mut conn = establish_connection();
+    conn.begin_test_transaction()
+        .expect("error starting test transaction");
+
+    // Nothing listens on the port of a dropped listener
+    let url = {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("error binding listener");
+        format!(
+            "http://{}/hook",
+            listener.local_addr().expect("listener without address")
+        )
+    };
+    let subscription = insert_subscription(&mut conn, &url, &[]);
+    for _ in 0..3 {
+        let _ = create_ticket(&mut conn);
+    }
+
+    let mut now = Utc::now();
+    for _ in 0..MAX_ATTEMPTS {
+        let _ = deliver_due(&mut conn, now).expect("error delivering");
+        now = now + chrono::Duration::days(1);
+    }
+
+    let subscription = db::webhook::get_subscription_with_id(&mut conn, subscription.id)
+        .expect("error retrieving subscription")
+        .expect("subscription disappeared");
+    assert!(!subscription.enabled, "the subscription was not disabled");
+    assert_eq!(subscription.consecutive_failures, MAX_CONSECUTIVE_FAILURES);
+
+    let deliveries = db::webhook::get_deliveries(&mut conn, subscription.id, 10, 0)
+        .expect("error retrieving deliveries");
+    assert_eq!(deliveries.len(), 3);
+    assert!(deliveries
+        .iter()
+        .all(|delivery| delivery.status() == DeliveryStatus::Failed));
+    assert_eq!(
+        deliveries.iter().map(|d| d.attempts).sum::<i32>(),
+        MAX_CONSECUTIVE_FAILURES
+    );
+}
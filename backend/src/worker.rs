@@ -0,0 +1,95 @@
+//! Background workers.
+//!
+//! Workers run in their own thread, with their own database connection, so that they don't
+//! compete with request handlers for the connections of the pool.
+
+use diesel::{Connection, PgConnection};
+use rocket::{
+    error,
+    fairing::{AdHoc, Fairing},
+    info,
+};
+use std::{
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+/// Task run repeatedly by a worker.
+///
+/// It returns whether there is more work to do right away. Otherwise, the worker waits for the
+/// poll interval before running it again.
+pub type Task = fn(&mut PgConnection) -> io::Result<bool>;
+
+/// Fairing starting a worker once the application launches.
+///
+/// The `started` flag makes sure that only one instance of the worker runs per process, even if
+/// several applications are launched, as happens in tests.
+pub fn fairing(
+    name: &'static str,
+    started: &'static AtomicBool,
+    poll_interval: Duration,
+    task: Task,
+) -> impl Fairing {
+    thread_fairing(name, started, move |database_url| {
+        run(name, &database_url, poll_interval, task)
+    })
+}
+
+/// Fairing starting a thread once the application launches, that receives the database URL.
+///
+/// Like for [`fairing()`], the `started` flag makes sure that only one instance of the thread runs
+/// per process.
+pub fn thread_fairing<F>(name: &'static str, started: &'static AtomicBool, f: F) -> impl Fairing
+where
+    F: FnOnce(String) + Send + Sync + 'static,
+{
+    AdHoc::on_liftoff(name, move |rocket| {
+        Box::pin(async move {
+            if started.swap(true, Ordering::SeqCst) {
+                return;
+            }
+
+            match rocket
+                .figment()
+                .extract_inner::<String>("databases.main.url")
+            {
+                Ok(database_url) => {
+                    let _ = thread::spawn(move || f(database_url));
+                    info!("{} started", name);
+                }
+                Err(e) => error!("could not start {}: {}", name, e),
+            }
+        })
+    })
+}
+
+/// Runs a worker forever, reconnecting to the database after errors.
+fn run(name: &str, database_url: &str, poll_interval: Duration, task: Task) {
+    let mut conn = None;
+
+    loop {
+        let conn_ref = match &mut conn {
+            Some(conn) => conn,
+            None => match PgConnection::establish(database_url) {
+                Ok(new_conn) => conn.insert(new_conn),
+                Err(e) => {
+                    error!("{} could not connect to the database: {}", name, e);
+                    thread::sleep(poll_interval);
+                    continue;
+                }
+            },
+        };
+
+        match task(conn_ref) {
+            Ok(true) => {}
+            Ok(false) => thread::sleep(poll_interval),
+            Err(e) => {
+                error!("{} error: {}", name, e);
+                conn = None;
+                thread::sleep(poll_interval);
+            }
+        }
+    }
+}
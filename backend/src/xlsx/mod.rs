@@ -0,0 +1,349 @@
+//! Office Open XML workbooks.
+//!
+//! Workbooks with a single worksheet are [written](Writer) row by row, so that large sheets are
+//! never held in memory: the ZIP package is deflated on the fly, and the checksum and sizes of each
+//! of its parts are written after their data. Cells are written as inline strings or numbers,
+//! without styles.
+//!
+//! Packages don't use the ZIP64 extensions, so they are limited to 4 GiB.
+
+use crc32fast::Hasher;
+use flate2::{write::DeflateEncoder, Compression};
+use std::{
+    io::{self, Write},
+    mem,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Content types of the parts of the package.
+const CONTENT_TYPES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+    r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+    r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+    r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+    r#"<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+    r#"</Types>"#,
+);
+
+/// Relationships of the package, pointing to the workbook.
+const RELS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+    r#"</Relationships>"#,
+);
+
+/// Template of the workbook, with a `{{sheet}}` placeholder for the name of its worksheet.
+const WORKBOOK: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+    r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+    r#"<sheets><sheet name="{{sheet}}" sheetId="1" r:id="rId1"/></sheets>"#,
+    r#"</workbook>"#,
+);
+
+/// Relationships of the workbook, pointing to its worksheet.
+const WORKBOOK_RELS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+    r#"</Relationships>"#,
+);
+
+/// Start of the worksheet, before its rows.
+const SHEET_START: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+);
+
+/// End of the worksheet, after its rows.
+const SHEET_END: &str = "</sheetData></worksheet>";
+
+/// Path of the worksheet in the package.
+const SHEET_PATH: &str = "xl/worksheets/sheet1.xml";
+
+/// General purpose flags of the parts: sizes after the data, and UTF-8 names.
+const FLAGS: u16 = 0x0808;
+
+/// Version of the ZIP specification needed to extract the parts, 2.0 for deflate.
+const VERSION: u16 = 20;
+
+/// Compression method of the parts, deflate.
+const DEFLATE: u16 = 8;
+
+/// MS-DOS modification date of the parts, 1980-01-01, since workbooks are generated on demand.
+const DOS_DATE: u16 = 0x21;
+
+/// Cell of a worksheet row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cell<'c> {
+    Text(&'c str),
+    /// Number, written as an empty cell if it's not finite.
+    Number(f64),
+    Empty,
+}
+
+/// Part of the package, already written.
+#[derive(Debug)]
+struct Part {
+    path: &'static str,
+    /// Offset of the local header of the part in the package.
+    offset: u64,
+    crc: u32,
+    compressed_size: u64,
+    size: u64,
+}
+
+/// Part of the package being written.
+struct OpenPart {
+    path: &'static str,
+    offset: u64,
+    encoder: DeflateEncoder<Vec<u8>>,
+    hasher: Hasher,
+    compressed_size: u64,
+    size: u64,
+}
+
+/// Streaming writer of a workbook with a single worksheet.
+///
+/// Each method returns the bytes of the package written since the previous call, which can be
+/// empty while the compressor buffers the data.
+pub struct Writer {
+    /// Number of bytes of the package written so far.
+    written: u64,
+    parts: Vec<Part>,
+    /// The worksheet, while it's being written.
+    sheet: Option<OpenPart>,
+    /// Number of rows of the worksheet written so far.
+    rows: u32,
+}
+
+impl Writer {
+    /// Starts a workbook with a worksheet with the given name, returning the writer and the first
+    /// bytes of the package.
+    ///
+    /// Names should have at most 31 characters, none of them `[]:*?/\`.
+    pub fn start(sheet: &str) -> io::Result<(Self, Vec<u8>)> {
+        let mut writer = Self {
+            written: 0,
+            parts: Vec::new(),
+            sheet: None,
+            rows: 0,
+        };
+        let workbook = WORKBOOK.replace("{{sheet}}", &escape(sheet));
+
+        let mut bytes = Vec::new();
+        for (path, data) in [
+            ("[Content_Types].xml", CONTENT_TYPES),
+            ("_rels/.rels", RELS),
+            ("xl/workbook.xml", workbook.as_str()),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS),
+        ] {
+            let mut part = writer.open(path, &mut bytes);
+            writer.write(&mut part, data.as_bytes(), &mut bytes)?;
+            writer.close(part, &mut bytes)?;
+        }
+
+        let mut sheet = writer.open(SHEET_PATH, &mut bytes);
+        writer.write(&mut sheet, SHEET_START.as_bytes(), &mut bytes)?;
+        writer.sheet = Some(sheet);
+
+        Ok((writer, bytes))
+    }
+
+    /// Writes the next row of the worksheet.
+    pub fn write_row(&mut self, cells: &[Cell<'_>]) -> io::Result<Vec<u8>> {
+        self.rows += 1;
+        let mut row = format!(r#"<row r="{}">"#, self.rows);
+        for (index, cell) in cells.iter().enumerate() {
+            let reference = format!("{}{}", column(index), self.rows);
+            match cell {
+                Cell::Text(text) => row.push_str(&format!(
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    reference,
+                    escape(text)
+                )),
+                Cell::Number(number) if number.is_finite() => {
+                    row.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, number));
+                }
+                Cell::Number(_) | Cell::Empty => {}
+            }
+        }
+        row.push_str("</row>");
+
+        let mut bytes = Vec::new();
+        let mut sheet = self.sheet.take().expect("the worksheet was not open");
+        self.write(&mut sheet, row.as_bytes(), &mut bytes)?;
+        self.sheet = Some(sheet);
+
+        Ok(bytes)
+    }
+
+    /// Finishes the workbook, returning the last bytes of the package.
+    pub fn finish(mut self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        let mut sheet = self.sheet.take().expect("the worksheet was not open");
+        self.write(&mut sheet, SHEET_END.as_bytes(), &mut bytes)?;
+        self.close(sheet, &mut bytes)?;
+
+        // Central directory
+        let directory_offset = self.written;
+        let mut directory = Vec::new();
+        for part in &self.parts {
+            put_u32(&mut directory, 0x0201_4b50);
+            put_u16(&mut directory, VERSION);
+            put_u16(&mut directory, VERSION);
+            put_u16(&mut directory, FLAGS);
+            put_u16(&mut directory, DEFLATE);
+            put_u16(&mut directory, 0);
+            put_u16(&mut directory, DOS_DATE);
+            put_u32(&mut directory, part.crc);
+            put_u32(&mut directory, to_u32(part.compressed_size)?);
+            put_u32(&mut directory, to_u32(part.size)?);
+            put_u16(&mut directory, part.path.len() as u16);
+            // Extra field, comment, disk, internal and external attributes
+            put_u16(&mut directory, 0);
+            put_u16(&mut directory, 0);
+            put_u16(&mut directory, 0);
+            put_u16(&mut directory, 0);
+            put_u32(&mut directory, 0);
+            put_u32(&mut directory, to_u32(part.offset)?);
+            directory.extend_from_slice(part.path.as_bytes());
+        }
+
+        // End of central directory record
+        let count = self.parts.len() as u16;
+        let directory_size = directory.len() as u64;
+        put_u32(&mut directory, 0x0605_4b50);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, count);
+        put_u16(&mut directory, count);
+        put_u32(&mut directory, to_u32(directory_size)?);
+        put_u32(&mut directory, to_u32(directory_offset)?);
+        put_u16(&mut directory, 0);
+
+        self.emit(directory, &mut bytes);
+        Ok(bytes)
+    }
+
+    /// Opens a new part, writing its local header.
+    fn open(&mut self, path: &'static str, bytes: &mut Vec<u8>) -> OpenPart {
+        let part = OpenPart {
+            path,
+            offset: self.written,
+            encoder: DeflateEncoder::new(Vec::new(), Compression::default()),
+            hasher: Hasher::new(),
+            compressed_size: 0,
+            size: 0,
+        };
+
+        // The checksum and sizes are left empty, and written after the data
+        let mut header = Vec::with_capacity(30 + path.len());
+        put_u32(&mut header, 0x0403_4b50);
+        put_u16(&mut header, VERSION);
+        put_u16(&mut header, FLAGS);
+        put_u16(&mut header, DEFLATE);
+        put_u16(&mut header, 0);
+        put_u16(&mut header, DOS_DATE);
+        put_u32(&mut header, 0);
+        put_u32(&mut header, 0);
+        put_u32(&mut header, 0);
+        put_u16(&mut header, path.len() as u16);
+        put_u16(&mut header, 0);
+        header.extend_from_slice(path.as_bytes());
+        self.emit(header, bytes);
+
+        part
+    }
+
+    /// Writes data to an open part, emitting the compressed bytes available so far.
+    fn write(&mut self, part: &mut OpenPart, data: &[u8], bytes: &mut Vec<u8>) -> io::Result<()> {
+        part.hasher.update(data);
+        part.size += data.len() as u64;
+        part.encoder.write_all(data)?;
+
+        let compressed = mem::take(part.encoder.get_mut());
+        part.compressed_size += compressed.len() as u64;
+        self.emit(compressed, bytes);
+
+        Ok(())
+    }
+
+    /// Closes a part, writing the rest of its compressed data followed by its checksum and sizes.
+    fn close(&mut self, part: OpenPart, bytes: &mut Vec<u8>) -> io::Result<()> {
+        let mut data = part.encoder.finish()?;
+        let part = Part {
+            path: part.path,
+            offset: part.offset,
+            crc: part.hasher.finalize(),
+            compressed_size: part.compressed_size + data.len() as u64,
+            size: part.size,
+        };
+
+        put_u32(&mut data, 0x0807_4b50);
+        put_u32(&mut data, part.crc);
+        put_u32(&mut data, to_u32(part.compressed_size)?);
+        put_u32(&mut data, to_u32(part.size)?);
+        self.emit(data, bytes);
+        self.parts.push(part);
+
+        Ok(())
+    }
+
+    /// Appends bytes of the package to the output.
+    fn emit(&mut self, data: Vec<u8>, bytes: &mut Vec<u8>) {
+        self.written += data.len() as u64;
+        bytes.extend(data);
+    }
+}
+
+/// Gets the name of the column at an index, starting at 0: `A` to `Z`, then `AA` and so on.
+fn column(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut index = index + 1;
+    while index > 0 {
+        index -= 1;
+        name.push(b'A' + (index % 26) as u8);
+        index /= 26;
+    }
+    name.reverse();
+
+    String::from_utf8(name).expect("column names are ASCII")
+}
+
+/// Escapes a text for XML, leaving out the control characters XML doesn't allow.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Converts a size or offset to the 32 bits of the ZIP format.
+fn to_u32(value: u64) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| io::Error::new(io::ErrorKind::Other, "workbook is too large"))
+}
+
+/// Appends a little-endian 16-bit integer.
+fn put_u16(bytes: &mut Vec<u8>, value: u16) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Appends a little-endian 32-bit integer.
+fn put_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
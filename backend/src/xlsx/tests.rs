@@ -0,0 +1,120 @@
+use super::*;
+use flate2::read::DeflateDecoder;
+use std::io::Read;
+
+/// Reads a little-endian 16-bit integer of a package.
+fn u16_at(bytes: &[u8], at: usize) -> usize {
+    usize::from(u16::from_le_bytes([bytes[at], bytes[at + 1]]))
+}
+
+/// Reads a little-endian 32-bit integer of a package.
+fn u32_at(bytes: &[u8], at: usize) -> usize {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as usize
+}
+
+/// Extracts the parts of a package through its central directory, checking their checksums.
+fn extract(package: &[u8]) -> Vec<(String, String)> {
+    let end = package.len() - 22;
+    assert_eq!(u32_at(package, end), 0x0605_4b50);
+    let (count, mut at) = (u16_at(package, end + 10), u32_at(package, end + 16));
+
+    let mut parts = Vec::new();
+    for _ in 0..count {
+        assert_eq!(u32_at(package, at), 0x0201_4b50);
+        let (crc, compressed_size, size) = (
+            u32_at(package, at + 16) as u32,
+            u32_at(package, at + 20),
+            u32_at(package, at + 24),
+        );
+        let name_len = u16_at(package, at + 28);
+        let name = String::from_utf8(package[at + 46..at + 46 + name_len].to_vec())
+            .expect("part name was not UTF-8");
+        let offset = u32_at(package, at + 42);
+        at += 46 + name_len;
+
+        assert_eq!(u32_at(package, offset), 0x0403_4b50);
+        let start = offset + 30 + u16_at(package, offset + 26);
+        let mut data = String::new();
+        let _ = DeflateDecoder::new(&package[start..start + compressed_size])
+            .read_to_string(&mut data)
+            .expect("error inflating part");
+        assert_eq!(data.len(), size);
+        let mut hasher = Hasher::new();
+        hasher.update(data.as_bytes());
+        assert_eq!(hasher.finalize(), crc);
+
+        parts.push((name, data));
+    }
+
+    parts
+}
+
+/// Workbooks are valid packages with a row per written row.
+#[test]
+fn ut_sunny_workbook() {
+    let (mut writer, mut package) = Writer::start("Backlog").expect("error starting workbook");
+    package.extend(
+        writer
+            .write_row(&[Cell::Text("period"), Cell::Text("value")])
+            .expect("error writing row"),
+    );
+    package.extend(
+        writer
+            .write_row(&[Cell::Text("R&D <team>"), Cell::Number(1.5)])
+            .expect("error writing row"),
+    );
+    package.extend(writer.finish().expect("error finishing workbook"));
+
+    let parts = extract(&package);
+    assert_eq!(
+        parts
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "[Content_Types].xml",
+            "_rels/.rels",
+            "xl/workbook.xml",
+            "xl/_rels/workbook.xml.rels",
+            "xl/worksheets/sheet1.xml",
+        ]
+    );
+    assert!(parts[2]
+        .1
+        .contains(r#"<sheet name="Backlog" sheetId="1" r:id="rId1"/>"#));
+    let sheet = &parts[4].1;
+    assert!(sheet.starts_with(SHEET_START));
+    assert!(sheet.ends_with(SHEET_END));
+    assert!(sheet.contains(concat!(
+        r#"<row r="2"><c r="A2" t="inlineStr"><is><t xml:space="preserve">R&amp;D &lt;team&gt;"#,
+        r#"</t></is></c><c r="B2"><v>1.5</v></c></row>"#
+    )));
+}
+
+/// Cells that can't be written are left empty.
+#[test]
+fn ut_rainy_workbook() {
+    let (mut writer, mut package) = Writer::start("Empty").expect("error starting workbook");
+    package.extend(
+        writer
+            .write_row(&[Cell::Number(f64::NAN), Cell::Empty, Cell::Text("\u{1}ok\t")])
+            .expect("error writing row"),
+    );
+    package.extend(writer.finish().expect("error finishing workbook"));
+
+    let parts = extract(&package);
+    assert!(parts[4].1.contains(
+        r#"<row r="1"><c r="C1" t="inlineStr"><is><t xml:space="preserve">ok	</t></is></c></row>"#
+    ));
+}
+
+/// Columns are named like in spreadsheets.
+#[test]
+fn ut_sunny_column() {
+    assert_eq!(column(0), "A");
+    assert_eq!(column(25), "Z");
+    assert_eq!(column(26), "AA");
+    assert_eq!(column(27), "AB");
+    assert_eq!(column(701), "ZZ");
+    assert_eq!(column(702), "AAA");
+}
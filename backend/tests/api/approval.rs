@@ -0,0 +1,348 @@
+use crate::{logged_in_client, sync_client, user_id};
+use common::{
+    approval::{ApprovalChainDTO, ApprovalDTO, PendingApprovalDTO, Status as ApprovalStatus},
+    catalog::{CatalogItemDTO, FieldError},
+    notification::{Kind, NotificationDTO},
+    ticket::{Status as TicketStatus, TicketDTO},
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Creates an approval chain as Alice, asking Bob and then Alice.
+fn create_chain(alice: &Client) -> ApprovalChainDTO {
+    let (alice_id, bob_id) = (user_id("alice"), user_id("bob"));
+    let response = alice
+        .post("/api/v1/approval-chains")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "name": format!("IT approval {}", Uuid::new_v4()),
+                "steps": [
+                    {"mode": "any", "approvers": [{"type": "user", "id": bob_id}]},
+                    {"mode": "all", "approvers": [{"type": "user", "id": alice_id}]},
+                ],
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<ApprovalChainDTO, String>>()
+        .expect("body was not a valid chain")
+        .expect("chain was not created")
+}
+
+/// Opens a ticket as Carol, from a new item of the catalog that needs the given chain.
+fn request(alice: &Client, carol: &Client, chain_id: Uuid) -> TicketDTO {
+    let item = alice
+        .post("/api/v1/catalog")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "name": format!("IT approval {}", Uuid::new_v4()),
+                "description": "",
+                "form": [{"key": "reason", "label": "Reason", "type": "text"}],
+                "priority": "normal",
+                "approval_chain_id": chain_id,
+                "active": true,
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<CatalogItemDTO, String>>()
+        .expect("body was not a valid catalog item")
+        .expect("catalog item was not created");
+    assert_eq!(item.approval_chain_id, Some(chain_id));
+
+    carol
+        .post(format!("/api/v1/catalog/{}/requests", item.id))
+        .header(ContentType::JSON)
+        .body(json!({"answers": {}}).to_string())
+        .dispatch()
+        .into_json::<Result<TicketDTO, Vec<FieldError>>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created")
+}
+
+/// Sunny integration test for a ticket approved in the application and from an email link.
+#[test]
+fn it_sunny_approval() {
+    let (alice, bob, carol) = (
+        logged_in_client("alice"),
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+    );
+    let chain = create_chain(&alice);
+    let ticket = request(&alice, &carol, chain.id);
+    assert_eq!(ticket.status, TicketStatus::Pending);
+
+    assert!(bob
+        .get("/api/v1/approvals")
+        .dispatch()
+        .into_json::<Vec<PendingApprovalDTO>>()
+        .expect("body was not a valid approval list")
+        .iter()
+        .any(|pending| pending.ticket_id == ticket.id));
+    let response = bob
+        .post(format!("/api/v1/tickets/{}/approval", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"decision": "approved", "comment": "Within budget"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let approval = response
+        .into_json::<Result<ApprovalDTO, String>>()
+        .expect("body was not a valid approval")
+        .expect("decision was not recorded");
+    assert_eq!(
+        (approval.status, approval.step),
+        (ApprovalStatus::Pending, 1)
+    );
+
+    // Alice decides from the link of her notification, without logging in
+    let body = alice
+        .get("/api/v1/notifications?limit=500")
+        .dispatch()
+        .into_json::<Vec<NotificationDTO>>()
+        .expect("body was not a valid notification list")
+        .into_iter()
+        .find(|n| n.kind == Kind::ApprovalRequested && n.ticket_id == Some(ticket.id))
+        .expect("Alice was not asked to approve")
+        .body;
+    let token = body
+        .rsplit("/approvals/")
+        .next()
+        .expect("notification had no link");
+    let anonymous = sync_client();
+    let pending = anonymous
+        .get(format!("/api/v1/approvals/{}", token))
+        .dispatch()
+        .into_json::<Option<PendingApprovalDTO>>()
+        .expect("body was not a valid approval")
+        .expect("approval was not found");
+    assert_eq!(pending.ticket_id, ticket.id);
+    let response = anonymous
+        .post(format!("/api/v1/approvals/{}", token))
+        .header(ContentType::JSON)
+        .body(json!({"decision": "approved"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    assert_eq!(
+        response
+            .into_json::<Result<ApprovalStatus, String>>()
+            .expect("body was not a valid approval status"),
+        Ok(ApprovalStatus::Approved)
+    );
+
+    let ticket = carol
+        .get(format!("/api/v1/tickets/{}", ticket.id))
+        .dispatch()
+        .into_json::<Option<TicketDTO>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not found");
+    assert_eq!(ticket.status, TicketStatus::Open);
+    let approval = carol
+        .get(format!("/api/v1/tickets/{}/approval", ticket.id))
+        .dispatch()
+        .into_json::<Option<ApprovalDTO>>()
+        .expect("body was not a valid approval")
+        .expect("approval was not found");
+    assert_eq!(approval.status, ApprovalStatus::Approved);
+    assert_eq!(approval.approvers[0].comment, "Within budget");
+
+    // Links can't be used twice
+    assert_eq!(
+        anonymous
+            .get(format!("/api/v1/approvals/{}", token))
+            .dispatch()
+            .status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+}
+
+/// Rainy integration test for approvals.
+#[test]
+fn it_rainy_approval() {
+    let (alice, carol) = (logged_in_client("alice"), logged_in_client("carol"));
+
+    let response = alice
+        .post("/api/v1/approval-chains")
+        .header(ContentType::JSON)
+        .body(json!({"name": "IT approval without steps", "steps": []}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+    let response = carol
+        .post("/api/v1/approval-chains")
+        .header(ContentType::JSON)
+        .body(json!({"name": "IT approval by a customer", "steps": []}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+    let response = alice
+        .post("/api/v1/catalog")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "name": format!("IT approval {}", Uuid::new_v4()),
+                "description": "",
+                "form": [{"key": "reason", "label": "Reason", "type": "text"}],
+                "priority": "normal",
+                "approval_chain_id": Uuid::new_v4(),
+                "active": true,
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    // Requesters see the approval of their tickets, but can't decide on it
+    let chain = create_chain(&alice);
+    let ticket = request(&alice, &carol, chain.id);
+    let response = carol
+        .post(format!("/api/v1/tickets/{}/approval", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"decision": "approved"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Conflict,
+        "response HTTP status code was not 409 Conflict"
+    );
+    let dave = logged_in_client("dave");
+    let response = dave
+        .get(format!("/api/v1/tickets/{}/approval", ticket.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+
+    let bob = logged_in_client("bob");
+    let response = bob
+        .post(format!("/api/v1/tickets/{}/approval", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"decision": "rejected", "comment": "x".repeat(1001)}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+    let response = bob
+        .post(format!("/api/v1/tickets/{}/approval", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"decision": "rejected"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response
+            .into_json::<Result<ApprovalDTO, String>>()
+            .expect("body was not a valid approval")
+            .expect("decision was not recorded")
+            .status,
+        ApprovalStatus::Rejected
+    );
+    let ticket = carol
+        .get(format!("/api/v1/tickets/{}", ticket.id))
+        .dispatch()
+        .into_json::<Option<TicketDTO>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not found");
+    assert_eq!(ticket.status, TicketStatus::Closed);
+
+    let anonymous = sync_client();
+    let response = anonymous
+        .post("/api/v1/approvals/not-a-token")
+        .header(ContentType::JSON)
+        .body(json!({"decision": "approved"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+}
+
+/// Rainy integration test for a requester picking themselves as the approver of their request.
+#[test]
+fn it_rainy_self_approval() {
+    let (alice, carol) = (logged_in_client("alice"), logged_in_client("carol"));
+    let carol_id = user_id("carol");
+
+    let chain = alice
+        .post("/api/v1/approval-chains")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "name": format!("IT approval {}", Uuid::new_v4()),
+                "steps": [{"mode": "any", "approvers": [{"type": "field", "key": "manager"}]}],
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<ApprovalChainDTO, String>>()
+        .expect("body was not a valid chain")
+        .expect("chain was not created");
+    let item = alice
+        .post("/api/v1/catalog")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "name": format!("IT approval {}", Uuid::new_v4()),
+                "description": "",
+                "form": [{"key": "manager", "label": "Manager", "type": "user"}],
+                "priority": "normal",
+                "approval_chain_id": chain.id,
+                "active": true,
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<CatalogItemDTO, String>>()
+        .expect("body was not a valid catalog item")
+        .expect("catalog item was not created");
+
+    let response = carol
+        .post(format!("/api/v1/catalog/{}/requests", item.id))
+        .header(ContentType::JSON)
+        .body(json!({"answers": {"manager": carol_id}}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+    let errors = response
+        .into_json::<Result<TicketDTO, Vec<FieldError>>>()
+        .expect("body was not a valid list of errors")
+        .expect_err("ticket was opened");
+    assert!(errors.iter().any(|error| error.field == "manager"));
+}
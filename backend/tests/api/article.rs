@@ -0,0 +1,252 @@
+use crate::{logged_in_client, sync_client};
+use common::{
+    article::{ArticleDTO, ArticleSummaryDTO, Change, DiffDTO, RevisionDTO},
+    ticket::TicketDTO,
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use uuid::Uuid;
+
+/// Writes an article as Bob.
+fn write_article(client: &Client, title: &str, body: &str, visibility: &str) -> ArticleDTO {
+    let response = client
+        .post("/api/v1/articles")
+        .header(ContentType::JSON)
+        .body(
+            serde_json::json!({
+                "category_id": null,
+                "title": title,
+                "body": body,
+                "status": "published",
+                "visibility": visibility,
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<ArticleDTO, String>>()
+        .expect("body was not a valid article")
+        .expect("article was not created")
+}
+
+/// Sunny integration test for knowledge base articles, their revisions and ticket links.
+#[test]
+fn it_sunny_articles() {
+    let bob = logged_in_client("bob");
+    let title = format!("IT article {}", Uuid::new_v4());
+    let article = write_article(
+        &bob,
+        &title,
+        "Restart the printer.\n\n<script>alert(1)</script>",
+        "public",
+    );
+    assert_eq!(article.html, "<p>Restart the printer.</p>\n");
+
+    // Public articles can be read without logging in
+    let anonymous = sync_client();
+    let read = anonymous
+        .get(format!("/api/v1/articles/{}", article.id))
+        .dispatch()
+        .into_json::<Option<ArticleDTO>>()
+        .expect("body was not a valid article");
+    assert_eq!(read, Some(article.clone()));
+    assert!(anonymous
+        .get("/api/v1/articles?limit=500")
+        .dispatch()
+        .into_json::<Vec<ArticleSummaryDTO>>()
+        .expect("body was not a valid article list")
+        .iter()
+        .any(|summary| summary.id == article.id));
+
+    // Searches only match the articles with all the words
+    let searched = anonymous
+        .get(format!("/api/v1/articles?q={}", title.replace(' ', "%20")))
+        .dispatch()
+        .into_json::<Vec<ArticleSummaryDTO>>()
+        .expect("body was not a valid article list");
+    assert_eq!(
+        searched
+            .iter()
+            .map(|summary| summary.id)
+            .collect::<Vec<_>>(),
+        vec![article.id]
+    );
+
+    let updated = bob
+        .put(format!("/api/v1/articles/{}", article.id))
+        .header(ContentType::JSON)
+        .body(
+            serde_json::json!({
+                "category_id": null,
+                "title": title,
+                "body": "Unplug the printer.",
+                "status": "published",
+                "visibility": "public",
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<ArticleDTO, String>>()
+        .expect("body was not a valid article")
+        .expect("article was not updated");
+    assert_eq!(updated.revision, 2);
+
+    let revisions = bob
+        .get(format!("/api/v1/articles/{}/revisions", article.id))
+        .dispatch()
+        .into_json::<Vec<RevisionDTO>>()
+        .expect("body was not a valid revision list");
+    assert_eq!(revisions.len(), 2);
+    let diff = bob
+        .get(format!("/api/v1/articles/{}/diff", article.id))
+        .dispatch()
+        .into_json::<Option<DiffDTO>>()
+        .expect("body was not a valid diff")
+        .expect("diff was not found");
+    assert_eq!((diff.from, diff.to), (1, 2));
+    assert!(diff
+        .body
+        .iter()
+        .any(|line| line.change == Change::Insert && line.text == "Unplug the printer."));
+
+    // Links to tickets are visible to the requester
+    let carol = logged_in_client("carol");
+    let ticket = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"IT article link","description":"","priority":"low"}"#)
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+    let response = bob
+        .post(format!("/api/v1/tickets/{}/articles", ticket.id))
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"article_id":"{}"}}"#, article.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let linked = carol
+        .get(format!("/api/v1/tickets/{}/articles", ticket.id))
+        .dispatch()
+        .into_json::<Vec<ArticleSummaryDTO>>()
+        .expect("body was not a valid article list");
+    assert_eq!(
+        linked.iter().map(|summary| summary.id).collect::<Vec<_>>(),
+        vec![article.id]
+    );
+
+    let alice = logged_in_client("alice");
+    let response = alice
+        .delete(format!("/api/v1/articles/{}", article.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Rainy integration test for knowledge base articles: only their audience can read them.
+#[test]
+fn it_rainy_articles() {
+    let bob = logged_in_client("bob");
+    let internal = write_article(
+        &bob,
+        &format!("IT internal article {}", Uuid::new_v4()),
+        "Internal notes.",
+        "internal",
+    );
+    let customers = write_article(
+        &bob,
+        &format!("IT customer article {}", Uuid::new_v4()),
+        "For customers.",
+        "customers",
+    );
+
+    let anonymous = sync_client();
+    let carol = logged_in_client("carol");
+    for (client, article) in [
+        (&anonymous, &internal),
+        (&anonymous, &customers),
+        (&carol, &internal),
+    ] {
+        let response = client
+            .get(format!("/api/v1/articles/{}", article.id))
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::NotFound,
+            "response HTTP status code was not 404 Not Found"
+        );
+    }
+    let response = carol
+        .get(format!("/api/v1/articles/{}", customers.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    // Only agents can write articles and see revisions
+    for response in [
+        carol
+            .post("/api/v1/articles")
+            .header(ContentType::JSON)
+            .body(r#"{"category_id":null,"title":"IT forbidden","body":""}"#)
+            .dispatch(),
+        carol
+            .get(format!("/api/v1/articles/{}/revisions", customers.id))
+            .dispatch(),
+    ] {
+        assert_eq!(
+            response.status(),
+            Status::Forbidden,
+            "response HTTP status code was not 403 Forbidden"
+        );
+    }
+
+    let response = bob
+        .post("/api/v1/articles")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"category_id":"{}","title":"IT no category","body":""}}"#,
+            Uuid::new_v4()
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+    for query in ["to=0", "to=-2147483648", "from=-1&to=1"] {
+        let response = bob
+            .get(format!("/api/v1/articles/{}/diff?{}", internal.id, query))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest, "{}", query);
+    }
+
+    let alice = logged_in_client("alice");
+    for article in [internal, customers] {
+        let response = alice
+            .delete(format!("/api/v1/articles/{}", article.id))
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::NoContent,
+            "response HTTP status code was not 204 No Content"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::logged_in_client;
+use common::audit::{AuditEntryDTO, AuditEvent};
+use rocket::http::Status;
+
+/// Sunny integration test for the `/api/v1/audit` endpoint.
+#[test]
+fn it_sunny_audit_query() {
+    let client = logged_in_client("alice");
+    let response = client
+        .get("/api/v1/audit?kind=login_success&limit=10")
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    let entries = response
+        .into_json::<Vec<AuditEntryDTO>>()
+        .expect("body was not a valid list of audit entries");
+    assert!(!entries.is_empty(), "the login was not audited");
+    assert!(entries
+        .iter()
+        .all(|entry| entry.event == AuditEvent::LoginSuccess));
+}
+
+/// Rainy integration test for the `/api/v1/audit` endpoint as an agent.
+#[test]
+fn it_rainy_audit_query_agent() {
+    let client = logged_in_client("bob");
+    let response = client.get("/api/v1/audit").dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Rainy integration test for the `/api/v1/audit` endpoint with an invalid date.
+#[test]
+fn it_rainy_audit_query_invalid_date() {
+    let client = logged_in_client("alice");
+    let response = client.get("/api/v1/audit?since=yesterday").dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+}
@@ -0,0 +1,85 @@
+use crate::{logged_in_client, sync_client};
+use common::user::{Role, UserDTO};
+use rocket::http::{ContentType, Status};
+
+/// Sunny integration test for the `/api/v1/login` endpoint.
+#[test]
+fn it_sunny_login() {
+    let client = logged_in_client("alice");
+
+    let response = client.get("/api/v1/login").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    let user = response
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+    assert_eq!(user.username, "alice");
+    assert_eq!(user.role, Role::Admin);
+
+    let response = client.post("/api/v1/logout").dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+
+    let response = client.get("/api/v1/login").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Unauthorized,
+        "the session was still valid after logging out"
+    );
+}
+
+/// Sunny integration test for the session cookie of the `/api/v1/login` endpoint.
+#[test]
+fn it_sunny_login_cookie() {
+    let client = sync_client();
+    let response = client
+        .post("/api/v1/login")
+        .header(ContentType::JSON)
+        .body(r#"{"user":"bob","pass":"bob_password"}"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    let cookie = response
+        .cookies()
+        .get("session")
+        .expect("no session cookie was set");
+    assert_eq!(cookie.secure(), Some(true));
+    assert_eq!(cookie.http_only(), Some(true));
+}
+
+/// Rainy integration test for the `/api/v1/login` endpoint with a wrong password.
+#[test]
+fn it_rainy_login_wrong_password() {
+    let client = sync_client();
+    let response = client
+        .post("/api/v1/login")
+        .header(ContentType::JSON)
+        .body(r#"{"user":"alice","pass":"bob_password"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Unauthorized,
+        "response HTTP status code was not 401 Unauthorized"
+    );
+}
+
+/// Rainy integration test for the `/api/v1/login` endpoint without logging in.
+#[test]
+fn it_rainy_current_user_not_logged_in() {
+    let client = sync_client();
+    let response = client.get("/api/v1/login").dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Unauthorized,
+        "response HTTP status code was not 401 Unauthorized"
+    );
+}
@@ -0,0 +1,211 @@
+use crate::{logged_in_client, open_ticket};
+use common::{
+    automation::{DryRunResultDTO, LogEntryDTO, RuleDTO},
+    ticket::TicketDTO,
+    webhook::SubscriptionDTO,
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use uuid::Uuid;
+
+/// Creates a rule matching tickets with the given title.
+fn create_rule(client: &Client, title: &str, actions: &str) -> RuleDTO {
+    let response = client
+        .post("/api/v1/automations")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"name":"{0}","condition":{{"type":"equals","field":"title","value":"{0}"}},"actions":{1}}}"#,
+            title, actions
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<RuleDTO, String>>()
+        .expect("body was not a valid rule")
+        .expect("rule was not created")
+}
+
+/// Sunny integration test for running automation rules on new tickets.
+#[test]
+fn it_sunny_automation() {
+    let client = logged_in_client("alice");
+    let title = format!("IT automation {}", Uuid::new_v4());
+    let rule = create_rule(
+        &client,
+        &title,
+        r#"[{"type":"set_field","field":"category","value":"network"},{"type":"add_tag","tag":"Automated"}]"#,
+    );
+
+    let ticket = open_ticket(&logged_in_client("carol"), &title);
+    assert_eq!(ticket.category.as_deref(), Some("network"));
+    assert_eq!(ticket.tags, vec!["automated".to_owned()]);
+
+    let log = client
+        .get(format!("/api/v1/automations/log?rule={}", rule.id))
+        .dispatch()
+        .into_json::<Vec<LogEntryDTO>>()
+        .expect("body was not a valid execution log");
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].ticket_id, ticket.id);
+
+    let response = client
+        .delete(format!("/api/v1/automations/{}", rule.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Sunny integration test for the errors of webhooks in the execution log.
+#[test]
+fn it_sunny_automation_webhook_error() {
+    let client = logged_in_client("alice");
+    let subscription = client
+        .post("/api/v1/webhooks")
+        .header(ContentType::JSON)
+        .body(r#"{"url":"http://127.0.0.1:9/unreachable","events":["ticket.updated"]}"#)
+        .dispatch()
+        .into_json::<Result<SubscriptionDTO, String>>()
+        .expect("body was not a valid subscription")
+        .expect("subscription was not created");
+    let title = format!("IT webhook {}", Uuid::new_v4());
+    let rule = create_rule(
+        &client,
+        &title,
+        &format!(
+            r#"[{{"type":"call_webhook","subscription_id":"{}"}}]"#,
+            subscription.id
+        ),
+    );
+
+    let _ = open_ticket(&logged_in_client("carol"), &title);
+    let log = client
+        .get(format!("/api/v1/automations/log?rule={}", rule.id))
+        .dispatch()
+        .into_json::<Vec<LogEntryDTO>>()
+        .expect("body was not a valid execution log");
+    assert_eq!(log.len(), 1);
+    assert!(log[0].error.is_some(), "the webhook error was not logged");
+
+    let _ = client
+        .delete(format!("/api/v1/automations/{}", rule.id))
+        .dispatch();
+    let _ = client
+        .delete(format!("/api/v1/webhooks/{}", subscription.id))
+        .dispatch();
+}
+
+/// Rainy integration test for a rule calling a webhook subscription that doesn't exist.
+#[test]
+fn it_rainy_automation_webhook_not_found() {
+    let client = logged_in_client("alice");
+    let response = client
+        .post("/api/v1/automations")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"name":"IT missing webhook","condition":{{"type":"event","event":"created"}},"actions":[{{"type":"call_webhook","subscription_id":"{}"}}]}}"#,
+            Uuid::new_v4()
+        ))
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+}
+
+/// Sunny integration test for the `/api/v1/automations/dry-run` endpoint.
+#[test]
+fn it_sunny_dry_run() {
+    let ticket = open_ticket(&logged_in_client("carol"), "IT dry run");
+    let client = logged_in_client("alice");
+
+    let response = client
+        .post("/api/v1/automations/dry-run")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"ticket_id":"{}","rule":{{"name":"IT dry run","condition":{{"type":"equals","field":"priority","value":"normal"}},"actions":[{{"type":"set_field","field":"status","value":"pending"}}]}}}}"#,
+            ticket.id
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let result = response
+        .into_json::<Result<DryRunResultDTO, String>>()
+        .expect("body was not a valid dry run result")
+        .expect("dry run failed");
+    assert!(result.matched);
+    assert_eq!(result.ticket.status.as_str(), "pending");
+
+    let stored = client
+        .get(format!("/api/v1/tickets/{}", ticket.id))
+        .dispatch()
+        .into_json::<TicketDTO>()
+        .expect("body was not a valid ticket");
+    assert_eq!(
+        stored.status, ticket.status,
+        "the dry run changed the ticket"
+    );
+}
+
+/// Rainy integration test for creating an invalid rule.
+#[test]
+fn it_rainy_create_rule_invalid() {
+    let client = logged_in_client("alice");
+    let response = client
+        .post("/api/v1/automations")
+        .header(ContentType::JSON)
+        .body(r#"{"name":"IT invalid","condition":{"type":"always"},"actions":[{"type":"set_field","field":"requester_id","value":null}]}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+}
+
+/// Rainy integration test for creating a rule as an agent.
+#[test]
+fn it_rainy_create_rule_agent() {
+    let client = logged_in_client("bob");
+    let response = client
+        .post("/api/v1/automations")
+        .header(ContentType::JSON)
+        .body(r#"{"name":"IT agent rule","condition":{"type":"always"},"actions":[{"type":"add_tag","tag":"agent"}]}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Rainy integration test for getting a missing rule.
+#[test]
+fn it_rainy_get_rule_missing() {
+    let client = logged_in_client("alice");
+    let response = client
+        .get(format!("/api/v1/automations/{}", Uuid::new_v4()))
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+}
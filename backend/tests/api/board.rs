@@ -0,0 +1,188 @@
+use crate::{logged_in_client, open_ticket, user_id};
+use common::{
+    board::{BoardDTO, ColumnSettingsDTO, Grouping},
+    ticket::{Status as TicketStatus, TicketDTO},
+};
+use rocket::http::{ContentType, Status};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Sunny integration test for ticket boards: tickets are grouped in columns, move between them
+/// when updated, and columns get the limits set by administrators, which refuse tickets once
+/// reached.
+#[test]
+fn it_sunny_board() {
+    let carol = logged_in_client("carol");
+    let ticket = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(json!({"title": "IT board", "description": "Card to move."}).to_string())
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+
+    let bob = logged_in_client("bob");
+    let response = bob.get("/api/v1/board").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let board = response
+        .into_json::<Result<BoardDTO, String>>()
+        .expect("body was not a valid board")
+        .expect("board was not loaded");
+    assert_eq!(board.grouping, Grouping::Status);
+    assert_eq!(
+        board.columns[..4]
+            .iter()
+            .map(|column| column.key.as_str())
+            .collect::<Vec<_>>(),
+        vec!["new", "open", "pending", "resolved"]
+    );
+    let key = Grouping::Status.key(&ticket);
+    assert!(board
+        .columns
+        .iter()
+        .find(|column| column.key == key)
+        .expect("column of the ticket was missing")
+        .tickets
+        .iter()
+        .any(|t| t.id == ticket.id));
+
+    // Moving a card updates the ticket, which is also tagged to find it on the board
+    let tag = format!("board-{}", &Uuid::new_v4().to_simple().to_string()[..8]);
+    let mut update = Grouping::Status
+        .update("pending")
+        .expect("pending column was invalid");
+    update.tags = Some(vec![tag.clone()]);
+    let moved = bob
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(serde_json::to_string(&update).expect("error serializing ticket update"))
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not moved");
+    assert_eq!(moved.status, TicketStatus::Pending);
+
+    // Limits are set on the column of an assignee that no other test assigns tickets to
+    let alice = logged_in_client("alice");
+    let alice_id = user_id("alice").to_string();
+    let assign = |id: Uuid| {
+        let update = Grouping::Assignee
+            .update(&alice_id)
+            .expect("column of the assignee was invalid");
+        bob.patch(format!("/api/v1/tickets/{}", id))
+            .header(ContentType::JSON)
+            .body(serde_json::to_string(&update).expect("error serializing ticket update"))
+            .dispatch()
+    };
+    let limit_url = format!("/api/v1/board/assignee/columns/{}", alice_id);
+    let response = assign(ticket.id);
+    assert_eq!(response.status(), Status::Ok);
+    let response = alice
+        .put(&limit_url)
+        .header(ContentType::JSON)
+        .body(json!({"wip_limit": 1}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let board = bob
+        .get(format!("/api/v1/board?group=assignee&q=tag:{}", tag))
+        .dispatch()
+        .into_json::<Result<BoardDTO, String>>()
+        .expect("body was not a valid board")
+        .expect("board was not loaded");
+    let column = board
+        .columns
+        .iter()
+        .find(|column| column.key == alice_id)
+        .expect("column of the assignee was missing");
+    assert!(!board.truncated);
+    assert_eq!(column.wip_limit, Some(1));
+    assert_eq!(
+        column.tickets.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![ticket.id]
+    );
+
+    // Full columns refuse new tickets, but tickets already in them can still change
+    let other = open_ticket(&carol, "IT board overflow");
+    let response = assign(other.id);
+    assert_eq!(response.status(), Status::Conflict);
+    let response = bob
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"status": "open"}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = alice
+        .put(&limit_url)
+        .header(ContentType::JSON)
+        .body(
+            serde_json::to_string(&ColumnSettingsDTO { wip_limit: None })
+                .expect("error serializing column settings"),
+        )
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let response = assign(other.id);
+    assert_eq!(response.status(), Status::Ok);
+
+    let board = bob
+        .get("/api/v1/board?group=assignee")
+        .dispatch()
+        .into_json::<Result<BoardDTO, String>>()
+        .expect("body was not a valid board")
+        .expect("board was not loaded");
+    assert_eq!(board.columns[0].key, "unassigned");
+    assert!(board
+        .columns
+        .iter()
+        .any(|column| column.title == "Bob Smith"));
+    assert!(board
+        .columns
+        .iter()
+        .any(|column| column.key == alice_id && column.tickets.iter().any(|t| t.id == other.id)));
+}
+
+/// Rainy integration test for ticket boards: customers have no board, and only administrators
+/// set valid limits on existing columns of active tickets.
+#[test]
+fn it_rainy_board() {
+    let carol = logged_in_client("carol");
+    let response = carol.get("/api/v1/board").dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let bob = logged_in_client("bob");
+    for url in ["/api/v1/board?group=weather", "/api/v1/board?q=status:"] {
+        let response = bob.get(url).dispatch();
+        assert_eq!(response.status(), Status::BadRequest, "{}", url);
+    }
+    let response = bob
+        .put("/api/v1/board/status/columns/open")
+        .header(ContentType::JSON)
+        .body(json!({"wip_limit": 3}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let alice = logged_in_client("alice");
+    for (url, limit, status) in [
+        ("/api/v1/board/status/columns/open", 0, Status::BadRequest),
+        (
+            "/api/v1/board/status/columns/resolved",
+            3,
+            Status::BadRequest,
+        ),
+        ("/api/v1/board/status/columns/archived", 3, Status::NotFound),
+        ("/api/v1/board/assignee/columns/bob", 3, Status::NotFound),
+        ("/api/v1/board/weather/columns/open", 3, Status::NotFound),
+    ] {
+        let response = alice
+            .put(url)
+            .header(ContentType::JSON)
+            .body(json!({ "wip_limit": limit }).to_string())
+            .dispatch();
+        assert_eq!(response.status(), status, "{}", url);
+    }
+}
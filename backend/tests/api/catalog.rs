@@ -0,0 +1,225 @@
+use crate::logged_in_client;
+use common::{
+    catalog::{CatalogItemDTO, FieldError, PickableUserDTO, ServiceRequestDTO},
+    ticket::{Priority, TicketDTO},
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Gets the definition of a VPN access form.
+fn vpn_form() -> serde_json::Value {
+    json!([
+        {"key": "access", "label": "Access", "required": true, "type": "select",
+         "options": ["Office", "Datacenter"]},
+        {"key": "rack", "label": "Rack", "required": true, "type": "text",
+         "visible_if": {"field": "access", "equals": "Datacenter"}},
+        {"key": "manager", "label": "Manager", "type": "user"},
+    ])
+}
+
+/// Creates an item of the catalog as Alice.
+fn create_item(alice: &Client, name: &str, active: bool) -> CatalogItemDTO {
+    let response = alice
+        .post("/api/v1/catalog")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "name": name,
+                "description": "Remote access to the network",
+                "form": vpn_form(),
+                "priority": "high",
+                "active": active,
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<CatalogItemDTO, String>>()
+        .expect("body was not a valid catalog item")
+        .expect("catalog item was not created")
+}
+
+/// Gets the ID of a user that a client can pick.
+fn pick(client: &Client, username: &str) -> Option<Uuid> {
+    client
+        .get(format!("/api/v1/catalog/users?q={}", username))
+        .dispatch()
+        .into_json::<Vec<PickableUserDTO>>()
+        .expect("body was not a valid user list")
+        .into_iter()
+        .find(|user| user.username == username)
+        .map(|user| user.id)
+}
+
+/// Sunny integration test for requesting an item of the catalog.
+#[test]
+fn it_sunny_catalog_request() {
+    let alice = logged_in_client("alice");
+    let item = create_item(&alice, &format!("IT catalog {}", Uuid::new_v4()), true);
+
+    let carol = logged_in_client("carol");
+    assert!(carol
+        .get("/api/v1/catalog")
+        .dispatch()
+        .into_json::<Vec<CatalogItemDTO>>()
+        .expect("body was not a valid catalog")
+        .contains(&item));
+
+    let carol_id = pick(&carol, "carol").expect("Carol can't pick herself");
+    let response = carol
+        .post(format!("/api/v1/catalog/{}/requests", item.id))
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "answers": {"access": "Datacenter", "rack": " R-42 ", "manager": carol_id},
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let ticket = response
+        .into_json::<Result<TicketDTO, Vec<FieldError>>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+    assert_eq!(ticket.title, item.name);
+    assert_eq!(ticket.priority, Priority::High);
+    assert_eq!(
+        ticket.description,
+        "Access: Datacenter\nRack: R-42\nManager: carol"
+    );
+
+    // Agents see the structured answers of the ticket
+    let request = logged_in_client("bob")
+        .get(format!("/api/v1/tickets/{}/request", ticket.id))
+        .dispatch()
+        .into_json::<Option<ServiceRequestDTO>>()
+        .expect("body was not a valid service request")
+        .expect("service request was not found");
+    assert_eq!(request.catalog_item_id, Some(item.id));
+    assert_eq!(request.form, item.form);
+    assert_eq!(
+        serde_json::to_value(&request.answers).expect("error serializing answers"),
+        json!({"access": "Datacenter", "rack": "R-42", "manager": carol_id})
+    );
+
+    let response = alice
+        .delete(format!("/api/v1/catalog/{}", item.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Rainy integration test for invalid requests and catalog items.
+#[test]
+fn it_rainy_catalog_request() {
+    let alice = logged_in_client("alice");
+    let name = format!("IT catalog {}", Uuid::new_v4());
+    let item = create_item(&alice, &name, true);
+    let inactive = create_item(&alice, &format!("IT catalog {}", Uuid::new_v4()), false);
+
+    let response = alice
+        .post("/api/v1/catalog")
+        .header(ContentType::JSON)
+        .body(json!({"name": name, "form": vpn_form(), "active": true}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Conflict,
+        "response HTTP status code was not 409 Conflict"
+    );
+    let response = alice
+        .put(format!("/api/v1/catalog/{}", item.id))
+        .header(ContentType::JSON)
+        .body(json!({"name": name, "form": [], "active": true}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    let carol = logged_in_client("carol");
+    let response = carol
+        .post("/api/v1/catalog")
+        .header(ContentType::JSON)
+        .body(json!({"name": "Free laptop", "form": vpn_form(), "active": true}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+    assert!(!carol
+        .get("/api/v1/catalog?all=true")
+        .dispatch()
+        .into_json::<Vec<CatalogItemDTO>>()
+        .expect("body was not a valid catalog")
+        .contains(&inactive));
+
+    // Customers can't pick users from other organisations
+    let dave_id = pick(&alice, "dave").expect("Alice can't pick Dave");
+    assert_eq!(pick(&carol, "dave"), None);
+    let response = carol
+        .post(format!("/api/v1/catalog/{}/requests", item.id))
+        .header(ContentType::JSON)
+        .body(json!({"answers": {"access": "Office", "manager": dave_id}}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+    let errors = response
+        .into_json::<Result<TicketDTO, Vec<FieldError>>>()
+        .expect("body was not a valid response")
+        .expect_err("ticket was created");
+    assert_eq!(errors[0].field, "manager");
+
+    let errors = carol
+        .post(format!("/api/v1/catalog/{}/requests", item.id))
+        .header(ContentType::JSON)
+        .body(json!({"answers": {"access": "Datacenter"}}).to_string())
+        .dispatch()
+        .into_json::<Result<TicketDTO, Vec<FieldError>>>()
+        .expect("body was not a valid response")
+        .expect_err("ticket was created");
+    assert_eq!(
+        errors,
+        vec![FieldError {
+            field: "rack".to_owned(),
+            message: "this field is required".to_owned(),
+        }]
+    );
+
+    let response = carol
+        .post(format!("/api/v1/catalog/{}/requests", inactive.id))
+        .header(ContentType::JSON)
+        .body(json!({"answers": {"access": "Office"}}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+
+    for id in [item.id, inactive.id] {
+        let _ = alice.delete(format!("/api/v1/catalog/{}", id)).dispatch();
+    }
+}
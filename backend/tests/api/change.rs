@@ -0,0 +1,404 @@
+use crate::{logged_in_client, sync_client};
+use common::{
+    approval::ApprovalChainDTO,
+    catalog::PickableUserDTO,
+    change::{CabChainDTO, ChangeDTO, CollisionDTO, MaintenanceWindowDTO, Risk},
+    cmdb::{CiTypeDTO, ConfigurationItemDTO},
+    ticket::Status as TicketStatus,
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Creates configuration items of a new type as Alice, returning their IDs.
+fn create_items(alice: &Client, token: &str, count: usize) -> Vec<Uuid> {
+    let ci_type = alice
+        .post("/api/v1/ci-types")
+        .header(ContentType::JSON)
+        .body(json!({"name": format!("IT server {}", token), "attributes": []}).to_string())
+        .dispatch()
+        .into_json::<Result<CiTypeDTO, String>>()
+        .expect("body was not a valid type")
+        .expect("type was not created");
+
+    (0..count)
+        .map(|i| {
+            alice
+                .post("/api/v1/configuration-items")
+                .header(ContentType::JSON)
+                .body(
+                    json!({
+                        "type_id": ci_type.id,
+                        "name": format!("it-server-{}-{}", i, token),
+                        "attributes": {},
+                    })
+                    .to_string(),
+                )
+                .dispatch()
+                .into_json::<Result<ConfigurationItemDTO, String>>()
+                .expect("body was not a valid item")
+                .expect("item was not created")
+                .id
+        })
+        .collect()
+}
+
+/// Requests a change as Bob, returning the response status and body.
+fn request_change(
+    bob: &Client,
+    change: serde_json::Value,
+) -> (Status, Result<ChangeDTO, serde_json::Value>) {
+    let response = bob
+        .post("/api/v1/changes")
+        .header(ContentType::JSON)
+        .body(change.to_string())
+        .dispatch();
+    let status = response.status();
+
+    (
+        status,
+        response
+            .into_json::<Result<ChangeDTO, serde_json::Value>>()
+            .expect("body was not a valid change"),
+    )
+}
+
+/// Answers to the questionnaire of a change with a high risk.
+fn risky_answers() -> serde_json::Value {
+    json!({
+        "experience": "never",
+        "testing": "none",
+        "rollback": "manual",
+        "users": "all",
+        "downtime": "outage",
+        "critical": "yes",
+    })
+}
+
+/// Answers to the questionnaire of a change with a low risk.
+fn safe_answers() -> serde_json::Value {
+    json!({
+        "experience": "routine",
+        "testing": "full",
+        "rollback": "automatic",
+        "users": "none",
+        "downtime": "none",
+        "critical": "no",
+    })
+}
+
+/// Sunny integration test for changes, from their request to the change calendar.
+#[test]
+fn it_sunny_change() {
+    let (alice, bob) = (logged_in_client("alice"), logged_in_client("bob"));
+    let token = Uuid::new_v4().to_simple().to_string();
+    let ids = create_items(&alice, &token, 2);
+
+    // High risk changes go through the chain of the board
+    let bob_id = alice
+        .get("/api/v1/catalog/users?q=bob")
+        .dispatch()
+        .into_json::<Vec<PickableUserDTO>>()
+        .expect("body was not a valid user list")
+        .into_iter()
+        .find(|user| user.username == "bob")
+        .expect("user was not found")
+        .id;
+    let chain = alice
+        .post("/api/v1/approval-chains")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "name": format!("IT CAB {}", token),
+                "steps": [{"mode": "any", "approvers": [{"type": "user", "id": bob_id}]}],
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<ApprovalChainDTO, String>>()
+        .expect("body was not a valid chain")
+        .expect("chain was not created");
+    let response = alice
+        .put("/api/v1/cab-chains")
+        .header(ContentType::JSON)
+        .body(json!({"risk": "high", "approval_chain_id": chain.id}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    let (status, upgrade) = request_change(
+        &bob,
+        json!({
+            "title": format!("IT database upgrade {}", token),
+            "description": "Upgrade; then restart.",
+            "planned_start": "2031-03-01T22:00:00Z",
+            "planned_end": "2031-03-02T02:00:00Z",
+            "answers": risky_answers(),
+            "ci_ids": [ids[0], ids[1], ids[0]],
+        }),
+    );
+    assert_eq!(
+        status,
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let upgrade = upgrade.expect("change was not requested");
+    assert_eq!(upgrade.assessment.risk, Risk::High);
+    assert_eq!(upgrade.ticket.status, TicketStatus::Pending);
+    assert_eq!(upgrade.ci_ids.len(), 2);
+
+    let _ = alice
+        .put("/api/v1/cab-chains")
+        .header(ContentType::JSON)
+        .body(json!({"risk": "high", "approval_chain_id": null}).to_string())
+        .dispatch();
+    let chains = bob
+        .get("/api/v1/cab-chains")
+        .dispatch()
+        .into_json::<Vec<CabChainDTO>>()
+        .expect("body was not a valid chain list");
+    assert_eq!(chains.len(), Risk::ALL.len());
+
+    // Low risk changes colliding with it are reported
+    let (_, patch) = request_change(
+        &bob,
+        json!({
+            "title": format!("IT web server patch {}", token),
+            "planned_start": "2031-03-02T01:00:00Z",
+            "planned_end": "2031-03-02T03:00:00Z",
+            "answers": safe_answers(),
+            "ci_ids": [ids[1]],
+        }),
+    );
+    let patch = patch.expect("change was not requested");
+    assert_eq!(patch.assessment.risk, Risk::Low);
+    assert_eq!(patch.ticket.status, TicketStatus::New);
+
+    let window = bob
+        .post("/api/v1/maintenance-windows")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "title": format!("IT provider maintenance {}", token),
+                "starts_on": "2031-03-02T02:30:00Z",
+                "ends_on": "2031-03-02T05:00:00Z",
+                "ci_ids": [ids[1]],
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<MaintenanceWindowDTO, String>>()
+        .expect("body was not a valid window")
+        .expect("window was not scheduled");
+
+    let collisions = bob
+        .get(format!("/api/v1/changes/{}/collisions", patch.id))
+        .dispatch()
+        .into_json::<Vec<CollisionDTO>>()
+        .expect("body was not a valid collision list");
+    assert_eq!(collisions.len(), 2);
+    assert!(collisions.iter().any(|collision| matches!(
+        collision,
+        CollisionDTO::Change { id, ci_ids, .. } if *id == upgrade.id && ci_ids == &[ids[1]]
+    )));
+    assert!(collisions.iter().any(|collision| matches!(
+        collision,
+        CollisionDTO::MaintenanceWindow { id, .. } if *id == window.id
+    )));
+
+    // Planning the change again moves it out of the collisions
+    let response = bob
+        .put(format!("/api/v1/changes/{}", patch.id))
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "planned_start": "2031-03-03T01:00:00Z",
+                "planned_end": "2031-03-03T03:00:00Z",
+                "answers": safe_answers(),
+                "ci_ids": [ids[1]],
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    assert!(bob
+        .get(format!("/api/v1/changes/{}/collisions", patch.id))
+        .dispatch()
+        .into_json::<Vec<CollisionDTO>>()
+        .expect("body was not a valid collision list")
+        .is_empty());
+
+    let changes = bob
+        .get("/api/v1/changes?from=2031-03-01T00:00:00Z&to=2031-03-04T00:00:00Z&limit=500")
+        .dispatch()
+        .into_json::<Vec<ChangeDTO>>()
+        .expect("body was not a valid change list");
+    assert!(changes.iter().any(|change| change.id == upgrade.id));
+    assert!(changes.iter().any(|change| change.id == patch.id));
+
+    // The calendar has the changes and the window
+    let response = bob
+        .get("/api/v1/changes/calendar.ics?from=2031-03-01T00:00:00Z&to=2031-03-04T00:00:00Z")
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    assert_eq!(response.content_type(), Some(ContentType::Calendar));
+    let calendar = response.into_string().expect("calendar was empty");
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(calendar.contains(&format!("UID:change-{}@mysupport\r\n", upgrade.id)));
+    assert!(calendar.contains(&format!("UID:change-{}@mysupport\r\n", patch.id)));
+    assert!(calendar.contains(&format!("UID:maintenance-{}@mysupport\r\n", window.id)));
+    assert!(calendar.contains("DESCRIPTION:Upgrade\\; then restart.\r\n"));
+
+    let response = bob
+        .delete(format!("/api/v1/maintenance-windows/{}", window.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Rainy integration test for changes: invalid plans and users other than agents are refused.
+#[test]
+fn it_rainy_change() {
+    let (alice, bob, carol) = (
+        logged_in_client("alice"),
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+    );
+    let token = Uuid::new_v4().to_simple().to_string();
+    let ids = create_items(&alice, &token, 1);
+    let change = json!({
+        "title": format!("IT change {}", token),
+        "planned_start": "2031-04-01T22:00:00Z",
+        "planned_end": "2031-04-01T23:00:00Z",
+        "answers": safe_answers(),
+        "ci_ids": [ids[0]],
+    });
+
+    // Customers and anonymous users can't see or request changes
+    let response = carol
+        .post("/api/v1/changes")
+        .header(ContentType::JSON)
+        .body(change.to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+    for path in ["/api/v1/changes", "/api/v1/changes/calendar.ics"] {
+        assert_eq!(
+            sync_client().get(path).dispatch().status(),
+            Status::Unauthorized,
+            "response HTTP status code was not 401 Unauthorized"
+        );
+    }
+
+    // Invalid plans
+    let invalid = [
+        ("title", json!("")),
+        ("planned_end", json!("2031-04-01T21:00:00Z")),
+        ("answers", json!({"experience": "routine"})),
+        ("answers", json!({"experience": "sometimes"})),
+        ("ci_ids", json!([Uuid::new_v4()])),
+    ];
+    for (field, value) in invalid {
+        let mut change = change.clone();
+        change[field] = value;
+        let (status, _) = request_change(&bob, change);
+        assert_eq!(
+            status,
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+    }
+    let response = bob.get("/api/v1/changes?from=yesterday").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    // Closed changes are cancelled, and can't be planned again
+    let (_, cancelled) = request_change(&bob, change.clone());
+    let cancelled = cancelled.expect("change was not requested");
+    let response = bob
+        .patch(format!("/api/v1/tickets/{}", cancelled.ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"status": "closed"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let response = bob
+        .put(format!("/api/v1/changes/{}", cancelled.id))
+        .header(ContentType::JSON)
+        .body(change.to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+    let calendar = bob
+        .get("/api/v1/changes/calendar.ics?from=2031-04-01T00:00:00Z&to=2031-04-02T00:00:00Z")
+        .dispatch()
+        .into_string()
+        .expect("calendar was empty");
+    assert!(!calendar.contains(&cancelled.id.to_string()));
+
+    // Missing changes and windows, and board chains only set by administrators
+    let missing = Uuid::new_v4();
+    assert_eq!(
+        bob.get(format!("/api/v1/changes/{}", missing))
+            .dispatch()
+            .status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+    assert_eq!(
+        bob.delete(format!("/api/v1/maintenance-windows/{}", missing))
+            .dispatch()
+            .status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+    let chain = json!({"risk": "medium", "approval_chain_id": missing}).to_string();
+    assert_eq!(
+        bob.put("/api/v1/cab-chains")
+            .header(ContentType::JSON)
+            .body(chain.clone())
+            .dispatch()
+            .status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+    assert_eq!(
+        alice
+            .put("/api/v1/cab-chains")
+            .header(ContentType::JSON)
+            .body(chain)
+            .dispatch()
+            .status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+}
@@ -0,0 +1,380 @@
+use crate::{logged_in_client, user_id};
+use common::{
+    cmdb::{CiTypeDTO, ConfigurationItemDTO, ImpactedItemDTO, ImportReportDTO, RelationshipDTO},
+    custom_field::Value,
+    ticket::TicketDTO,
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Creates a type of configuration items as Alice, returning it.
+fn create_type(alice: &Client, ci_type: serde_json::Value) -> CiTypeDTO {
+    let response = alice
+        .post("/api/v1/ci-types")
+        .header(ContentType::JSON)
+        .body(ci_type.to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<CiTypeDTO, String>>()
+        .expect("body was not a valid type")
+        .expect("type was not created")
+}
+
+/// Creates a configuration item as Bob, returning it.
+fn create_item(bob: &Client, item: serde_json::Value) -> ConfigurationItemDTO {
+    let response = bob
+        .post("/api/v1/configuration-items")
+        .header(ContentType::JSON)
+        .body(item.to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<ConfigurationItemDTO, String>>()
+        .expect("body was not a valid item")
+        .expect("item was not created")
+}
+
+/// Deletes a type as Alice, along with its items.
+fn delete_type(alice: &Client, ci_type: &CiTypeDTO) {
+    let items = alice
+        .get(format!(
+            "/api/v1/configuration-items?type_id={}&limit=500",
+            ci_type.id
+        ))
+        .dispatch()
+        .into_json::<Vec<ConfigurationItemDTO>>()
+        .expect("body was not a valid item list");
+    for item in items {
+        assert_eq!(
+            alice
+                .delete(format!("/api/v1/configuration-items/{}", item.id))
+                .dispatch()
+                .status(),
+            Status::NoContent,
+            "response HTTP status code was not 204 No Content"
+        );
+    }
+    assert_eq!(
+        alice
+            .delete(format!("/api/v1/ci-types/{}", ci_type.id))
+            .dispatch()
+            .status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Sunny integration test for the CMDB, from the import of items to the impact of an outage on
+/// the tickets.
+#[test]
+fn it_sunny_cmdb() {
+    let (alice, bob, carol) = (
+        logged_in_client("alice"),
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+    );
+    let token = Uuid::new_v4().to_simple().to_string();
+    let server = create_type(
+        &alice,
+        json!({
+            "name": format!("IT server {}", token),
+            "attributes": [
+                {"key": "os", "label": "OS", "type": "enum", "options": ["linux", "windows"]},
+                {"key": "cores", "label": "Cores", "type": "number", "required": true},
+            ],
+        }),
+    );
+    let host = create_item(
+        &bob,
+        json!({
+            "type_id": server.id,
+            "name": format!("it-host-{}", token),
+            "attributes": {"cores": 64},
+        }),
+    );
+
+    let csv = format!(
+        "name,os,cores\r\nit-host-{0},linux,128\r\n\"it-vm-{0}\",,8\r\n",
+        token
+    );
+    let response = alice
+        .post(format!("/api/v1/ci-types/{}/import", server.id))
+        .header(ContentType::CSV)
+        .body(csv)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let report = response
+        .into_json::<Option<ImportReportDTO>>()
+        .expect("body was not a valid report")
+        .expect("no report was sent");
+    assert_eq!((report.created, report.updated), (1, 1));
+    let host = bob
+        .get(format!("/api/v1/configuration-items/{}", host.id))
+        .dispatch()
+        .into_json::<Option<ConfigurationItemDTO>>()
+        .expect("body was not a valid item")
+        .expect("item was not found");
+    assert_eq!(host.attributes.get("cores"), Some(&Value::Number(128.0)));
+    let items = bob
+        .get(format!("/api/v1/configuration-items?type_id={}", server.id))
+        .dispatch()
+        .into_json::<Vec<ConfigurationItemDTO>>()
+        .expect("body was not a valid item list");
+    let vm = items
+        .iter()
+        .find(|item| item.name == format!("it-vm-{}", token))
+        .expect("imported item was not listed");
+
+    let response = bob
+        .post(format!(
+            "/api/v1/configuration-items/{}/relationships",
+            vm.id
+        ))
+        .header(ContentType::JSON)
+        .body(json!({"target_id": host.id, "kind": "runs_on"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let relationship = response
+        .into_json::<Result<Option<RelationshipDTO>, String>>()
+        .expect("body was not a valid relationship")
+        .expect("items were not related")
+        .expect("relationship already existed");
+    let impacted = bob
+        .get(format!("/api/v1/configuration-items/{}/impact", host.id))
+        .dispatch()
+        .into_json::<Vec<ImpactedItemDTO>>()
+        .expect("body was not a valid impact");
+    assert_eq!(
+        impacted
+            .iter()
+            .map(|item| (item.id, item.depth))
+            .collect::<Vec<_>>(),
+        [(vm.id, 1)]
+    );
+
+    // Customers see the items linked to their tickets
+    let ticket = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "title": format!("IT VM down {}", token),
+                "description": "",
+                "priority": "high",
+            })
+            .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+    let response = bob
+        .post(format!("/api/v1/tickets/{}/configuration-items", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({ "ci_id": vm.id }).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let linked = carol
+        .get(format!("/api/v1/tickets/{}/configuration-items", ticket.id))
+        .dispatch()
+        .into_json::<Vec<ConfigurationItemDTO>>()
+        .expect("body was not a valid item list");
+    assert_eq!(linked.len(), 1);
+    assert!(bob
+        .get(format!("/api/v1/configuration-items/{}/tickets", vm.id))
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid ticket list")
+        .iter()
+        .any(|t| t.id == ticket.id));
+
+    assert_eq!(
+        bob.delete(format!(
+            "/api/v1/configuration-items/{}/relationships/{}",
+            host.id, relationship.id
+        ))
+        .dispatch()
+        .status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+    delete_type(&alice, &server);
+}
+
+/// Rainy integration test for the CMDB.
+#[test]
+fn it_rainy_cmdb() {
+    let (alice, bob, carol) = (
+        logged_in_client("alice"),
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+    );
+    let token = Uuid::new_v4().to_simple().to_string();
+
+    assert_eq!(
+        carol.get("/api/v1/configuration-items").dispatch().status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+    let response = bob
+        .post("/api/v1/ci-types")
+        .header(ContentType::JSON)
+        .body(json!({"name": "IT laptop"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+
+    let laptop = create_type(
+        &alice,
+        json!({
+            "name": format!("IT laptop {}", token),
+            "attributes": [
+                {"key": "ram", "label": "RAM", "type": "number", "required": true},
+                {"key": "owner", "label": "Owner", "type": "user"},
+            ],
+        }),
+    );
+    let response = alice
+        .post("/api/v1/ci-types")
+        .header(ContentType::JSON)
+        .body(json!({ "name": laptop.name }).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Conflict,
+        "response HTTP status code was not 409 Conflict"
+    );
+
+    for item in [
+        json!({"type_id": laptop.id, "name": " ", "attributes": {"ram": 16}}),
+        json!({"type_id": laptop.id, "name": "it-laptop", "attributes": {}}),
+        json!({"type_id": laptop.id, "name": "it-laptop", "attributes": {"ram": "lots"}}),
+        json!({"type_id": Uuid::new_v4(), "name": "it-laptop"}),
+    ] {
+        let response = bob
+            .post("/api/v1/configuration-items")
+            .header(ContentType::JSON)
+            .body(item.to_string())
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+    }
+    let item = create_item(
+        &bob,
+        json!({
+            "type_id": laptop.id,
+            "name": format!("it-laptop-{}", token),
+            "attributes": {"ram": 16},
+        }),
+    );
+    let response = bob
+        .post(format!(
+            "/api/v1/configuration-items/{}/relationships",
+            item.id
+        ))
+        .header(ContentType::JSON)
+        .body(json!({"target_id": item.id, "kind": "depends_on"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    // Imports are all or nothing
+    for (csv, line) in [
+        ("os\nit-x\n".to_owned(), 1),
+        (format!("name,ram\nit-new-{},8\n,8\n", token), 3),
+        (format!("name,ram\nit-new-{},eight\n", token), 2),
+        (format!("name,ram\nit-new-{0},8\nit-new-{0},16\n", token), 3),
+        ("name,ram\n\"unterminated,8\n".to_owned(), 2),
+        (
+            format!(
+                "name,ram,owner\nit-new-{0},8,{1}\nit-old-{0},8,{2}\n",
+                token,
+                user_id("carol"),
+                Uuid::new_v4()
+            ),
+            3,
+        ),
+    ] {
+        let response = alice
+            .post(format!("/api/v1/ci-types/{}/import", laptop.id))
+            .header(ContentType::CSV)
+            .body(csv)
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+        let report = response
+            .into_json::<Option<ImportReportDTO>>()
+            .expect("body was not a valid report")
+            .expect("no report was sent");
+        assert_eq!(report.errors[0].line, line);
+    }
+    assert_eq!(
+        bob.get(format!("/api/v1/configuration-items?type_id={}", laptop.id))
+            .dispatch()
+            .into_json::<Vec<ConfigurationItemDTO>>()
+            .expect("body was not a valid item list")
+            .len(),
+        1
+    );
+
+    // Types with items can't be deleted
+    assert_eq!(
+        alice
+            .delete(format!("/api/v1/ci-types/{}", laptop.id))
+            .dispatch()
+            .status(),
+        Status::Conflict,
+        "response HTTP status code was not 409 Conflict"
+    );
+    assert_eq!(
+        carol
+            .get(format!(
+                "/api/v1/tickets/{}/configuration-items",
+                Uuid::new_v4()
+            ))
+            .dispatch()
+            .status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+    delete_type(&alice, &laptop);
+}
@@ -0,0 +1,290 @@
+use crate::logged_in_client;
+use common::{
+    custom_field::{CustomFieldDTO, Value},
+    search::TicketHitDTO,
+    ticket::TicketDTO,
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Creates a custom field as Alice, returning it.
+fn create_field(alice: &Client, field: serde_json::Value) -> CustomFieldDTO {
+    let response = alice
+        .post("/api/v1/custom-fields")
+        .header(ContentType::JSON)
+        .body(field.to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<CustomFieldDTO, String>>()
+        .expect("body was not a valid field")
+        .expect("field was not created")
+}
+
+/// Sunny integration test for custom fields, from their definition to the tickets using them.
+#[test]
+fn it_sunny_custom_field() {
+    let (alice, bob, carol) = (
+        logged_in_client("alice"),
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+    );
+    let token = Uuid::new_v4().to_simple().to_string();
+    let category = format!("IT hardware {}", token);
+    let asset_tag = create_field(
+        &alice,
+        json!({
+            "key": format!("it_asset_{}", token),
+            "label": "Asset tag",
+            "type": "string",
+            "required": true,
+            "categories": [format!(" {} ", category)],
+        }),
+    );
+    assert_eq!(asset_tag.categories, vec![category.clone()]);
+    let cores = create_field(
+        &alice,
+        json!({
+            "key": format!("it_cores_{}", token),
+            "label": "Cores",
+            "type": "number",
+            "categories": [category],
+        }),
+    );
+    assert!(carol
+        .get("/api/v1/custom-fields")
+        .dispatch()
+        .into_json::<Vec<CustomFieldDTO>>()
+        .expect("body was not a valid field list")
+        .contains(&asset_tag));
+
+    let response = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "title": format!("IT custom field laptop {}", token),
+                "description": "",
+                "priority": "normal",
+                "category": category,
+                "custom_fields": {&asset_tag.key: " LT-042 ", &cores.key: 8},
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let ticket = response
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+    assert_eq!(
+        ticket.custom_fields.get(&asset_tag.key),
+        Some(&Value::Text("LT-042".to_owned()))
+    );
+
+    // Values can be filtered in queries and searches
+    let found = bob
+        .get(format!(
+            "/api/v1/tickets?q={}%20cf.{}%3ALT-042%20cf.{}%3E4",
+            token, asset_tag.key, cores.key
+        ))
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid ticket list");
+    assert!(found.iter().any(|t| t.id == ticket.id));
+    let hits = bob
+        .get(format!("/api/v1/search?q={}&cf[{}]=8", token, cores.key))
+        .dispatch()
+        .into_json::<Vec<TicketHitDTO>>()
+        .expect("body was not valid search results");
+    assert!(hits.iter().any(|hit| hit.id == ticket.id));
+    let hits = bob
+        .get(format!("/api/v1/search?q={}&cf[{}]=4", token, cores.key))
+        .dispatch()
+        .into_json::<Vec<TicketHitDTO>>()
+        .expect("body was not valid search results");
+    assert!(hits.iter().all(|hit| hit.id != ticket.id));
+
+    let response = bob
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"custom_fields": {&asset_tag.key: "LT-043"}}).to_string())
+        .dispatch();
+    let updated = response
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not updated");
+    assert_eq!(
+        updated.custom_fields.get(&asset_tag.key),
+        Some(&Value::Text("LT-043".to_owned()))
+    );
+    assert!(!updated.custom_fields.contains_key(&cores.key));
+
+    // Deleting a field removes its values
+    for field in [&asset_tag, &cores] {
+        assert_eq!(
+            alice
+                .delete(format!("/api/v1/custom-fields/{}", field.id))
+                .dispatch()
+                .status(),
+            Status::NoContent,
+            "response HTTP status code was not 204 No Content"
+        );
+    }
+    let ticket = carol
+        .get(format!("/api/v1/tickets/{}", ticket.id))
+        .dispatch()
+        .into_json::<Option<TicketDTO>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not found");
+    assert!(ticket.custom_fields.is_empty());
+}
+
+/// Rainy integration test for custom fields.
+#[test]
+fn it_rainy_custom_field() {
+    let (alice, carol) = (logged_in_client("alice"), logged_in_client("carol"));
+    let token = Uuid::new_v4().to_simple().to_string();
+    let category = format!("IT server {}", token);
+    let key = format!("it_rack_{}", token);
+
+    let response = carol
+        .post("/api/v1/custom-fields")
+        .header(ContentType::JSON)
+        .body(json!({"key": key, "label": "Rack", "type": "string"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+    for field in [
+        json!({"key": "Rack", "label": "Rack", "type": "string"}),
+        json!({"key": key, "label": " ", "type": "string"}),
+        json!({"key": key, "label": "Rack", "type": "enum", "options": []}),
+    ] {
+        let response = alice
+            .post("/api/v1/custom-fields")
+            .header(ContentType::JSON)
+            .body(field.to_string())
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+    }
+
+    let rack = create_field(
+        &alice,
+        json!({
+            "key": key,
+            "label": "Rack",
+            "type": "enum",
+            "options": ["A1", "B2"],
+            "required": true,
+            "categories": [category],
+        }),
+    );
+    let response = alice
+        .post("/api/v1/custom-fields")
+        .header(ContentType::JSON)
+        .body(json!({"key": key, "label": "Rack again", "type": "string"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Conflict,
+        "response HTTP status code was not 409 Conflict"
+    );
+    let response = alice
+        .put(format!("/api/v1/custom-fields/{}", rack.id))
+        .header(ContentType::JSON)
+        .body(json!({"key": "it_renamed", "label": "Rack", "type": "string"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    for custom_fields in [json!({}), json!({&key: "C3"}), json!({&key: 12})] {
+        let response = carol
+            .post("/api/v1/tickets")
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "title": "IT custom field server",
+                    "description": "",
+                    "priority": "normal",
+                    "category": category,
+                    "custom_fields": custom_fields,
+                })
+                .to_string(),
+            )
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+        assert!(response
+            .into_json::<Result<TicketDTO, String>>()
+            .expect("body was not a valid ticket")
+            .expect_err("ticket was created")
+            .starts_with(&key));
+    }
+    // Tickets of other categories don't have the field
+    let response = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "title": "IT custom field printer",
+                "description": "",
+                "priority": "normal",
+                "custom_fields": {&key: "A1"},
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    for query in [
+        "cf.it_unknown_field%3A1".to_owned(),
+        format!("cf.{}%3E%3DA1", key),
+        format!("cf.{}%3AC3", key),
+    ] {
+        let response = carol.get(format!("/api/v1/tickets?q={}", query)).dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+    }
+
+    assert_eq!(
+        alice
+            .delete(format!("/api/v1/custom-fields/{}", rack.id))
+            .dispatch()
+            .status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
@@ -0,0 +1,110 @@
+use crate::logged_in_client;
+use common::{
+    dashboard::{ActivityDTO, ActivityKind, DashboardDTO, SlaRiskDTO, Widget, WidgetDTO},
+    ticket::TicketDTO,
+};
+use rocket::http::{ContentType, Status};
+use serde_json::json;
+
+/// Sunny integration test for dashboards: agents store their layout, and list the tickets at risk
+/// and the latest activity.
+#[test]
+fn it_sunny_dashboard() {
+    let alice = logged_in_client("alice");
+    let layout = DashboardDTO {
+        widgets: vec![
+            WidgetDTO {
+                kind: Widget::SlaAtRisk,
+                wide: true,
+                limit: 5,
+            },
+            WidgetDTO {
+                kind: Widget::MyOpenTickets,
+                wide: false,
+                limit: 20,
+            },
+        ],
+    };
+    let response = alice
+        .put("/api/v1/dashboard")
+        .header(ContentType::JSON)
+        .body(serde_json::to_string(&layout).expect("error serializing dashboard"))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let stored = alice
+        .get("/api/v1/dashboard")
+        .dispatch()
+        .into_json::<DashboardDTO>()
+        .expect("body was not a valid dashboard");
+    assert_eq!(stored, layout);
+
+    let response = alice
+        .get("/api/v1/dashboard/sla-at-risk?limit=5")
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let at_risk = response
+        .into_json::<Vec<SlaRiskDTO>>()
+        .expect("body was not a valid list of tickets at risk");
+    assert!(at_risk.len() <= 5);
+    assert!(at_risk
+        .windows(2)
+        .all(|pair| pair[0].due_on <= pair[1].due_on));
+
+    let carol = logged_in_client("carol");
+    let ticket = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(json!({"title": "IT dashboard", "description": "Dynamite delivered."}).to_string())
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+    let bob = logged_in_client("bob");
+    let activity = bob
+        .get("/api/v1/dashboard/activity?limit=50")
+        .dispatch()
+        .into_json::<Vec<ActivityDTO>>()
+        .expect("body was not a valid activity list");
+    assert!(activity
+        .iter()
+        .any(|a| a.ticket_id == ticket.id && a.kind == ActivityKind::Created));
+}
+
+/// Rainy integration test for dashboards: invalid layouts are rejected, and customers have no
+/// dashboard.
+#[test]
+fn it_rainy_dashboard() {
+    let bob = logged_in_client("bob");
+    for widgets in [
+        json!([{"kind": "sla_at_risk"}, {"kind": "sla_at_risk", "wide": true}]),
+        json!([{"kind": "recent_activity", "limit": 0}]),
+    ] {
+        let response = bob
+            .put("/api/v1/dashboard")
+            .header(ContentType::JSON)
+            .body(json!({ "widgets": widgets }).to_string())
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+    let response = bob
+        .put("/api/v1/dashboard")
+        .header(ContentType::JSON)
+        .body(json!({"widgets": [{"kind": "weather"}]}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let carol = logged_in_client("carol");
+    for url in [
+        "/api/v1/dashboard",
+        "/api/v1/dashboard/sla-at-risk",
+        "/api/v1/dashboard/activity",
+        "/api/v1/tickets/activity",
+    ] {
+        let response = carol.get(url).dispatch();
+        assert_eq!(response.status(), Status::Forbidden, "{}", url);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::logged_in_client;
+use common::{
+    inbound::{InboundEmailDTO, Outcome},
+    ticket::{CommentDTO, TicketDTO},
+};
+use rocket::http::{ContentType, Status};
+use uuid::Uuid;
+
+/// Generates a unique `Message-ID`.
+fn message_id() -> String {
+    format!("{}@mail.acme.test", Uuid::new_v4())
+}
+
+/// Sunny integration test for the inbound email endpoints.
+#[test]
+fn it_sunny_inbound_email() {
+    let client = logged_in_client("alice");
+    let first_id = message_id();
+    let response = client
+        .post("/api/v1/inbound/email")
+        .body(format!(
+            "From: carol@acme.test\r\nMessage-ID: <{}>\r\nSubject: IT inbound\r\n\r\nHelp!",
+            first_id
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let entry = response
+        .into_json::<Option<InboundEmailDTO>>()
+        .expect("body was not a valid log entry")
+        .expect("email was not logged");
+    assert_eq!(entry.outcome, Outcome::Created);
+    let ticket_id = entry.ticket_id.expect("ticket was not created");
+
+    let carol = logged_in_client("carol");
+    let ticket = carol
+        .get(format!("/api/v1/tickets/{}", ticket_id))
+        .dispatch()
+        .into_json::<Option<TicketDTO>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not visible to the requester");
+    assert_eq!(ticket.title, "IT inbound");
+
+    let entry = client
+        .post("/api/v1/inbound/email")
+        .body(format!(
+            "From: carol@acme.test\r\nMessage-ID: <{}>\r\nIn-Reply-To: <{}>\r\n\
+             Subject: Re: IT inbound\r\n\r\nStill broken\r\n> Help!",
+            message_id(),
+            first_id
+        ))
+        .dispatch()
+        .into_json::<Option<InboundEmailDTO>>()
+        .expect("body was not a valid log entry")
+        .expect("email was not logged");
+    assert_eq!(entry.outcome, Outcome::Commented);
+
+    let response = carol
+        .post(format!("/api/v1/tickets/{}/comments", ticket_id))
+        .header(ContentType::JSON)
+        .body(r#"{"body":" Thanks "}"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    let comments = carol
+        .get(format!("/api/v1/tickets/{}/comments", ticket_id))
+        .dispatch()
+        .into_json::<Vec<CommentDTO>>()
+        .expect("body was not a valid comment list");
+    assert_eq!(
+        comments
+            .iter()
+            .map(|comment| comment.body.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Still broken", "Thanks"]
+    );
+
+    let log = client
+        .get("/api/v1/inbound/log?limit=5")
+        .dispatch()
+        .into_json::<Vec<InboundEmailDTO>>()
+        .expect("body was not a valid log");
+    assert!(log.iter().any(|logged| logged.id == entry.id));
+}
+
+/// Rainy integration test for the inbound email endpoints.
+#[test]
+fn it_rainy_inbound_email() {
+    let client = logged_in_client("carol");
+    let response = client
+        .post("/api/v1/inbound/email")
+        .body("From: carol@acme.test\r\nSubject: IT inbound\r\n\r\nHelp!")
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Unauthorized,
+        "response HTTP status code was not 401 Unauthorized"
+    );
+
+    let client = logged_in_client("bob");
+    let response = client.get("/api/v1/inbound/log").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Rainy integration test for the comments of tickets from other organisations.
+#[test]
+fn it_rainy_comment_other_organisation() {
+    let ticket = logged_in_client("carol")
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"IT inbound private","description":"","priority":"low"}"#)
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+
+    let dave = logged_in_client("dave");
+    let response = dave
+        .post(format!("/api/v1/tickets/{}/comments", ticket.id))
+        .header(ContentType::JSON)
+        .body(r#"{"body":"Hi"}"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+    let response = dave
+        .get(format!("/api/v1/tickets/{}/comments", ticket.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+}
@@ -0,0 +1,68 @@
+use crate::{logged_in_client, sync_client};
+use common::registration::PendingInvitationDTO;
+use rocket::http::{ContentType, Status};
+
+/// Sunny integration test for the `GET /api/v1/invitations` endpoint.
+#[test]
+fn it_sunny_list_invitations() {
+    let client = logged_in_client("bob");
+    let response = client.get("/api/v1/invitations").dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    assert!(
+        response.into_json::<Vec<PendingInvitationDTO>>().is_some(),
+        "body was not a valid list of invitations"
+    );
+}
+
+/// Rainy integration test for the `POST /api/v1/invitations` endpoint without logging in.
+#[test]
+fn it_rainy_invite_not_logged_in() {
+    let client = sync_client();
+    let response = client
+        .post("/api/v1/invitations")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"someone@my-support.com","role":"customer"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Unauthorized,
+        "response HTTP status code was not 401 Unauthorized"
+    );
+}
+
+/// Rainy integration test for the `POST /api/v1/invitations` endpoint, inviting an administrator
+/// as an agent.
+#[test]
+fn it_rainy_invite_higher_role() {
+    let client = logged_in_client("bob");
+    let response = client
+        .post("/api/v1/invitations")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"someone@my-support.com","role":"admin"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Rainy integration test for the `DELETE /api/v1/invitations/<code>` endpoint.
+#[test]
+fn it_rainy_revoke_nonexistant() {
+    let client = logged_in_client("alice");
+    let response = client.delete("/api/v1/invitations/not_a_code").dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+}
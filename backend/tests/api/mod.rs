@@ -1 +1,28 @@
+mod approval;
+mod article;
+mod audit;
+mod auth;
+mod automation;
+mod board;
+mod catalog;
+mod change;
+mod cmdb;
+mod custom_field;
+mod dashboard;
 mod hello;
+mod inbound;
+mod invitation;
+mod notification;
+mod organisation;
+mod problem;
+mod register;
+mod relation;
+mod report;
+mod search;
+mod survey;
+mod team;
+mod ticket;
+mod user;
+mod view;
+mod webhook;
+mod work_log;
@@ -0,0 +1,228 @@
+use crate::{logged_in_client, open_ticket, user_id};
+use common::notification::{Delivery, Kind, NotificationDTO, PreferenceDTO, UnreadCountDTO};
+use rocket::http::{ContentType, Status};
+use std::{io::Read, sync::mpsc, thread, time::Duration};
+use uuid::Uuid;
+
+/// Sunny integration test for the notification endpoints.
+#[test]
+fn it_sunny_notifications() {
+    let (ticket, bob_id) = (
+        open_ticket(&logged_in_client("carol"), "IT notifications"),
+        user_id("bob"),
+    );
+
+    let alice = logged_in_client("alice");
+    let response = alice
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"status":"open","assignee_id":"{}"}}"#, bob_id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    let client = logged_in_client("bob");
+    let notification = client
+        .get("/api/v1/notifications?unread=true&limit=500")
+        .dispatch()
+        .into_json::<Vec<NotificationDTO>>()
+        .expect("body was not a valid notification list")
+        .into_iter()
+        .find(|notification| notification.ticket_id == Some(ticket.id))
+        .expect("the assignment was not notified");
+    assert_eq!(notification.kind, Kind::TicketAssigned);
+    assert!(!notification.read);
+
+    let unread = client
+        .get("/api/v1/notifications/unread")
+        .dispatch()
+        .into_json::<UnreadCountDTO>()
+        .expect("body was not a valid count");
+    assert!(unread.count >= 1);
+
+    let response = client
+        .post(format!("/api/v1/notifications/{}/read", notification.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+
+    // The requester is notified of the status change
+    let carol = logged_in_client("carol");
+    assert!(carol
+        .get("/api/v1/notifications?limit=500")
+        .dispatch()
+        .into_json::<Vec<NotificationDTO>>()
+        .expect("body was not a valid notification list")
+        .iter()
+        .any(|notification| notification.ticket_id == Some(ticket.id)
+            && notification.kind == Kind::TicketStatusChanged));
+    let response = carol.post("/api/v1/notifications/read").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+    let unread = carol
+        .get("/api/v1/notifications/unread")
+        .dispatch()
+        .into_json::<UnreadCountDTO>()
+        .expect("body was not a valid count");
+    assert_eq!(unread.count, 0);
+}
+
+/// Sunny integration test for the notification event stream.
+#[test]
+fn it_sunny_notification_stream() {
+    let (ticket, bob_id) = (
+        open_ticket(&logged_in_client("carol"), "IT notification stream"),
+        user_id("bob"),
+    );
+
+    let (subscribed_tx, subscribed_rx) = mpsc::channel();
+    let (received_tx, received_rx) = mpsc::channel();
+    let ticket_id = ticket.id.to_string();
+    let _ = thread::spawn(move || {
+        let client = logged_in_client("bob");
+        let mut response = client.get("/api/v1/notifications/stream").dispatch();
+        let _ = subscribed_tx.send((
+            response.status(),
+            response.content_type().map(|ct| ct.to_string()),
+        ));
+
+        let (mut received, mut buffer) = (String::new(), [0; 1024]);
+        while let Ok(len) = response.read(&mut buffer) {
+            received.push_str(&String::from_utf8_lossy(&buffer[..len]));
+            if received.contains(&ticket_id) {
+                let _ = received_tx.send(received);
+                break;
+            }
+        }
+    });
+
+    let (status, content_type) = subscribed_rx
+        .recv_timeout(Duration::from_secs(10))
+        .expect("the stream was not opened");
+    assert_eq!(status, Status::Ok);
+    assert_eq!(content_type.as_deref(), Some("text/event-stream"));
+
+    // The listener could still be connecting to the database, so the assignment is retried
+    let client = logged_in_client("alice");
+    let mut received = None;
+    for assignee in ["null".to_owned(), format!(r#""{}""#, bob_id)]
+        .iter()
+        .cycle()
+        .take(10)
+    {
+        let _ = client
+            .patch(format!("/api/v1/tickets/{}", ticket.id))
+            .header(ContentType::JSON)
+            .body(format!(r#"{{"assignee_id":{}}}"#, assignee))
+            .dispatch();
+        if let Ok(events) = received_rx.recv_timeout(Duration::from_secs(1)) {
+            received = Some(events);
+            break;
+        }
+    }
+
+    let received = received.expect("the notification was not streamed");
+    assert!(received.contains("event:notification"));
+    assert!(received.contains(r#""kind":"ticket.assigned""#));
+}
+
+/// Rainy integration test for the notification endpoints.
+#[test]
+fn it_rainy_notifications() {
+    let client = logged_in_client("carol");
+    let response = client
+        .post(format!("/api/v1/notifications/{}/read", Uuid::new_v4()))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+
+    let client = crate::sync_client();
+    let response = client.get("/api/v1/notifications/stream").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Unauthorized,
+        "response HTTP status code was not 401 Unauthorized"
+    );
+}
+
+/// Sunny integration test for the notification preferences endpoints.
+#[test]
+fn it_sunny_notification_preferences() {
+    let client = logged_in_client("dave");
+    let preferences = client
+        .get("/api/v1/notifications/preferences")
+        .dispatch()
+        .into_json::<Vec<PreferenceDTO>>()
+        .expect("body was not valid preferences");
+    assert_eq!(preferences.len(), Kind::ALL.len());
+
+    let response = client
+        .put("/api/v1/notifications/preferences")
+        .header(ContentType::JSON)
+        .body(r#"[{"kind":"ticket.commented","delivery":"digest"}]"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let updated = response
+        .into_json::<Vec<PreferenceDTO>>()
+        .expect("body was not valid preferences");
+    assert!(updated.contains(&PreferenceDTO {
+        kind: Kind::TicketCommented,
+        delivery: Delivery::Digest,
+    }));
+    assert!(updated.contains(&PreferenceDTO {
+        kind: Kind::TicketAssigned,
+        delivery: Delivery::InApp,
+    }));
+
+    // Restore the default preferences
+    let response = client
+        .put("/api/v1/notifications/preferences")
+        .header(ContentType::JSON)
+        .body(r#"[{"kind":"ticket.commented","delivery":"in_app"}]"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+}
+
+/// Rainy integration test for the notification preferences endpoints.
+#[test]
+fn it_rainy_notification_preferences() {
+    let client = logged_in_client("dave");
+    let response = client
+        .put("/api/v1/notifications/preferences")
+        .header(ContentType::JSON)
+        .body(r#"[{"kind":"ticket.commented","delivery":"carrier_pigeon"}]"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::UnprocessableEntity,
+        "response HTTP status code was not 422 Unprocessable Entity"
+    );
+
+    let client = crate::sync_client();
+    let response = client.get("/api/v1/notifications/preferences").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Unauthorized,
+        "response HTTP status code was not 401 Unauthorized"
+    );
+}
@@ -0,0 +1,304 @@
+use crate::logged_in_client;
+use common::{
+    organisation::{BrandingDTO, MemberDTO, OrganisationDTO},
+    user::UserDTO,
+};
+use rocket::http::{ContentType, Status};
+use uuid::Uuid;
+
+/// Sunny integration test for the `/api/v1/organisations` endpoint.
+#[test]
+fn it_sunny_list_organisations() {
+    let client = logged_in_client("bob");
+    let response = client.get("/api/v1/organisations").dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let organisations = response
+        .into_json::<Vec<OrganisationDTO>>()
+        .expect("body was not a valid list of organisations");
+    let acme = organisations
+        .iter()
+        .find(|org| org.name == "Acme")
+        .expect("Acme was not listed");
+    assert_eq!(acme.domains, vec!["acme.test".to_owned()]);
+}
+
+/// Rainy integration test for the `/api/v1/organisations` endpoint as a customer.
+#[test]
+fn it_rainy_list_organisations_customer() {
+    let client = logged_in_client("carol");
+    let response = client.get("/api/v1/organisations").dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Sunny integration test for creating and updating organisations.
+#[test]
+fn it_sunny_create_organisation() {
+    let client = logged_in_client("alice");
+    let suffix = Uuid::new_v4().to_simple().to_string()[..8].to_owned();
+    let response = client
+        .post("/api/v1/organisations")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"name":"IT Initech {0}","domains":["IT-Initech-{0}.test"]}}"#,
+            suffix
+        ))
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let org = response
+        .into_json::<Result<OrganisationDTO, String>>()
+        .expect("body was not a valid organisation")
+        .expect("organisation was not created");
+    assert_eq!(org.domains, vec![format!("it-initech-{}.test", suffix)]);
+
+    let response = client
+        .put(format!("/api/v1/organisations/{}", org.id))
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"name":"IT Initrode {}","domains":[]}}"#,
+            suffix
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+}
+
+/// Rainy integration test for creating an organisation with a domain of another one.
+#[test]
+fn it_rainy_create_organisation_conflict() {
+    let client = logged_in_client("alice");
+    let response = client
+        .post("/api/v1/organisations")
+        .header(ContentType::JSON)
+        .body(r#"{"name":"IT Acme clone","domains":["acme.test"]}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Conflict,
+        "response HTTP status code was not 409 Conflict"
+    );
+}
+
+/// Rainy integration test for creating an organisation as an agent.
+#[test]
+fn it_rainy_create_organisation_agent() {
+    let client = logged_in_client("bob");
+    let response = client
+        .post("/api/v1/organisations")
+        .header(ContentType::JSON)
+        .body(r#"{"name":"IT Hooli","domains":["it-hooli.test"]}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Sunny integration test for the organisation membership endpoints.
+#[test]
+fn it_sunny_members() {
+    let client = logged_in_client("alice");
+    let bob = logged_in_client("bob")
+        .get("/api/v1/login")
+        .dispatch()
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+    let acme = client
+        .get("/api/v1/organisations")
+        .dispatch()
+        .into_json::<Vec<OrganisationDTO>>()
+        .expect("body was not a valid list of organisations")
+        .into_iter()
+        .find(|org| org.name == "Acme")
+        .expect("Acme was not listed");
+
+    let response = client
+        .post(format!("/api/v1/organisations/{}/members", acme.id))
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"user_id":"{}"}}"#, bob.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    let members = client
+        .get(format!("/api/v1/organisations/{}/members", acme.id))
+        .dispatch()
+        .into_json::<Vec<MemberDTO>>()
+        .expect("body was not a valid list of members");
+    assert!(members.iter().any(|member| member.username == "carol"));
+    assert!(members.iter().any(|member| member.user_id == bob.id));
+
+    let response = client
+        .delete(format!(
+            "/api/v1/organisations/{}/members/{}",
+            acme.id, bob.id
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Sunny integration test for the branding of the customer portal: members of an organisation get
+/// its branding, and other users the default one.
+#[test]
+fn it_sunny_branding() {
+    let alice = logged_in_client("alice");
+    let acme = alice
+        .get("/api/v1/organisations")
+        .dispatch()
+        .into_json::<Vec<OrganisationDTO>>()
+        .expect("body was not a valid list of organisations")
+        .into_iter()
+        .find(|org| org.name == "Acme")
+        .expect("Acme was not listed");
+    let response = alice
+        .put(format!("/api/v1/organisations/{}/branding", acme.id))
+        .header(ContentType::JSON)
+        .body(
+            serde_json::json!({
+                "logo_url": "https://acme.test/logo.png",
+                "primary_colour": "#A01010",
+                "accent_colour": "#ffcc00",
+                "welcome_text": " Welcome to Acme support. ",
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let branding = BrandingDTO {
+        logo_url: Some("https://acme.test/logo.png".to_owned()),
+        primary_colour: "#a01010".to_owned(),
+        accent_colour: "#ffcc00".to_owned(),
+        welcome_text: "Welcome to Acme support.".to_owned(),
+    };
+    assert_eq!(
+        response
+            .into_json::<Result<BrandingDTO, String>>()
+            .expect("body was not a valid branding"),
+        Ok(branding.clone())
+    );
+
+    let bob = logged_in_client("bob");
+    let read = bob
+        .get(format!("/api/v1/organisations/{}/branding", acme.id))
+        .dispatch()
+        .into_json::<Result<BrandingDTO, String>>()
+        .expect("body was not a valid branding");
+    assert_eq!(read, Ok(branding.clone()));
+
+    let carol = logged_in_client("carol");
+    let portal = carol
+        .get("/api/v1/portal/branding")
+        .dispatch()
+        .into_json::<BrandingDTO>()
+        .expect("body was not a valid branding");
+    assert_eq!(portal, branding);
+
+    let dave = logged_in_client("dave");
+    let portal = dave
+        .get("/api/v1/portal/branding")
+        .dispatch()
+        .into_json::<BrandingDTO>()
+        .expect("body was not a valid branding");
+    assert_ne!(portal, branding);
+}
+
+/// Rainy integration test for the branding of the customer portal: only admins change it, and only
+/// to valid colours and logos.
+#[test]
+fn it_rainy_branding() {
+    let alice = logged_in_client("alice");
+    let acme = alice
+        .get("/api/v1/organisations")
+        .dispatch()
+        .into_json::<Vec<OrganisationDTO>>()
+        .expect("body was not a valid list of organisations")
+        .into_iter()
+        .find(|org| org.name == "Acme")
+        .expect("Acme was not listed");
+    let url = format!("/api/v1/organisations/{}/branding", acme.id);
+    let valid = BrandingDTO::default();
+
+    for invalid in [
+        BrandingDTO {
+            primary_colour: "red".to_owned(),
+            ..valid.clone()
+        },
+        BrandingDTO {
+            accent_colour: "#12345g".to_owned(),
+            ..valid.clone()
+        },
+        BrandingDTO {
+            logo_url: Some("javascript:alert(1)".to_owned()),
+            ..valid.clone()
+        },
+        BrandingDTO {
+            logo_url: Some("//evil.test/logo.png".to_owned()),
+            ..valid.clone()
+        },
+        BrandingDTO {
+            welcome_text: "a".repeat(2001),
+            ..valid.clone()
+        },
+    ] {
+        let response = alice
+            .put(&url)
+            .header(ContentType::JSON)
+            .body(serde_json::to_string(&invalid).expect("error serializing branding"))
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+    }
+
+    let response = alice
+        .put(format!("/api/v1/organisations/{}/branding", Uuid::new_v4()))
+        .header(ContentType::JSON)
+        .body(serde_json::to_string(&valid).expect("error serializing branding"))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    let bob = logged_in_client("bob");
+    let response = bob
+        .put(&url)
+        .header(ContentType::JSON)
+        .body(serde_json::to_string(&valid).expect("error serializing branding"))
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let carol = logged_in_client("carol");
+    let response = carol.get(&url).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+}
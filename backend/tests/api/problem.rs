@@ -0,0 +1,311 @@
+use crate::{logged_in_client, open_ticket, sync_client};
+use common::{
+    article::{ArticleDTO, Status as ArticleStatus},
+    problem::{ProblemDTO, ResolutionDTO, Status as ProblemStatus},
+    ticket::{Status as TicketStatus, TicketDTO},
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Creates a problem as Bob, returning it.
+fn create_problem(bob: &Client, problem: serde_json::Value) -> ProblemDTO {
+    let response = bob
+        .post("/api/v1/problems")
+        .header(ContentType::JSON)
+        .body(problem.to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<ProblemDTO, String>>()
+        .expect("body was not a valid problem")
+        .expect("problem was not created")
+}
+
+/// Publishes the known error of a problem as Bob, returning the response status and article.
+fn publish(bob: &Client, problem: &ProblemDTO) -> (Status, ArticleDTO) {
+    let response = bob
+        .post(format!("/api/v1/problems/{}/publish", problem.id))
+        .header(ContentType::JSON)
+        .body(json!({"visibility": "public"}).to_string())
+        .dispatch();
+    let status = response.status();
+
+    (
+        status,
+        response
+            .into_json::<Result<ArticleDTO, String>>()
+            .expect("body was not a valid article")
+            .expect("known error was not published"),
+    )
+}
+
+/// Sunny integration test for problems, from their incidents to their resolution.
+#[test]
+fn it_sunny_problem() {
+    let (alice, bob, carol) = (
+        logged_in_client("alice"),
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+    );
+    let token = Uuid::new_v4().to_simple().to_string();
+    let problem = create_problem(
+        &bob,
+        json!({"title": format!("IT mail outage {}", token), "description": "No mail is sent."}),
+    );
+    assert_eq!(problem.status, ProblemStatus::Investigating);
+
+    let incidents = [
+        open_ticket(&carol, &format!("IT no mail {}", token)),
+        open_ticket(&carol, &format!("IT still no mail {}", token)),
+    ];
+    for incident in &incidents {
+        let response = bob
+            .post(format!("/api/v1/problems/{}/tickets", problem.id))
+            .header(ContentType::JSON)
+            .body(json!({ "ticket_id": incident.id }).to_string())
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::Created,
+            "response HTTP status code was not 201 Created"
+        );
+    }
+    assert_eq!(
+        bob.get(format!("/api/v1/problems/{}/tickets", problem.id))
+            .dispatch()
+            .into_json::<Vec<TicketDTO>>()
+            .expect("body was not a valid ticket list")
+            .len(),
+        2
+    );
+    assert_eq!(
+        bob.get(format!("/api/v1/tickets/{}/problems", incidents[0].id))
+            .dispatch()
+            .into_json::<Vec<ProblemDTO>>()
+            .expect("body was not a valid problem list"),
+        std::slice::from_ref(&problem)
+    );
+
+    // Known errors are published, then kept up to date
+    let response = bob
+        .put(format!("/api/v1/problems/{}", problem.id))
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "title": problem.title,
+                "description": problem.description,
+                "status": "known_error",
+                "root_cause": "The disk of the mail server is full.",
+            })
+            .to_string(),
+        )
+        .dispatch();
+    let problem = response
+        .into_json::<Result<ProblemDTO, String>>()
+        .expect("body was not a valid problem")
+        .expect("problem was not updated");
+    assert_eq!(problem.status, ProblemStatus::KnownError);
+    let (status, article) = publish(&bob, &problem);
+    assert_eq!(status, Status::Created);
+    assert_eq!(article.status, ArticleStatus::Published);
+    assert!(article.body.contains("No workaround is known yet."));
+    let anonymous = sync_client();
+    assert_eq!(
+        anonymous
+            .get(format!("/api/v1/articles/{}", article.id))
+            .dispatch()
+            .status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    let response = bob
+        .put(format!("/api/v1/problems/{}", problem.id))
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "title": problem.title,
+                "status": "known_error",
+                "root_cause": problem.root_cause,
+                "workaround": "Send the mail again later.",
+            })
+            .to_string(),
+        )
+        .dispatch();
+    let problem = response
+        .into_json::<Result<ProblemDTO, String>>()
+        .expect("body was not a valid problem")
+        .expect("problem was not updated");
+    assert_eq!(problem.article_id, Some(article.id));
+    let (status, updated) = publish(&bob, &problem);
+    assert_eq!(status, Status::Ok);
+    assert_eq!(updated.id, article.id);
+    assert!(updated.body.contains("Send the mail again later."));
+
+    // Resolving the problem resolves its active incidents
+    let response = bob
+        .patch(format!("/api/v1/tickets/{}", incidents[1].id))
+        .header(ContentType::JSON)
+        .body(json!({"status": "closed"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let response = bob
+        .post(format!("/api/v1/problems/{}/resolve", problem.id))
+        .header(ContentType::JSON)
+        .body(json!({"comment": "The disk was replaced."}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let resolution = response
+        .into_json::<Result<ResolutionDTO, String>>()
+        .expect("body was not a valid resolution")
+        .expect("problem was not resolved");
+    assert_eq!(resolution.problem.status, ProblemStatus::Resolved);
+    assert!(resolution.problem.resolved_on.is_some());
+    assert_eq!(resolution.resolved_ticket_ids, [incidents[0].id]);
+    let incident = carol
+        .get(format!("/api/v1/tickets/{}", incidents[0].id))
+        .dispatch()
+        .into_json::<Option<TicketDTO>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not found");
+    assert_eq!(incident.status, TicketStatus::Resolved);
+
+    assert_eq!(
+        alice
+            .delete(format!("/api/v1/articles/{}", article.id))
+            .dispatch()
+            .status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+    assert_eq!(
+        bob.delete(format!(
+            "/api/v1/problems/{}/tickets/{}",
+            problem.id, incidents[0].id
+        ))
+        .dispatch()
+        .status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Rainy integration test for problems.
+#[test]
+fn it_rainy_problem() {
+    let (bob, carol) = (logged_in_client("bob"), logged_in_client("carol"));
+    let token = Uuid::new_v4().to_simple().to_string();
+
+    assert_eq!(
+        carol.get("/api/v1/problems").dispatch().status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+    assert_eq!(
+        bob.get("/api/v1/problems?status=solved")
+            .dispatch()
+            .status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+    for problem in [
+        json!({"title": " "}),
+        json!({"title": "IT printer jam", "status": "known_error"}),
+        json!({"title": "IT printer jam", "status": "resolved", "root_cause": "Paper"}),
+        json!({"title": "IT printer jam", "owner_id": Uuid::new_v4()}),
+        json!({"title": "x".repeat(201)}),
+    ] {
+        let response = bob
+            .post("/api/v1/problems")
+            .header(ContentType::JSON)
+            .body(problem.to_string())
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+    }
+
+    let problem = create_problem(&bob, json!({"title": format!("IT printer jam {}", token)}));
+    for (uri, body) in [
+        (
+            format!("/api/v1/problems/{}/publish", problem.id),
+            json!({}),
+        ),
+        (
+            format!("/api/v1/problems/{}/resolve", problem.id),
+            json!({}),
+        ),
+    ] {
+        let response = bob
+            .post(uri)
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request"
+        );
+    }
+    let response = bob
+        .put(format!("/api/v1/problems/{}", problem.id))
+        .header(ContentType::JSON)
+        .body(
+            json!({"title": problem.title, "status": "resolved", "root_cause": "Paper"})
+                .to_string(),
+        )
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    let response = bob
+        .post(format!("/api/v1/problems/{}/tickets", problem.id))
+        .header(ContentType::JSON)
+        .body(json!({ "ticket_id": Uuid::new_v4() }).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+    assert_eq!(
+        bob.delete(format!(
+            "/api/v1/problems/{}/tickets/{}",
+            problem.id,
+            Uuid::new_v4()
+        ))
+        .dispatch()
+        .status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+    assert_eq!(
+        bob.get(format!("/api/v1/problems/{}", Uuid::new_v4()))
+            .dispatch()
+            .status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+}
@@ -0,0 +1,148 @@
+use crate::{configured_client, connection, sync_client};
+use common::{
+    registration::{CodeInfoDTO, ResponseDTO},
+    user::{Role, UserDTO},
+};
+use diesel::connection::SimpleConnection;
+use rocket::http::{ContentType, Status};
+use uuid::Uuid;
+
+/// Generates a unique registration code and username.
+fn unique_code() -> (String, String) {
+    let id = Uuid::new_v4().to_simple().to_string();
+    (id[..10].to_owned(), format!("it_{}", &id[..12]))
+}
+
+/// Sunny integration test for registering with an invitation, with open registration disabled.
+#[test]
+fn it_sunny_register_invited() {
+    let client = configured_client("open_registration", false);
+    let (code, username) = unique_code();
+    connection()
+        .batch_execute(&format!(
+            "INSERT INTO sys_invitation (code, email, role, invited_by) \
+             SELECT '{}', '{}@my-support.com', 'agent', id FROM sys_user WHERE username = 'alice'",
+            code, username
+        ))
+        .expect("error inserting invitation");
+
+    let response = client
+        .get(format!("/api/v1/register/code/{}", code))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let info = response
+        .into_json::<Option<CodeInfoDTO>>()
+        .expect("body was not a valid code")
+        .expect("code was not found");
+    assert!(info.invited);
+    assert_eq!(info.email, format!("{}@my-support.com", username));
+
+    let body = format!(
+        r#"{{"user":"{}","pass":"{}_correct_horse_battery","fn":"Ivy","ln":"Invited"}}"#,
+        username, username
+    );
+    let response = client
+        .post(format!("/api/v1/register/user/{}", code))
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    // Invitations can only be used once
+    let response = client
+        .post(format!("/api/v1/register/user/{}", code))
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    let client = sync_client();
+    let response = client
+        .post("/api/v1/login")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"user":"{}","pass":"{}_correct_horse_battery"}}"#,
+            username, username
+        ))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok, "couldn't log in");
+    let user = response
+        .into_json::<Option<UserDTO>>()
+        .expect("body was not a valid user")
+        .expect("user was not logged in");
+    assert_eq!(user.role, Role::Agent);
+}
+
+/// Rainy integration test for registering without an invitation, with open registration disabled.
+#[test]
+fn it_rainy_register_closed() {
+    let client = configured_client("open_registration", false);
+    let response = client
+        .post("/api/v1/register/email")
+        .header(ContentType::JSON)
+        .body(r#"{"email":"someone@my-support.com"}"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+
+    // Codes sent while registration was open can't be used anymore
+    let (code, username) = unique_code();
+    connection()
+        .batch_execute(&format!(
+            "INSERT INTO sys_email_registration (code, email) VALUES ('{}', '{}@my-support.com')",
+            code, username
+        ))
+        .expect("error inserting email registration");
+
+    let response = client
+        .get(format!("/api/v1/register/code/{}", code))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+    let response = client
+        .post(format!("/api/v1/register/user/{}", code))
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"user":"{}","pass":"{}_correct_horse_battery","fn":"Cal","ln":"Closed"}}"#,
+            username, username
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+    let response = response
+        .into_json::<ResponseDTO>()
+        .expect("body was not a valid response");
+    assert!(response.other.is_some());
+
+    // The same code is valid with open registration
+    let client = sync_client();
+    let response = client
+        .get(format!("/api/v1/register/code/{}", code))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+}
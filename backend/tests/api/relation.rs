@@ -0,0 +1,276 @@
+use crate::{logged_in_client, open_ticket};
+use common::ticket::{CommentDTO, LinkKind, Status as TicketStatus, TicketDTO, TicketLinkDTO};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Sets the status of a ticket as Bob, returning the response status.
+fn set_status(bob: &Client, ticket_id: Uuid, status: TicketStatus) -> Status {
+    bob.patch(format!("/api/v1/tickets/{}", ticket_id))
+        .header(ContentType::JSON)
+        .body(json!({ "status": status }).to_string())
+        .dispatch()
+        .status()
+}
+
+/// Sunny integration test for merging and splitting tickets, parent tickets, links and watchers.
+#[test]
+fn it_sunny_relation() {
+    let (bob, carol) = (logged_in_client("bob"), logged_in_client("carol"));
+    let target = open_ticket(&carol, "IT rocket skates exploded");
+    let source = open_ticket(&carol, "IT rocket skates exploded again");
+    let comment = carol
+        .post(format!("/api/v1/tickets/{}/comments", source.id))
+        .header(ContentType::JSON)
+        .body(r#"{"body":"Also, the anvil is missing."}"#)
+        .dispatch()
+        .into_json::<Result<CommentDTO, String>>()
+        .expect("body was not a valid comment")
+        .expect("comment was not added");
+
+    // Merge the tickets, the merged number leading to the surviving ticket
+    let response = bob
+        .post(format!("/api/v1/tickets/{}/merge", source.id))
+        .header(ContentType::JSON)
+        .body(json!({ "into_id": target.id }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let survivor = carol
+        .get(format!(
+            "/api/v1/tickets/by-number?number={}",
+            source.number
+        ))
+        .dispatch()
+        .into_json::<Option<TicketDTO>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not found");
+    assert_eq!(survivor.id, target.id);
+    let merged = carol
+        .get(format!("/api/v1/tickets/{}", source.id))
+        .dispatch()
+        .into_json::<Option<TicketDTO>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not found");
+    assert_eq!(merged.merged_into_id, Some(target.id));
+    assert_eq!(merged.status, TicketStatus::Closed);
+
+    // Split the moved comment out into a new ticket, linked to the surviving one
+    let response = bob
+        .post(format!(
+            "/api/v1/tickets/{}/comments/{}/split",
+            target.id, comment.id
+        ))
+        .header(ContentType::JSON)
+        .body(json!({"title": "IT missing anvil"}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Created);
+    let split = response
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not split");
+    assert_eq!(split.requester_id, target.requester_id);
+    let comments = carol
+        .get(format!("/api/v1/tickets/{}/comments", split.id))
+        .dispatch()
+        .into_json::<Vec<CommentDTO>>()
+        .expect("body was not a valid list of comments");
+    assert_eq!(
+        comments.iter().map(|c| c.id).collect::<Vec<_>>(),
+        [comment.id]
+    );
+    let links = carol
+        .get(format!("/api/v1/tickets/{}/links", target.id))
+        .dispatch()
+        .into_json::<Vec<TicketLinkDTO>>()
+        .expect("body was not a valid list of links");
+    assert_eq!(
+        links
+            .iter()
+            .map(|link| (link.ticket.id, link.kind))
+            .collect::<Vec<_>>(),
+        [(split.id, LinkKind::Related)]
+    );
+
+    // The parent ticket can't be closed before its child
+    let response = bob
+        .put(format!("/api/v1/tickets/{}/parent", split.id))
+        .header(ContentType::JSON)
+        .body(json!({ "parent_id": target.id }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let children = carol
+        .get(format!("/api/v1/tickets/{}/children", target.id))
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid list of tickets");
+    assert_eq!(
+        children.iter().map(|t| t.id).collect::<Vec<_>>(),
+        [split.id]
+    );
+    assert_eq!(
+        set_status(&bob, target.id, TicketStatus::Closed),
+        Status::BadRequest
+    );
+    assert_eq!(
+        set_status(&bob, split.id, TicketStatus::Resolved),
+        Status::Ok
+    );
+    assert_eq!(
+        set_status(&bob, target.id, TicketStatus::Closed),
+        Status::Ok
+    );
+
+    // Link the other way around as a duplicate, after unlinking
+    let response = bob
+        .delete(format!("/api/v1/tickets/{}/links/{}", split.id, target.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    let response = bob
+        .post(format!("/api/v1/tickets/{}/links", split.id))
+        .header(ContentType::JSON)
+        .body(json!({"ticket_id": target.id, "kind": "duplicate_of"}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Created);
+    let links = carol
+        .get(format!("/api/v1/tickets/{}/links", target.id))
+        .dispatch()
+        .into_json::<Vec<TicketLinkDTO>>()
+        .expect("body was not a valid list of links");
+    assert_eq!(links[0].kind, LinkKind::DuplicatedBy);
+
+    // Watch the ticket
+    let response = bob
+        .put(format!("/api/v1/tickets/{}/watch", split.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::Created);
+    let watchers = carol
+        .get(format!("/api/v1/tickets/{}/watchers", split.id))
+        .dispatch()
+        .into_json::<Vec<Uuid>>()
+        .expect("body was not a valid list of watchers");
+    assert_eq!(watchers.len(), 1);
+    let response = bob
+        .delete(format!("/api/v1/tickets/{}/watch", split.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+}
+
+/// Sunny integration test for merging a ticket into one of its descendants: the surviving ticket
+/// takes the place of the merged one instead of descending from its own child.
+#[test]
+fn it_sunny_merge_into_descendant() {
+    let (bob, carol) = (logged_in_client("bob"), logged_in_client("carol"));
+    let source = open_ticket(&carol, "IT rocket skates order");
+    let child = open_ticket(&carol, "IT rocket skates delivery");
+    let grandchild = open_ticket(&carol, "IT rocket skates wheels");
+    for (id, parent_id) in [(child.id, source.id), (grandchild.id, child.id)] {
+        let response = bob
+            .put(format!("/api/v1/tickets/{}/parent", id))
+            .header(ContentType::JSON)
+            .body(json!({ "parent_id": parent_id }).to_string())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    let response = bob
+        .post(format!("/api/v1/tickets/{}/merge", source.id))
+        .header(ContentType::JSON)
+        .body(json!({ "into_id": grandchild.id }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let get = |id: Uuid| {
+        carol
+            .get(format!("/api/v1/tickets/{}", id))
+            .dispatch()
+            .into_json::<Option<TicketDTO>>()
+            .expect("body was not a valid ticket")
+            .expect("ticket was not found")
+    };
+    assert_eq!(get(grandchild.id).parent_id, source.parent_id);
+    assert_eq!(get(child.id).parent_id, Some(grandchild.id));
+}
+
+/// Rainy integration test for relationships between tickets: merges across requesters, cycles of
+/// parents, conflicting links and access from customers.
+#[test]
+fn it_rainy_relation() {
+    let (bob, carol, dave) = (
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+        logged_in_client("dave"),
+    );
+    let carol_ticket = open_ticket(&carol, "IT broken jet pack");
+    let dave_ticket = open_ticket(&dave, "IT broken jet pack too");
+
+    let merge = |id: Uuid, into_id: Uuid| {
+        bob.post(format!("/api/v1/tickets/{}/merge", id))
+            .header(ContentType::JSON)
+            .body(json!({ "into_id": into_id }).to_string())
+            .dispatch()
+            .status()
+    };
+    assert_eq!(merge(dave_ticket.id, carol_ticket.id), Status::BadRequest);
+    assert_eq!(merge(carol_ticket.id, carol_ticket.id), Status::BadRequest);
+    assert_eq!(merge(carol_ticket.id, Uuid::new_v4()), Status::NotFound);
+    let response = carol
+        .post(format!("/api/v1/tickets/{}/merge", carol_ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({ "into_id": dave_ticket.id }).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let set_parent = |id: Uuid, parent_id: Uuid| {
+        bob.put(format!("/api/v1/tickets/{}/parent", id))
+            .header(ContentType::JSON)
+            .body(json!({ "parent_id": parent_id }).to_string())
+            .dispatch()
+            .status()
+    };
+    assert_eq!(set_parent(dave_ticket.id, carol_ticket.id), Status::Ok);
+    assert_eq!(
+        set_parent(carol_ticket.id, dave_ticket.id),
+        Status::BadRequest
+    );
+    assert_eq!(
+        set_parent(carol_ticket.id, carol_ticket.id),
+        Status::BadRequest
+    );
+
+    // Customers don't see the tickets of other organisations among the children
+    let children = carol
+        .get(format!("/api/v1/tickets/{}/children", carol_ticket.id))
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid list of tickets");
+    assert!(children.is_empty());
+    let response = dave
+        .get(format!("/api/v1/tickets/{}/children", carol_ticket.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    let link = |kind: &str| {
+        bob.post(format!("/api/v1/tickets/{}/links", carol_ticket.id))
+            .header(ContentType::JSON)
+            .body(json!({"ticket_id": dave_ticket.id, "kind": kind}).to_string())
+            .dispatch()
+            .status()
+    };
+    assert_eq!(link("related"), Status::Created);
+    assert_eq!(link("related"), Status::Ok);
+    assert_eq!(link("duplicate_of"), Status::Conflict);
+    let response = bob
+        .delete(format!(
+            "/api/v1/tickets/{}/links/{}",
+            carol_ticket.id,
+            Uuid::new_v4()
+        ))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    let response = dave
+        .put(format!("/api/v1/tickets/{}/watch", carol_ticket.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
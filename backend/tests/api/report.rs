@@ -0,0 +1,169 @@
+use crate::logged_in_client;
+use chrono::{Duration, SecondsFormat, Utc};
+use common::{
+    report::{Dimension, Metric, ReportDTO, SlaPolicyDTO},
+    ticket::{Priority, TicketDTO},
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Opens a ticket as Carol in a category, and resolves it as Alice.
+fn resolve_ticket(alice: &Client, category: &str) -> TicketDTO {
+    let carol = logged_in_client("carol");
+    let ticket = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(
+            json!({"title": "IT report", "description": "The tunnel was painted on.", "category": category})
+                .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+
+    let response = alice
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"status": "resolved"}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    ticket
+}
+
+/// Gets the query of a report by category over the last and next minute.
+fn report_query() -> String {
+    let now = Utc::now();
+    format!(
+        "by=category&period=week&tz=Europe/Madrid&from={}&to={}",
+        (now - Duration::minutes(1)).to_rfc3339_opts(SecondsFormat::Micros, true),
+        (now + Duration::minutes(1)).to_rfc3339_opts(SecondsFormat::Micros, true),
+    )
+}
+
+/// Sunny integration test for reports: resolved tickets are reported and exported, and the SLA
+/// policies can be changed.
+#[test]
+fn it_sunny_report() {
+    let alice = logged_in_client("alice");
+    let category = format!("it-report-{}", Uuid::new_v4().to_simple());
+    let _ = resolve_ticket(&alice, &category);
+    let query = report_query();
+
+    for (metric, value) in [(Metric::Volume, 1.0), (Metric::SlaCompliance, 100.0)] {
+        let response = alice
+            .get(format!("/api/v1/reports/{}?{}", metric, query))
+            .dispatch();
+        assert_eq!(
+            response.status(),
+            Status::Ok,
+            "response HTTP status code was not 200 OK"
+        );
+        let report = response
+            .into_json::<Result<ReportDTO, String>>()
+            .expect("body was not a valid report")
+            .expect("report was not run");
+        assert_eq!(report.metric, metric);
+        assert_eq!(report.by, Dimension::Category);
+        let row = report
+            .rows
+            .iter()
+            .find(|row| row.group.as_ref() == Some(&category))
+            .expect("the category was not reported");
+        assert_eq!(row.value, Some(value));
+        assert_eq!(row.count, 1);
+    }
+
+    let response = alice
+        .get(format!("/api/v1/reports/resolution/export.csv?{}", query))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::CSV));
+    let csv = response.into_string().expect("body was not text");
+    assert!(csv.starts_with("period,group_id,group,value,count\r\n"));
+    assert!(csv.contains(&format!(",,{},", category)));
+
+    let response = alice
+        .get(format!("/api/v1/reports/backlog/export.xlsx?{}", query))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let xlsx = response.into_bytes().expect("body was empty");
+    assert!(xlsx.starts_with(b"PK\x03\x04"));
+
+    let policies = alice
+        .get("/api/v1/sla-policies")
+        .dispatch()
+        .into_json::<Vec<SlaPolicyDTO>>()
+        .expect("body was not a valid SLA policy list");
+    assert_eq!(policies.len(), Priority::ALL.len());
+    let urgent = policies
+        .iter()
+        .find(|policy| policy.priority == Priority::Urgent)
+        .expect("there was no policy for urgent tickets");
+    let response = alice
+        .put("/api/v1/sla-policies/urgent")
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "response_minutes": urgent.response_minutes,
+                "resolution_minutes": urgent.resolution_minutes,
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+}
+
+/// Rainy integration test for reports: invalid parameters are rejected, and only agents run
+/// reports and only administrators change SLA policies.
+#[test]
+fn it_rainy_report() {
+    let bob = logged_in_client("bob");
+    for url in [
+        "/api/v1/reports/happiness",
+        "/api/v1/reports/volume?by=planet",
+        "/api/v1/reports/volume?period=decade",
+        "/api/v1/reports/volume?tz=Mars/Olympus_Mons",
+        "/api/v1/reports/volume?from=2022-08-02T00:00:00Z&to=2022-08-01T00:00:00Z",
+        "/api/v1/reports/volume?from=1900-01-01T00:00:00Z",
+        "/api/v1/reports/volume/export.csv?from=yesterday",
+        "/api/v1/reports/volume/export.xlsx?tz=Nowhere",
+    ] {
+        let response = bob.get(url).dispatch();
+        assert_eq!(response.status(), Status::BadRequest, "{}", url);
+    }
+
+    let carol = logged_in_client("carol");
+    let response = carol.get("/api/v1/reports/volume").dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+
+    let body = json!({"response_minutes": 60, "resolution_minutes": 240}).to_string();
+    let response = bob
+        .put("/api/v1/sla-policies/urgent")
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    let alice = logged_in_client("alice");
+    let response = alice
+        .put("/api/v1/sla-policies/critical")
+        .header(ContentType::JSON)
+        .body(&body)
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    let response = alice
+        .put("/api/v1/sla-policies/urgent")
+        .header(ContentType::JSON)
+        .body(json!({"response_minutes": 60, "resolution_minutes": 30}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+}
@@ -0,0 +1,79 @@
+use crate::logged_in_client;
+use common::{search::TicketHitDTO, ticket::TicketDTO};
+use rocket::http::{ContentType, Status};
+
+/// Searches the tickets as a user.
+fn search(username: &str, query: &str) -> Vec<TicketHitDTO> {
+    let client = logged_in_client(username);
+    let response = client.get(format!("/api/v1/search?{}", query)).dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    response
+        .into_json::<Vec<TicketHitDTO>>()
+        .expect("body was not valid search results")
+}
+
+/// Sunny integration test for the search endpoint.
+#[test]
+fn it_sunny_search() {
+    let ticket = logged_in_client("carol")
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"IT search quantum toaster","description":"It burns the bread","priority":"low"}"#)
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+
+    let hits = search("bob", "q=quantum%20toaster&status=new");
+    let hit = hits
+        .iter()
+        .find(|hit| hit.id == ticket.id)
+        .expect("ticket was not found");
+    assert_eq!(hit.number, ticket.number);
+    assert!(hit
+        .title
+        .iter()
+        .any(|fragment| fragment.highlighted && fragment.text == "toaster"));
+
+    assert!(search("carol", "q=toaster")
+        .iter()
+        .any(|hit| hit.id == ticket.id));
+    assert!(search("dave", "q=toaster")
+        .iter()
+        .all(|hit| hit.id != ticket.id));
+    assert!(search("bob", "q=toaster&status=closed")
+        .iter()
+        .all(|hit| hit.id != ticket.id));
+}
+
+/// Rainy integration test for the search endpoint.
+#[test]
+fn it_rainy_search() {
+    let client = logged_in_client("bob");
+    for query in [
+        "q=%20",
+        "q=toaster&status=unknown",
+        "q=toaster&since=yesterday",
+    ] {
+        let response = client.get(format!("/api/v1/search?{}", query)).dispatch();
+        assert_eq!(
+            response.status(),
+            Status::BadRequest,
+            "response HTTP status code was not 400 Bad Request for {}",
+            query
+        );
+    }
+
+    let client = crate::sync_client();
+    let response = client.get("/api/v1/search?q=toaster").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Unauthorized,
+        "response HTTP status code was not 401 Unauthorized"
+    );
+}
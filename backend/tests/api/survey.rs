@@ -0,0 +1,126 @@
+use crate::{logged_in_client, sync_client};
+use common::{
+    survey::SurveyResultDTO,
+    ticket::{Status as TicketStatus, TicketDTO},
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Opens a ticket as Carol and resolves it as Alice, in a category.
+fn resolve_ticket(alice: &Client, title: &str, category: &str) -> TicketDTO {
+    let carol = logged_in_client("carol");
+    let ticket = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(json!({"title": title, "description": "The coyote is unhappy."}).to_string())
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+
+    let response = alice
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(json!({"status": "resolved", "category": category}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let ticket = response
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not updated");
+    assert_eq!(ticket.status, TicketStatus::Resolved);
+
+    ticket
+}
+
+/// Gets the surveys of a ticket, as Alice.
+fn surveys(alice: &Client, ticket_id: Uuid) -> Vec<SurveyResultDTO> {
+    alice
+        .get(format!("/api/v1/tickets/{}/surveys", ticket_id))
+        .dispatch()
+        .into_json::<Vec<SurveyResultDTO>>()
+        .expect("body was not a valid survey list")
+}
+
+/// Sunny integration test for surveys: resolving a ticket asks its requester for a rating, unless
+/// its category is exempted.
+#[test]
+fn it_sunny_survey() {
+    let alice = logged_in_client("alice");
+    let ticket = resolve_ticket(&alice, "IT rocket skates", "it-hardware");
+    let found = surveys(&alice, ticket.id);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].ticket_id, ticket.id);
+    assert_eq!(found[0].rating, None);
+    assert_eq!(found[0].answered_on, None);
+
+    let category = format!("it-internal-{}", Uuid::new_v4().to_simple());
+    let url = format!("/api/v1/survey-suppressions/{}", category);
+    let response = alice.put(&url).dispatch();
+    assert_eq!(response.status(), Status::Created);
+    let response = alice.put(&url).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let suppressions = alice
+        .get("/api/v1/survey-suppressions")
+        .dispatch()
+        .into_json::<Vec<String>>()
+        .expect("body was not a valid category list");
+    assert!(suppressions.contains(&category));
+
+    let ticket = resolve_ticket(&alice, "IT giant magnet", &category);
+    assert!(surveys(&alice, ticket.id).is_empty());
+
+    let response = alice.delete(&url).dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+    let response = alice.delete(&url).dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+/// Rainy integration test for surveys: unknown links are not found, and only the staff see the
+/// answers and only administrators exempt categories.
+#[test]
+fn it_rainy_survey() {
+    let client = sync_client();
+    let response = client.get("/api/v1/surveys/not-a-token").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    let response = client
+        .post("/api/v1/surveys/not-a-token")
+        .header(ContentType::JSON)
+        .body(json!({"rating": "good"}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    let response = client
+        .post("/api/v1/surveys/not-a-token")
+        .header(ContentType::JSON)
+        .body(json!({"rating": "good", "comment": "a".repeat(2001)}).to_string())
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let alice = logged_in_client("alice");
+    let ticket = resolve_ticket(&alice, "IT dehydrated boulders", "it-hardware");
+    let carol = logged_in_client("carol");
+    let response = carol
+        .get(format!("/api/v1/tickets/{}/surveys", ticket.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    let response = alice
+        .get(format!("/api/v1/tickets/{}/surveys", Uuid::new_v4()))
+        .dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    let bob = logged_in_client("bob");
+    let response = bob
+        .put("/api/v1/survey-suppressions/it-hardware")
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    let response = bob.get("/api/v1/survey-suppressions").dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+}
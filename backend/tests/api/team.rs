@@ -0,0 +1,253 @@
+use crate::logged_in_client;
+use common::{
+    team::{AssignmentDTO, QueueDTO, Strategy, TeamDTO},
+    ticket::TicketDTO,
+    user::UserDTO,
+};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use uuid::Uuid;
+
+/// Creates a team with Bob as its only member, and a queue with the given strategy.
+fn setup(client: &Client, strategy: Strategy) -> (TeamDTO, QueueDTO, UserDTO) {
+    let suffix = Uuid::new_v4().to_simple().to_string()[..8].to_owned();
+    let bob = logged_in_client("bob")
+        .get("/api/v1/login")
+        .dispatch()
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+
+    let response = client
+        .post("/api/v1/teams")
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"name":"IT Team {}"}}"#, suffix))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let team = response
+        .into_json::<Result<TeamDTO, String>>()
+        .expect("body was not a valid team")
+        .expect("team was not created");
+
+    let response = client
+        .put(format!("/api/v1/teams/{}/members", team.id))
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"user_id":"{}","skills":[" Printers ","printers"]}}"#,
+            bob.id
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+
+    let response = client
+        .post("/api/v1/queues")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"name":"IT Queue {}","team_id":"{}","strategy":"{}"}}"#,
+            suffix, team.id, strategy
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let queue = response
+        .into_json::<Result<QueueDTO, String>>()
+        .expect("body was not a valid queue")
+        .expect("queue was not created");
+
+    (team, queue, bob)
+}
+
+/// Sunny integration test for the `/api/v1/teams` endpoint.
+#[test]
+fn it_sunny_teams() {
+    let client = logged_in_client("alice");
+    let (team, queue, bob) = setup(&client, Strategy::Manual);
+
+    let teams = client
+        .get("/api/v1/teams")
+        .dispatch()
+        .into_json::<Vec<TeamDTO>>()
+        .expect("body was not a valid list of teams");
+    let listed = teams
+        .iter()
+        .find(|t| t.id == team.id)
+        .expect("team was not listed");
+    assert_eq!(listed.members.len(), 1);
+    assert_eq!(listed.members[0].user_id, bob.id);
+    assert_eq!(listed.members[0].skills, vec!["printers".to_owned()]);
+
+    let queues = logged_in_client("carol")
+        .get("/api/v1/queues")
+        .dispatch()
+        .into_json::<Vec<QueueDTO>>()
+        .expect("body was not a valid list of queues");
+    assert!(queues.contains(&queue), "queue was not listed");
+}
+
+/// Rainy integration test for adding a customer to a team.
+#[test]
+fn it_rainy_team_member_customer() {
+    let client = logged_in_client("alice");
+    let (team, _, _) = setup(&client, Strategy::Manual);
+    let carol = logged_in_client("carol")
+        .get("/api/v1/login")
+        .dispatch()
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+
+    let response = client
+        .put(format!("/api/v1/teams/{}/members", team.id))
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"user_id":"{}"}}"#, carol.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+}
+
+/// Rainy integration test for creating a team as an agent.
+#[test]
+fn it_rainy_create_team_agent() {
+    let client = logged_in_client("bob");
+    let response = client
+        .post("/api/v1/teams")
+        .header(ContentType::JSON)
+        .body(r#"{"name":"IT Rogue team"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Rainy integration test for creating a queue for a nonexistent team.
+#[test]
+fn it_rainy_create_queue_team() {
+    let client = logged_in_client("alice");
+    let response = client
+        .post("/api/v1/queues")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"name":"IT Orphan queue","team_id":"{}"}}"#,
+            Uuid::new_v4()
+        ))
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+}
+
+/// Sunny integration test for the automatic assignment of new tickets.
+#[test]
+fn it_sunny_auto_assign() {
+    let (_, queue, bob) = setup(&logged_in_client("alice"), Strategy::RoundRobin);
+
+    let carol = logged_in_client("carol");
+    let response = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"title":"IT queued ticket","description":"","queue_id":"{}"}}"#,
+            queue.id
+        ))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let ticket = response
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+    assert_eq!(ticket.queue_id, Some(queue.id));
+    assert_eq!(ticket.assignee_id, Some(bob.id));
+
+    let history = logged_in_client("bob")
+        .get(format!("/api/v1/tickets/{}/assignments", ticket.id))
+        .dispatch()
+        .into_json::<Vec<AssignmentDTO>>()
+        .expect("body was not a valid assignment history");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].strategy, Some(Strategy::RoundRobin));
+}
+
+/// Sunny integration test for moving a ticket to a queue and reassigning it.
+#[test]
+fn it_sunny_move_reassign() {
+    let alice = logged_in_client("alice");
+    let (_, queue, bob) = setup(&alice, Strategy::LeastLoaded);
+    let ticket = logged_in_client("carol")
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"IT moved ticket","description":""}"#)
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+
+    let moved = alice
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"queue_id":"{}"}}"#, queue.id))
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not moved");
+    assert_eq!(moved.assignee_id, Some(bob.id));
+
+    let unassigned = alice
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(r#"{"assignee_id":null}"#)
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not reassigned");
+    assert_eq!(unassigned.assignee_id, None);
+
+    let history = alice
+        .get(format!("/api/v1/tickets/{}/assignments", ticket.id))
+        .dispatch()
+        .into_json::<Vec<AssignmentDTO>>()
+        .expect("body was not a valid assignment history");
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1].previous_assignee_id, Some(bob.id));
+    assert_eq!(history[1].assignee_id, None);
+    assert_eq!(history[1].strategy, None);
+    assert!(history[1].assigned_by.is_some());
+}
+
+/// Rainy integration test for the assignment history as a customer.
+#[test]
+fn it_rainy_assignments_customer() {
+    let client = logged_in_client("carol");
+    let response = client
+        .get(format!("/api/v1/tickets/{}/assignments", Uuid::new_v4()))
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
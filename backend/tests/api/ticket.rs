@@ -0,0 +1,170 @@
+use crate::{logged_in_client, open_ticket};
+use common::{
+    audit::{AuditEntryDTO, AuditEvent},
+    ticket::{Status as TicketStatus, TicketDTO},
+    user::UserDTO,
+};
+use rocket::http::{ContentType, Status};
+
+/// Sunny integration test for the `/api/v1/tickets` endpoint.
+#[test]
+fn it_sunny_open_ticket() {
+    let carol = logged_in_client("carol");
+    let ticket = open_ticket(&carol, "IT open ticket");
+
+    assert_eq!(ticket.status, TicketStatus::New);
+    assert!(
+        ticket.organisation_id.is_some(),
+        "the ticket was not assigned to Carol's organisation"
+    );
+
+    let tickets = carol
+        .get("/api/v1/tickets")
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid list of tickets");
+    assert!(tickets.iter().any(|t| t.id == ticket.id));
+
+    let tickets = carol
+        .get("/api/v1/tickets?limit=1")
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid list of tickets");
+    assert_eq!(tickets.len(), 1);
+}
+
+/// Rainy integration test for the `/api/v1/tickets` endpoint, with an empty title.
+#[test]
+fn it_rainy_open_ticket_empty_title() {
+    let client = logged_in_client("carol");
+    let response = client
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"  ","description":""}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+}
+
+/// Rainy integration test for the `/api/v1/tickets` endpoint, for another organisation.
+#[test]
+fn it_rainy_open_ticket_other_organisation() {
+    let carol_ticket = open_ticket(&logged_in_client("carol"), "IT organisation ticket");
+
+    let client = logged_in_client("dave");
+    let response = client
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"title":"IT intruder","description":"","organisation_id":"{}"}}"#,
+            carol_ticket.organisation_id.unwrap()
+        ))
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Rainy integration test for the tenant isolation of tickets.
+#[test]
+fn it_rainy_ticket_isolation() {
+    let ticket = open_ticket(&logged_in_client("carol"), "IT isolated ticket");
+
+    let dave = logged_in_client("dave");
+    let tickets = dave
+        .get("/api/v1/tickets")
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid list of tickets");
+    assert!(
+        tickets
+            .iter()
+            .all(|t| t.organisation_id != ticket.organisation_id),
+        "Dave can see tickets from Acme"
+    );
+
+    let response = dave
+        .get(format!("/api/v1/tickets/{}", ticket.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NotFound,
+        "response HTTP status code was not 404 Not Found"
+    );
+}
+
+/// Sunny integration test for the `/api/v1/tickets/<id>` update endpoint.
+#[test]
+fn it_sunny_update_ticket() {
+    let ticket = open_ticket(&logged_in_client("carol"), "IT updated ticket");
+
+    let client = logged_in_client("bob");
+    let bob = client
+        .get("/api/v1/login")
+        .dispatch()
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+    let response = client
+        .get(format!("/api/v1/tickets/{}", ticket.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "agents can't see customer tickets"
+    );
+
+    let response = client
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(format!(r#"{{"status":"open","assignee_id":"{}"}}"#, bob.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let updated = response
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not updated");
+    assert_eq!(updated.status, TicketStatus::Open);
+    assert_eq!(updated.assignee_id, Some(bob.id));
+
+    let entries = logged_in_client("alice")
+        .get("/api/v1/audit?kind=ticket_field_change")
+        .dispatch()
+        .into_json::<Vec<AuditEntryDTO>>()
+        .expect("body was not a valid list of audit entries");
+    let changes = entries
+        .iter()
+        .filter(|entry| {
+            matches!(entry.event, AuditEvent::TicketFieldChange { ticket_id, .. } if ticket_id == ticket.id)
+        })
+        .count();
+    assert_eq!(changes, 2, "the field changes were not audited");
+}
+
+/// Rainy integration test for the `/api/v1/tickets/<id>` update endpoint as a customer.
+#[test]
+fn it_rainy_update_ticket_customer() {
+    let client = logged_in_client("carol");
+    let ticket = open_ticket(&client, "IT customer update ticket");
+
+    let response = client
+        .patch(format!("/api/v1/tickets/{}", ticket.id))
+        .header(ContentType::JSON)
+        .body(r#"{"status":"closed"}"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
@@ -0,0 +1,76 @@
+use crate::logged_in_client;
+use common::user::{Role, UserDTO};
+use rocket::http::{ContentType, Status};
+
+/// Sunny integration test for the `/api/v1/users/<id>/role` endpoint.
+#[test]
+fn it_sunny_change_role() {
+    let bob = logged_in_client("bob")
+        .get("/api/v1/login")
+        .dispatch()
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+
+    let client = logged_in_client("alice");
+    let response = client
+        .put(format!("/api/v1/users/{}/role", bob.id))
+        .header(ContentType::JSON)
+        .body(r#"{"role":"agent"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let user = response
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+    assert_eq!(user.role, Role::Agent);
+}
+
+/// Rainy integration test for the `/api/v1/users/<id>/role` endpoint, for the own user.
+#[test]
+fn it_rainy_change_own_role() {
+    let client = logged_in_client("alice");
+    let alice = client
+        .get("/api/v1/login")
+        .dispatch()
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+
+    let response = client
+        .put(format!("/api/v1/users/{}/role", alice.id))
+        .header(ContentType::JSON)
+        .body(r#"{"role":"customer"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
+
+/// Rainy integration test for the `/api/v1/users/<id>/role` endpoint as an agent.
+#[test]
+fn it_rainy_change_role_agent() {
+    let client = logged_in_client("bob");
+    let bob = client
+        .get("/api/v1/login")
+        .dispatch()
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user");
+
+    let response = client
+        .put(format!("/api/v1/users/{}/role", bob.id))
+        .header(ContentType::JSON)
+        .body(r#"{"role":"admin"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
@@ -0,0 +1,167 @@
+use crate::logged_in_client;
+use common::{ticket::TicketDTO, view::ViewDTO};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use uuid::Uuid;
+
+/// Saves a view, returning the response status and body.
+fn save_view(
+    client: &Client,
+    name: &str,
+    query: &str,
+    shared: bool,
+) -> (Status, Result<ViewDTO, String>) {
+    let response = client
+        .post("/api/v1/views")
+        .header(ContentType::JSON)
+        .body(serde_json::json!({ "name": name, "query": query, "shared": shared }).to_string())
+        .dispatch();
+
+    (
+        response.status(),
+        response
+            .into_json::<Result<ViewDTO, String>>()
+            .expect("body was not a valid view"),
+    )
+}
+
+/// Sunny integration test for the ticket query language and the saved views.
+#[test]
+fn it_sunny_views() {
+    let token = Uuid::new_v4().to_simple().to_string();
+    let carol = logged_in_client("carol");
+    let ticket = carol
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"title":"IT view {}","description":"","priority":"high"}}"#,
+            token
+        ))
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+
+    let client = logged_in_client("bob");
+    let tickets = client
+        .get(format!(
+            "/api/v1/tickets?q=requester:carol%20priority%3E%3Dhigh%20{}",
+            token
+        ))
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid ticket list");
+    assert_eq!(
+        tickets.iter().map(|ticket| ticket.id).collect::<Vec<_>>(),
+        vec![ticket.id]
+    );
+
+    // Saved queries are canonicalized
+    let name = format!("IT view {}", token);
+    let (status, view) = save_view(&client, &name, &format!("{}  status:new", token), true);
+    assert_eq!(status, Status::Created);
+    let view = view.expect("view was not created");
+    assert_eq!(view.query, format!("{} status:new", token));
+
+    let listed = carol
+        .get("/api/v1/views")
+        .dispatch()
+        .into_json::<Vec<ViewDTO>>()
+        .expect("body was not a valid view list");
+    assert!(
+        listed.contains(&view),
+        "shared view was not listed for Carol"
+    );
+    let tickets = carol
+        .get(format!("/api/v1/views/{}/tickets", view.id))
+        .dispatch()
+        .into_json::<Vec<TicketDTO>>()
+        .expect("body was not a valid ticket list");
+    assert_eq!(
+        tickets.iter().map(|ticket| ticket.id).collect::<Vec<_>>(),
+        vec![ticket.id]
+    );
+
+    let updated = client
+        .put(format!("/api/v1/views/{}", view.id))
+        .header(ContentType::JSON)
+        .body(
+            serde_json::json!({ "name": name, "query": format!("{} status:closed", token) })
+                .to_string(),
+        )
+        .dispatch()
+        .into_json::<Result<ViewDTO, String>>()
+        .expect("body was not a valid view")
+        .expect("view was not updated");
+    assert!(!updated.shared);
+
+    let response = client
+        .delete(format!("/api/v1/views/{}", view.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Rainy integration test for the ticket query language and the saved views.
+#[test]
+fn it_rainy_views() {
+    let token = Uuid::new_v4().to_simple().to_string();
+    let dave = logged_in_client("dave");
+
+    let response = dave.get("/api/v1/tickets?q=colour:red").dispatch();
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+
+    let (status, view) = save_view(&dave, "IT invalid view", "status>open", false);
+    assert_eq!(status, Status::BadRequest);
+    assert!(view.expect_err("invalid view was saved").contains("status"));
+    let (status, _) = save_view(&dave, " ", "status:open", false);
+    assert_eq!(status, Status::BadRequest);
+    let (status, _) = save_view(&dave, "IT shared view", "status:open", true);
+    assert_eq!(status, Status::Forbidden, "customers can share views");
+
+    // Private views are only visible to their owner
+    let name = format!("IT private view {}", token);
+    let (status, view) = save_view(&dave, &name, "status:open", false);
+    assert_eq!(status, Status::Created);
+    let view = view.expect("view was not created");
+    let (status, _) = save_view(&dave, &name, "status:closed", false);
+    assert_eq!(status, Status::Conflict);
+
+    let alice = logged_in_client("alice");
+    assert!(!alice
+        .get("/api/v1/views")
+        .dispatch()
+        .into_json::<Vec<ViewDTO>>()
+        .expect("body was not a valid view list")
+        .contains(&view));
+    for response in [
+        alice
+            .get(format!("/api/v1/views/{}/tickets", view.id))
+            .dispatch(),
+        alice
+            .delete(format!("/api/v1/views/{}", view.id))
+            .dispatch(),
+    ] {
+        assert_eq!(
+            response.status(),
+            Status::NotFound,
+            "response HTTP status code was not 404 Not Found"
+        );
+    }
+
+    let response = dave.delete(format!("/api/v1/views/{}", view.id)).dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
@@ -0,0 +1,130 @@
+use crate::logged_in_client;
+use common::{
+    ticket::TicketDTO,
+    webhook::{DeliveryDTO, Event, SubscriptionDTO},
+};
+use rocket::http::{ContentType, Status};
+
+/// Sunny integration test for the webhook subscription endpoints.
+#[test]
+fn it_sunny_webhooks() {
+    let client = logged_in_client("alice");
+    let response = client
+        .post("/api/v1/webhooks")
+        .header(ContentType::JSON)
+        .body(r#"{"url":" http://127.0.0.1:9/it ","events":["ticket.created"]}"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+    let subscription = response
+        .into_json::<Result<SubscriptionDTO, String>>()
+        .expect("body was not a valid subscription")
+        .expect("subscription was not created");
+    assert_eq!(subscription.url, "http://127.0.0.1:9/it");
+    assert_eq!(subscription.events, vec![Event::TicketCreated]);
+    assert_eq!(
+        subscription.secret.as_ref().map(String::len),
+        Some(32),
+        "the generated secret was not sent"
+    );
+
+    let ticket = logged_in_client("carol")
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(r#"{"title":"IT webhook","description":"","priority":"low"}"#)
+        .dispatch()
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created");
+    assert_eq!(ticket.title, "IT webhook");
+
+    let deliveries = client
+        .get(format!("/api/v1/webhooks/{}/deliveries", subscription.id))
+        .dispatch()
+        .into_json::<Vec<DeliveryDTO>>()
+        .expect("body was not a valid delivery list");
+    assert!(
+        !deliveries.is_empty(),
+        "the ticket creation was not enqueued"
+    );
+    assert!(deliveries
+        .iter()
+        .all(|delivery| delivery.event == Event::TicketCreated));
+
+    let response = client
+        .put(format!("/api/v1/webhooks/{}", subscription.id))
+        .header(ContentType::JSON)
+        .body(r#"{"url":"https://127.0.0.1:9/it","enabled":false}"#)
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Ok,
+        "response HTTP status code was not 200 OK"
+    );
+    let updated = response
+        .into_json::<Result<SubscriptionDTO, String>>()
+        .expect("body was not a valid subscription")
+        .expect("subscription was not updated");
+    assert!(!updated.enabled);
+    assert!(updated.events.is_empty());
+    assert_eq!(updated.secret, None, "the secret was sent again");
+
+    let response = client
+        .delete(format!("/api/v1/webhooks/{}", subscription.id))
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::NoContent,
+        "response HTTP status code was not 204 No Content"
+    );
+}
+
+/// Rainy integration test for creating a subscription with an invalid URL.
+#[test]
+fn it_rainy_create_webhook_invalid_url() {
+    let client = logged_in_client("alice");
+    let response = client
+        .post("/api/v1/webhooks")
+        .header(ContentType::JSON)
+        .body(r#"{"url":"ftp://127.0.0.1/it"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+}
+
+/// Rainy integration test for creating a subscription with a short secret.
+#[test]
+fn it_rainy_create_webhook_short_secret() {
+    let client = logged_in_client("alice");
+    let response = client
+        .post("/api/v1/webhooks")
+        .header(ContentType::JSON)
+        .body(r#"{"url":"http://127.0.0.1/it","secret":"short"}"#)
+        .dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::BadRequest,
+        "response HTTP status code was not 400 Bad Request"
+    );
+}
+
+/// Rainy integration test for listing the subscriptions as an agent.
+#[test]
+fn it_rainy_list_webhooks_agent() {
+    let client = logged_in_client("bob");
+    let response = client.get("/api/v1/webhooks").dispatch();
+
+    assert_eq!(
+        response.status(),
+        Status::Forbidden,
+        "response HTTP status code was not 403 Forbidden"
+    );
+}
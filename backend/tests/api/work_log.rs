@@ -0,0 +1,235 @@
+use crate::{logged_in_client, open_ticket};
+use chrono::{Duration, SecondsFormat, Utc};
+use common::work_log::{Activity, ContractDTO, TimeTotalDTO, WorkLogDTO};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Sunny integration test for time tracking: work is logged on a ticket, counted against the
+/// contract of its organisation, totalled and exported.
+#[test]
+fn it_sunny_work_log() {
+    let (alice, bob, carol) = (
+        logged_in_client("alice"),
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+    );
+    let ticket = open_ticket(&carol, "IT anvil delivery");
+    let organisation_id = ticket.organisation_id.expect("ticket had no organisation");
+    // The period is short enough for the work logged by earlier runs to be left out
+    let start = Utc::now();
+    let (from, to) = (
+        start.to_rfc3339_opts(SecondsFormat::Micros, true),
+        (start + Duration::milliseconds(1)).to_rfc3339_opts(SecondsFormat::Micros, true),
+    );
+
+    let response = alice
+        .post(format!(
+            "/api/v1/organisations/{}/contracts",
+            organisation_id
+        ))
+        .header(ContentType::JSON)
+        .body(
+            json!({"name": "IT gold", "prepaid_minutes": 600, "starts_on": from, "ends_on": to})
+                .to_string(),
+        )
+        .dispatch();
+    assert_eq!(response.status(), Status::Created);
+    let contract = response
+        .into_json::<Result<ContractDTO, String>>()
+        .expect("body was not a valid contract")
+        .expect("contract was not created");
+
+    // Log billable and non-billable work, then correct an entry
+    let log = |minutes: i32, billable: bool| {
+        let response = bob
+            .post(format!("/api/v1/tickets/{}/work-logs", ticket.id))
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "minutes": minutes,
+                    "billable": billable,
+                    "activity": "travel",
+                    "description": "Drove to the canyon, twice",
+                    "worked_on": from,
+                })
+                .to_string(),
+            )
+            .dispatch();
+        assert_eq!(response.status(), Status::Created);
+        response
+            .into_json::<Result<WorkLogDTO, String>>()
+            .expect("body was not a valid work log")
+            .expect("work was not logged")
+    };
+    let billable = log(60, true);
+    let _ = log(30, false);
+    assert_eq!(billable.activity, Activity::Travel);
+    let response = bob
+        .put(format!("/api/v1/work-logs/{}", billable.id))
+        .header(ContentType::JSON)
+        .body(
+            json!({
+                "minutes": 90,
+                "activity": "travel",
+                "description": billable.description,
+                "worked_on": billable.worked_on,
+            })
+            .to_string(),
+        )
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let logs = bob
+        .get(format!("/api/v1/tickets/{}/work-logs", ticket.id))
+        .dispatch()
+        .into_json::<Vec<WorkLogDTO>>()
+        .expect("body was not a valid list of work logs");
+    assert_eq!(
+        logs.iter().map(|log| log.minutes).collect::<Vec<_>>(),
+        [90, 30]
+    );
+
+    let contracts = bob
+        .get(format!(
+            "/api/v1/organisations/{}/contracts",
+            organisation_id
+        ))
+        .dispatch()
+        .into_json::<Vec<ContractDTO>>()
+        .expect("body was not a valid list of contracts");
+    let found = contracts
+        .iter()
+        .find(|c| c.id == contract.id)
+        .expect("contract was not listed");
+    assert_eq!((found.used_minutes, found.balance_minutes), (90, 510));
+
+    let totals = bob
+        .get(format!(
+            "/api/v1/work-logs/totals?by=organisation&from={}&to={}",
+            from, to
+        ))
+        .dispatch()
+        .into_json::<Result<Vec<TimeTotalDTO>, String>>()
+        .expect("body was not a valid list of totals")
+        .expect("time was not totalled");
+    let total = totals
+        .iter()
+        .find(|t| t.id == Some(organisation_id))
+        .expect("organisation was not totalled");
+    assert_eq!((total.minutes, total.billable_minutes), (120, 90));
+
+    let response = bob
+        .get(format!(
+            "/api/v1/work-logs/export.csv?from={}&to={}&organisation_id={}",
+            from, to, organisation_id
+        ))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::CSV));
+    let csv = response.into_string().expect("export had no body");
+    assert!(csv.starts_with("worked_on,ticket,organisation,agent,"));
+    assert!(
+        csv.contains(&format!(
+            ",{},Acme,bob,travel,90,true,\"Drove to the canyon, twice\"\r\n",
+            ticket.number
+        )),
+        "{}",
+        csv
+    );
+
+    let response = alice
+        .delete(format!("/api/v1/contracts/{}", contract.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+}
+
+/// Rainy integration test for time tracking: invalid entries, entries of other agents, customers
+/// and contracts managed by agents are rejected.
+#[test]
+fn it_rainy_work_log() {
+    let (alice, bob, carol) = (
+        logged_in_client("alice"),
+        logged_in_client("bob"),
+        logged_in_client("carol"),
+    );
+    let ticket = open_ticket(&carol, "IT anvil delivery, again");
+    let url = format!("/api/v1/tickets/{}/work-logs", ticket.id);
+    let post = |client: &Client, url: &str, body: &str| {
+        client
+            .post(url)
+            .header(ContentType::JSON)
+            .body(body)
+            .dispatch()
+            .status()
+    };
+
+    assert_eq!(post(&bob, &url, r#"{"minutes":0}"#), Status::BadRequest);
+    assert_eq!(post(&bob, &url, r#"{"minutes":1441}"#), Status::BadRequest);
+    assert_eq!(post(&carol, &url, r#"{"minutes":15}"#), Status::Forbidden);
+    assert_eq!(
+        post(
+            &bob,
+            &format!("/api/v1/tickets/{}/work-logs", Uuid::new_v4()),
+            r#"{"minutes":15}"#
+        ),
+        Status::NotFound
+    );
+
+    // Only the author or an administrator can correct or remove an entry
+    let log = alice
+        .post(&url)
+        .header(ContentType::JSON)
+        .body(r#"{"minutes":15}"#)
+        .dispatch()
+        .into_json::<Result<WorkLogDTO, String>>()
+        .expect("body was not a valid work log")
+        .expect("work was not logged");
+    let response = bob
+        .put(format!("/api/v1/work-logs/{}", log.id))
+        .header(ContentType::JSON)
+        .body(r#"{"minutes":30}"#)
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    let response = bob
+        .delete(format!("/api/v1/work-logs/{}", log.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+    let response = alice
+        .delete(format!("/api/v1/work-logs/{}", log.id))
+        .dispatch();
+    assert_eq!(response.status(), Status::NoContent);
+
+    let response = bob.get("/api/v1/work-logs/totals?by=queue").dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = bob
+        .get("/api/v1/work-logs/export.csv?from=yesterday")
+        .dispatch();
+    assert_eq!(response.status(), Status::BadRequest);
+
+    let organisation_id = ticket.organisation_id.expect("ticket had no organisation");
+    let contracts_url = format!("/api/v1/organisations/{}/contracts", organisation_id);
+    let contract =
+        r#"{"name":"IT silver","prepaid_minutes":60,"starts_on":"2022-08-01T00:00:00Z"}"#;
+    assert_eq!(post(&bob, &contracts_url, contract), Status::Forbidden);
+    assert_eq!(
+        post(
+            &alice,
+            &contracts_url,
+            r#"{"name":"IT silver","prepaid_minutes":60,"starts_on":"2022-08-01T00:00:00Z","ends_on":"2022-07-01T00:00:00Z"}"#
+        ),
+        Status::BadRequest
+    );
+    assert_eq!(
+        post(
+            &alice,
+            &format!("/api/v1/organisations/{}/contracts", Uuid::new_v4()),
+            contract
+        ),
+        Status::NotFound
+    );
+    let response = carol.get(&contracts_url).dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+}
@@ -2,12 +2,100 @@
 
 mod api;
 
-use rocket::local::blocking::Client;
+use common::{ticket::TicketDTO, user::UserDTO};
+use diesel::{connection::SimpleConnection, Connection, PgConnection};
+use rocket::{
+    http::{ContentType, Status},
+    local::blocking::Client,
+    Config,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::{env, sync::Once};
+use uuid::Uuid;
 
-/// Creates a testing client for Rocket.
+/// Connects to the test database, to set up data the API can't create.
+fn connection() -> PgConnection {
+    let _ = dotenv::dotenv().ok();
+
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    PgConnection::establish(&database_url)
+        .unwrap_or_else(|e| panic!("error connecting to {}: {}", database_url, e))
+}
+
+/// Seeds the test users, the first time it's called.
+fn seed() {
+    static SEED: Once = Once::new();
+
+    SEED.call_once(|| {
+        connection()
+            .batch_execute(include_str!("fixtures/users.sql"))
+            .expect("error seeding the test users");
+    });
+}
+
+/// Creates a testing client for Rocket, with the test users seeded.
 fn sync_client() -> Client {
     let _ = dotenv::dotenv().ok();
+    seed();
 
     let rocket = backend_core::initialize();
     Client::tracked(rocket).expect("couldn't generate the Rocket client")
 }
+
+/// Creates a testing client for Rocket, overriding a key of its configuration.
+fn configured_client<T: Serialize>(key: &str, value: T) -> Client {
+    let _ = dotenv::dotenv().ok();
+    seed();
+
+    let rocket = backend_core::initialize_with(Config::figment().merge((key, value)));
+    Client::tracked(rocket).expect("couldn't generate the Rocket client")
+}
+
+/// Creates a testing client for Rocket, logged in as the given test user.
+///
+/// Test users have `<username>_password` as their password.
+fn logged_in_client(username: &str) -> Client {
+    let client = sync_client();
+    let status = client
+        .post("/api/v1/login")
+        .header(ContentType::JSON)
+        .body(format!(
+            r#"{{"user":"{0}","pass":"{0}_password"}}"#,
+            username
+        ))
+        .dispatch()
+        .status();
+    assert_eq!(status, Status::Ok, "couldn't log in as {}", username);
+
+    client
+}
+
+/// Gets the ID of a test user, logging in as them.
+fn user_id(username: &str) -> Uuid {
+    logged_in_client(username)
+        .get("/api/v1/login")
+        .dispatch()
+        .into_json::<UserDTO>()
+        .expect("body was not a valid user")
+        .id
+}
+
+/// Opens a ticket with the given client, with the default priority.
+fn open_ticket(client: &Client, title: &str) -> TicketDTO {
+    let response = client
+        .post("/api/v1/tickets")
+        .header(ContentType::JSON)
+        .body(json!({"title": title, "description": "The rocket skates exploded."}).to_string())
+        .dispatch();
+    assert_eq!(
+        response.status(),
+        Status::Created,
+        "response HTTP status code was not 201 Created"
+    );
+
+    response
+        .into_json::<Result<TicketDTO, String>>()
+        .expect("body was not a valid ticket")
+        .expect("ticket was not created")
+}
@@ -0,0 +1,43 @@
+-- Test users and organisations, applied by the test harness before the tests run
+--
+-- Every statement can run again on an already seeded database. Test users have
+-- `<username>_password` as their password.
+
+-- Give the initial users a role and a password
+UPDATE sys_user
+SET role = 'admin', password = '\x616c69636573616c74307c49a7bbfd628ca6a7d1b142758f109efa3aadb1349f0bda2342953f1f07e29e'
+WHERE username = 'alice';
+
+UPDATE sys_user
+SET role = 'agent', password = '\x626f6273616c74303030ede461ff20dac8e0055bc5ca0ff47ede2b96fdff9c7fa857225335b1d6aea2c6'
+WHERE username = 'bob';
+
+-- Add the test organisations and their customers
+INSERT INTO organisation
+    (name)
+VALUES
+    ('Acme'),
+    ('Globex')
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO organisation_domain
+    (domain, organisation_id)
+SELECT 'acme.test', id FROM organisation WHERE name = 'Acme'
+UNION ALL
+SELECT 'globex.test', id FROM organisation WHERE name = 'Globex'
+ON CONFLICT (domain) DO NOTHING;
+
+INSERT INTO sys_user
+    (active, username, email, password, first_name, last_name, role)
+VALUES
+    (TRUE, 'carol', 'carol@acme.test', '\x6361726f6c73616c74309886835ffdd96ae75d24dace1244be00ee9ab0479f25527a1d6ef6bcfe5ba1a1', 'Carol', 'Jones', 'customer'),
+    (TRUE, 'dave', 'dave@globex.test', '\x6461766573616c743030017a19fb43f57caa4ebf41702893bf1e2042e3b671178b2c1e84d541c6579d8f', 'Dave', 'Brown', 'customer')
+ON CONFLICT (username) DO NOTHING;
+
+INSERT INTO organisation_member
+    (organisation_id, user_id)
+SELECT organisation_domain.organisation_id, sys_user.id
+FROM sys_user
+INNER JOIN organisation_domain ON sys_user.email LIKE '%@' || organisation_domain.domain
+WHERE sys_user.username IN ('carol', 'dave')
+ON CONFLICT (organisation_id, user_id) DO NOTHING;
@@ -0,0 +1,181 @@
+//! Approval workflows.
+//!
+//! Some tickets need to be approved before being worked on. An approval chain is a list of steps
+//! that are followed in order: the approvers of a step decide in parallel, and the step passes
+//! once [any or all of them](Mode) approve. Tickets move on automatically when the last step
+//! passes, or as soon as a step is rejected.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+string_enum! {
+    /// How the decisions of the approvers of a step are combined.
+    pub enum Mode {
+        /// The step passes when any approver approves, and is rejected if all of them reject.
+        Any => "any",
+        /// The step passes when all approvers approve, and is rejected if any of them rejects.
+        All => "all",
+    }
+}
+
+impl Mode {
+    /// Gets the outcome of a step, given the decisions of its approvers so far.
+    ///
+    /// Returns `None` while the step is still waiting for decisions.
+    pub fn outcome(self, decisions: &[Option<Decision>]) -> Option<Decision> {
+        // A single decisive decision is enough, the other one needs to be unanimous
+        let (decisive, unanimous) = match self {
+            Self::Any => (Decision::Approved, Decision::Rejected),
+            Self::All => (Decision::Rejected, Decision::Approved),
+        };
+
+        if decisions.contains(&Some(decisive)) {
+            Some(decisive)
+        } else if !decisions.is_empty() && decisions.iter().all(|d| *d == Some(unanimous)) {
+            Some(unanimous)
+        } else {
+            None
+        }
+    }
+}
+
+string_enum! {
+    /// Decision of an approver.
+    pub enum Decision {
+        Approved => "approved",
+        Rejected => "rejected",
+    }
+}
+
+string_enum! {
+    /// Status of the approval of a ticket.
+    pub enum Status {
+        /// Some steps are still waiting for decisions.
+        Pending => "pending",
+        /// All the steps passed.
+        Approved => "approved",
+        /// A step was rejected.
+        Rejected => "rejected",
+    }
+}
+
+impl From<Decision> for Status {
+    fn from(decision: Decision) -> Self {
+        match decision {
+            Decision::Approved => Self::Approved,
+            Decision::Rejected => Self::Rejected,
+        }
+    }
+}
+
+/// Approver of a step of a chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Approver {
+    /// A given user.
+    User { id: Uuid },
+    /// The user picked in a user field of the request form, such as the requester's manager.
+    ///
+    /// Steps are skipped if none of their approvers was picked.
+    Field { key: String },
+}
+
+/// Definition of a step of an approval chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepDefinition {
+    pub mode: Mode,
+    pub approvers: Vec<Approver>,
+}
+
+/// Checks that the steps of a chain are consistent, returning the reason if they aren't.
+pub fn check_steps(steps: &[StepDefinition]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("chain has no steps".to_owned());
+    }
+
+    for (i, step) in steps.iter().enumerate() {
+        if step.approvers.is_empty() {
+            return Err(format!("step {} has no approvers", i + 1));
+        }
+        if step
+            .approvers
+            .iter()
+            .enumerate()
+            .any(|(j, approver)| step.approvers[..j].contains(approver))
+        {
+            return Err(format!("step {} has duplicated approvers", i + 1));
+        }
+    }
+
+    Ok(())
+}
+
+/// Approval chain, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalChainDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub steps: Vec<StepDefinition>,
+}
+
+/// Approval chain form data, used by administrators to create or update chains.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalChainFormDTO {
+    pub name: String,
+    pub steps: Vec<StepDefinition>,
+}
+
+/// Step of the approval of a ticket, with the approvers resolved when the approval started.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalStepDTO {
+    pub mode: Mode,
+    pub approver_ids: Vec<Uuid>,
+}
+
+/// Decision of an approver of a ticket, or the lack of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApproverDTO {
+    /// Index of the step, starting at 0.
+    pub step: i32,
+    pub user_id: Uuid,
+    pub username: String,
+    pub decision: Option<Decision>,
+    pub comment: String,
+    pub decided_on: Option<DateTime<Utc>>,
+}
+
+/// Approval of a ticket, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalDTO {
+    pub ticket_id: Uuid,
+    pub status: Status,
+    /// Index of the current step, or of the last one if the approval has finished.
+    pub step: i32,
+    pub steps: Vec<ApprovalStepDTO>,
+    /// Approvers of the steps reached so far.
+    pub approvers: Vec<ApproverDTO>,
+}
+
+/// Approval waiting for a decision of the user, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingApprovalDTO {
+    pub ticket_id: Uuid,
+    pub number: i64,
+    pub title: String,
+    pub description: String,
+    /// Index of the step, starting at 0.
+    pub step: i32,
+    pub requested_on: DateTime<Utc>,
+}
+
+/// Decision form data, sent by approvers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionDTO {
+    pub decision: Decision,
+    #[serde(default)]
+    pub comment: String,
+}
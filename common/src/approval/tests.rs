@@ -0,0 +1,39 @@
+use super::*;
+
+/// Steps pass or are rejected as soon as the decisions so far allow it.
+#[test]
+fn ut_sunny_outcome() {
+    use Decision::{Approved, Rejected};
+
+    assert_eq!(Mode::Any.outcome(&[None, Some(Approved)]), Some(Approved));
+    assert_eq!(Mode::Any.outcome(&[Some(Rejected), None]), None);
+    assert_eq!(
+        Mode::Any.outcome(&[Some(Rejected), Some(Rejected)]),
+        Some(Rejected)
+    );
+
+    assert_eq!(Mode::All.outcome(&[Some(Approved), None]), None);
+    assert_eq!(Mode::All.outcome(&[None, Some(Rejected)]), Some(Rejected));
+    assert_eq!(
+        Mode::All.outcome(&[Some(Approved), Some(Approved)]),
+        Some(Approved)
+    );
+    assert_eq!(Mode::All.outcome(&[]), None);
+}
+
+/// Chains without steps or approvers are rejected.
+#[test]
+fn ut_rainy_check_steps() {
+    let manager = Approver::Field {
+        key: "manager".to_owned(),
+    };
+    let step = |approvers: Vec<Approver>| StepDefinition {
+        mode: Mode::All,
+        approvers,
+    };
+
+    assert_eq!(check_steps(&[step(vec![manager.clone()])]), Ok(()));
+    assert!(check_steps(&[]).is_err());
+    assert!(check_steps(&[step(vec![manager.clone()]), step(Vec::new())]).is_err());
+    assert!(check_steps(&[step(vec![manager.clone(), manager])]).is_err());
+}
@@ -0,0 +1,161 @@
+//! Knowledge base articles.
+
+use crate::user::Role;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Publication status of an article.
+    pub enum Status {
+        /// The article is being written, and only agents can see it.
+        Draft => "draft",
+        /// The article can be read by its audience.
+        Published => "published",
+    }
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::Draft
+    }
+}
+
+string_enum! {
+    /// Audience of a published article.
+    ///
+    /// Visibilities are ordered from the widest to the narrowest audience.
+    pub enum Visibility {
+        /// Anyone can read the article, even without logging in.
+        Public => "public",
+        /// Any logged in user can read the article.
+        Customers => "customers",
+        /// Only agents and administrators can read the article.
+        Internal => "internal",
+    }
+}
+
+impl Visibility {
+    /// Gets the visibilities of the articles that a user with the given role can read, or an
+    /// anonymous user if `None`.
+    pub fn readable_by(role: Option<Role>) -> &'static [Self] {
+        match role {
+            None => &Self::ALL[..1],
+            Some(role) if role.is_staff() => Self::ALL,
+            Some(_) => &Self::ALL[..2],
+        }
+    }
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Self::Internal
+    }
+}
+
+/// Category of articles, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+}
+
+/// Category form data, used by administrators to create or update categories.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryFormDTO<'r> {
+    pub name: &'r str,
+    #[serde(default)]
+    pub description: &'r str,
+}
+
+/// Article, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleDTO {
+    pub id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub title: String,
+    /// Markdown source of the article.
+    pub body: String,
+    /// Sanitised HTML rendering of the body.
+    pub html: String,
+    pub status: Status,
+    pub visibility: Visibility,
+    /// Number of the latest revision.
+    pub revision: i32,
+    pub author_id: Uuid,
+    pub published_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
+
+/// Article without its body, sent from the server to the client in article lists.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArticleSummaryDTO {
+    pub id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub title: String,
+    pub status: Status,
+    pub visibility: Visibility,
+    pub updated_on: DateTime<Utc>,
+}
+
+/// Article form data, used by agents to create or update articles.
+///
+/// Updating the title or the body creates a new revision.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArticleFormDTO<'r> {
+    pub category_id: Option<Uuid>,
+    pub title: &'r str,
+    /// Owned, since Markdown bodies have escaped line breaks in JSON.
+    pub body: String,
+    #[serde(default)]
+    pub status: Status,
+    #[serde(default)]
+    pub visibility: Visibility,
+}
+
+/// Past revision of an article, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionDTO {
+    pub revision: i32,
+    pub title: String,
+    pub body: String,
+    pub editor_id: Uuid,
+    pub created_on: DateTime<Utc>,
+}
+
+string_enum! {
+    /// Change of a line between two revisions.
+    pub enum Change {
+        /// The line is in both revisions.
+        Equal => "equal",
+        /// The line was added in the newer revision.
+        Insert => "insert",
+        /// The line was removed in the newer revision.
+        Delete => "delete",
+    }
+}
+
+/// Line of a diff, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLineDTO {
+    pub change: Change,
+    /// Text of the line, without the line break.
+    pub text: String,
+}
+
+/// Line differences between two revisions of an article, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffDTO {
+    pub from: i32,
+    pub to: i32,
+    pub title: Vec<DiffLineDTO>,
+    pub body: Vec<DiffLineDTO>,
+}
+
+/// Article link data, used by agents to link an article to a ticket.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArticleLinkDTO {
+    pub article_id: Uuid,
+}
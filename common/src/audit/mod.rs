@@ -0,0 +1,113 @@
+use crate::{approval::Decision, user::Role};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Security-relevant or ticket event stored in the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// A user logged in.
+    LoginSuccess,
+    /// Someone tried to log in with invalid credentials.
+    LoginFailure { username: String },
+    /// A new user registered.
+    Registration {
+        user_id: Uuid,
+        /// Whether the user registered with an invitation.
+        invited: bool,
+    },
+    /// Pending email registrations were deleted, because a new registration was requested.
+    EmailRegistrationDeleted { email: String },
+    /// A user was invited.
+    InvitationCreated { email: String, role: Role },
+    /// A pending invitation was revoked.
+    InvitationRevoked { code: String },
+    /// The role of a user changed.
+    RoleChange {
+        user_id: Uuid,
+        before: Role,
+        after: Role,
+    },
+    /// A field of a ticket changed.
+    TicketFieldChange {
+        ticket_id: Uuid,
+        field: String,
+        before: Option<String>,
+        after: Option<String>,
+    },
+    /// An approver decided on the approval of a ticket.
+    ApprovalDecision {
+        ticket_id: Uuid,
+        /// Index of the step, starting at 0.
+        step: i32,
+        approver_id: Uuid,
+        decision: Decision,
+        /// Whether the decision was taken with the link of an approval email.
+        by_email: bool,
+    },
+    /// A ticket was merged into another one, which received its comments.
+    TicketMerged { ticket_id: Uuid, into_id: Uuid },
+    /// A comment of a ticket was split out into a new ticket.
+    TicketSplit {
+        ticket_id: Uuid,
+        comment_id: Uuid,
+        into_id: Uuid,
+    },
+}
+
+impl AuditEvent {
+    /// All the event kinds, as stored in the database.
+    pub const KINDS: [&'static str; 11] = [
+        "login_success",
+        "login_failure",
+        "registration",
+        "email_registration_deleted",
+        "invitation_created",
+        "invitation_revoked",
+        "role_change",
+        "ticket_field_change",
+        "approval_decision",
+        "ticket_merged",
+        "ticket_split",
+    ];
+
+    /// Gets the kind of the event, as stored in the database.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::LoginSuccess => "login_success",
+            Self::LoginFailure { .. } => "login_failure",
+            Self::Registration { .. } => "registration",
+            Self::EmailRegistrationDeleted { .. } => "email_registration_deleted",
+            Self::InvitationCreated { .. } => "invitation_created",
+            Self::InvitationRevoked { .. } => "invitation_revoked",
+            Self::RoleChange { .. } => "role_change",
+            Self::TicketFieldChange { .. } => "ticket_field_change",
+            Self::ApprovalDecision { .. } => "approval_decision",
+            Self::TicketMerged { .. } => "ticket_merged",
+            Self::TicketSplit { .. } => "ticket_split",
+        }
+    }
+}
+
+/// Entry of the audit log, sent from the server to administrators.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntryDTO {
+    pub id: i64,
+    pub created_on: DateTime<Utc>,
+    /// User that performed the action, if any.
+    pub actor_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: String,
+    pub event: AuditEvent,
+}
+
+/// Role change form data.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RoleDTO {
+    pub role: Role,
+}
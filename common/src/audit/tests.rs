@@ -0,0 +1,60 @@
+use super::*;
+
+/// The serialized kind of every event must match the kind stored in the database.
+#[test]
+fn ut_sunny_event_kind() {
+    let events = [
+        AuditEvent::LoginSuccess,
+        AuditEvent::LoginFailure {
+            username: "alice".to_owned(),
+        },
+        AuditEvent::Registration {
+            user_id: Uuid::nil(),
+            invited: false,
+        },
+        AuditEvent::EmailRegistrationDeleted {
+            email: "alice@example.com".to_owned(),
+        },
+        AuditEvent::InvitationCreated {
+            email: "alice@example.com".to_owned(),
+            role: Role::Agent,
+        },
+        AuditEvent::InvitationRevoked {
+            code: "0123456789".to_owned(),
+        },
+        AuditEvent::RoleChange {
+            user_id: Uuid::nil(),
+            before: Role::Customer,
+            after: Role::Agent,
+        },
+        AuditEvent::TicketFieldChange {
+            ticket_id: Uuid::nil(),
+            field: "status".to_owned(),
+            before: Some("open".to_owned()),
+            after: None,
+        },
+        AuditEvent::ApprovalDecision {
+            ticket_id: Uuid::nil(),
+            step: 0,
+            approver_id: Uuid::nil(),
+            decision: Decision::Approved,
+            by_email: true,
+        },
+        AuditEvent::TicketMerged {
+            ticket_id: Uuid::nil(),
+            into_id: Uuid::nil(),
+        },
+        AuditEvent::TicketSplit {
+            ticket_id: Uuid::nil(),
+            comment_id: Uuid::nil(),
+            into_id: Uuid::nil(),
+        },
+    ];
+
+    for (event, kind) in events.iter().zip(AuditEvent::KINDS) {
+        assert_eq!(event.kind(), kind);
+
+        let json = serde_json::to_value(event).expect("could not serialize event");
+        assert_eq!(json["kind"], kind, "serialized kind doesn't match");
+    }
+}
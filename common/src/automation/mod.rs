@@ -0,0 +1,290 @@
+//! Rule-based automation of tickets.
+//!
+//! Automation rules are made of a [`Condition`] and a list of [`Action`]s. They are evaluated on
+//! every ticket [`Event`], and their actions are run in order when the condition matches.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    normalize_labels,
+    ticket::{Priority, Status, TicketDTO},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Ticket event that triggers the evaluation of automation rules.
+    pub enum Event {
+        /// The ticket has been created.
+        Created => "created",
+        /// The ticket has been updated.
+        Updated => "updated",
+    }
+}
+
+string_enum! {
+    /// Field of a ticket that can be used in conditions and actions.
+    pub enum Field {
+        Title => "title",
+        Description => "description",
+        Status => "status",
+        Priority => "priority",
+        Category => "category",
+        RequesterId => "requester_id",
+        AssigneeId => "assignee_id",
+        OrganisationId => "organisation_id",
+        QueueId => "queue_id",
+    }
+}
+
+impl Field {
+    /// Gets the value of the field in a ticket, as a string.
+    pub fn value(self, ticket: &TicketDTO) -> Option<String> {
+        match self {
+            Self::Title => Some(ticket.title.clone()),
+            Self::Description => Some(ticket.description.clone()),
+            Self::Status => Some(ticket.status.to_string()),
+            Self::Priority => Some(ticket.priority.to_string()),
+            Self::Category => ticket.category.clone(),
+            Self::RequesterId => Some(ticket.requester_id.to_string()),
+            Self::AssigneeId => ticket.assignee_id.map(|id| id.to_string()),
+            Self::OrganisationId => ticket.organisation_id.map(|id| id.to_string()),
+            Self::QueueId => ticket.queue_id.map(|id| id.to_string()),
+        }
+    }
+
+    /// Checks if the field can be changed with the [`Action::SetField`] action.
+    ///
+    /// Assignees and queues have their own actions, since changing them has side effects.
+    pub fn is_settable(self) -> bool {
+        matches!(
+            self,
+            Self::Title | Self::Status | Self::Priority | Self::Category
+        )
+    }
+}
+
+/// Condition of an automation rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Condition {
+    /// Always matches.
+    Always,
+    /// Matches if all the conditions match.
+    All { conditions: Vec<Condition> },
+    /// Matches if any of the conditions matches.
+    Any { conditions: Vec<Condition> },
+    /// Matches if the condition doesn't match.
+    Not { condition: Box<Condition> },
+    /// Matches a ticket event.
+    Event { event: Event },
+    /// Matches if the field has the given value. A `null` value matches empty fields.
+    Equals { field: Field, value: Option<String> },
+    /// Matches if the field contains the given text, ignoring case.
+    Contains { field: Field, value: String },
+    /// Matches if the field was changed by the event.
+    Changed { field: Field },
+    /// Matches if the ticket has the given tag.
+    HasTag { tag: String },
+}
+
+impl Condition {
+    /// Checks if the condition matches an event of a ticket.
+    ///
+    /// The ticket before the event is only available for updates.
+    pub fn matches(&self, event: Event, before: Option<&TicketDTO>, after: &TicketDTO) -> bool {
+        match self {
+            Self::Always => true,
+            Self::All { conditions } => conditions.iter().all(|c| c.matches(event, before, after)),
+            Self::Any { conditions } => conditions.iter().any(|c| c.matches(event, before, after)),
+            Self::Not { condition } => !condition.matches(event, before, after),
+            Self::Event { event: expected } => event == *expected,
+            Self::Equals { field, value } => field.value(after) == *value,
+            Self::Contains { field, value } => field
+                .value(after)
+                .map_or(false, |v| v.to_lowercase().contains(&value.to_lowercase())),
+            Self::Changed { field } => {
+                before.map_or(false, |before| field.value(before) != field.value(after))
+            }
+            Self::HasTag { tag } => after.tags.contains(&tag.trim().to_lowercase()),
+        }
+    }
+}
+
+/// Action of an automation rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// Sets the value of a field. A `null` value clears the field, if it's optional.
+    SetField { field: Field, value: Option<String> },
+    /// Assigns the ticket to an agent, or removes the assignee.
+    Assign { assignee_id: Option<Uuid> },
+    /// Moves the ticket to a queue, which assigns it following the strategy of the queue.
+    MoveToQueue { queue_id: Uuid },
+    /// Adds a tag to the ticket.
+    AddTag { tag: String },
+    /// Removes a tag from the ticket.
+    RemoveTag { tag: String },
+    /// Sends an email.
+    ///
+    /// The subject and body can contain the `{{number}}`, `{{title}}`, `{{status}}` and
+    /// `{{priority}}` placeholders.
+    SendEmail {
+        to: String,
+        subject: String,
+        body: String,
+    },
+    /// Sends the ticket to the URL of a [webhook subscription](crate::webhook), as JSON in a
+    /// `POST` request signed with the secret of the subscription.
+    CallWebhook { subscription_id: Uuid },
+}
+
+impl Action {
+    /// Validates the action, returning the reason if it's not valid.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Self::SetField { field, value } => {
+                if !field.is_settable() {
+                    return Err("field can't be set");
+                }
+                match (field, value) {
+                    (Field::Category, _) => Ok(()),
+                    (Field::Title, Some(title)) if !title.trim().is_empty() => Ok(()),
+                    (Field::Status, Some(status)) if status.parse::<Status>().is_ok() => Ok(()),
+                    (Field::Priority, Some(priority)) if priority.parse::<Priority>().is_ok() => {
+                        Ok(())
+                    }
+                    _ => Err("invalid field value"),
+                }
+            }
+            Self::AddTag { tag } | Self::RemoveTag { tag } if tag.trim().is_empty() => {
+                Err("tag can't be empty")
+            }
+            Self::SendEmail { to, .. } if !to.contains('@') => Err("invalid email"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the action to a ticket, returning whether the ticket was changed.
+    ///
+    /// Side effects, such as emails and webhooks, are not run, and don't change the ticket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the action is not [valid](Self::validate).
+    pub fn apply(&self, ticket: &mut TicketDTO) -> bool {
+        let before = ticket.clone();
+
+        match self {
+            Self::SetField { field, value } => match (field, value) {
+                (Field::Title, Some(title)) => ticket.title = title.trim().to_owned(),
+                (Field::Status, Some(status)) => {
+                    ticket.status = status.parse().expect("invalid status in action");
+                }
+                (Field::Priority, Some(priority)) => {
+                    ticket.priority = priority.parse().expect("invalid priority in action");
+                }
+                (Field::Category, category) => {
+                    ticket.category = category
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|category| !category.is_empty())
+                        .map(ToOwned::to_owned);
+                }
+                _ => panic!("invalid set field action"),
+            },
+            Self::Assign { assignee_id } => ticket.assignee_id = *assignee_id,
+            Self::MoveToQueue { queue_id } => ticket.queue_id = Some(*queue_id),
+            Self::AddTag { tag } => {
+                ticket.tags = normalize_labels(ticket.tags.iter().chain(Some(tag)));
+            }
+            Self::RemoveTag { tag } => {
+                let tag = tag.trim().to_lowercase();
+                ticket.tags.retain(|t| *t != tag);
+            }
+            Self::SendEmail { .. } | Self::CallWebhook { .. } => {}
+        }
+
+        *ticket != before
+    }
+}
+
+/// Replaces the placeholders of an email template with the values of a ticket.
+pub fn render_template(template: &str, ticket: &TicketDTO) -> String {
+    template
+        .replace("{{number}}", &ticket.number.to_string())
+        .replace("{{title}}", &ticket.title)
+        .replace("{{status}}", ticket.status.as_str())
+        .replace("{{priority}}", ticket.priority.as_str())
+}
+
+/// Automation rule, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub enabled: bool,
+    /// Position of the rule. Rules are evaluated in ascending order.
+    pub position: i32,
+    pub condition: Condition,
+    pub actions: Vec<Action>,
+}
+
+/// Automation rule form data, used by administrators to create or update rules.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleFormDTO {
+    pub name: String,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub position: i32,
+    pub condition: Condition,
+    pub actions: Vec<Action>,
+}
+
+/// Rules are enabled by default.
+fn enabled_default() -> bool {
+    true
+}
+
+/// Dry run request, to test a rule against an existing ticket without changing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunDTO {
+    pub rule: RuleFormDTO,
+    pub ticket_id: Uuid,
+    #[serde(default = "dry_run_event_default")]
+    pub event: Event,
+}
+
+/// Dry runs simulate updates by default, since tickets already exist.
+fn dry_run_event_default() -> Event {
+    Event::Updated
+}
+
+/// Result of a dry run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DryRunResultDTO {
+    /// Whether the condition of the rule matched.
+    pub matched: bool,
+    /// Actions that would run.
+    pub actions: Vec<Action>,
+    /// Ticket after running the actions.
+    pub ticket: TicketDTO,
+}
+
+/// Entry of the automation execution log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntryDTO {
+    pub id: i64,
+    pub rule_id: Uuid,
+    pub ticket_id: Uuid,
+    pub event: Event,
+    /// Number of cascaded updates caused by automations before this execution.
+    pub depth: i16,
+    pub actions: Vec<Action>,
+    /// Error running the actions or their side effects, if any.
+    pub error: Option<String>,
+    pub created_on: DateTime<Utc>,
+}
@@ -0,0 +1,172 @@
+use super::*;
+
+/// Creates a test ticket.
+fn ticket() -> TicketDTO {
+    TicketDTO {
+        id: Uuid::nil(),
+        number: 42,
+        title: "Network down".to_owned(),
+        description: "The whole floor is offline".to_owned(),
+        status: Status::New,
+        priority: Priority::Urgent,
+        requester_id: Uuid::nil(),
+        assignee_id: None,
+        organisation_id: None,
+        queue_id: None,
+        category: Some("network".to_owned()),
+        tags: vec!["vip".to_owned()],
+        custom_fields: Default::default(),
+        merged_into_id: None,
+        parent_id: None,
+        created_on: Utc::now(),
+        updated_on: Utc::now(),
+    }
+}
+
+/// Conditions must match the fields of the ticket and the event.
+#[test]
+fn ut_sunny_condition_matches() {
+    let ticket = ticket();
+    let condition = Condition::All {
+        conditions: vec![
+            Condition::Event {
+                event: Event::Created,
+            },
+            Condition::Equals {
+                field: Field::Priority,
+                value: Some("urgent".to_owned()),
+            },
+            Condition::Equals {
+                field: Field::Category,
+                value: Some("network".to_owned()),
+            },
+            Condition::Contains {
+                field: Field::Description,
+                value: "OFFLINE".to_owned(),
+            },
+            Condition::HasTag {
+                tag: " VIP ".to_owned(),
+            },
+            Condition::Not {
+                condition: Box::new(Condition::Equals {
+                    field: Field::AssigneeId,
+                    value: Some(Uuid::nil().to_string()),
+                }),
+            },
+        ],
+    };
+
+    assert!(condition.matches(Event::Created, None, &ticket));
+    assert!(!condition.matches(Event::Updated, Some(&ticket), &ticket));
+}
+
+/// The `changed` condition only matches fields changed by updates.
+#[test]
+fn ut_sunny_condition_changed() {
+    let before = ticket();
+    let mut after = before.clone();
+    after.status = Status::Open;
+
+    let status = Condition::Changed {
+        field: Field::Status,
+    };
+    let title = Condition::Changed {
+        field: Field::Title,
+    };
+    assert!(status.matches(Event::Updated, Some(&before), &after));
+    assert!(!title.matches(Event::Updated, Some(&before), &after));
+    assert!(!status.matches(Event::Created, None, &after));
+}
+
+/// Rules must be serialized with a tag, to be readable and editable by hand.
+#[test]
+fn ut_sunny_rule_json() {
+    let json = r##"{
+        "name": "Urgent network tickets",
+        "condition": {"type": "all", "conditions": [
+            {"type": "equals", "field": "priority", "value": "urgent"},
+            {"type": "has_tag", "tag": "network"}
+        ]},
+        "actions": [
+            {"type": "move_to_queue", "queue_id": "00000000-0000-0000-0000-000000000000"},
+            {"type": "send_email", "to": "oncall@example.com", "subject": "#{{number}}", "body": ""}
+        ]
+    }"##;
+
+    let rule = serde_json::from_str::<RuleFormDTO>(json).expect("invalid rule JSON");
+    assert!(rule.enabled, "rules must be enabled by default");
+    assert_eq!(rule.actions.len(), 2);
+    assert_eq!(
+        rule.actions[0],
+        Action::MoveToQueue {
+            queue_id: Uuid::nil()
+        }
+    );
+}
+
+/// Actions must change the ticket, and report whether they did.
+#[test]
+fn ut_sunny_action_apply() {
+    let mut ticket = ticket();
+
+    let set_status = Action::SetField {
+        field: Field::Status,
+        value: Some("open".to_owned()),
+    };
+    assert!(set_status.apply(&mut ticket));
+    assert_eq!(ticket.status, Status::Open);
+    assert!(!set_status.apply(&mut ticket), "no change was reported");
+
+    let add_tag = Action::AddTag {
+        tag: " Outage".to_owned(),
+    };
+    assert!(add_tag.apply(&mut ticket));
+    assert_eq!(ticket.tags, vec!["outage".to_owned(), "vip".to_owned()]);
+    assert!(Action::RemoveTag {
+        tag: "VIP".to_owned()
+    }
+    .apply(&mut ticket));
+    assert_eq!(ticket.tags, vec!["outage".to_owned()]);
+
+    let email = Action::SendEmail {
+        to: "oncall@example.com".to_owned(),
+        subject: "#{{number}}: {{title}}".to_owned(),
+        body: String::new(),
+    };
+    assert!(!email.apply(&mut ticket), "side effects changed the ticket");
+    assert_eq!(
+        render_template("#{{number}}: {{title}}", &ticket),
+        "#42: Network down"
+    );
+}
+
+/// Invalid actions must be rejected.
+#[test]
+fn ut_rainy_action_validate() {
+    let actions = [
+        Action::SetField {
+            field: Field::AssigneeId,
+            value: None,
+        },
+        Action::SetField {
+            field: Field::Status,
+            value: Some("unknown".to_owned()),
+        },
+        Action::SetField {
+            field: Field::Title,
+            value: None,
+        },
+        Action::AddTag {
+            tag: " ".to_owned(),
+        },
+        Action::SendEmail {
+            to: "nobody".to_owned(),
+            subject: String::new(),
+            body: String::new(),
+        },
+    ];
+
+    for action in &actions {
+        assert!(action.validate().is_err(), "{:?} was valid", action);
+    }
+}
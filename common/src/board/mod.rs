@@ -0,0 +1,148 @@
+//! Ticket boards.
+//!
+//! Boards lay out the tickets in columns, grouped by [status or assignee](Grouping). Moving a
+//! ticket to another column [updates](Grouping::update) its status or assignee, and columns can
+//! have a limit of work in progress: the server refuses to move tickets to a full column.
+
+use crate::ticket::{Status, TicketDTO, TicketUpdateDTO};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum limit of work in progress of a column.
+pub const MAX_WIP_LIMIT: i32 = 500;
+
+/// Key of the column of the tickets without an assignee.
+pub const UNASSIGNED: &str = "unassigned";
+
+string_enum! {
+    /// How the tickets of a board are grouped into columns.
+    pub enum Grouping {
+        /// A column for each status.
+        Status => "status",
+        /// A column for each agent, and one for the tickets without an assignee.
+        Assignee => "assignee",
+    }
+}
+
+impl Default for Grouping {
+    fn default() -> Self {
+        Self::Status
+    }
+}
+
+impl Grouping {
+    /// Checks if the board holds the tickets with the given status.
+    ///
+    /// Closed tickets are never on the board, and resolved ones only when grouped by status.
+    pub fn holds(self, status: Status) -> bool {
+        match self {
+            Self::Status => status != Status::Closed,
+            Self::Assignee => status.is_active(),
+        }
+    }
+
+    /// Gets the key of the column of a ticket.
+    pub fn key(self, ticket: &TicketDTO) -> String {
+        match self {
+            Self::Status => ticket.status.to_string(),
+            Self::Assignee => match ticket.assignee_id {
+                Some(assignee_id) => assignee_id.to_string(),
+                None => UNASSIGNED.to_owned(),
+            },
+        }
+    }
+
+    /// Gets the update moving a ticket to the column with the given key, if the key is valid.
+    pub fn update(self, key: &str) -> Option<TicketUpdateDTO> {
+        let mut update = TicketUpdateDTO::default();
+        match self {
+            Self::Status => update.status = Some(key.parse::<Status>().ok()?),
+            Self::Assignee if key == UNASSIGNED => update.assignee_id = Some(None),
+            Self::Assignee => update.assignee_id = Some(Some(key.parse::<Uuid>().ok()?)),
+        }
+
+        Some(update)
+    }
+
+    /// Checks if the column with the given key can have a limit of work in progress.
+    ///
+    /// Only the columns of active tickets are limited, resolved tickets piling up until closed.
+    pub fn limits(self, key: &str) -> bool {
+        match self.update(key) {
+            Some(update) => update.status.map_or(true, Status::is_active),
+            None => false,
+        }
+    }
+
+    /// Applies the move of a ticket to the column with the given key, as the server would.
+    ///
+    /// Returns `false` if the key is invalid, leaving the ticket untouched.
+    pub fn apply(self, key: &str, ticket: &mut TicketDTO) -> bool {
+        let update = match self.update(key) {
+            Some(update) => update,
+            None => return false,
+        };
+        if let Some(status) = update.status {
+            ticket.status = status;
+        }
+        if let Some(assignee_id) = update.assignee_id {
+            ticket.assignee_id = assignee_id;
+        }
+
+        true
+    }
+}
+
+/// Gets the title of the column of a ticket status.
+pub fn status_title(status: Status) -> &'static str {
+    match status {
+        Status::New => "New",
+        Status::Open => "Open",
+        Status::Pending => "Pending",
+        Status::Resolved => "Resolved",
+        Status::Closed => "Closed",
+    }
+}
+
+/// Column of a board, sent from the server to agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnDTO {
+    /// Status of the tickets, or ID of their assignee or [`UNASSIGNED`].
+    pub key: String,
+    pub title: String,
+    /// Maximum number of tickets the column should hold, if limited.
+    pub wip_limit: Option<i32>,
+    pub tickets: Vec<TicketDTO>,
+}
+
+impl ColumnDTO {
+    /// Checks if the column reached its limit of work in progress, so no ticket should be added.
+    pub fn is_full(&self) -> bool {
+        matches!(self.wip_limit, Some(limit) if self.tickets.len() >= limit as usize)
+    }
+
+    /// Checks if the column holds more tickets than its limit of work in progress.
+    pub fn is_over_limit(&self) -> bool {
+        matches!(self.wip_limit, Some(limit) if self.tickets.len() > limit as usize)
+    }
+}
+
+/// Board of tickets, sent from the server to agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardDTO {
+    pub grouping: Grouping,
+    /// Columns of the board, in order.
+    pub columns: Vec<ColumnDTO>,
+    /// Whether older tickets were left out of the board, as too many tickets matched.
+    pub truncated: bool,
+}
+
+/// Settings of a column of a board, sent between the server and the client.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSettingsDTO {
+    /// Maximum number of tickets the column should hold, `None` for no limit.
+    pub wip_limit: Option<i32>,
+}
@@ -0,0 +1,76 @@
+use super::*;
+use crate::ticket::Priority;
+use chrono::Utc;
+
+/// Creates a test ticket.
+fn ticket() -> TicketDTO {
+    TicketDTO {
+        id: Uuid::nil(),
+        number: 7,
+        title: "Printer jammed".to_owned(),
+        description: "Paper everywhere".to_owned(),
+        status: Status::New,
+        priority: Priority::Normal,
+        requester_id: Uuid::nil(),
+        assignee_id: None,
+        organisation_id: None,
+        queue_id: None,
+        category: None,
+        tags: Vec::new(),
+        custom_fields: Default::default(),
+        merged_into_id: None,
+        parent_id: None,
+        created_on: Utc::now(),
+        updated_on: Utc::now(),
+    }
+}
+
+/// Moving tickets between columns changes their status or assignee, and their key accordingly.
+#[test]
+fn ut_sunny_move() {
+    let mut ticket = ticket();
+    assert_eq!(Grouping::Status.key(&ticket), "new");
+    assert_eq!(Grouping::Assignee.key(&ticket), UNASSIGNED);
+    assert!(Grouping::Status.holds(Status::Resolved));
+    assert!(!Grouping::Status.holds(Status::Closed));
+    assert!(!Grouping::Assignee.holds(Status::Resolved));
+    assert!(Grouping::Status.limits("pending"));
+    assert!(!Grouping::Status.limits("resolved"));
+    assert!(Grouping::Assignee.limits(UNASSIGNED));
+
+    assert!(Grouping::Status.apply("pending", &mut ticket));
+    assert_eq!(ticket.status, Status::Pending);
+
+    let agent_id = Uuid::from_u128(0x2a);
+    assert!(Grouping::Assignee.apply(&agent_id.to_string(), &mut ticket));
+    assert_eq!(Grouping::Assignee.key(&ticket), agent_id.to_string());
+    let update = Grouping::Assignee
+        .update(UNASSIGNED)
+        .expect("unassigned column was invalid");
+    assert_eq!(update.assignee_id, Some(None));
+    assert_eq!(update.status, None);
+
+    let mut column = ColumnDTO {
+        key: "pending".to_owned(),
+        title: "Pending".to_owned(),
+        wip_limit: Some(1),
+        tickets: Vec::new(),
+    };
+    assert!(!column.is_full());
+    column.tickets.push(ticket.clone());
+    assert!(column.is_full());
+    assert!(!column.is_over_limit());
+    column.tickets.push(ticket);
+    assert!(column.is_over_limit());
+}
+
+/// Tickets can't be moved to columns with invalid keys.
+#[test]
+fn ut_rainy_move() {
+    let mut ticket = ticket();
+    assert!(!Grouping::Status.apply("archived", &mut ticket));
+    assert!(!Grouping::Assignee.apply("bob", &mut ticket));
+    assert_eq!(ticket.status, Status::New);
+    assert_eq!(ticket.assignee_id, None);
+    assert!(!Grouping::Status.limits("archived"));
+}
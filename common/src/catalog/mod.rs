@@ -0,0 +1,325 @@
+//! Service catalog.
+//!
+//! Catalog items are standard services that customers can request through structured forms.
+//! Forms are defined as data, with a list of [field definitions](FieldDefinition), so that the
+//! frontend can render them generically, and both sides can [validate](FormDefinition::validate)
+//! the answers with the same rules.
+
+use crate::ticket::Priority;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Answers to a form, by field key.
+pub type Answers = BTreeMap<String, Value>;
+
+/// Answer to a field of a form.
+///
+/// Dates are `YYYY-MM-DD` texts, and users are given by their ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Bool(bool),
+    Text(String),
+    List(Vec<String>),
+}
+
+/// Kind of a form field, along with its settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldKind {
+    /// Free text.
+    Text {
+        /// Whether the text can have several lines.
+        #[serde(default)]
+        multiline: bool,
+        /// Maximum length of the text, in characters.
+        #[serde(default)]
+        max_length: Option<usize>,
+    },
+    /// Choice among a list of options.
+    Select {
+        options: Vec<String>,
+        /// Whether several options can be chosen.
+        #[serde(default)]
+        multiple: bool,
+    },
+    /// Calendar date.
+    Date,
+    /// User of the application, among the ones the requester can pick.
+    User,
+    /// Yes or no question. Required checkboxes must be checked.
+    Checkbox,
+}
+
+/// Condition for a field to be shown, depending on the answer to a previous field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Condition {
+    /// Key of the previous field.
+    pub field: String,
+    /// Answer that shows the field. Multiple choice answers show it if they include this option.
+    pub equals: Value,
+}
+
+impl Condition {
+    /// Checks if the condition holds for an answer.
+    pub fn matches(&self, answer: Option<&Value>) -> bool {
+        match (answer, &self.equals) {
+            (Some(Value::List(options)), Value::Text(option)) => options.contains(option),
+            (Some(answer), expected) => answer == expected,
+            // Unchecked checkboxes have no answer
+            (None, expected) => *expected == Value::Bool(false),
+        }
+    }
+}
+
+/// Definition of a form field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldDefinition {
+    /// Identifier of the field in the answers.
+    pub key: String,
+    pub label: String,
+    /// Explanation shown below the field.
+    #[serde(default)]
+    pub help: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(flatten)]
+    pub kind: FieldKind,
+    /// The field is only shown, and its answer only kept, if this condition holds.
+    #[serde(default)]
+    pub visible_if: Option<Condition>,
+}
+
+/// Validation error of an answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Key of the field.
+    pub field: String,
+    pub message: String,
+}
+
+/// Definition of a request form.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FormDefinition {
+    pub fields: Vec<FieldDefinition>,
+}
+
+impl FormDefinition {
+    /// Checks that the definition is consistent, returning the reason if it isn't.
+    ///
+    /// Keys must be unique identifiers in `snake_case`, select fields need distinct options, and
+    /// conditions can only depend on previous fields.
+    pub fn check(&self) -> Result<(), String> {
+        if self.fields.is_empty() {
+            return Err("form has no fields".to_owned());
+        }
+
+        let mut keys = HashSet::new();
+        for field in &self.fields {
+            let valid_key = field.key.starts_with(|c: char| c.is_ascii_lowercase())
+                && field
+                    .key
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid_key {
+                return Err(format!("invalid field key: {:?}", field.key));
+            }
+            if field.label.trim().is_empty() {
+                return Err(format!("field {} has no label", field.key));
+            }
+            if let FieldKind::Select { options, .. } = &field.kind {
+                let distinct = options.iter().collect::<HashSet<_>>();
+                if options.is_empty()
+                    || distinct.len() != options.len()
+                    || options.iter().any(|option| option.trim().is_empty())
+                {
+                    return Err(format!("field {} needs distinct options", field.key));
+                }
+            }
+            if let Some(condition) = &field.visible_if {
+                if !keys.contains(condition.field.as_str()) {
+                    return Err(format!(
+                        "field {} depends on an unknown or later field",
+                        field.key
+                    ));
+                }
+            }
+            if !keys.insert(field.key.as_str()) {
+                return Err(format!("duplicated field key: {}", field.key));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks if a field is shown, given the answers to the previous fields.
+    pub fn is_visible(field: &FieldDefinition, answers: &Answers) -> bool {
+        field.visible_if.as_ref().map_or(true, |condition| {
+            condition.matches(answers.get(&condition.field))
+        })
+    }
+
+    /// Validates the answers to the form, returning them normalized.
+    ///
+    /// Answers to hidden and unknown fields are dropped, texts are trimmed, and empty answers
+    /// are removed. Errors are returned in the order of the fields.
+    pub fn validate(&self, answers: &Answers) -> Result<Answers, Vec<FieldError>> {
+        let mut valid = Answers::new();
+        let mut errors = Vec::new();
+
+        for field in &self.fields {
+            if !Self::is_visible(field, &valid) {
+                continue;
+            }
+
+            match normalize(field, answers.get(&field.key)) {
+                Ok(Some(answer)) => {
+                    let _ = valid.insert(field.key.clone(), answer);
+                }
+                Ok(None) if field.required => errors.push(FieldError {
+                    field: field.key.clone(),
+                    message: "this field is required".to_owned(),
+                }),
+                Ok(None) => {}
+                Err(message) => errors.push(FieldError {
+                    field: field.key.clone(),
+                    message: message.to_owned(),
+                }),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(valid)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Normalizes the answer to a field, returning `None` if it's empty.
+fn normalize(
+    field: &FieldDefinition,
+    answer: Option<&Value>,
+) -> Result<Option<Value>, &'static str> {
+    let answer = match answer {
+        None => return Ok(None),
+        Some(answer) => answer,
+    };
+
+    match (&field.kind, answer) {
+        (_, Value::Text(text)) if text.trim().is_empty() => Ok(None),
+        (
+            FieldKind::Text {
+                multiline,
+                max_length,
+            },
+            Value::Text(text),
+        ) => {
+            let text = text.trim();
+            if !multiline && text.contains('\n') {
+                Err("must be a single line")
+            } else if max_length.map_or(false, |max| text.chars().count() > max) {
+                Err("is too long")
+            } else {
+                Ok(Some(Value::Text(text.to_owned())))
+            }
+        }
+        (FieldKind::Select { options, multiple }, Value::Text(option)) if !multiple => {
+            if options.contains(option) {
+                Ok(Some(answer.clone()))
+            } else {
+                Err("is not one of the options")
+            }
+        }
+        (FieldKind::Select { options, multiple }, Value::List(chosen)) if *multiple => {
+            if !chosen.iter().all(|option| options.contains(option)) {
+                return Err("is not one of the options");
+            }
+            // Chosen options are kept in the order of the definition
+            let chosen = options
+                .iter()
+                .filter(|option| chosen.contains(option))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            Ok((!chosen.is_empty()).then(|| Value::List(chosen)))
+        }
+        (FieldKind::Date, Value::Text(date)) => NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map(|date| Some(Value::Text(date.to_string())))
+            .map_err(|_| "must be a date"),
+        (FieldKind::User, Value::Text(id)) => id
+            .trim()
+            .parse::<Uuid>()
+            .map(|id| Some(Value::Text(id.to_string())))
+            .map_err(|_| "must be a user"),
+        (FieldKind::Checkbox, Value::Bool(checked)) => Ok(checked.then(|| Value::Bool(true))),
+        _ => Err("has the wrong type"),
+    }
+}
+
+/// Catalog item, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogItemDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub form: FormDefinition,
+    /// Priority of the tickets created for the item.
+    pub priority: Priority,
+    /// Queue of the tickets created for the item, or the default queue if `None`.
+    pub queue_id: Option<Uuid>,
+    /// Inactive items can't be requested.
+    pub active: bool,
+    /// Approval chain the tickets created for the item go through, if any.
+    pub approval_chain_id: Option<Uuid>,
+}
+
+/// Catalog item form data, used by administrators to create or update items.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogItemFormDTO {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub form: FormDefinition,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub queue_id: Option<Uuid>,
+    pub active: bool,
+    #[serde(default)]
+    pub approval_chain_id: Option<Uuid>,
+}
+
+/// Service request form data, used to request a catalog item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceRequestFormDTO {
+    pub answers: Answers,
+    /// Organisation of the ticket, with the same rules as for other tickets.
+    #[serde(default)]
+    pub organisation_id: Option<Uuid>,
+}
+
+/// Structured data of a ticket created from the catalog, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceRequestDTO {
+    pub ticket_id: Uuid,
+    /// ID of the requested item, if it still exists.
+    pub catalog_item_id: Option<Uuid>,
+    /// Form definition at the time of the request.
+    pub form: FormDefinition,
+    pub answers: Answers,
+}
+
+/// User that can be picked in user fields, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PickableUserDTO {
+    pub id: Uuid,
+    pub username: String,
+    pub name: String,
+}
@@ -0,0 +1,121 @@
+use super::*;
+
+/// Gets the form of a laptop request.
+fn laptop_form() -> FormDefinition {
+    serde_json::from_str(
+        r#"[
+            {"key": "model", "label": "Model", "required": true, "type": "select",
+             "options": ["13 inch", "15 inch"]},
+            {"key": "accessories", "label": "Accessories", "type": "select",
+             "options": ["Mouse", "Dock", "Bag"], "multiple": true},
+            {"key": "dock_desk", "label": "Desk for the dock", "required": true, "type": "text",
+             "max_length": 10, "visible_if": {"field": "accessories", "equals": "Dock"}},
+            {"key": "needed_by", "label": "Needed by", "type": "date"},
+            {"key": "for_user", "label": "For", "type": "user"},
+            {"key": "policy", "label": "I accept the policy", "required": true, "type": "checkbox"}
+        ]"#,
+    )
+    .expect("error parsing form definition")
+}
+
+/// Creates answers from a JSON object.
+fn answers(json: &str) -> Answers {
+    serde_json::from_str(json).expect("error parsing answers")
+}
+
+/// Forms are defined as data, and valid answers are normalized.
+#[test]
+fn ut_sunny_validate() {
+    let form = laptop_form();
+    assert_eq!(form.check(), Ok(()));
+    assert_eq!(
+        form.fields[2].kind,
+        FieldKind::Text {
+            multiline: false,
+            max_length: Some(10)
+        }
+    );
+
+    let user_id = Uuid::parse_str("8f6b0a0e-6c0c-4a8a-9c44-0f3a7b1e2d11").expect("invalid UUID");
+    let valid = form
+        .validate(&answers(&format!(
+            r#"{{"model": "13 inch", "accessories": ["Dock", "Mouse"], "dock_desk": " B-12 ",
+                "needed_by": "2022-07-01", "for_user": "{}", "policy": true, "unknown": "x"}}"#,
+            user_id
+        )))
+        .expect("answers were not valid");
+    assert_eq!(
+        valid,
+        answers(&format!(
+            r#"{{"model": "13 inch", "accessories": ["Mouse", "Dock"], "dock_desk": "B-12",
+                "needed_by": "2022-07-01", "for_user": "{}", "policy": true}}"#,
+            user_id
+        ))
+    );
+
+    // Hidden fields are not required, and their answers are dropped
+    let valid = form
+        .validate(&answers(
+            r#"{"model": "15 inch", "accessories": ["Bag"], "dock_desk": "B-12", "policy": true}"#,
+        ))
+        .expect("answers were not valid");
+    assert!(!valid.contains_key("dock_desk"));
+}
+
+/// Invalid answers are rejected, with an error for every field.
+#[test]
+fn ut_rainy_validate() {
+    let form = laptop_form();
+    let errors = form
+        .validate(&answers(
+            r#"{"model": "17 inch", "accessories": ["Dock"], "dock_desk": "Second floor, B-12",
+                "needed_by": "tomorrow", "for_user": "bob", "policy": false}"#,
+        ))
+        .expect_err("invalid answers were accepted");
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect::<Vec<_>>(),
+        vec!["model", "dock_desk", "needed_by", "for_user", "policy"]
+    );
+    assert_eq!(errors[4].message, "this field is required");
+    assert_eq!(
+        form.validate(&answers(r#"{"model": ["13 inch"], "policy": true}"#))
+            .expect_err("invalid answers were accepted")[0]
+            .message,
+        "has the wrong type"
+    );
+}
+
+/// Inconsistent form definitions are rejected.
+#[test]
+fn ut_rainy_check() {
+    let mut form = laptop_form();
+    form.fields[1].key = "model".to_owned();
+    assert!(form.check().is_err(), "duplicated keys were accepted");
+
+    let mut form = laptop_form();
+    form.fields.swap(1, 2);
+    assert!(
+        form.check().is_err(),
+        "conditions on later fields were accepted"
+    );
+
+    let mut form = laptop_form();
+    form.fields[0].kind = FieldKind::Select {
+        options: Vec::new(),
+        multiple: false,
+    };
+    assert!(
+        form.check().is_err(),
+        "select fields without options were accepted"
+    );
+
+    let mut form = laptop_form();
+    form.fields[0].key = "Model".to_owned();
+    assert!(form.check().is_err(), "invalid keys were accepted");
+
+    assert!(FormDefinition::default().check().is_err());
+}
@@ -0,0 +1,381 @@
+//! Change management.
+//!
+//! Changes to the infrastructure are planned in a window of time, and assessed with a
+//! [questionnaire](QUESTIONNAIRE) scoring the risk of failure and the impact on the users. Each
+//! change is tracked by its own ticket, which goes through the change advisory board (CAB)
+//! approval chain of its risk level before being worked on. Changes collide with the other changes
+//! and maintenance windows touching the same configuration items at the same time.
+
+use crate::{catalog::FieldError, ticket::TicketDTO};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Answers to the questionnaire, with the key of the choice by question key.
+pub type Answers = BTreeMap<String, String>;
+
+string_enum! {
+    /// Risk level of a change, combining the risk of failure and the impact.
+    pub enum Risk {
+        Low => "low",
+        Medium => "medium",
+        High => "high",
+    }
+}
+
+impl Risk {
+    /// Gets the risk level of the scores of an assessment.
+    ///
+    /// Each score is rated from 1 to 3, and the level follows the product of the ratings.
+    pub fn from_scores(risk_score: i32, impact_score: i32) -> Self {
+        let rating = |score: i32| match score {
+            i32::MIN..=1 => 1,
+            2..=3 => 2,
+            _ => 3,
+        };
+
+        match rating(risk_score) * rating(impact_score) {
+            6..=9 => Self::High,
+            3..=5 => Self::Medium,
+            _ => Self::Low,
+        }
+    }
+}
+
+/// What a question of the questionnaire assesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    /// Likelihood of the change failing.
+    Risk,
+    /// Consequences on the users while the change is made, or if it fails.
+    Impact,
+}
+
+/// Choice of a question of the questionnaire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Choice {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub score: i32,
+}
+
+/// Question of the questionnaire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Question {
+    pub key: &'static str,
+    pub text: &'static str,
+    pub dimension: Dimension,
+    pub choices: &'static [Choice],
+}
+
+/// Questionnaire assessing the risk and the impact of changes.
+pub const QUESTIONNAIRE: &[Question] = &[
+    Question {
+        key: "experience",
+        text: "Has this change been made before?",
+        dimension: Dimension::Risk,
+        choices: &[
+            Choice {
+                key: "routine",
+                label: "Routinely",
+                score: 0,
+            },
+            Choice {
+                key: "rarely",
+                label: "Once or twice",
+                score: 1,
+            },
+            Choice {
+                key: "never",
+                label: "Never",
+                score: 2,
+            },
+        ],
+    },
+    Question {
+        key: "testing",
+        text: "Was the change tested?",
+        dimension: Dimension::Risk,
+        choices: &[
+            Choice {
+                key: "full",
+                label: "In a production-like environment",
+                score: 0,
+            },
+            Choice {
+                key: "partial",
+                label: "Partially",
+                score: 1,
+            },
+            Choice {
+                key: "none",
+                label: "Not tested",
+                score: 2,
+            },
+        ],
+    },
+    Question {
+        key: "rollback",
+        text: "Can the change be rolled back?",
+        dimension: Dimension::Risk,
+        choices: &[
+            Choice {
+                key: "automatic",
+                label: "Automatically",
+                score: 0,
+            },
+            Choice {
+                key: "manual",
+                label: "By hand",
+                score: 1,
+            },
+            Choice {
+                key: "impossible",
+                label: "No",
+                score: 2,
+            },
+        ],
+    },
+    Question {
+        key: "users",
+        text: "Which users are affected?",
+        dimension: Dimension::Impact,
+        choices: &[
+            Choice {
+                key: "none",
+                label: "None",
+                score: 0,
+            },
+            Choice {
+                key: "some",
+                label: "Some teams",
+                score: 1,
+            },
+            Choice {
+                key: "all",
+                label: "Everyone",
+                score: 2,
+            },
+        ],
+    },
+    Question {
+        key: "downtime",
+        text: "Is the service interrupted?",
+        dimension: Dimension::Impact,
+        choices: &[
+            Choice {
+                key: "none",
+                label: "No",
+                score: 0,
+            },
+            Choice {
+                key: "degraded",
+                label: "It is degraded",
+                score: 1,
+            },
+            Choice {
+                key: "outage",
+                label: "It is down",
+                score: 2,
+            },
+        ],
+    },
+    Question {
+        key: "critical",
+        text: "Does the change touch business-critical services?",
+        dimension: Dimension::Impact,
+        choices: &[
+            Choice {
+                key: "no",
+                label: "No",
+                score: 0,
+            },
+            Choice {
+                key: "yes",
+                label: "Yes",
+                score: 2,
+            },
+        ],
+    },
+];
+
+/// Assessment of a change, calculated from the answers to the questionnaire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Assessment {
+    pub risk_score: i32,
+    pub impact_score: i32,
+    pub risk: Risk,
+}
+
+/// Assesses a change from the answers to the questionnaire.
+///
+/// Every question needs an answer, and errors are returned in the order of the questions.
+pub fn assess(answers: &Answers) -> Result<Assessment, Vec<FieldError>> {
+    let (mut risk_score, mut impact_score) = (0, 0);
+    let mut errors = Vec::new();
+
+    for question in QUESTIONNAIRE {
+        let answer = answers.get(question.key).map(|answer| answer.trim());
+        let message = match answer {
+            None | Some("") => "this question needs an answer",
+            Some(answer) => match question.choices.iter().find(|c| c.key == answer) {
+                Some(choice) => {
+                    match question.dimension {
+                        Dimension::Risk => risk_score += choice.score,
+                        Dimension::Impact => impact_score += choice.score,
+                    }
+                    continue;
+                }
+                None => "is not one of the choices",
+            },
+        };
+        errors.push(FieldError {
+            field: question.key.to_owned(),
+            message: message.to_owned(),
+        });
+    }
+    for key in answers.keys() {
+        if !QUESTIONNAIRE.iter().any(|question| question.key == key) {
+            errors.push(FieldError {
+                field: key.clone(),
+                message: "is not a question of the assessment".to_owned(),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(Assessment {
+            risk_score,
+            impact_score,
+            risk: Risk::from_scores(risk_score, impact_score),
+        })
+    } else {
+        Err(errors)
+    }
+}
+
+/// Change request, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeDTO {
+    pub id: Uuid,
+    /// Ticket tracking the change, and its approval.
+    pub ticket: TicketDTO,
+    pub planned_start: DateTime<Utc>,
+    pub planned_end: DateTime<Utc>,
+    pub answers: Answers,
+    #[serde(flatten)]
+    pub assessment: Assessment,
+    /// IDs of the configuration items touched by the change.
+    pub ci_ids: Vec<Uuid>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
+
+/// Plan of a change, used by agents to schedule and assess it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangePlanDTO {
+    pub planned_start: DateTime<Utc>,
+    pub planned_end: DateTime<Utc>,
+    pub answers: Answers,
+    #[serde(default)]
+    pub ci_ids: Vec<Uuid>,
+}
+
+impl ChangePlanDTO {
+    /// Checks that the plan is consistent, returning the reason if it isn't.
+    pub fn check(&self) -> Result<(), &'static str> {
+        if self.planned_end <= self.planned_start {
+            return Err("planned end must be after the planned start");
+        }
+
+        Ok(())
+    }
+}
+
+/// Change request form data, used by agents to request a change.
+///
+/// The title and description are those of the ticket of the change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeFormDTO {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(flatten)]
+    pub plan: ChangePlanDTO,
+}
+
+/// Maintenance window, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceWindowDTO {
+    pub id: Uuid,
+    pub title: String,
+    pub starts_on: DateTime<Utc>,
+    pub ends_on: DateTime<Utc>,
+    /// IDs of the configuration items under maintenance.
+    pub ci_ids: Vec<Uuid>,
+    pub created_by: Uuid,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
+
+/// Maintenance window form data, used by agents to schedule maintenance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceWindowFormDTO {
+    pub title: String,
+    pub starts_on: DateTime<Utc>,
+    pub ends_on: DateTime<Utc>,
+    #[serde(default)]
+    pub ci_ids: Vec<Uuid>,
+}
+
+impl MaintenanceWindowFormDTO {
+    /// Checks that the window is consistent, returning the reason if it isn't.
+    pub fn check(&self) -> Result<(), &'static str> {
+        if self.title.trim().is_empty() {
+            return Err("title can't be empty");
+        }
+        if self.ends_on <= self.starts_on {
+            return Err("window must end after it starts");
+        }
+
+        Ok(())
+    }
+}
+
+/// Collision of a change with another change or a maintenance window, sent from the server to the
+/// client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CollisionDTO {
+    Change {
+        id: Uuid,
+        /// Number of the ticket of the change.
+        number: i64,
+        title: String,
+        starts_on: DateTime<Utc>,
+        ends_on: DateTime<Utc>,
+        /// IDs of the configuration items touched by both.
+        ci_ids: Vec<Uuid>,
+    },
+    MaintenanceWindow {
+        id: Uuid,
+        title: String,
+        starts_on: DateTime<Utc>,
+        ends_on: DateTime<Utc>,
+        /// IDs of the configuration items touched by both.
+        ci_ids: Vec<Uuid>,
+    },
+}
+
+/// Approval chain of the change advisory board for the changes of a risk level.
+///
+/// Changes of levels without a chain don't need an approval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CabChainDTO {
+    pub risk: Risk,
+    pub approval_chain_id: Option<Uuid>,
+}
@@ -0,0 +1,72 @@
+use super::*;
+
+/// Builds answers from pairs of question and choice keys.
+fn answers(pairs: &[(&str, &str)]) -> Answers {
+    pairs
+        .iter()
+        .map(|(question, choice)| ((*question).to_owned(), (*choice).to_owned()))
+        .collect()
+}
+
+/// Scores are summed by dimension, and their ratings give the risk level.
+#[test]
+fn ut_sunny_assess() {
+    let routine = answers(&[
+        ("experience", "routine"),
+        ("testing", "full"),
+        ("rollback", "manual"),
+        ("users", "some"),
+        ("downtime", "degraded"),
+        ("critical", "no"),
+    ]);
+    assert_eq!(
+        assess(&routine),
+        Ok(Assessment {
+            risk_score: 1,
+            impact_score: 2,
+            risk: Risk::Low,
+        })
+    );
+
+    let risky = answers(&[
+        ("experience", "never"),
+        ("testing", "partial"),
+        ("rollback", "impossible"),
+        ("users", "all"),
+        ("downtime", "outage"),
+        ("critical", " yes "),
+    ]);
+    assert_eq!(
+        assess(&risky).map(|assessment| assessment.risk),
+        Ok(Risk::High)
+    );
+    assert_eq!(Risk::from_scores(2, 3), Risk::Medium);
+    assert_eq!(Risk::from_scores(0, 6), Risk::Medium);
+}
+
+/// Every question needs one of its choices, and unknown questions are rejected.
+#[test]
+fn ut_rainy_assess() {
+    let errors = assess(&answers(&[
+        ("experience", "routine"),
+        ("testing", "maybe"),
+        ("rollback", " "),
+        ("users", "none"),
+        ("downtime", "none"),
+        ("critical", "no"),
+        ("budget", "high"),
+    ]))
+    .expect_err("invalid answers were accepted");
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect::<Vec<_>>(),
+        ["testing", "rollback", "budget"]
+    );
+    assert_eq!(
+        assess(&Answers::new()).map_err(|errors| errors.len()),
+        Err(QUESTIONNAIRE.len())
+    );
+}
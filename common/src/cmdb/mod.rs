@@ -0,0 +1,172 @@
+//! Configuration management database (CMDB).
+//!
+//! Configuration items (CIs) are the servers, laptops and services that incidents affect. Each item
+//! has a type, which defines the typed attributes of its items, and items are related to each other
+//! by typed relationships forming a dependency graph. The impact of an outage of an item is found
+//! by walking the graph backwards, to the items that depend on it or run on it.
+
+use crate::{
+    catalog::FieldError,
+    custom_field::{self, Kind, Values},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Column of the name of the items in CSV imports, which attributes can't use as key.
+pub const NAME_COLUMN: &str = "name";
+
+/// Definition of an attribute of the items of a type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    /// Identifier of the attribute in the values of items.
+    pub key: String,
+    pub label: String,
+    #[serde(flatten)]
+    pub kind: Kind,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Type of configuration items, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CiTypeDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub attributes: Vec<AttributeDefinition>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
+
+impl CiTypeDTO {
+    /// Validates the attribute values of an item of the type, returning them normalized.
+    ///
+    /// Values follow the rules of [custom fields](custom_field::validate).
+    pub fn validate(&self, values: &Values) -> Result<Values, Vec<FieldError>> {
+        custom_field::validate_values(
+            self.attributes
+                .iter()
+                .map(|attribute| (attribute.key.as_str(), &attribute.kind, attribute.required)),
+            values,
+            "is not an attribute of this type",
+        )
+    }
+}
+
+/// Type form data, used by administrators to create or update types.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiTypeFormDTO {
+    pub name: String,
+    #[serde(default)]
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+impl CiTypeFormDTO {
+    /// Checks that the definition is consistent, returning the reason if it isn't.
+    ///
+    /// Attribute keys must be distinct identifiers in `snake_case`, other than `name`.
+    pub fn check(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name can't be empty".to_owned());
+        }
+        for (i, attribute) in self.attributes.iter().enumerate() {
+            if !custom_field::is_valid_key(&attribute.key) || attribute.key == NAME_COLUMN {
+                return Err(format!("invalid attribute key: {:?}", attribute.key));
+            }
+            if self.attributes[..i].iter().any(|a| a.key == attribute.key) {
+                return Err(format!("duplicate attribute key: {:?}", attribute.key));
+            }
+            if attribute.label.trim().is_empty() {
+                return Err("attribute labels can't be empty".to_owned());
+            }
+            attribute.kind.check()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Configuration item, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigurationItemDTO {
+    pub id: Uuid,
+    pub type_id: Uuid,
+    /// Unique name of the item, such as a hostname.
+    pub name: String,
+    pub attributes: Values,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
+
+/// Configuration item form data, used by agents to create or update items.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigurationItemFormDTO {
+    pub type_id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub attributes: Values,
+}
+
+string_enum! {
+    /// Kind of relationship from a source item to a target item.
+    pub enum RelationKind {
+        /// The source needs the target to work, like a service needing its database.
+        DependsOn => "depends_on",
+        /// The source is hosted on the target, like a virtual machine on its host.
+        RunsOn => "runs_on",
+    }
+}
+
+/// Relationship between two configuration items, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationshipDTO {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    pub target_id: Uuid,
+    pub kind: RelationKind,
+    pub created_on: DateTime<Utc>,
+}
+
+/// Relationship form data, used by agents to relate an item, as the source, to a target.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelationshipFormDTO {
+    pub target_id: Uuid,
+    pub kind: RelationKind,
+}
+
+/// Item impacted by an outage of another one, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImpactedItemDTO {
+    pub id: Uuid,
+    pub type_id: Uuid,
+    pub name: String,
+    /// Number of relationships between the item and the one in outage, at least 1.
+    pub depth: i32,
+}
+
+/// Configuration item link data, used by agents to link an item to a ticket.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CiLinkDTO {
+    pub ci_id: Uuid,
+}
+
+/// Error on a line of a CSV import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportErrorDTO {
+    /// Line of the record, starting at 1 with the header.
+    pub line: usize,
+    pub message: String,
+}
+
+/// Result of a CSV import, sent from the server to the client.
+///
+/// Imports are all or nothing, so nothing is created or updated if there are errors.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ImportReportDTO {
+    pub created: usize,
+    pub updated: usize,
+    pub errors: Vec<ImportErrorDTO>,
+}
@@ -0,0 +1,74 @@
+use super::*;
+
+/// Creates a type form from JSON.
+fn form(json: &str) -> CiTypeFormDTO {
+    serde_json::from_str(json).expect("error parsing type form")
+}
+
+/// Valid types are accepted, and their items' attributes are validated like custom fields.
+#[test]
+fn ut_sunny_ci_type() {
+    let form = form(
+        r#"{"name": "Server", "attributes": [
+            {"key": "os", "label": "OS", "type": "enum", "options": ["linux", "windows"]},
+            {"key": "cores", "label": "Cores", "type": "number", "required": true}
+        ]}"#,
+    );
+    assert_eq!(form.check(), Ok(()));
+
+    let server = CiTypeDTO {
+        id: Uuid::nil(),
+        name: form.name,
+        attributes: form.attributes,
+        created_on: Utc::now(),
+        updated_on: Utc::now(),
+    };
+    let values = serde_json::from_str(r#"{"os": " linux ", "cores": 16}"#).expect("bad values");
+    let valid = server.validate(&values).expect("values were rejected");
+    assert_eq!(
+        valid.get("os"),
+        Some(&custom_field::Value::Text("linux".to_owned()))
+    );
+
+    assert_eq!("runs_on".parse(), Ok(RelationKind::RunsOn));
+}
+
+/// Inconsistent types are rejected, and so are invalid attribute values.
+#[test]
+fn ut_rainy_ci_type() {
+    for json in [
+        r#"{"name": " "}"#,
+        r#"{"name": "Server", "attributes": [{"key": "name", "label": "Name", "type": "string"}]}"#,
+        r#"{"name": "Server", "attributes": [{"key": "OS", "label": "OS", "type": "string"}]}"#,
+        r#"{"name": "Server", "attributes": [{"key": "os", "label": "", "type": "string"}]}"#,
+        r#"{"name": "Server", "attributes": [{"key": "os", "label": "OS", "type": "enum",
+            "options": []}]}"#,
+        r#"{"name": "Server", "attributes": [{"key": "os", "label": "OS", "type": "string"},
+            {"key": "os", "label": "OS again", "type": "string"}]}"#,
+    ] {
+        assert!(form(json).check().is_err(), "{} was accepted", json);
+    }
+
+    let server = CiTypeDTO {
+        id: Uuid::nil(),
+        name: "Server".to_owned(),
+        attributes: form(
+            r#"{"name": "Server", "attributes": [
+                {"key": "cores", "label": "Cores", "type": "number", "required": true}
+            ]}"#,
+        )
+        .attributes,
+        created_on: Utc::now(),
+        updated_on: Utc::now(),
+    };
+    let values = serde_json::from_str(r#"{"cores": "many", "rack": "A1"}"#).expect("bad values");
+    let errors = server.validate(&values).expect_err("values were accepted");
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect::<Vec<_>>(),
+        ["cores", "rack"]
+    );
+    assert!(server.validate(&Values::new()).is_err());
+}
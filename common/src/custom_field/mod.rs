@@ -0,0 +1,310 @@
+//! Custom fields of tickets.
+//!
+//! Administrators define typed fields that tickets carry on top of the built-in ones, such as an
+//! asset tag or the affected version. Fields can be limited to the tickets of some categories, so
+//! that every kind of ticket gets its own fields. Values are [validated](validate) with the same
+//! rules on both sides, and can be filtered in ticket queries with `cf.<key>`.
+
+use crate::{catalog::FieldError, query::Operator};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Values of the custom fields of a ticket, by field key.
+pub type Values = BTreeMap<String, Value>;
+
+/// Value of a custom field.
+///
+/// Dates are `YYYY-MM-DD` texts, and users are given by their ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+/// Type of a custom field, along with its settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Kind {
+    /// Single line of text.
+    String,
+    /// Decimal number.
+    Number,
+    /// Choice among a list of options.
+    Enum { options: Vec<String> },
+    /// Calendar date.
+    Date,
+    /// Reference to a user of the application.
+    User,
+}
+
+impl Kind {
+    /// Checks if the values of the kind are ordered, so that they can be filtered with the
+    /// ordering operators.
+    pub fn is_ordered(&self) -> bool {
+        matches!(self, Self::Number | Self::Date)
+    }
+
+    /// Checks that the settings of the kind are consistent, returning the reason if they aren't.
+    ///
+    /// Enum kinds need distinct options.
+    pub fn check(&self) -> Result<(), String> {
+        if let Self::Enum { options } = self {
+            let mut distinct = options
+                .iter()
+                .map(|option| option.trim())
+                .collect::<Vec<_>>();
+            distinct.sort_unstable();
+            distinct.dedup();
+            if options.is_empty()
+                || distinct.len() != options.len()
+                || distinct.contains(&"")
+                || options.iter().any(|option| option.trim() != option)
+            {
+                return Err("enum fields need distinct options".to_owned());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses a value of the kind from its text, as typed in forms and queries.
+    ///
+    /// Returns `None` if the text is not a valid value.
+    pub fn parse(&self, text: &str) -> Option<Value> {
+        let text = text.trim();
+        match self {
+            Self::String => Some(Value::Text(text.to_owned())),
+            Self::Number => text
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .map(Value::Number),
+            Self::Enum { options } => options
+                .iter()
+                .any(|option| option == text)
+                .then(|| Value::Text(text.to_owned())),
+            Self::Date => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .map(|date| Value::Text(date.to_string())),
+            Self::User => text
+                .parse::<Uuid>()
+                .ok()
+                .map(|id| Value::Text(id.to_string())),
+        }
+    }
+}
+
+/// Maximum length of the key of a field, in characters.
+pub const MAX_KEY_LEN: usize = 50;
+
+/// Maximum length of string values, in characters.
+pub const MAX_STRING_LEN: usize = 1000;
+
+/// Checks if a key is an identifier in `snake_case`, short enough to be the key of a field.
+pub fn is_valid_key(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.len() <= MAX_KEY_LEN
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Custom field definition, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomFieldDTO {
+    pub id: Uuid,
+    /// Identifier of the field in the values of tickets.
+    pub key: String,
+    pub label: String,
+    #[serde(flatten)]
+    pub kind: Kind,
+    pub required: bool,
+    /// Categories of the tickets that have the field, or empty if all tickets have it.
+    pub categories: Vec<String>,
+    /// Fields are shown by ascending position.
+    pub position: i32,
+}
+
+impl CustomFieldDTO {
+    /// Checks if the tickets of a category have the field.
+    pub fn applies_to(&self, category: Option<&str>) -> bool {
+        self.categories.is_empty()
+            || category.map_or(false, |category| {
+                self.categories.iter().any(|c| c == category)
+            })
+    }
+}
+
+/// Custom field form data, used by administrators to create or update fields.
+///
+/// The key of a field can't be changed once created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomFieldFormDTO {
+    pub key: String,
+    pub label: String,
+    #[serde(flatten)]
+    pub kind: Kind,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub position: i32,
+}
+
+impl CustomFieldFormDTO {
+    /// Checks that the definition is consistent, returning the reason if it isn't.
+    ///
+    /// Keys must be identifiers in `snake_case`, and enum fields need distinct options.
+    pub fn check(&self) -> Result<(), String> {
+        if !is_valid_key(&self.key) {
+            return Err(format!("invalid field key: {:?}", self.key));
+        }
+        if self.label.trim().is_empty() {
+            return Err("label can't be empty".to_owned());
+        }
+
+        self.kind.check()
+    }
+}
+
+/// Validates the custom field values of a ticket of a category, returning them normalized.
+///
+/// Texts are trimmed and empty values are removed. Values of fields the ticket doesn't have are
+/// rejected, and errors are returned in the order of the fields.
+pub fn validate(
+    fields: &[CustomFieldDTO],
+    category: Option<&str>,
+    values: &Values,
+) -> Result<Values, Vec<FieldError>> {
+    let applicable = fields
+        .iter()
+        .filter(|field| field.applies_to(category))
+        .collect::<Vec<_>>();
+
+    validate_values(
+        applicable
+            .iter()
+            .map(|field| (field.key.as_str(), &field.kind, field.required)),
+        values,
+        "is not a field of this ticket",
+    )
+}
+
+/// Validates values against typed fields, given by key, kind and whether they're required,
+/// returning them normalized.
+///
+/// Values of other keys are rejected with the `unknown` message, and errors are returned in the
+/// order of the fields.
+pub fn validate_values<'f, I>(
+    fields: I,
+    values: &Values,
+    unknown: &str,
+) -> Result<Values, Vec<FieldError>>
+where
+    I: IntoIterator<Item = (&'f str, &'f Kind, bool)>,
+{
+    let mut keys = Vec::new();
+    let mut valid = Values::new();
+    let mut errors = Vec::new();
+
+    for (key, kind, required) in fields {
+        keys.push(key);
+        match normalize(kind, values.get(key)) {
+            Ok(Some(value)) => {
+                let _ = valid.insert(key.to_owned(), value);
+            }
+            Ok(None) if required => errors.push(FieldError {
+                field: key.to_owned(),
+                message: "this field is required".to_owned(),
+            }),
+            Ok(None) => {}
+            Err(message) => errors.push(FieldError {
+                field: key.to_owned(),
+                message: message.to_owned(),
+            }),
+        }
+    }
+    for key in values.keys() {
+        if !keys.contains(&key.as_str()) {
+            errors.push(FieldError {
+                field: key.clone(),
+                message: unknown.to_owned(),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(valid)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Normalizes the value of a field, returning `None` if it's empty.
+fn normalize(kind: &Kind, value: Option<&Value>) -> Result<Option<Value>, &'static str> {
+    match (kind, value) {
+        (_, None) => Ok(None),
+        (_, Some(Value::Text(text))) if text.trim().is_empty() => Ok(None),
+        (Kind::String, Some(Value::Text(text))) => {
+            let text = text.trim();
+            if text.contains('\n') {
+                Err("must be a single line")
+            } else if text.chars().count() > MAX_STRING_LEN {
+                Err("is too long")
+            } else {
+                Ok(Some(Value::Text(text.to_owned())))
+            }
+        }
+        (Kind::Number, Some(Value::Number(number))) if number.is_finite() => {
+            Ok(Some(Value::Number(*number)))
+        }
+        (Kind::Number, Some(Value::Number(_))) => Err("must be a number"),
+        (Kind::Enum { .. }, Some(Value::Text(text))) => kind
+            .parse(text)
+            .map(Some)
+            .ok_or("is not one of the options"),
+        (Kind::Date, Some(Value::Text(text))) => kind.parse(text).map(Some).ok_or("must be a date"),
+        (Kind::User, Some(Value::Text(text))) => kind.parse(text).map(Some).ok_or("must be a user"),
+        _ => Err("has the wrong type"),
+    }
+}
+
+/// Checks that a query filter on a custom field is valid, returning the reason if it isn't.
+///
+/// `value` is `None` for the tickets without a value. Users are given by username, or `me`.
+pub fn check_filter(
+    fields: &[CustomFieldDTO],
+    key: &str,
+    operator: Operator,
+    value: Option<&str>,
+) -> Result<(), String> {
+    let field = fields
+        .iter()
+        .find(|field| field.key == key)
+        .ok_or_else(|| format!("unknown custom field `{}`", key))?;
+    if operator != Operator::Is && !field.kind.is_ordered() {
+        return Err(format!(
+            "operator `{}` is not supported by custom field `{}`",
+            operator.as_str(),
+            key
+        ));
+    }
+
+    match value {
+        None if operator != Operator::Is => Err(format!(
+            "custom field `{}` can only be compared with values",
+            key
+        )),
+        Some(value) if field.kind != Kind::User && field.kind.parse(value).is_none() => Err(
+            format!("invalid value `{}` for custom field `{}`", value, key),
+        ),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,130 @@
+use super::*;
+
+/// Gets the custom fields of a test setup, where only hardware tickets have an asset tag.
+fn fields() -> Vec<CustomFieldDTO> {
+    serde_json::from_str(
+        r#"[
+            {"id": "5b0e3f64-1f9b-4c1e-8f0c-2a6a3c9d1e01", "key": "asset_tag", "label": "Asset tag",
+             "type": "string", "required": true, "categories": ["hardware"], "position": 0},
+            {"id": "5b0e3f64-1f9b-4c1e-8f0c-2a6a3c9d1e02", "key": "region", "label": "Region",
+             "type": "enum", "options": ["EU", "US"], "required": false, "categories": [],
+             "position": 1},
+            {"id": "5b0e3f64-1f9b-4c1e-8f0c-2a6a3c9d1e03", "key": "seats", "label": "Seats",
+             "type": "number", "required": false, "categories": [], "position": 2},
+            {"id": "5b0e3f64-1f9b-4c1e-8f0c-2a6a3c9d1e04", "key": "due", "label": "Due",
+             "type": "date", "required": false, "categories": [], "position": 3},
+            {"id": "5b0e3f64-1f9b-4c1e-8f0c-2a6a3c9d1e05", "key": "owner", "label": "Owner",
+             "type": "user", "required": false, "categories": [], "position": 4}
+        ]"#,
+    )
+    .expect("error parsing custom fields")
+}
+
+/// Creates values from a JSON object.
+fn values(json: &str) -> Values {
+    serde_json::from_str(json).expect("error parsing values")
+}
+
+/// Valid values are normalized, and fields only apply to their categories.
+#[test]
+fn ut_sunny_validate() {
+    let fields = fields();
+    assert_eq!(
+        fields[1].kind,
+        Kind::Enum {
+            options: vec!["EU".to_owned(), "US".to_owned()]
+        }
+    );
+    assert!(fields[0].applies_to(Some("hardware")));
+    assert!(!fields[0].applies_to(Some("software")) && !fields[0].applies_to(None));
+    assert!(fields[1].applies_to(None));
+
+    let valid = validate(
+        &fields,
+        Some("hardware"),
+        &values(
+            r#"{"asset_tag": " LAP-42 ", "region": "EU", "seats": 3, "due": "2022-07-09",
+                "owner": "8F6B0A0E-6C0C-4A8A-9C44-0F3A7B1E2D11"}"#,
+        ),
+    )
+    .expect("values were not valid");
+    assert_eq!(
+        valid,
+        values(
+            r#"{"asset_tag": "LAP-42", "region": "EU", "seats": 3.0, "due": "2022-07-09",
+                "owner": "8f6b0a0e-6c0c-4a8a-9c44-0f3a7b1e2d11"}"#
+        )
+    );
+    assert_eq!(
+        validate(&fields, None, &values(r#"{"region": " "}"#)),
+        Ok(Values::new())
+    );
+
+    assert_eq!(Kind::Number.parse(" 2.5 "), Some(Value::Number(2.5)));
+    assert_eq!(
+        check_filter(&fields, "seats", Operator::Ge, Some("10")),
+        Ok(())
+    );
+    assert_eq!(
+        check_filter(&fields, "owner", Operator::Is, Some("me")),
+        Ok(())
+    );
+    assert_eq!(check_filter(&fields, "region", Operator::Is, None), Ok(()));
+}
+
+/// Wrong values and definitions are reported.
+#[test]
+fn ut_rainy_validate() {
+    let fields = fields();
+    let errors = validate(
+        &fields,
+        Some("hardware"),
+        &values(
+            r#"{"region": "Asia", "seats": "3", "due": "09/07/2022", "owner": "alice",
+                "colour": "red"}"#,
+        ),
+    )
+    .expect_err("values were valid");
+    assert_eq!(
+        errors
+            .iter()
+            .map(|error| (error.field.as_str(), error.message.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("asset_tag", "this field is required"),
+            ("region", "is not one of the options"),
+            ("seats", "has the wrong type"),
+            ("due", "must be a date"),
+            ("owner", "must be a user"),
+            ("colour", "is not a field of this ticket"),
+        ]
+    );
+    let errors = validate(&fields, None, &values(r#"{"asset_tag": "LAP-42"}"#))
+        .expect_err("values were valid");
+    assert_eq!(errors[0].field, "asset_tag");
+
+    assert!(check_filter(&fields, "colour", Operator::Is, Some("red")).is_err());
+    assert!(check_filter(&fields, "region", Operator::Gt, Some("EU")).is_err());
+    assert!(check_filter(&fields, "seats", Operator::Lt, Some("many")).is_err());
+    assert!(check_filter(&fields, "seats", Operator::Lt, None).is_err());
+
+    let form = |key: &str, kind: Kind| CustomFieldFormDTO {
+        key: key.to_owned(),
+        label: "Label".to_owned(),
+        kind,
+        required: false,
+        categories: Vec::new(),
+        position: 0,
+    };
+    assert_eq!(form("asset_tag", Kind::String).check(), Ok(()));
+    assert!(form("Asset tag", Kind::String).check().is_err());
+    assert!(form(&"a".repeat(MAX_KEY_LEN + 1), Kind::String)
+        .check()
+        .is_err());
+    let options = |options: &[&str]| Kind::Enum {
+        options: options.iter().map(|option| (*option).to_owned()).collect(),
+    };
+    assert!(form("region", options(&[])).check().is_err());
+    assert!(form("region", options(&["EU", "EU"])).check().is_err());
+    assert!(form("region", options(&["EU", " "])).check().is_err());
+}
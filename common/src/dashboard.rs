@@ -0,0 +1,141 @@
+//! Agent dashboards.
+//!
+//! The dashboard of each agent is a list of [widgets](Widget), laid out in order. Agents that
+//! never changed their dashboard get the [default one](DashboardDTO::default).
+
+use crate::ticket::TicketDTO;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Maximum number of items listed by a widget.
+pub const MAX_WIDGET_LIMIT: i64 = 50;
+
+/// Default number of items listed by a widget.
+pub const DEFAULT_WIDGET_LIMIT: i64 = 10;
+
+string_enum! {
+    /// Kind of widget of a dashboard.
+    pub enum Widget {
+        /// Unresolved tickets assigned to the agent.
+        MyOpenTickets => "my_open_tickets",
+        /// Unresolved tickets without an assignee.
+        UnassignedQueue => "unassigned_queue",
+        /// Unresolved tickets close to missing, or that missed, a target of their SLA policy.
+        SlaAtRisk => "sla_at_risk",
+        /// Latest tickets opened, commented on or changed.
+        RecentActivity => "recent_activity",
+    }
+}
+
+/// Widget of a dashboard, with its settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WidgetDTO {
+    pub kind: Widget,
+    /// Whether the widget takes the full width of the dashboard, instead of half of it.
+    #[serde(default)]
+    pub wide: bool,
+    /// Number of items listed by the widget.
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+/// Gets the default number of items listed by a widget.
+fn default_limit() -> i64 {
+    DEFAULT_WIDGET_LIMIT
+}
+
+/// Layout of the dashboard of an agent, sent between the server and the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DashboardDTO {
+    /// Widgets of the dashboard, in order. Each kind of widget appears at most once.
+    pub widgets: Vec<WidgetDTO>,
+}
+
+impl Default for DashboardDTO {
+    fn default() -> Self {
+        Self {
+            widgets: Widget::ALL
+                .iter()
+                .map(|&kind| WidgetDTO {
+                    kind,
+                    wide: kind == Widget::RecentActivity,
+                    limit: DEFAULT_WIDGET_LIMIT,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DashboardDTO {
+    /// Checks that the layout can be stored, returning the reason why it can't otherwise.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        for (i, widget) in self.widgets.iter().enumerate() {
+            if !(1..=MAX_WIDGET_LIMIT).contains(&widget.limit) {
+                return Err("invalid widget limit");
+            }
+            if self.widgets[..i].iter().any(|w| w.kind == widget.kind) {
+                return Err("duplicated widget");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+string_enum! {
+    /// Target of an SLA policy.
+    pub enum SlaTarget {
+        /// The first response of the staff.
+        Response => "response",
+        /// The resolution of the ticket.
+        Resolution => "resolution",
+    }
+}
+
+/// Ticket at risk of missing a target of its SLA policy, sent from the server to agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlaRiskDTO {
+    pub ticket: TicketDTO,
+    /// The next target of the ticket.
+    pub target: SlaTarget,
+    /// When the target is due, which may have passed.
+    pub due_on: DateTime<Utc>,
+    /// Whether the target was missed.
+    pub breached: bool,
+}
+
+string_enum! {
+    /// Kind of ticket activity.
+    pub enum ActivityKind {
+        Created => "created",
+        Commented => "commented",
+        /// A field of the ticket changed.
+        Changed => "changed",
+    }
+}
+
+/// Ticket activity, sent from the server to agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityDTO {
+    pub ticket_id: Uuid,
+    pub number: i64,
+    pub title: String,
+    pub kind: ActivityKind,
+    /// Full name of the user that acted, `None` for the application itself or deleted users.
+    pub actor: Option<String>,
+    /// Field that changed, for changes.
+    pub field: Option<String>,
+    /// New value of the field that changed, for changes.
+    pub value: Option<String>,
+    pub happened_on: DateTime<Utc>,
+}
+
+/// Live ticket activity, pushed from the server to agents as it happens.
+///
+/// It only identifies the ticket, which clients load again if they show it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TicketActivityDTO {
+    pub ticket_id: Uuid,
+    pub kind: ActivityKind,
+}
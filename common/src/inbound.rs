@@ -0,0 +1,34 @@
+//! Inbound email-to-ticket ingestion.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Outcome of the ingestion of an email.
+    pub enum Outcome {
+        /// A new ticket was created.
+        Created => "created",
+        /// A comment was added to an existing ticket.
+        Commented => "commented",
+        /// The email was an auto-reply or part of a mail loop, and was discarded.
+        Ignored => "ignored",
+        /// The email could not be ingested, for example because the sender is unknown.
+        Rejected => "rejected",
+    }
+}
+
+/// Entry of the inbound email log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InboundEmailDTO {
+    pub id: i64,
+    pub message_id: Option<String>,
+    pub from_address: String,
+    pub subject: String,
+    pub outcome: Outcome,
+    /// Reason of the outcome, for ignored and rejected emails.
+    pub reason: Option<String>,
+    pub ticket_id: Option<Uuid>,
+    pub comment_id: Option<Uuid>,
+    pub created_on: DateTime<Utc>,
+}
@@ -1 +1,49 @@
+#[macro_use]
+mod macros;
+
+pub mod approval;
+pub mod article;
+pub mod audit;
+pub mod automation;
+pub mod board;
+pub mod catalog;
+pub mod change;
+pub mod cmdb;
+pub mod custom_field;
+pub mod dashboard;
+pub mod inbound;
+pub mod login;
+pub mod notification;
+pub mod organisation;
+pub mod problem;
+pub mod query;
 pub mod registration;
+pub mod report;
+pub mod search;
+pub mod survey;
+pub mod team;
+pub mod ticket;
+pub mod user;
+pub mod view;
+pub mod webhook;
+pub mod work_log;
+
+/// Normalizes a list of labels, such as tags or skills.
+///
+/// Labels are trimmed and converted to lowercase, and empty and duplicated labels are removed. The
+/// result is sorted.
+pub fn normalize_labels<I, S>(labels: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized = labels
+        .into_iter()
+        .map(|label| label.as_ref().trim().to_lowercase())
+        .filter(|label| !label.is_empty())
+        .collect::<Vec<_>>();
+    normalized.sort_unstable();
+    normalized.dedup();
+
+    normalized
+}
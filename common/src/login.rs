@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Data Transfer Object used from the client when transferring the login form information to the
+/// server.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginDTO<'d> {
+    #[serde(rename = "user")]
+    pub username: &'d str,
+    #[serde(rename = "pass")]
+    pub password: &'d str,
+}
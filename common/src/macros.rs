@@ -0,0 +1,65 @@
+/// Defines an enumeration represented as a string, both in JSON and in the database.
+///
+/// Each variant is followed by its string representation. The enumeration gets an `ALL` constant
+/// with every variant in order, an `as_str()` method, and `Display` and `FromStr` implementations.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident => $repr:literal,
+            )+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(
+            Debug,
+            Clone,
+            Copy,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+            serde::Serialize,
+            serde::Deserialize,
+        )]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                #[serde(rename = $repr)]
+                $variant,
+            )+
+        }
+
+        impl $name {
+            /// All the possible values, in order.
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            /// Gets the string representation of the value, as stored in the database.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $repr,)+
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($repr => Ok(Self::$variant),)+
+                    other => Err(format!("invalid {}: {}", stringify!($name), other)),
+                }
+            }
+        }
+    };
+}
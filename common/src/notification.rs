@@ -0,0 +1,79 @@
+//! In-app notifications.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Kind of a notification.
+    pub enum Kind {
+        /// A ticket has been assigned to the user.
+        TicketAssigned => "ticket.assigned",
+        /// A ticket of the user has received a comment.
+        TicketCommented => "ticket.commented",
+        /// The status of a ticket of the user has changed.
+        TicketStatusChanged => "ticket.status_changed",
+        /// The user has to approve or reject a ticket.
+        ApprovalRequested => "approval.requested",
+    }
+}
+
+impl Kind {
+    /// Gets how users receive this kind of notification if they have no preference for it.
+    ///
+    /// Approval requests are emailed right away, since they carry the links to decide on them.
+    pub fn default_delivery(self) -> Delivery {
+        match self {
+            Self::ApprovalRequested => Delivery::Immediate,
+            _ => Delivery::default(),
+        }
+    }
+}
+
+string_enum! {
+    /// How a user receives a kind of notification.
+    ///
+    /// All notifications, except the ones turned off, are shown in the application.
+    pub enum Delivery {
+        /// Notifications are also sent by email as soon as they happen.
+        Immediate => "immediate",
+        /// Notifications are also sent in a daily digest email.
+        Digest => "digest",
+        /// Notifications are only shown in the application.
+        InApp => "in_app",
+        /// Notifications are not sent.
+        Off => "off",
+    }
+}
+
+impl Default for Delivery {
+    fn default() -> Self {
+        Self::InApp
+    }
+}
+
+/// Notification of a user, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationDTO {
+    pub id: Uuid,
+    pub kind: Kind,
+    pub title: String,
+    pub body: String,
+    /// Ticket the notification is about, if any.
+    pub ticket_id: Option<Uuid>,
+    pub read: bool,
+    pub created_on: DateTime<Utc>,
+}
+
+/// Number of unread notifications of a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnreadCountDTO {
+    pub count: i64,
+}
+
+/// Preference of a user for a kind of notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreferenceDTO {
+    pub kind: Kind,
+    pub delivery: Delivery,
+}
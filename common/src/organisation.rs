@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Customer organisation, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrganisationDTO {
+    pub id: Uuid,
+    pub name: String,
+    /// Email domains of the organisation. Users registering with these domains will automatically
+    /// become members.
+    pub domains: Vec<String>,
+}
+
+/// Organisation form data, used by administrators to create or update organisations.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrganisationFormDTO<'r> {
+    pub name: &'r str,
+    #[serde(borrow)]
+    pub domains: Vec<&'r str>,
+}
+
+/// Member of an organisation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberDTO {
+    pub user_id: Uuid,
+    pub username: String,
+    pub email: String,
+}
+
+/// Membership form data, used to add a user to an organisation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MembershipDTO {
+    pub user_id: Uuid,
+}
+
+/// Branding of the customer portal for the members of an organisation, sent between the server and
+/// the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrandingDTO {
+    /// URL of the logo, either HTTPS or relative to the application.
+    pub logo_url: Option<String>,
+    /// Colour of the header of the portal, as `#rrggbb`.
+    pub primary_colour: String,
+    /// Colour of the buttons and links of the portal, as `#rrggbb`.
+    pub accent_colour: String,
+    /// Text shown on the home page of the portal.
+    pub welcome_text: String,
+}
+
+impl Default for BrandingDTO {
+    fn default() -> Self {
+        Self {
+            logo_url: None,
+            primary_colour: "#212529".to_owned(),
+            accent_colour: "#0d6efd".to_owned(),
+            welcome_text: "How can we help you?".to_owned(),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Problem management.
+//!
+//! Problems are the causes of recurring incidents. Incident tickets are linked to their problem,
+//! whose root cause and workaround are tracked until it's fixed. Once the root cause is known, the
+//! problem becomes a known error, which can be published in the knowledge base.
+
+use crate::article::Visibility;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+string_enum! {
+    /// Status of a problem.
+    pub enum Status {
+        /// The root cause of the problem is being investigated.
+        Investigating => "investigating",
+        /// The root cause is known, but the problem is not fixed yet.
+        KnownError => "known_error",
+        /// The problem is fixed, and its incidents were resolved.
+        Resolved => "resolved",
+    }
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::Investigating
+    }
+}
+
+/// Problem, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDTO {
+    pub id: Uuid,
+    /// Human readable number of the problem.
+    pub number: i64,
+    pub title: String,
+    pub description: String,
+    pub status: Status,
+    pub root_cause: String,
+    pub workaround: String,
+    pub owner_id: Option<Uuid>,
+    /// Knowledge base article of the known error, if published.
+    pub article_id: Option<Uuid>,
+    pub created_by: Uuid,
+    pub resolved_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
+
+/// Problem form data, used by agents to create or update problems.
+///
+/// Problems are resolved with their own action, which also resolves their incidents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProblemFormDTO {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub status: Status,
+    #[serde(default)]
+    pub root_cause: String,
+    #[serde(default)]
+    pub workaround: String,
+    #[serde(default)]
+    pub owner_id: Option<Uuid>,
+}
+
+impl ProblemFormDTO {
+    /// Checks that the problem is consistent, returning the reason if it isn't.
+    ///
+    /// Known errors and resolved problems need a root cause.
+    pub fn check(&self) -> Result<(), &'static str> {
+        if self.title.trim().is_empty() {
+            return Err("title can't be empty");
+        }
+        if self.status != Status::Investigating && self.root_cause.trim().is_empty() {
+            return Err("known errors need a root cause");
+        }
+
+        Ok(())
+    }
+}
+
+/// Incident link data, used by agents to link an incident ticket to a problem.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IncidentLinkDTO {
+    pub ticket_id: Uuid,
+}
+
+/// Resolution form data, used by agents to resolve a problem and its incidents.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResolutionFormDTO {
+    /// Comment added to the resolved incidents, if not empty.
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// Result of the resolution of a problem, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolutionDTO {
+    pub problem: ProblemDTO,
+    /// IDs of the incidents resolved along with the problem.
+    ///
+    /// Incidents that were already resolved or closed are left as they were.
+    pub resolved_ticket_ids: Vec<Uuid>,
+}
+
+/// Publication form data, used by agents to publish a known error in the knowledge base.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PublicationFormDTO {
+    #[serde(default)]
+    pub category_id: Option<Uuid>,
+    #[serde(default)]
+    pub visibility: Visibility,
+}
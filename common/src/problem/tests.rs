@@ -0,0 +1,32 @@
+use super::*;
+
+/// Creates a problem form.
+fn form(title: &str, status: Status, root_cause: &str) -> ProblemFormDTO {
+    ProblemFormDTO {
+        title: title.to_owned(),
+        description: String::new(),
+        status,
+        root_cause: root_cause.to_owned(),
+        workaround: String::new(),
+        owner_id: None,
+    }
+}
+
+/// Problems under investigation don't need a root cause, and known errors have one.
+#[test]
+fn ut_sunny_check() {
+    assert_eq!(form("VPN drops", Status::Investigating, "").check(), Ok(()));
+    assert_eq!(
+        form("VPN drops", Status::KnownError, "Expired certificate").check(),
+        Ok(())
+    );
+    assert_eq!("known_error".parse(), Ok(Status::KnownError));
+}
+
+/// Problems need a title, and known errors need a root cause.
+#[test]
+fn ut_rainy_check() {
+    assert!(form(" ", Status::Investigating, "").check().is_err());
+    assert!(form("VPN drops", Status::KnownError, " ").check().is_err());
+    assert!(form("VPN drops", Status::Resolved, "").check().is_err());
+}
@@ -0,0 +1,596 @@
+//! Query language for filtering tickets.
+//!
+//! Queries are made of terms separated by whitespace, all of which must match. A term is either a
+//! filter on a field, such as `status:open` or `priority>=high`, or free text, matched against the
+//! title and description of tickets. Prefixing a term with `-` negates it, and values containing
+//! spaces can be quoted: `organisation:"Acme Corp"`.
+//!
+//! | Field                     | Operators                 | Values                                  |
+//! |---------------------------|---------------------------|-----------------------------------------|
+//! | `status`                  | `:`                       | statuses, separated by commas           |
+//! | `priority`                | `:` `<` `<=` `>` `>=`     | a priority                              |
+//! | `assignee`, `requester`   | `:`                       | `me`, `none` or a username              |
+//! | `organisation`, `queue`   | `:`                       | a name, or `none`                       |
+//! | `category`                | `:`                       | a category, or `none`                   |
+//! | `tag`                     | `:`                       | a tag                                   |
+//! | `created`, `updated`      | `:` `<` `<=` `>` `>=`     | a `YYYY-MM-DD` date, or `-7d` (h, d, w) |
+//! | `cf.<key>`                | `:`, and the others for numbers and dates | a value, or `none` |
+//!
+//! Dates are in UTC. Relative times are counted back from now, so `created>-7d` matches the tickets
+//! created in the last week. Relative times go back a century at most.
+//!
+//! Filters on [custom fields](crate::custom_field) can only be checked against the field
+//! definitions, so parsing accepts any key and value.
+
+#[cfg(test)]
+mod tests;
+
+use crate::ticket::{Priority, Status};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use std::{fmt, str::FromStr};
+
+string_enum! {
+    /// Field of a ticket that can be filtered.
+    pub enum Field {
+        Status => "status",
+        Priority => "priority",
+        Assignee => "assignee",
+        Requester => "requester",
+        Organisation => "organisation",
+        Queue => "queue",
+        Category => "category",
+        Tag => "tag",
+        Created => "created",
+        Updated => "updated",
+    }
+}
+
+impl Field {
+    /// Checks if the field supports the ordering operators (`<`, `<=`, `>` and `>=`).
+    pub fn is_ordered(self) -> bool {
+        matches!(self, Self::Priority | Self::Created | Self::Updated)
+    }
+
+    /// Gets the suggested values for the field.
+    fn suggestions(self) -> Vec<&'static str> {
+        match self {
+            Self::Status => Status::ALL.iter().map(|status| status.as_str()).collect(),
+            Self::Priority => Priority::ALL
+                .iter()
+                .map(|priority| priority.as_str())
+                .collect(),
+            Self::Assignee | Self::Requester => vec![ME, NONE],
+            Self::Organisation | Self::Queue | Self::Category => vec![NONE],
+            Self::Tag => Vec::new(),
+            Self::Created | Self::Updated => vec!["-1d", "-7d", "-30d"],
+        }
+    }
+}
+
+/// Value referring to the user running the query.
+pub const ME: &str = "me";
+
+/// Value referring to a missing value.
+const NONE: &str = "none";
+
+/// Prefix of the filters on custom fields.
+pub const CUSTOM_PREFIX: &str = "cf.";
+
+/// Maximum number of days of relative times, about a century.
+const MAX_AGO_DAYS: i64 = 36_525;
+
+/// Comparison operator of a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `:`, the field has the value.
+    Is,
+    /// `<`, the field is lower than the value.
+    Lt,
+    /// `<=`, the field is lower than or equal to the value.
+    Le,
+    /// `>`, the field is greater than the value.
+    Gt,
+    /// `>=`, the field is greater than or equal to the value.
+    Ge,
+}
+
+impl Operator {
+    /// Gets the string representation of the operator.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Is => ":",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+
+    /// Checks if a value compared with the operator matches a target.
+    pub fn matches<T: Ord>(self, value: T, target: T) -> bool {
+        match self {
+            Self::Is => value == target,
+            Self::Lt => value < target,
+            Self::Le => value <= target,
+            Self::Gt => value > target,
+            Self::Ge => value >= target,
+        }
+    }
+}
+
+/// User in a filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserRef {
+    /// The user running the query.
+    Me,
+    /// No user.
+    Nobody,
+    /// The user with the given username.
+    Username(String),
+}
+
+/// Point in time in a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Moment {
+    /// A whole day, in UTC.
+    Date(NaiveDate),
+    /// The given time before now.
+    Ago(Duration),
+}
+
+impl Moment {
+    /// Gets the first and last instants of the moment: the start and the end (exclusive) of the
+    /// day for dates, and the same instant twice for relative times.
+    ///
+    /// Returns `None` when the moment is out of the range of dates.
+    pub fn bounds(self, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        match self {
+            Self::Date(date) => {
+                let start = Utc.from_utc_date(&date).and_hms(0, 0, 0);
+                Some((start, start.checked_add_signed(Duration::days(1))?))
+            }
+            Self::Ago(duration) => {
+                let instant = now.checked_sub_signed(duration)?;
+                Some((instant, instant))
+            }
+        }
+    }
+}
+
+/// Filter of a query term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// The ticket has any of the statuses.
+    Status(Vec<Status>),
+    Priority(Operator, Priority),
+    Assignee(UserRef),
+    Requester(UserRef),
+    /// The ticket belongs to the organisation with the name, ignoring case, or to none.
+    Organisation(Option<String>),
+    /// The ticket is in the queue with the name, ignoring case, or in none.
+    Queue(Option<String>),
+    Category(Option<String>),
+    Tag(String),
+    Created(Operator, Moment),
+    Updated(Operator, Moment),
+    /// The custom field with the key compares with the value, or has no value.
+    Custom {
+        key: String,
+        operator: Operator,
+        value: Option<String>,
+    },
+    /// The title or the description of the ticket contains the text, ignoring case.
+    Text(String),
+}
+
+/// Term of a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    /// Whether the term must not match.
+    pub negated: bool,
+    pub filter: Filter,
+}
+
+/// Query filtering tickets, matching the tickets that match all its terms.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    pub terms: Vec<Term>,
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let terms = split(s)?
+            .into_iter()
+            .map(|(position, raw)| parse_term(raw).map_err(|kind| ParseError { position, kind }))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { terms })
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, term) in self.terms.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", term)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            f.write_str("-")?;
+        }
+
+        let (field, operator, value) = match &self.filter {
+            // Texts that would be parsed as a negation or a filter must be quoted
+            Filter::Text(text) if text.starts_with('-') || split_operator(text).is_some() => {
+                return write!(f, "\"{}\"", text);
+            }
+            Filter::Text(text) => return f.write_str(&quote(text)),
+            Filter::Status(statuses) => (
+                Field::Status,
+                Operator::Is,
+                statuses
+                    .iter()
+                    .map(|status| status.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            Filter::Priority(operator, priority) => {
+                (Field::Priority, *operator, priority.to_string())
+            }
+            Filter::Assignee(user) => (Field::Assignee, Operator::Is, user_value(user)),
+            Filter::Requester(user) => (Field::Requester, Operator::Is, user_value(user)),
+            Filter::Organisation(name) => (Field::Organisation, Operator::Is, optional(name)),
+            Filter::Queue(name) => (Field::Queue, Operator::Is, optional(name)),
+            Filter::Category(category) => (Field::Category, Operator::Is, optional(category)),
+            Filter::Tag(tag) => (Field::Tag, Operator::Is, tag.clone()),
+            Filter::Created(operator, moment) => (Field::Created, *operator, moment_value(moment)),
+            Filter::Updated(operator, moment) => (Field::Updated, *operator, moment_value(moment)),
+            Filter::Custom {
+                key,
+                operator,
+                value,
+            } => {
+                return write!(
+                    f,
+                    "{}{}{}{}",
+                    CUSTOM_PREFIX,
+                    key,
+                    operator.as_str(),
+                    quote(&optional(value))
+                );
+            }
+        };
+
+        write!(f, "{}{}{}", field, operator.as_str(), quote(&value))
+    }
+}
+
+/// Gets the value of a user in a query.
+fn user_value(user: &UserRef) -> String {
+    match user {
+        UserRef::Me => ME.to_owned(),
+        UserRef::Nobody => NONE.to_owned(),
+        UserRef::Username(username) => username.clone(),
+    }
+}
+
+/// Gets the value of an optional name in a query.
+fn optional(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| NONE.to_owned())
+}
+
+/// Gets the value of a moment in a query.
+fn moment_value(moment: &Moment) -> String {
+    match moment {
+        Moment::Date(date) => date.format("%Y-%m-%d").to_string(),
+        Moment::Ago(duration) if duration.num_hours() % (24 * 7) == 0 => {
+            format!("-{}w", duration.num_weeks())
+        }
+        Moment::Ago(duration) if duration.num_hours() % 24 == 0 => {
+            format!("-{}d", duration.num_days())
+        }
+        Moment::Ago(duration) => format!("-{}h", duration.num_hours()),
+    }
+}
+
+/// Quotes a value if needed.
+fn quote(value: &str) -> String {
+    if value.is_empty() || value.contains(char::is_whitespace) {
+        format!("\"{}\"", value)
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Error parsing a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The position of the wrong term in the query, in bytes.
+    pub position: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Kind of error parsing a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A quote is not closed.
+    UnterminatedQuote,
+    /// The field does not exist.
+    UnknownField(String),
+    /// The field does not support the operator.
+    UnsupportedOperator(Field, Operator),
+    /// The field has no value.
+    MissingValue(Field),
+    /// The value is not valid for the field.
+    InvalidValue(Field, String),
+    /// The custom field with the key has no value.
+    MissingCustomValue(String),
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnterminatedQuote => f.write_str("unterminated quote"),
+            Self::UnknownField(field) => write!(f, "unknown field `{}`", field),
+            Self::UnsupportedOperator(field, operator) => write!(
+                f,
+                "operator `{}` is not supported by field `{}`",
+                operator.as_str(),
+                field
+            ),
+            Self::MissingValue(field) => write!(f, "missing value for field `{}`", field),
+            Self::InvalidValue(field, value) => {
+                write!(f, "invalid value `{}` for field `{}`", value, field)
+            }
+            Self::MissingCustomValue(key) => {
+                write!(f, "missing value for custom field `{}`", key)
+            }
+        }
+    }
+}
+
+/// Splits a query in its raw terms, along with their positions.
+///
+/// Terms are separated by whitespace, except inside quotes.
+fn split(query: &str) -> Result<Vec<(usize, &str)>, ParseError> {
+    let mut terms = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+
+    for (i, c) in query.char_indices() {
+        match (c, start) {
+            ('"', _) => {
+                quoted = !quoted;
+                let _ = start.get_or_insert(i);
+            }
+            (c, Some(term_start)) if c.is_whitespace() && !quoted => {
+                terms.push((term_start, &query[term_start..i]));
+                start = None;
+            }
+            (c, None) if !c.is_whitespace() => start = Some(i),
+            _ => {}
+        }
+    }
+
+    if let Some(term_start) = start {
+        if quoted {
+            return Err(ParseError {
+                position: term_start,
+                kind: ErrorKind::UnterminatedQuote,
+            });
+        }
+        terms.push((term_start, &query[term_start..]));
+    }
+
+    Ok(terms)
+}
+
+/// Parses a raw term.
+fn parse_term(raw: &str) -> Result<Term, ErrorKind> {
+    let (negated, raw) = match raw.strip_prefix('-') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, raw),
+    };
+
+    let filter = match split_operator(raw) {
+        Some((field, operator, value)) if field.starts_with(CUSTOM_PREFIX) => {
+            let key = &field[CUSTOM_PREFIX.len()..];
+            let value = unquote(value).trim();
+            if value.is_empty() {
+                return Err(ErrorKind::MissingCustomValue(key.to_owned()));
+            }
+
+            Filter::Custom {
+                key: key.to_owned(),
+                operator,
+                value: parse_optional(value),
+            }
+        }
+        Some((field, operator, value)) => {
+            let field = field
+                .parse::<Field>()
+                .map_err(|_| ErrorKind::UnknownField(field.to_owned()))?;
+            parse_filter(field, operator, unquote(value))?
+        }
+        None => Filter::Text(unquote(raw).to_owned()),
+    };
+
+    Ok(Term { negated, filter })
+}
+
+/// Splits a raw term in its field, operator and value, if it's a filter on a field.
+///
+/// Custom fields are prefixed with [`CUSTOM_PREFIX`], and their keys can contain digits.
+fn split_operator(raw: &str) -> Option<(&str, Operator, &str)> {
+    let end = match raw.strip_prefix(CUSTOM_PREFIX) {
+        Some(key) => {
+            CUSTOM_PREFIX.len()
+                + key.find(|c: char| !c.is_ascii_lowercase() && !c.is_ascii_digit() && c != '_')?
+        }
+        None => raw.find(|c: char| !c.is_ascii_alphabetic() && c != '_')?,
+    };
+    if end == 0 || end == CUSTOM_PREFIX.len() && raw.starts_with(CUSTOM_PREFIX) {
+        return None;
+    }
+
+    let (field, rest) = raw.split_at(end);
+    let (operator, len) = if rest.starts_with("<=") {
+        (Operator::Le, 2)
+    } else if rest.starts_with(">=") {
+        (Operator::Ge, 2)
+    } else if rest.starts_with('<') {
+        (Operator::Lt, 1)
+    } else if rest.starts_with('>') {
+        (Operator::Gt, 1)
+    } else if rest.starts_with(':') {
+        (Operator::Is, 1)
+    } else {
+        return None;
+    };
+
+    Some((field, operator, &rest[len..]))
+}
+
+/// Removes the quotes around a value, if any.
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Parses the filter of a field.
+fn parse_filter(field: Field, operator: Operator, value: &str) -> Result<Filter, ErrorKind> {
+    if operator != Operator::Is && !field.is_ordered() {
+        return Err(ErrorKind::UnsupportedOperator(field, operator));
+    }
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(ErrorKind::MissingValue(field));
+    }
+    let invalid = || ErrorKind::InvalidValue(field, value.to_owned());
+
+    Ok(match field {
+        Field::Status => Filter::Status(
+            value
+                .split(',')
+                .map(|status| status.trim().parse().map_err(|_| invalid()))
+                .collect::<Result<_, _>>()?,
+        ),
+        Field::Priority => Filter::Priority(operator, value.parse().map_err(|_| invalid())?),
+        Field::Assignee => Filter::Assignee(parse_user(value)),
+        Field::Requester => Filter::Requester(parse_user(value)),
+        Field::Organisation => Filter::Organisation(parse_optional(value)),
+        Field::Queue => Filter::Queue(parse_optional(value)),
+        Field::Category => Filter::Category(parse_optional(value)),
+        Field::Tag => Filter::Tag(value.to_lowercase()),
+        Field::Created => Filter::Created(operator, parse_moment(value).ok_or_else(invalid)?),
+        Field::Updated => Filter::Updated(operator, parse_moment(value).ok_or_else(invalid)?),
+    })
+}
+
+/// Parses a user value.
+fn parse_user(value: &str) -> UserRef {
+    match value {
+        ME => UserRef::Me,
+        NONE => UserRef::Nobody,
+        username => UserRef::Username(username.to_owned()),
+    }
+}
+
+/// Parses an optional name.
+fn parse_optional(value: &str) -> Option<String> {
+    (value != NONE).then(|| value.to_owned())
+}
+
+/// Parses a date, or a relative time such as `-7d`.
+fn parse_moment(value: &str) -> Option<Moment> {
+    if let Some(relative) = value.strip_prefix('-') {
+        let (index, unit) = relative.char_indices().last()?;
+        let seconds_per_unit = match unit {
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        let seconds = relative[..index]
+            .parse::<i64>()
+            .ok()
+            .filter(|amount| *amount >= 0)?
+            .checked_mul(seconds_per_unit)
+            .filter(|seconds| *seconds <= MAX_AGO_DAYS * 24 * 60 * 60)?;
+
+        return Some(Moment::Ago(Duration::seconds(seconds)));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .filter(|date| date.succ_opt().is_some())
+        .map(Moment::Date)
+}
+
+/// Gets the completions for the last term of a partial query.
+///
+/// Completions are the whole query with its last term completed, so that they can replace it.
+pub fn complete(query: &str) -> Vec<String> {
+    let last = query.rsplit(char::is_whitespace).next().unwrap_or_default();
+    let head = &query[..query.len() - last.len()];
+    let (sign, last) = match last.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", last),
+    };
+
+    match split_operator(last) {
+        Some((field, operator, value)) => {
+            let field = match field.parse::<Field>() {
+                Ok(field) => field,
+                Err(_) => return Vec::new(),
+            };
+            // Statuses are completed after the last comma
+            let (done, partial) = match value.rfind(',') {
+                Some(i) if field == Field::Status => value.split_at(i + 1),
+                _ => ("", value),
+            };
+
+            field
+                .suggestions()
+                .into_iter()
+                .filter(|suggestion| suggestion.starts_with(partial) && *suggestion != partial)
+                .map(|suggestion| {
+                    format!(
+                        "{}{}{}{}{}{}",
+                        head,
+                        sign,
+                        field,
+                        operator.as_str(),
+                        done,
+                        suggestion
+                    )
+                })
+                .collect()
+        }
+        None => Field::ALL
+            .iter()
+            .filter(|field| field.as_str().starts_with(last))
+            .map(|field| format!("{}{}{}:", head, sign, field))
+            .collect(),
+    }
+}
@@ -0,0 +1,182 @@
+use super::*;
+
+/// Parses a query, panicking on errors.
+fn parse(query: &str) -> Query {
+    query.parse().expect("error parsing query")
+}
+
+/// Creates a term that is not negated.
+fn term(filter: Filter) -> Term {
+    Term {
+        negated: false,
+        filter,
+    }
+}
+
+/// Queries are parsed into their terms.
+#[test]
+fn ut_sunny_parse() {
+    let query = parse(
+        "status:open,pending assignee:me priority>=high created>-7d tag:VPN -category:none \
+         organisation:\"Acme Corp\" printer",
+    );
+
+    assert_eq!(
+        query.terms,
+        vec![
+            term(Filter::Status(vec![Status::Open, Status::Pending])),
+            term(Filter::Assignee(UserRef::Me)),
+            term(Filter::Priority(Operator::Ge, Priority::High)),
+            term(Filter::Created(
+                Operator::Gt,
+                Moment::Ago(Duration::days(7))
+            )),
+            term(Filter::Tag("vpn".to_owned())),
+            Term {
+                negated: true,
+                filter: Filter::Category(None),
+            },
+            term(Filter::Organisation(Some("Acme Corp".to_owned()))),
+            term(Filter::Text("printer".to_owned())),
+        ]
+    );
+    assert_eq!(parse("  ").terms, Vec::new());
+    assert_eq!(
+        parse("updated<=2022-05-01 requester:carol").terms,
+        vec![
+            term(Filter::Updated(
+                Operator::Le,
+                Moment::Date(NaiveDate::from_ymd(2022, 5, 1))
+            )),
+            term(Filter::Requester(UserRef::Username("carol".to_owned()))),
+        ]
+    );
+    assert_eq!(
+        parse("cf.asset_tag2:\"LAP 42\" -cf.region:none").terms,
+        vec![
+            term(Filter::Custom {
+                key: "asset_tag2".to_owned(),
+                operator: Operator::Is,
+                value: Some("LAP 42".to_owned()),
+            }),
+            Term {
+                negated: true,
+                filter: Filter::Custom {
+                    key: "region".to_owned(),
+                    operator: Operator::Is,
+                    value: None,
+                },
+            },
+        ]
+    );
+}
+
+/// Displayed queries are parsed back to the same query.
+#[test]
+fn ut_sunny_display() {
+    for query in [
+        "status:open,pending assignee:none priority<urgent created:2022-05-01 updated>-12h",
+        "-queue:\"Level 2\" \"out of paper\" \"-1\" \"a:b\" tag:vpn created>=-2w",
+        "cf.seats>=10 -cf.region:none cf.asset_tag:\"LAP 42\" v1.2",
+    ] {
+        let parsed = parse(query);
+        assert_eq!(parsed.to_string(), query);
+        assert_eq!(parse(&parsed.to_string()), parsed);
+    }
+}
+
+/// Invalid queries are rejected, with the position of the wrong term.
+#[test]
+fn ut_rainy_parse() {
+    let error = |query: &str| query.parse::<Query>().expect_err("query was parsed");
+
+    assert_eq!(
+        error("status:open colour:red"),
+        ParseError {
+            position: 12,
+            kind: ErrorKind::UnknownField("colour".to_owned()),
+        }
+    );
+    assert_eq!(
+        error("status>open").kind,
+        ErrorKind::UnsupportedOperator(Field::Status, Operator::Gt)
+    );
+    assert_eq!(
+        error("status:open,closd").kind,
+        ErrorKind::InvalidValue(Field::Status, "open,closd".to_owned())
+    );
+    assert_eq!(
+        error("priority:").kind,
+        ErrorKind::MissingValue(Field::Priority)
+    );
+    assert_eq!(
+        error("created>-7y").kind,
+        ErrorKind::InvalidValue(Field::Created, "-7y".to_owned())
+    );
+    assert_eq!(
+        error("created:-é").kind,
+        ErrorKind::InvalidValue(Field::Created, "-é".to_owned())
+    );
+    assert_eq!(
+        error("created:-7é").kind,
+        ErrorKind::InvalidValue(Field::Created, "-7é".to_owned())
+    );
+    assert_eq!(
+        error("created>-99999999999999w").kind,
+        ErrorKind::InvalidValue(Field::Created, "-99999999999999w".to_owned())
+    );
+    assert_eq!(
+        error("updated<-9223372036854775807h").kind,
+        ErrorKind::InvalidValue(Field::Updated, "-9223372036854775807h".to_owned())
+    );
+    assert_eq!(
+        error("created>-40000d").kind,
+        ErrorKind::InvalidValue(Field::Created, "-40000d".to_owned())
+    );
+    assert_eq!(error("title \"out of").kind, ErrorKind::UnterminatedQuote);
+    assert_eq!(
+        error("cf.region:").kind,
+        ErrorKind::MissingCustomValue("region".to_owned())
+    );
+}
+
+/// Moments are bounded in time.
+#[test]
+fn ut_sunny_moment_bounds() {
+    let now = Utc.ymd(2022, 6, 11).and_hms(10, 30, 0);
+
+    assert_eq!(
+        Moment::Date(NaiveDate::from_ymd(2022, 6, 1)).bounds(now),
+        Some((
+            Utc.ymd(2022, 6, 1).and_hms(0, 0, 0),
+            Utc.ymd(2022, 6, 2).and_hms(0, 0, 0)
+        ))
+    );
+    let week_ago = Utc.ymd(2022, 6, 4).and_hms(10, 30, 0);
+    assert_eq!(
+        Moment::Ago(Duration::weeks(1)).bounds(now),
+        Some((week_ago, week_ago))
+    );
+}
+
+/// Moments out of the range of dates have no bounds.
+#[test]
+fn ut_rainy_moment_bounds() {
+    let now = Utc.ymd(2022, 6, 11).and_hms(10, 30, 0);
+
+    assert_eq!(Moment::Date(chrono::naive::MAX_DATE).bounds(now), None);
+    assert_eq!(Moment::Ago(Duration::max_value()).bounds(now), None);
+}
+
+/// The last term of partial queries is completed.
+#[test]
+fn ut_sunny_complete() {
+    assert_eq!(complete("printer st"), vec!["printer status:"]);
+    assert_eq!(complete("-assi"), vec!["-assignee:"]);
+    assert_eq!(complete("assignee:"), vec!["assignee:me", "assignee:none"]);
+    assert_eq!(complete("status:open,p"), vec!["status:open,pending"]);
+    assert_eq!(complete("priority>=h"), vec!["priority>=high"]);
+    assert_eq!(complete("").len(), Field::ALL.len());
+    assert!(complete("colour:").is_empty());
+    assert!(complete("status:open").is_empty());
+}
@@ -1,4 +1,6 @@
+use crate::user::Role;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 /// Email registration form data.
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,3 +45,31 @@ impl ResponseDTO {
         self.username.is_none() && self.password.is_none() && self.other.is_none()
     }
 }
+
+/// Invitation form data, used by agents and administrators to invite a new user.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvitationDTO<'r> {
+    pub email: &'r str,
+    pub role: Role,
+    /// Organisation the invited user will be a member of.
+    #[serde(default)]
+    pub organisation_id: Option<Uuid>,
+}
+
+/// Pending invitation, as listed to agents and administrators.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingInvitationDTO {
+    pub code: String,
+    pub email: String,
+    pub role: Role,
+    pub organisation_id: Option<Uuid>,
+    pub invited_by: String,
+}
+
+/// Information about a registration code, used to pre-fill the registration form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeInfoDTO {
+    pub email: String,
+    /// Whether the code comes from an invitation.
+    pub invited: bool,
+}
@@ -0,0 +1,93 @@
+//! Reports for supervisors.
+//!
+//! Reports aggregate a [metric](Metric) of the tickets by [period](Period), in a timezone, and
+//! optionally by a [dimension](Dimension) such as the team or the agent.
+
+use crate::ticket::Priority;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Metric of a report.
+    pub enum Metric {
+        /// Number of unresolved tickets at the end of each period.
+        Backlog => "backlog",
+        /// Number of tickets opened in each period.
+        Volume => "volume",
+        /// Average minutes to the first response of the staff, for the tickets opened in each
+        /// period.
+        FirstResponse => "first_response",
+        /// Average minutes to the resolution, for the tickets resolved in each period.
+        Resolution => "resolution",
+        /// Percentage of the tickets resolved in each period that met the targets of the SLA
+        /// policy of their priority.
+        SlaCompliance => "sla_compliance",
+        /// Percentage of good ratings among the surveys answered in each period.
+        Csat => "csat",
+    }
+}
+
+string_enum! {
+    /// What the tickets of a report are grouped by, besides the period.
+    pub enum Dimension {
+        Total => "total",
+        /// Team of the queue of the tickets.
+        Team => "team",
+        /// Assignee of the tickets, or the agent rated by the surveys.
+        Agent => "agent",
+        Category => "category",
+    }
+}
+
+string_enum! {
+    /// Length of the periods of a report.
+    pub enum Period {
+        Day => "day",
+        Week => "week",
+        Month => "month",
+    }
+}
+
+/// Value of a report for a period and group, sent from the server to agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportRowDTO {
+    /// First day of the period, in the timezone of the report.
+    pub period: NaiveDate,
+    /// ID of the team or agent of the group, `None` for other dimensions or tickets without one.
+    pub group_id: Option<Uuid>,
+    /// Name of the group, `None` for the total or tickets without one.
+    pub group: Option<String>,
+    /// Value of the metric, `None` if there was nothing to average.
+    pub value: Option<f64>,
+    /// Number of tickets or surveys the value is computed from.
+    pub count: i64,
+}
+
+/// Report sent from the server to agents, ready to be charted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportDTO {
+    pub metric: Metric,
+    pub by: Dimension,
+    pub period: Period,
+    pub timezone: String,
+    /// Values ordered by period and group name.
+    pub rows: Vec<ReportRowDTO>,
+}
+
+/// Service level targets of a ticket priority.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlaPolicyDTO {
+    pub priority: Priority,
+    /// Minutes from the creation of a ticket to the first response of the staff.
+    pub response_minutes: i32,
+    /// Minutes from the creation of a ticket to its resolution.
+    pub resolution_minutes: i32,
+}
+
+/// New service level targets of a ticket priority, sent by administrators.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaPolicyFormDTO {
+    pub response_minutes: i32,
+    pub resolution_minutes: i32,
+}
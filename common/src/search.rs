@@ -0,0 +1,34 @@
+//! Full-text search.
+
+use crate::ticket::{Priority, Status};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Piece of a highlighted text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentDTO {
+    pub text: String,
+    /// Whether the piece matches the search.
+    pub highlighted: bool,
+}
+
+/// Ticket matching a search, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TicketHitDTO {
+    pub id: Uuid,
+    /// Human readable number of the ticket.
+    pub number: i64,
+    /// Title of the ticket, with the matching words highlighted.
+    pub title: Vec<FragmentDTO>,
+    /// Excerpt of the description of the ticket, or of its best matching comment if only the
+    /// comment matches, with the matching words highlighted.
+    pub snippet: Vec<FragmentDTO>,
+    pub status: Status,
+    pub priority: Priority,
+    pub assignee_id: Option<Uuid>,
+    pub organisation_id: Option<Uuid>,
+    pub created_on: DateTime<Utc>,
+    /// Relevance of the ticket for the search, higher is better.
+    pub rank: f32,
+}
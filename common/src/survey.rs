@@ -0,0 +1,48 @@
+//! Customer satisfaction surveys.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Rating of the resolution of a ticket by its requester.
+    pub enum Rating {
+        Good => "good",
+        Bad => "bad",
+    }
+}
+
+/// Survey an email link is for, sent from the server to the requester.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurveyDTO {
+    pub number: i64,
+    pub title: String,
+    /// Rating given so far, if any.
+    pub rating: Option<Rating>,
+    pub comment: String,
+}
+
+/// Answer to a survey, sent by the requester from the link of the survey email.
+///
+/// Requesters can change their answer, for example to add a comment after the one-click rating.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurveyAnswerDTO {
+    pub rating: Rating,
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// Survey of a ticket, with its answer if any, sent from the server to agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurveyResultDTO {
+    pub id: Uuid,
+    pub ticket_id: Uuid,
+    /// Agent assigned to the ticket when it was resolved.
+    pub agent_id: Option<Uuid>,
+    pub rating: Option<Rating>,
+    pub comment: String,
+    /// When the survey was emailed, `None` while it's waiting to be sent.
+    pub sent_on: Option<DateTime<Utc>>,
+    pub answered_on: Option<DateTime<Utc>>,
+    pub created_on: DateTime<Utc>,
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Strategy used to assign the tickets entering a queue.
+    pub enum Strategy {
+        /// Tickets are not assigned automatically.
+        Manual => "manual",
+        /// Tickets are assigned to each member of the team in turn.
+        RoundRobin => "round_robin",
+        /// Tickets are assigned to the member of the team with the fewest active tickets.
+        LeastLoaded => "least_loaded",
+        /// Tickets are assigned to the least loaded member of the team having all the skills
+        /// required by the queue.
+        SkillsBased => "skills_based",
+    }
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Self::Manual
+    }
+}
+
+/// Team of agents, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub members: Vec<TeamMemberDTO>,
+}
+
+/// Team form data, used by administrators to create or rename teams.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamFormDTO<'r> {
+    pub name: &'r str,
+}
+
+/// Member of a team.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamMemberDTO {
+    pub user_id: Uuid,
+    pub username: String,
+    pub skills: Vec<String>,
+}
+
+/// Team membership form data, used to add an agent to a team or to change their skills.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMemberFormDTO {
+    pub user_id: Uuid,
+    #[serde(default)]
+    pub skills: Vec<String>,
+}
+
+/// Queue of tickets, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueDTO {
+    pub id: Uuid,
+    pub name: String,
+    pub team_id: Uuid,
+    pub strategy: Strategy,
+    /// Skills required to be assigned tickets, with the skills-based strategy.
+    pub skills: Vec<String>,
+    /// Whether new tickets without a queue enter this queue.
+    pub is_default: bool,
+}
+
+/// Queue form data, used by administrators to create or update queues.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueueFormDTO<'r> {
+    pub name: &'r str,
+    pub team_id: Uuid,
+    #[serde(default)]
+    pub strategy: Strategy,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+/// Change in the assignment of a ticket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentDTO {
+    pub id: i64,
+    pub ticket_id: Uuid,
+    pub previous_assignee_id: Option<Uuid>,
+    pub assignee_id: Option<Uuid>,
+    /// Queue of the ticket when it was assigned.
+    pub queue_id: Option<Uuid>,
+    /// Strategy used, for automatic assignments.
+    pub strategy: Option<Strategy>,
+    /// User that changed the assignment, for manual assignments.
+    pub assigned_by: Option<Uuid>,
+    pub created_on: DateTime<Utc>,
+}
@@ -0,0 +1,257 @@
+use crate::custom_field::Values;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Status of a ticket.
+    pub enum Status {
+        /// The ticket has not been handled yet.
+        New => "new",
+        /// The ticket is being worked on.
+        Open => "open",
+        /// The ticket is waiting for the customer or a third party.
+        Pending => "pending",
+        /// The ticket has been resolved.
+        Resolved => "resolved",
+        /// The ticket is closed, and can't be reopened.
+        Closed => "closed",
+    }
+}
+
+impl Status {
+    /// Checks if the ticket still requires work from the support team.
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::New | Self::Open | Self::Pending)
+    }
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::New
+    }
+}
+
+string_enum! {
+    /// Priority of a ticket.
+    ///
+    /// Priorities are ordered, so `Priority::Low < Priority::Urgent`.
+    pub enum Priority {
+        Low => "low",
+        Normal => "normal",
+        High => "high",
+        Urgent => "urgent",
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// Ticket, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TicketDTO {
+    pub id: Uuid,
+    /// Human readable number of the ticket.
+    pub number: i64,
+    pub title: String,
+    pub description: String,
+    pub status: Status,
+    pub priority: Priority,
+    pub requester_id: Uuid,
+    pub assignee_id: Option<Uuid>,
+    pub organisation_id: Option<Uuid>,
+    pub queue_id: Option<Uuid>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    /// Values of the custom fields of the ticket.
+    pub custom_fields: Values,
+    /// Ticket this ticket was merged into, if it was.
+    pub merged_into_id: Option<Uuid>,
+    /// Parent ticket, if the ticket is a task of another one.
+    pub parent_id: Option<Uuid>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
+
+/// Data Transfer Object used from the client to open a new ticket.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewTicketDTO<'r> {
+    pub title: &'r str,
+    pub description: &'r str,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default, borrow)]
+    pub category: Option<&'r str>,
+    /// Organisation of the ticket.
+    ///
+    /// If not provided, the organisation of the requester will be used, if there is only one.
+    #[serde(default)]
+    pub organisation_id: Option<Uuid>,
+    /// Queue of the ticket.
+    ///
+    /// If not provided, the ticket will enter the default queue, if there is one.
+    #[serde(default)]
+    pub queue_id: Option<Uuid>,
+    /// Values of the custom fields of the category of the ticket.
+    #[serde(default)]
+    pub custom_fields: Values,
+}
+
+/// Data Transfer Object used from the client to update a ticket.
+///
+/// Only the provided fields will be updated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TicketUpdateDTO {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// New assignee of the ticket. `Some(None)` removes the assignee.
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub assignee_id: Option<Option<Uuid>>,
+    /// New queue of the ticket. `Some(None)` removes the ticket from its queue.
+    ///
+    /// Unless an assignee is also provided, the ticket will be assigned following the strategy
+    /// of the new queue.
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub queue_id: Option<Option<Uuid>>,
+    /// New category of the ticket. `Some(None)` removes the category.
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub category: Option<Option<String>>,
+    /// New tags of the ticket, replacing the current ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// New values of the custom fields, replacing the current ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_fields: Option<Values>,
+}
+
+string_enum! {
+    /// Channel a ticket comment was received from.
+    pub enum CommentSource {
+        /// The comment was written in the application.
+        Web => "web",
+        /// The comment was sent by email.
+        Email => "email",
+    }
+}
+
+/// Comment of a ticket, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommentDTO {
+    pub id: Uuid,
+    pub ticket_id: Uuid,
+    /// Author of the comment, if the user still exists.
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub source: CommentSource,
+    pub attachments: Vec<AttachmentDTO>,
+    pub created_on: DateTime<Utc>,
+}
+
+/// New comment form data, sent from the client to the server.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewCommentDTO<'r> {
+    pub body: &'r str,
+}
+
+/// Attachment of a ticket, without its contents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentDTO {
+    pub id: Uuid,
+    /// Comment the attachment was sent with, if any.
+    pub comment_id: Option<Uuid>,
+    pub filename: String,
+    pub content_type: String,
+    /// Size of the attachment, in bytes.
+    pub size: i32,
+}
+
+string_enum! {
+    /// Kind of link between two tickets, seen from one of them.
+    pub enum LinkKind {
+        /// The tickets are about related issues.
+        Related => "related",
+        /// The ticket is a duplicate of the linked one.
+        DuplicateOf => "duplicate_of",
+        /// The linked ticket is a duplicate of this one.
+        DuplicatedBy => "duplicated_by",
+    }
+}
+
+impl LinkKind {
+    /// Gets the kind of the link seen from the linked ticket.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Related => Self::Related,
+            Self::DuplicateOf => Self::DuplicatedBy,
+            Self::DuplicatedBy => Self::DuplicateOf,
+        }
+    }
+}
+
+/// Link of a ticket to another one, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TicketLinkDTO {
+    /// Linked ticket.
+    pub ticket: TicketDTO,
+    pub kind: LinkKind,
+    pub created_by: Uuid,
+    pub created_on: DateTime<Utc>,
+}
+
+/// Link form data, sent from the client to link a ticket to another one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TicketLinkFormDTO {
+    pub ticket_id: Uuid,
+    pub kind: LinkKind,
+}
+
+/// Merge form data, sent from the client to merge a ticket into another one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MergeDTO {
+    /// Ticket surviving the merge.
+    pub into_id: Uuid,
+}
+
+/// Split form data, sent from the client to split a comment out into a new ticket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitDTO {
+    /// Title of the new ticket.
+    pub title: String,
+}
+
+/// Parent form data, sent from the client to make a ticket a task of another one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ParentDTO {
+    /// Parent ticket, or `None` to detach the ticket from its parent.
+    pub parent_id: Option<Uuid>,
+}
+
+/// Deserializes a field that can be missing, `null` or have a value.
+///
+/// A missing field is `None` (thanks to `#[serde(default)]`), while `null` is `Some(None)`.
+pub(crate) fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Deserialize::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Role of a user in the application.
+    ///
+    /// Roles are ordered by privilege, so `Role::Customer < Role::Agent < Role::Admin`.
+    pub enum Role {
+        /// Customer requesting support.
+        Customer => "customer",
+        /// Support agent.
+        Agent => "agent",
+        /// Administrator of the application.
+        Admin => "admin",
+    }
+}
+
+impl Role {
+    /// Checks if the role belongs to the support staff (agents and administrators).
+    pub fn is_staff(self) -> bool {
+        self >= Self::Agent
+    }
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Self::Customer
+    }
+}
+
+/// Information about the logged in user, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserDTO {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    #[serde(rename = "fn")]
+    pub first_name: String,
+    #[serde(rename = "ln")]
+    pub last_name: String,
+    pub role: Role,
+}
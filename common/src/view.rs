@@ -0,0 +1,29 @@
+//! Saved ticket views.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Named ticket query, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewDTO {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    /// Query of the view, in the [query language](crate::query).
+    pub query: String,
+    /// Whether the view is listed for everyone, or only for its owner.
+    pub shared: bool,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
+
+/// View form data, used to create or update views.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ViewFormDTO<'r> {
+    pub name: &'r str,
+    pub query: &'r str,
+    /// Only agents and administrators can share views.
+    #[serde(default)]
+    pub shared: bool,
+}
@@ -0,0 +1,96 @@
+//! Outbound webhooks.
+//!
+//! Webhook subscriptions receive the ticket [`Event`]s in their filter as signed JSON payloads.
+
+use crate::ticket::TicketDTO;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+string_enum! {
+    /// Event sent to webhook subscriptions.
+    pub enum Event {
+        /// A ticket has been created.
+        TicketCreated => "ticket.created",
+        /// A ticket has been updated.
+        TicketUpdated => "ticket.updated",
+    }
+}
+
+string_enum! {
+    /// Status of a webhook delivery.
+    pub enum DeliveryStatus {
+        /// The delivery has not succeeded yet, and will be attempted again.
+        Pending => "pending",
+        /// The subscriber accepted the delivery.
+        Delivered => "delivered",
+        /// The delivery failed too many times, or its subscription was disabled.
+        Failed => "failed",
+    }
+}
+
+/// Payload sent to webhook subscriptions, as the body of a `POST` request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayloadDTO {
+    pub event: Event,
+    pub occurred_on: DateTime<Utc>,
+    /// Ticket after the event.
+    pub ticket: TicketDTO,
+}
+
+/// Webhook subscription, sent from the server to the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionDTO {
+    pub id: Uuid,
+    pub url: String,
+    /// Events sent to the subscription. All events are sent if empty.
+    pub events: Vec<Event>,
+    pub enabled: bool,
+    /// Number of failed delivery attempts since the last successful one.
+    pub consecutive_failures: i32,
+    /// Secret used to sign the payloads.
+    ///
+    /// It's only sent when the subscription is created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+/// Webhook subscription form data, used by administrators to create or update subscriptions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionFormDTO {
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    /// Secret used to sign the payloads.
+    ///
+    /// A random secret is generated for new subscriptions if not provided, and the current one is
+    /// kept when updating them.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Enabling a subscription resets its failure count.
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+}
+
+/// Subscriptions are enabled by default.
+fn enabled_default() -> bool {
+    true
+}
+
+/// Delivery of an event to a webhook subscription.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryDTO {
+    pub id: i64,
+    pub subscription_id: Uuid,
+    pub event: Event,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// Time of the next attempt, for pending deliveries.
+    pub next_attempt_on: DateTime<Utc>,
+    /// HTTP status code of the last response, if any.
+    pub response_code: Option<i16>,
+    /// Error of the last attempt, if any.
+    pub error: Option<String>,
+    pub created_on: DateTime<Utc>,
+    pub updated_on: DateTime<Utc>,
+}
@@ -0,0 +1,157 @@
+//! Time tracking.
+//!
+//! Agents log the time they spend on tickets. Billable work is charged to the organisation of the
+//! ticket, against the hours prepaid in its [contracts](ContractDTO), and totals of the time spent
+//! per organisation or agent are reported over a period.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum duration of a work log entry, in minutes.
+pub const MAX_MINUTES: i32 = 24 * 60;
+
+/// Maximum length of the description of a work log entry, in characters.
+pub const MAX_DESCRIPTION_LEN: usize = 2000;
+
+/// Maximum length of the name of a contract, in characters.
+pub const MAX_CONTRACT_NAME_LEN: usize = 200;
+
+string_enum! {
+    /// Kind of work done on a ticket.
+    pub enum Activity {
+        Support => "support",
+        Consulting => "consulting",
+        Development => "development",
+        Travel => "travel",
+        Meeting => "meeting",
+        Other => "other",
+    }
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self::Support
+    }
+}
+
+string_enum! {
+    /// What the totals of the time spent are grouped by.
+    pub enum Grouping {
+        Organisation => "organisation",
+        Agent => "agent",
+    }
+}
+
+/// Work log entry of a ticket, sent from the server to agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkLogDTO {
+    pub id: Uuid,
+    pub ticket_id: Uuid,
+    /// Agent that did the work.
+    pub agent_id: Uuid,
+    pub minutes: i32,
+    pub billable: bool,
+    pub activity: Activity,
+    pub description: String,
+    /// When the work was done.
+    pub worked_on: DateTime<Utc>,
+}
+
+/// Work log form data, used by agents to log their work on a ticket or to correct it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkLogFormDTO {
+    pub minutes: i32,
+    #[serde(default = "billable_default")]
+    pub billable: bool,
+    #[serde(default)]
+    pub activity: Activity,
+    #[serde(default)]
+    pub description: String,
+    /// When the work was done, now if not given.
+    #[serde(default)]
+    pub worked_on: Option<DateTime<Utc>>,
+}
+
+impl WorkLogFormDTO {
+    /// Checks that the entry is consistent, returning the reason if it isn't.
+    pub fn check(&self) -> Result<(), &'static str> {
+        if self.minutes <= 0 {
+            return Err("duration must be positive");
+        }
+        if self.minutes > MAX_MINUTES {
+            return Err("duration can't exceed a day");
+        }
+        if self.description.chars().count() > MAX_DESCRIPTION_LEN {
+            return Err("description is too long");
+        }
+
+        Ok(())
+    }
+}
+
+/// Work is billable unless stated otherwise.
+fn billable_default() -> bool {
+    true
+}
+
+/// Contract of an organisation, with the balance of its prepaid time, sent from the server to
+/// agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractDTO {
+    pub id: Uuid,
+    pub organisation_id: Uuid,
+    pub name: String,
+    pub prepaid_minutes: i32,
+    pub starts_on: DateTime<Utc>,
+    /// End of the contract, if it has one.
+    pub ends_on: Option<DateTime<Utc>>,
+    /// Billable minutes logged on the tickets of the organisation during the contract.
+    pub used_minutes: i64,
+    /// Prepaid minutes left, negative when the contract is overdrawn.
+    pub balance_minutes: i64,
+}
+
+/// Contract form data, used by administrators to create or update contracts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractFormDTO {
+    pub name: String,
+    pub prepaid_minutes: i32,
+    pub starts_on: DateTime<Utc>,
+    #[serde(default)]
+    pub ends_on: Option<DateTime<Utc>>,
+}
+
+impl ContractFormDTO {
+    /// Checks that the contract is consistent, returning the reason if it isn't.
+    pub fn check(&self) -> Result<(), &'static str> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("name can't be empty");
+        }
+        if name.chars().count() > MAX_CONTRACT_NAME_LEN {
+            return Err("name is too long");
+        }
+        if self.prepaid_minutes < 0 {
+            return Err("prepaid time can't be negative");
+        }
+        if matches!(self.ends_on, Some(ends_on) if ends_on <= self.starts_on) {
+            return Err("contract must end after it starts");
+        }
+
+        Ok(())
+    }
+}
+
+/// Time spent for an organisation or by an agent over a period, sent from the server to agents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeTotalDTO {
+    /// ID of the organisation or agent, `None` for the tickets without organisation.
+    pub id: Option<Uuid>,
+    pub name: Option<String>,
+    pub minutes: i64,
+    pub billable_minutes: i64,
+}
@@ -0,0 +1,71 @@
+use super::*;
+use chrono::Duration;
+
+/// Builds a work log form with the given duration and description.
+fn work_log(minutes: i32, description: &str) -> WorkLogFormDTO {
+    WorkLogFormDTO {
+        minutes,
+        billable: true,
+        activity: Activity::Support,
+        description: description.to_owned(),
+        worked_on: None,
+    }
+}
+
+/// Builds a contract form with the given name, prepaid time and end.
+fn contract(name: &str, prepaid_minutes: i32, ends_on: Option<DateTime<Utc>>) -> ContractFormDTO {
+    ContractFormDTO {
+        name: name.to_owned(),
+        prepaid_minutes,
+        starts_on: DateTime::parse_from_rfc3339("2022-08-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+        ends_on,
+    }
+}
+
+/// Consistent work log entries and contracts pass the checks, and work is billable by default.
+#[test]
+fn ut_sunny_check() {
+    assert_eq!(work_log(30, "Replaced the toner").check(), Ok(()));
+    assert_eq!(work_log(MAX_MINUTES, "").check(), Ok(()));
+
+    let form = serde_json::from_str::<WorkLogFormDTO>(r#"{"minutes":15}"#)
+        .expect("minimal form was not valid");
+    assert!(form.billable);
+    assert_eq!(form.activity, Activity::Support);
+
+    assert_eq!(contract("Gold support", 6000, None).check(), Ok(()));
+    let gold = contract("Gold support", 0, None);
+    let ends_on = Some(gold.starts_on + Duration::days(365));
+    assert_eq!(contract("Gold support", 0, ends_on).check(), Ok(()));
+}
+
+/// Work log entries are checked for their duration and description, and contracts for their name,
+/// prepaid time and period.
+#[test]
+fn ut_rainy_check() {
+    assert_eq!(work_log(0, "").check(), Err("duration must be positive"));
+    assert_eq!(
+        work_log(MAX_MINUTES + 1, "").check(),
+        Err("duration can't exceed a day")
+    );
+    assert_eq!(
+        work_log(30, &"a".repeat(MAX_DESCRIPTION_LEN + 1)).check(),
+        Err("description is too long")
+    );
+
+    assert_eq!(
+        contract("  ", 6000, None).check(),
+        Err("name can't be empty")
+    );
+    assert_eq!(
+        contract("Gold support", -1, None).check(),
+        Err("prepaid time can't be negative")
+    );
+    let starts_on = contract("Gold support", 6000, None).starts_on;
+    assert_eq!(
+        contract("Gold support", 6000, Some(starts_on)).check(),
+        Err("contract must end after it starts")
+    );
+}
@@ -0,0 +1,358 @@
+//! Approval components.
+//!
+//! Approvers decide on the tickets waiting for them either in the application, or from the link of
+//! their approval email, which works without logging in.
+
+use common::approval::{Decision, DecisionDTO, PendingApprovalDTO, Status};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+/// Approvals component messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The approvals waiting for the user have been loaded.
+    Loaded(Vec<PendingApprovalDTO>),
+    /// The comment of the decision changed.
+    Comment(String),
+    /// The user decided on the approval at the given position.
+    Decide(usize, Decision),
+    /// The decision on the approval at the given position has been recorded.
+    Decided(usize),
+    /// The decision could not be recorded.
+    Failed(String),
+}
+
+/// Approvals component, listing the approvals waiting for a decision of the logged in user.
+#[derive(Debug, Default)]
+pub struct Approvals {
+    pending: Vec<PendingApprovalDTO>,
+    comment: String,
+    error: Option<String>,
+}
+
+impl Component for Approvals {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            Msg::Loaded(
+                get_json::<Vec<_>>("/api/v1/approvals")
+                    .await
+                    .unwrap_or_default(),
+            )
+        });
+
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(pending) => {
+                self.pending = pending;
+
+                true
+            }
+            Msg::Comment(comment) => {
+                self.comment = comment;
+
+                false
+            }
+            Msg::Decide(index, decision) => {
+                let pending = match self.pending.get(index) {
+                    Some(pending) => pending,
+                    None => return false,
+                };
+                let url = format!("/api/v1/tickets/{}/approval", pending.ticket_id);
+                let body = decision_body(decision, &self.comment);
+                ctx.link().send_future(async move {
+                    match post_json(&url, body).await {
+                        Ok(()) => Msg::Decided(index),
+                        Err(e) => Msg::Failed(e),
+                    }
+                });
+
+                false
+            }
+            Msg::Decided(index) => {
+                if index < self.pending.len() {
+                    let _ = self.pending.remove(index);
+                }
+                self.comment.clear();
+                self.error = None;
+
+                true
+            }
+            Msg::Failed(e) => {
+                self.error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            Msg::Comment(e.target_unchecked_into::<HtmlTextAreaElement>().value())
+        });
+
+        html! {
+            <main class="container">
+                <h1>{"Approvals"}</h1>
+                { error(&self.error) }
+                {
+                    if self.pending.is_empty() {
+                        html! { <p>{"No tickets are waiting for your approval."}</p> }
+                    } else {
+                        html! {
+                            <>
+                                <textarea class="form-control mb-3" placeholder="Comment"
+                                    value={self.comment.clone()} {oninput} />
+                                <div class="list-group">
+                                    {
+                                        self.pending
+                                            .iter()
+                                            .enumerate()
+                                            .map(|(index, pending)| self.item(ctx, index, pending))
+                                            .collect::<Html>()
+                                    }
+                                </div>
+                            </>
+                        }
+                    }
+                }
+            </main>
+        }
+    }
+}
+
+impl Approvals {
+    /// Renders an approval of the list.
+    fn item(&self, ctx: &Context<Self>, index: usize, pending: &PendingApprovalDTO) -> Html {
+        let approve = ctx
+            .link()
+            .callback(move |_| Msg::Decide(index, Decision::Approved));
+        let reject = ctx
+            .link()
+            .callback(move |_| Msg::Decide(index, Decision::Rejected));
+
+        html! {
+            <div class="list-group-item">
+                { summary(pending) }
+                <button class="btn btn-success me-2" onclick={approve}>{"Approve"}</button>
+                <button class="btn btn-danger" onclick={reject}>{"Reject"}</button>
+            </div>
+        }
+    }
+}
+
+/// Approval link properties.
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ApprovalLinkProps {
+    /// Token of the approval email.
+    pub token: String,
+}
+
+/// Approval link component messages.
+#[derive(Debug)]
+pub enum LinkMsg {
+    /// The approval of the link has been loaded, if it's still waiting for a decision.
+    Loaded(Option<PendingApprovalDTO>),
+    /// The comment of the decision changed.
+    Comment(String),
+    /// The approver decided.
+    Decide(Decision),
+    /// The decision has been recorded, with the resulting status of the approval.
+    Decided(Status),
+    /// The decision could not be recorded.
+    Failed(String),
+}
+
+/// Approval link component, where approvers land from their approval email.
+#[derive(Debug, Default)]
+pub struct ApprovalLink {
+    loaded: bool,
+    pending: Option<PendingApprovalDTO>,
+    comment: String,
+    status: Option<Status>,
+    error: Option<String>,
+}
+
+impl Component for ApprovalLink {
+    type Message = LinkMsg;
+    type Properties = ApprovalLinkProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let url = format!("/api/v1/approvals/{}", ctx.props().token);
+        ctx.link()
+            .send_future(async move { LinkMsg::Loaded(get_json(&url).await) });
+
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            LinkMsg::Loaded(pending) => {
+                self.loaded = true;
+                self.pending = pending;
+
+                true
+            }
+            LinkMsg::Comment(comment) => {
+                self.comment = comment;
+
+                false
+            }
+            LinkMsg::Decide(decision) => {
+                let url = format!("/api/v1/approvals/{}", ctx.props().token);
+                let body = decision_body(decision, &self.comment);
+                ctx.link().send_future(async move {
+                    let response = Request::post(&url)
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    match response {
+                        Ok(response) => match response.json::<Result<Status, String>>().await {
+                            Ok(Ok(status)) => LinkMsg::Decided(status),
+                            Ok(Err(e)) => LinkMsg::Failed(e),
+                            Err(_) => LinkMsg::Failed("The decision could not be sent.".to_owned()),
+                        },
+                        Err(_) => LinkMsg::Failed("The server could not be reached.".to_owned()),
+                    }
+                });
+
+                false
+            }
+            LinkMsg::Decided(status) => {
+                self.status = Some(status);
+                self.error = None;
+
+                true
+            }
+            LinkMsg::Failed(e) => {
+                self.error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if !self.loaded {
+            return html! {};
+        }
+        let pending = match &self.pending {
+            Some(pending) => pending,
+            None => {
+                return html! {
+                    <main class="container">
+                        <h1>{"Approval"}</h1>
+                        <p class="alert alert-warning">
+                            {"This link has expired or has already been used."}
+                        </p>
+                    </main>
+                };
+            }
+        };
+        if let Some(status) = self.status {
+            let message = match status {
+                Status::Pending => "Your decision has been recorded.",
+                Status::Approved => "Your decision has been recorded, and the ticket is approved.",
+                Status::Rejected => "Your decision has been recorded, and the ticket is rejected.",
+            };
+            return html! {
+                <main class="container">
+                    <h1>{"Approval"}</h1>
+                    <p class="alert alert-success">{message}</p>
+                </main>
+            };
+        }
+
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            LinkMsg::Comment(e.target_unchecked_into::<HtmlTextAreaElement>().value())
+        });
+        let approve = ctx.link().callback(|_| LinkMsg::Decide(Decision::Approved));
+        let reject = ctx.link().callback(|_| LinkMsg::Decide(Decision::Rejected));
+
+        html! {
+            <main class="container">
+                <h1>{"Approval"}</h1>
+                { error(&self.error) }
+                { summary(pending) }
+                <textarea class="form-control mb-3" placeholder="Comment"
+                    value={self.comment.clone()} {oninput} />
+                <button class="btn btn-success me-2" onclick={approve}>{"Approve"}</button>
+                <button class="btn btn-danger" onclick={reject}>{"Reject"}</button>
+            </main>
+        }
+    }
+}
+
+/// Renders the ticket an approval is about.
+fn summary(pending: &PendingApprovalDTO) -> Html {
+    html! {
+        <>
+            <h5>{format!("#{} {}", pending.number, pending.title)}</h5>
+            <p class="text-muted mb-1">
+                {format!("Step {}, requested on {}", pending.step + 1, pending.requested_on.format("%Y-%m-%d %H:%M"))}
+            </p>
+            <p style="white-space: pre-line">{&pending.description}</p>
+        </>
+    }
+}
+
+/// Renders an error message, if any.
+fn error(error: &Option<String>) -> Html {
+    match error {
+        Some(e) => html! { <p class="alert alert-danger">{e}</p> },
+        None => html! {},
+    }
+}
+
+/// Serializes a decision with its trimmed comment.
+fn decision_body(decision: Decision, comment: &str) -> String {
+    to_string(&DecisionDTO {
+        decision,
+        comment: comment.trim().to_owned(),
+    })
+    .expect("error serializing a decision")
+}
+
+/// Posts a JSON body, returning the error message of the server if it failed.
+async fn post_json(url: &str, body: String) -> Result<(), String> {
+    let response = Request::post(url)
+        .header("Accept", "application/json")
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|_| "The server could not be reached.".to_owned())?;
+    if response.ok() {
+        return Ok(());
+    }
+
+    Err(response
+        .json::<Result<serde_json::Value, String>>()
+        .await
+        .ok()
+        .and_then(Result::err)
+        .unwrap_or_else(|| "The decision could not be sent.".to_owned()))
+}
+
+/// Gets a JSON resource, returning `None` if the request failed.
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+    let response = Request::get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    response.json().await.ok()
+}
@@ -0,0 +1,566 @@
+//! Ticket board component.
+//!
+//! Agents drag the cards of the tickets between the columns of the board, which updates the status
+//! or the assignee of the tickets. Moves show at once and are undone if the server rejects them.
+//! The board is reloaded whenever ticket activity is received from the server-sent event stream,
+//! so that the moves of the other agents show up live.
+
+use crate::router::Route;
+use common::{
+    board::{BoardDTO, ColumnDTO, ColumnSettingsDTO, Grouping, MAX_WIP_LIMIT},
+    ticket::TicketDTO,
+    user::{Role, UserDTO},
+};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use std::collections::HashMap;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{EventSource, HtmlInputElement, HtmlSelectElement, MessageEvent, UrlSearchParams};
+use yew::prelude::*;
+use yew_router::components::Link;
+
+/// Component messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The board has been loaded, or the user is not an agent.
+    Loaded(Option<BoardDTO>),
+    /// The logged in user has been loaded.
+    User(Option<UserDTO>),
+    /// The tickets should be grouped differently.
+    Group(Grouping),
+    /// The search query narrowing down the tickets changed.
+    Query(String),
+    /// The user wants to apply the search query.
+    Search,
+    /// Ticket activity has been received, so the board should be reloaded.
+    Activity,
+    /// The card of the ticket with the given ID started being dragged.
+    Drag(String),
+    /// The dragged card was dropped on the column with the given key.
+    Drop(String),
+    /// The move of the ticket with the given ID was saved, or the reason why it wasn't.
+    Moved(String, Result<TicketDTO, String>),
+    /// The WIP limit of the column with the given key changed.
+    Limit(String, Option<i32>),
+    /// The WIP limit has been saved, or the reason why it wasn't.
+    LimitSaved(Result<ColumnSettingsDTO, String>),
+}
+
+/// Move of a ticket waiting for the server.
+#[derive(Debug)]
+struct Move {
+    grouping: Grouping,
+    /// Key of the column the ticket was moved from.
+    from: String,
+    /// Key of the column the ticket was moved to.
+    to: String,
+    /// The ticket before the move, restored if the move fails.
+    ticket: TicketDTO,
+}
+
+/// Ticket board component.
+pub struct Board {
+    grouping: Grouping,
+    query: String,
+    loaded: bool,
+    board: Option<BoardDTO>,
+    admin: bool,
+    /// Whether the board is being loaded.
+    loading: bool,
+    /// Whether the board should be loaded again once it's loaded, as it's already outdated.
+    stale: bool,
+    /// ID of the ticket of the card being dragged.
+    dragging: Option<String>,
+    /// Moves waiting for the server, by ticket ID.
+    pending: HashMap<String, Move>,
+    error: Option<String>,
+    source: Option<EventSource>,
+    /// Event listener of the stream, which must live as long as it.
+    _listener: Option<Closure<dyn FnMut(MessageEvent)>>,
+}
+
+impl Component for Board {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(async { Msg::User(get_json("/api/v1/login").await) });
+
+        let mut board = Self {
+            grouping: Grouping::default(),
+            query: String::new(),
+            loaded: false,
+            board: None,
+            admin: false,
+            loading: false,
+            stale: false,
+            dragging: None,
+            pending: HashMap::new(),
+            error: None,
+            source: None,
+            _listener: None,
+        };
+        board.load(ctx);
+
+        board
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(board) => {
+                self.loading = false;
+                self.loaded = true;
+                if board.is_some() && self.source.is_none() {
+                    self.connect(ctx);
+                }
+                self.board = board;
+                // Moves the server didn't answer yet are shown over the loaded board
+                if let Some(board) = &mut self.board {
+                    for (id, pending) in &self.pending {
+                        if pending.grouping == board.grouping {
+                            if let Some(mut ticket) = take_ticket(board, id) {
+                                board.grouping.apply(&pending.to, &mut ticket);
+                                put_ticket(board, &pending.to, ticket);
+                            }
+                        }
+                    }
+                }
+                if self.stale {
+                    self.stale = false;
+                    self.load(ctx);
+                }
+
+                true
+            }
+            Msg::User(user) => {
+                self.admin = matches!(user, Some(user) if user.role == Role::Admin);
+
+                true
+            }
+            Msg::Group(grouping) => {
+                self.grouping = grouping;
+                self.load(ctx);
+
+                false
+            }
+            Msg::Query(query) => {
+                self.query = query;
+
+                false
+            }
+            Msg::Search | Msg::Activity => {
+                self.load(ctx);
+
+                false
+            }
+            Msg::Drag(id) => {
+                self.dragging = Some(id);
+
+                false
+            }
+            Msg::Drop(key) => {
+                let (id, board) = match (self.dragging.take(), &mut self.board) {
+                    (Some(id), Some(board)) => (id, board),
+                    _ => return false,
+                };
+                let from = match board.columns.iter().find(|column| has_ticket(column, &id)) {
+                    Some(from) if from.key != key => from.key.clone(),
+                    _ => return false,
+                };
+                let (to, update) = match (
+                    board.columns.iter().find(|column| column.key == key),
+                    board.grouping.update(&key),
+                ) {
+                    (Some(to), Some(update)) => (to, update),
+                    _ => return false,
+                };
+                if to.is_full() {
+                    self.error = Some(format!("{} is at its WIP limit.", to.title));
+                    return true;
+                }
+
+                let ticket = match take_ticket(board, &id) {
+                    Some(ticket) => ticket,
+                    None => return false,
+                };
+                let mut moved = ticket.clone();
+                board.grouping.apply(&key, &mut moved);
+                put_ticket(board, &key, moved);
+                let _ = self.pending.insert(
+                    id.clone(),
+                    Move {
+                        grouping: board.grouping,
+                        from,
+                        to: key,
+                        ticket,
+                    },
+                );
+                self.error = None;
+
+                let url = format!("/api/v1/tickets/{}", id);
+                let body = to_string(&update).expect("could not serialize ticket update to JSON");
+                ctx.link().send_future(async move {
+                    let response = Request::patch(&url)
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    let moved = match response {
+                        Ok(response) => response
+                            .json()
+                            .await
+                            .unwrap_or_else(|_| Err("The ticket could not be moved.".to_owned())),
+                        Err(_) => Err("The server could not be reached.".to_owned()),
+                    };
+                    Msg::Moved(id, moved)
+                });
+
+                true
+            }
+            Msg::Moved(id, Ok(ticket)) => {
+                let _ = self.pending.remove(&id);
+                if let Some(board) = &mut self.board {
+                    if take_ticket(board, &id).is_some() {
+                        put_ticket(board, &board.grouping.key(&ticket), ticket);
+                    }
+                }
+
+                true
+            }
+            Msg::Moved(id, Err(e)) => {
+                let pending = match self.pending.remove(&id) {
+                    Some(pending) => pending,
+                    None => return false,
+                };
+                self.error = Some(format!(
+                    "#{} could not be moved: {}",
+                    pending.ticket.number, e
+                ));
+                if let Some(board) = &mut self.board {
+                    if board.grouping == pending.grouping && take_ticket(board, &id).is_some() {
+                        put_ticket(board, &pending.from, pending.ticket);
+                    }
+                }
+
+                true
+            }
+            Msg::Limit(key, wip_limit) => {
+                let board = match &mut self.board {
+                    Some(board) => board,
+                    None => return false,
+                };
+                if let Some(column) = board.columns.iter_mut().find(|column| column.key == key) {
+                    column.wip_limit = wip_limit;
+                }
+
+                let url = format!("/api/v1/board/{}/columns/{}", board.grouping, key);
+                let body = to_string(&ColumnSettingsDTO { wip_limit })
+                    .expect("could not serialize column settings DTO to JSON");
+                ctx.link().send_future(async move {
+                    let response = Request::put(&url)
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    let saved = match response {
+                        Ok(response) => response.json().await.unwrap_or_else(|_| {
+                            Err("The WIP limit could not be saved.".to_owned())
+                        }),
+                        Err(_) => Err("The server could not be reached.".to_owned()),
+                    };
+                    Msg::LimitSaved(saved)
+                });
+
+                true
+            }
+            Msg::LimitSaved(Ok(_)) => false,
+            Msg::LimitSaved(Err(e)) => {
+                self.error = Some(e);
+                self.load(ctx);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let board = match &self.board {
+            Some(board) => board,
+            None if self.loaded => {
+                return html! {
+                    <main class="container"><p>{"Only agents have a ticket board."}</p></main>
+                }
+            }
+            None => return html! { <main class="container"><p>{"Loading…"}</p></main> },
+        };
+
+        html! {
+            <main class="container-fluid">
+                <div class="d-flex justify-content-between align-items-center mb-3">
+                    <h1>{"Board"}</h1>
+                    { self.toolbar(ctx) }
+                </div>
+                {
+                    match &self.error {
+                        Some(e) => html! { <div class="alert alert-danger">{e}</div> },
+                        None => html! {},
+                    }
+                }
+                {
+                    if board.truncated {
+                        html! {
+                            <div class="alert alert-warning">
+                                {"Only the newest tickets are shown, filter the board to see the others."}
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div class="d-flex align-items-start overflow-auto">
+                    { board.columns.iter().map(|column| self.column(ctx, column)).collect::<Html>() }
+                </div>
+            </main>
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        if let Some(source) = self.source.take() {
+            source.close();
+        }
+    }
+}
+
+impl Board {
+    /// Loads the board, or loads it again once it's loaded if it's already being loaded.
+    fn load(&mut self, ctx: &Context<Self>) {
+        if self.loading {
+            self.stale = true;
+            return;
+        }
+
+        self.loading = true;
+        let (grouping, query) = (self.grouping, self.query.clone());
+        ctx.link()
+            .send_future(async move { Msg::Loaded(load(grouping, &query).await) });
+    }
+
+    /// Renders the grouping and search query of the board.
+    fn toolbar(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().batch_callback(|e: Event| {
+            let target = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+            Some(Msg::Group(target.value().parse().ok()?))
+        });
+        let oninput = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(Msg::Query(target.value()))
+        });
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            Msg::Search
+        });
+
+        html! {
+            <form class="d-flex" {onsubmit}>
+                <select class="form-select me-2" aria-label="Group by" {onchange}>
+                    {
+                        Grouping::ALL.iter().map(|&grouping| html! {
+                            <option value={grouping.as_str()} selected={grouping == self.grouping}>
+                                { match grouping {
+                                    Grouping::Status => "By status",
+                                    Grouping::Assignee => "By assignee",
+                                } }
+                            </option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <input type="search" class="form-control me-2" placeholder="queue:none priority:>=high"
+                    aria-label="Filter" value={self.query.clone()} {oninput} />
+                <button type="submit" class="btn btn-outline-secondary">{"Filter"}</button>
+            </form>
+        }
+    }
+
+    /// Renders a column of the board, with its cards.
+    fn column(&self, ctx: &Context<Self>, column: &ColumnDTO) -> Html {
+        let key = column.key.clone();
+        let ondrop = ctx.link().callback(move |e: DragEvent| {
+            e.prevent_default();
+            Msg::Drop(key.clone())
+        });
+        // Columns only accept drops if dragging over them is cancelled
+        let ondragover = Callback::from(|e: DragEvent| e.prevent_default());
+        let badge = classes!(
+            "badge",
+            if column.is_over_limit() {
+                "bg-danger"
+            } else if column.is_full() {
+                "bg-warning"
+            } else {
+                "bg-secondary"
+            }
+        );
+        let count = match column.wip_limit {
+            Some(limit) => format!("{} / {}", column.tickets.len(), limit),
+            None => column.tickets.len().to_string(),
+        };
+
+        html! {
+            <section class="card bg-light me-3 flex-shrink-0" style="width: 18rem" {ondrop} {ondragover}>
+                <header class="card-header d-flex justify-content-between align-items-center">
+                    <span>{&column.title}</span>
+                    <span class={badge}>{count}</span>
+                </header>
+                <div class="card-body p-2" style="min-height: 8rem">
+                    {
+                        if self.admin && self.grouping.limits(&column.key) {
+                            self.limit_input(ctx, column)
+                        } else {
+                            html! {}
+                        }
+                    }
+                    { column.tickets.iter().map(|ticket| self.card(ctx, ticket)).collect::<Html>() }
+                </div>
+            </section>
+        }
+    }
+
+    /// Renders the input of the WIP limit of a column, for administrators.
+    fn limit_input(&self, ctx: &Context<Self>, column: &ColumnDTO) -> Html {
+        let key = column.key.clone();
+        let onchange = ctx.link().batch_callback(move |e: Event| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            let value = target.value();
+            let limit = if value.is_empty() {
+                None
+            } else {
+                Some(value.parse().ok()?)
+            };
+            Some(Msg::Limit(key.clone(), limit))
+        });
+        let value = column
+            .wip_limit
+            .map(|limit| limit.to_string())
+            .unwrap_or_default();
+
+        html! {
+            <input type="number" class="form-control form-control-sm mb-2" min="1"
+                max={MAX_WIP_LIMIT.to_string()} placeholder="WIP limit" aria-label="WIP limit"
+                {value} {onchange} />
+        }
+    }
+
+    /// Renders the draggable card of a ticket.
+    fn card(&self, ctx: &Context<Self>, ticket: &TicketDTO) -> Html {
+        let id = ticket.id.to_string();
+        let class = classes!(
+            "card",
+            "mb-2",
+            self.pending.contains_key(&id).then(|| "opacity-50")
+        );
+        let ondragstart = ctx.link().callback(move |e: DragEvent| {
+            // Some browsers only drag elements with data
+            if let Some(data) = e.data_transfer() {
+                let _ = data.set_data("text/plain", &id);
+            }
+            Msg::Drag(id.clone())
+        });
+
+        html! {
+            <div {class} draggable="true" {ondragstart}>
+                <div class="card-body p-2">
+                    <Link<Route> to={Route::Ticket { id: ticket.id.to_string() }}>
+                        {format!("#{} {}", ticket.number, ticket.title)}
+                    </Link<Route>>
+                    <div class="small text-muted">{format!("{}, {}", ticket.status, ticket.priority)}</div>
+                </div>
+            </div>
+        }
+    }
+
+    /// Connects to the ticket activity event stream.
+    fn connect(&mut self, ctx: &Context<Self>) {
+        let source = match EventSource::new("/api/v1/tickets/activity") {
+            Ok(source) => source,
+            Err(e) => {
+                gloo_console::error!("could not open the ticket activity stream", e);
+                return;
+            }
+        };
+
+        // Activity could have been missed while (re)connecting or falling behind
+        let link = ctx.link().clone();
+        let on_activity = Closure::wrap(Box::new(move |_e: MessageEvent| {
+            link.send_message(Msg::Activity);
+        }) as Box<dyn FnMut(MessageEvent)>);
+        for event in ["activity", "lagged", "open"] {
+            let _ = source
+                .add_event_listener_with_callback(event, on_activity.as_ref().unchecked_ref());
+        }
+
+        self.source = Some(source);
+        self._listener = Some(on_activity);
+    }
+}
+
+/// Checks if a column holds the ticket with the given ID.
+fn has_ticket(column: &ColumnDTO, id: &str) -> bool {
+    column
+        .tickets
+        .iter()
+        .any(|ticket| ticket.id.to_string() == id)
+}
+
+/// Removes the ticket with the given ID from its column, returning it.
+fn take_ticket(board: &mut BoardDTO, id: &str) -> Option<TicketDTO> {
+    let column = board
+        .columns
+        .iter_mut()
+        .find(|column| has_ticket(column, id))?;
+    let index = column
+        .tickets
+        .iter()
+        .position(|ticket| ticket.id.to_string() == id)?;
+
+    Some(column.tickets.remove(index))
+}
+
+/// Puts a ticket at the top of the column with the given key, if the board has it.
+fn put_ticket(board: &mut BoardDTO, key: &str, ticket: TicketDTO) {
+    if let Some(column) = board.columns.iter_mut().find(|column| column.key == key) {
+        column.tickets.insert(0, ticket);
+    }
+}
+
+/// Loads the board, returning `None` if the request failed.
+async fn load(grouping: Grouping, query: &str) -> Option<BoardDTO> {
+    let params = UrlSearchParams::new().ok()?;
+    params.append("group", grouping.as_str());
+    if !query.trim().is_empty() {
+        params.append("q", query);
+    }
+
+    get_json::<Result<_, String>>(&format!(
+        "/api/v1/board?{}",
+        String::from(params.to_string())
+    ))
+    .await?
+    .ok()
+}
+
+/// Gets a JSON resource, returning `None` if the request failed.
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+    let response = Request::get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    response.json().await.ok()
+}
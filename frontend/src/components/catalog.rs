@@ -0,0 +1,468 @@
+//! Service catalog components.
+//!
+//! The catalog lists the items that can be requested, and each item has a request form that is
+//! rendered from its definition. Answers are validated with the same rules as in the server
+//! before sending them.
+
+use crate::router::Route;
+use common::{
+    catalog::{
+        Answers, CatalogItemDTO, FieldDefinition, FieldError, FieldKind, FormDefinition,
+        PickableUserDTO, ServiceRequestFormDTO, Value,
+    },
+    ticket::{Status, TicketDTO},
+};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use std::collections::HashMap;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement, UrlSearchParams};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+/// Catalog component, listing the items that can be requested.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    items: Vec<CatalogItemDTO>,
+}
+
+impl Component for Catalog {
+    type Message = Vec<CatalogItemDTO>;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            get_json::<Vec<_>>("/api/v1/catalog")
+                .await
+                .unwrap_or_default()
+        });
+
+        Self::default()
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, items: Self::Message) -> bool {
+        self.items = items;
+
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <main class="container">
+                <h1>{"Service catalog"}</h1>
+                <div class="list-group">
+                    {
+                        self.items.iter().map(|item| html! {
+                            <Link<Route> classes="list-group-item list-group-item-action"
+                                to={Route::CatalogItem { id: item.id.to_string() }}>
+                                <h5>{&item.name}</h5>
+                                <p class="mb-0">{&item.description}</p>
+                            </Link<Route>>
+                        }).collect::<Html>()
+                    }
+                </div>
+            </main>
+        }
+    }
+}
+
+/// Request form properties.
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct RequestFormProps {
+    /// ID of the catalog item.
+    pub id: String,
+}
+
+/// Request form messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The catalog item has been loaded, if it exists.
+    Loaded(Option<CatalogItemDTO>),
+    /// The answer to a field changed.
+    Answer(String, Option<Value>),
+    /// An option of a multiple choice field was checked or unchecked.
+    Toggle(String, String, bool),
+    /// The text of a user field changed.
+    UserInput(String, String),
+    /// The users matching the text of a user field have been received.
+    Candidates(Vec<PickableUserDTO>),
+    /// The user wants to send the request.
+    Submit,
+    /// The ticket has been opened, or the errors of the answers.
+    Submitted(Result<TicketDTO, Vec<FieldError>>),
+    /// The request could not be sent.
+    Failed(String),
+}
+
+/// Request form component, rendering the form of a catalog item.
+#[derive(Debug, Default)]
+pub struct RequestForm {
+    item: Option<CatalogItemDTO>,
+    answers: Answers,
+    /// Texts of the user fields, by key.
+    user_texts: HashMap<String, String>,
+    candidates: Vec<PickableUserDTO>,
+    errors: Vec<FieldError>,
+    error: Option<String>,
+    ticket: Option<TicketDTO>,
+}
+
+impl Component for RequestForm {
+    type Message = Msg;
+    type Properties = RequestFormProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let url = format!("/api/v1/catalog/{}", ctx.props().id);
+        ctx.link()
+            .send_future(async move { Msg::Loaded(get_json(&url).await) });
+
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(item) => {
+                self.item = item;
+
+                true
+            }
+            Msg::Answer(key, Some(answer)) => {
+                let _ = self.answers.insert(key, answer);
+
+                true
+            }
+            Msg::Answer(key, None) => {
+                let _ = self.answers.remove(&key);
+
+                true
+            }
+            Msg::Toggle(key, option, checked) => {
+                let mut chosen = match self.answers.remove(&key) {
+                    Some(Value::List(chosen)) => chosen,
+                    _ => Vec::new(),
+                };
+                chosen.retain(|chosen| *chosen != option);
+                if checked {
+                    chosen.push(option);
+                }
+                if !chosen.is_empty() {
+                    let _ = self.answers.insert(key, Value::List(chosen));
+                }
+
+                true
+            }
+            Msg::UserInput(key, text) => {
+                // Picked users are answered with their ID, other texts are left to validation
+                let answer = match self.candidates.iter().find(|user| user.username == text) {
+                    Some(user) => user.id.to_string(),
+                    None => text.clone(),
+                };
+                let _ = self.answers.insert(key.clone(), Value::Text(answer));
+                let _ = self.user_texts.insert(key, text.clone());
+                ctx.link().send_future(async move {
+                    Msg::Candidates(pickable(&text).await.unwrap_or_default())
+                });
+
+                true
+            }
+            Msg::Candidates(candidates) => {
+                self.candidates = candidates;
+
+                true
+            }
+            Msg::Submit => {
+                let item = match &self.item {
+                    Some(item) => item,
+                    None => return false,
+                };
+                if let Err(errors) = item.form.validate(&self.answers) {
+                    self.errors = errors;
+                    return true;
+                }
+
+                let url = format!("/api/v1/catalog/{}/requests", item.id);
+                let body = to_string(&ServiceRequestFormDTO {
+                    answers: self.answers.clone(),
+                    organisation_id: None,
+                })
+                .expect("could not serialize service request form DTO to JSON");
+                ctx.link().send_future(async move {
+                    let response = Request::post(&url)
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    match response {
+                        Ok(response) => match response.json().await {
+                            Ok(submitted) => Msg::Submitted(submitted),
+                            Err(_) => Msg::Failed("The request could not be sent.".to_owned()),
+                        },
+                        Err(_) => Msg::Failed("The server could not be reached.".to_owned()),
+                    }
+                });
+
+                false
+            }
+            Msg::Submitted(Ok(ticket)) => {
+                self.ticket = Some(ticket);
+
+                true
+            }
+            Msg::Submitted(Err(errors)) => {
+                self.errors = errors;
+                self.error = None;
+
+                true
+            }
+            Msg::Failed(e) => {
+                self.error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let item = match &self.item {
+            Some(item) => item,
+            None => return html! { <main class="container"><p>{"Loading…"}</p></main> },
+        };
+        if let Some(ticket) = &self.ticket {
+            return html! {
+                <main class="container">
+                    <h1>{&item.name}</h1>
+                    <p class="alert alert-success">
+                        {format!("Your request has been sent as ticket #{}.", ticket.number)}
+                        {
+                            if ticket.status == Status::Pending {
+                                " It will be worked on once it's approved."
+                            } else {
+                                ""
+                            }
+                        }
+                    </p>
+                </main>
+            };
+        }
+
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            Msg::Submit
+        });
+
+        html! {
+            <main class="container">
+                <h1>{&item.name}</h1>
+                <p>{&item.description}</p>
+                <form {onsubmit}>
+                    {
+                        item.form
+                            .fields
+                            .iter()
+                            .filter(|field| FormDefinition::is_visible(field, &self.answers))
+                            .map(|field| self.field(ctx, field))
+                            .collect::<Html>()
+                    }
+                    <datalist id="pickable-users">
+                        {
+                            self.candidates.iter().map(|user| html! {
+                                <option value={user.username.clone()}>{&user.name}</option>
+                            }).collect::<Html>()
+                        }
+                    </datalist>
+                    <button type="submit" class="btn btn-primary">{"Send request"}</button>
+                    {
+                        match &self.error {
+                            Some(e) => html! { <div class="text-danger">{e}</div> },
+                            None => html! {},
+                        }
+                    }
+                </form>
+            </main>
+        }
+    }
+}
+
+impl RequestForm {
+    /// Renders a field of the form, with its validation error.
+    fn field(&self, ctx: &Context<Self>, field: &FieldDefinition) -> Html {
+        let id = format!("field-{}", field.key);
+        let error = self
+            .errors
+            .iter()
+            .find(|error| error.field == field.key)
+            .map(|error| error.message.clone());
+        let class = classes!(
+            if field.kind == FieldKind::Checkbox {
+                "form-check-input"
+            } else {
+                "form-control"
+            },
+            error.as_ref().map(|_| "is-invalid")
+        );
+        let text = match self.answers.get(&field.key) {
+            Some(Value::Text(text)) => text.clone(),
+            _ => String::new(),
+        };
+        let key = field.key.clone();
+
+        let input = match &field.kind {
+            FieldKind::Text {
+                multiline: true, ..
+            } => {
+                let oninput = ctx.link().batch_callback(move |e: InputEvent| {
+                    let target = e.target()?.dyn_into::<HtmlTextAreaElement>().ok()?;
+                    Some(Msg::Answer(key.clone(), Some(Value::Text(target.value()))))
+                });
+                html! { <textarea id={id.clone()} {class} value={text} {oninput} /> }
+            }
+            FieldKind::Text { .. } | FieldKind::Date => {
+                let kind = if field.kind == FieldKind::Date {
+                    "date"
+                } else {
+                    "text"
+                };
+                let oninput = ctx.link().batch_callback(move |e: InputEvent| {
+                    let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+                    Some(Msg::Answer(key.clone(), Some(Value::Text(target.value()))))
+                });
+                html! { <input type={kind} id={id.clone()} {class} value={text} {oninput} /> }
+            }
+            FieldKind::Select {
+                options,
+                multiple: false,
+            } => {
+                let onchange = ctx.link().batch_callback(move |e: Event| {
+                    let target = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+                    let value = target.value();
+                    Some(Msg::Answer(
+                        key.clone(),
+                        (!value.is_empty()).then(|| Value::Text(value)),
+                    ))
+                });
+                html! {
+                    <select id={id.clone()} {class} {onchange}>
+                        <option value="" selected={text.is_empty()}>{"—"}</option>
+                        {
+                            options.iter().map(|option| html! {
+                                <option value={option.clone()} selected={*option == text}>{option}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                }
+            }
+            FieldKind::Select { options, .. } => {
+                let chosen = match self.answers.get(&field.key) {
+                    Some(Value::List(chosen)) => chosen.clone(),
+                    _ => Vec::new(),
+                };
+                options
+                    .iter()
+                    .enumerate()
+                    .map(|(i, option)| {
+                        let (key, option_clone) = (key.clone(), option.clone());
+                        let onchange = ctx.link().batch_callback(move |e: Event| {
+                            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+                            Some(Msg::Toggle(key.clone(), option_clone.clone(), target.checked()))
+                        });
+                        let option_id = format!("{}-{}", id, i);
+                        html! {
+                            <div class="form-check">
+                                <input type="checkbox" class="form-check-input" id={option_id.clone()}
+                                    checked={chosen.contains(option)} {onchange} />
+                                <label class="form-check-label" for={option_id}>{option}</label>
+                            </div>
+                        }
+                    })
+                    .collect::<Html>()
+            }
+            FieldKind::User => {
+                let oninput = ctx.link().batch_callback(move |e: InputEvent| {
+                    let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+                    Some(Msg::UserInput(key.clone(), target.value()))
+                });
+                let text = self.user_texts.get(&field.key).cloned().unwrap_or_default();
+                html! {
+                    <input type="search" list="pickable-users" id={id.clone()} {class} value={text}
+                        placeholder="Username" {oninput} />
+                }
+            }
+            FieldKind::Checkbox => {
+                let onchange = ctx.link().batch_callback(move |e: Event| {
+                    let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+                    Some(Msg::Answer(
+                        key.clone(),
+                        Some(Value::Bool(target.checked())),
+                    ))
+                });
+                let checked = self.answers.get(&field.key) == Some(&Value::Bool(true));
+                return html! {
+                    <div class="form-check mb-3">
+                        <input type="checkbox" id={id.clone()} {class} {checked} {onchange} />
+                        <label class="form-check-label" for={id}>{&field.label}</label>
+                        { feedback(&field.help, error) }
+                    </div>
+                };
+            }
+        };
+
+        html! {
+            <div class="mb-3">
+                <label class="form-label" for={id}>
+                    {&field.label}
+                    { if field.required { html! { <span class="text-danger">{" *"}</span> } } else { html! {} } }
+                </label>
+                {input}
+                { feedback(&field.help, error) }
+            </div>
+        }
+    }
+}
+
+/// Renders the help text and validation error of a field.
+fn feedback(help: &str, error: Option<String>) -> Html {
+    html! {
+        <>
+            {
+                match error {
+                    Some(e) => html! { <div class="invalid-feedback d-block">{e}</div> },
+                    None => html! {},
+                }
+            }
+            {
+                if help.is_empty() {
+                    html! {}
+                } else {
+                    html! { <div class="form-text">{help}</div> }
+                }
+            }
+        </>
+    }
+}
+
+/// Searches the users that can be picked, returning `None` if the request failed.
+async fn pickable(text: &str) -> Option<Vec<PickableUserDTO>> {
+    let params = UrlSearchParams::new().ok()?;
+    params.append("q", text);
+
+    get_json(&format!(
+        "/api/v1/catalog/users?{}",
+        String::from(params.to_string())
+    ))
+    .await
+}
+
+/// Gets a JSON resource, returning `None` if the request failed.
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+    let response = Request::get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    response.json().await.ok()
+}
@@ -1,31 +1,578 @@
 //! Homepage component.
+//!
+//! Agents get their dashboard, made of the widgets they lay out. Widgets are reloaded whenever
+//! ticket activity is received from the server-sent event stream, so that they stay up to date
+//! without reloading the page. Other users are sent to the customer portal.
 
+use crate::router::Route;
+use common::{
+    dashboard::{
+        ActivityDTO, ActivityKind, DashboardDTO, SlaRiskDTO, SlaTarget, Widget, WidgetDTO,
+        DEFAULT_WIDGET_LIMIT,
+    },
+    ticket::TicketDTO,
+};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{EventSource, HtmlSelectElement, MessageEvent, UrlSearchParams};
 use yew::prelude::*;
+use yew_router::components::{Link, Redirect};
+
+/// Numbers of items a widget can be set to list.
+const LIMITS: [i64; 4] = [5, 10, 20, 50];
 
 /// Component messages.
 #[derive(Debug)]
-pub enum Msg {}
+pub enum Msg {
+    /// The dashboard layout has been loaded, or the user is not an agent.
+    Loaded(Option<DashboardDTO>),
+    /// Ticket activity has been received, so the widgets should be reloaded.
+    Activity,
+    /// The user started or stopped laying out the dashboard.
+    Edit(bool),
+    /// The widget at the given position should be moved up.
+    MoveUp(usize),
+    /// The widget at the given position should take the full width, or not.
+    Wide(usize, bool),
+    /// The number of items of the widget at the given position changed.
+    Limit(usize, i64),
+    /// The widget at the given position should be removed.
+    Remove(usize),
+    /// A widget should be added at the end.
+    Add(Widget),
+    /// The user wants to save the layout.
+    Save,
+    /// The layout has been saved, or the reason why it wasn't.
+    Saved(Result<DashboardDTO, String>),
+}
 
 /// Homepage component.
-#[derive(Debug)]
-pub struct Home;
+pub struct Home {
+    loaded: bool,
+    dashboard: Option<DashboardDTO>,
+    editing: bool,
+    error: Option<String>,
+    /// Number of times ticket activity has been received, passed to the widgets to reload them.
+    revision: u32,
+    source: Option<EventSource>,
+    /// Event listener of the stream, which must live as long as it.
+    _listener: Option<Closure<dyn FnMut(MessageEvent)>>,
+}
 
 impl Component for Home {
     type Message = Msg;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        Self
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(async { Msg::Loaded(get_json("/api/v1/dashboard").await) });
+
+        Self {
+            loaded: false,
+            dashboard: None,
+            editing: false,
+            error: None,
+            revision: 0,
+            source: None,
+            _listener: None,
+        }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        // Do nothing for now
-        false
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(dashboard) => {
+                if dashboard.is_some() && self.source.is_none() {
+                    self.connect(ctx);
+                }
+                self.loaded = true;
+                self.dashboard = dashboard;
+                self.editing = false;
+                self.error = None;
+
+                true
+            }
+            Msg::Activity => {
+                self.revision = self.revision.wrapping_add(1);
+
+                true
+            }
+            Msg::Edit(true) => {
+                self.editing = true;
+
+                true
+            }
+            Msg::Edit(false) => {
+                // The changes are discarded
+                ctx.link()
+                    .send_future(async { Msg::Loaded(get_json("/api/v1/dashboard").await) });
+
+                false
+            }
+            Msg::MoveUp(index) => self.change(|widgets| {
+                if index > 0 && index < widgets.len() {
+                    widgets.swap(index - 1, index);
+                }
+            }),
+            Msg::Wide(index, wide) => self.change(|widgets| {
+                if let Some(widget) = widgets.get_mut(index) {
+                    widget.wide = wide;
+                }
+            }),
+            Msg::Limit(index, limit) => self.change(|widgets| {
+                if let Some(widget) = widgets.get_mut(index) {
+                    widget.limit = limit;
+                }
+            }),
+            Msg::Remove(index) => self.change(|widgets| {
+                if index < widgets.len() {
+                    let _ = widgets.remove(index);
+                }
+            }),
+            Msg::Add(kind) => self.change(|widgets| {
+                if widgets.iter().all(|widget| widget.kind != kind) {
+                    widgets.push(WidgetDTO {
+                        kind,
+                        wide: false,
+                        limit: DEFAULT_WIDGET_LIMIT,
+                    });
+                }
+            }),
+            Msg::Save => {
+                let dashboard = match &self.dashboard {
+                    Some(dashboard) => dashboard,
+                    None => return false,
+                };
+                let body = to_string(dashboard).expect("could not serialize dashboard DTO to JSON");
+                ctx.link().send_future(async move {
+                    let response = Request::put("/api/v1/dashboard")
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    let saved = match response {
+                        Ok(response) => response.json().await.unwrap_or_else(|_| {
+                            Err("The dashboard could not be saved.".to_owned())
+                        }),
+                        Err(_) => Err("The server could not be reached.".to_owned()),
+                    };
+                    Msg::Saved(saved)
+                });
+
+                false
+            }
+            Msg::Saved(Ok(dashboard)) => {
+                self.dashboard = Some(dashboard);
+                self.editing = false;
+                self.error = None;
+
+                true
+            }
+            Msg::Saved(Err(e)) => {
+                self.error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let dashboard = match &self.dashboard {
+            Some(dashboard) => dashboard,
+            None if self.loaded => return html! { <Redirect<Route> to={Route::Portal} /> },
+            None => return html! { <main class="container"><p>{"Loading…"}</p></main> },
+        };
+
+        html! {
+            <main class="container-fluid">
+                <div class="d-flex justify-content-between align-items-center mb-3">
+                    <h1>{"Dashboard"}</h1>
+                    { self.toolbar(ctx, dashboard) }
+                </div>
+                {
+                    match &self.error {
+                        Some(e) => html! { <div class="alert alert-danger">{e}</div> },
+                        None => html! {},
+                    }
+                }
+                <div class="row">
+                    {
+                        dashboard
+                            .widgets
+                            .iter()
+                            .enumerate()
+                            .map(|(index, widget)| self.widget(ctx, index, widget))
+                            .collect::<Html>()
+                    }
+                </div>
+            </main>
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        if let Some(source) = self.source.take() {
+            source.close();
+        }
+    }
+}
+
+impl Home {
+    /// Changes the widgets of the dashboard being laid out.
+    fn change<F: FnOnce(&mut Vec<WidgetDTO>)>(&mut self, f: F) -> bool {
+        match &mut self.dashboard {
+            Some(dashboard) if self.editing => {
+                f(&mut dashboard.widgets);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Renders the buttons to lay out the dashboard.
+    fn toolbar(&self, ctx: &Context<Self>, dashboard: &DashboardDTO) -> Html {
+        if !self.editing {
+            let onclick = ctx.link().callback(|_| Msg::Edit(true));
+            return html! {
+                <button type="button" class="btn btn-outline-secondary" {onclick}>{"Customize"}</button>
+            };
+        }
+
+        let missing = Widget::ALL
+            .iter()
+            .filter(|&&kind| dashboard.widgets.iter().all(|widget| widget.kind != kind))
+            .collect::<Vec<_>>();
+        let add = ctx.link().batch_callback(|e: Event| {
+            let target = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+            let kind = target.value().parse().ok();
+            target.set_value("");
+            kind.map(Msg::Add)
+        });
+        let save = ctx.link().callback(|_| Msg::Save);
+        let cancel = ctx.link().callback(|_| Msg::Edit(false));
+
+        html! {
+            <div class="d-flex gap-2">
+                <select class="form-select" aria-label="Add widget" onchange={add}
+                    disabled={missing.is_empty()}>
+                    <option value="" selected=true>{"Add widget…"}</option>
+                    {
+                        missing.into_iter().map(|&kind| html! {
+                            <option value={kind.as_str()}>{title(kind)}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <button type="button" class="btn btn-primary" onclick={save}>{"Save"}</button>
+                <button type="button" class="btn btn-secondary" onclick={cancel}>{"Cancel"}</button>
+            </div>
+        }
+    }
+
+    /// Renders a widget of the dashboard, with its settings while laying it out.
+    fn widget(&self, ctx: &Context<Self>, index: usize, widget: &WidgetDTO) -> Html {
+        let settings = if self.editing {
+            let wide = widget.wide;
+            let toggle_wide = ctx.link().callback(move |_| Msg::Wide(index, !wide));
+            let move_up = ctx.link().callback(move |_| Msg::MoveUp(index));
+            let remove = ctx.link().callback(move |_| Msg::Remove(index));
+            let limit = ctx.link().batch_callback(move |e: Event| {
+                let target = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+                target
+                    .value()
+                    .parse()
+                    .ok()
+                    .map(|limit| Msg::Limit(index, limit))
+            });
+
+            html! {
+                <div class="d-flex gap-1">
+                    <select class="form-select form-select-sm" aria-label="Items" onchange={limit}>
+                        {
+                            LIMITS.iter().map(|&limit| html! {
+                                <option value={limit.to_string()} selected={limit == widget.limit}>
+                                    {limit}
+                                </option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                    <button type="button" class="btn btn-sm btn-outline-secondary" onclick={toggle_wide}>
+                        { if wide { "Narrow" } else { "Wide" } }
+                    </button>
+                    <button type="button" class="btn btn-sm btn-outline-secondary" onclick={move_up}
+                        disabled={index == 0}>{"Up"}</button>
+                    <button type="button" class="btn btn-sm btn-outline-danger" onclick={remove}>
+                        {"Remove"}
+                    </button>
+                </div>
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <section class={classes!("mb-3", if widget.wide { "col-12" } else { "col-md-6" })}>
+                <div class="card h-100">
+                    <div class="card-header d-flex justify-content-between align-items-center">
+                        <span>{title(widget.kind)}</span>
+                        { settings }
+                    </div>
+                    <DashboardWidget widget={widget.clone()} revision={self.revision} />
+                </div>
+            </section>
+        }
+    }
+
+    /// Connects to the ticket activity event stream.
+    fn connect(&mut self, ctx: &Context<Self>) {
+        let source = match EventSource::new("/api/v1/tickets/activity") {
+            Ok(source) => source,
+            Err(e) => {
+                gloo_console::error!("could not open the ticket activity stream", e);
+                return;
+            }
+        };
+
+        // Activity could have been missed while (re)connecting or falling behind
+        let link = ctx.link().clone();
+        let on_activity = Closure::wrap(Box::new(move |_e: MessageEvent| {
+            link.send_message(Msg::Activity);
+        }) as Box<dyn FnMut(MessageEvent)>);
+        for event in ["activity", "lagged", "open"] {
+            let _ = source
+                .add_event_listener_with_callback(event, on_activity.as_ref().unchecked_ref());
+        }
+
+        self.source = Some(source);
+        self._listener = Some(on_activity);
+    }
+}
+
+/// Gets the title of a kind of widget.
+fn title(kind: Widget) -> &'static str {
+    match kind {
+        Widget::MyOpenTickets => "My open tickets",
+        Widget::UnassignedQueue => "Unassigned queue",
+        Widget::SlaAtRisk => "SLA at risk",
+        Widget::RecentActivity => "Recent activity",
+    }
+}
+
+/// Dashboard widget properties.
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct WidgetProps {
+    pub widget: WidgetDTO,
+    /// Revision of the ticket activity, the widget is reloaded when it changes.
+    pub revision: u32,
+}
+
+/// Items listed by a widget.
+#[derive(Debug)]
+pub enum Content {
+    Tickets(Vec<TicketDTO>),
+    AtRisk(Vec<SlaRiskDTO>),
+    Activity(Vec<ActivityDTO>),
+}
+
+/// Dashboard widget messages.
+#[derive(Debug)]
+pub enum WidgetMsg {
+    /// The items of the widget should be loaded.
+    Reload,
+    /// The items of the widget have been loaded, or could not be.
+    Loaded(Option<Content>),
+}
+
+/// Dashboard widget component.
+#[derive(Debug, Default)]
+pub struct DashboardWidget {
+    content: Option<Content>,
+    loading: bool,
+    /// Whether the widget must be loaded again once the current load finishes.
+    stale: bool,
+    failed: bool,
+}
+
+impl Component for DashboardWidget {
+    type Message = WidgetMsg;
+    type Properties = WidgetProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(WidgetMsg::Reload);
+
+        Self::default()
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        ctx.link().send_message(WidgetMsg::Reload);
+
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            // Activity often comes in bursts, so reloads are coalesced
+            WidgetMsg::Reload if self.loading => {
+                self.stale = true;
+
+                false
+            }
+            WidgetMsg::Reload => {
+                self.loading = true;
+                let widget = ctx.props().widget.clone();
+                ctx.link()
+                    .send_future(async move { WidgetMsg::Loaded(load(&widget).await) });
+
+                false
+            }
+            WidgetMsg::Loaded(content) => {
+                self.loading = false;
+                self.failed = content.is_none();
+                if content.is_some() {
+                    self.content = content;
+                }
+                if self.stale {
+                    self.stale = false;
+                    ctx.link().send_message(WidgetMsg::Reload);
+                }
+
+                true
+            }
+        }
     }
 
     fn view(&self, _ctx: &Context<Self>) -> Html {
+        let content = match &self.content {
+            Some(content) => content,
+            None if self.failed => {
+                return html! { <div class="card-body text-danger">{"The widget could not be loaded."}</div> }
+            }
+            None => return html! { <div class="card-body text-muted">{"Loading…"}</div> },
+        };
+
+        let empty = match content {
+            Content::Tickets(tickets) => tickets.is_empty(),
+            Content::AtRisk(risks) => risks.is_empty(),
+            Content::Activity(activity) => activity.is_empty(),
+        };
+        if empty {
+            return html! { <div class="card-body text-muted">{"Nothing to show"}</div> };
+        }
+
         html! {
-            <main class="container">{{"Home component"}}</main>
+            <ul class="list-group list-group-flush">
+                {
+                    match content {
+                        Content::Tickets(tickets) => tickets.iter().map(ticket_item).collect::<Html>(),
+                        Content::AtRisk(risks) => risks.iter().map(risk_item).collect::<Html>(),
+                        Content::Activity(activity) => activity.iter().map(activity_item).collect::<Html>(),
+                    }
+                }
+            </ul>
+        }
+    }
+}
+
+/// Renders a link to a ticket, with its number and title.
+fn ticket_link(id: String, number: i64, title: &str) -> Html {
+    html! {
+        <Link<Route> to={Route::Ticket { id }}>{format!("#{} {}", number, title)}</Link<Route>>
+    }
+}
+
+/// Renders a ticket of a widget.
+fn ticket_item(ticket: &TicketDTO) -> Html {
+    html! {
+        <li class="list-group-item d-flex justify-content-between">
+            { ticket_link(ticket.id.to_string(), ticket.number, &ticket.title) }
+            <small class="text-muted">{format!("{} · {}", ticket.status, ticket.priority)}</small>
+        </li>
+    }
+}
+
+/// Renders a ticket at risk of missing its next SLA target.
+fn risk_item(risk: &SlaRiskDTO) -> Html {
+    let target = match risk.target {
+        SlaTarget::Response => "Response",
+        SlaTarget::Resolution => "Resolution",
+    };
+    let due = risk.due_on.format("%Y-%m-%d %H:%M UTC");
+
+    html! {
+        <li class="list-group-item d-flex justify-content-between">
+            { ticket_link(risk.ticket.id.to_string(), risk.ticket.number, &risk.ticket.title) }
+            <small class={if risk.breached { "text-danger" } else { "text-warning" }}>
+                {
+                    if risk.breached {
+                        format!("{} overdue since {}", target, due)
+                    } else {
+                        format!("{} due {}", target, due)
+                    }
+                }
+            </small>
+        </li>
+    }
+}
+
+/// Renders a ticket activity.
+fn activity_item(activity: &ActivityDTO) -> Html {
+    let actor = activity.actor.as_deref().unwrap_or("MySupport");
+    let what = match activity.kind {
+        ActivityKind::Created => "opened".to_owned(),
+        ActivityKind::Commented => "commented on".to_owned(),
+        ActivityKind::Changed => format!(
+            "set {} to {} on",
+            activity.field.as_deref().unwrap_or("a field"),
+            activity.value.as_deref().unwrap_or("nothing")
+        ),
+    };
+
+    html! {
+        <li class="list-group-item">
+            {format!("{} {} ", actor, what)}
+            { ticket_link(activity.ticket_id.to_string(), activity.number, &activity.title) }
+            <small class="text-muted ms-2">
+                {activity.happened_on.format("%Y-%m-%d %H:%M UTC").to_string()}
+            </small>
+        </li>
+    }
+}
+
+/// Loads the items listed by a widget, returning `None` if the request failed.
+async fn load(widget: &WidgetDTO) -> Option<Content> {
+    let query = match widget.kind {
+        Widget::MyOpenTickets => "assignee:me status:new,open,pending",
+        Widget::UnassignedQueue => "assignee:none status:new,open,pending",
+        Widget::SlaAtRisk => {
+            let url = format!("/api/v1/dashboard/sla-at-risk?limit={}", widget.limit);
+            return get_json(&url).await.map(Content::AtRisk);
+        }
+        Widget::RecentActivity => {
+            let url = format!("/api/v1/dashboard/activity?limit={}", widget.limit);
+            return get_json(&url).await.map(Content::Activity);
         }
+    };
+
+    let params = UrlSearchParams::new().ok()?;
+    params.append("q", query);
+    params.append("limit", &widget.limit.to_string());
+    get_json(&format!(
+        "/api/v1/tickets?{}",
+        String::from(params.to_string())
+    ))
+    .await
+    .map(Content::Tickets)
+}
+
+/// Gets a JSON resource, returning `None` if the request failed.
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+    let response = Request::get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
     }
+
+    response.json().await.ok()
 }
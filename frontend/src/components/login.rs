@@ -1,31 +1,190 @@
 //! Login component.
 
+use crate::router::Route;
+use common::login::LoginDTO;
+use reqwasm::http::Request;
+use serde_json::to_string;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
+use yew_router::{history::History, hooks::use_history};
 
 /// Component messages.
 #[derive(Debug)]
-pub enum Msg {}
+pub enum Msg {
+    /// The form has been submitted.
+    Submitted,
+    /// Username changed.
+    Username(String),
+    /// Password changed.
+    Password(String),
+    /// Server response.
+    ServerResponse(Result<(), ()>),
+}
 
 /// Login component.
-#[derive(Debug)]
-pub struct Login;
+#[derive(Debug, Default)]
+pub struct Login {
+    submitted: bool,
+    login_ok: bool,
+    login_err: bool,
+    username: String,
+    password: String,
+}
 
 impl Component for Login {
     type Message = Msg;
     type Properties = ();
 
     fn create(_ctx: &Context<Self>) -> Self {
-        Self
+        Self::default()
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        // Do nothing for now
-        false
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Username(username) => {
+                if self.username != username {
+                    self.username = username;
+                    self.login_err = false;
+
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::Password(password) => {
+                if self.password != password {
+                    self.password = password;
+                    self.login_err = false;
+
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::Submitted => {
+                self.submitted = true;
+                let username = self.username.clone();
+                let password = self.password.clone();
+
+                ctx.link().send_future(async move {
+                    let response = Request::post("/api/v1/login")
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(
+                            to_string(&LoginDTO {
+                                username: &username,
+                                password: &password,
+                            })
+                            .expect("could not serialize login DTO to JSON"),
+                        )
+                        .send()
+                        .await
+                        .expect("error sending request");
+
+                    Msg::ServerResponse(if response.ok() { Ok(()) } else { Err(()) })
+                });
+
+                true
+            }
+            Msg::ServerResponse(res) => {
+                self.submitted = false;
+                self.login_ok = res.is_ok();
+                self.login_err = res.is_err();
+
+                true
+            }
+        }
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="full-page">
+                <main class="container">
+                    <div class="row d-flex align-items-center">
+                        <div class="col-md-6 offset-md-3 card">
+                            <div class="card-body">
+                                {
+                                    if self.login_ok {
+                                        self.confirmation()
+                                    } else {
+                                        self.form(ctx)
+                                    }
+                                }
+                            </div>
+                        </div>
+                    </div>
+                </main>
+            </div>
+        }
+    }
+}
+
+impl Login {
+    /// Renders the login form.
+    fn form(&self, ctx: &Context<Self>) -> Html {
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            let target = e.target().unwrap().dyn_into::<HtmlInputElement>().unwrap();
+            let name = target.name();
+            let value = target.value();
+
+            match name.as_str() {
+                "username" => Msg::Username(value),
+                "password" => Msg::Password(value),
+                other => panic!("unexpected input name: {other}"),
+            }
+        });
+
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            e.stop_propagation();
+            Msg::Submitted
+        });
+
+        html! {
+            <>
+                <h2>{"Log in"}</h2>
+                <form {onsubmit}>
+                    <div>
+                        <label for="username" class="form-label">{"Username"}</label>
+                        <input type="text" name="username"
+                            class={if self.login_err {"form-control is-invalid"} else {"form-control"}}
+                            id="username" required=true oninput={oninput.clone()} />
+                    </div>
+                    <div>
+                        <label for="password" class="form-label">{"Password"}</label>
+                        <input type="password" name="password"
+                            class={if self.login_err {"form-control is-invalid"} else {"form-control"}}
+                            id="password" aria-describedby="loginValidationFeedback"
+                            required=true {oninput} />
+                        {
+                            if self.login_err {
+                                html! {<div id="loginValidationFeedback" class="invalid-feedback">{"Error: invalid username or password"}</div>}
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </div>
+                    <button type="submit" class="btn btn-primary" disabled={
+                        self.submitted || self.username.is_empty() || self.password.is_empty()}>{"Log in"}</button>
+                </form>
+            </>
+        }
+    }
+
+    /// Renders the login confirmation.
+    fn confirmation(&self) -> Html {
+        let history = use_history().expect("component outside of the router");
+        let onclick = Callback::once(move |e: MouseEvent| {
+            e.prevent_default();
+            history.push(Route::Home)
+        });
+
         html! {
-            <main class="container">{{"Login"}}</main>
+            <>
+                <h2>{"Welcome back!"}</h2>
+                <p><a href="/" title="Home" {onclick}>{"Continue"}</a></p>
+            </>
         }
     }
 }
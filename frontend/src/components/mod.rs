@@ -2,18 +2,38 @@
 //!
 //! This module contains the main `MySupport` component.
 
+pub mod approvals;
+pub mod board;
+pub mod catalog;
 pub mod email_registration;
 pub mod home;
 pub mod login;
 pub mod nav;
+pub mod notification_settings;
+pub mod notifications;
+pub mod portal;
 pub mod register;
+pub mod search;
+pub mod survey;
+pub mod ticket;
+pub mod tickets;
 
 use crate::router::*;
+pub use approvals::*;
+pub use board::*;
+pub use catalog::*;
 pub use email_registration::*;
 pub use home::*;
 pub use login::*;
 pub use nav::*;
+pub use notification_settings::*;
+pub use notifications::*;
+pub use portal::*;
 pub use register::*;
+pub use search::*;
+pub use survey::*;
+pub use ticket::*;
+pub use tickets::*;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
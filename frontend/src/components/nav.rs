@@ -1,3 +1,4 @@
+use super::{Notifications, SearchBox};
 use crate::router::Route;
 use std::collections::HashMap;
 use wasm_bindgen::JsCast;
@@ -8,6 +9,11 @@ use yew_router::prelude::*;
 #[function_component(Nav)]
 pub fn nav() -> Html {
     let history = use_history().expect("navigation bar instantiated outside of the router");
+    // The portal has its own navigation, branded for the customer
+    if matches!(use_route(), Some(Route::Portal | Route::PortalPage)) {
+        return html! {};
+    }
+
     let onclick = Callback::once(move |e: MouseEvent| {
         e.prevent_default();
 
@@ -26,12 +32,29 @@ pub fn nav() -> Html {
                 <li class="nav-item">
                     <a class="nav-link active" aria-current="page" href="/" onclick={onclick.clone()}>{"Home"}</a>
                 </li>
+                <li class="nav-item">
+                    <a class="nav-link" href="/tickets" onclick={onclick.clone()}>{"Tickets"}</a>
+                </li>
+                <li class="nav-item">
+                    <a class="nav-link" href="/board" onclick={onclick.clone()}>{"Board"}</a>
+                </li>
+                <li class="nav-item">
+                    <a class="nav-link" href="/tickets/new" onclick={onclick.clone()}>{"New ticket"}</a>
+                </li>
+                <li class="nav-item">
+                    <a class="nav-link" href="/catalog" onclick={onclick.clone()}>{"Catalog"}</a>
+                </li>
+                <li class="nav-item">
+                    <a class="nav-link" href="/approvals" onclick={onclick.clone()}>{"Approvals"}</a>
+                </li>
                 <li class="nav-item">
                     <a class="nav-link" href="/register" onclick={onclick.clone()}>{"Register"}</a>
                 </li>
                 <li class="nav-item">
                     <a class="nav-link" href="/login" onclick={onclick.clone()}>{"Log in"}</a>
                 </li>
+                <SearchBox />
+                <Notifications />
             </ul>
         </nav>
     }
@@ -0,0 +1,153 @@
+//! Notification settings component.
+//!
+//! It lets the logged in user choose how they receive every kind of notification.
+
+use common::notification::{Delivery, Kind, PreferenceDTO};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+/// Component messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The preferences have been loaded or saved, or the user is not logged in.
+    Loaded(Option<Vec<PreferenceDTO>>),
+    /// The delivery of a kind of notification has been changed.
+    Changed(Kind, Delivery),
+}
+
+/// Notification settings component.
+#[derive(Debug, Default)]
+pub struct NotificationSettings {
+    loaded: bool,
+    preferences: Option<Vec<PreferenceDTO>>,
+}
+
+impl Component for NotificationSettings {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            let response = Request::get("/api/v1/notifications/preferences")
+                .header("Accept", "application/json")
+                .send()
+                .await;
+            Msg::Loaded(parse(response).await)
+        });
+
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(preferences) => {
+                self.loaded = true;
+                self.preferences = preferences;
+
+                true
+            }
+            Msg::Changed(kind, delivery) => {
+                let body = to_string(&[PreferenceDTO { kind, delivery }])
+                    .expect("could not serialize preference DTO to JSON");
+                ctx.link().send_future(async move {
+                    let response = Request::put("/api/v1/notifications/preferences")
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    Msg::Loaded(parse(response).await)
+                });
+
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <main class="container">
+                <h2>{"Notification settings"}</h2>
+                {
+                    match &self.preferences {
+                        Some(preferences) => html! {
+                            <table class="table">
+                                <thead>
+                                    <tr><th>{"Notification"}</th><th>{"Delivery"}</th></tr>
+                                </thead>
+                                <tbody>
+                                    { preferences.iter().map(|preference| row(ctx, preference)).collect::<Html>() }
+                                </tbody>
+                            </table>
+                        },
+                        None if self.loaded => html! { <p>{"You need to log in to change your notification settings."}</p> },
+                        None => html! {},
+                    }
+                }
+            </main>
+        }
+    }
+}
+
+/// Renders the row of a kind of notification.
+fn row(ctx: &Context<NotificationSettings>, preference: &PreferenceDTO) -> Html {
+    let kind = preference.kind;
+    let onchange = ctx.link().batch_callback(move |e: Event| {
+        let target = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+        target
+            .value()
+            .parse()
+            .ok()
+            .map(|delivery| Msg::Changed(kind, delivery))
+    });
+
+    html! {
+        <tr>
+            <td>{kind_label(kind)}</td>
+            <td>
+                <select class="form-select" {onchange}>
+                    {
+                        Delivery::ALL.iter().map(|&delivery| html! {
+                            <option value={delivery.as_str()} selected={delivery == preference.delivery}>
+                                {delivery_label(delivery)}
+                            </option>
+                        }).collect::<Html>()
+                    }
+                </select>
+            </td>
+        </tr>
+    }
+}
+
+/// Gets the label of a kind of notification.
+fn kind_label(kind: Kind) -> &'static str {
+    match kind {
+        Kind::TicketAssigned => "A ticket is assigned to me",
+        Kind::TicketCommented => "A ticket of mine gets a comment",
+        Kind::TicketStatusChanged => "The status of a ticket of mine changes",
+        Kind::ApprovalRequested => "My approval is requested",
+    }
+}
+
+/// Gets the label of a way of receiving notifications.
+fn delivery_label(delivery: Delivery) -> &'static str {
+    match delivery {
+        Delivery::Immediate => "In the application and by email",
+        Delivery::Digest => "In the application and in a daily email digest",
+        Delivery::InApp => "Only in the application",
+        Delivery::Off => "Never",
+    }
+}
+
+/// Parses the preferences returned by the server, if the request succeeded.
+async fn parse(
+    response: Result<reqwasm::http::Response, reqwasm::Error>,
+) -> Option<Vec<PreferenceDTO>> {
+    match response {
+        Ok(response) if response.ok() => response.json().await.ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,280 @@
+//! Notification centre component.
+//!
+//! It shows the number of unread notifications of the logged in user, along with a dropdown with
+//! the latest ones. New notifications are received in real time from the server-sent event stream.
+
+use crate::router::Route;
+use common::notification::{NotificationDTO, UnreadCountDTO};
+use reqwasm::http::Request;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{EventSource, MessageEvent};
+use yew::prelude::*;
+use yew_router::components::Link;
+
+/// Number of notifications shown in the dropdown.
+const SHOWN: usize = 10;
+
+/// Component messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The notifications should be loaded again.
+    Reload,
+    /// The latest notifications and the number of unread ones have been loaded, or the user is not
+    /// logged in.
+    Loaded(Option<(Vec<NotificationDTO>, i64)>),
+    /// A new notification has been received.
+    Received(NotificationDTO),
+    /// The dropdown has been opened or closed.
+    Toggle,
+    /// The notification at the given position has been clicked.
+    Read(usize),
+    /// All the notifications have been marked as read.
+    ReadAll,
+}
+
+/// Notification centre component.
+pub struct Notifications {
+    logged_in: bool,
+    open: bool,
+    notifications: Vec<NotificationDTO>,
+    unread: i64,
+    source: Option<EventSource>,
+    /// Event listeners of the stream, which must live as long as it.
+    _listeners: Vec<Closure<dyn FnMut(MessageEvent)>>,
+}
+
+impl Component for Notifications {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::Reload);
+
+        Self {
+            logged_in: false,
+            open: false,
+            notifications: Vec::new(),
+            unread: 0,
+            source: None,
+            _listeners: Vec::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Reload => {
+                ctx.link().send_future(async { Msg::Loaded(load().await) });
+
+                false
+            }
+            Msg::Loaded(Some((notifications, unread))) => {
+                self.logged_in = true;
+                self.notifications = notifications;
+                self.unread = unread;
+                if self.source.is_none() {
+                    self.connect(ctx);
+                }
+
+                true
+            }
+            Msg::Loaded(None) => {
+                self.logged_in = false;
+                self.disconnect();
+
+                true
+            }
+            Msg::Received(notification) => {
+                if !notification.read {
+                    self.unread += 1;
+                }
+                self.notifications.insert(0, notification);
+                self.notifications.truncate(SHOWN);
+
+                true
+            }
+            Msg::Toggle => {
+                self.open = !self.open;
+
+                true
+            }
+            Msg::Read(index) => match self.notifications.get_mut(index) {
+                Some(notification) if !notification.read => {
+                    notification.read = true;
+                    self.unread = (self.unread - 1).max(0);
+
+                    let url = format!("/api/v1/notifications/{}/read", notification.id);
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let _ = Request::post(&url).send().await;
+                    });
+
+                    true
+                }
+                _ => false,
+            },
+            Msg::ReadAll => {
+                self.notifications
+                    .iter_mut()
+                    .for_each(|notification| notification.read = true);
+                self.unread = 0;
+
+                wasm_bindgen_futures::spawn_local(async {
+                    let _ = Request::post("/api/v1/notifications/read").send().await;
+                });
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if !self.logged_in {
+            return html! {};
+        }
+
+        let toggle = ctx.link().callback(|e: MouseEvent| {
+            e.prevent_default();
+            Msg::Toggle
+        });
+        let read_all = ctx.link().callback(|e: MouseEvent| {
+            e.prevent_default();
+            Msg::ReadAll
+        });
+
+        html! {
+            <li class="nav-item dropdown">
+                <a class="nav-link dropdown-toggle" href="#" role="button"
+                    aria-expanded={self.open.to_string()} onclick={toggle}>
+                    {"Notifications"}
+                    {
+                        if self.unread > 0 {
+                            html! {
+                                <span class="badge rounded-pill bg-danger ms-1">{self.unread}</span>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </a>
+                <ul class={classes!("dropdown-menu", "dropdown-menu-end", self.open.then(|| "show"))}>
+                    {
+                        if self.notifications.is_empty() {
+                            html! { <li><span class="dropdown-item-text">{"No notifications"}</span></li> }
+                        } else {
+                            self.notifications
+                                .iter()
+                                .enumerate()
+                                .map(|(index, notification)| self.item(ctx, index, notification))
+                                .collect::<Html>()
+                        }
+                    }
+                    <li><hr class="dropdown-divider" /></li>
+                    <li><a class="dropdown-item" href="#" onclick={read_all}>{"Mark all as read"}</a></li>
+                    <li>
+                        <Link<Route> classes="dropdown-item" to={Route::NotificationSettings}>
+                            {"Settings"}
+                        </Link<Route>>
+                    </li>
+                </ul>
+            </li>
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        self.disconnect();
+    }
+}
+
+impl Notifications {
+    /// Renders a notification of the dropdown.
+    fn item(&self, ctx: &Context<Self>, index: usize, notification: &NotificationDTO) -> Html {
+        let onclick = ctx.link().callback(move |e: MouseEvent| {
+            e.prevent_default();
+            Msg::Read(index)
+        });
+
+        html! {
+            <li>
+                <a class={classes!("dropdown-item", (!notification.read).then(|| "fw-bold"))}
+                    href="#" {onclick}>
+                    <div>{&notification.title}</div>
+                    <small class="text-muted">{&notification.body}</small>
+                </a>
+            </li>
+        }
+    }
+
+    /// Connects to the notification event stream.
+    fn connect(&mut self, ctx: &Context<Self>) {
+        let source = match EventSource::new("/api/v1/notifications/stream") {
+            Ok(source) => source,
+            Err(e) => {
+                gloo_console::error!("could not open the notification stream", e);
+                return;
+            }
+        };
+
+        let link = ctx.link().clone();
+        let on_notification = Closure::wrap(Box::new(move |e: MessageEvent| {
+            let notification = e
+                .data()
+                .as_string()
+                .and_then(|data| serde_json::from_str::<NotificationDTO>(&data).ok());
+            if let Some(notification) = notification {
+                link.send_message(Msg::Received(notification));
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+
+        // Notifications could have been missed while (re)connecting or falling behind
+        let link = ctx.link().clone();
+        let on_reload = Closure::wrap(Box::new(move |_e: MessageEvent| {
+            link.send_message(Msg::Reload);
+        }) as Box<dyn FnMut(MessageEvent)>);
+
+        let listeners = [
+            ("notification", &on_notification),
+            ("lagged", &on_reload),
+            ("open", &on_reload),
+        ];
+        for (event, listener) in listeners {
+            let _ =
+                source.add_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+        }
+
+        self.source = Some(source);
+        self._listeners = vec![on_notification, on_reload];
+    }
+
+    /// Disconnects from the notification event stream.
+    fn disconnect(&mut self) {
+        if let Some(source) = self.source.take() {
+            source.close();
+        }
+        self._listeners.clear();
+    }
+}
+
+/// Loads the latest notifications and the number of unread ones.
+///
+/// Returns `None` if the user is not logged in.
+async fn load() -> Option<(Vec<NotificationDTO>, i64)> {
+    let response = Request::get(&format!("/api/v1/notifications?limit={}", SHOWN))
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+    let notifications = response.json::<Vec<NotificationDTO>>().await.ok()?;
+
+    let unread = Request::get("/api/v1/notifications/unread")
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?
+        .json::<UnreadCountDTO>()
+        .await
+        .ok()?;
+
+    Some((notifications, unread.count))
+}
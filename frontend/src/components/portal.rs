@@ -0,0 +1,639 @@
+//! Customer self-service portal components.
+//!
+//! Customers get a portal of their own, where they request services from the catalog, follow the
+//! conversation of their tickets and search the knowledge base. The portal is branded with the
+//! logo, colours and welcome text of the organisation of the customer, loaded from the server, so
+//! that the same application serves every organisation. Staff are sent to their dashboard instead.
+
+use super::RequestForm;
+use crate::router::Route;
+use common::{
+    article::{ArticleDTO, ArticleSummaryDTO},
+    catalog::CatalogItemDTO,
+    organisation::BrandingDTO,
+    ticket::{CommentDTO, NewCommentDTO, TicketDTO},
+    user::UserDTO,
+};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement, UrlSearchParams};
+use yew::{prelude::*, virtual_dom::VNode};
+use yew_router::prelude::*;
+
+/// Number of articles listed by a knowledge base search.
+const SEARCH_LIMIT: i64 = 20;
+
+/// Portal router, nested in the `/portal` routes of the application router.
+#[derive(Clone, Debug, PartialEq, Routable)]
+pub enum PortalRoute {
+    #[at("/portal/catalog/:id")]
+    Request { id: String },
+    #[at("/portal/catalog")]
+    Catalog,
+    #[at("/portal/tickets/:id")]
+    Ticket { id: String },
+    #[at("/portal/tickets")]
+    Tickets,
+    #[at("/portal/help/:id")]
+    Article { id: String },
+    #[at("/portal/help")]
+    Help,
+    #[not_found]
+    #[at("/portal")]
+    Home,
+}
+
+/// Portal messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The logged in user, if any, and the branding of the portal have been loaded.
+    Loaded(Option<UserDTO>, BrandingDTO),
+}
+
+/// Portal component, laying out the branded header around the page of the portal route.
+#[derive(Debug, Default)]
+pub struct Portal {
+    loaded: bool,
+    user: Option<UserDTO>,
+    branding: BrandingDTO,
+}
+
+impl Component for Portal {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            let user = get_json("/api/v1/login").await;
+            let branding = match user {
+                Some(_) => get_json("/api/v1/portal/branding").await,
+                None => None,
+            };
+            Msg::Loaded(user, branding.unwrap_or_default())
+        });
+
+        Self::default()
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(user, branding) => {
+                self.loaded = true;
+                self.user = user;
+                self.branding = branding;
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        if !self.loaded {
+            return html! { <main class="container"><p>{"Loading…"}</p></main> };
+        }
+        let user = match &self.user {
+            Some(user) if user.role.is_staff() => {
+                return html! { <Redirect<Route> to={Route::Home} /> }
+            }
+            Some(user) => user,
+            None => return html! { <Redirect<Route> to={Route::Login} /> },
+        };
+
+        let branding = &self.branding;
+        // Colours are validated as `#rrggbb` by the server
+        let style = format!(
+            ".portal a:not(.btn) {{ color: {0}; }} \
+             .portal .btn-primary {{ background-color: {0}; border-color: {0}; }}",
+            branding.accent_colour
+        );
+        let (user_id, welcome_text) = (user.id.to_string(), branding.welcome_text.clone());
+        let render = Switch::render(move |route| switch(route, &user_id, &welcome_text));
+
+        html! {
+            <div class="portal">
+                <style>{style}</style>
+                <header class="mb-4 py-3" style={format!("background-color: {}", branding.primary_colour)}>
+                    <nav class="container d-flex align-items-center">
+                        <Link<PortalRoute> classes="navbar-brand me-auto" to={PortalRoute::Home}>
+                            {
+                                match &branding.logo_url {
+                                    Some(url) => html! { <img src={url.clone()} alt="Support" height="40" /> },
+                                    None => html! { <span class="text-white">{"Support"}</span> },
+                                }
+                            }
+                        </Link<PortalRoute>>
+                        <Link<PortalRoute> classes="btn btn-link text-white" to={PortalRoute::Catalog}>
+                            {"Request"}
+                        </Link<PortalRoute>>
+                        <Link<PortalRoute> classes="btn btn-link text-white" to={PortalRoute::Tickets}>
+                            {"My tickets"}
+                        </Link<PortalRoute>>
+                        <Link<PortalRoute> classes="btn btn-link text-white" to={PortalRoute::Help}>
+                            {"Help"}
+                        </Link<PortalRoute>>
+                    </nav>
+                </header>
+                <Switch<PortalRoute> {render} />
+            </div>
+        }
+    }
+}
+
+/// Renders the page of a portal route.
+fn switch(route: &PortalRoute, user_id: &str, welcome_text: &str) -> Html {
+    match route {
+        PortalRoute::Request { id } => html! { <RequestForm id={id.clone()} /> },
+        PortalRoute::Catalog => html! { <PortalCatalog /> },
+        PortalRoute::Ticket { id } => {
+            html! { <Conversation id={id.clone()} user_id={user_id.to_owned()} /> }
+        }
+        PortalRoute::Tickets => html! { <MyTickets /> },
+        PortalRoute::Article { id } => html! { <PortalArticle id={id.clone()} /> },
+        PortalRoute::Help => html! {
+            <main class="container">
+                <h1>{"Help"}</h1>
+                <ArticleSearch />
+            </main>
+        },
+        PortalRoute::Home => html! {
+            <main class="container">
+                <h1 class="mb-4" style="white-space: pre-line">{welcome_text}</h1>
+                <ArticleSearch />
+                <div class="row mt-4">
+                    <div class="col-md-6">
+                        <Link<PortalRoute> classes="btn btn-primary w-100" to={PortalRoute::Catalog}>
+                            {"Submit a request"}
+                        </Link<PortalRoute>>
+                    </div>
+                    <div class="col-md-6">
+                        <Link<PortalRoute> classes="btn btn-outline-secondary w-100" to={PortalRoute::Tickets}>
+                            {"Follow my tickets"}
+                        </Link<PortalRoute>>
+                    </div>
+                </div>
+            </main>
+        },
+    }
+}
+
+/// Portal catalog component, listing the items that can be requested.
+#[derive(Debug, Default)]
+pub struct PortalCatalog {
+    items: Vec<CatalogItemDTO>,
+}
+
+impl Component for PortalCatalog {
+    type Message = Vec<CatalogItemDTO>;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            get_json::<Vec<_>>("/api/v1/catalog")
+                .await
+                .unwrap_or_default()
+        });
+
+        Self::default()
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, items: Self::Message) -> bool {
+        self.items = items;
+
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <main class="container">
+                <h1>{"What do you need?"}</h1>
+                <div class="list-group">
+                    {
+                        self.items.iter().map(|item| html! {
+                            <Link<PortalRoute> classes="list-group-item list-group-item-action"
+                                to={PortalRoute::Request { id: item.id.to_string() }}>
+                                <h5>{&item.name}</h5>
+                                <p class="mb-0">{&item.description}</p>
+                            </Link<PortalRoute>>
+                        }).collect::<Html>()
+                    }
+                </div>
+            </main>
+        }
+    }
+}
+
+/// Component listing the tickets requested by the customer.
+#[derive(Debug, Default)]
+pub struct MyTickets {
+    tickets: Option<Vec<TicketDTO>>,
+}
+
+impl Component for MyTickets {
+    type Message = Option<Vec<TicketDTO>>;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(async { get_json("/api/v1/tickets?q=requester%3Ame").await });
+
+        Self::default()
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, tickets: Self::Message) -> bool {
+        self.tickets = Some(tickets.unwrap_or_default());
+
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let tickets = match &self.tickets {
+            Some(tickets) => tickets,
+            None => return html! { <main class="container"><p>{"Loading…"}</p></main> },
+        };
+
+        html! {
+            <main class="container">
+                <h1>{"My tickets"}</h1>
+                {
+                    if tickets.is_empty() {
+                        html! { <p>{"You haven't requested anything yet."}</p> }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div class="list-group">
+                    {
+                        tickets.iter().map(|ticket| html! {
+                            <Link<PortalRoute> classes="list-group-item list-group-item-action d-flex justify-content-between"
+                                to={PortalRoute::Ticket { id: ticket.id.to_string() }}>
+                                <span>{format!("#{} {}", ticket.number, ticket.title)}</span>
+                                <span class="badge bg-secondary">{ticket.status}</span>
+                            </Link<PortalRoute>>
+                        }).collect::<Html>()
+                    }
+                </div>
+            </main>
+        }
+    }
+}
+
+/// Conversation properties.
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ConversationProps {
+    /// ID of the ticket.
+    pub id: String,
+    /// ID of the logged in customer.
+    pub user_id: String,
+}
+
+/// Conversation messages.
+#[derive(Debug)]
+pub enum ConversationMsg {
+    /// The ticket and its comments have been loaded, if it exists.
+    Loaded(Option<(TicketDTO, Vec<CommentDTO>)>),
+    /// The text of the reply changed.
+    Input(String),
+    /// The customer wants to send the reply.
+    Reply,
+    /// The reply has been sent, or the reason why it wasn't.
+    Replied(Result<CommentDTO, String>),
+}
+
+/// Conversation component, showing a ticket of the customer with its comments and a reply form.
+#[derive(Debug, Default)]
+pub struct Conversation {
+    loaded: bool,
+    ticket: Option<TicketDTO>,
+    comments: Vec<CommentDTO>,
+    reply: String,
+    error: Option<String>,
+}
+
+impl Component for Conversation {
+    type Message = ConversationMsg;
+    type Properties = ConversationProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let url = format!("/api/v1/tickets/{}", ctx.props().id);
+        ctx.link().send_future(async move {
+            let ticket = get_json::<Option<TicketDTO>>(&url).await.flatten();
+            let loaded = match ticket {
+                Some(ticket) => {
+                    let comments = get_json(&format!("{}/comments", url))
+                        .await
+                        .unwrap_or_default();
+                    Some((ticket, comments))
+                }
+                None => None,
+            };
+            ConversationMsg::Loaded(loaded)
+        });
+
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ConversationMsg::Loaded(loaded) => {
+                self.loaded = true;
+                if let Some((ticket, comments)) = loaded {
+                    self.ticket = Some(ticket);
+                    self.comments = comments;
+                }
+
+                true
+            }
+            ConversationMsg::Input(reply) => {
+                self.reply = reply;
+
+                false
+            }
+            ConversationMsg::Reply => {
+                if self.reply.trim().is_empty() {
+                    return false;
+                }
+
+                let url = format!("/api/v1/tickets/{}/comments", ctx.props().id);
+                let body = to_string(&NewCommentDTO { body: &self.reply })
+                    .expect("could not serialize new comment DTO to JSON");
+                ctx.link().send_future(async move {
+                    let response = Request::post(&url)
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    let replied = match response {
+                        Ok(response) => response
+                            .json()
+                            .await
+                            .unwrap_or_else(|_| Err("The reply could not be sent.".to_owned())),
+                        Err(_) => Err("The server could not be reached.".to_owned()),
+                    };
+                    ConversationMsg::Replied(replied)
+                });
+
+                false
+            }
+            ConversationMsg::Replied(Ok(comment)) => {
+                self.comments.push(comment);
+                self.reply.clear();
+                self.error = None;
+
+                true
+            }
+            ConversationMsg::Replied(Err(e)) => {
+                self.error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let ticket = match &self.ticket {
+            Some(ticket) => ticket,
+            None if self.loaded => {
+                return html! { <main class="container"><p>{"This ticket doesn't exist."}</p></main> }
+            }
+            None => return html! { <main class="container"><p>{"Loading…"}</p></main> },
+        };
+
+        let user_id = &ctx.props().user_id;
+        let oninput = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlTextAreaElement>().ok()?;
+            Some(ConversationMsg::Input(target.value()))
+        });
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            ConversationMsg::Reply
+        });
+
+        html! {
+            <main class="container">
+                <h1>{format!("#{} {}", ticket.number, ticket.title)}</h1>
+                <p><span class="badge bg-secondary">{ticket.status}</span></p>
+                <p style="white-space: pre-wrap">{&ticket.description}</p>
+                {
+                    self.comments.iter().map(|comment| {
+                        let (author, class) = if comment.author_id.map(|id| id.to_string()).as_ref() == Some(user_id) {
+                            ("You", "card mb-3 ms-5")
+                        } else {
+                            ("Support", "card mb-3 me-5 bg-light")
+                        };
+                        html! {
+                            <div {class}>
+                                <div class="card-body">
+                                    <h6 class="card-subtitle mb-2 text-muted">
+                                        {format!("{}, {}", author, comment.created_on.format("%Y-%m-%d %H:%M"))}
+                                    </h6>
+                                    <p class="card-text" style="white-space: pre-wrap">{&comment.body}</p>
+                                </div>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+                <form {onsubmit}>
+                    <div class="mb-3">
+                        <label class="form-label" for="reply">{"Reply"}</label>
+                        <textarea id="reply" class="form-control" rows="4" value={self.reply.clone()} {oninput} />
+                    </div>
+                    <button type="submit" class="btn btn-primary">{"Send"}</button>
+                    {
+                        match &self.error {
+                            Some(e) => html! { <div class="text-danger">{e}</div> },
+                            None => html! {},
+                        }
+                    }
+                </form>
+            </main>
+        }
+    }
+}
+
+/// Knowledge base search messages.
+#[derive(Debug)]
+pub enum SearchMsg {
+    /// The words searched for changed.
+    Input(String),
+    /// The user wants to search.
+    Search,
+    /// The matching articles have been received.
+    Found(Vec<ArticleSummaryDTO>),
+}
+
+/// Knowledge base search component, listing the articles matching the words searched for.
+#[derive(Debug, Default)]
+pub struct ArticleSearch {
+    words: String,
+    /// The articles found, `None` until the first search.
+    found: Option<Vec<ArticleSummaryDTO>>,
+}
+
+impl Component for ArticleSearch {
+    type Message = SearchMsg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            SearchMsg::Input(words) => {
+                self.words = words;
+
+                false
+            }
+            SearchMsg::Search => {
+                let words = self.words.clone();
+                ctx.link().send_future(async move {
+                    SearchMsg::Found(search_articles(&words).await.unwrap_or_default())
+                });
+
+                false
+            }
+            SearchMsg::Found(found) => {
+                self.found = Some(found);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let oninput = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(SearchMsg::Input(target.value()))
+        });
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            SearchMsg::Search
+        });
+
+        html! {
+            <>
+                <form class="d-flex" role="search" {onsubmit}>
+                    <input type="search" class="form-control me-2" placeholder="Search the help articles"
+                        aria-label="Search" value={self.words.clone()} {oninput} />
+                    <button type="submit" class="btn btn-primary">{"Search"}</button>
+                </form>
+                {
+                    match &self.found {
+                        Some(found) if found.is_empty() => html! {
+                            <p class="mt-3">{"No article matches your search."}</p>
+                        },
+                        Some(found) => html! {
+                            <div class="list-group mt-3">
+                                {
+                                    found.iter().map(|article| html! {
+                                        <Link<PortalRoute> classes="list-group-item list-group-item-action"
+                                            to={PortalRoute::Article { id: article.id.to_string() }}>
+                                            {&article.title}
+                                        </Link<PortalRoute>>
+                                    }).collect::<Html>()
+                                }
+                            </div>
+                        },
+                        None => html! {},
+                    }
+                }
+            </>
+        }
+    }
+}
+
+/// Portal article properties.
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct PortalArticleProps {
+    /// ID of the article.
+    pub id: String,
+}
+
+/// Portal article component, showing a knowledge base article.
+#[derive(Debug, Default)]
+pub struct PortalArticle {
+    loaded: bool,
+    article: Option<ArticleDTO>,
+}
+
+impl Component for PortalArticle {
+    type Message = Option<ArticleDTO>;
+    type Properties = PortalArticleProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let url = format!("/api/v1/articles/{}", ctx.props().id);
+        ctx.link()
+            .send_future(async move { get_json::<Option<_>>(&url).await.flatten() });
+
+        Self::default()
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, article: Self::Message) -> bool {
+        self.loaded = true;
+        self.article = article;
+
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let article = match &self.article {
+            Some(article) => article,
+            None if self.loaded => {
+                return html! { <main class="container"><p>{"This article doesn't exist."}</p></main> }
+            }
+            None => return html! { <main class="container"><p>{"Loading…"}</p></main> },
+        };
+
+        html! {
+            <main class="container">
+                <h1>{&article.title}</h1>
+                { raw_html(&article.html) }
+                <Link<PortalRoute> to={PortalRoute::Help}>{"Back to the help articles"}</Link<PortalRoute>>
+            </main>
+        }
+    }
+}
+
+/// Renders HTML that has been sanitised by the server.
+fn raw_html(html: &str) -> Html {
+    let div = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("div").ok());
+
+    match div {
+        Some(div) => {
+            div.set_inner_html(html);
+            VNode::VRef(div.into())
+        }
+        None => html! {},
+    }
+}
+
+/// Searches the readable articles matching the words, returning `None` if the request failed.
+async fn search_articles(words: &str) -> Option<Vec<ArticleSummaryDTO>> {
+    let params = UrlSearchParams::new().ok()?;
+    params.append("q", words);
+    params.append("limit", &SEARCH_LIMIT.to_string());
+
+    get_json(&format!(
+        "/api/v1/articles?{}",
+        String::from(params.to_string())
+    ))
+    .await
+}
+
+/// Gets a JSON resource, returning `None` if the request failed.
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+    let response = Request::get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    response.json().await.ok()
+}
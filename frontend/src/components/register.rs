@@ -1,7 +1,7 @@
 //! Registration form.
 
 use crate::router::Route;
-use common::registration::{ResponseDTO, SubmitDTO};
+use common::registration::{CodeInfoDTO, ResponseDTO, SubmitDTO};
 use reqwasm::http::Request;
 use serde_json::to_string;
 use wasm_bindgen::JsCast;
@@ -24,6 +24,8 @@ pub enum Msg {
     LastName(String),
     /// Server response.
     ServerResponse(Result<(), ResponseDTO>),
+    /// Registration code information.
+    CodeInfo(Option<CodeInfoDTO>),
 }
 
 /// Properties for the registration form
@@ -45,13 +47,31 @@ pub struct RegistrationForm {
     pass_err: Option<String>,
     first_name: String,
     last_name: String,
+    code_info: Option<CodeInfoDTO>,
 }
 
 impl Component for RegistrationForm {
     type Message = Msg;
     type Properties = Props;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let Props { code } = ctx.props();
+        let code = code.clone();
+
+        ctx.link().send_future(async move {
+            let response = Request::get(&format!("/api/v1/register/code/{}", code))
+                .header("Accept", "application/json")
+                .send()
+                .await
+                .expect("error sending request");
+
+            Msg::CodeInfo(if response.ok() {
+                response.json().await.ok()
+            } else {
+                None
+            })
+        });
+
         Self::default()
     }
 
@@ -135,6 +155,14 @@ impl Component for RegistrationForm {
 
                 true
             }
+            Msg::CodeInfo(code_info) => {
+                if code_info.is_none() {
+                    self.general_err = Some("invalid registration code".to_owned());
+                }
+                self.code_info = code_info;
+
+                true
+            }
             Msg::ServerResponse(res) => match res {
                 Ok(_res) => {
                     self.submit_ok = true;
@@ -208,6 +236,19 @@ impl RegistrationForm {
             <>
                 <h2>{{"Create your account"}}</h2>
                 <form {onsubmit}>
+                    <div>
+                        <label for="email" class="form-label">{"Email address"}</label>
+                        <input type="email" class="form-control" id="email" name="email"
+                            value={self.code_info.as_ref().map(|info| info.email.clone())}
+                            aria-describedby="emailHelp" readonly=true disabled=true />
+                        {
+                            if self.code_info.as_ref().map_or(false, |info| info.invited) {
+                                html!{<div id="emailHelp" class="form-text">{"You have been invited with this email address."}</div>}
+                            } else {
+                                html!{}
+                            }
+                        }
+                    </div>
                     <div>
                         <label for="username" class="form-label">{"Username"}</label>
                         <input type="text" name="username"
@@ -247,7 +288,7 @@ impl RegistrationForm {
                             aria-describedby="lastNameHelp" required=true {oninput} />
                     </div>
                     <button type="submit" class="btn btn-primary" disabled={
-                        self.submitted || self.username.is_empty() ||
+                        self.submitted || self.code_info.is_none() || self.username.is_empty() ||
                         self.password.is_empty() || self.first_name.is_empty() ||
                         self.last_name.is_empty()}>{"Submit"}</button>
                 </form>
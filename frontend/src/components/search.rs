@@ -0,0 +1,155 @@
+//! Search box component.
+//!
+//! It searches the tickets visible to the logged in user while they type, and shows the best
+//! matches in a dropdown.
+
+use common::search::{FragmentDTO, TicketHitDTO};
+use reqwasm::http::Request;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, UrlSearchParams};
+use yew::prelude::*;
+
+/// Minimum number of characters needed to search.
+const MIN_LEN: usize = 2;
+
+/// Number of results shown in the dropdown.
+const SHOWN: usize = 8;
+
+/// Component messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The search text changed.
+    Input(String),
+    /// The results for a search text have been received.
+    Loaded(String, Vec<TicketHitDTO>),
+    /// The search box lost the focus.
+    Blur,
+}
+
+/// Search box component.
+#[derive(Debug, Default)]
+pub struct SearchBox {
+    query: String,
+    hits: Vec<TicketHitDTO>,
+    open: bool,
+}
+
+impl Component for SearchBox {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Input(query) => {
+                if query == self.query {
+                    return false;
+                }
+                self.query = query.clone();
+
+                if query.trim().chars().count() < MIN_LEN {
+                    self.hits.clear();
+                    self.open = false;
+
+                    return true;
+                }
+
+                ctx.link().send_future(async move {
+                    let hits = search(&query).await.unwrap_or_default();
+                    Msg::Loaded(query, hits)
+                });
+
+                false
+            }
+            Msg::Loaded(query, hits) => {
+                // Responses of outdated searches can arrive late
+                if query != self.query {
+                    return false;
+                }
+                self.hits = hits;
+                self.open = true;
+
+                true
+            }
+            Msg::Blur => {
+                self.open = false;
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let oninput = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(Msg::Input(target.value()))
+        });
+        let onblur = ctx.link().callback(|_: FocusEvent| Msg::Blur);
+
+        html! {
+            <li class="nav-item dropdown">
+                <input type="search" class="form-control" placeholder="Search tickets"
+                    aria-label="Search tickets" value={self.query.clone()} {oninput} {onblur} />
+                <ul class={classes!("dropdown-menu", self.open.then(|| "show"))}>
+                    {
+                        if self.hits.is_empty() {
+                            html! { <li><span class="dropdown-item-text">{"No tickets found"}</span></li> }
+                        } else {
+                            self.hits.iter().take(SHOWN).map(hit).collect::<Html>()
+                        }
+                    }
+                </ul>
+            </li>
+        }
+    }
+}
+
+/// Renders a search result.
+fn hit(hit: &TicketHitDTO) -> Html {
+    html! {
+        <li>
+            <span class="dropdown-item-text">
+                <div>{format!("#{} ", hit.number)}{highlight(&hit.title)}</div>
+                <small class="text-muted">{highlight(&hit.snippet)}</small>
+            </span>
+        </li>
+    }
+}
+
+/// Renders a highlighted text.
+fn highlight(fragments: &[FragmentDTO]) -> Html {
+    fragments
+        .iter()
+        .map(|fragment| {
+            if fragment.highlighted {
+                html! { <mark>{&fragment.text}</mark> }
+            } else {
+                html! { {&fragment.text} }
+            }
+        })
+        .collect()
+}
+
+/// Searches the tickets, returning `None` if the search failed.
+async fn search(query: &str) -> Option<Vec<TicketHitDTO>> {
+    let params = UrlSearchParams::new().ok()?;
+    params.append("q", query);
+    params.append("limit", &SHOWN.to_string());
+
+    let response = Request::get(&format!(
+        "/api/v1/search?{}",
+        String::from(params.to_string())
+    ))
+    .header("Accept", "application/json")
+    .send()
+    .await
+    .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    response.json().await.ok()
+}
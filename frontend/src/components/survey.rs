@@ -0,0 +1,201 @@
+//! Survey components.
+//!
+//! Requesters land on the survey link component from the links of the survey email sent when their
+//! ticket is resolved. Following a link records its rating right away, without logging in, and
+//! the requester can then add a comment.
+
+use common::survey::{Rating, SurveyAnswerDTO, SurveyDTO};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+/// Survey link properties.
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct SurveyLinkProps {
+    /// Token of the survey email.
+    pub token: String,
+    /// Rating of the followed link.
+    pub rating: String,
+}
+
+/// Survey link component messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The survey of the link has been loaded, if it hasn't expired.
+    Loaded(Option<SurveyDTO>),
+    /// The comment changed.
+    Comment(String),
+    /// The requester sent the answer.
+    Send,
+    /// The answer has been recorded.
+    Answered(SurveyDTO),
+    /// The answer could not be recorded.
+    Failed(String),
+}
+
+/// Survey link component, where requesters land from their survey email.
+#[derive(Debug, Default)]
+pub struct SurveyLink {
+    loaded: bool,
+    survey: Option<SurveyDTO>,
+    comment: String,
+    commented: bool,
+    error: Option<String>,
+}
+
+impl Component for SurveyLink {
+    type Message = Msg;
+    type Properties = SurveyLinkProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let url = format!("/api/v1/surveys/{}", ctx.props().token);
+        ctx.link()
+            .send_future(async move { Msg::Loaded(get_json(&url).await) });
+
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(survey) => {
+                self.loaded = true;
+                if let Some(survey) = &survey {
+                    // Following the link is the answer, keeping any comment already given
+                    self.comment = survey.comment.clone();
+                    self.send(ctx);
+                }
+                self.survey = survey;
+
+                true
+            }
+            Msg::Comment(comment) => {
+                self.comment = comment;
+
+                false
+            }
+            Msg::Send => {
+                self.commented = true;
+                self.send(ctx);
+
+                false
+            }
+            Msg::Answered(survey) => {
+                self.comment = survey.comment.clone();
+                self.survey = Some(survey);
+                self.error = None;
+
+                true
+            }
+            Msg::Failed(e) => {
+                self.error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if !self.loaded {
+            return html! {};
+        }
+        let survey = match &self.survey {
+            Some(survey) => survey,
+            None => {
+                return html! {
+                    <main class="container">
+                        <h1>{"Survey"}</h1>
+                        <p class="alert alert-warning">{"This link has expired."}</p>
+                    </main>
+                };
+            }
+        };
+        let thanks = match survey.rating {
+            _ if self.commented && self.error.is_none() => {
+                "Thank you for your feedback, it has been recorded."
+            }
+            Some(Rating::Good) => "Thank you, we're glad we could help!",
+            Some(Rating::Bad) => "Thank you, we're sorry we didn't do better.",
+            None => "",
+        };
+
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            Msg::Comment(e.target_unchecked_into::<HtmlTextAreaElement>().value())
+        });
+        let onclick = ctx.link().callback(|_| Msg::Send);
+
+        html! {
+            <main class="container">
+                <h1>{"Survey"}</h1>
+                <h5>{format!("#{} {}", survey.number, survey.title)}</h5>
+                { error(&self.error) }
+                if !thanks.is_empty() {
+                    <p class="alert alert-success">{thanks}</p>
+                }
+                <textarea class="form-control mb-3" placeholder="Tell us more (optional)"
+                    value={self.comment.clone()} {oninput} />
+                <button class="btn btn-primary" {onclick}>{"Send"}</button>
+            </main>
+        }
+    }
+}
+
+impl SurveyLink {
+    /// Sends the rating of the link with the current comment.
+    fn send(&self, ctx: &Context<Self>) {
+        let props = ctx.props();
+        let rating = match props.rating.parse::<Rating>() {
+            Ok(rating) => rating,
+            Err(_) => {
+                ctx.link()
+                    .send_message(Msg::Failed("This link is not valid.".to_owned()));
+                return;
+            }
+        };
+        let url = format!("/api/v1/surveys/{}", props.token);
+        let body = to_string(&SurveyAnswerDTO {
+            rating,
+            comment: self.comment.trim().to_owned(),
+        })
+        .expect("error serializing an answer");
+
+        ctx.link().send_future(async move {
+            let response = Request::post(&url)
+                .header("Accept", "application/json")
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .await;
+            match response {
+                Ok(response) => match response.json::<Result<SurveyDTO, String>>().await {
+                    Ok(Ok(survey)) => Msg::Answered(survey),
+                    Ok(Err(e)) => Msg::Failed(e),
+                    Err(_) => Msg::Failed("The answer could not be sent.".to_owned()),
+                },
+                Err(_) => Msg::Failed("The server could not be reached.".to_owned()),
+            }
+        });
+    }
+}
+
+/// Renders an error message, if any.
+fn error(error: &Option<String>) -> Html {
+    match error {
+        Some(e) => html! { <p class="alert alert-danger">{e}</p> },
+        None => html! {},
+    }
+}
+
+/// Gets a JSON resource, returning `None` if the request failed.
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+    let response = Request::get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    response.json().await.ok()
+}
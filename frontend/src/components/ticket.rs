@@ -0,0 +1,1135 @@
+//! Ticket components.
+//!
+//! Tickets are opened with a form, and shown with their details. Both render the custom fields of
+//! the category of the ticket, as defined by the administrators. The details also show the comments
+//! and the related tickets, which agents merge, split, break down and link.
+
+use crate::router::Route;
+use common::{
+    catalog::{FieldError, PickableUserDTO},
+    cmdb::ConfigurationItemDTO,
+    custom_field::{validate, CustomFieldDTO, Kind, Value, Values},
+    ticket::{
+        CommentDTO, LinkKind, MergeDTO, NewTicketDTO, ParentDTO, Priority, SplitDTO, TicketDTO,
+        TicketLinkDTO, TicketLinkFormDTO,
+    },
+    user::UserDTO,
+};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use std::collections::HashMap;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement, UrlSearchParams};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+/// New ticket form messages.
+#[derive(Debug)]
+pub enum FormMsg {
+    /// The custom fields have been loaded.
+    FieldsLoaded(Vec<CustomFieldDTO>),
+    Title(String),
+    Description(String),
+    Priority(Priority),
+    Category(String),
+    /// The value of a custom field changed.
+    Value(String, Option<Value>),
+    /// The text of a user field changed.
+    UserInput(String, String),
+    /// The users matching the text of a user field have been received.
+    Candidates(Vec<PickableUserDTO>),
+    /// The user wants to open the ticket.
+    Submit,
+    /// The ticket has been opened.
+    Opened(Box<TicketDTO>),
+    /// The ticket could not be opened.
+    Failed(String),
+}
+
+/// New ticket form component.
+#[derive(Debug, Default)]
+pub struct NewTicketForm {
+    fields: Vec<CustomFieldDTO>,
+    title: String,
+    description: String,
+    priority: Priority,
+    category: String,
+    values: Values,
+    /// Texts of the user fields, by key.
+    user_texts: HashMap<String, String>,
+    candidates: Vec<PickableUserDTO>,
+    errors: Vec<FieldError>,
+    error: Option<String>,
+}
+
+impl NewTicketForm {
+    /// Gets the category of the ticket, if any.
+    fn category(&self) -> Option<&str> {
+        Some(self.category.trim()).filter(|category| !category.is_empty())
+    }
+}
+
+impl Component for NewTicketForm {
+    type Message = FormMsg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            FormMsg::FieldsLoaded(
+                get_json::<Vec<_>>("/api/v1/custom-fields")
+                    .await
+                    .unwrap_or_default(),
+            )
+        });
+
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            FormMsg::FieldsLoaded(fields) => {
+                self.fields = fields;
+
+                true
+            }
+            FormMsg::Title(title) => {
+                self.title = title;
+
+                false
+            }
+            FormMsg::Description(description) => {
+                self.description = description;
+
+                false
+            }
+            FormMsg::Priority(priority) => {
+                self.priority = priority;
+
+                false
+            }
+            FormMsg::Category(category) => {
+                self.category = category;
+
+                true
+            }
+            FormMsg::Value(key, Some(value)) => {
+                let _ = self.values.insert(key, value);
+
+                true
+            }
+            FormMsg::Value(key, None) => {
+                let _ = self.values.remove(&key);
+
+                true
+            }
+            FormMsg::UserInput(key, text) => {
+                // Picked users are sent with their ID, other texts are left to validation
+                let value = match self.candidates.iter().find(|user| user.username == text) {
+                    Some(user) => user.id.to_string(),
+                    None => text.clone(),
+                };
+                let _ = self.values.insert(key.clone(), Value::Text(value));
+                let _ = self.user_texts.insert(key, text.clone());
+                ctx.link().send_future(async move {
+                    FormMsg::Candidates(pickable(&text).await.unwrap_or_default())
+                });
+
+                true
+            }
+            FormMsg::Candidates(candidates) => {
+                self.candidates = candidates;
+
+                true
+            }
+            FormMsg::Submit => {
+                // Values of the fields of other categories are dropped
+                let category = self.category().map(ToOwned::to_owned);
+                let category = category.as_deref();
+                let mut values = self.values.clone();
+                values.retain(|key, _| {
+                    self.fields
+                        .iter()
+                        .any(|field| field.key == *key && field.applies_to(category))
+                });
+                let values = match validate(&self.fields, category, &values) {
+                    Ok(values) => values,
+                    Err(errors) => {
+                        self.errors = errors;
+                        return true;
+                    }
+                };
+                self.errors.clear();
+
+                let body = to_string(&NewTicketDTO {
+                    title: &self.title,
+                    description: &self.description,
+                    priority: self.priority,
+                    category,
+                    organisation_id: None,
+                    queue_id: None,
+                    custom_fields: values,
+                })
+                .expect("could not serialize new ticket DTO to JSON");
+                ctx.link().send_future(async move {
+                    let response = Request::post("/api/v1/tickets")
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    match response {
+                        Ok(response) => match response.json::<Result<TicketDTO, String>>().await {
+                            Ok(Ok(ticket)) => FormMsg::Opened(Box::new(ticket)),
+                            Ok(Err(e)) => FormMsg::Failed(e),
+                            Err(_) => FormMsg::Failed("The ticket could not be sent.".to_owned()),
+                        },
+                        Err(_) => FormMsg::Failed("The server could not be reached.".to_owned()),
+                    }
+                });
+
+                false
+            }
+            FormMsg::Opened(ticket) => {
+                if let Some(history) = ctx.link().history() {
+                    history.push(Route::Ticket {
+                        id: ticket.id.to_string(),
+                    });
+                }
+
+                false
+            }
+            FormMsg::Failed(e) => {
+                self.error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let title = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(FormMsg::Title(target.value()))
+        });
+        let description = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlTextAreaElement>().ok()?;
+            Some(FormMsg::Description(target.value()))
+        });
+        let priority = ctx.link().batch_callback(|e: Event| {
+            let target = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+            target.value().parse().ok().map(FormMsg::Priority)
+        });
+        let category = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(FormMsg::Category(target.value()))
+        });
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            FormMsg::Submit
+        });
+        let category_value = self.category();
+
+        html! {
+            <main class="container">
+                <h1>{"New ticket"}</h1>
+                <form {onsubmit}>
+                    <div class="mb-3">
+                        <label class="form-label" for="ticket-title">{"Title"}</label>
+                        <input type="text" id="ticket-title" class="form-control" required=true
+                            value={self.title.clone()} oninput={title} />
+                    </div>
+                    <div class="mb-3">
+                        <label class="form-label" for="ticket-description">{"Description"}</label>
+                        <textarea id="ticket-description" class="form-control" rows="5"
+                            value={self.description.clone()} oninput={description} />
+                    </div>
+                    <div class="mb-3">
+                        <label class="form-label" for="ticket-priority">{"Priority"}</label>
+                        <select id="ticket-priority" class="form-select" onchange={priority}>
+                            {
+                                Priority::ALL.iter().map(|priority| html! {
+                                    <option value={priority.as_str()} selected={*priority == self.priority}>
+                                        {priority}
+                                    </option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </div>
+                    <div class="mb-3">
+                        <label class="form-label" for="ticket-category">{"Category"}</label>
+                        <input type="text" id="ticket-category" class="form-control"
+                            value={self.category.clone()} oninput={category} />
+                    </div>
+                    {
+                        self.fields
+                            .iter()
+                            .filter(|field| field.applies_to(category_value))
+                            .map(|field| self.field(ctx, field))
+                            .collect::<Html>()
+                    }
+                    <datalist id="pickable-users">
+                        {
+                            self.candidates.iter().map(|user| html! {
+                                <option value={user.username.clone()}>{&user.name}</option>
+                            }).collect::<Html>()
+                        }
+                    </datalist>
+                    <button type="submit" class="btn btn-primary">{"Open ticket"}</button>
+                    {
+                        match &self.error {
+                            Some(e) => html! { <div class="text-danger">{e}</div> },
+                            None => html! {},
+                        }
+                    }
+                </form>
+            </main>
+        }
+    }
+}
+
+impl NewTicketForm {
+    /// Renders a custom field of the form, with its validation error.
+    fn field(&self, ctx: &Context<Self>, field: &CustomFieldDTO) -> Html {
+        let id = format!("field-{}", field.key);
+        let error = self
+            .errors
+            .iter()
+            .find(|error| error.field == field.key)
+            .map(|error| error.message.clone());
+        let class = classes!("form-control", error.as_ref().map(|_| "is-invalid"));
+        let text = match self.values.get(&field.key) {
+            Some(Value::Text(text)) => text.clone(),
+            Some(Value::Number(number)) => number.to_string(),
+            None => String::new(),
+        };
+        let key = field.key.clone();
+
+        let input = match &field.kind {
+            Kind::String | Kind::Date => {
+                let kind = if field.kind == Kind::Date {
+                    "date"
+                } else {
+                    "text"
+                };
+                let oninput = ctx.link().batch_callback(move |e: InputEvent| {
+                    let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+                    Some(FormMsg::Value(
+                        key.clone(),
+                        Some(Value::Text(target.value())),
+                    ))
+                });
+                html! { <input type={kind} id={id.clone()} {class} value={text} {oninput} /> }
+            }
+            Kind::Number => {
+                let oninput = ctx.link().batch_callback(move |e: InputEvent| {
+                    let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+                    let number = target.value().trim().parse().ok();
+                    Some(FormMsg::Value(key.clone(), number.map(Value::Number)))
+                });
+                html! {
+                    <input type="number" step="any" id={id.clone()} {class} value={text} {oninput} />
+                }
+            }
+            Kind::Enum { options } => {
+                let onchange = ctx.link().batch_callback(move |e: Event| {
+                    let target = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+                    let value = target.value();
+                    Some(FormMsg::Value(
+                        key.clone(),
+                        (!value.is_empty()).then(|| Value::Text(value)),
+                    ))
+                });
+                html! {
+                    <select id={id.clone()} {class} {onchange}>
+                        <option value="" selected={text.is_empty()}>{"—"}</option>
+                        {
+                            options.iter().map(|option| html! {
+                                <option value={option.clone()} selected={*option == text}>{option}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                }
+            }
+            Kind::User => {
+                let oninput = ctx.link().batch_callback(move |e: InputEvent| {
+                    let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+                    Some(FormMsg::UserInput(key.clone(), target.value()))
+                });
+                let text = self.user_texts.get(&field.key).cloned().unwrap_or_default();
+                html! {
+                    <input type="search" list="pickable-users" id={id.clone()} {class} value={text}
+                        placeholder="Username" {oninput} />
+                }
+            }
+        };
+
+        html! {
+            <div class="mb-3">
+                <label class="form-label" for={id}>
+                    {&field.label}
+                    { if field.required { html! { <span class="text-danger">{" *"}</span> } } else { html! {} } }
+                </label>
+                {input}
+                {
+                    match error {
+                        Some(e) => html! { <div class="invalid-feedback d-block">{e}</div> },
+                        None => html! {},
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+/// Ticket detail properties.
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct TicketDetailProps {
+    /// ID of the ticket.
+    pub id: String,
+}
+
+/// Ticket detail messages.
+#[derive(Debug)]
+pub enum DetailMsg {
+    /// The ticket has been loaded, if it exists, along with the custom fields and the affected
+    /// configuration items.
+    Loaded(
+        Box<Option<TicketDTO>>,
+        Vec<CustomFieldDTO>,
+        Vec<ConfigurationItemDTO>,
+    ),
+    /// The comments and the related tickets have been loaded.
+    Related(Box<Relations>),
+    /// A user referenced by a custom field has been loaded.
+    User(PickableUserDTO),
+    /// The number of the other ticket of a new relationship changed.
+    Number(String),
+    /// The kind of the new relationship changed.
+    Relation(Relation),
+    /// The agent wants to relate the ticket with the other one.
+    Relate,
+    /// The agent wants to detach the ticket from its parent.
+    Detach,
+    /// The agent wants to unlink the ticket with the given ID.
+    Unlink(String),
+    /// The agent wants to split the comment with the given ID out, or changed their mind.
+    Splitting(Option<String>),
+    SplitTitle(String),
+    /// The agent wants to split the comment out into a new ticket.
+    Split,
+    /// The user wants to watch the ticket, or to stop watching it.
+    Watch(bool),
+    /// The ticket was changed, or could not be.
+    Done(Result<(), String>),
+}
+
+/// Relationship created by an agent between the ticket and another one, given by number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Relation {
+    /// The ticket is merged into the other one.
+    MergeInto,
+    /// The ticket is linked to the other one.
+    Link(LinkKind),
+    /// The other ticket becomes the parent of the ticket.
+    ChildOf,
+}
+
+impl Relation {
+    /// Relationships, in the order they are offered.
+    const ALL: [Self; 5] = [
+        Self::Link(LinkKind::Related),
+        Self::Link(LinkKind::DuplicateOf),
+        Self::Link(LinkKind::DuplicatedBy),
+        Self::ChildOf,
+        Self::MergeInto,
+    ];
+
+    /// Gets the value of the relationship in the form.
+    fn value(self) -> &'static str {
+        match self {
+            Self::MergeInto => "merge",
+            Self::Link(kind) => kind.as_str(),
+            Self::ChildOf => "parent",
+        }
+    }
+
+    /// Gets the label of the relationship.
+    fn label(self) -> &'static str {
+        match self {
+            Self::MergeInto => "Merge into",
+            Self::Link(kind) => link_label(kind),
+            Self::ChildOf => "Task of",
+        }
+    }
+}
+
+impl Default for Relation {
+    fn default() -> Self {
+        Self::Link(LinkKind::Related)
+    }
+}
+
+/// Comments of a ticket and its relationships with other tickets.
+#[derive(Debug, Default)]
+pub struct Relations {
+    comments: Vec<CommentDTO>,
+    parent: Option<TicketDTO>,
+    children: Vec<TicketDTO>,
+    links: Vec<TicketLinkDTO>,
+    watcher_ids: Vec<String>,
+    /// Logged in user.
+    user: Option<UserDTO>,
+}
+
+/// Ticket detail component.
+#[derive(Debug, Default)]
+pub struct TicketDetail {
+    loaded: bool,
+    ticket: Option<TicketDTO>,
+    fields: Vec<CustomFieldDTO>,
+    items: Vec<ConfigurationItemDTO>,
+    /// Names of the users referenced by custom fields, by ID.
+    users: HashMap<String, String>,
+    relations: Relations,
+    number: String,
+    relation: Relation,
+    /// ID of the comment being split out.
+    splitting: Option<String>,
+    split_title: String,
+    error: Option<String>,
+}
+
+impl Component for TicketDetail {
+    type Message = DetailMsg;
+    type Properties = TicketDetailProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self::load(ctx);
+
+        Self::default()
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        // Following a merge shows another ticket in the same component
+        *self = Self::default();
+        Self::load(ctx);
+
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            DetailMsg::Loaded(ticket, fields, items) => {
+                if let Some(ticket) = &*ticket {
+                    // Merged tickets lead to the ticket they were merged into
+                    if let (Some(into_id), Some(history)) =
+                        (ticket.merged_into_id, ctx.link().history())
+                    {
+                        history.replace(Route::Ticket {
+                            id: into_id.to_string(),
+                        });
+                        return false;
+                    }
+
+                    let user_ids = fields
+                        .iter()
+                        .filter(|field| field.kind == Kind::User)
+                        .filter_map(|field| match ticket.custom_fields.get(&field.key) {
+                            Some(Value::Text(id)) => Some(id.clone()),
+                            _ => None,
+                        });
+                    for id in user_ids {
+                        ctx.link().send_future_batch(async move {
+                            pickable(&id)
+                                .await
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(DetailMsg::User)
+                                .collect()
+                        });
+                    }
+
+                    let (url, parent_id) = (
+                        format!("/api/v1/tickets/{}", ticket.id),
+                        ticket.parent_id.map(|id| id.to_string()),
+                    );
+                    ctx.link().send_future(async move {
+                        DetailMsg::Related(Box::new(relations(url, parent_id).await))
+                    });
+                }
+                self.loaded = true;
+                self.ticket = *ticket;
+                self.fields = fields;
+                self.items = items;
+
+                true
+            }
+            DetailMsg::Related(relations) => {
+                self.relations = *relations;
+
+                true
+            }
+            DetailMsg::User(user) => {
+                let _ = self.users.insert(
+                    user.id.to_string(),
+                    format!("{} ({})", user.name, user.username),
+                );
+
+                true
+            }
+            DetailMsg::Number(number) => {
+                self.number = number;
+
+                false
+            }
+            DetailMsg::Relation(relation) => {
+                self.relation = relation;
+
+                false
+            }
+            DetailMsg::Relate => {
+                let number = match self.number.trim().trim_start_matches('#').parse::<i64>() {
+                    Ok(number) => number,
+                    Err(_) => {
+                        self.error = Some("Enter the number of the other ticket.".to_owned());
+                        return true;
+                    }
+                };
+                let (url, relation) = (ticket_url(ctx), self.relation);
+                ctx.link().send_future(async move {
+                    let other = match get_json::<Option<TicketDTO>>(&format!(
+                        "/api/v1/tickets/by-number?number={}",
+                        number
+                    ))
+                    .await
+                    .flatten()
+                    {
+                        Some(other) => other,
+                        None => {
+                            return DetailMsg::Done(Err(format!(
+                                "Ticket #{} doesn't exist.",
+                                number
+                            )))
+                        }
+                    };
+                    let request = match relation {
+                        Relation::MergeInto => Request::post(&format!("{}/merge", url))
+                            .body(to_json(&MergeDTO { into_id: other.id })),
+                        Relation::Link(kind) => Request::post(&format!("{}/links", url)).body(
+                            to_json(&TicketLinkFormDTO {
+                                ticket_id: other.id,
+                                kind,
+                            }),
+                        ),
+                        Relation::ChildOf => {
+                            Request::put(&format!("{}/parent", url)).body(to_json(&ParentDTO {
+                                parent_id: Some(other.id),
+                            }))
+                        }
+                    };
+                    DetailMsg::Done(send(request.header("Content-Type", "application/json")).await)
+                });
+
+                false
+            }
+            DetailMsg::Detach => {
+                let url = format!("{}/parent", ticket_url(ctx));
+                ctx.link().send_future(async move {
+                    let request = Request::put(&url)
+                        .header("Content-Type", "application/json")
+                        .body(to_json(&ParentDTO { parent_id: None }));
+                    DetailMsg::Done(send(request).await)
+                });
+
+                false
+            }
+            DetailMsg::Unlink(id) => {
+                let url = format!("{}/links/{}", ticket_url(ctx), id);
+                ctx.link()
+                    .send_future(async move { DetailMsg::Done(send(Request::delete(&url)).await) });
+
+                false
+            }
+            DetailMsg::Splitting(comment_id) => {
+                self.splitting = comment_id;
+                self.split_title.clear();
+
+                true
+            }
+            DetailMsg::SplitTitle(title) => {
+                self.split_title = title;
+
+                false
+            }
+            DetailMsg::Split => {
+                let comment_id = match &self.splitting {
+                    Some(comment_id) => comment_id,
+                    None => return false,
+                };
+                let url = format!("{}/comments/{}/split", ticket_url(ctx), comment_id);
+                let body = to_json(&SplitDTO {
+                    title: self.split_title.clone(),
+                });
+                ctx.link().send_future(async move {
+                    let request = Request::post(&url)
+                        .header("Content-Type", "application/json")
+                        .body(body);
+                    DetailMsg::Done(send(request).await)
+                });
+
+                false
+            }
+            DetailMsg::Watch(watch) => {
+                let url = format!("{}/watch", ticket_url(ctx));
+                ctx.link().send_future(async move {
+                    let request = if watch {
+                        Request::put(&url)
+                    } else {
+                        Request::delete(&url)
+                    };
+                    DetailMsg::Done(send(request).await)
+                });
+
+                false
+            }
+            DetailMsg::Done(Ok(())) => {
+                self.number.clear();
+                self.splitting = None;
+                self.error = None;
+                Self::load(ctx);
+
+                true
+            }
+            DetailMsg::Done(Err(e)) => {
+                self.error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if !self.loaded {
+            return html! { <main class="container"><p>{"Loading…"}</p></main> };
+        }
+        let ticket = match &self.ticket {
+            Some(ticket) => ticket,
+            None => {
+                return html! {
+                    <main class="container">
+                        <p class="alert alert-warning">{"This ticket doesn't exist."}</p>
+                    </main>
+                };
+            }
+        };
+        let watching = self
+            .relations
+            .user
+            .as_ref()
+            .map(|user| self.relations.watcher_ids.contains(&user.id.to_string()));
+
+        html! {
+            <main class="container">
+                <h1>
+                    {format!("#{} {}", ticket.number, ticket.title)}
+                    {
+                        match watching {
+                            Some(watching) => html! {
+                                <button type="button" class="btn btn-sm btn-outline-secondary ms-3"
+                                    onclick={ctx.link().callback(move |_| DetailMsg::Watch(!watching))}>
+                                    { if watching { "Stop watching" } else { "Watch" } }
+                                </button>
+                            },
+                            None => html! {},
+                        }
+                    }
+                </h1>
+                <dl class="row">
+                    <dt class="col-sm-3">{"Status"}</dt>
+                    <dd class="col-sm-9">{ticket.status}</dd>
+                    <dt class="col-sm-3">{"Priority"}</dt>
+                    <dd class="col-sm-9">{ticket.priority}</dd>
+                    <dt class="col-sm-3">{"Category"}</dt>
+                    <dd class="col-sm-9">{ticket.category.as_deref().unwrap_or("—")}</dd>
+                    {
+                        self.fields
+                            .iter()
+                            .filter_map(|field| {
+                                let value = ticket.custom_fields.get(&field.key)?;
+                                Some(html! {
+                                    <>
+                                        <dt class="col-sm-3">{&field.label}</dt>
+                                        <dd class="col-sm-9">{self.value(field, value)}</dd>
+                                    </>
+                                })
+                            })
+                            .collect::<Html>()
+                    }
+                    {
+                        if self.items.is_empty() {
+                            html! {}
+                        } else {
+                            html! {
+                                <>
+                                    <dt class="col-sm-3">{"Affected items"}</dt>
+                                    <dd class="col-sm-9">
+                                        {
+                                            self.items
+                                                .iter()
+                                                .map(|item| item.name.as_str())
+                                                .collect::<Vec<_>>()
+                                                .join(", ")
+                                        }
+                                    </dd>
+                                </>
+                            }
+                        }
+                    }
+                    {
+                        match &self.relations.parent {
+                            Some(parent) => html! {
+                                <>
+                                    <dt class="col-sm-3">{"Task of"}</dt>
+                                    <dd class="col-sm-9">
+                                        {ticket_link(parent)}
+                                        {
+                                            if self.is_staff() {
+                                                html! {
+                                                    <button type="button" class="btn btn-sm btn-link"
+                                                        onclick={ctx.link().callback(|_| DetailMsg::Detach)}>
+                                                        {"Detach"}
+                                                    </button>
+                                                }
+                                            } else {
+                                                html! {}
+                                            }
+                                        }
+                                    </dd>
+                                </>
+                            },
+                            None => html! {},
+                        }
+                    }
+                    <dt class="col-sm-3">{"Updated"}</dt>
+                    <dd class="col-sm-9">{ticket.updated_on.format("%Y-%m-%d %H:%M").to_string()}</dd>
+                </dl>
+                <p style="white-space: pre-line">{&ticket.description}</p>
+                {self.view_relations(ctx)}
+                {self.view_comments(ctx)}
+            </main>
+        }
+    }
+}
+
+impl TicketDetail {
+    /// Loads the ticket, with the custom fields and the affected configuration items.
+    fn load(ctx: &Context<Self>) {
+        let url = ticket_url(ctx);
+        ctx.link().send_future(async move {
+            let ticket = get_json(&url).await;
+            let fields = get_json("/api/v1/custom-fields").await.unwrap_or_default();
+            let items = get_json(&format!("{}/configuration-items", url))
+                .await
+                .unwrap_or_default();
+            DetailMsg::Loaded(Box::new(ticket), fields, items)
+        });
+    }
+
+    /// Checks if the logged in user is part of the support team.
+    fn is_staff(&self) -> bool {
+        matches!(&self.relations.user, Some(user) if user.role.is_staff())
+    }
+
+    /// Formats the value of a custom field, with the names of users.
+    fn value(&self, field: &CustomFieldDTO, value: &Value) -> String {
+        match (&field.kind, value) {
+            (Kind::User, Value::Text(id)) => {
+                self.users.get(id).cloned().unwrap_or_else(|| id.clone())
+            }
+            (_, Value::Text(text)) => text.clone(),
+            (_, Value::Number(number)) => number.to_string(),
+        }
+    }
+
+    /// Renders the child and linked tickets, with the form to relate the ticket to another one.
+    fn view_relations(&self, ctx: &Context<Self>) -> Html {
+        let Relations {
+            children, links, ..
+        } = &self.relations;
+        let staff = self.is_staff();
+        let number = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(DetailMsg::Number(target.value()))
+        });
+        let relation = ctx.link().batch_callback(|e: Event| {
+            let target = e.target()?.dyn_into::<HtmlSelectElement>().ok()?;
+            let value = target.value();
+            Relation::ALL
+                .into_iter()
+                .find(|relation| relation.value() == value)
+                .map(DetailMsg::Relation)
+        });
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            DetailMsg::Relate
+        });
+
+        html! {
+            <>
+                {
+                    if children.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <section class="mb-3">
+                                <h2 class="h5">{"Tasks"}</h2>
+                                <ul class="list-unstyled">
+                                    {
+                                        children.iter().map(|child| html! {
+                                            <li>{ticket_link(child)}{format!(" — {}", child.status)}</li>
+                                        }).collect::<Html>()
+                                    }
+                                </ul>
+                            </section>
+                        }
+                    }
+                }
+                {
+                    if links.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <section class="mb-3">
+                                <h2 class="h5">{"Linked tickets"}</h2>
+                                <ul class="list-unstyled">
+                                    {
+                                        links.iter().map(|link| {
+                                            let id = link.ticket.id.to_string();
+                                            html! {
+                                                <li>
+                                                    {format!("{} ", link_label(link.kind))}
+                                                    {ticket_link(&link.ticket)}
+                                                    {
+                                                        if staff {
+                                                            html! {
+                                                                <button type="button" class="btn btn-sm btn-link"
+                                                                    onclick={ctx.link().callback(move |_| DetailMsg::Unlink(id.clone()))}>
+                                                                    {"Unlink"}
+                                                                </button>
+                                                            }
+                                                        } else {
+                                                            html! {}
+                                                        }
+                                                    }
+                                                </li>
+                                            }
+                                        }).collect::<Html>()
+                                    }
+                                </ul>
+                            </section>
+                        }
+                    }
+                }
+                {
+                    if staff {
+                        html! {
+                            <form class="row g-2 mb-3" {onsubmit}>
+                                <div class="col-auto">
+                                    <select class="form-select" aria-label="Relationship" onchange={relation}>
+                                        {
+                                            Relation::ALL.into_iter().map(|relation| html! {
+                                                <option value={relation.value()} selected={relation == self.relation}>
+                                                    {relation.label()}
+                                                </option>
+                                            }).collect::<Html>()
+                                        }
+                                    </select>
+                                </div>
+                                <div class="col-auto">
+                                    <input type="text" class="form-control" placeholder="Ticket number"
+                                        aria-label="Ticket number" required=true
+                                        value={self.number.clone()} oninput={number} />
+                                </div>
+                                <div class="col-auto">
+                                    <button type="submit" class="btn btn-secondary">{"Apply"}</button>
+                                </div>
+                            </form>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    match &self.error {
+                        Some(e) => html! { <div class="text-danger mb-3">{e}</div> },
+                        None => html! {},
+                    }
+                }
+            </>
+        }
+    }
+
+    /// Renders the comments of the ticket, which agents can split out into new tickets.
+    fn view_comments(&self, ctx: &Context<Self>) -> Html {
+        let staff = self.is_staff();
+
+        html! {
+            <section>
+                <h2 class="h5">{"Comments"}</h2>
+                {
+                    self.relations.comments.iter().map(|comment| {
+                        let id = comment.id.to_string();
+                        let splitting = self.splitting.as_deref() == Some(id.as_str());
+                        html! {
+                            <article class="border-top py-2">
+                                <small class="text-muted">
+                                    {comment.created_on.format("%Y-%m-%d %H:%M").to_string()}
+                                </small>
+                                <p class="mb-1" style="white-space: pre-line">{&comment.body}</p>
+                                {
+                                    if splitting {
+                                        self.view_split_form(ctx)
+                                    } else if staff {
+                                        html! {
+                                            <button type="button" class="btn btn-sm btn-link p-0"
+                                                onclick={ctx.link().callback(move |_| DetailMsg::Splitting(Some(id.clone())))}>
+                                                {"Split out into a new ticket"}
+                                            </button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                            </article>
+                        }
+                    }).collect::<Html>()
+                }
+            </section>
+        }
+    }
+
+    /// Renders the form giving a title to the ticket a comment is split out into.
+    fn view_split_form(&self, ctx: &Context<Self>) -> Html {
+        let title = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(DetailMsg::SplitTitle(target.value()))
+        });
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            DetailMsg::Split
+        });
+
+        html! {
+            <form class="row g-2" {onsubmit}>
+                <div class="col">
+                    <input type="text" class="form-control form-control-sm" placeholder="Title of the new ticket"
+                        aria-label="Title of the new ticket" required=true
+                        value={self.split_title.clone()} oninput={title} />
+                </div>
+                <div class="col-auto">
+                    <button type="submit" class="btn btn-sm btn-primary">{"Split"}</button>
+                    <button type="button" class="btn btn-sm btn-link"
+                        onclick={ctx.link().callback(|_| DetailMsg::Splitting(None))}>
+                        {"Cancel"}
+                    </button>
+                </div>
+            </form>
+        }
+    }
+}
+
+/// Gets the API URL of the ticket of the detail.
+fn ticket_url(ctx: &Context<TicketDetail>) -> String {
+    format!("/api/v1/tickets/{}", ctx.props().id)
+}
+
+/// Loads the comments and the related tickets of a ticket, along with the logged in user.
+async fn relations(url: String, parent_id: Option<String>) -> Relations {
+    let parent = match parent_id {
+        Some(parent_id) => get_json(&format!("/api/v1/tickets/{}", parent_id)).await,
+        None => None,
+    };
+
+    Relations {
+        comments: get_json(&format!("{}/comments", url))
+            .await
+            .unwrap_or_default(),
+        parent,
+        children: get_json(&format!("{}/children", url))
+            .await
+            .unwrap_or_default(),
+        links: get_json(&format!("{}/links", url))
+            .await
+            .unwrap_or_default(),
+        watcher_ids: get_json(&format!("{}/watchers", url))
+            .await
+            .unwrap_or_default(),
+        user: get_json("/api/v1/login").await,
+    }
+}
+
+/// Renders a link to a ticket, with its number and title.
+fn ticket_link(ticket: &TicketDTO) -> Html {
+    html! {
+        <Link<Route> to={Route::Ticket { id: ticket.id.to_string() }}>
+            {format!("#{} {}", ticket.number, ticket.title)}
+        </Link<Route>>
+    }
+}
+
+/// Gets the label of a kind of link.
+fn link_label(kind: LinkKind) -> &'static str {
+    match kind {
+        LinkKind::Related => "Related to",
+        LinkKind::DuplicateOf => "Duplicate of",
+        LinkKind::DuplicatedBy => "Duplicated by",
+    }
+}
+
+/// Serializes the body of a request to JSON.
+fn to_json<T: serde::Serialize>(body: &T) -> String {
+    to_string(body).expect("could not serialize DTO to JSON")
+}
+
+/// Sends a request changing a ticket, returning the error given by the server if it failed.
+async fn send(request: Request) -> Result<(), String> {
+    let response = request
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|_| "The server could not be reached.".to_owned())?;
+    if response.ok() {
+        return Ok(());
+    }
+
+    match response.json::<Result<serde_json::Value, String>>().await {
+        Ok(Err(e)) => Err(e),
+        _ => Err("The change was rejected.".to_owned()),
+    }
+}
+
+/// Searches the users that can be picked, by name or ID, returning `None` if the request failed.
+async fn pickable(text: &str) -> Option<Vec<PickableUserDTO>> {
+    let params = UrlSearchParams::new().ok()?;
+    params.append("q", text);
+
+    get_json(&format!(
+        "/api/v1/catalog/users?{}",
+        String::from(params.to_string())
+    ))
+    .await
+}
+
+/// Gets a JSON resource, returning `None` if the request failed.
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+    let response = Request::get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    response.json().await.ok()
+}
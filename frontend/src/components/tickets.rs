@@ -0,0 +1,331 @@
+//! Ticket list component.
+//!
+//! It lists the tickets matching a query, validating and completing the query while the user
+//! types. Saved views are listed in a sidebar, and the current query can be saved as a new view.
+
+use crate::router::Route;
+use common::{
+    query::{complete, Query},
+    ticket::TicketDTO,
+    view::{ViewDTO, ViewFormDTO},
+};
+use reqwasm::http::Request;
+use serde_json::to_string;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, UrlSearchParams};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+/// Component messages.
+#[derive(Debug)]
+pub enum Msg {
+    /// The saved views have been loaded.
+    ViewsLoaded(Vec<ViewDTO>),
+    /// The query text changed.
+    Input(String),
+    /// The user wants to run the current query.
+    Run,
+    /// The user selected a saved view.
+    Select(ViewDTO),
+    /// The tickets for a query have been received.
+    Loaded(String, Vec<TicketDTO>),
+    /// The name of the view to save changed.
+    Name(String),
+    /// The user wants the view to save to be shared, or not.
+    Shared(bool),
+    /// The user wants to save the current query as a view.
+    Save,
+    /// The view has been saved, or the reason why it wasn't.
+    Saved(Result<ViewDTO, String>),
+}
+
+/// Ticket list component.
+#[derive(Debug, Default)]
+pub struct Tickets {
+    views: Vec<ViewDTO>,
+    query: String,
+    error: Option<String>,
+    completions: Vec<String>,
+    ran_query: String,
+    tickets: Vec<TicketDTO>,
+    name: String,
+    shared: bool,
+    save_error: Option<String>,
+}
+
+impl Component for Tickets {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(async {
+            let views = get_json("/api/v1/views").await.unwrap_or_default();
+            Msg::ViewsLoaded(views)
+        });
+        ctx.link().send_message(Msg::Run);
+
+        Self::default()
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::ViewsLoaded(views) => {
+                self.views = views;
+
+                true
+            }
+            Msg::Input(query) => {
+                self.error = query.parse::<Query>().err().map(|e| e.to_string());
+                self.completions = complete(&query);
+                self.query = query;
+
+                true
+            }
+            Msg::Run => {
+                if self.error.is_some() {
+                    return false;
+                }
+                let query = self.query.clone();
+                ctx.link().send_future(async move {
+                    let tickets = search(&query).await.unwrap_or_default();
+                    Msg::Loaded(query, tickets)
+                });
+
+                false
+            }
+            Msg::Select(view) => {
+                self.query = view.query;
+                self.error = None;
+                self.completions.clear();
+                let (id, query) = (view.id, self.query.clone());
+                ctx.link().send_future(async move {
+                    let tickets = get_json(&format!("/api/v1/views/{}/tickets", id))
+                        .await
+                        .unwrap_or_default();
+                    Msg::Loaded(query, tickets)
+                });
+
+                true
+            }
+            Msg::Loaded(query, tickets) => {
+                self.ran_query = query;
+                self.tickets = tickets;
+
+                true
+            }
+            Msg::Name(name) => {
+                self.name = name;
+
+                false
+            }
+            Msg::Shared(shared) => {
+                self.shared = shared;
+
+                false
+            }
+            Msg::Save => {
+                let body = to_string(&ViewFormDTO {
+                    name: &self.name,
+                    query: &self.query,
+                    shared: self.shared,
+                })
+                .expect("could not serialize view form DTO to JSON");
+                ctx.link().send_future(async move {
+                    let response = Request::post("/api/v1/views")
+                        .header("Accept", "application/json")
+                        .header("Content-Type", "application/json")
+                        .body(body)
+                        .send()
+                        .await;
+                    let saved = match response {
+                        Ok(response) => response
+                            .json()
+                            .await
+                            .unwrap_or_else(|_| Err("The view could not be saved.".to_owned())),
+                        Err(_) => Err("The server could not be reached.".to_owned()),
+                    };
+                    Msg::Saved(saved)
+                });
+
+                false
+            }
+            Msg::Saved(Ok(view)) => {
+                self.save_error = None;
+                self.name.clear();
+                self.views.push(view);
+                self.views.sort_by(|a, b| a.name.cmp(&b.name));
+
+                true
+            }
+            Msg::Saved(Err(e)) => {
+                self.save_error = Some(e);
+
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let oninput = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(Msg::Input(target.value()))
+        });
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            Msg::Run
+        });
+
+        html! {
+            <main class="container-fluid">
+                <div class="row">
+                    <aside class="col-md-3">
+                        <h5>{"Views"}</h5>
+                        <ul class="nav flex-column">
+                            { self.views.iter().map(|view| self.view_link(ctx, view)).collect::<Html>() }
+                        </ul>
+                        { self.save_form(ctx) }
+                    </aside>
+                    <section class="col-md-9">
+                        <form {onsubmit}>
+                            <input type="search" list="query-completions"
+                                class={classes!("form-control", self.error.as_ref().map(|_| "is-invalid"))}
+                                placeholder="status:open assignee:me priority>=high"
+                                aria-label="Ticket query" value={self.query.clone()} {oninput} />
+                            <datalist id="query-completions">
+                                { self.completions.iter().map(|completion| html! { <option value={completion.clone()} /> }).collect::<Html>() }
+                            </datalist>
+                            {
+                                match &self.error {
+                                    Some(e) => html! { <div class="invalid-feedback">{e}</div> },
+                                    None => html! {},
+                                }
+                            }
+                        </form>
+                        { self.results() }
+                    </section>
+                </div>
+            </main>
+        }
+    }
+}
+
+impl Tickets {
+    /// Renders the link to a saved view.
+    fn view_link(&self, ctx: &Context<Self>, view: &ViewDTO) -> Html {
+        let active = view.query == self.ran_query;
+        let selected = view.clone();
+        let onclick = ctx.link().callback(move |e: MouseEvent| {
+            e.prevent_default();
+            Msg::Select(selected.clone())
+        });
+
+        html! {
+            <li class="nav-item">
+                <a href="#" class={classes!("nav-link", active.then(|| "active"))} {onclick}>
+                    {&view.name}
+                    { if view.shared { html! { <small class="text-muted">{" (shared)"}</small> } } else { html! {} } }
+                </a>
+            </li>
+        }
+    }
+
+    /// Renders the form to save the current query as a view.
+    fn save_form(&self, ctx: &Context<Self>) -> Html {
+        let oninput = ctx.link().batch_callback(|e: InputEvent| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(Msg::Name(target.value()))
+        });
+        let onchange = ctx.link().batch_callback(|e: Event| {
+            let target = e.target()?.dyn_into::<HtmlInputElement>().ok()?;
+            Some(Msg::Shared(target.checked()))
+        });
+        let onsubmit = ctx.link().callback(|e: FocusEvent| {
+            e.prevent_default();
+            Msg::Save
+        });
+
+        html! {
+            <form class="mt-3" {onsubmit}>
+                <input type="text" class="form-control" placeholder="View name" aria-label="View name"
+                    value={self.name.clone()} {oninput} />
+                <div class="form-check">
+                    <input type="checkbox" class="form-check-input" id="view-shared" {onchange} />
+                    <label class="form-check-label" for="view-shared">{"Share with everyone"}</label>
+                </div>
+                <button type="submit" class="btn btn-secondary" disabled={self.error.is_some()}>
+                    {"Save view"}
+                </button>
+                {
+                    match &self.save_error {
+                        Some(e) => html! { <div class="text-danger">{e}</div> },
+                        None => html! {},
+                    }
+                }
+            </form>
+        }
+    }
+
+    /// Renders the tickets of the last query.
+    fn results(&self) -> Html {
+        if self.tickets.is_empty() {
+            return html! { <p class="mt-3">{"No tickets found"}</p> };
+        }
+
+        html! {
+            <table class="table mt-3">
+                <thead>
+                    <tr>
+                        <th>{"#"}</th>
+                        <th>{"Title"}</th>
+                        <th>{"Status"}</th>
+                        <th>{"Priority"}</th>
+                        <th>{"Updated"}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {
+                        self.tickets.iter().map(|ticket| html! {
+                            <tr>
+                                <td>{ticket.number}</td>
+                                <td>
+                                    <Link<Route> to={Route::Ticket { id: ticket.id.to_string() }}>
+                                        {&ticket.title}
+                                    </Link<Route>>
+                                </td>
+                                <td>{ticket.status}</td>
+                                <td>{ticket.priority}</td>
+                                <td>{ticket.updated_on.format("%Y-%m-%d %H:%M").to_string()}</td>
+                            </tr>
+                        }).collect::<Html>()
+                    }
+                </tbody>
+            </table>
+        }
+    }
+}
+
+/// Lists the tickets matching a query, returning `None` if the request failed.
+async fn search(query: &str) -> Option<Vec<TicketDTO>> {
+    let params = UrlSearchParams::new().ok()?;
+    params.append("q", query);
+
+    get_json(&format!(
+        "/api/v1/tickets?{}",
+        String::from(params.to_string())
+    ))
+    .await
+}
+
+/// Gets a JSON resource, returning `None` if the request failed.
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Option<T> {
+    let response = Request::get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+
+    response.json().await.ok()
+}
@@ -13,6 +13,30 @@ pub enum Route {
     EmailRegistration,
     #[at("/login")]
     Login,
+    #[at("/approvals/:token")]
+    ApprovalLink { token: String },
+    #[at("/approvals")]
+    Approvals,
+    #[at("/surveys/:token/:rating")]
+    SurveyLink { token: String, rating: String },
+    #[at("/board")]
+    Board,
+    #[at("/catalog/:id")]
+    CatalogItem { id: String },
+    #[at("/catalog")]
+    Catalog,
+    #[at("/tickets/new")]
+    NewTicket,
+    #[at("/tickets/:id")]
+    Ticket { id: String },
+    #[at("/tickets")]
+    Tickets,
+    #[at("/portal")]
+    Portal,
+    #[at("/portal/*")]
+    PortalPage,
+    #[at("/settings/notifications")]
+    NotificationSettings,
     #[at("/")]
     Home,
 }
@@ -28,6 +52,39 @@ pub fn switch(switch: &Route) -> Html {
         Route::Login => {
             html! { <Login /> }
         }
+        Route::ApprovalLink { token } => {
+            html! { <ApprovalLink token={token.clone()} /> }
+        }
+        Route::Approvals => {
+            html! { <Approvals /> }
+        }
+        Route::SurveyLink { token, rating } => {
+            html! { <SurveyLink token={token.clone()} rating={rating.clone()} /> }
+        }
+        Route::Board => {
+            html! { <Board /> }
+        }
+        Route::CatalogItem { id } => {
+            html! { <RequestForm id={id.clone()} /> }
+        }
+        Route::Catalog => {
+            html! { <Catalog /> }
+        }
+        Route::NewTicket => {
+            html! { <NewTicketForm /> }
+        }
+        Route::Ticket { id } => {
+            html! { <TicketDetail id={id.clone()} /> }
+        }
+        Route::Tickets => {
+            html! { <Tickets /> }
+        }
+        Route::Portal | Route::PortalPage => {
+            html! { <Portal /> }
+        }
+        Route::NotificationSettings => {
+            html! { <NotificationSettings /> }
+        }
         Route::Home => {
             html! { <Home /> }
         }
@@ -0,0 +1,8 @@
+-- Drop `sys_invitation` table
+DROP TABLE sys_invitation;
+
+-- Drop `sys_session` table
+DROP TABLE sys_session;
+
+-- Remove the role of users
+ALTER TABLE sys_user DROP COLUMN role;
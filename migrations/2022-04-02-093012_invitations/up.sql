@@ -0,0 +1,23 @@
+-- Add the role of each user
+ALTER TABLE sys_user
+    ADD COLUMN role VARCHAR(10) NOT NULL DEFAULT 'customer'
+        CHECK (role IN ('customer', 'agent', 'admin'));
+
+-- Create `sys_session` table
+--
+-- Sessions are found by the SHA3-256 hash of their token, so that a leaked table can't be used to
+-- log in.
+CREATE TABLE sys_session (
+    token_hash BYTEA NOT NULL PRIMARY KEY,
+    user_id uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create `sys_invitation` table
+CREATE TABLE sys_invitation (
+    code CHAR(10) NOT NULL PRIMARY KEY,
+    email VARCHAR(50) NOT NULL UNIQUE CHECK (email LIKE '%@%'), -- Emails must have an @ symbol
+    role VARCHAR(10) NOT NULL CHECK (role IN ('customer', 'agent', 'admin')),
+    invited_by uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,5 @@
+-- Drop `sys_audit_log` table
+DROP TABLE sys_audit_log;
+
+-- Drop the append-only trigger function
+DROP FUNCTION sys_audit_log_append_only();
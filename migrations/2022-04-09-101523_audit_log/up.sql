@@ -0,0 +1,39 @@
+-- Create `sys_audit_log` table
+--
+-- The actor is not a foreign key, since the log must outlive the users it references.
+CREATE TABLE sys_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    kind VARCHAR(30) NOT NULL CHECK (kind IN (
+        'login_success',
+        'login_failure',
+        'registration',
+        'email_registration_deleted',
+        'invitation_created',
+        'invitation_revoked',
+        'role_change',
+        'ticket_field_change'
+    )),
+    actor_id uuid,
+    ip_address VARCHAR(45),
+    user_agent TEXT,
+    request_id VARCHAR(64) NOT NULL,
+    details JSONB NOT NULL,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX sys_audit_log_kind_idx ON sys_audit_log (kind);
+CREATE INDEX sys_audit_log_actor_idx ON sys_audit_log (actor_id);
+CREATE INDEX sys_audit_log_created_on_idx ON sys_audit_log (created_on);
+
+-- The audit log is append-only
+CREATE FUNCTION sys_audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'the audit log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER sys_audit_log_no_update_delete BEFORE UPDATE OR DELETE ON sys_audit_log
+    FOR EACH ROW EXECUTE PROCEDURE sys_audit_log_append_only();
+
+CREATE TRIGGER sys_audit_log_no_truncate BEFORE TRUNCATE ON sys_audit_log
+    FOR EACH STATEMENT EXECUTE PROCEDURE sys_audit_log_append_only();
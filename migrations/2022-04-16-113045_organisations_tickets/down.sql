@@ -0,0 +1,19 @@
+-- Drop `ticket` table
+DROP TABLE ticket;
+
+-- Drop the row-level security role
+REVOKE my_support_tenant FROM CURRENT_USER;
+DROP ROLE IF EXISTS my_support_tenant;
+
+-- Remove the organisation of registrations and invitations
+ALTER TABLE sys_invitation DROP COLUMN organisation_id;
+ALTER TABLE sys_email_registration DROP COLUMN organisation_id;
+
+-- Drop `organisation_member` table
+DROP TABLE organisation_member;
+
+-- Drop `organisation_domain` table
+DROP TABLE organisation_domain;
+
+-- Drop `organisation` table
+DROP TABLE organisation;
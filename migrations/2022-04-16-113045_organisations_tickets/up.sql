@@ -0,0 +1,81 @@
+-- Create `organisation` table
+CREATE TABLE organisation (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create `organisation_domain` table
+--
+-- Users registering with an email in one of these domains automatically become members.
+CREATE TABLE organisation_domain (
+    domain VARCHAR(50) NOT NULL PRIMARY KEY CHECK (domain = lower(domain)),
+    organisation_id uuid NOT NULL REFERENCES organisation (id) ON DELETE CASCADE
+);
+
+-- Create `organisation_member` table
+CREATE TABLE organisation_member (
+    organisation_id uuid NOT NULL REFERENCES organisation (id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (organisation_id, user_id)
+);
+
+CREATE INDEX organisation_member_user_idx ON organisation_member (user_id);
+
+-- Registrations and invitations remember the organisation of the future user
+ALTER TABLE sys_email_registration
+    ADD COLUMN organisation_id uuid REFERENCES organisation (id) ON DELETE SET NULL;
+ALTER TABLE sys_invitation
+    ADD COLUMN organisation_id uuid REFERENCES organisation (id) ON DELETE CASCADE;
+
+-- Create `ticket` table
+CREATE TABLE ticket (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    number BIGSERIAL NOT NULL UNIQUE,
+    title VARCHAR(200) NOT NULL,
+    description TEXT NOT NULL,
+    status VARCHAR(10) NOT NULL DEFAULT 'new'
+        CHECK (status IN ('new', 'open', 'pending', 'resolved', 'closed')),
+    priority VARCHAR(10) NOT NULL DEFAULT 'normal'
+        CHECK (priority IN ('low', 'normal', 'high', 'urgent')),
+    requester_id uuid NOT NULL REFERENCES sys_user (id),
+    assignee_id uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    organisation_id uuid REFERENCES organisation (id),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX ticket_organisation_idx ON ticket (organisation_id);
+CREATE INDEX ticket_requester_idx ON ticket (requester_id);
+CREATE INDEX ticket_assignee_idx ON ticket (assignee_id);
+
+-- Row-level security
+--
+-- When the `ROW_LEVEL_SECURITY` environment variable is set, the backend switches to the
+-- `my_support_tenant` role for ticket queries, and sets the `my_support.user_id`,
+-- `my_support.user_role` and `my_support.organisation_ids` settings for the transaction. The
+-- policy then only lets customers see the tickets they requested or those of their organisations.
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT FROM pg_roles WHERE rolname = 'my_support_tenant') THEN
+        CREATE ROLE my_support_tenant NOLOGIN;
+    END IF;
+END
+$$;
+
+GRANT my_support_tenant TO CURRENT_USER;
+GRANT SELECT, INSERT, UPDATE ON ticket TO my_support_tenant;
+GRANT USAGE ON SEQUENCE ticket_number_seq TO my_support_tenant;
+
+ALTER TABLE ticket ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY ticket_tenant_isolation ON ticket TO my_support_tenant
+    USING (
+        current_setting('my_support.user_role', true) IN ('agent', 'admin')
+        OR requester_id = nullif(current_setting('my_support.user_id', true), '')::uuid
+        OR organisation_id = ANY (
+            string_to_array(nullif(current_setting('my_support.organisation_ids', true), ''), ',')::uuid[]
+        )
+    );
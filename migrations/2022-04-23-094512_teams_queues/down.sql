@@ -0,0 +1,14 @@
+-- Drop `ticket_assignment` table
+DROP TABLE ticket_assignment;
+
+-- Remove the queue of tickets
+ALTER TABLE ticket DROP COLUMN queue_id;
+
+-- Drop `queue` table
+DROP TABLE queue;
+
+-- Drop `team_member` table
+DROP TABLE team_member;
+
+-- Drop `team` table
+DROP TABLE team;
@@ -0,0 +1,63 @@
+-- Create `team` table
+CREATE TABLE team (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create `team_member` table
+--
+-- The skills of the agents are used by the skills-based assignment strategy.
+CREATE TABLE team_member (
+    team_id uuid NOT NULL REFERENCES team (id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    skills TEXT[] NOT NULL DEFAULT '{}',
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (team_id, user_id)
+);
+
+CREATE INDEX team_member_user_idx ON team_member (user_id);
+
+-- Create `queue` table
+--
+-- Tickets entering a queue are assigned to the members of its team, following the assignment
+-- strategy of the queue. New tickets without a queue go to the default queue, if there is one.
+CREATE TABLE queue (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    team_id uuid NOT NULL REFERENCES team (id) ON DELETE CASCADE,
+    strategy VARCHAR(15) NOT NULL DEFAULT 'manual'
+        CHECK (strategy IN ('manual', 'round_robin', 'least_loaded', 'skills_based')),
+    skills TEXT[] NOT NULL DEFAULT '{}',
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    last_assignee_id uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX queue_default_idx ON queue (is_default) WHERE is_default;
+
+-- Tickets belong to a queue
+ALTER TABLE ticket
+    ADD COLUMN queue_id uuid REFERENCES queue (id) ON DELETE SET NULL;
+
+CREATE INDEX ticket_queue_idx ON ticket (queue_id);
+
+-- Create `ticket_assignment` table
+--
+-- History of the assignment changes of tickets. The strategy is only set for automatic
+-- assignments, and the user that made the change only for manual ones.
+CREATE TABLE ticket_assignment (
+    id BIGSERIAL PRIMARY KEY,
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    previous_assignee_id uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    assignee_id uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    queue_id uuid REFERENCES queue (id) ON DELETE SET NULL,
+    strategy VARCHAR(15)
+        CHECK (strategy IN ('round_robin', 'least_loaded', 'skills_based')),
+    assigned_by uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX ticket_assignment_ticket_idx ON ticket_assignment (ticket_id, id);
@@ -0,0 +1,10 @@
+-- Drop `automation_log` table
+DROP TABLE automation_log;
+
+-- Drop `automation_rule` table
+DROP TABLE automation_rule;
+
+-- Remove the category and tags of tickets
+ALTER TABLE ticket
+    DROP COLUMN tags,
+    DROP COLUMN category;
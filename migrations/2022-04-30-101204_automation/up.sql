@@ -0,0 +1,40 @@
+-- Tickets have a category and tags, which automation rules can match and change
+ALTER TABLE ticket
+    ADD COLUMN category VARCHAR(50),
+    ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}';
+
+-- Create `automation_rule` table
+--
+-- Rules are evaluated in order of position on every ticket event. Conditions and actions are
+-- stored as the JSON serialization of the `common::automation` types.
+CREATE TABLE automation_rule (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    position INTEGER NOT NULL DEFAULT 0,
+    condition JSONB NOT NULL,
+    actions JSONB NOT NULL,
+    created_by uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX automation_rule_position_idx ON automation_rule (position, name);
+
+-- Create `automation_log` table
+--
+-- Execution log of the rules, with the actions run and the error of the side effects (emails and
+-- webhooks), if any. The depth is the number of cascaded ticket updates caused by automations.
+CREATE TABLE automation_log (
+    id BIGSERIAL PRIMARY KEY,
+    rule_id uuid NOT NULL REFERENCES automation_rule (id) ON DELETE CASCADE,
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    event VARCHAR(10) NOT NULL CHECK (event IN ('created', 'updated')),
+    depth SMALLINT NOT NULL,
+    actions JSONB NOT NULL,
+    error TEXT,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX automation_log_rule_idx ON automation_log (rule_id, id);
+CREATE INDEX automation_log_ticket_idx ON automation_log (ticket_id, id);
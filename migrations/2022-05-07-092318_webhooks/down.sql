@@ -0,0 +1,5 @@
+-- Drop `webhook_delivery` table
+DROP TABLE webhook_delivery;
+
+-- Drop `webhook_subscription` table
+DROP TABLE webhook_subscription;
@@ -0,0 +1,39 @@
+-- Create `webhook_subscription` table
+--
+-- Subscriptions receive the ticket events in their filter, or all of them if the filter is empty.
+-- Payloads are signed with the secret of the subscription. Subscriptions are disabled after too
+-- many consecutive failed delivery attempts.
+CREATE TABLE webhook_subscription (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    url VARCHAR(2048) NOT NULL,
+    events TEXT[] NOT NULL DEFAULT '{}',
+    secret VARCHAR(128) NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    created_by uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create `webhook_delivery` table
+--
+-- Deliveries are pending until the subscriber accepts them, or until they fail too many times.
+-- The response code and error are the ones of the last attempt.
+CREATE TABLE webhook_delivery (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id uuid NOT NULL REFERENCES webhook_subscription (id) ON DELETE CASCADE,
+    event VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(10) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    response_code SMALLINT,
+    error TEXT,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX webhook_delivery_subscription_idx ON webhook_delivery (subscription_id, id);
+CREATE INDEX webhook_delivery_pending_idx ON webhook_delivery (next_attempt_on)
+    WHERE status = 'pending';
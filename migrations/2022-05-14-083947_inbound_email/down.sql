@@ -0,0 +1,8 @@
+-- Drop `inbound_email` table
+DROP TABLE inbound_email;
+
+-- Drop `ticket_attachment` table
+DROP TABLE ticket_attachment;
+
+-- Drop `ticket_comment` table
+DROP TABLE ticket_comment;
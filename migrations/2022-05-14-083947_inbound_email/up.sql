@@ -0,0 +1,47 @@
+-- Create `ticket_comment` table
+--
+-- Access to comments is checked through their ticket.
+CREATE TABLE ticket_comment (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    author_id uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    source VARCHAR(10) NOT NULL DEFAULT 'web' CHECK (source IN ('web', 'email')),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX ticket_comment_ticket_idx ON ticket_comment (ticket_id, created_on);
+
+-- Create `ticket_attachment` table
+--
+-- Attachments belong to a ticket, and to the comment they were sent with, if any.
+CREATE TABLE ticket_attachment (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    comment_id uuid REFERENCES ticket_comment (id) ON DELETE CASCADE,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size INTEGER NOT NULL,
+    data BYTEA NOT NULL,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX ticket_attachment_ticket_idx ON ticket_attachment (ticket_id);
+
+-- Create `inbound_email` table
+--
+-- Log of the received emails and the outcome of their ingestion. Message IDs are used to detect
+-- duplicates, and to match replies to the ticket of the original message.
+CREATE TABLE inbound_email (
+    id BIGSERIAL PRIMARY KEY,
+    message_id VARCHAR(998) UNIQUE,
+    from_address VARCHAR(320) NOT NULL,
+    subject TEXT NOT NULL,
+    outcome VARCHAR(10) NOT NULL CHECK (outcome IN ('created', 'commented', 'ignored', 'rejected')),
+    reason TEXT,
+    ticket_id uuid REFERENCES ticket (id) ON DELETE SET NULL,
+    comment_id uuid REFERENCES ticket_comment (id) ON DELETE SET NULL,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX inbound_email_from_idx ON inbound_email (from_address, created_on);
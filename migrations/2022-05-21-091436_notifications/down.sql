@@ -0,0 +1,5 @@
+-- Drop `notification` table
+DROP TABLE notification;
+
+-- Drop the push trigger function
+DROP FUNCTION notification_push();
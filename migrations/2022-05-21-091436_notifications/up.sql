@@ -0,0 +1,30 @@
+-- Create `notification` table
+--
+-- In-app notifications of each user. Bodies are limited, so that new notifications fit in the
+-- payload of a `NOTIFY` message.
+CREATE TABLE notification (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL
+        CHECK (kind IN ('ticket.assigned', 'ticket.commented', 'ticket.status_changed')),
+    title VARCHAR(200) NOT NULL,
+    body VARCHAR(1000) NOT NULL DEFAULT '',
+    ticket_id uuid REFERENCES ticket (id) ON DELETE CASCADE,
+    read_on TIMESTAMP WITH TIME ZONE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX notification_user_idx ON notification (user_id, created_on);
+CREATE INDEX notification_unread_idx ON notification (user_id) WHERE read_on IS NULL;
+
+-- New notifications are pushed to all the application instances listening on the `notification`
+-- channel
+CREATE FUNCTION notification_push() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('notification', row_to_json(NEW)::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notification_push AFTER INSERT ON notification
+    FOR EACH ROW EXECUTE PROCEDURE notification_push();
@@ -0,0 +1,7 @@
+-- Remove the email delivery of notifications
+ALTER TABLE notification
+    DROP COLUMN emailed_on,
+    DROP COLUMN email;
+
+-- Drop `notification_preference` table
+DROP TABLE notification_preference;
@@ -0,0 +1,22 @@
+-- Create `notification_preference` table
+--
+-- Users without a preference for a kind of notification only receive it in the application.
+CREATE TABLE notification_preference (
+    user_id uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL
+        CHECK (kind IN ('ticket.assigned', 'ticket.commented', 'ticket.status_changed')),
+    delivery VARCHAR(10) NOT NULL CHECK (delivery IN ('immediate', 'digest', 'in_app', 'off')),
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, kind)
+);
+
+-- Add the email delivery of notifications
+--
+-- Notifications to be sent by email, immediately or in the daily digest, are pending until
+-- `emailed_on` is set.
+ALTER TABLE notification
+    ADD COLUMN email VARCHAR(10) CHECK (email IN ('immediate', 'digest')),
+    ADD COLUMN emailed_on TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX notification_email_pending_idx ON notification (email, created_on)
+    WHERE email IS NOT NULL AND emailed_on IS NULL;
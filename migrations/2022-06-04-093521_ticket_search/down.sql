@@ -0,0 +1,8 @@
+-- Remove tenant isolation from ticket comments
+DROP POLICY ticket_comment_tenant_isolation ON ticket_comment;
+ALTER TABLE ticket_comment DISABLE ROW LEVEL SECURITY;
+REVOKE SELECT ON ticket_comment FROM my_support_tenant;
+
+-- Remove full-text search documents
+ALTER TABLE ticket_comment DROP COLUMN search;
+ALTER TABLE ticket DROP COLUMN search;
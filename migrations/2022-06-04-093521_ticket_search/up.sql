@@ -0,0 +1,22 @@
+-- Add full-text search documents to tickets, weighting titles above descriptions
+ALTER TABLE ticket ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', title), 'A') ||
+    setweight(to_tsvector('english', description), 'B')
+) STORED;
+
+CREATE INDEX ticket_search_idx ON ticket USING GIN (search);
+
+-- Add full-text search documents to ticket comments
+ALTER TABLE ticket_comment ADD COLUMN search TSVECTOR GENERATED ALWAYS AS (
+    to_tsvector('english', body)
+) STORED;
+
+CREATE INDEX ticket_comment_search_idx ON ticket_comment USING GIN (search);
+
+-- Let searches with tenant isolation read the comments of the tickets visible to the user
+GRANT SELECT ON ticket_comment TO my_support_tenant;
+
+ALTER TABLE ticket_comment ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY ticket_comment_tenant_isolation ON ticket_comment TO my_support_tenant
+    USING (EXISTS (SELECT FROM ticket WHERE ticket.id = ticket_comment.ticket_id));
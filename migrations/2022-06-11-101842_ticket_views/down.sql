@@ -0,0 +1,7 @@
+-- Revoke the name columns from the tenant role
+REVOKE SELECT (id, username) ON sys_user FROM my_support_tenant;
+REVOKE SELECT (id, name) ON organisation FROM my_support_tenant;
+REVOKE SELECT (id, name) ON queue FROM my_support_tenant;
+
+-- Drop `ticket_view` table
+DROP TABLE ticket_view;
@@ -0,0 +1,24 @@
+-- Create `ticket_view` table
+--
+-- Views are named ticket queries, written in the query language. Personal views are only listed
+-- for their owner, while shared views are listed for everyone.
+CREATE TABLE ticket_view (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    owner_id uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL CHECK (name <> ''),
+    query VARCHAR(1000) NOT NULL,
+    shared BOOLEAN NOT NULL DEFAULT FALSE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (owner_id, name)
+);
+
+CREATE INDEX ticket_view_shared_idx ON ticket_view (shared) WHERE shared;
+
+-- Let tenant-isolated ticket queries resolve names
+--
+-- Ticket queries filter by username, organisation name and queue name in the same statement as
+-- the tickets, so the tenant role needs to read these columns.
+GRANT SELECT (id, username) ON sys_user TO my_support_tenant;
+GRANT SELECT (id, name) ON organisation TO my_support_tenant;
+GRANT SELECT (id, name) ON queue TO my_support_tenant;
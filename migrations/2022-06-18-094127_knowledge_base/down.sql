@@ -0,0 +1,11 @@
+-- Drop `ticket_article` table
+DROP TABLE ticket_article;
+
+-- Drop `article_revision` table
+DROP TABLE article_revision;
+
+-- Drop `article` table
+DROP TABLE article;
+
+-- Drop `article_category` table
+DROP TABLE article_category;
@@ -0,0 +1,59 @@
+-- Create `article_category` table
+CREATE TABLE article_category (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL UNIQUE CHECK (name <> ''),
+    description TEXT NOT NULL DEFAULT '',
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create `article` table
+--
+-- Articles are written in Markdown. The title and body are those of the latest revision.
+--
+-- The visibility is the audience of published articles: `public` articles can be read by anyone,
+-- even without logging in, `customers` articles by any logged in user, and `internal` articles
+-- only by agents and administrators. Drafts are only visible to agents and administrators.
+CREATE TABLE article (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    category_id uuid REFERENCES article_category (id) ON DELETE SET NULL,
+    title VARCHAR(200) NOT NULL CHECK (title <> ''),
+    body TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'draft' CHECK (status IN ('draft', 'published')),
+    visibility VARCHAR(20) NOT NULL DEFAULT 'internal' CHECK (
+        visibility IN ('public', 'customers', 'internal')
+    ),
+    revision INTEGER NOT NULL DEFAULT 1 CHECK (revision > 0),
+    author_id uuid NOT NULL REFERENCES sys_user (id),
+    published_on TIMESTAMP WITH TIME ZONE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX article_category_id_idx ON article (category_id);
+CREATE INDEX article_status_visibility_idx ON article (status, visibility);
+
+-- Create `article_revision` table
+--
+-- Every change to the title or body of an article is kept as a full copy.
+CREATE TABLE article_revision (
+    article_id uuid NOT NULL REFERENCES article (id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    title VARCHAR(200) NOT NULL,
+    body TEXT NOT NULL,
+    editor_id uuid NOT NULL REFERENCES sys_user (id),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (article_id, revision)
+);
+
+-- Create `ticket_article` table
+--
+-- Articles linked to tickets, usually because they answer them.
+CREATE TABLE ticket_article (
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    article_id uuid NOT NULL REFERENCES article (id) ON DELETE CASCADE,
+    linked_by uuid NOT NULL REFERENCES sys_user (id),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (ticket_id, article_id)
+);
+
+CREATE INDEX ticket_article_article_id_idx ON ticket_article (article_id);
@@ -0,0 +1,5 @@
+-- Drop `service_request` table
+DROP TABLE service_request;
+
+-- Drop `catalog_item` table
+DROP TABLE catalog_item;
@@ -0,0 +1,30 @@
+-- Create `catalog_item` table
+--
+-- Catalog items are standard services, requested through a form. The form is a JSON array of
+-- field definitions, validated by the application.
+CREATE TABLE catalog_item (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL UNIQUE CHECK (name <> ''),
+    description TEXT NOT NULL DEFAULT '',
+    form JSONB NOT NULL CHECK (jsonb_typeof(form) = 'array'),
+    priority VARCHAR(10) NOT NULL DEFAULT 'normal'
+        CHECK (priority IN ('low', 'normal', 'high', 'urgent')),
+    queue_id uuid REFERENCES queue (id) ON DELETE SET NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create `service_request` table
+--
+-- Structured answers of the tickets created from the catalog. The form is copied from the item,
+-- so that the answers can still be read if the item changes or is deleted.
+CREATE TABLE service_request (
+    ticket_id uuid PRIMARY KEY REFERENCES ticket (id) ON DELETE CASCADE,
+    catalog_item_id uuid REFERENCES catalog_item (id) ON DELETE SET NULL,
+    form JSONB NOT NULL,
+    answers JSONB NOT NULL CHECK (jsonb_typeof(answers) = 'object'),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX service_request_catalog_item_id_idx ON service_request (catalog_item_id);
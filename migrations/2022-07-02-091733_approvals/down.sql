@@ -0,0 +1,37 @@
+-- Restore the previous kinds of notifications
+DELETE FROM notification_preference WHERE kind = 'approval.requested';
+ALTER TABLE notification_preference DROP CONSTRAINT notification_preference_kind_check;
+ALTER TABLE notification_preference ADD CONSTRAINT notification_preference_kind_check
+    CHECK (kind IN ('ticket.assigned', 'ticket.commented', 'ticket.status_changed'));
+
+DELETE FROM notification WHERE kind = 'approval.requested';
+ALTER TABLE notification DROP CONSTRAINT notification_kind_check;
+ALTER TABLE notification ADD CONSTRAINT notification_kind_check
+    CHECK (kind IN ('ticket.assigned', 'ticket.commented', 'ticket.status_changed'));
+
+-- Restore the previous kinds of audit events
+--
+-- The audit log is append-only, so existing approval decisions are kept.
+ALTER TABLE sys_audit_log DROP CONSTRAINT sys_audit_log_kind_check;
+ALTER TABLE sys_audit_log ADD CONSTRAINT sys_audit_log_kind_check CHECK (kind IN (
+    'login_success',
+    'login_failure',
+    'registration',
+    'email_registration_deleted',
+    'invitation_created',
+    'invitation_revoked',
+    'role_change',
+    'ticket_field_change'
+)) NOT VALID;
+
+-- Drop `ticket_approver` table
+DROP TABLE ticket_approver;
+
+-- Drop `ticket_approval` table
+DROP TABLE ticket_approval;
+
+-- Remove the approval chains of catalog items
+ALTER TABLE catalog_item DROP COLUMN approval_chain_id;
+
+-- Drop `approval_chain` table
+DROP TABLE approval_chain;
@@ -0,0 +1,71 @@
+-- Create `approval_chain` table
+--
+-- The steps are a JSON array of step definitions, validated by the application.
+CREATE TABLE approval_chain (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL UNIQUE CHECK (name <> ''),
+    steps JSONB NOT NULL CHECK (jsonb_typeof(steps) = 'array'),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Catalog items can require an approval
+ALTER TABLE catalog_item
+    ADD COLUMN approval_chain_id uuid REFERENCES approval_chain (id) ON DELETE SET NULL;
+
+-- Create `ticket_approval` table
+--
+-- The steps are copied from the chain when the approval starts, with their approvers resolved, so
+-- that later changes to the chain don't affect running approvals.
+CREATE TABLE ticket_approval (
+    ticket_id uuid PRIMARY KEY REFERENCES ticket (id) ON DELETE CASCADE,
+    steps JSONB NOT NULL CHECK (jsonb_typeof(steps) = 'array'),
+    step INTEGER NOT NULL DEFAULT 0 CHECK (step >= 0),
+    status VARCHAR(10) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected')),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create `ticket_approver` table
+--
+-- Approvers are added when their step is reached. Their token lets them decide from the approval
+-- email, and expires some time after the step is reached.
+CREATE TABLE ticket_approver (
+    ticket_id uuid NOT NULL REFERENCES ticket_approval (ticket_id) ON DELETE CASCADE,
+    step INTEGER NOT NULL,
+    user_id uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    token CHAR(32) NOT NULL UNIQUE,
+    decision VARCHAR(10) CHECK (decision IN ('approved', 'rejected')),
+    comment TEXT NOT NULL DEFAULT '',
+    decided_on TIMESTAMP WITH TIME ZONE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (ticket_id, step, user_id),
+    CHECK ((decision IS NULL) = (decided_on IS NULL))
+);
+
+CREATE INDEX ticket_approver_user_id_idx ON ticket_approver (user_id);
+
+-- New kinds of audit events and notifications
+ALTER TABLE sys_audit_log DROP CONSTRAINT sys_audit_log_kind_check;
+ALTER TABLE sys_audit_log ADD CONSTRAINT sys_audit_log_kind_check CHECK (kind IN (
+    'login_success',
+    'login_failure',
+    'registration',
+    'email_registration_deleted',
+    'invitation_created',
+    'invitation_revoked',
+    'role_change',
+    'ticket_field_change',
+    'approval_decision'
+));
+
+ALTER TABLE notification DROP CONSTRAINT notification_kind_check;
+ALTER TABLE notification ADD CONSTRAINT notification_kind_check
+    CHECK (kind IN ('ticket.assigned', 'ticket.commented', 'ticket.status_changed',
+        'approval.requested'));
+
+ALTER TABLE notification_preference DROP CONSTRAINT notification_preference_kind_check;
+ALTER TABLE notification_preference ADD CONSTRAINT notification_preference_kind_check
+    CHECK (kind IN ('ticket.assigned', 'ticket.commented', 'ticket.status_changed',
+        'approval.requested'));
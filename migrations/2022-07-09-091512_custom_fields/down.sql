@@ -0,0 +1,6 @@
+-- Remove the values of the custom fields from tickets
+DROP INDEX ticket_custom_fields_idx;
+ALTER TABLE ticket DROP COLUMN custom_fields;
+
+-- Drop `custom_field` table
+DROP TABLE custom_field;
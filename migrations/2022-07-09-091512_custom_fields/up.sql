@@ -0,0 +1,23 @@
+-- Create `custom_field` table
+--
+-- Custom fields are typed fields defined by administrators, optionally limited to the tickets of
+-- some categories. The kind holds the type of the field and its settings, validated by the
+-- application.
+CREATE TABLE custom_field (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    key VARCHAR(50) NOT NULL UNIQUE CHECK (key ~ '^[a-z][a-z0-9_]*$'),
+    label VARCHAR(100) NOT NULL CHECK (label <> ''),
+    kind JSONB NOT NULL CHECK (jsonb_typeof(kind) = 'object'),
+    required BOOLEAN NOT NULL DEFAULT FALSE,
+    categories TEXT[] NOT NULL DEFAULT '{}',
+    position INTEGER NOT NULL DEFAULT 0,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Add the values of the custom fields to tickets, by field key
+ALTER TABLE ticket
+    ADD COLUMN custom_fields JSONB NOT NULL DEFAULT '{}'
+        CHECK (jsonb_typeof(custom_fields) = 'object');
+
+CREATE INDEX ticket_custom_fields_idx ON ticket USING GIN (custom_fields jsonb_path_ops);
@@ -0,0 +1,11 @@
+-- Drop `ticket_ci` table
+DROP TABLE ticket_ci;
+
+-- Drop `ci_relationship` table
+DROP TABLE ci_relationship;
+
+-- Drop `configuration_item` table
+DROP TABLE configuration_item;
+
+-- Drop `ci_type` table
+DROP TABLE ci_type;
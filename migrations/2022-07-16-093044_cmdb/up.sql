@@ -0,0 +1,56 @@
+-- Create `ci_type` table
+--
+-- Types of configuration items, such as servers, laptops or services. The attributes are the
+-- definitions of the typed attributes of their items, validated by the application.
+CREATE TABLE ci_type (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL UNIQUE CHECK (name <> ''),
+    attributes JSONB NOT NULL DEFAULT '[]' CHECK (jsonb_typeof(attributes) = 'array'),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create `configuration_item` table
+--
+-- Names are unique across types, so that imports can match items by name.
+CREATE TABLE configuration_item (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    type_id uuid NOT NULL REFERENCES ci_type (id) ON DELETE RESTRICT,
+    name VARCHAR(200) NOT NULL UNIQUE CHECK (name <> ''),
+    attributes JSONB NOT NULL DEFAULT '{}' CHECK (jsonb_typeof(attributes) = 'object'),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX configuration_item_type_id_idx ON configuration_item (type_id);
+
+-- Create `ci_relationship` table
+--
+-- Directed edges of the dependency graph: the source depends on, or runs on, the target. An
+-- outage of the target impacts the source.
+CREATE TABLE ci_relationship (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    source_id uuid NOT NULL REFERENCES configuration_item (id) ON DELETE CASCADE,
+    target_id uuid NOT NULL REFERENCES configuration_item (id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('depends_on', 'runs_on')),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (source_id, target_id, kind),
+    CHECK (source_id <> target_id)
+);
+
+CREATE INDEX ci_relationship_target_id_idx ON ci_relationship (target_id);
+
+-- Create `ticket_ci` table
+--
+-- Configuration items affected by tickets. Tenant-isolated ticket queries can filter by item.
+CREATE TABLE ticket_ci (
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    ci_id uuid NOT NULL REFERENCES configuration_item (id) ON DELETE CASCADE,
+    linked_by uuid NOT NULL REFERENCES sys_user (id),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (ticket_id, ci_id)
+);
+
+CREATE INDEX ticket_ci_ci_id_idx ON ticket_ci (ci_id);
+
+GRANT SELECT ON ticket_ci TO my_support_tenant;
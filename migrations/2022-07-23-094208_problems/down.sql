@@ -0,0 +1,5 @@
+-- Drop `problem_ticket` table
+DROP TABLE problem_ticket;
+
+-- Drop `problem` table
+DROP TABLE problem;
@@ -0,0 +1,39 @@
+-- Create `problem` table
+--
+-- Problems are the causes of recurring incidents. Once the root cause is known, a problem is a
+-- known error, which can be published as an article of the knowledge base.
+CREATE TABLE problem (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    number BIGSERIAL NOT NULL UNIQUE,
+    title VARCHAR(200) NOT NULL CHECK (title <> ''),
+    description TEXT NOT NULL DEFAULT '',
+    status VARCHAR(20) NOT NULL DEFAULT 'investigating' CHECK (
+        status IN ('investigating', 'known_error', 'resolved')
+    ),
+    root_cause TEXT NOT NULL DEFAULT '',
+    workaround TEXT NOT NULL DEFAULT '',
+    owner_id uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    article_id uuid REFERENCES article (id) ON DELETE SET NULL,
+    created_by uuid NOT NULL REFERENCES sys_user (id),
+    resolved_on TIMESTAMP WITH TIME ZONE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX problem_status_idx ON problem (status);
+
+-- Create `problem_ticket` table
+--
+-- Incident tickets caused by problems. A ticket can be linked to several problems, and
+-- tenant-isolated ticket queries can filter by problem.
+CREATE TABLE problem_ticket (
+    problem_id uuid NOT NULL REFERENCES problem (id) ON DELETE CASCADE,
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    linked_by uuid NOT NULL REFERENCES sys_user (id),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (problem_id, ticket_id)
+);
+
+CREATE INDEX problem_ticket_ticket_id_idx ON problem_ticket (ticket_id);
+
+GRANT SELECT ON problem_ticket TO my_support_tenant;
@@ -0,0 +1,14 @@
+-- Drop `cab_chain` table
+DROP TABLE cab_chain;
+
+-- Drop `maintenance_window_ci` table
+DROP TABLE maintenance_window_ci;
+
+-- Drop `maintenance_window` table
+DROP TABLE maintenance_window;
+
+-- Drop `change_ci` table
+DROP TABLE change_ci;
+
+-- Drop `change_request` table
+DROP TABLE change_request;
@@ -0,0 +1,66 @@
+-- Create `change_request` table
+--
+-- Each change is tracked by its own ticket, which carries its title, status, comments and
+-- approval. The answers to the questionnaire are kept along with the assessment calculated from
+-- them, so that later changes to the questionnaire don't affect assessed changes.
+CREATE TABLE change_request (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    ticket_id uuid NOT NULL UNIQUE REFERENCES ticket (id) ON DELETE CASCADE,
+    planned_start TIMESTAMP WITH TIME ZONE NOT NULL,
+    planned_end TIMESTAMP WITH TIME ZONE NOT NULL,
+    answers JSONB NOT NULL CHECK (jsonb_typeof(answers) = 'object'),
+    risk_score INTEGER NOT NULL,
+    impact_score INTEGER NOT NULL,
+    risk VARCHAR(10) NOT NULL CHECK (risk IN ('low', 'medium', 'high')),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (planned_end > planned_start)
+);
+
+CREATE INDEX change_request_planned_start_idx ON change_request (planned_start);
+
+-- Create `change_ci` table
+--
+-- Configuration items touched by changes, used to detect collisions.
+CREATE TABLE change_ci (
+    change_id uuid NOT NULL REFERENCES change_request (id) ON DELETE CASCADE,
+    ci_id uuid NOT NULL REFERENCES configuration_item (id) ON DELETE CASCADE,
+    PRIMARY KEY (change_id, ci_id)
+);
+
+CREATE INDEX change_ci_ci_id_idx ON change_ci (ci_id);
+
+-- Create `maintenance_window` table
+--
+-- Scheduled maintenance of configuration items, such as the ones announced by providers, that
+-- changes shouldn't collide with.
+CREATE TABLE maintenance_window (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    title VARCHAR(200) NOT NULL CHECK (title <> ''),
+    starts_on TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_on TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_by uuid NOT NULL REFERENCES sys_user (id),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_on > starts_on)
+);
+
+CREATE INDEX maintenance_window_starts_on_idx ON maintenance_window (starts_on);
+
+-- Create `maintenance_window_ci` table
+CREATE TABLE maintenance_window_ci (
+    window_id uuid NOT NULL REFERENCES maintenance_window (id) ON DELETE CASCADE,
+    ci_id uuid NOT NULL REFERENCES configuration_item (id) ON DELETE CASCADE,
+    PRIMARY KEY (window_id, ci_id)
+);
+
+CREATE INDEX maintenance_window_ci_ci_id_idx ON maintenance_window_ci (ci_id);
+
+-- Create `cab_chain` table
+--
+-- Approval chains of the change advisory board, by risk level. Changes of levels without a chain
+-- don't need an approval.
+CREATE TABLE cab_chain (
+    risk VARCHAR(10) PRIMARY KEY CHECK (risk IN ('low', 'medium', 'high')),
+    approval_chain_id uuid NOT NULL REFERENCES approval_chain (id) ON DELETE CASCADE
+);
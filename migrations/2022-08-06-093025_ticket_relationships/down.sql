@@ -0,0 +1,24 @@
+-- Restore the previous kinds of audit events
+--
+-- The audit log is append-only, so existing merges and splits are kept.
+ALTER TABLE sys_audit_log DROP CONSTRAINT sys_audit_log_kind_check;
+ALTER TABLE sys_audit_log ADD CONSTRAINT sys_audit_log_kind_check CHECK (kind IN (
+    'login_success',
+    'login_failure',
+    'registration',
+    'email_registration_deleted',
+    'invitation_created',
+    'invitation_revoked',
+    'role_change',
+    'ticket_field_change',
+    'approval_decision'
+)) NOT VALID;
+
+-- Drop `ticket_watcher` table
+DROP TABLE ticket_watcher;
+
+-- Drop `ticket_link` table
+DROP TABLE ticket_link;
+
+-- Remove merged and parent tickets
+ALTER TABLE ticket DROP COLUMN parent_id, DROP COLUMN merged_into_id;
@@ -0,0 +1,62 @@
+-- Add merged and parent tickets
+--
+-- Merged tickets are closed and point to the ticket they were merged into, so that their number
+-- and the replies to them lead to it. Child tickets are tasks of their parent, which can't be
+-- resolved or closed before them.
+ALTER TABLE ticket
+    ADD COLUMN merged_into_id uuid REFERENCES ticket (id) ON DELETE SET NULL,
+    ADD COLUMN parent_id uuid REFERENCES ticket (id) ON DELETE SET NULL,
+    ADD CONSTRAINT ticket_merged_into_id_check CHECK (merged_into_id <> id),
+    ADD CONSTRAINT ticket_parent_id_check CHECK (parent_id <> id);
+
+CREATE INDEX ticket_merged_into_id_idx ON ticket (merged_into_id);
+CREATE INDEX ticket_parent_id_idx ON ticket (parent_id);
+
+-- Create `ticket_link` table
+--
+-- Related tickets, or a ticket duplicating the linked one. Two tickets have one link at most,
+-- whatever its direction.
+CREATE TABLE ticket_link (
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    linked_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('related', 'duplicate')),
+    created_by uuid NOT NULL REFERENCES sys_user (id),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (ticket_id, linked_id),
+    CHECK (ticket_id <> linked_id)
+);
+
+CREATE UNIQUE INDEX ticket_link_pair_idx
+    ON ticket_link (LEAST(ticket_id, linked_id), GREATEST(ticket_id, linked_id));
+CREATE INDEX ticket_link_linked_id_idx ON ticket_link (linked_id);
+
+GRANT SELECT ON ticket_link TO my_support_tenant;
+
+-- Create `ticket_watcher` table
+--
+-- Users notified of the comments and status changes of tickets, besides their requester and
+-- assignee.
+CREATE TABLE ticket_watcher (
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES sys_user (id) ON DELETE CASCADE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (ticket_id, user_id)
+);
+
+CREATE INDEX ticket_watcher_user_id_idx ON ticket_watcher (user_id);
+
+-- New kinds of audit events
+ALTER TABLE sys_audit_log DROP CONSTRAINT sys_audit_log_kind_check;
+ALTER TABLE sys_audit_log ADD CONSTRAINT sys_audit_log_kind_check CHECK (kind IN (
+    'login_success',
+    'login_failure',
+    'registration',
+    'email_registration_deleted',
+    'invitation_created',
+    'invitation_revoked',
+    'role_change',
+    'ticket_field_change',
+    'approval_decision',
+    'ticket_merged',
+    'ticket_split'
+));
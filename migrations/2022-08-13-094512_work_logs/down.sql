@@ -0,0 +1,5 @@
+-- Drop `contract` table
+DROP TABLE contract;
+
+-- Drop `work_log` table
+DROP TABLE work_log;
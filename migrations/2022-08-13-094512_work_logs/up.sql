@@ -0,0 +1,40 @@
+-- Create `work_log` table
+--
+-- Time spent by agents on tickets. Billable work is charged to the organisation of the ticket,
+-- against the prepaid hours of its contracts.
+CREATE TABLE work_log (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    agent_id uuid NOT NULL REFERENCES sys_user (id),
+    minutes INTEGER NOT NULL CHECK (minutes > 0 AND minutes <= 1440),
+    billable BOOLEAN NOT NULL DEFAULT TRUE,
+    activity VARCHAR(20) NOT NULL CHECK (
+        activity IN ('support', 'consulting', 'development', 'travel', 'meeting', 'other')
+    ),
+    description TEXT NOT NULL DEFAULT '',
+    worked_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX work_log_ticket_id_idx ON work_log (ticket_id);
+CREATE INDEX work_log_agent_id_idx ON work_log (agent_id);
+CREATE INDEX work_log_worked_on_idx ON work_log (worked_on);
+
+-- Create `contract` table
+--
+-- Managed-services contracts of organisations, with the hours they prepaid for a period. Periods
+-- without an end run until further notice.
+CREATE TABLE contract (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    organisation_id uuid NOT NULL REFERENCES organisation (id) ON DELETE CASCADE,
+    name VARCHAR(200) NOT NULL CHECK (name <> ''),
+    prepaid_minutes INTEGER NOT NULL CHECK (prepaid_minutes >= 0),
+    starts_on TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_on TIMESTAMP WITH TIME ZONE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_on > starts_on)
+);
+
+CREATE INDEX contract_organisation_id_idx ON contract (organisation_id);
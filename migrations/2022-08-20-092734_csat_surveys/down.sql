@@ -0,0 +1,5 @@
+-- Drop `survey_suppression` table
+DROP TABLE survey_suppression;
+
+-- Drop `survey` table
+DROP TABLE survey;
@@ -0,0 +1,29 @@
+-- Create `survey` table
+--
+-- Satisfaction surveys sent to requesters when their ticket is resolved, one per resolution. The
+-- token of the links of the survey email lets requesters answer without logging in. The agent is
+-- the assignee of the ticket when it was resolved.
+CREATE TABLE survey (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    ticket_id uuid NOT NULL REFERENCES ticket (id) ON DELETE CASCADE,
+    agent_id uuid REFERENCES sys_user (id) ON DELETE SET NULL,
+    token CHAR(32) NOT NULL UNIQUE,
+    rating VARCHAR(10) CHECK (rating IN ('good', 'bad')),
+    comment TEXT NOT NULL DEFAULT '',
+    sent_on TIMESTAMP WITH TIME ZONE,
+    answered_on TIMESTAMP WITH TIME ZONE,
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((rating IS NULL) = (answered_on IS NULL))
+);
+
+CREATE INDEX survey_ticket_id_idx ON survey (ticket_id);
+CREATE INDEX survey_agent_id_idx ON survey (agent_id);
+CREATE INDEX survey_pending_idx ON survey (created_on) WHERE sent_on IS NULL;
+
+-- Create `survey_suppression` table
+--
+-- Ticket categories whose tickets don't get surveys when resolved, such as internal requests.
+CREATE TABLE survey_suppression (
+    category VARCHAR(50) PRIMARY KEY CHECK (category <> ''),
+    created_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
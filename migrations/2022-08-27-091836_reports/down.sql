@@ -0,0 +1,11 @@
+-- Drop `ticket_metric` table
+DROP TRIGGER ticket_metric_comment ON ticket_comment;
+DROP FUNCTION ticket_metric_comment();
+DROP TRIGGER ticket_metric_status ON ticket;
+DROP FUNCTION ticket_metric_status();
+DROP TRIGGER ticket_metric_insert ON ticket;
+DROP FUNCTION ticket_metric_insert();
+DROP TABLE ticket_metric;
+
+-- Drop `sla_policy` table
+DROP TABLE sla_policy;
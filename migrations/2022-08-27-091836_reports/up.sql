@@ -0,0 +1,79 @@
+-- Create `sla_policy` table
+--
+-- Service level targets of each ticket priority: the time from the creation of a ticket to the
+-- first response of the staff, and to its resolution.
+CREATE TABLE sla_policy (
+    priority VARCHAR(10) PRIMARY KEY CHECK (priority IN ('low', 'normal', 'high', 'urgent')),
+    response_minutes INTEGER NOT NULL CHECK (response_minutes > 0),
+    resolution_minutes INTEGER NOT NULL CHECK (resolution_minutes >= response_minutes),
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO sla_policy (priority, response_minutes, resolution_minutes) VALUES
+    ('urgent', 60, 240),
+    ('high', 240, 1440),
+    ('normal', 480, 4320),
+    ('low', 1440, 10080);
+
+-- Create `ticket_metric` table
+--
+-- Milestones of each ticket used for reporting: the first response of the staff and the latest
+-- resolution. They are kept by triggers, so that they are right whatever changes the ticket.
+CREATE TABLE ticket_metric (
+    ticket_id uuid PRIMARY KEY REFERENCES ticket (id) ON DELETE CASCADE,
+    first_response_on TIMESTAMP WITH TIME ZONE,
+    resolved_on TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX ticket_metric_resolved_on_idx ON ticket_metric (resolved_on);
+
+-- Existing tickets count as resolved when they were last updated
+INSERT INTO ticket_metric (ticket_id, first_response_on, resolved_on)
+SELECT t.id,
+    (SELECT MIN(c.created_on) FROM ticket_comment c JOIN sys_user u ON u.id = c.author_id
+        WHERE c.ticket_id = t.id AND u.role IN ('agent', 'admin') AND u.id <> t.requester_id),
+    CASE WHEN t.status IN ('resolved', 'closed') THEN t.updated_on END
+FROM ticket t;
+
+-- The triggers run as the owner of the table, since tickets and comments are also written by the
+-- tenant role
+CREATE FUNCTION ticket_metric_insert() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO ticket_metric (ticket_id) VALUES (NEW.id);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER SET search_path = public;
+
+CREATE TRIGGER ticket_metric_insert AFTER INSERT ON ticket
+    FOR EACH ROW EXECUTE PROCEDURE ticket_metric_insert();
+
+-- Reopening a ticket clears its resolution, which is set again when it's resolved or closed
+CREATE FUNCTION ticket_metric_status() RETURNS trigger AS $$
+BEGIN
+    IF NEW.status IN ('resolved', 'closed') AND OLD.status NOT IN ('resolved', 'closed') THEN
+        UPDATE ticket_metric SET resolved_on = CURRENT_TIMESTAMP WHERE ticket_id = NEW.id;
+    ELSIF NEW.status NOT IN ('resolved', 'closed') AND OLD.status IN ('resolved', 'closed') THEN
+        UPDATE ticket_metric SET resolved_on = NULL WHERE ticket_id = NEW.id;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER SET search_path = public;
+
+CREATE TRIGGER ticket_metric_status AFTER UPDATE OF status ON ticket
+    FOR EACH ROW WHEN (OLD.status IS DISTINCT FROM NEW.status)
+    EXECUTE PROCEDURE ticket_metric_status();
+
+-- The first response is the first comment of a member of the staff other than the requester
+CREATE FUNCTION ticket_metric_comment() RETURNS trigger AS $$
+BEGIN
+    UPDATE ticket_metric m SET first_response_on = NEW.created_on
+    FROM ticket t, sys_user u
+    WHERE m.ticket_id = NEW.ticket_id AND m.first_response_on IS NULL
+        AND t.id = NEW.ticket_id AND u.id = NEW.author_id
+        AND u.role IN ('agent', 'admin') AND u.id <> t.requester_id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER SET search_path = public;
+
+CREATE TRIGGER ticket_metric_comment AFTER INSERT ON ticket_comment
+    FOR EACH ROW EXECUTE PROCEDURE ticket_metric_comment();
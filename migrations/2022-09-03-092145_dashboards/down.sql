@@ -0,0 +1,7 @@
+-- Drop the push of ticket activity
+DROP TRIGGER ticket_activity_push ON ticket_comment;
+DROP TRIGGER ticket_activity_push ON ticket;
+DROP FUNCTION ticket_activity_push();
+
+-- Drop `dashboard` table
+DROP TABLE dashboard;
@@ -0,0 +1,32 @@
+-- Create `dashboard` table
+--
+-- Layout of the dashboard of each agent. Agents without a stored layout get the default one.
+CREATE TABLE dashboard (
+    user_id uuid PRIMARY KEY REFERENCES sys_user (id) ON DELETE CASCADE,
+    widgets JSONB NOT NULL CHECK (jsonb_typeof(widgets) = 'array'),
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Ticket activity is pushed to all the application instances listening on the `ticket_activity`
+-- channel, so that agents see it live
+CREATE FUNCTION ticket_activity_push() RETURNS trigger AS $$
+BEGIN
+    IF TG_TABLE_NAME = 'ticket_comment' THEN
+        PERFORM pg_notify('ticket_activity',
+            json_build_object('ticket_id', NEW.ticket_id, 'kind', 'commented')::text);
+    ELSIF TG_OP = 'INSERT' THEN
+        PERFORM pg_notify('ticket_activity',
+            json_build_object('ticket_id', NEW.id, 'kind', 'created')::text);
+    ELSE
+        PERFORM pg_notify('ticket_activity',
+            json_build_object('ticket_id', NEW.id, 'kind', 'changed')::text);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER ticket_activity_push AFTER INSERT OR UPDATE ON ticket
+    FOR EACH ROW EXECUTE PROCEDURE ticket_activity_push();
+
+CREATE TRIGGER ticket_activity_push AFTER INSERT ON ticket_comment
+    FOR EACH ROW EXECUTE PROCEDURE ticket_activity_push();
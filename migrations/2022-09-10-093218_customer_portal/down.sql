@@ -0,0 +1,5 @@
+-- Drop the full-text search index of the articles
+DROP INDEX article_search_idx;
+
+-- Drop `organisation_branding` table
+DROP TABLE organisation_branding;
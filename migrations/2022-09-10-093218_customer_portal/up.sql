@@ -0,0 +1,20 @@
+-- Create `organisation_branding` table
+--
+-- Branding of the customer portal for the members of an organisation. Organisations without one
+-- get the default branding.
+CREATE TABLE organisation_branding (
+    organisation_id uuid PRIMARY KEY REFERENCES organisation (id) ON DELETE CASCADE,
+    logo_url VARCHAR(500),
+    primary_colour CHAR(7) NOT NULL CHECK (primary_colour ~ '^#[0-9a-f]{6}$'),
+    accent_colour CHAR(7) NOT NULL CHECK (accent_colour ~ '^#[0-9a-f]{6}$'),
+    welcome_text VARCHAR(2000) NOT NULL DEFAULT '',
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Index the full-text search documents of the articles, weighting titles above bodies
+--
+-- Searches must use the same expression for the index to be used.
+CREATE INDEX article_search_idx ON article USING GIN ((
+    setweight(to_tsvector('english', title), 'A') ||
+    setweight(to_tsvector('english', body), 'B')
+));
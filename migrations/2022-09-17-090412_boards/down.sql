@@ -0,0 +1,2 @@
+-- Drop `board_column` table
+DROP TABLE board_column;
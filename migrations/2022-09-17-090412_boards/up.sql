@@ -0,0 +1,12 @@
+-- Create `board_column` table
+--
+-- Settings of the columns of the ticket board, for each way of grouping the tickets. Columns are
+-- keyed by ticket status, or by assignee ID and `unassigned` for the tickets without an assignee.
+CREATE TABLE board_column (
+    grouping VARCHAR(20) NOT NULL CHECK (grouping IN ('status', 'assignee')),
+    column_key VARCHAR(50) NOT NULL,
+    -- Maximum number of tickets the column should hold
+    wip_limit INTEGER NOT NULL CHECK (wip_limit > 0),
+    updated_on TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (grouping, column_key)
+);